@@ -1,7 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{path::PathBuf, time::Duration};
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -90,6 +97,12 @@ pub struct Parameters {
     /// Tonic network settings.
     #[serde(default = "TonicParameters::default")]
     pub tonic: TonicParameters,
+
+    /// Offset added to the timestamps of blocks proposed by this authority. Local test networks
+    /// share one offset between all their authorities, to move the whole network's clock forward.
+    /// It is never read from config files, so it stays at zero outside of tests.
+    #[serde(skip)]
+    pub clock_offset: ClockOffset,
 }
 
 impl Parameters {
@@ -208,10 +221,29 @@ impl Default for Parameters {
             commit_sync_batches_ahead: Parameters::default_commit_sync_batches_ahead(),
             anemo: AnemoParameters::default(),
             tonic: TonicParameters::default(),
+            clock_offset: ClockOffset::default(),
         }
     }
 }
 
+/// An offset to a clock, that can be moved forward while it is in use. Clones share the same
+/// offset.
+#[derive(Clone, Debug, Default)]
+pub struct ClockOffset(Arc<AtomicU64>);
+
+impl ClockOffset {
+    /// Move the offset forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.0
+            .fetch_add(duration.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    /// The current offset, in milliseconds.
+    pub fn as_millis(&self) -> u64 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AnemoParameters {
     /// Size in bytes above which network messages are considered excessively large. Excessively
@@ -7,3 +7,21 @@ fn parameters_snapshot_matches() {
     let parameters = consensus_config::Parameters::default();
     insta::assert_yaml_snapshot!("parameters", parameters)
 }
+
+#[test]
+fn clock_offset_is_shared_and_not_serialized() {
+    use std::time::Duration;
+
+    let parameters = consensus_config::Parameters::default();
+    let cloned = parameters.clone();
+    parameters.clock_offset.advance(Duration::from_secs(60));
+
+    // Clones of the parameters (e.g. in each authority's config) move forward together.
+    assert_eq!(cloned.clock_offset.as_millis(), 60_000);
+
+    // The offset can't be set from a config file, and doesn't survive being written to one.
+    let yaml = serde_yaml::to_string(&parameters).unwrap();
+    assert!(!yaml.contains("clock_offset"), "{yaml}");
+    let parameters: consensus_config::Parameters = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(parameters.clock_offset.as_millis(), 0);
+}
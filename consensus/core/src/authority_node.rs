@@ -196,13 +196,14 @@ where
         );
         info!("Consensus parameters: {:?}", parameters);
         info!("Consensus committee: {:?}", committee);
+        let clock = Arc::new(Clock::new(parameters.clock_offset.clone()));
         let context = Arc::new(Context::new(
             own_index,
             committee,
             parameters,
             protocol_config,
             initialise_metrics(registry),
-            Arc::new(Clock::new()),
+            clock,
         ));
         let start_time = Instant::now();
 
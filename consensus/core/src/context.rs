@@ -3,7 +3,7 @@
 
 use std::{sync::Arc, time::SystemTime};
 
-use consensus_config::{AuthorityIndex, Committee, Parameters};
+use consensus_config::{AuthorityIndex, ClockOffset, Committee, Parameters};
 #[cfg(test)]
 use consensus_config::{NetworkKeyPair, ProtocolKeyPair};
 use sui_protocol_config::ProtocolConfig;
@@ -61,7 +61,7 @@ impl Context {
             consensus_config::local_committee_and_keys(0, vec![1; committee_size]);
         let metrics = test_metrics();
         let temp_dir = TempDir::new().unwrap();
-        let clock = Arc::new(Clock::new());
+        let clock = Arc::new(Clock::new(ClockOffset::default()));
 
         let context = Context::new(
             AuthorityIndex::new_for_test(0),
@@ -107,16 +107,24 @@ impl Context {
 /// Explicitly avoid to make `[Clock]` cloneable to ensure that a single instance is shared behind an `[Arc]`
 /// wherever is needed in order to make sure that consecutive calls to receive the system timestamp
 /// will remain monotonically increasing.
+///
+/// Timestamps are moved forward by the clock's [`ClockOffset`], which is always zero outside of
+/// local test networks. There, it is shared by every authority in the network, so that moving it
+/// forward moves consensus commit timestamps (and the time that transactions observe) forward too.
+/// The offset is not persisted, so authorities restarted from persisted state with a fresh offset
+/// will not propose blocks until their clock catches up with the old offset.
 pub(crate) struct Clock {
     initial_instant: Instant,
     initial_system_time: SystemTime,
+    offset: ClockOffset,
 }
 
 impl Clock {
-    pub fn new() -> Self {
+    pub fn new(offset: ClockOffset) -> Self {
         Self {
             initial_instant: Instant::now(),
             initial_system_time: SystemTime::now(),
+            offset,
         }
     }
 
@@ -147,5 +155,6 @@ impl Clock {
                 )
             })
             .as_millis() as BlockTimestampMs
+            + self.offset.as_millis()
     }
 }
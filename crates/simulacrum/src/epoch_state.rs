@@ -25,6 +25,7 @@ use sui_types::{
 
 use crate::SimulatorStore;
 
+#[derive(Clone)]
 pub struct EpochState {
     epoch_start_state: EpochStartSystemState,
     committee: Committee,
@@ -86,6 +86,15 @@ pub struct Simulacrum<R = OsRng, Store: SimulatorStore = InMemoryStore> {
     verifier_signing_config: VerifierSigningConfig,
 }
 
+/// A copy of a [`Simulacrum`]'s chain state, captured by [`Simulacrum::snapshot`], that the
+/// Simulacrum can be rolled back to (any number of times) with [`Simulacrum::restore`].
+#[derive(Clone)]
+pub struct SimulacrumSnapshot<Store = InMemoryStore> {
+    store: Store,
+    checkpoint_builder: MockCheckpointBuilder,
+    epoch_state: EpochState,
+}
+
 impl Simulacrum {
     /// Create a new, random Simulacrum instance using an `OsRng` as the source of randomness.
     #[allow(clippy::new_without_default)]
@@ -450,6 +459,52 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
     }
 }
 
+impl<R, S: store::SimulatorStore + Clone> Simulacrum<R, S> {
+    /// Capture the full chain state: all objects, transactions and checkpoints, the current epoch,
+    /// and transactions executed since the last checkpoint.
+    ///
+    /// This allows a test to run a destructive scenario and roll back to a common prefix
+    /// afterwards, to explore many branches from the same state.
+    ///
+    /// ```
+    /// use simulacrum::Simulacrum;
+    /// use sui_types::base_types::SuiAddress;
+    /// use sui_types::gas_coin::MIST_PER_SUI;
+    ///
+    /// # fn main() {
+    /// let mut simulacrum = Simulacrum::new();
+    /// let snapshot = simulacrum.snapshot();
+    ///
+    /// let address = SuiAddress::generate(simulacrum.rng());
+    /// simulacrum.request_gas(address, MIST_PER_SUI).unwrap();
+    /// simulacrum.create_checkpoint();
+    ///
+    /// // `address` no longer owns any objects, and the checkpoint is gone.
+    /// simulacrum.restore(&snapshot);
+    /// assert_eq!(simulacrum.store().owned_objects(address).count(), 0);
+    /// # }
+    /// ```
+    pub fn snapshot(&self) -> SimulacrumSnapshot<S> {
+        SimulacrumSnapshot {
+            store: self.store.clone(),
+            checkpoint_builder: self.checkpoint_builder.clone(),
+            epoch_state: self.epoch_state.clone(),
+        }
+    }
+
+    /// Roll the chain state back to `snapshot`, which must have been taken from this Simulacrum.
+    ///
+    /// The RNG is not rolled back, so accounts and keys created after restoring differ from those
+    /// created after the snapshot was taken originally. Checkpoints already written to the data
+    /// ingestion path (if one is set) are not removed, but are overwritten as new checkpoints are
+    /// created.
+    pub fn restore(&mut self, snapshot: &SimulacrumSnapshot<S>) {
+        self.store = snapshot.store.clone();
+        self.checkpoint_builder = snapshot.checkpoint_builder.clone();
+        self.epoch_state = snapshot.epoch_state.clone();
+    }
+}
+
 pub struct CommitteeWithKeys<'a> {
     keystore: &'a KeyStore,
     committee: &'a Committee,
@@ -724,6 +779,40 @@ mod tests {
         dbg!(chain.store().get_highest_checkpint());
     }
 
+    #[test]
+    fn snapshot_restore() {
+        let mut sim = Simulacrum::new();
+        sim.create_checkpoint();
+        let snapshot = sim.snapshot();
+        let start_checkpoint = sim.store().get_highest_checkpint().unwrap();
+
+        // Explore two branches from the same snapshot, each of which should be identical to the
+        // other, because they start from the same state.
+        let mut branches = vec![];
+        for _ in 0..2 {
+            sim.restore(&snapshot);
+
+            let recipient = SuiAddress::random_for_testing_only();
+            let (tx, _) = sim.transfer_txn(recipient);
+            let gas_id = tx.data().transaction_data().gas_data().payment[0].0;
+            sim.execute_transaction(tx).unwrap();
+            sim.advance_epoch(/* create_random_state */ false);
+
+            let gas = store::SimulatorStore::get_object(sim.store(), &gas_id).unwrap();
+            let checkpoint = sim.store().get_highest_checkpint().unwrap();
+            assert_eq!(checkpoint.epoch, start_checkpoint.epoch + 1);
+            branches.push((gas.version(), checkpoint.sequence_number));
+        }
+
+        assert_eq!(branches[0], branches[1]);
+
+        // Restoring discards all changes since the snapshot.
+        sim.restore(&snapshot);
+        let checkpoint = sim.store().get_highest_checkpint().unwrap();
+        assert_eq!(checkpoint.digest(), start_checkpoint.digest());
+        assert_eq!(sim.epoch_state.epoch(), start_checkpoint.epoch);
+    }
+
     #[test]
     fn transfer() {
         let mut sim = Simulacrum::new();
@@ -22,11 +22,12 @@ use sui_storage::object_store::util::{copy_file, path_to_filesystem};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
 use crate::analytics_metrics::AnalyticsMetrics;
+use crate::delta::{manifest_path, DeltaTable, PendingCommit};
 use crate::handlers::AnalyticsHandler;
 use crate::writers::AnalyticsWriter;
 use crate::{
-    join_paths, AnalyticsIndexerConfig, FileMetadata, MaxCheckpointReader, ParquetSchema,
-    EPOCH_DIR_PREFIX,
+    join_paths, make_delta_table, AnalyticsIndexerConfig, FileMetadata, MaxCheckpointReader,
+    ParquetSchema, TableFormat, EPOCH_DIR_PREFIX,
 };
 
 struct State<S: Serialize + ParquetSchema> {
@@ -122,11 +123,16 @@ impl<S: Serialize + ParquetSchema + 'static> AnalyticsProcessor<S> {
         let name: String = handler.name().parse()?;
         let checkpoint_dir = config.checkpoint_dir.clone();
         let cloned_metrics = metrics.clone();
+        let delta_table = match config.table_format {
+            TableFormat::Files => None,
+            TableFormat::Delta => Some(make_delta_table(&config, config.file_type)?),
+        };
         tokio::task::spawn(Self::start_syncing_with_remote(
             remote_object_store,
             local_object_store.clone(),
             checkpoint_dir,
             config.remote_store_path_prefix.clone(),
+            delta_table,
             receiver,
             kill_receiver,
             cloned_metrics,
@@ -222,6 +228,7 @@ impl<S: Serialize + ParquetSchema + 'static> AnalyticsProcessor<S> {
         local_object_store: Arc<DynObjectStore>,
         local_staging_root_dir: PathBuf,
         remote_store_path_prefix: Option<Path>,
+        delta_table: Option<DeltaTable>,
         mut file_recv: mpsc::Receiver<FileMetadata>,
         mut recv: oneshot::Receiver<()>,
         metrics: AnalyticsMetrics,
@@ -234,15 +241,26 @@ impl<S: Serialize + ParquetSchema + 'static> AnalyticsProcessor<S> {
                     if let Some(file_metadata) = file {
                         info!("Received {name} file with checkpoints: {:?}", &file_metadata.checkpoint_seq_range);
                         let checkpoint_seq_num = file_metadata.checkpoint_seq_range.end;
-                        Self::sync_file_to_remote(
-                                local_staging_root_dir.clone(),
-                                file_metadata.file_path(),
-                                remote_store_path_prefix.clone(),
-                                local_object_store.clone(),
-                                remote_object_store.clone()
-                            )
-                            .await
-                            .expect("Syncing checkpoint should not fail");
+                        if let Some(delta_table) = &delta_table {
+                            Self::commit_to_delta_table(
+                                    local_staging_root_dir.clone(),
+                                    &file_metadata,
+                                    local_object_store.clone(),
+                                    delta_table,
+                                )
+                                .await
+                                .expect("Committing checkpoint should not fail");
+                        } else {
+                            Self::sync_file_to_remote(
+                                    local_staging_root_dir.clone(),
+                                    file_metadata.file_path(),
+                                    remote_store_path_prefix.clone(),
+                                    local_object_store.clone(),
+                                    remote_object_store.clone()
+                                )
+                                .await
+                                .expect("Syncing checkpoint should not fail");
+                        }
                         metrics.last_uploaded_checkpoint.with_label_values(&[&name]).set(checkpoint_seq_num as i64);
                     } else {
                         info!("Terminating upload sync loop");
@@ -294,4 +312,41 @@ impl<S: Serialize + ParquetSchema + 'static> AnalyticsProcessor<S> {
         fs::remove_file(path_to_filesystem(dir, &path)?)?;
         Ok(())
     }
+
+    /// Upload the data files that were written for `file_metadata`'s range of checkpoints, and
+    /// then commit them to the Delta Lake table, so that they only become visible once they have
+    /// all been uploaded.
+    async fn commit_to_delta_table(
+        dir: PathBuf,
+        file_metadata: &FileMetadata,
+        from: Arc<DynObjectStore>,
+        table: &DeltaTable,
+    ) -> Result<()> {
+        let file_type = file_metadata.file_type;
+        let manifest = path_to_filesystem(
+            dir.clone(),
+            &manifest_path(file_type, &file_metadata.checkpoint_seq_range),
+        )?;
+        let pending: PendingCommit = serde_json::from_slice(&fs::read(&manifest)?)?;
+
+        let mut local_files = vec![];
+        for file in &pending.files {
+            let local = Path::parse(format!("{}/{}", file_type.dir_prefix(), file.path))?;
+            table.upload(&from, &local, &file.path).await?;
+            local_files.push(local);
+        }
+
+        let version = table.commit(&pending).await?;
+        info!(
+            "Committed {} files to Delta table {:?} at version {version}",
+            pending.files.len(),
+            file_type.dir_prefix(),
+        );
+
+        for local in local_files {
+            fs::remove_file(path_to_filesystem(dir.clone(), &local)?)?;
+        }
+        fs::remove_file(manifest)?;
+        Ok(())
+    }
 }
@@ -0,0 +1,897 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for writing analytics tables as Delta Lake tables, so that they can be queried by
+//! lakehouse engines directly (see https://github.com/delta-io/delta/blob/master/PROTOCOL.md).
+//!
+//! Data files are written as parquet by the `DeltaWriter` into the local staging directory,
+//! alongside a manifest describing them (a `PendingCommit`). Once the data files have been
+//! uploaded to the table, the manifest is turned into a commit in the table's transaction log.
+//! Until then, the data files are not part of the table, so readers never see partial writes.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use arrow::datatypes::{DataType, Field as ArrowField, Fields, Schema, SchemaRef};
+use arrow::json::{ArrayWriter, ReaderBuilder};
+use bytes::Bytes;
+use object_store::path::Path;
+use object_store::{DynObjectStore, PutMode, PutOptions, PutPayload};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use sui_storage::object_store::util::copy_file;
+
+use crate::{FileType, ParquetValue};
+
+/// Directory, relative to the table's root, that holds its transaction log.
+const DELTA_LOG_DIR: &str = "_delta_log";
+
+/// File in the transaction log that points at its latest checkpoint.
+const LAST_CHECKPOINT: &str = "_last_checkpoint";
+
+/// Number of commits between checkpoints of the transaction log, which save readers (and the
+/// indexer, on restart) from replaying the whole log.
+const CHECKPOINT_INTERVAL: u64 = 10;
+
+/// Identifies the indexer in the table's log, in `txn` actions and as the engine of commits.
+const APP_ID: &str = "sui-analytics-indexer";
+
+/// Directory, relative to the table's root in the local staging directory, that holds manifests
+/// for data files that have not been committed yet.
+const PENDING_DIR: &str = "_pending";
+
+/// The delta type that unsigned 64-bit integers are stored as, as lakehouse engines don't support
+/// unsigned integers.
+const U64_TYPE: &str = "decimal(20,0)";
+
+/// Readers and writers need to support the first version of the protocol, which does not include
+/// any table features.
+const MIN_READER_VERSION: u32 = 1;
+const MIN_WRITER_VERSION: u32 = 2;
+
+/// A column in a table's schema.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Field {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub nullable: bool,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StructType {
+    #[serde(rename = "type")]
+    ty: String,
+    fields: Vec<Field>,
+}
+
+/// A data file to add to the table.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AddFile {
+    /// Path of the file, relative to the table's root.
+    pub path: String,
+    pub partition_values: BTreeMap<String, Option<String>>,
+    pub size: u64,
+    pub modification_time: i64,
+    pub data_change: bool,
+    pub stats: String,
+}
+
+/// Data files written by the `DeltaWriter`, waiting to be committed to the table.
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct PendingCommit {
+    pub epoch: u64,
+    pub checkpoint_range: Range<u64>,
+    pub schema: Vec<Field>,
+    pub partition_columns: Vec<String>,
+    pub files: Vec<AddFile>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Metadata {
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    format: Format,
+    schema_string: String,
+    partition_columns: Vec<String>,
+    #[serde(default)]
+    configuration: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_time: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Format {
+    provider: String,
+    #[serde(default)]
+    options: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Protocol {
+    min_reader_version: u32,
+    min_writer_version: u32,
+}
+
+/// Progress of an application writing to the table, recorded in the log so that it survives
+/// checkpoints (unlike `CommitInfo`). The indexer records the checkpoint to resume from as the
+/// version of its transaction.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Txn {
+    app_id: String,
+    version: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_updated: Option<i64>,
+}
+
+/// Extra information recorded in each commit, describing the data it added.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommitInfo {
+    timestamp: i64,
+    operation: String,
+    operation_parameters: BTreeMap<String, String>,
+    engine_info: String,
+    sui_epoch: u64,
+    sui_checkpoint_range: Range<u64>,
+}
+
+/// Contents of the `_last_checkpoint` file, pointing at the latest checkpoint of the log.
+#[derive(Serialize, Deserialize)]
+struct LastCheckpoint {
+    version: u64,
+    size: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Action<'a> {
+    Protocol(&'a Protocol),
+    MetaData(&'a Metadata),
+    Txn(&'a Txn),
+    Add(&'a AddFile),
+    CommitInfo(&'a CommitInfo),
+}
+
+/// The state of the table as of some version, from replaying its log.
+#[derive(Default)]
+struct Snapshot {
+    /// Latest version of the table, or `None` if it does not exist yet.
+    version: Option<u64>,
+    protocol: Option<Protocol>,
+    metadata: Option<Metadata>,
+    /// The indexer's latest transaction.
+    txn: Option<Txn>,
+    /// Data files in the table, by path.
+    files: BTreeMap<String, AddFile>,
+}
+
+/// A Delta Lake table in the remote store.
+pub(crate) struct DeltaTable {
+    store: Arc<DynObjectStore>,
+    root: Path,
+    /// The table's latest state, loaded from the log on first use, and kept up-to-date by commits
+    /// so that they don't need to re-read the log.
+    snapshot: Mutex<Option<Snapshot>>,
+}
+
+impl Snapshot {
+    /// Apply an action read from the log.
+    fn apply(&mut self, mut action: Value) -> Result<()> {
+        if let Some(protocol) = action.get_mut("protocol") {
+            self.protocol = Some(serde_json::from_value(protocol.take())?);
+        } else if let Some(metadata) = action.get_mut("metaData") {
+            self.metadata = Some(serde_json::from_value(metadata.take())?);
+        } else if let Some(txn) = action.get_mut("txn") {
+            let txn: Txn = serde_json::from_value(txn.take())?;
+            if txn.app_id == APP_ID {
+                self.txn = Some(txn);
+            }
+        } else if let Some(add) = action.get_mut("add") {
+            let add: AddFile = serde_json::from_value(add.take())?;
+            self.files.insert(add.path.clone(), add);
+        } else if let Some(path) = action["remove"]["path"].as_str() {
+            self.files.remove(path);
+        }
+
+        Ok(())
+    }
+
+    /// The actions that reconstruct this snapshot, as rows of a checkpoint.
+    fn checkpoint(&self) -> Result<Vec<Value>> {
+        let mut actions = vec![];
+        actions.extend(self.protocol.as_ref().map(Action::Protocol));
+        actions.extend(self.metadata.as_ref().map(Action::MetaData));
+        actions.extend(self.txn.as_ref().map(Action::Txn));
+        actions.extend(self.files.values().map(Action::Add));
+
+        Ok(actions
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<_, _>>()?)
+    }
+}
+
+impl DeltaTable {
+    pub(crate) fn new(store: Arc<DynObjectStore>, root: Path) -> Self {
+        Self {
+            store,
+            root,
+            snapshot: Mutex::new(None),
+        }
+    }
+
+    /// The checkpoint to resume indexing from: the end of the range of checkpoints in the latest
+    /// commit, or `None` if nothing has been committed to the table yet.
+    pub(crate) async fn next_checkpoint(&self) -> Result<Option<u64>> {
+        let mut snapshot = self.snapshot.lock().await;
+        if snapshot.is_none() {
+            *snapshot = Some(self.load().await?);
+        }
+
+        Ok(snapshot
+            .as_ref()
+            .and_then(|s| s.txn.as_ref())
+            .map(|txn| txn.version as u64))
+    }
+
+    /// Commit the data files described by `pending` to the table, creating the table if it does
+    /// not exist yet, and adding any new columns to its schema. Returns the version of the commit.
+    pub(crate) async fn commit(&self, pending: &PendingCommit) -> Result<u64> {
+        let mut guard = self.snapshot.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.load().await?);
+        }
+
+        let snapshot = guard.as_mut().expect("Snapshot was just loaded");
+
+        let version = snapshot.version.map_or(0, |v| v + 1);
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let mut protocol = None;
+        let mut metadata = None;
+        match &snapshot.metadata {
+            None => {
+                protocol = Some(Protocol {
+                    min_reader_version: MIN_READER_VERSION,
+                    min_writer_version: MIN_WRITER_VERSION,
+                });
+
+                metadata = Some(Metadata {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name: None,
+                    description: None,
+                    format: Format {
+                        provider: "parquet".to_string(),
+                        options: BTreeMap::new(),
+                    },
+                    schema_string: schema_string(pending.schema.clone())?,
+                    partition_columns: pending.partition_columns.clone(),
+                    configuration: BTreeMap::new(),
+                    created_time: Some(now),
+                });
+            }
+
+            Some(existing) => {
+                if existing.partition_columns != pending.partition_columns {
+                    bail!(
+                        "Table is partitioned by {:?}, but data was partitioned by {:?}",
+                        existing.partition_columns,
+                        pending.partition_columns,
+                    );
+                }
+
+                let table: StructType = serde_json::from_str(&existing.schema_string)
+                    .context("Failed to parse table schema")?;
+
+                if let Some(fields) = evolve_schema(&table.fields, &pending.schema)? {
+                    info!(
+                        "Adding columns to Delta table {}: {:?}",
+                        self.root,
+                        &fields[table.fields.len()..],
+                    );
+
+                    metadata = Some(Metadata {
+                        schema_string: schema_string(fields)?,
+                        ..existing.clone()
+                    });
+                }
+            }
+        }
+
+        let txn = Txn {
+            app_id: APP_ID.to_string(),
+            version: pending.checkpoint_range.end as i64,
+            last_updated: Some(now),
+        };
+
+        let info = CommitInfo {
+            timestamp: now,
+            operation: "WRITE".to_string(),
+            operation_parameters: BTreeMap::from([("mode".to_string(), "Append".to_string())]),
+            engine_info: APP_ID.to_string(),
+            sui_epoch: pending.epoch,
+            sui_checkpoint_range: pending.checkpoint_range.clone(),
+        };
+
+        let mut actions = vec![];
+        actions.extend(protocol.as_ref().map(Action::Protocol));
+        actions.extend(metadata.as_ref().map(Action::MetaData));
+        actions.push(Action::Txn(&txn));
+        actions.extend(pending.files.iter().map(Action::Add));
+        actions.push(Action::CommitInfo(&info));
+
+        let mut commit = String::new();
+        for action in actions {
+            commit.push_str(&serde_json::to_string(&action)?);
+            commit.push('\n');
+        }
+
+        // Commits must not overwrite each other, so the store must support conditional writes.
+        // If the outcome of the write is unknown, the cached snapshot is dropped, to be reloaded
+        // from the log on the next attempt.
+        let path = self.commit_path(version);
+        let options = PutOptions {
+            mode: PutMode::Create,
+            ..Default::default()
+        };
+
+        match self
+            .store
+            .put_opts(&path, PutPayload::from(commit.into_bytes()), options)
+            .await
+        {
+            Ok(_) => {}
+            Err(object_store::Error::NotImplemented) => {
+                bail!(
+                    "Delta table {} requires a store that supports conditional writes",
+                    self.root,
+                );
+            }
+            Err(object_store::Error::AlreadyExists { .. }) => {
+                *guard = None;
+                bail!("Delta table {} was written to concurrently", self.root);
+            }
+            Err(e) => {
+                *guard = None;
+                return Err(e.into());
+            }
+        }
+
+        snapshot.version = Some(version);
+        snapshot.protocol = protocol.or(snapshot.protocol.take());
+        snapshot.metadata = metadata.or(snapshot.metadata.take());
+        snapshot.txn = Some(txn);
+        for file in &pending.files {
+            snapshot.files.insert(file.path.clone(), file.clone());
+        }
+
+        // Checkpoints only speed up loading the table, so failing to write one is not fatal.
+        if version > 0 && version % CHECKPOINT_INTERVAL == 0 {
+            if let Err(e) = self.write_checkpoint(version, snapshot).await {
+                warn!(
+                    "Failed to checkpoint Delta table {} at {version}: {e:?}",
+                    self.root
+                );
+            }
+        }
+
+        Ok(version)
+    }
+
+    /// Upload the data file at `local` in the `from` store to `path`, relative to the table's
+    /// root.
+    pub(crate) async fn upload(
+        &self,
+        from: &Arc<DynObjectStore>,
+        local: &Path,
+        path: &str,
+    ) -> Result<()> {
+        let remote = Path::parse(format!("{}/{path}", self.root))?;
+        copy_file(local, &remote, from, &self.store).await
+    }
+
+    /// Load the table's latest state from its log: from its latest checkpoint, if it has one,
+    /// followed by the commits after it, up to the first version that does not exist.
+    async fn load(&self) -> Result<Snapshot> {
+        let mut snapshot = Snapshot::default();
+        if let Some(bytes) = self.read(&self.last_checkpoint_path()).await? {
+            let last: LastCheckpoint =
+                serde_json::from_slice(&bytes).context("Failed to parse _last_checkpoint")?;
+
+            for action in self.read_checkpoint(last.version).await? {
+                snapshot.apply(action)?;
+            }
+
+            snapshot.version = Some(last.version);
+        }
+
+        loop {
+            let version = snapshot.version.map_or(0, |v| v + 1);
+            let Some(actions) = self.read_commit(version).await? else {
+                break;
+            };
+
+            for action in actions {
+                snapshot.apply(action)?;
+            }
+
+            snapshot.version = Some(version);
+        }
+
+        let Some(metadata) = &snapshot.metadata else {
+            if snapshot.version.is_some() {
+                bail!("Failed to find the metadata of Delta table {}", self.root);
+            }
+
+            return Ok(snapshot);
+        };
+
+        // Checkpoints can drop null partition values, so restore them.
+        for file in snapshot.files.values_mut() {
+            for column in &metadata.partition_columns {
+                file.partition_values.entry(column.clone()).or_default();
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Write a checkpoint of `snapshot`, the state of the table at `version`, and point
+    /// `_last_checkpoint` at it.
+    async fn write_checkpoint(&self, version: u64, snapshot: &Snapshot) -> Result<()> {
+        let rows = snapshot.checkpoint()?;
+        let schema = checkpoint_schema();
+
+        let mut decoder = ReaderBuilder::new(schema.clone()).build_decoder()?;
+        decoder.serialize(&rows)?;
+        let batch = decoder.flush()?.context("Checkpoint has no actions")?;
+
+        let mut buf = vec![];
+        let mut writer = ArrowWriter::try_new(&mut buf, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        self.store
+            .put(&self.checkpoint_path(version), buf.into())
+            .await?;
+
+        let last = LastCheckpoint {
+            version,
+            size: rows.len() as u64,
+        };
+
+        self.store
+            .put(
+                &self.last_checkpoint_path(),
+                serde_json::to_vec(&last)?.into(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// The actions in the checkpoint at `version`.
+    async fn read_checkpoint(&self, version: u64) -> Result<Vec<Value>> {
+        let bytes = self
+            .read(&self.checkpoint_path(version))
+            .await?
+            .ok_or_else(|| anyhow!("Missing checkpoint {version} of Delta table {}", self.root))?;
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(bytes)?.build()?;
+        let mut writer = ArrayWriter::new(vec![]);
+        for batch in reader {
+            writer.write(&batch?)?;
+        }
+
+        writer.finish()?;
+        let json = writer.into_inner();
+        if json.is_empty() {
+            return Ok(vec![]);
+        }
+
+        serde_json::from_slice(&json)
+            .with_context(|| format!("Failed to parse checkpoint {version}"))
+    }
+
+    /// The actions in the commit at `version`, or `None` if there is no such commit.
+    async fn read_commit(&self, version: u64) -> Result<Option<Vec<Value>>> {
+        let Some(bytes) = self.read(&self.commit_path(version)).await? else {
+            return Ok(None);
+        };
+
+        let commit = std::str::from_utf8(&bytes)?;
+        commit
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Failed to parse commit {version}"))
+            })
+            .collect::<Result<_>>()
+            .map(Some)
+    }
+
+    /// The contents of the file at `path`, or `None` if it does not exist.
+    async fn read(&self, path: &Path) -> Result<Option<Bytes>> {
+        match self.store.get(path).await {
+            Ok(result) => Ok(Some(result.bytes().await?)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn commit_path(&self, version: u64) -> Path {
+        self.log_path(format!("{version:020}.json"))
+    }
+
+    fn checkpoint_path(&self, version: u64) -> Path {
+        self.log_path(format!("{version:020}.checkpoint.parquet"))
+    }
+
+    fn last_checkpoint_path(&self) -> Path {
+        self.log_path(LAST_CHECKPOINT)
+    }
+
+    fn log_path(&self, file: impl AsRef<str>) -> Path {
+        self.root.child(DELTA_LOG_DIR).child(file.as_ref())
+    }
+}
+
+/// Path of the manifest for data files covering `checkpoint_range`, in the local staging
+/// directory.
+pub(crate) fn manifest_path(file_type: FileType, checkpoint_range: &Range<u64>) -> Path {
+    file_type.dir_prefix().child(PENDING_DIR).child(format!(
+        "{}_{}.json",
+        checkpoint_range.start, checkpoint_range.end
+    ))
+}
+
+/// Path of a data file relative to the table's root. Partition values are only included in the
+/// path to group files together: readers get them from the log, so it is fine to sanitize them.
+pub(crate) fn data_file_path(
+    partition_values: &BTreeMap<String, Option<String>>,
+    checkpoint_range: &Range<u64>,
+    index: usize,
+) -> String {
+    let mut path = String::new();
+    for (column, value) in partition_values {
+        let value = match value {
+            Some(value) => value
+                .chars()
+                .map(|c| match c {
+                    'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+                    _ => '_',
+                })
+                .collect(),
+            None => "__HIVE_DEFAULT_PARTITION__".to_string(),
+        };
+
+        path.push_str(&format!("{column}={value}/"));
+    }
+
+    path.push_str(&format!(
+        "{}_{}_{index}.parquet",
+        checkpoint_range.start, checkpoint_range.end
+    ));
+    path
+}
+
+/// The type of a column in the table, given one of its values.
+pub(crate) fn delta_type(value: &ParquetValue) -> &'static str {
+    match value {
+        ParquetValue::U64(_) | ParquetValue::OptionU64(_) => U64_TYPE,
+        ParquetValue::I64(_) => "long",
+        ParquetValue::Str(_) | ParquetValue::OptionStr(_) => "string",
+        ParquetValue::Bool(_) => "boolean",
+    }
+}
+
+/// The value of a partition column, as it is recorded in the log.
+pub(crate) fn partition_value(value: &ParquetValue) -> Option<String> {
+    match value {
+        ParquetValue::U64(v) => Some(v.to_string()),
+        ParquetValue::I64(v) => Some(v.to_string()),
+        ParquetValue::Str(v) => Some(v.clone()),
+        ParquetValue::Bool(v) => Some(v.to_string()),
+        ParquetValue::OptionU64(v) => v.map(|v| v.to_string()),
+        ParquetValue::OptionStr(v) => v.clone(),
+    }
+}
+
+/// Merge the columns in `written` into the table's schema, `table`. Columns can be added to the
+/// table, but not removed (columns that are no longer written are null in new data files), and
+/// their types cannot change. Returns the new schema, if it changed.
+fn evolve_schema(table: &[Field], written: &[Field]) -> Result<Option<Vec<Field>>> {
+    let mut fields = table.to_vec();
+    for field in written {
+        match table.iter().find(|f| f.name == field.name) {
+            Some(existing) if existing.ty != field.ty => bail!(
+                "Column {} has type {} in the table, but {} in the data written to it",
+                field.name,
+                existing.ty,
+                field.ty,
+            ),
+            Some(_) => {}
+            None => fields.push(field.clone()),
+        }
+    }
+
+    Ok((fields.len() > table.len()).then_some(fields))
+}
+
+fn schema_string(fields: Vec<Field>) -> Result<String> {
+    Ok(serde_json::to_string(&StructType {
+        ty: "struct".to_string(),
+        fields,
+    })?)
+}
+
+/// Schema of the checkpoints written to the table's log, covering the actions that the indexer
+/// writes.
+fn checkpoint_schema() -> SchemaRef {
+    fn string(name: &str) -> ArrowField {
+        ArrowField::new(name, DataType::Utf8, true)
+    }
+
+    fn long(name: &str) -> ArrowField {
+        ArrowField::new(name, DataType::Int64, true)
+    }
+
+    fn boolean(name: &str) -> ArrowField {
+        ArrowField::new(name, DataType::Boolean, true)
+    }
+
+    fn map(name: &str) -> ArrowField {
+        let key = ArrowField::new("key", DataType::Utf8, false);
+        let value = ArrowField::new("value", DataType::Utf8, true);
+        ArrowField::new_map(name, "key_value", key, value, false, true)
+    }
+
+    fn list(name: &str) -> ArrowField {
+        let element = ArrowField::new("element", DataType::Utf8, true);
+        ArrowField::new(name, DataType::List(Arc::new(element)), true)
+    }
+
+    fn structure(name: &str, fields: Vec<ArrowField>) -> ArrowField {
+        ArrowField::new(name, DataType::Struct(Fields::from(fields)), true)
+    }
+
+    Arc::new(Schema::new(vec![
+        structure(
+            "txn",
+            vec![string("appId"), long("version"), long("lastUpdated")],
+        ),
+        structure(
+            "add",
+            vec![
+                string("path"),
+                map("partitionValues"),
+                long("size"),
+                long("modificationTime"),
+                boolean("dataChange"),
+                string("stats"),
+            ],
+        ),
+        structure(
+            "remove",
+            vec![
+                string("path"),
+                long("deletionTimestamp"),
+                boolean("dataChange"),
+            ],
+        ),
+        structure(
+            "metaData",
+            vec![
+                string("id"),
+                string("name"),
+                string("description"),
+                structure("format", vec![string("provider"), map("options")]),
+                string("schemaString"),
+                list("partitionColumns"),
+                map("configuration"),
+                long("createdTime"),
+            ],
+        ),
+        structure(
+            "protocol",
+            vec![
+                ArrowField::new("minReaderVersion", DataType::Int32, true),
+                ArrowField::new("minWriterVersion", DataType::Int32, true),
+            ],
+        ),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::http::HttpBuilder;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    fn pending(checkpoint_range: Range<u64>, columns: &[&str]) -> PendingCommit {
+        let schema = columns
+            .iter()
+            .map(|name| Field {
+                name: name.to_string(),
+                ty: "long".to_string(),
+                nullable: true,
+                metadata: BTreeMap::new(),
+            })
+            .collect();
+
+        // Alternate between null and non-null partition values.
+        let epoch =
+            (checkpoint_range.start / 10 % 2 == 0).then(|| checkpoint_range.start.to_string());
+        let partition_values = BTreeMap::from([("epoch".to_string(), epoch)]);
+        let path = data_file_path(&partition_values, &checkpoint_range, 0);
+
+        PendingCommit {
+            epoch: 0,
+            checkpoint_range,
+            schema,
+            partition_columns: vec!["epoch".to_string()],
+            files: vec![AddFile {
+                path,
+                partition_values,
+                size: 100,
+                modification_time: 0,
+                data_change: true,
+                stats: "{}".to_string(),
+            }],
+        }
+    }
+
+    fn table(store: &Arc<DynObjectStore>) -> DeltaTable {
+        DeltaTable::new(store.clone(), Path::from("table"))
+    }
+
+    fn columns(metadata: &Metadata) -> Vec<String> {
+        let schema: StructType = serde_json::from_str(&metadata.schema_string).unwrap();
+        schema.fields.into_iter().map(|f| f.name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_commit_round_trip() {
+        let store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+
+        let writer = table(&store);
+        assert_eq!(writer.next_checkpoint().await.unwrap(), None);
+        assert_eq!(writer.commit(&pending(0..10, &["a"])).await.unwrap(), 0);
+        assert_eq!(writer.next_checkpoint().await.unwrap(), Some(10));
+        assert_eq!(
+            writer.commit(&pending(10..20, &["a", "b"])).await.unwrap(),
+            1
+        );
+        assert_eq!(writer.commit(&pending(20..30, &["b"])).await.unwrap(), 2);
+
+        // A fresh table sees the same state, by reading the log.
+        let reader = table(&store);
+        assert_eq!(reader.next_checkpoint().await.unwrap(), Some(30));
+
+        let snapshot = reader.load().await.unwrap();
+        assert_eq!(snapshot.version, Some(2));
+        assert_eq!(snapshot.files.len(), 3);
+        assert!(snapshot.protocol.is_some());
+        assert_eq!(columns(snapshot.metadata.as_ref().unwrap()), ["a", "b"]);
+
+        // ...and carries on from there.
+        assert_eq!(reader.commit(&pending(30..40, &["a"])).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_round_trip() {
+        let store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+
+        let writer = table(&store);
+        for i in 0..=CHECKPOINT_INTERVAL {
+            let columns: &[&str] = if i < 5 { &["a"] } else { &["a", "b"] };
+            let version = writer
+                .commit(&pending(i * 10..(i + 1) * 10, columns))
+                .await
+                .unwrap();
+            assert_eq!(version, i);
+        }
+
+        let last = store.get(&writer.last_checkpoint_path()).await.unwrap();
+        let last: LastCheckpoint = serde_json::from_slice(&last.bytes().await.unwrap()).unwrap();
+        assert_eq!(last.version, CHECKPOINT_INTERVAL);
+
+        // Protocol, metadata, txn, and one file per commit.
+        let files = CHECKPOINT_INTERVAL as usize + 1;
+        assert_eq!(last.size as usize, 3 + files);
+
+        // Remove the commits covered by the checkpoint, so that the table can only be loaded
+        // from the checkpoint.
+        for version in 0..=CHECKPOINT_INTERVAL {
+            store.delete(&writer.commit_path(version)).await.unwrap();
+        }
+
+        let reader = table(&store);
+        let expected = writer.snapshot.lock().await;
+        let expected = expected.as_ref().unwrap();
+        let snapshot = reader.load().await.unwrap();
+
+        assert_eq!(snapshot.version, Some(CHECKPOINT_INTERVAL));
+        assert_eq!(
+            snapshot.txn.as_ref().unwrap().version,
+            expected.txn.as_ref().unwrap().version,
+        );
+
+        let metadata = snapshot.metadata.as_ref().unwrap();
+        assert_eq!(metadata.id, expected.metadata.as_ref().unwrap().id);
+        assert_eq!(metadata.partition_columns, ["epoch"]);
+        assert_eq!(columns(metadata), ["a", "b"]);
+
+        assert_eq!(snapshot.files.len(), files);
+        for (path, file) in &snapshot.files {
+            let original = &expected.files[path];
+            assert_eq!(file.partition_values, original.partition_values);
+            assert_eq!(file.size, original.size);
+            assert_eq!(file.stats, original.stats);
+        }
+
+        let next = CHECKPOINT_INTERVAL * 10 + 10;
+        assert_eq!(reader.next_checkpoint().await.unwrap(), Some(next));
+        assert_eq!(
+            reader
+                .commit(&pending(next..next + 10, &["a"]))
+                .await
+                .unwrap(),
+            CHECKPOINT_INTERVAL + 1,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_commit() {
+        let store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+
+        let a = table(&store);
+        let b = table(&store);
+        assert_eq!(a.next_checkpoint().await.unwrap(), None);
+        assert_eq!(b.next_checkpoint().await.unwrap(), None);
+
+        assert_eq!(a.commit(&pending(0..10, &["a"])).await.unwrap(), 0);
+        let err = b.commit(&pending(0..10, &["a"])).await.unwrap_err();
+        assert!(err.to_string().contains("concurrently"), "{err}");
+
+        // The losing writer reloads the table before trying again.
+        assert_eq!(b.next_checkpoint().await.unwrap(), Some(10));
+        assert_eq!(b.commit(&pending(10..20, &["a"])).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_conditional_writes_required() {
+        // The HTTP store does not support conditional writes.
+        let store: Arc<DynObjectStore> = Arc::new(
+            HttpBuilder::new()
+                .with_url("http://localhost:1")
+                .build()
+                .unwrap(),
+        );
+
+        let table = table(&store);
+        *table.snapshot.lock().await = Some(Snapshot::default());
+
+        let err = table.commit(&pending(0..10, &["a"])).await.unwrap_err();
+        assert!(err.to_string().contains("conditional writes"), "{err}");
+    }
+}
@@ -4,7 +4,7 @@
 use std::ops::Range;
 use std::path::PathBuf;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use arrow_array::{Array, Int32Array};
 use clap::*;
 use gcp_bigquery_client::model::query_request::QueryRequest;
@@ -29,6 +29,7 @@ use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
 use crate::analytics_metrics::AnalyticsMetrics;
 use crate::analytics_processor::AnalyticsProcessor;
+use crate::delta::DeltaTable;
 use crate::handlers::checkpoint_handler::CheckpointHandler;
 use crate::handlers::df_handler::DynamicFieldHandler;
 use crate::handlers::event_handler::EventHandler;
@@ -45,12 +46,14 @@ use crate::tables::{
     TransactionObjectEntry, WrappedObjectEntry,
 };
 use crate::writers::csv_writer::CSVWriter;
+use crate::writers::delta_writer::DeltaWriter;
 use crate::writers::parquet_writer::ParquetWriter;
 use crate::writers::AnalyticsWriter;
 use gcp_bigquery_client::model::query_response::ResultSet;
 
 pub mod analytics_metrics;
 pub mod analytics_processor;
+mod delta;
 pub mod errors;
 mod handlers;
 mod package_store;
@@ -109,6 +112,12 @@ pub struct AnalyticsIndexerConfig {
     // File format to store data in i.e. csv, parquet, etc
     #[clap(long, value_enum, default_value = "csv", global = true)]
     pub file_format: FileFormat,
+    // Table format to store data in i.e. plain files, or a Delta Lake table (requires parquet)
+    #[clap(long, value_enum, default_value = "files", global = true)]
+    pub table_format: TableFormat,
+    // Columns to partition Delta Lake tables by, e.g. epoch
+    #[clap(long, value_delimiter = ',', global = true)]
+    pub partition_columns: Vec<String>,
     // Type of data to write i.e. checkpoint, object, transaction, etc
     #[clap(long, value_enum, long, global = true)]
     pub file_type: FileType,
@@ -307,6 +316,14 @@ impl FileFormat {
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, ValueEnum)]
+pub enum TableFormat {
+    /// Files for each range of checkpoints, in a directory per epoch.
+    Files,
+    /// A Delta Lake table, whose schema evolves as columns are added to it.
+    Delta,
+}
+
 #[derive(
     Copy,
     Clone,
@@ -869,6 +886,19 @@ pub fn make_writer<S: Serialize + ParquetSchema>(
     file_type: FileType,
     starting_checkpoint_seq_num: u64,
 ) -> Result<Box<dyn AnalyticsWriter<S>>> {
+    if config.table_format == TableFormat::Delta {
+        if config.file_format != FileFormat::PARQUET {
+            bail!("Delta Lake tables can only be written as parquet");
+        }
+
+        return Ok(Box::new(DeltaWriter::new::<S>(
+            &config.checkpoint_dir,
+            file_type,
+            config.partition_columns,
+            starting_checkpoint_seq_num,
+        )?));
+    }
+
     Ok(match config.file_format {
         FileFormat::CSV => Box::new(CSVWriter::new(
             &config.checkpoint_dir,
@@ -887,12 +917,20 @@ pub async fn get_starting_checkpoint_seq_num(
     config: AnalyticsIndexerConfig,
     file_type: FileType,
 ) -> Result<u64> {
-    let remote_latest = read_store_for_checkpoint(
-        config.remote_store_config,
-        file_type,
-        config.remote_store_path_prefix,
-    )
-    .await?;
+    let remote_latest = match config.table_format {
+        TableFormat::Files => {
+            read_store_for_checkpoint(
+                config.remote_store_config,
+                file_type,
+                config.remote_store_path_prefix,
+            )
+            .await?
+        }
+        TableFormat::Delta => make_delta_table(&config, file_type)?
+            .next_checkpoint()
+            .await?
+            .unwrap_or(0),
+    };
 
     Ok(config
         .starting_checkpoint_seq_num
@@ -916,6 +954,20 @@ pub async fn make_analytics_processor(
     }
 }
 
+/// The Delta Lake table that data of type `file_type` is written to, in the remote store.
+pub(crate) fn make_delta_table(
+    config: &AnalyticsIndexerConfig,
+    file_type: FileType,
+) -> Result<DeltaTable> {
+    Ok(DeltaTable::new(
+        config.remote_store_config.make()?,
+        join_paths(
+            config.remote_store_path_prefix.clone(),
+            &file_type.dir_prefix(),
+        ),
+    ))
+}
+
 pub fn join_paths(base: Option<Path>, child: &Path) -> Path {
     base.map(|p| {
         let mut out_path = p.clone();
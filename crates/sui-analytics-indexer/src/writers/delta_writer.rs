@@ -0,0 +1,230 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::delta::{
+    data_file_path, delta_type, manifest_path, partition_value, AddFile, Field, PendingCommit,
+};
+use crate::{AnalyticsWriter, FileFormat, FileType};
+use crate::{ParquetSchema, ParquetValue};
+use anyhow::{anyhow, bail, Result};
+use arrow_array::{ArrayRef, BooleanArray, Decimal128Array, Int64Array, RecordBatch, StringArray};
+use object_store::path::Path as ObjectPath;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::{create_dir_all, File};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use sui_types::base_types::EpochId;
+
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use sui_storage::object_store::util::path_to_filesystem;
+
+// Save table entries to parquet files, to be committed to a Delta Lake table. Rows are split into
+// one file per combination of values of the partition columns, which are not stored in the files
+// themselves.
+pub(crate) struct DeltaWriter {
+    root_dir_path: PathBuf,
+    file_type: FileType,
+    partition_columns: Vec<String>,
+    epoch: EpochId,
+    checkpoint_range: Range<u64>,
+    rows: Vec<Vec<ParquetValue>>,
+}
+
+impl DeltaWriter {
+    pub(crate) fn new<S: ParquetSchema>(
+        root_dir_path: &Path,
+        file_type: FileType,
+        partition_columns: Vec<String>,
+        start_checkpoint_seq_num: u64,
+    ) -> Result<Self> {
+        let schema = S::schema();
+        for column in &partition_columns {
+            if !schema.contains(column) {
+                bail!("Cannot partition {file_type:?} table by unknown column {column}");
+            }
+        }
+
+        let checkpoint_range = start_checkpoint_seq_num..u64::MAX;
+        Ok(Self {
+            root_dir_path: root_dir_path.to_path_buf(),
+            file_type,
+            partition_columns,
+            epoch: 0,
+            checkpoint_range,
+            rows: vec![],
+        })
+    }
+
+    fn file(&self, path: &ObjectPath) -> Result<(File, PathBuf)> {
+        let file_path = path_to_filesystem(self.root_dir_path.clone(), path)?;
+        create_dir_all(file_path.parent().ok_or(anyhow!("Bad directory path"))?)?;
+        Ok((File::create(&file_path)?, file_path))
+    }
+
+    /// Path of a file in the local staging directory, given its path relative to the table.
+    fn table_path(&self, path: &str) -> Result<ObjectPath> {
+        Ok(ObjectPath::parse(format!(
+            "{}/{path}",
+            self.file_type.dir_prefix()
+        ))?)
+    }
+}
+
+/// Convert a column of values into an arrow array, matching the column's type in the table.
+fn to_arrow_array(column: Vec<ParquetValue>) -> Result<ArrayRef> {
+    Ok(match &column[0] {
+        ParquetValue::U64(_) | ParquetValue::OptionU64(_) => Arc::new(
+            column
+                .into_iter()
+                .map(|value| match value {
+                    ParquetValue::U64(v) => Some(v as i128),
+                    ParquetValue::OptionU64(v) => v.map(|v| v as i128),
+                    _ => None,
+                })
+                .collect::<Decimal128Array>()
+                .with_precision_and_scale(20, 0)?,
+        ),
+        ParquetValue::I64(_) => {
+            Arc::new(Int64Array::from_iter(column.into_iter().map(
+                |value| match value {
+                    ParquetValue::I64(v) => Some(v),
+                    _ => None,
+                },
+            )))
+        }
+        ParquetValue::Str(_) | ParquetValue::OptionStr(_) => Arc::new(StringArray::from_iter(
+            column.into_iter().map(|value| match value {
+                ParquetValue::Str(v) => Some(v),
+                ParquetValue::OptionStr(v) => v,
+                _ => None,
+            }),
+        )),
+        ParquetValue::Bool(_) => Arc::new(BooleanArray::from_iter(column.into_iter().map(
+            |value| match value {
+                ParquetValue::Bool(v) => Some(v),
+                _ => None,
+            },
+        ))),
+    })
+}
+
+impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for DeltaWriter {
+    fn file_format(&self) -> Result<FileFormat> {
+        Ok(FileFormat::PARQUET)
+    }
+
+    fn write(&mut self, rows: &[S]) -> Result<()> {
+        let num_columns = S::schema().len();
+        for row in rows {
+            self.rows
+                .push((0..num_columns).map(|idx| row.get_column(idx)).collect());
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, end_checkpoint_seq_num: u64) -> Result<bool> {
+        if self.rows.is_empty() {
+            return Ok(false);
+        }
+        self.checkpoint_range.end = end_checkpoint_seq_num;
+
+        let schema = S::schema();
+        let partition_indices: Vec<_> = self
+            .partition_columns
+            .iter()
+            .map(|column| schema.iter().position(|c| c == column).unwrap())
+            .collect();
+
+        // The type of each column is only known from its values.
+        let fields: Vec<_> = schema
+            .iter()
+            .zip(&self.rows[0])
+            .map(|(name, value)| Field {
+                name: name.clone(),
+                ty: delta_type(value).to_string(),
+                nullable: true,
+                metadata: BTreeMap::new(),
+            })
+            .collect();
+
+        let mut partitions: BTreeMap<Vec<Option<String>>, Vec<Vec<ParquetValue>>> = BTreeMap::new();
+        for row in std::mem::take(&mut self.rows) {
+            let key = partition_indices
+                .iter()
+                .map(|idx| partition_value(&row[*idx]))
+                .collect();
+            partitions.entry(key).or_default().push(row);
+        }
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+
+        let mut files = vec![];
+        for (index, (key, rows)) in partitions.into_iter().enumerate() {
+            let num_records = rows.len();
+
+            let mut columns: Vec<Vec<ParquetValue>> = schema.iter().map(|_| vec![]).collect();
+            for row in rows {
+                for (idx, value) in row.into_iter().enumerate() {
+                    columns[idx].push(value);
+                }
+            }
+
+            let mut batch_data = vec![];
+            for (idx, column) in columns.into_iter().enumerate() {
+                if !partition_indices.contains(&idx) {
+                    batch_data.push((schema[idx].clone(), to_arrow_array(column)?));
+                }
+            }
+
+            let partition_values: BTreeMap<_, _> =
+                self.partition_columns.iter().cloned().zip(key).collect();
+            let path = data_file_path(&partition_values, &self.checkpoint_range, index);
+
+            let batch = RecordBatch::try_from_iter(batch_data)?;
+            let (file, file_path) = self.file(&self.table_path(&path)?)?;
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties.clone()))?;
+            writer.write(&batch)?;
+            writer.close()?;
+
+            files.push(AddFile {
+                path,
+                partition_values,
+                size: std::fs::metadata(&file_path)?.len(),
+                modification_time: chrono::Utc::now().timestamp_millis(),
+                data_change: true,
+                stats: serde_json::json!({ "numRecords": num_records }).to_string(),
+            });
+        }
+
+        let pending = PendingCommit {
+            epoch: self.epoch,
+            checkpoint_range: self.checkpoint_range.clone(),
+            schema: fields,
+            partition_columns: self.partition_columns.clone(),
+            files,
+        };
+
+        let (manifest, _) = self.file(&manifest_path(self.file_type, &self.checkpoint_range))?;
+        serde_json::to_writer(manifest, &pending)?;
+        Ok(true)
+    }
+
+    fn reset(&mut self, epoch_num: EpochId, start_checkpoint_seq_num: u64) -> Result<()> {
+        self.checkpoint_range.start = start_checkpoint_seq_num;
+        self.checkpoint_range.end = u64::MAX;
+        self.epoch = epoch_num;
+        self.rows = vec![];
+        Ok(())
+    }
+
+    fn file_size(&self) -> Result<Option<u64>> {
+        // like the parquet writer, rows are only serialized when they are flushed
+        Ok(None)
+    }
+}
@@ -7,6 +7,7 @@ use serde::Serialize;
 use sui_types::base_types::EpochId;
 
 pub mod csv_writer;
+pub mod delta_writer;
 pub mod parquet_writer;
 
 pub trait AnalyticsWriter<S: Serialize + ParquetSchema>: Send + Sync + 'static {
@@ -7,7 +7,7 @@ use crate::{
 use anyhow::{anyhow, Context, Result};
 use bytes::buf::Reader;
 use bytes::{Buf, Bytes};
-use futures::{StreamExt, TryStreamExt};
+use futures::{Stream, StreamExt, TryStreamExt};
 use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
 use rand::seq::SliceRandom;
 use std::borrow::Borrow;
@@ -414,41 +414,11 @@ impl ArchiveReader {
     where
         S: WriteStore + Clone,
     {
-        let manifest = self.manifest.lock().await.clone();
-
-        let latest_available_checkpoint = manifest
-            .next_checkpoint_seq_num()
-            .checked_sub(1)
-            .context("Checkpoint seq num underflow")?;
-
-        if checkpoint_range.start > latest_available_checkpoint {
-            return Err(anyhow!(
-                "Latest available checkpoint is: {}",
-                latest_available_checkpoint
-            ));
-        }
-
-        let files: Vec<(FileMetadata, FileMetadata)> = self.verify_manifest(manifest).await?;
-
-        let start_index = match files.binary_search_by_key(&checkpoint_range.start, |(s, _c)| {
-            s.checkpoint_seq_range.start
-        }) {
-            Ok(index) => index,
-            Err(index) => index - 1,
-        };
-
-        let end_index = match files.binary_search_by_key(&checkpoint_range.end, |(s, _c)| {
-            s.checkpoint_seq_range.start
-        }) {
-            Ok(index) => index,
-            Err(index) => index,
-        };
+        let files = self.files_in_range(&checkpoint_range).await?;
 
         let remote_object_store = self.remote_object_store.clone();
         futures::stream::iter(files.iter())
-            .enumerate()
-            .filter(|(index, (_s, _c))| future::ready(*index >= start_index && *index < end_index))
-            .map(|(_, (summary_metadata, content_metadata))| {
+            .map(|(summary_metadata, content_metadata)| {
                 let remote_object_store = remote_object_store.clone();
                 async move {
                     let summary_data =
@@ -515,6 +485,95 @@ impl ArchiveReader {
             .await
     }
 
+    /// Stream checkpoints in `checkpoint_range` from the archive, in order, with their contents,
+    /// without loading them into a store. Checkpoint summaries are not verified, but their contents
+    /// are checked against them. At most `concurrency` archive files are held in memory at once.
+    pub async fn stream(
+        &self,
+        checkpoint_range: Range<CheckpointSequenceNumber>,
+    ) -> Result<impl Stream<Item = Result<(CertifiedCheckpointSummary, CheckpointContents)>>> {
+        let files = self.files_in_range(&checkpoint_range).await?;
+
+        let remote_object_store = self.remote_object_store.clone();
+        Ok(futures::stream::iter(files)
+            .map(move |(summary_metadata, content_metadata)| {
+                let remote_object_store = remote_object_store.clone();
+                async move {
+                    let summary_data =
+                        get(&remote_object_store, &summary_metadata.file_path()).await?;
+                    let content_data =
+                        get(&remote_object_store, &content_metadata.file_path()).await?;
+                    Ok::<(Bytes, Bytes), anyhow::Error>((summary_data, content_data))
+                }
+            })
+            .buffered(self.concurrency)
+            .map(move |result| {
+                let (summary_data, content_data) = result?;
+                let summary_iter = make_iterator::<CertifiedCheckpointSummary, Reader<Bytes>>(
+                    SUMMARY_FILE_MAGIC,
+                    summary_data.reader(),
+                )?;
+                let content_iter = make_iterator::<CheckpointContents, Reader<Bytes>>(
+                    CHECKPOINT_FILE_MAGIC,
+                    content_data.reader(),
+                )?;
+
+                summary_iter
+                    .zip(content_iter)
+                    .filter(|(s, _c)| checkpoint_range.contains(&s.sequence_number))
+                    .map(|(summary, contents)| {
+                        contents.verify_digests(summary.content_digest)?;
+                        Ok((summary, contents))
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .map_ok(|checkpoints| {
+                futures::stream::iter(checkpoints.into_iter().map(Ok::<_, anyhow::Error>))
+            })
+            .try_flatten())
+    }
+
+    /// Summary and content files in the archive that contain checkpoints in `checkpoint_range`.
+    /// Fails if the archive does not contain the start of the range.
+    async fn files_in_range(
+        &self,
+        checkpoint_range: &Range<CheckpointSequenceNumber>,
+    ) -> Result<Vec<(FileMetadata, FileMetadata)>> {
+        let manifest = self.manifest.lock().await.clone();
+
+        let latest_available_checkpoint = manifest
+            .next_checkpoint_seq_num()
+            .checked_sub(1)
+            .context("Checkpoint seq num underflow")?;
+
+        if checkpoint_range.start > latest_available_checkpoint {
+            return Err(anyhow!(
+                "Latest available checkpoint is: {}",
+                latest_available_checkpoint
+            ));
+        }
+
+        let mut files: Vec<(FileMetadata, FileMetadata)> = self.verify_manifest(manifest).await?;
+
+        let start_index = match files.binary_search_by_key(&checkpoint_range.start, |(s, _c)| {
+            s.checkpoint_seq_range.start
+        }) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        };
+
+        let end_index = match files.binary_search_by_key(&checkpoint_range.end, |(s, _c)| {
+            s.checkpoint_seq_range.start
+        }) {
+            Ok(index) => index,
+            Err(index) => index,
+        };
+
+        files.truncate(end_index);
+        files.drain(..start_index.min(end_index));
+        Ok(files)
+    }
+
     /// Return latest available checkpoint in archive
     pub async fn latest_available_checkpoint(&self) -> Result<CheckpointSequenceNumber> {
         let manifest = self.manifest.lock().await.clone();
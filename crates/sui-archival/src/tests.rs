@@ -5,6 +5,7 @@ use crate::reader::{ArchiveReader, ArchiveReaderMetrics};
 use crate::writer::ArchiveWriter;
 use crate::{read_manifest, verify_archive_with_local_store, write_manifest, Manifest};
 use anyhow::{anyhow, Context, Result};
+use futures::TryStreamExt;
 use more_asserts as ma;
 use object_store::DynObjectStore;
 use prometheus::Registry;
@@ -252,6 +253,57 @@ async fn test_archive_reader_e2e() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_archive_reader_stream() -> Result<(), anyhow::Error> {
+    let test_store = SharedInMemoryStore::default();
+    let test_state = setup_test_state(temp_dir()).await?;
+    let kill = test_state.archive_writer.start(test_store.clone()).await?;
+    let mut latest_archived_checkpoint_seq_num = 0;
+    while latest_archived_checkpoint_seq_num < 10 {
+        insert_checkpoints_and_verify_manifest(&test_state, test_store.clone(), None).await?;
+        latest_archived_checkpoint_seq_num = test_state
+            .archive_reader
+            .latest_available_checkpoint()
+            .await?;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    // The range starts and ends part of the way through archive files.
+    test_state.archive_reader.sync_manifest_once().await?;
+    let checkpoints: Vec<_> = test_state
+        .archive_reader
+        .stream(3..8)
+        .await?
+        .try_collect()
+        .await?;
+
+    let sequence_numbers: Vec<_> = checkpoints
+        .iter()
+        .map(|(s, _c)| s.sequence_number)
+        .collect();
+    assert_eq!(sequence_numbers, (3..8).collect::<Vec<_>>());
+    for (summary, contents) in checkpoints {
+        let expected = test_store
+            .get_checkpoint_by_sequence_number(summary.sequence_number)
+            .context("Missing checkpoint")?;
+        assert_eq!(summary.digest(), expected.digest());
+        assert_eq!(
+            contents.checkpoint_contents().digest(),
+            &expected.content_digest
+        );
+    }
+
+    // Checkpoints past the end of the archive can't be streamed.
+    assert!(test_state
+        .archive_reader
+        .stream(latest_archived_checkpoint_seq_num + 1..latest_archived_checkpoint_seq_num + 2)
+        .await
+        .is_err());
+
+    kill.send(())?;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_verify_archive_with_oneshot_store() -> Result<(), anyhow::Error> {
     let test_store = SharedInMemoryStore::default();
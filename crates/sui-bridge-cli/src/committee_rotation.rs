@@ -0,0 +1,383 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tooling for rotating the bridge committee, i.e. blocklisting and unblocklisting members on both
+//! chains. A rotation is first proposed, which checks that the resulting committee is safe and
+//! writes the governance actions that perform it to a plan file. Committee members approve the
+//! plan by adding its actions to `approved-governance-actions` in their node configs, and once
+//! enough of them have, the plan is executed.
+
+use anyhow::anyhow;
+use clap::*;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_bridge::abi::{EthBridgeCommittee, EthBridgeConfig};
+use sui_bridge::client::bridge_authority_aggregator::BridgeAuthorityAggregator;
+use sui_bridge::client::bridge_client::BridgeClient;
+use sui_bridge::crypto::BridgeAuthorityPublicKeyBytes;
+use sui_bridge::eth_transaction_builder::build_eth_transaction;
+use sui_bridge::metrics::BridgeMetrics;
+use sui_bridge::sui_client::SuiBridgeClient;
+use sui_bridge::sui_transaction_builder::build_sui_transaction;
+use sui_bridge::types::{
+    BlocklistCommitteeAction, BlocklistType, BridgeAction, BridgeActionType, BridgeCommittee,
+};
+use sui_config::Config;
+use sui_types::bridge::BridgeChainId;
+
+use crate::{submit_on_sui, LoadedBridgeCliConfig};
+
+/// How long to wait for a committee member's server to respond.
+const MEMBER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The governance actions that perform a committee rotation.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CommitteeRotationPlan {
+    /// Actions to execute in order, on Sui and on Eth.
+    pub actions: Vec<BridgeAction>,
+}
+
+impl Config for CommitteeRotationPlan {}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum CommitteeRotationCommands {
+    /// Propose a rotation, checking that the resulting committee is reachable and can approve
+    /// every action, and write the plan to `plan-path` for committee members to approve.
+    #[clap(name = "propose")]
+    Propose {
+        /// Members to remove from the active committee.
+        #[clap(long, use_value_delimiter = true)]
+        blocklist: Vec<BridgeAuthorityPublicKeyBytes>,
+        /// Members to restore to the active committee.
+        #[clap(long, use_value_delimiter = true)]
+        unblocklist: Vec<BridgeAuthorityPublicKeyBytes>,
+        #[clap(long)]
+        plan_path: PathBuf,
+    },
+    /// Report how much of the committee has approved each action in a plan.
+    #[clap(name = "status")]
+    Status {
+        #[clap(long)]
+        plan_path: PathBuf,
+    },
+    /// Collect the committee's signatures for each action in a plan and execute it.
+    #[clap(name = "execute")]
+    Execute {
+        #[clap(long)]
+        plan_path: PathBuf,
+        /// If true, only collect signatures but not execute on chain
+        #[clap(long)]
+        dry_run: bool,
+    },
+}
+
+impl CommitteeRotationCommands {
+    pub async fn handle(
+        self,
+        config: &LoadedBridgeCliConfig,
+        sui_bridge_client: SuiBridgeClient,
+    ) -> anyhow::Result<()> {
+        match self {
+            CommitteeRotationCommands::Propose {
+                blocklist,
+                unblocklist,
+                plan_path,
+            } => {
+                let plan = propose(blocklist, unblocklist, config, &sui_bridge_client).await?;
+                plan.save(&plan_path)?;
+                println!(
+                    "Wrote {} actions to {}. Committee members approve the rotation by adding \
+                     them to `approved-governance-actions` in their node configs.",
+                    plan.actions.len(),
+                    plan_path.display()
+                );
+                Ok(())
+            }
+
+            CommitteeRotationCommands::Status { plan_path } => {
+                let plan = CommitteeRotationPlan::load(&plan_path)?;
+                status(&plan, &sui_bridge_client).await
+            }
+
+            CommitteeRotationCommands::Execute { plan_path, dry_run } => {
+                let plan = CommitteeRotationPlan::load(&plan_path)?;
+                execute(&plan, config, &sui_bridge_client, dry_run).await
+            }
+        }
+    }
+}
+
+/// Build the actions that blocklist `blocklist` and unblocklist `unblocklist` on both chains.
+/// Members are unblocklisted before others are blocklisted, so that the committee keeps as much
+/// active stake as possible throughout the rotation.
+async fn propose(
+    blocklist: Vec<BridgeAuthorityPublicKeyBytes>,
+    unblocklist: Vec<BridgeAuthorityPublicKeyBytes>,
+    config: &LoadedBridgeCliConfig,
+    sui_bridge_client: &SuiBridgeClient,
+) -> anyhow::Result<CommitteeRotationPlan> {
+    if blocklist.is_empty() && unblocklist.is_empty() {
+        return Err(anyhow!("Nothing to rotate"));
+    }
+    if let Some(member) = blocklist.iter().find(|m| unblocklist.contains(m)) {
+        return Err(anyhow!("{member:?} is both blocklisted and unblocklisted"));
+    }
+
+    let message_type = BridgeActionType::UpdateCommitteeBlocklist as u8;
+    let summary = sui_bridge_client
+        .get_bridge_summary()
+        .await
+        .map_err(|e| anyhow!("Error getting bridge summary: {:?}", e))?;
+    let sui_chain_id = BridgeChainId::try_from(summary.chain_id)?;
+    let mut sui_nonce = summary
+        .sequence_nums
+        .iter()
+        .find_map(|(type_, nonce)| (*type_ == message_type).then_some(*nonce))
+        .unwrap_or(0);
+
+    let provider = Arc::new(config.eth_signer().clone());
+    let eth_committee =
+        EthBridgeCommittee::new(config.eth_bridge_committee_proxy_address, provider.clone());
+    let mut eth_nonce: u64 = eth_committee.nonces(message_type).call().await?;
+    let eth_chain_id: u8 = EthBridgeConfig::new(config.eth_bridge_config_proxy_address, provider)
+        .chain_id()
+        .call()
+        .await?;
+    let eth_chain_id = BridgeChainId::try_from(eth_chain_id)?;
+
+    let mut committee = sui_bridge_client
+        .get_bridge_committee()
+        .await
+        .map_err(|e| anyhow!("Error getting bridge committee: {:?}", e))?;
+
+    let mut actions = vec![];
+    for (blocklist_type, members) in [
+        (BlocklistType::Unblocklist, &unblocklist),
+        (BlocklistType::Blocklist, &blocklist),
+    ] {
+        if members.is_empty() {
+            continue;
+        }
+
+        for (chain_id, nonce) in [
+            (sui_chain_id, &mut sui_nonce),
+            (eth_chain_id, &mut eth_nonce),
+        ] {
+            let action = BlocklistCommitteeAction {
+                nonce: *nonce,
+                chain_id,
+                blocklist_type,
+                members_to_update: members.clone(),
+            };
+            if chain_id.is_sui_chain() {
+                committee = committee
+                    .with_blocklist_update(&action)
+                    .and_then(|c| c.ensure_can_approve_all_actions().map(|_| c))
+                    .map_err(|e| anyhow!("Rotation is unsafe: {:?}", e))?;
+            }
+            actions.push(BridgeAction::BlocklistCommitteeAction(action));
+            *nonce += 1;
+        }
+    }
+
+    ensure_reachable(&committee, &unblocklist).await?;
+    Ok(CommitteeRotationPlan { actions })
+}
+
+/// Ping the active members of `committee`, failing if any of `new_members` are unreachable, or if
+/// the reachable members could not approve a governance action together.
+async fn ensure_reachable(
+    committee: &BridgeCommittee,
+    new_members: &[BridgeAuthorityPublicKeyBytes],
+) -> anyhow::Result<()> {
+    let committee = Arc::new(committee.clone());
+    let pings = committee
+        .members()
+        .keys()
+        .filter(|pk| committee.is_active_member(pk))
+        .map(|pk| {
+            let committee = committee.clone();
+            async move {
+                let client = BridgeClient::new(pk.clone(), committee)?;
+                let reachable = matches!(
+                    tokio::time::timeout(MEMBER_TIMEOUT, client.ping()).await,
+                    Ok(Ok(true))
+                );
+                anyhow::Ok((pk, reachable))
+            }
+        });
+
+    let mut reachable_stake = 0;
+    let mut unreachable = vec![];
+    for result in join_all(pings).await {
+        let (pk, reachable) = result?;
+        let url = &committee.member(pk).unwrap().base_url;
+        println!("{pk:?} ({url}): reachable = {reachable}");
+        if reachable {
+            reachable_stake += committee.active_stake(pk);
+        } else if new_members.contains(pk) {
+            unreachable.push(pk.clone());
+        }
+    }
+
+    if !unreachable.is_empty() {
+        return Err(anyhow!(
+            "Members being added to the committee are unreachable: {unreachable:?}"
+        ));
+    }
+
+    let threshold = BridgeAction::BlocklistCommitteeAction(BlocklistCommitteeAction {
+        nonce: 0,
+        chain_id: BridgeChainId::SuiCustom,
+        blocklist_type: BlocklistType::Blocklist,
+        members_to_update: vec![],
+    })
+    .approval_threshold();
+    if reachable_stake < threshold {
+        return Err(anyhow!(
+            "Only {reachable_stake} stake of the rotated committee is reachable, below the \
+             {threshold} needed to approve governance actions"
+        ));
+    }
+
+    Ok(())
+}
+
+async fn status(
+    plan: &CommitteeRotationPlan,
+    sui_bridge_client: &SuiBridgeClient,
+) -> anyhow::Result<()> {
+    let agg = aggregator(sui_bridge_client).await?;
+    for action in &plan.actions {
+        let requests = agg.clients.iter().map(|(pk, client)| async move {
+            let signed = matches!(
+                tokio::time::timeout(
+                    MEMBER_TIMEOUT,
+                    client.request_sign_bridge_action(action.clone())
+                )
+                .await,
+                Ok(Ok(_))
+            );
+            (pk, signed)
+        });
+
+        let mut signed_stake = 0;
+        let mut pending = vec![];
+        for (pk, signed) in join_all(requests).await {
+            if signed {
+                signed_stake += agg.committee.active_stake(pk);
+            } else {
+                pending.push(pk.clone());
+            }
+        }
+
+        println!(
+            "{:?} nonce {}: approved by {signed_stake} of {} stake needed. Not yet approved by: \
+             {pending:?}",
+            action.chain_id(),
+            action.seq_number(),
+            action.approval_threshold(),
+        );
+    }
+
+    Ok(())
+}
+
+async fn execute(
+    plan: &CommitteeRotationPlan,
+    config: &LoadedBridgeCliConfig,
+    sui_bridge_client: &SuiBridgeClient,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    // Re-check the plan against the current committee, which may have changed since it was
+    // proposed.
+    let mut committee = sui_bridge_client
+        .get_bridge_committee()
+        .await
+        .map_err(|e| anyhow!("Error getting bridge committee: {:?}", e))?;
+    for action in &plan.actions {
+        let BridgeAction::BlocklistCommitteeAction(blocklist) = action else {
+            return Err(anyhow!("Not a committee blocklist action: {:?}", action));
+        };
+        if action.chain_id().is_sui_chain() {
+            committee = committee
+                .with_blocklist_update(blocklist)
+                .and_then(|c| c.ensure_can_approve_all_actions().map(|_| c))
+                .map_err(|e| anyhow!("Rotation is unsafe: {:?}", e))?;
+        }
+    }
+
+    for action in &plan.actions {
+        println!("Executing {:?}", action);
+        // Each action may change the committee that signs the next.
+        let agg = aggregator(sui_bridge_client).await?;
+        let certified_action = agg
+            .request_committee_signatures(action.clone())
+            .await
+            .map_err(|e| anyhow!("Failed to collect committee signatures: {:?}", e))?;
+        if dry_run {
+            println!("Collected committee signatures.");
+            continue;
+        }
+
+        if action.chain_id().is_sui_chain() {
+            let (_, sender, gas_object_ref) = config.get_sui_account_info().await?;
+            let bridge_object_arg = sui_bridge_client
+                .get_mutable_bridge_object_arg_must_succeed()
+                .await;
+            let rgp = sui_bridge_client
+                .get_reference_gas_price_until_success()
+                .await;
+            let token_id_map = sui_bridge_client
+                .get_token_id_map()
+                .await
+                .map_err(|e| anyhow!("{:?}", e))?;
+            let tx_data = build_sui_transaction(
+                sender,
+                &gas_object_ref,
+                certified_action,
+                bridge_object_arg,
+                &token_id_map,
+                rgp,
+            )
+            .map_err(|e| anyhow!("{:?}", e))?;
+            submit_on_sui(tx_data, config, sui_bridge_client, false).await?;
+        } else {
+            let tx = build_eth_transaction(
+                config.eth_bridge_committee_proxy_address,
+                config.eth_signer().clone(),
+                certified_action,
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to build eth transaction: {:?}", e))?;
+            let receipt = tx
+                .send()
+                .await
+                .map_err(|e| anyhow!("Eth transaction reverted: {:?}", e.as_revert()))?
+                .await?;
+            println!("Eth transaction succeeded: {:?}", receipt);
+        }
+    }
+
+    Ok(())
+}
+
+async fn aggregator(
+    sui_bridge_client: &SuiBridgeClient,
+) -> anyhow::Result<BridgeAuthorityAggregator> {
+    let committee = sui_bridge_client
+        .get_bridge_committee()
+        .await
+        .map_err(|e| anyhow!("Error getting bridge committee: {:?}", e))?;
+    Ok(BridgeAuthorityAggregator::new(
+        Arc::new(committee),
+        Arc::new(BridgeMetrics::new_for_testing()),
+        Arc::new(BTreeMap::new()),
+    ))
+}
@@ -5,7 +5,7 @@ use anyhow::anyhow;
 use clap::*;
 use ethers::providers::Middleware;
 use ethers::types::Address as EthAddress;
-use ethers::types::U256;
+use ethers::types::{TxHash, U256};
 use fastcrypto::encoding::Encoding;
 use fastcrypto::encoding::Hex;
 use fastcrypto::hash::{HashFunction, Keccak256};
@@ -14,34 +14,44 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use shared_crypto::intent::Intent;
 use shared_crypto::intent::IntentMessage;
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 use sui_bridge::abi::EthBridgeCommittee;
 use sui_bridge::abi::{eth_sui_bridge, EthSuiBridge};
+use sui_bridge::client::bridge_authority_aggregator::BridgeAuthorityAggregator;
 use sui_bridge::crypto::BridgeAuthorityPublicKeyBytes;
 use sui_bridge::error::BridgeResult;
+use sui_bridge::eth_client::EthClient;
+use sui_bridge::metrics::BridgeMetrics;
 use sui_bridge::sui_client::SuiBridgeClient;
-use sui_bridge::types::BridgeAction;
+use sui_bridge::sui_transaction_builder::build_sui_transaction;
 use sui_bridge::types::{
     AddTokensOnEvmAction, AddTokensOnSuiAction, AssetPriceUpdateAction, BlocklistCommitteeAction,
     BlocklistType, EmergencyAction, EmergencyActionType, EvmContractUpgradeAction,
     LimitUpdateAction,
 };
+use sui_bridge::types::{BridgeAction, BridgeActionStatus, VerifiedCertifiedBridgeAction};
 use sui_bridge::utils::{get_eth_signer_client, EthSigner};
 use sui_config::Config;
-use sui_json_rpc_types::SuiObjectDataOptions;
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionBlockEffectsAPI};
 use sui_keys::keypair_file::read_key;
 use sui_sdk::SuiClientBuilder;
 use sui_types::base_types::SuiAddress;
 use sui_types::base_types::{ObjectID, ObjectRef};
 use sui_types::bridge::{BridgeChainId, BRIDGE_MODULE_NAME};
 use sui_types::crypto::{Signature, SuiKeyPair};
+use sui_types::digests::TransactionDigest;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
-use sui_types::transaction::{ObjectArg, Transaction, TransactionData};
+use sui_types::transaction::{CallArg, ObjectArg, Transaction, TransactionData};
 use sui_types::{TypeTag, BRIDGE_PACKAGE_ID};
 use tracing::info;
 
+use crate::committee_rotation::CommitteeRotationCommands;
+
+pub mod committee_rotation;
+
 pub const SEPOLIA_BRIDGE_PROXY_ADDR: &str = "0xAE68F87938439afEEDd6552B0E83D2CbC2473623";
 
 #[derive(Parser)]
@@ -130,6 +140,15 @@ pub enum BridgeCommand {
         #[clap(subcommand)]
         cmd: BridgeClientCommands,
     },
+    /// Propose, track and execute changes to the bridge committee's membership
+    #[clap(name = "committee-rotation")]
+    CommitteeRotation {
+        /// Path of BridgeCliConfig
+        #[clap(long = "config-path")]
+        config_path: PathBuf,
+        #[clap(subcommand)]
+        cmd: CommitteeRotationCommands,
+    },
 }
 
 #[derive(Parser)]
@@ -543,6 +562,33 @@ pub enum BridgeClientCommands {
         #[clap(long, default_value_t = true, action = clap::ArgAction::Set)]
         dry_run: bool,
     },
+    /// Claim a token transfer that has been approved on Sui, on its destination chain.
+    #[clap(name = "claim")]
+    Claim {
+        /// The chain the transfer was sent from
+        #[clap(long)]
+        source_chain: u8,
+        #[clap(long)]
+        nonce: u64,
+        #[clap(long, default_value_t = true, action = clap::ArgAction::Set)]
+        dry_run: bool,
+    },
+    /// Re-drive a stuck token transfer: collect the committee's signatures for it, and approve
+    /// and claim it on its destination chain.
+    #[clap(name = "resume")]
+    Resume {
+        /// The chain the transfer was sent from
+        #[clap(long)]
+        source_chain: u8,
+        /// Digest (Sui) or hash (Eth) of the transaction that initiated the transfer
+        #[clap(long)]
+        tx: String,
+        /// Index of the transfer's event in the transaction
+        #[clap(long, default_value_t = 0)]
+        event_index: u16,
+        #[clap(long, default_value_t = true, action = clap::ArgAction::Set)]
+        dry_run: bool,
+    },
 }
 
 impl BridgeClientCommands {
@@ -578,10 +624,37 @@ impl BridgeClientCommands {
                 Ok(())
             }
             BridgeClientCommands::ClaimOnEth { seq_num, dry_run } => {
-                claim_on_eth(seq_num, config, sui_bridge_client, dry_run)
+                claim_on_eth(seq_num, config, &sui_bridge_client, dry_run)
                     .await
                     .map_err(|e| anyhow!("{:?}", e))
             }
+            BridgeClientCommands::Claim {
+                source_chain,
+                nonce,
+                dry_run,
+            } => {
+                let source_chain = BridgeChainId::try_from(source_chain)
+                    .map_err(|_| anyhow!("Invalid chain id: {source_chain}"))?;
+                claim(source_chain, nonce, config, &sui_bridge_client, dry_run).await
+            }
+            BridgeClientCommands::Resume {
+                source_chain,
+                tx,
+                event_index,
+                dry_run,
+            } => {
+                let source_chain = BridgeChainId::try_from(source_chain)
+                    .map_err(|_| anyhow!("Invalid chain id: {source_chain}"))?;
+                resume(
+                    source_chain,
+                    &tx,
+                    event_index,
+                    config,
+                    &sui_bridge_client,
+                    dry_run,
+                )
+                .await
+            }
             BridgeClientCommands::DepositOnSui {
                 coin_object_id,
                 coin_type,
@@ -681,7 +754,7 @@ async fn deposit_on_sui(
 async fn claim_on_eth(
     seq_num: u64,
     config: &LoadedBridgeCliConfig,
-    sui_bridge_client: SuiBridgeClient,
+    sui_bridge_client: &SuiBridgeClient,
     dry_run: bool,
 ) -> BridgeResult<()> {
     let sui_chain_id = sui_bridge_client.get_bridge_summary().await?.chain_id;
@@ -706,11 +779,318 @@ async fn claim_on_eth(
         .map(|sig: Vec<u8>| ethers::types::Bytes::from(sig))
         .collect::<Vec<_>>();
 
+    submit_claim_on_eth(
+        signatures,
+        eth_sui_bridge::Message::from(parsed_message),
+        config,
+        dry_run,
+    )
+    .await;
+    Ok(())
+}
+
+/// Claim the transfer with `nonce` from `source_chain`, which must already be approved on Sui, on
+/// its destination chain.
+async fn claim(
+    source_chain: BridgeChainId,
+    nonce: u64,
+    config: &LoadedBridgeCliConfig,
+    sui_bridge_client: &SuiBridgeClient,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let status = sui_bridge_client
+        .get_token_transfer_action_onchain_status_until_success(source_chain as u8, nonce)
+        .await;
+    match claim_route(source_chain, &status) {
+        ClaimRoute::NotApproved => {
+            println!(
+                "Transfer {nonce} from {source_chain:?} is not approved on Sui yet (status: \
+                 {status:?}). Use `resume` to collect committee signatures for it."
+            );
+            Ok(())
+        }
+        ClaimRoute::ClaimedOnSui => {
+            println!("Transfer {nonce} from {source_chain:?} is already claimed on Sui");
+            Ok(())
+        }
+        ClaimRoute::OnEth => {
+            let eth_sui_bridge = EthSuiBridge::new(
+                config.eth_bridge_proxy_address,
+                Arc::new(config.eth_signer().clone()),
+            );
+            if eth_sui_bridge.is_transfer_processed(nonce).call().await? {
+                println!("Transfer {nonce} from {source_chain:?} is already claimed on Eth");
+                return Ok(());
+            }
+
+            claim_on_eth(nonce, config, sui_bridge_client, dry_run)
+                .await
+                .map_err(|e| anyhow!("{:?}", e))
+        }
+        ClaimRoute::OnSui => {
+            claim_on_sui(source_chain, nonce, config, sui_bridge_client, dry_run).await
+        }
+    }
+}
+
+/// Claim the Eth to Sui transfer with `nonce` from `source_chain`, which is approved on Sui, on
+/// Sui.
+async fn claim_on_sui(
+    source_chain: BridgeChainId,
+    nonce: u64,
+    config: &LoadedBridgeCliConfig,
+    sui_bridge_client: &SuiBridgeClient,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let message = sui_bridge_client
+        .get_parsed_token_transfer_message(source_chain as u8, nonce)
+        .await
+        .map_err(|e| anyhow!("{:?}", e))?
+        .ok_or_else(|| anyhow!("No record found for transfer {nonce} from {source_chain:?}"))?;
+    let token_id = message.parsed_payload.token_type;
+    let token_type = sui_bridge_client
+        .get_token_id_map()
+        .await
+        .map_err(|e| anyhow!("{:?}", e))?
+        .remove(&token_id)
+        .ok_or_else(|| anyhow!("Unknown token id: {token_id}"))?;
+
+    let (_, sender, gas_object_ref) = config.get_sui_account_info().await?;
+    let bridge_object_arg = sui_bridge_client
+        .get_mutable_bridge_object_arg_must_succeed()
+        .await;
+    let rgp = sui_bridge_client
+        .get_reference_gas_price_until_success()
+        .await;
+
+    let tx_data = build_claim_on_sui_txn(
+        sender,
+        gas_object_ref,
+        bridge_object_arg,
+        token_type,
+        source_chain,
+        nonce,
+        rgp,
+    )?;
+    submit_on_sui(tx_data, config, sui_bridge_client, dry_run).await
+}
+
+/// What `claim` has to do for a transfer from `source_chain`, given its status on Sui.
+#[derive(Debug, PartialEq, Eq)]
+enum ClaimRoute {
+    /// The transfer has not been approved on Sui, so there is nothing to claim yet.
+    NotApproved,
+    /// The transfer was sent to Sui, and has already been claimed there.
+    ClaimedOnSui,
+    /// The transfer was sent to Eth, and has to be claimed there, unless it already has been.
+    OnEth,
+    /// The transfer was sent to Sui, and has to be claimed there.
+    OnSui,
+}
+
+fn claim_route(source_chain: BridgeChainId, status: &BridgeActionStatus) -> ClaimRoute {
+    match status {
+        BridgeActionStatus::Pending | BridgeActionStatus::NotFound => ClaimRoute::NotApproved,
+        _ if source_chain.is_sui_chain() => ClaimRoute::OnEth,
+        BridgeActionStatus::Claimed => ClaimRoute::ClaimedOnSui,
+        BridgeActionStatus::Approved => ClaimRoute::OnSui,
+    }
+}
+
+/// Build the transaction that claims the approved transfer with `nonce` from `source_chain` on
+/// Sui, and sends the tokens to its recipient.
+fn build_claim_on_sui_txn(
+    sender: SuiAddress,
+    gas_object_ref: ObjectRef,
+    bridge_object_arg: ObjectArg,
+    token_type: TypeTag,
+    source_chain: BridgeChainId,
+    nonce: u64,
+    rgp: u64,
+) -> anyhow::Result<TransactionData> {
+    let mut builder = ProgrammableTransactionBuilder::new();
+    let arg_bridge = builder.obj(bridge_object_arg)?;
+    let arg_clock = builder.input(CallArg::CLOCK_IMM)?;
+    let arg_source_chain = builder.pure(source_chain as u8)?;
+    let arg_nonce = builder.pure(nonce)?;
+    builder.programmable_move_call(
+        BRIDGE_PACKAGE_ID,
+        BRIDGE_MODULE_NAME.to_owned(),
+        ident_str!("claim_and_transfer_token").to_owned(),
+        vec![token_type],
+        vec![arg_bridge, arg_clock, arg_source_chain, arg_nonce],
+    );
+    Ok(TransactionData::new_programmable(
+        sender,
+        vec![gas_object_ref],
+        builder.finish(),
+        100_000_000,
+        rgp,
+    ))
+}
+
+/// The transaction that initiated a transfer, on the chain it was sent from.
+#[derive(Debug, PartialEq, Eq)]
+enum SourceTx {
+    Sui(TransactionDigest),
+    Eth(TxHash),
+}
+
+fn parse_source_tx(source_chain: BridgeChainId, tx: &str) -> anyhow::Result<SourceTx> {
+    if source_chain.is_sui_chain() {
+        TransactionDigest::from_str(tx)
+            .map(SourceTx::Sui)
+            .map_err(|e| anyhow!("Invalid Sui transaction digest {tx}: {e}"))
+    } else {
+        TxHash::from_str(tx)
+            .map(SourceTx::Eth)
+            .map_err(|e| anyhow!("Invalid Eth transaction hash {tx}: {e}"))
+    }
+}
+
+/// Check that `action`, found in `tx`, is a token transfer sent from `source_chain`.
+fn check_transfer_action(
+    action: &BridgeAction,
+    source_chain: BridgeChainId,
+    tx: &str,
+) -> anyhow::Result<()> {
+    if !matches!(
+        action,
+        BridgeAction::SuiToEthBridgeAction(_) | BridgeAction::EthToSuiBridgeAction(_)
+    ) {
+        return Err(anyhow!("Not a token transfer: {:?}", action));
+    }
+
+    if action.chain_id() != source_chain {
+        return Err(anyhow!(
+            "Transfer in {tx} was sent from {:?}, not {source_chain:?}",
+            action.chain_id()
+        ));
+    }
+
+    Ok(())
+}
+
+/// The committee's signatures on `certified_action`, in the form the Eth bridge contract expects.
+fn eth_signatures(certified_action: &VerifiedCertifiedBridgeAction) -> Vec<ethers::types::Bytes> {
+    certified_action
+        .auth_sig()
+        .signatures
+        .values()
+        .map(|sig| ethers::types::Bytes::from(sig.as_ref().to_vec()))
+        .collect()
+}
+
+/// Re-drive the transfer initiated by event `event_index` of transaction `tx` on `source_chain`,
+/// by collecting the committee's signatures for it and approving and claiming it on its
+/// destination chain. Transfers that are already approved are only claimed.
+async fn resume(
+    source_chain: BridgeChainId,
+    tx: &str,
+    event_index: u16,
+    config: &LoadedBridgeCliConfig,
+    sui_bridge_client: &SuiBridgeClient,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let metrics = Arc::new(BridgeMetrics::new_for_testing());
+    let action = match parse_source_tx(source_chain, tx)? {
+        SourceTx::Sui(tx_digest) => {
+            sui_bridge_client
+                .get_bridge_action_by_tx_digest_and_event_idx_maybe(&tx_digest, event_index)
+                .await
+        }
+        SourceTx::Eth(tx_hash) => {
+            let eth_client = EthClient::new(
+                &config.eth_rpc_url,
+                HashSet::from([config.eth_bridge_proxy_address]),
+                metrics.clone(),
+            )
+            .await?;
+            eth_client
+                .get_finalized_bridge_action_maybe(tx_hash, event_index)
+                .await
+        }
+    };
+    let action = action.map_err(|e| anyhow!("Failed to find transfer in {tx}: {:?}", e))?;
+    check_transfer_action(&action, source_chain, tx)?;
+
+    let nonce = action.seq_number();
+    let status = sui_bridge_client
+        .get_token_transfer_action_onchain_status_until_success(source_chain as u8, nonce)
+        .await;
+    println!("Transfer {nonce} from {source_chain:?} has status {status:?} on Sui");
+    if matches!(
+        status,
+        BridgeActionStatus::Approved | BridgeActionStatus::Claimed
+    ) {
+        return claim(source_chain, nonce, config, sui_bridge_client, dry_run).await;
+    }
+
+    let committee = Arc::new(
+        sui_bridge_client
+            .get_bridge_committee()
+            .await
+            .map_err(|e| anyhow!("{:?}", e))?,
+    );
+    let agg = BridgeAuthorityAggregator::new(committee, metrics, Arc::new(BTreeMap::new()));
+    let certified_action = agg
+        .request_committee_signatures(action)
+        .await
+        .map_err(|e| anyhow!("Failed to collect committee signatures: {:?}", e))?;
+    println!(
+        "Collected {} committee signatures",
+        certified_action.auth_sig().signatures.len()
+    );
+
+    match certified_action.data() {
+        BridgeAction::SuiToEthBridgeAction(action) => {
+            let signatures = eth_signatures(&certified_action);
+            let message = eth_sui_bridge::Message::from(action.clone());
+            submit_claim_on_eth(signatures, message, config, dry_run).await;
+            Ok(())
+        }
+
+        BridgeAction::EthToSuiBridgeAction(_) => {
+            let (_, sender, gas_object_ref) = config.get_sui_account_info().await?;
+            let bridge_object_arg = sui_bridge_client
+                .get_mutable_bridge_object_arg_must_succeed()
+                .await;
+            let rgp = sui_bridge_client
+                .get_reference_gas_price_until_success()
+                .await;
+            let token_id_map = sui_bridge_client
+                .get_token_id_map()
+                .await
+                .map_err(|e| anyhow!("{:?}", e))?;
+            // Approves the transfer and claims it in the same transaction.
+            let tx_data = build_sui_transaction(
+                sender,
+                &gas_object_ref,
+                certified_action,
+                bridge_object_arg,
+                &token_id_map,
+                rgp,
+            )
+            .map_err(|e| anyhow!("{:?}", e))?;
+            submit_on_sui(tx_data, config, sui_bridge_client, dry_run).await
+        }
+
+        action => Err(anyhow!("Not a token transfer: {:?}", action)),
+    }
+}
+
+/// Claim a Sui to Eth transfer on Eth with the committee's `signatures`, or only estimate the
+/// gas for claiming it if `dry_run` is set.
+async fn submit_claim_on_eth(
+    signatures: Vec<ethers::types::Bytes>,
+    message: eth_sui_bridge::Message,
+    config: &LoadedBridgeCliConfig,
+    dry_run: bool,
+) {
     let eth_sui_bridge = EthSuiBridge::new(
         config.eth_bridge_proxy_address,
         Arc::new(config.eth_signer().clone()),
     );
-    let message = eth_sui_bridge::Message::from(parsed_message);
     let tx = eth_sui_bridge.transfer_bridged_tokens_with_signatures(signatures, message);
     if dry_run {
         let tx = tx.tx;
@@ -726,12 +1106,61 @@ async fn claim_on_eth(
             eth_claim_tx_receipt
         );
     }
+}
+
+/// Sign `tx_data` with the CLI's Sui key and execute it, or only dry run it if `dry_run` is set.
+pub(crate) async fn submit_on_sui(
+    tx_data: TransactionData,
+    config: &LoadedBridgeCliConfig,
+    sui_bridge_client: &SuiBridgeClient,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    if dry_run {
+        let resp = sui_bridge_client
+            .sui_client()
+            .read_api()
+            .dry_run_transaction_block(tx_data)
+            .await?;
+        println!(
+            "Sui transaction dry run result: {:?}",
+            resp.effects.status()
+        );
+        return Ok(());
+    }
+
+    let sig = Signature::new_secure(
+        &IntentMessage::new(Intent::sui_transaction(), tx_data.clone()),
+        &config.sui_key,
+    );
+    let signed_tx = Transaction::from_data(tx_data, vec![sig]);
+    let tx_digest = *signed_tx.digest();
+    info!(?tx_digest, "Sending transaction to Sui.");
+    let resp = sui_bridge_client
+        .execute_transaction_block_with_effects(signed_tx)
+        .await
+        .map_err(|e| anyhow!("{:?}", e))?;
+    if !resp.status_ok().unwrap_or(false) {
+        return Err(anyhow!(
+            "Transaction {:?} failed: {:?}",
+            tx_digest,
+            resp.effects
+        ));
+    }
+    println!("Sui transaction succeeded: {:?}", tx_digest);
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use ethers::abi::FunctionExt;
+    use sui_bridge::test_utils::{
+        get_certified_action_with_validator_secrets, get_test_authority_and_key,
+        get_test_eth_to_sui_bridge_action, get_test_sui_to_eth_bridge_action,
+        DUMMY_MUTALBE_BRIDGE_OBJECT_ARG,
+    };
+    use sui_types::base_types::random_object_ref;
+    use sui_types::transaction::{Command, TransactionDataAPI, TransactionKind};
+    use sui_types::type_input::TypeInput;
 
     use super::*;
 
@@ -764,4 +1193,210 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn test_parse_claim_and_resume_commands() {
+        let args = Args::try_parse_from([
+            "bridge-cli",
+            "client",
+            "--config-path",
+            "config.yaml",
+            "claim",
+            "--source-chain",
+            "12",
+            "--nonce",
+            "5",
+        ])
+        .unwrap();
+        let BridgeCommand::Client { cmd, .. } = args.command else {
+            panic!("Expected a client command");
+        };
+        assert!(matches!(
+            cmd,
+            BridgeClientCommands::Claim {
+                source_chain: 12,
+                nonce: 5,
+                dry_run: true,
+            }
+        ));
+
+        let tx = TransactionDigest::random().to_string();
+        let args = Args::try_parse_from([
+            "bridge-cli",
+            "client",
+            "--config-path",
+            "config.yaml",
+            "resume",
+            "--source-chain",
+            "2",
+            "--tx",
+            &tx,
+            "--event-index",
+            "3",
+            "--dry-run",
+            "false",
+        ])
+        .unwrap();
+        let BridgeCommand::Client { cmd, .. } = args.command else {
+            panic!("Expected a client command");
+        };
+        let BridgeClientCommands::Resume {
+            source_chain,
+            tx: parsed_tx,
+            event_index,
+            dry_run,
+        } = cmd
+        else {
+            panic!("Expected a resume command");
+        };
+        assert_eq!(source_chain, 2);
+        assert_eq!(parsed_tx, tx);
+        assert_eq!(event_index, 3);
+        assert!(!dry_run);
+
+        // The transfer has to be identified.
+        assert!(Args::try_parse_from([
+            "bridge-cli",
+            "client",
+            "--config-path",
+            "config.yaml",
+            "resume",
+            "--source-chain",
+            "2",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_claim_route() {
+        use BridgeActionStatus as S;
+        use BridgeChainId as C;
+
+        // Nothing can be claimed before the transfer is approved on Sui, whichever way it goes.
+        for chain in [C::SuiCustom, C::EthCustom] {
+            assert_eq!(claim_route(chain, &S::Pending), ClaimRoute::NotApproved);
+            assert_eq!(claim_route(chain, &S::NotFound), ClaimRoute::NotApproved);
+        }
+
+        // Transfers from Sui are claimed on Eth, and their status on Sui says nothing about
+        // whether that has happened yet.
+        assert_eq!(claim_route(C::SuiCustom, &S::Approved), ClaimRoute::OnEth);
+        assert_eq!(claim_route(C::SuiTestnet, &S::Claimed), ClaimRoute::OnEth);
+
+        // Transfers from Eth are claimed on Sui.
+        assert_eq!(claim_route(C::EthCustom, &S::Approved), ClaimRoute::OnSui);
+        assert_eq!(
+            claim_route(C::EthSepolia, &S::Claimed),
+            ClaimRoute::ClaimedOnSui
+        );
+    }
+
+    #[test]
+    fn test_build_claim_on_sui_txn() {
+        let sender = SuiAddress::random_for_testing_only();
+        let gas_object_ref = random_object_ref();
+        let token_type = TypeTag::from_str("0x2::sui::SUI").unwrap();
+
+        let tx_data = build_claim_on_sui_txn(
+            sender,
+            gas_object_ref,
+            DUMMY_MUTALBE_BRIDGE_OBJECT_ARG,
+            token_type.clone(),
+            BridgeChainId::EthCustom,
+            42,
+            1000,
+        )
+        .unwrap();
+
+        assert_eq!(tx_data.sender(), sender);
+        assert_eq!(tx_data.gas(), &[gas_object_ref]);
+        assert_eq!(tx_data.gas_price(), 1000);
+
+        let TransactionKind::ProgrammableTransaction(pt) = tx_data.kind() else {
+            panic!("Expected a programmable transaction");
+        };
+        assert_eq!(
+            pt.inputs,
+            vec![
+                CallArg::Object(DUMMY_MUTALBE_BRIDGE_OBJECT_ARG),
+                CallArg::CLOCK_IMM,
+                CallArg::Pure(vec![BridgeChainId::EthCustom as u8]),
+                CallArg::Pure(42u64.to_le_bytes().to_vec()),
+            ]
+        );
+
+        let [Command::MoveCall(call)] = pt.commands.as_slice() else {
+            panic!("Expected a single move call, got {:?}", pt.commands);
+        };
+        assert_eq!(call.package, BRIDGE_PACKAGE_ID);
+        assert_eq!(call.module, BRIDGE_MODULE_NAME.to_string());
+        assert_eq!(call.function, "claim_and_transfer_token");
+        assert_eq!(call.type_arguments, vec![TypeInput::from(token_type)]);
+        assert_eq!(call.arguments.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_source_tx() {
+        let digest = TransactionDigest::random();
+        let hash = TxHash::random();
+
+        assert_eq!(
+            parse_source_tx(BridgeChainId::SuiCustom, &digest.to_string()).unwrap(),
+            SourceTx::Sui(digest)
+        );
+        assert_eq!(
+            parse_source_tx(BridgeChainId::EthCustom, &format!("{hash:?}")).unwrap(),
+            SourceTx::Eth(hash)
+        );
+
+        // Transactions are looked up on the chain the transfer was sent from.
+        assert!(parse_source_tx(BridgeChainId::SuiCustom, &format!("{hash:?}")).is_err());
+        assert!(parse_source_tx(BridgeChainId::EthCustom, &digest.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_check_transfer_action() {
+        let sui_to_eth =
+            get_test_sui_to_eth_bridge_action(None, None, None, None, None, None, None);
+        let eth_to_sui = get_test_eth_to_sui_bridge_action(None, None, None, None);
+
+        check_transfer_action(&sui_to_eth, BridgeChainId::SuiCustom, "tx").unwrap();
+        check_transfer_action(&eth_to_sui, BridgeChainId::EthCustom, "tx").unwrap();
+
+        // The transfer found was sent from a different chain than the one given.
+        let err = check_transfer_action(&sui_to_eth, BridgeChainId::SuiTestnet, "tx").unwrap_err();
+        assert!(err.to_string().contains("was sent from SuiCustom"), "{err}");
+        check_transfer_action(&eth_to_sui, BridgeChainId::SuiCustom, "tx").unwrap_err();
+
+        // Governance actions can't be resumed.
+        let emergency = BridgeAction::EmergencyAction(EmergencyAction {
+            nonce: 0,
+            chain_id: BridgeChainId::SuiCustom,
+            action_type: EmergencyActionType::Pause,
+        });
+        let err = check_transfer_action(&emergency, BridgeChainId::SuiCustom, "tx").unwrap_err();
+        assert!(err.to_string().contains("Not a token transfer"), "{err}");
+    }
+
+    #[test]
+    fn test_eth_signatures() {
+        let (_, _, kp1) = get_test_authority_and_key(5000, 9999);
+        let (_, _, kp2) = get_test_authority_and_key(5000, 9999);
+        let action = get_test_sui_to_eth_bridge_action(None, None, None, None, None, None, None);
+        let certified_action = get_certified_action_with_validator_secrets(action, &vec![kp1, kp2]);
+
+        let signatures = eth_signatures(&certified_action);
+        let expected: Vec<_> = certified_action
+            .auth_sig()
+            .signatures
+            .values()
+            .map(|sig| sig.as_ref().to_vec())
+            .collect();
+        assert_eq!(signatures.len(), 2);
+        for (sig, expected) in signatures.iter().zip(expected) {
+            // Recoverable secp256k1 signatures, as the Eth contract expects.
+            assert_eq!(sig.len(), 65);
+            assert_eq!(sig.to_vec(), expected);
+        }
+    }
 }
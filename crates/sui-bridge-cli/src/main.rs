@@ -521,6 +521,15 @@ async fn main() -> anyhow::Result<()> {
             cmd.handle(&config, sui_bridge_client).await?;
             return Ok(());
         }
+        BridgeCommand::CommitteeRotation { config_path, cmd } => {
+            let config = BridgeCliConfig::load(config_path).expect("Couldn't load BridgeCliConfig");
+            let config = LoadedBridgeCliConfig::load(config).await?;
+            let metrics = Arc::new(BridgeMetrics::new_for_testing());
+            let sui_bridge_client =
+                SuiClient::<SuiSdkClient>::new(&config.sui_rpc_url, metrics).await?;
+            cmd.handle(&config, sui_bridge_client).await?;
+            return Ok(());
+        }
     }
 
     Ok(())
@@ -31,7 +31,7 @@ use crate::metrics::BridgeMetrics;
 use crate::{
     client::bridge_authority_aggregator::BridgeAuthorityAggregator,
     error::BridgeError,
-    storage::BridgeOrchestratorTables,
+    storage::{BridgeOrchestratorTables, TransferStatus},
     sui_client::{SuiClient, SuiClientInner},
     sui_transaction_builder::build_sui_transaction,
     types::{BridgeAction, BridgeActionStatus, VerifiedCertifiedBridgeAction},
@@ -307,6 +307,16 @@ where
                     action
                 );
                 metrics.action_executor_already_processed_actions.inc();
+                let transfer_status = if status == BridgeActionStatus::Claimed {
+                    TransferStatus::Claimed
+                } else {
+                    TransferStatus::Approved
+                };
+                store
+                    .complete_transfer(action, transfer_status)
+                    .unwrap_or_else(|e| {
+                        panic!("Write to DB should not fail: {:?}", e);
+                    });
                 store
                     .remove_pending_actions(&[action.digest()])
                     .unwrap_or_else(|e| {
@@ -358,12 +368,20 @@ where
         {
             return;
         }
-        match auth_agg
-            .load()
-            .request_committee_signatures(action.clone())
-            .await
-        {
+        let auth_agg = auth_agg.load_full();
+        match auth_agg.request_committee_signatures(action.clone()).await {
             Ok(certificate) => {
+                let signers = &certificate.auth_sig().signatures;
+                let signed_stake = signers
+                    .keys()
+                    .map(|signer| auth_agg.committee.active_stake(signer))
+                    .sum();
+                store
+                    .update_transfer_signatures(&action, signers.len() as u64, signed_stake)
+                    .unwrap_or_else(|e| {
+                        panic!("Write to DB should not fail: {:?}", e);
+                    });
+
                 info!("Sending certificate to execution");
                 execution_queue_sender
                     .send(CertifiedBridgeActionExecutionWrapper(certificate, 0))
@@ -623,6 +641,20 @@ where
                         }
                     }
                 });
+                let claimed = relevant_events.iter().any(|e| {
+                    e.type_ == *TokenTransferClaimed.get().unwrap()
+                        || e.type_ == *TokenTransferAlreadyClaimed.get().unwrap()
+                });
+                let transfer_status = if claimed {
+                    TransferStatus::Claimed
+                } else {
+                    TransferStatus::Approved
+                };
+                store
+                    .complete_transfer(action, transfer_status)
+                    .unwrap_or_else(|e| {
+                        panic!("Write to DB should not fail: {:?}", e);
+                    });
                 store
                     .remove_pending_actions(&[action.digest()])
                     .unwrap_or_else(|e| {
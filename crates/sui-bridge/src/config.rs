@@ -5,6 +5,7 @@ use crate::abi::EthBridgeConfig;
 use crate::crypto::BridgeAuthorityKeyPair;
 use crate::error::BridgeError;
 use crate::eth_client::EthClient;
+use crate::evm_chain::{evm_chain_adapter, EvmChainConfig};
 use crate::metered_eth_provider::new_metered_eth_provider;
 use crate::metered_eth_provider::MeteredEthHttpProvier;
 use crate::metrics::BridgeMetrics;
@@ -62,6 +63,11 @@ pub struct EthConfig {
     /// reprocess the events from this block number every time it starts.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub eth_contracts_start_block_override: Option<u64>,
+    /// Describes the EVM chain the bridge contracts are deployed on, when it is not one of the
+    /// Ethereum networks the bridge knows about, or when it needs a different finality rule.
+    /// Ethereum mainnet and Sepolia are assumed when this is unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eth_chain: Option<EvmChainConfig>,
 }
 
 #[serde_as]
@@ -202,6 +208,18 @@ impl BridgeNodeConfig {
                     BridgeError::ActionIsNotGovernanceAction(action.clone())
                 ));
             }
+            // Refuse to sign committee rotations that would leave the committee unable to
+            // approve the actions that undo them.
+            if let BridgeAction::BlocklistCommitteeAction(blocklist) = action {
+                if let Err(e) = bridge_committee
+                    .with_blocklist_update(blocklist)
+                    .and_then(|committee| committee.ensure_can_approve_all_actions())
+                {
+                    anyhow::bail!(
+                        "Approved committee blocklist action {action:?} is unsafe: {e:?}"
+                    );
+                }
+            }
         }
         let approved_governance_actions = self.approved_governance_actions.clone();
 
@@ -278,8 +296,6 @@ impl BridgeNodeConfig {
             ));
         }
 
-        // If bridge chain id is Eth Mainent or Sepolia, we expect to see chain
-        // identifier to match accordingly.
         let bridge_chain_id: u8 = config.chain_id().call().await?;
         if self.eth.eth_bridge_chain_id != bridge_chain_id {
             return Err(anyhow!(
@@ -288,22 +304,28 @@ impl BridgeNodeConfig {
                 bridge_chain_id
             ));
         }
-        if bridge_chain_id == BridgeChainId::EthMainnet as u8 && chain_id.as_u64() != 1 {
-            anyhow::bail!(
-                "Expected Eth chain id 1, but connected to {}",
-                chain_id.as_u64()
-            );
-        }
-        if bridge_chain_id == BridgeChainId::EthSepolia as u8 && chain_id.as_u64() != 11155111 {
-            anyhow::bail!(
-                "Expected Eth chain id 11155111, but connected to {}",
-                chain_id.as_u64()
-            );
+
+        // If the chain's EVM chain id is known (it is configured, or the chain is Eth Mainnet or
+        // Sepolia), we expect the RPC to be connected to that chain.
+        let evm_chain = evm_chain_adapter(
+            BridgeChainId::try_from(bridge_chain_id)?,
+            self.eth.eth_chain.as_ref(),
+        )?;
+        if let Some(expected) = evm_chain.evm_chain_id() {
+            if chain_id.as_u64() != expected {
+                anyhow::bail!(
+                    "Expected {} chain id {expected}, but connected to {}",
+                    evm_chain.name(),
+                    chain_id.as_u64()
+                );
+            }
         }
         info!(
-            "Connected to Eth chain: {}, Bridge chain id: {}",
+            "Connected to {} chain: {}, Bridge chain id: {}, finality: {:?}",
+            evm_chain.name(),
             chain_id.as_u64(),
             bridge_chain_id,
+            evm_chain.finality(),
         );
 
         let eth_client = Arc::new(
@@ -318,7 +340,8 @@ impl BridgeNodeConfig {
                 ]),
                 metrics,
             )
-            .await?,
+            .await?
+            .with_finality(evm_chain.finality()),
         );
         let contract_addresses = vec![
             bridge_proxy_address,
@@ -6,6 +6,7 @@ use std::sync::Arc;
 
 use crate::abi::EthBridgeEvent;
 use crate::error::{BridgeError, BridgeResult};
+use crate::evm_chain::FinalityRule;
 use crate::metered_eth_provider::{new_metered_eth_provider, MeteredEthHttpProvier};
 use crate::metrics::BridgeMetrics;
 use crate::types::{BridgeAction, EthLog, RawEthLog};
@@ -20,6 +21,7 @@ use ethers::types::Address as EthAddress;
 pub struct EthClient<P> {
     provider: Provider<P>,
     contract_addresses: HashSet<EthAddress>,
+    finality: FinalityRule,
 }
 
 impl EthClient<MeteredEthHttpProvier> {
@@ -32,6 +34,7 @@ impl EthClient<MeteredEthHttpProvier> {
         let self_ = Self {
             provider,
             contract_addresses,
+            finality: FinalityRule::default(),
         };
         self_.describe().await?;
         Ok(self_)
@@ -49,6 +52,7 @@ impl EthClient<EthMockProvider> {
         Self {
             provider,
             contract_addresses,
+            finality: FinalityRule::default(),
         }
     }
 }
@@ -57,6 +61,12 @@ impl<P> EthClient<P>
 where
     P: JsonRpcClient,
 {
+    /// Use `finality` to decide which blocks are final, instead of the `finalized` block tag.
+    pub fn with_finality(mut self, finality: FinalityRule) -> Self {
+        self.finality = finality;
+        self
+    }
+
     pub async fn get_chain_id(&self) -> Result<u64, anyhow::Error> {
         let chain_id = self.provider.get_chainid().await?;
         Ok(chain_id.as_u64())
@@ -118,9 +128,22 @@ where
     }
 
     pub async fn get_last_finalized_block_id(&self) -> BridgeResult<u64> {
+        let tag = match self.finality {
+            FinalityRule::FinalizedTag => "finalized",
+            FinalityRule::SafeTag => "safe",
+            FinalityRule::Confirmations(confirmations) => {
+                let latest = self
+                    .provider
+                    .get_block_number()
+                    .await
+                    .map_err(BridgeError::from)?;
+                return Ok(latest.as_u64().saturating_sub(confirmations));
+            }
+        };
+
         let block: Result<Option<Block<ethers::types::TxHash>>, ethers::prelude::ProviderError> =
             self.provider
-                .request("eth_getBlockByNumber", ("finalized", false))
+                .request("eth_getBlockByNumber", (tag, false))
                 .await;
         let block = block?.ok_or(BridgeError::TransientProviderError(
             "Provider fails to return last finalized block".into(),
@@ -430,4 +453,39 @@ mod tests {
             .unwrap();
         assert_eq!(action, bridge_action);
     }
+
+    #[tokio::test]
+    async fn test_get_last_finalized_block_id_with_finality_rule() {
+        let mock_provider = EthMockProvider::new();
+        mock_last_finalized_block(&mock_provider, 777);
+        mock_provider
+            .add_response(
+                "eth_getBlockByNumber",
+                ("safe", false),
+                Block::<TxHash> {
+                    number: Some(U64::from(790)),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        mock_provider
+            .add_response("eth_blockNumber", (), U64::from(800))
+            .unwrap();
+
+        let client = EthClient::new_mocked(mock_provider.clone(), HashSet::new());
+        assert_eq!(client.get_last_finalized_block_id().await.unwrap(), 777);
+
+        let client = EthClient::new_mocked(mock_provider.clone(), HashSet::new())
+            .with_finality(FinalityRule::SafeTag);
+        assert_eq!(client.get_last_finalized_block_id().await.unwrap(), 790);
+
+        let client = EthClient::new_mocked(mock_provider.clone(), HashSet::new())
+            .with_finality(FinalityRule::Confirmations(64));
+        assert_eq!(client.get_last_finalized_block_id().await.unwrap(), 736);
+
+        // Chains younger than the confirmation depth have no final blocks past genesis.
+        let client = EthClient::new_mocked(mock_provider, HashSet::new())
+            .with_finality(FinalityRule::Confirmations(1000));
+        assert_eq!(client.get_last_finalized_block_id().await.unwrap(), 0);
+    }
 }
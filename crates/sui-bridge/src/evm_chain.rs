@@ -0,0 +1,210 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Chain-specific behaviour of the EVM chain that the bridge connects Sui to. Ethereum mainnet and
+//! Sepolia are supported out of the box, and other EVM chains can be described in the bridge
+//! node's config, without changes to the rest of the bridge.
+
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use sui_types::bridge::BridgeChainId;
+
+/// How blocks on an EVM chain are considered final, before the bridge acts on events in them.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FinalityRule {
+    /// Blocks up to the one returned for the `finalized` block tag are final.
+    #[default]
+    FinalizedTag,
+    /// Blocks up to the one returned for the `safe` block tag are final.
+    SafeTag,
+    /// Blocks with at least this many blocks on top of them are final, for chains that do not
+    /// support the `finalized` or `safe` block tags.
+    Confirmations(u64),
+}
+
+/// Parameters of an EVM chain other than the Ethereum networks the bridge knows about.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct EvmChainConfig {
+    /// Human-readable name of the chain, used in logs.
+    pub name: String,
+    /// The EIP-155 chain ID that the chain's RPC is expected to report.
+    pub evm_chain_id: u64,
+    /// How blocks on the chain are considered final.
+    #[serde(default)]
+    pub finality: FinalityRule,
+}
+
+/// Describes an EVM chain that the bridge connects to.
+pub trait EvmChainAdapter: Send + Sync + fmt::Debug {
+    /// Human-readable name of the chain.
+    fn name(&self) -> &str;
+
+    /// The chain's ID on the bridge.
+    fn bridge_chain_id(&self) -> BridgeChainId;
+
+    /// The EIP-155 chain ID that the chain's RPC must report, if it is known.
+    fn evm_chain_id(&self) -> Option<u64>;
+
+    /// How blocks on the chain are considered final.
+    fn finality(&self) -> FinalityRule;
+}
+
+/// Ethereum networks, whose parameters are built into the bridge.
+#[derive(Debug)]
+pub struct Ethereum {
+    bridge_chain_id: BridgeChainId,
+}
+
+/// An EVM chain whose parameters are read from the bridge node's config.
+#[derive(Debug)]
+pub struct ConfiguredEvmChain {
+    bridge_chain_id: BridgeChainId,
+    config: EvmChainConfig,
+}
+
+impl Ethereum {
+    pub fn new(bridge_chain_id: BridgeChainId) -> anyhow::Result<Self> {
+        if bridge_chain_id.is_sui_chain() {
+            return Err(anyhow!(
+                "Bridge chain id {bridge_chain_id:?} is not an Ethereum chain"
+            ));
+        }
+
+        Ok(Self { bridge_chain_id })
+    }
+}
+
+impl ConfiguredEvmChain {
+    pub fn new(bridge_chain_id: BridgeChainId, config: EvmChainConfig) -> anyhow::Result<Self> {
+        if bridge_chain_id.is_sui_chain() {
+            return Err(anyhow!(
+                "Bridge chain id {bridge_chain_id:?} is not an EVM chain"
+            ));
+        }
+
+        // Well-known networks must not be misconfigured to point at a different chain.
+        if let Some(expected) = Ethereum::new(bridge_chain_id)?.evm_chain_id() {
+            if expected != config.evm_chain_id {
+                return Err(anyhow!(
+                    "Bridge chain id {bridge_chain_id:?} is EVM chain {expected}, but {} is \
+                     configured with EVM chain id {}",
+                    config.name,
+                    config.evm_chain_id,
+                ));
+            }
+        }
+
+        Ok(Self {
+            bridge_chain_id,
+            config,
+        })
+    }
+}
+
+impl EvmChainAdapter for Ethereum {
+    fn name(&self) -> &str {
+        match self.bridge_chain_id {
+            BridgeChainId::EthMainnet => "Ethereum",
+            BridgeChainId::EthSepolia => "Sepolia",
+            _ => "Ethereum (custom)",
+        }
+    }
+
+    fn bridge_chain_id(&self) -> BridgeChainId {
+        self.bridge_chain_id
+    }
+
+    fn evm_chain_id(&self) -> Option<u64> {
+        match self.bridge_chain_id {
+            BridgeChainId::EthMainnet => Some(1),
+            BridgeChainId::EthSepolia => Some(11155111),
+            _ => None,
+        }
+    }
+
+    fn finality(&self) -> FinalityRule {
+        FinalityRule::FinalizedTag
+    }
+}
+
+impl EvmChainAdapter for ConfiguredEvmChain {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn bridge_chain_id(&self) -> BridgeChainId {
+        self.bridge_chain_id
+    }
+
+    fn evm_chain_id(&self) -> Option<u64> {
+        Some(self.config.evm_chain_id)
+    }
+
+    fn finality(&self) -> FinalityRule {
+        self.config.finality
+    }
+}
+
+/// The adapter for the EVM chain with ID `bridge_chain_id` on the bridge, described by `config`
+/// if it is set, and otherwise assumed to be an Ethereum network.
+pub fn evm_chain_adapter(
+    bridge_chain_id: BridgeChainId,
+    config: Option<&EvmChainConfig>,
+) -> anyhow::Result<Arc<dyn EvmChainAdapter>> {
+    Ok(match config {
+        Some(config) => Arc::new(ConfiguredEvmChain::new(bridge_chain_id, config.clone())?),
+        None => Arc::new(Ethereum::new(bridge_chain_id)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ethereum_adapter() {
+        let mainnet = evm_chain_adapter(BridgeChainId::EthMainnet, None).unwrap();
+        assert_eq!(mainnet.evm_chain_id(), Some(1));
+        assert_eq!(mainnet.finality(), FinalityRule::FinalizedTag);
+
+        let sepolia = evm_chain_adapter(BridgeChainId::EthSepolia, None).unwrap();
+        assert_eq!(sepolia.evm_chain_id(), Some(11155111));
+
+        // Chain ID is not checked for custom chains, without config.
+        let custom = evm_chain_adapter(BridgeChainId::EthCustom, None).unwrap();
+        assert_eq!(custom.evm_chain_id(), None);
+
+        assert!(evm_chain_adapter(BridgeChainId::SuiMainnet, None).is_err());
+    }
+
+    #[test]
+    fn test_configured_adapter() {
+        let config: EvmChainConfig = serde_json::from_str(
+            r#"{
+                "name": "Base Sepolia",
+                "evm-chain-id": 84532,
+                "finality": { "confirmations": 20 }
+            }"#,
+        )
+        .unwrap();
+
+        let chain = evm_chain_adapter(BridgeChainId::EthCustom, Some(&config)).unwrap();
+        assert_eq!(chain.name(), "Base Sepolia");
+        assert_eq!(chain.bridge_chain_id(), BridgeChainId::EthCustom);
+        assert_eq!(chain.evm_chain_id(), Some(84532));
+        assert_eq!(chain.finality(), FinalityRule::Confirmations(20));
+
+        // Well-known networks can't be pointed at another chain.
+        assert!(evm_chain_adapter(BridgeChainId::EthMainnet, Some(&config)).is_err());
+
+        // Finality defaults to the `finalized` block tag.
+        let config: EvmChainConfig =
+            serde_json::from_str(r#"{ "name": "Holesky", "evm-chain-id": 17000 }"#).unwrap();
+        assert_eq!(config.finality, FinalityRule::FinalizedTag);
+    }
+}
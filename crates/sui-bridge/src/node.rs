@@ -110,17 +110,21 @@ pub async fn run_bridge_node(
         .await?;
 
     // Start Client
+    let mut transfer_records = None;
     if let Some(client_config) = client_config {
         let committee_keys_to_names =
             Arc::new(get_validator_names_by_pub_keys(&committee, &sui_system).await);
+        let store = BridgeOrchestratorTables::new(&client_config.db_path.join("client"));
         let client_components = start_client_components(
             client_config,
+            store.clone(),
             committee.clone(),
             committee_keys_to_names,
             metrics.clone(),
         )
         .await?;
         handles.extend(client_components);
+        transfer_records = Some(store);
     }
 
     let committee_name_mapping = get_committee_voting_power_by_name(&committee, &sui_system).await;
@@ -147,6 +151,7 @@ pub async fn run_bridge_node(
         ),
         metrics,
         Arc::new(metadata),
+        transfer_records,
     ))
 }
 
@@ -235,12 +240,11 @@ async fn start_watchdog(
 // TODO: is there a way to clean up the overrides after it's stored in DB?
 async fn start_client_components(
     client_config: BridgeClientConfig,
+    store: Arc<BridgeOrchestratorTables>,
     committee: Arc<BridgeCommittee>,
     committee_keys_to_names: Arc<BTreeMap<BridgeAuthorityPublicKeyBytes, String>>,
     metrics: Arc<BridgeMetrics>,
 ) -> anyhow::Result<Vec<JoinHandle<()>>> {
-    let store: std::sync::Arc<BridgeOrchestratorTables> =
-        BridgeOrchestratorTables::new(&client_config.db_path.join("client"));
     let sui_modules_to_watch = get_sui_modules_to_watch(
         &store,
         client_config.sui_bridge_module_last_processed_event_id_override,
@@ -604,6 +608,7 @@ mod tests {
                 eth_bridge_chain_id: BridgeChainId::EthCustom as u8,
                 eth_contracts_start_block_fallback: None,
                 eth_contracts_start_block_override: None,
+                eth_chain: None,
             },
             approved_governance_actions: vec![],
             run_client: false,
@@ -671,6 +676,7 @@ mod tests {
                 eth_bridge_chain_id: BridgeChainId::EthCustom as u8,
                 eth_contracts_start_block_fallback: Some(0),
                 eth_contracts_start_block_override: None,
+                eth_chain: None,
             },
             approved_governance_actions: vec![],
             run_client: true,
@@ -749,6 +755,7 @@ mod tests {
                 eth_bridge_chain_id: BridgeChainId::EthCustom as u8,
                 eth_contracts_start_block_fallback: Some(0),
                 eth_contracts_start_block_override: Some(0),
+                eth_chain: None,
             },
             approved_governance_actions: vec![],
             run_client: true,
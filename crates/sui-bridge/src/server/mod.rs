@@ -8,6 +8,8 @@ use crate::{
     error::BridgeError,
     metrics::BridgeMetrics,
     server::handler::{BridgeRequestHandler, BridgeRequestHandlerTrait},
+    server::transfer_status::make_transfer_status_router,
+    storage::BridgeOrchestratorTables,
     types::{
         AddTokensOnEvmAction, AddTokensOnSuiAction, AssetPriceUpdateAction,
         BlocklistCommitteeAction, BlocklistType, BridgeAction, EmergencyAction,
@@ -32,6 +34,7 @@ use tracing::{info, instrument};
 
 pub mod governance_verifier;
 pub mod handler;
+pub mod transfer_status;
 
 #[cfg(any(feature = "test-utils", test))]
 pub(crate) mod mock_handler;
@@ -84,21 +87,25 @@ impl BridgeNodePublicMetadata {
     }
 }
 
+/// Serve signing requests with `handler`. If `transfer_records` is set (the node runs the bridge
+/// client), the status of the transfers it has processed is served as well.
 pub fn run_server(
     socket_address: &SocketAddr,
     handler: BridgeRequestHandler,
     metrics: Arc<BridgeMetrics>,
     metadata: Arc<BridgeNodePublicMetadata>,
+    transfer_records: Option<Arc<BridgeOrchestratorTables>>,
 ) -> tokio::task::JoinHandle<()> {
     let socket_address = *socket_address;
     tokio::spawn(async move {
         let listener = tokio::net::TcpListener::bind(socket_address).await.unwrap();
-        axum::serve(
-            listener,
-            make_router(Arc::new(handler), metrics, metadata).into_make_service(),
-        )
-        .await
-        .unwrap();
+        let mut router = make_router(Arc::new(handler), metrics, metadata);
+        if let Some(store) = transfer_records {
+            router = router.merge(make_transfer_status_router(store));
+        }
+        axum::serve(listener, router.into_make_service())
+            .await
+            .unwrap();
     })
 }
 
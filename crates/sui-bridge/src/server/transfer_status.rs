@@ -0,0 +1,209 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Read-only routes exposing the token transfers this node's client has processed, for bridge
+//! frontends to track transfers with. Only served when the node runs the bridge client.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sui_types::bridge::BridgeChainId;
+
+use crate::{
+    error::BridgeError,
+    storage::{BridgeOrchestratorTables, TransferRecord, TransferStatus, TransferVolume},
+};
+
+pub const TRANSFERS_PATH: &str = "/transfers/:source_chain";
+pub const TRANSFER_PATH: &str = "/transfers/:source_chain/:nonce";
+pub const TRANSFER_VOLUMES_PATH: &str = "/transfer_volumes";
+
+/// Maximum number of transfers returned by a single query.
+const MAX_TRANSFERS_LIMIT: usize = 100;
+
+#[derive(Deserialize, Debug, Default)]
+pub struct TransfersQuery {
+    /// Only include transfers with this status.
+    pub status: Option<TransferStatus>,
+    /// Only include transfers with nonces at or after this one.
+    pub from_nonce: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct TransferPage {
+    pub transfers: Vec<TransferRecord>,
+    /// The nonce to query from, to fetch the next page, if there may be more transfers.
+    pub next_nonce: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DirectionVolume {
+    pub source_chain: BridgeChainId,
+    pub destination_chain: BridgeChainId,
+    pub token_id: u8,
+    #[serde(flatten)]
+    pub volume: TransferVolume,
+}
+
+pub(crate) fn make_transfer_status_router(store: Arc<BridgeOrchestratorTables>) -> Router {
+    Router::new()
+        .route(TRANSFERS_PATH, get(handle_get_transfers))
+        .route(TRANSFER_PATH, get(handle_get_transfer))
+        .route(TRANSFER_VOLUMES_PATH, get(handle_get_transfer_volumes))
+        .with_state(store)
+}
+
+async fn handle_get_transfers(
+    Path(source_chain): Path<u8>,
+    Query(query): Query<TransfersQuery>,
+    State(store): State<Arc<BridgeOrchestratorTables>>,
+) -> Result<Json<TransferPage>, BridgeError> {
+    let source_chain = parse_chain_id(source_chain)?;
+    let limit = query
+        .limit
+        .unwrap_or(MAX_TRANSFERS_LIMIT)
+        .clamp(1, MAX_TRANSFERS_LIMIT);
+
+    let transfers = store.get_transfer_records(
+        source_chain,
+        query.from_nonce.unwrap_or(0),
+        query.status,
+        limit,
+    )?;
+
+    let next_nonce = (transfers.len() == limit)
+        .then(|| transfers.last().and_then(|t| t.nonce.checked_add(1)))
+        .flatten();
+
+    Ok(Json(TransferPage {
+        transfers,
+        next_nonce,
+    }))
+}
+
+async fn handle_get_transfer(
+    Path((source_chain, nonce)): Path<(u8, u64)>,
+    State(store): State<Arc<BridgeOrchestratorTables>>,
+) -> Result<Response, BridgeError> {
+    let source_chain = parse_chain_id(source_chain)?;
+    Ok(match store.get_transfer_record(source_chain, nonce)? {
+        Some(record) => Json(record).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    })
+}
+
+async fn handle_get_transfer_volumes(
+    State(store): State<Arc<BridgeOrchestratorTables>>,
+) -> Result<Json<Vec<DirectionVolume>>, BridgeError> {
+    store
+        .get_transfer_volumes()?
+        .into_iter()
+        .map(|((source_chain, destination_chain, token_id), volume)| {
+            Ok(DirectionVolume {
+                source_chain: parse_chain_id(source_chain)?,
+                destination_chain: parse_chain_id(destination_chain)?,
+                token_id,
+                volume,
+            })
+        })
+        .collect::<Result<_, _>>()
+        .map(Json)
+}
+
+fn parse_chain_id(chain_id: u8) -> Result<BridgeChainId, BridgeError> {
+    BridgeChainId::try_from(chain_id).map_err(|err| {
+        BridgeError::InvalidBridgeClientRequest(format!("Invalid chain id: {:?}", err))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::get_test_sui_to_eth_bridge_action;
+
+    #[tokio::test]
+    async fn test_transfer_status_routes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = BridgeOrchestratorTables::new(temp_dir.path());
+
+        let actions: Vec<_> = (0..3)
+            .map(|nonce| {
+                get_test_sui_to_eth_bridge_action(
+                    None,
+                    None,
+                    Some(nonce),
+                    Some(100 * (nonce + 1)),
+                    None,
+                    None,
+                    None,
+                )
+            })
+            .collect();
+        store.insert_pending_actions(&actions).unwrap();
+        store
+            .update_transfer_signatures(&actions[0], 3, 6000)
+            .unwrap();
+        store
+            .complete_transfer(&actions[1], TransferStatus::Approved)
+            .unwrap();
+        let source_chain = actions[0].chain_id() as u8;
+
+        let Json(page) = handle_get_transfers(
+            Path(source_chain),
+            Query(TransfersQuery {
+                limit: Some(2),
+                ..Default::default()
+            }),
+            State(store.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.transfers.len(), 2);
+        assert_eq!(page.transfers[0].status, TransferStatus::Signed);
+        assert_eq!(page.transfers[0].signed_stake, 6000);
+        assert_eq!(page.transfers[1].status, TransferStatus::Approved);
+        assert_eq!(page.next_nonce, Some(2));
+
+        let Json(page) = handle_get_transfers(
+            Path(source_chain),
+            Query(TransfersQuery {
+                status: Some(TransferStatus::Pending),
+                ..Default::default()
+            }),
+            State(store.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.transfers.len(), 1);
+        assert_eq!(page.transfers[0].nonce, 2);
+        assert_eq!(page.next_nonce, None);
+
+        let response = handle_get_transfer(Path((source_chain, 7)), State(store.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Only the completed transfer counts towards volumes, and only once.
+        store
+            .complete_transfer(&actions[1], TransferStatus::Approved)
+            .unwrap();
+        let Json(volumes) = handle_get_transfer_volumes(State(store)).await.unwrap();
+        assert_eq!(volumes.len(), 1);
+        assert_eq!(volumes[0].source_chain, actions[1].chain_id());
+        assert_eq!(
+            volumes[0].volume,
+            TransferVolume {
+                transfers: 1,
+                amount: 200,
+            }
+        );
+    }
+}
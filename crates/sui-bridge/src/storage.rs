@@ -4,8 +4,12 @@
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use sui_types::Identifier;
 
+use serde::{Deserialize, Serialize};
+use sui_types::bridge::BridgeChainId;
+use sui_types::committee::StakeUnit;
 use sui_types::event::EventID;
 use typed_store::rocks::{DBMap, MetricConf};
 use typed_store::traits::TableSummary;
@@ -24,6 +28,123 @@ pub struct BridgeOrchestratorTables {
     pub(crate) sui_syncer_cursors: DBMap<Identifier, EventID>,
     /// contract address to the last processed block
     pub(crate) eth_syncer_cursors: DBMap<ethers::types::Address, u64>,
+    /// (source chain id, nonce) to the progress of the token transfer with that nonce
+    pub(crate) transfer_records: DBMap<(u8, u64), TransferRecord>,
+    /// (source chain id, destination chain id, token id) to the volume of completed transfers
+    pub(crate) transfer_volumes: DBMap<(u8, u8, u8), TransferVolume>,
+}
+
+/// The progress of a token transfer through the bridge, as observed by this node.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferStatus {
+    /// The transfer was observed on its source chain, and is waiting for committee signatures.
+    Pending,
+    /// Enough committee signatures were collected to approve the transfer.
+    Signed,
+    /// The transfer was approved on Sui.
+    Approved,
+    /// The transfer was approved and its tokens were claimed on Sui.
+    Claimed,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransferRecord {
+    pub source_chain: BridgeChainId,
+    pub destination_chain: BridgeChainId,
+    pub nonce: u64,
+    /// The transaction on the source chain that initiated the transfer.
+    pub source_tx: String,
+    pub sender: String,
+    pub recipient: String,
+    pub token_id: u8,
+    /// The amount transferred, adjusted to the token's decimals on Sui.
+    pub amount: u64,
+    pub status: TransferStatus,
+    /// The number of committee signatures collected for the transfer, and their total stake.
+    pub signatures: u64,
+    pub signed_stake: StakeUnit,
+    /// The stake that must sign the transfer for it to be approved.
+    pub approval_threshold: StakeUnit,
+    pub first_seen_ms: u64,
+    pub updated_ms: u64,
+}
+
+/// Total transfers completed between two chains, for one token.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransferVolume {
+    pub transfers: u64,
+    /// Total amount transferred, adjusted to the token's decimals on Sui.
+    pub amount: u64,
+}
+
+impl TransferStatus {
+    pub fn is_complete(&self) -> bool {
+        matches!(self, TransferStatus::Approved | TransferStatus::Claimed)
+    }
+}
+
+impl TransferRecord {
+    /// A new, pending record for `action`, or `None` if it is not a token transfer.
+    fn new(action: &BridgeAction, now_ms: u64) -> Option<Self> {
+        let (source_chain, destination_chain, source_tx, sender, recipient, token_id, amount) =
+            match action {
+                BridgeAction::SuiToEthBridgeAction(a) => {
+                    let e = &a.sui_bridge_event;
+                    (
+                        e.sui_chain_id,
+                        e.eth_chain_id,
+                        a.sui_tx_digest.to_string(),
+                        e.sui_address.to_string(),
+                        format!("{:#x}", e.eth_address),
+                        e.token_id,
+                        e.amount_sui_adjusted,
+                    )
+                }
+                BridgeAction::EthToSuiBridgeAction(a) => {
+                    let e = &a.eth_bridge_event;
+                    (
+                        e.eth_chain_id,
+                        e.sui_chain_id,
+                        format!("{:#x}", a.eth_tx_hash),
+                        format!("{:#x}", e.eth_address),
+                        e.sui_address.to_string(),
+                        e.token_id,
+                        e.sui_adjusted_amount,
+                    )
+                }
+                _ => return None,
+            };
+
+        Some(Self {
+            source_chain,
+            destination_chain,
+            nonce: action.seq_number(),
+            source_tx,
+            sender,
+            recipient,
+            token_id,
+            amount,
+            status: TransferStatus::Pending,
+            signatures: 0,
+            signed_stake: 0,
+            approval_threshold: action.approval_threshold(),
+            first_seen_ms: now_ms,
+            updated_ms: now_ms,
+        })
+    }
+
+    fn key(&self) -> (u8, u64) {
+        (self.source_chain as u8, self.nonce)
+    }
+
+    fn volume_key(&self) -> (u8, u8, u8) {
+        (
+            self.source_chain as u8,
+            self.destination_chain as u8,
+            self.token_id,
+        )
+    }
 }
 
 impl BridgeOrchestratorTables {
@@ -37,6 +158,20 @@ impl BridgeOrchestratorTables {
     }
 
     pub(crate) fn insert_pending_actions(&self, actions: &[BridgeAction]) -> BridgeResult<()> {
+        // Start tracking token transfers that have not been seen before, without resetting the
+        // progress of ones that have.
+        let now_ms = now_ms();
+        let records: Vec<_> = actions
+            .iter()
+            .filter_map(|a| TransferRecord::new(a, now_ms))
+            .collect();
+        let existing = self
+            .transfer_records
+            .multi_get(records.iter().map(|r| r.key()))
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't get transfer_records: {:?}", e))
+            })?;
+
         let mut batch = self.pending_actions.batch();
         batch
             .insert_batch(
@@ -46,6 +181,18 @@ impl BridgeOrchestratorTables {
             .map_err(|e| {
                 BridgeError::StorageError(format!("Couldn't insert into pending_actions: {:?}", e))
             })?;
+        batch
+            .insert_batch(
+                &self.transfer_records,
+                records
+                    .iter()
+                    .zip(existing)
+                    .filter(|(_, existing)| existing.is_none())
+                    .map(|(r, _)| (r.key(), r)),
+            )
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't insert into transfer_records: {:?}", e))
+            })?;
         batch
             .write()
             .map_err(|e| BridgeError::StorageError(format!("Couldn't write batch: {:?}", e)))
@@ -106,6 +253,145 @@ impl BridgeOrchestratorTables {
             .map_err(|e| BridgeError::StorageError(format!("Couldn't write batch: {:?}", e)))
     }
 
+    /// Record that `signatures` committee signatures, with `signed_stake` in total, were
+    /// collected for the token transfer `action`.
+    pub(crate) fn update_transfer_signatures(
+        &self,
+        action: &BridgeAction,
+        signatures: u64,
+        signed_stake: StakeUnit,
+    ) -> BridgeResult<()> {
+        self.update_transfer_record(action, |record| {
+            record.signatures = signatures;
+            record.signed_stake = signed_stake;
+            if record.status == TransferStatus::Pending {
+                record.status = TransferStatus::Signed;
+            }
+        })
+    }
+
+    /// Record that the token transfer `action` reached `status` on Sui, counting it towards the
+    /// transfer volumes the first time it is completed.
+    pub(crate) fn complete_transfer(
+        &self,
+        action: &BridgeAction,
+        status: TransferStatus,
+    ) -> BridgeResult<()> {
+        self.update_transfer_record(action, |record| {
+            // A claimed transfer is also approved, so never go back from claimed to approved.
+            if record.status != TransferStatus::Claimed {
+                record.status = status;
+            }
+        })
+    }
+
+    pub fn get_transfer_record(
+        &self,
+        source_chain: BridgeChainId,
+        nonce: u64,
+    ) -> BridgeResult<Option<TransferRecord>> {
+        self.transfer_records
+            .get(&(source_chain as u8, nonce))
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't get transfer_records: {:?}", e))
+            })
+    }
+
+    /// Up to `limit` transfers from `source_chain`, with nonces starting from `from_nonce`, in
+    /// nonce order, optionally only those with status `status`.
+    pub fn get_transfer_records(
+        &self,
+        source_chain: BridgeChainId,
+        from_nonce: u64,
+        status: Option<TransferStatus>,
+        limit: usize,
+    ) -> BridgeResult<Vec<TransferRecord>> {
+        let chain = source_chain as u8;
+        let mut records = vec![];
+        for entry in self
+            .transfer_records
+            .safe_range_iter((chain, from_nonce)..=(chain, u64::MAX))
+        {
+            if records.len() >= limit {
+                break;
+            }
+
+            let (_, record) = entry.map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't iterate transfer_records: {:?}", e))
+            })?;
+            if status.map_or(true, |status| status == record.status) {
+                records.push(record);
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Volumes of completed transfers, keyed by source chain, destination chain and token id.
+    pub fn get_transfer_volumes(&self) -> BridgeResult<Vec<((u8, u8, u8), TransferVolume)>> {
+        self.transfer_volumes
+            .safe_iter()
+            .collect::<Result<_, _>>()
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't iterate transfer_volumes: {:?}", e))
+            })
+    }
+
+    fn update_transfer_record(
+        &self,
+        action: &BridgeAction,
+        update: impl FnOnce(&mut TransferRecord),
+    ) -> BridgeResult<()> {
+        let now_ms = now_ms();
+        let Some(new) = TransferRecord::new(action, now_ms) else {
+            return Ok(());
+        };
+
+        let key = new.key();
+        let mut record = self
+            .transfer_records
+            .get(&key)
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't get transfer_records: {:?}", e))
+            })?
+            .unwrap_or(new);
+
+        let was_complete = record.status.is_complete();
+        update(&mut record);
+        record.updated_ms = now_ms;
+
+        let mut batch = self.transfer_records.batch();
+        if !was_complete && record.status.is_complete() {
+            let volume_key = record.volume_key();
+            let mut volume = self
+                .transfer_volumes
+                .get(&volume_key)
+                .map_err(|e| {
+                    BridgeError::StorageError(format!("Couldn't get transfer_volumes: {:?}", e))
+                })?
+                .unwrap_or_default();
+            volume.transfers += 1;
+            volume.amount = volume.amount.saturating_add(record.amount);
+            batch
+                .insert_batch(&self.transfer_volumes, [(volume_key, volume)])
+                .map_err(|e| {
+                    BridgeError::StorageError(format!(
+                        "Couldn't insert into transfer_volumes: {:?}",
+                        e
+                    ))
+                })?;
+        }
+
+        batch
+            .insert_batch(&self.transfer_records, [(key, record)])
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't insert into transfer_records: {:?}", e))
+            })?;
+        batch
+            .write()
+            .map_err(|e| BridgeError::StorageError(format!("Couldn't write batch: {:?}", e)))
+    }
+
     pub fn get_all_pending_actions(&self) -> HashMap<BridgeActionDigest, BridgeAction> {
         self.pending_actions.unbounded_iter().collect()
     }
@@ -131,6 +417,13 @@ impl BridgeOrchestratorTables {
     }
 }
 
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis() as u64
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
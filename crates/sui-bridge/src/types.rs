@@ -129,6 +129,58 @@ impl BridgeCommittee {
             .map(|a| if a.is_blocklisted { 0 } else { a.voting_power })
             .unwrap_or(0)
     }
+
+    /// Total stake of the members that are not blocklisted.
+    pub fn total_active_stake(&self) -> StakeUnit {
+        self.members
+            .values()
+            .filter(|a| !a.is_blocklisted)
+            .map(|a| a.voting_power)
+            .sum()
+    }
+
+    /// The committee that results from executing `action` against this committee.
+    pub fn with_blocklist_update(&self, action: &BlocklistCommitteeAction) -> BridgeResult<Self> {
+        let mut members = self.members.clone();
+        for key in &action.members_to_update {
+            let member = members.get_mut(key).ok_or_else(|| {
+                BridgeError::InvalidBridgeCommittee(format!(
+                    "{} is not a member of the committee",
+                    Hex::encode(key.as_bytes())
+                ))
+            })?;
+            member.is_blocklisted = action.blocklist_type == BlocklistType::Blocklist;
+        }
+        Self::new(members.into_values().collect())
+    }
+
+    /// Errors if the members that are not blocklisted can't approve every kind of action
+    /// together. Such a committee can't execute the governance actions that would fix it, so
+    /// committee rotations must never produce one.
+    pub fn ensure_can_approve_all_actions(&self) -> BridgeResult<()> {
+        let required = [
+            APPROVAL_THRESHOLD_TOKEN_TRANSFER,
+            APPROVAL_THRESHOLD_COMMITTEE_BLOCKLIST,
+            APPROVAL_THRESHOLD_EMERGENCY_PAUSE,
+            APPROVAL_THRESHOLD_EMERGENCY_UNPAUSE,
+            APPROVAL_THRESHOLD_LIMIT_UPDATE,
+            APPROVAL_THRESHOLD_ASSET_PRICE_UPDATE,
+            APPROVAL_THRESHOLD_EVM_CONTRACT_UPGRADE,
+            APPROVAL_THRESHOLD_ADD_TOKENS_ON_SUI,
+            APPROVAL_THRESHOLD_ADD_TOKENS_ON_EVM,
+        ]
+        .into_iter()
+        .max()
+        .unwrap();
+
+        let active = self.total_active_stake();
+        if active < required {
+            return Err(BridgeError::InvalidBridgeCommittee(format!(
+                "Active stake {active} is below the {required} needed to approve all actions"
+            )));
+        }
+        Ok(())
+    }
 }
 
 impl core::fmt::Display for BridgeCommittee {
@@ -687,6 +739,54 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_bridge_committee_blocklist_update() -> anyhow::Result<()> {
+        let (authority1, _, _) = get_test_authority_and_key(5000, 9999);
+        let (authority2, _, _) = get_test_authority_and_key(3000, 9999);
+        let (authority3, _, _) = get_test_authority_and_key(2000, 9999);
+        let committee = BridgeCommittee::new(vec![
+            authority1.clone(),
+            authority2.clone(),
+            authority3.clone(),
+        ])?;
+        assert_eq!(committee.total_active_stake(), 10000);
+        committee.ensure_can_approve_all_actions()?;
+
+        let blocklist = |members: Vec<&BridgeAuthority>, blocklist_type| BlocklistCommitteeAction {
+            nonce: 0,
+            chain_id: BridgeChainId::SuiCustom,
+            blocklist_type,
+            members_to_update: members.into_iter().map(|a| a.pubkey_bytes()).collect(),
+        };
+
+        // Blocklisting a small member leaves enough stake to approve everything.
+        let rotated = committee
+            .with_blocklist_update(&blocklist(vec![&authority3], BlocklistType::Blocklist))?;
+        assert!(!rotated.is_active_member(&authority3.pubkey_bytes()));
+        assert_eq!(rotated.total_active_stake(), 8000);
+        rotated.ensure_can_approve_all_actions()?;
+
+        // Blocklisting a majority does not.
+        let rotated = rotated
+            .with_blocklist_update(&blocklist(vec![&authority1], BlocklistType::Blocklist))?;
+        assert!(rotated.ensure_can_approve_all_actions().is_err());
+
+        // Unblocklisting restores members' stake.
+        let restored = rotated.with_blocklist_update(&blocklist(
+            vec![&authority1, &authority3],
+            BlocklistType::Unblocklist,
+        ))?;
+        assert_eq!(restored.total_active_stake(), 10000);
+
+        // Members must be part of the committee.
+        let (stranger, _, _) = get_test_authority_and_key(1000, 9999);
+        assert!(committee
+            .with_blocklist_update(&blocklist(vec![&stranger], BlocklistType::Blocklist))
+            .is_err());
+
+        Ok(())
+    }
+
     // Regression test to avoid accidentally change to approval threshold
     #[test]
     fn test_bridge_action_approval_threshold_regression_test() -> anyhow::Result<()> {
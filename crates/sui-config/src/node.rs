@@ -167,6 +167,13 @@ pub struct NodeConfig {
     #[serde(default = "default_zklogin_oauth_providers")]
     pub zklogin_oauth_providers: BTreeMap<Chain, BTreeSet<String>>,
 
+    /// OpenID providers to fetch zkLogin JWKs from in addition to the well-known providers in
+    /// `zklogin_oauth_providers`, for deployments that run their own identity provider. These are
+    /// fetched regardless of chain. Signatures from these issuers are only accepted on networks
+    /// whose protocol config does not restrict zkLogin to a list of supported providers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub zklogin_custom_oidc_providers: Vec<CustomOidcProviderConfig>,
+
     #[serde(default = "default_authority_overload_config")]
     pub authority_overload_config: AuthorityOverloadConfig,
 
@@ -213,6 +220,16 @@ pub struct NodeConfig {
     /// If unspecified, this will default to `10000`.
     #[serde(default = "default_local_execution_time_cache_size")]
     pub local_execution_time_cache_size: usize,
+
+    /// Webhooks to notify when this node reaches the end of an epoch, finishes reconfiguring for
+    /// the next one, or observes a protocol upgrade.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub epoch_change_webhooks: Vec<EpochChangeWebhookConfig>,
+
+    /// Stream events emitted by executed transactions to a local file, named pipe or socket.
+    /// Events are only available to stream if `enable-index-processing` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_firehose: Option<EventFirehoseConfig>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -504,6 +521,23 @@ pub fn default_zklogin_oauth_providers() -> BTreeMap<Chain, BTreeSet<String>> {
     map
 }
 
+/// An OpenID provider that is not one of the well-known zkLogin providers.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct CustomOidcProviderConfig {
+    /// The provider's issuer URL. JWKs fetched from this provider are recorded against this
+    /// issuer, so it must match the `iss` claim of the JWTs it signs.
+    pub iss: String,
+
+    /// URL of the provider's JSON Web Key Set.
+    pub jwks_uri: String,
+
+    /// How long fetched keys are considered fresh before the key set is fetched again. Defaults
+    /// to `jwk_fetch_interval_seconds`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl_seconds: Option<u64>,
+}
+
 fn default_transaction_kv_store_config() -> TransactionKeyValueStoreReadConfig {
     TransactionKeyValueStoreReadConfig::default()
 }
@@ -1087,6 +1121,27 @@ pub struct AuthorityOverloadConfig {
     // is above the threshold.
     #[serde(default = "default_max_transaction_manager_per_object_queue_length")]
     pub max_transaction_manager_per_object_queue_length: usize,
+
+    // When the execution queue is no longer growing but transactions are still arriving faster
+    // than `safe_transaction_ready_rate`, load shedding is reduced by this many percentage points
+    // per `overload_monitor_interval`.
+    #[serde(default = "default_steady_load_shedding_reduction_percentage")]
+    pub steady_load_shedding_reduction_percentage: u32,
+
+    // The execution rate is multiplied by this ratio before being compared to the transaction
+    // ready rate, so that load shedding kicks in slightly before execution falls behind.
+    #[serde(default = "default_execution_rate_ratio_for_comparison")]
+    pub execution_rate_ratio_for_comparison: f64,
+
+    // Percentage points of load shed on top of what is needed to keep the execution queue from
+    // growing, so that the queue drains.
+    #[serde(default = "default_additional_load_shedding_percentage")]
+    pub additional_load_shedding_percentage: u32,
+
+    // How long a shed transaction is rejected for before it has a chance to be accepted again.
+    // This is also the retry delay suggested to clients.
+    #[serde(default = "default_load_shedding_seed_update_interval")]
+    pub load_shedding_seed_update_interval: Duration,
 }
 
 fn default_max_txn_age_in_queue() -> Duration {
@@ -1129,6 +1184,22 @@ fn default_max_transaction_manager_per_object_queue_length() -> usize {
     20
 }
 
+fn default_steady_load_shedding_reduction_percentage() -> u32 {
+    10
+}
+
+fn default_execution_rate_ratio_for_comparison() -> f64 {
+    0.95
+}
+
+fn default_additional_load_shedding_percentage() -> u32 {
+    2
+}
+
+fn default_load_shedding_seed_update_interval() -> Duration {
+    Duration::from_secs(30)
+}
+
 impl Default for AuthorityOverloadConfig {
     fn default() -> Self {
         Self {
@@ -1145,6 +1216,11 @@ impl Default for AuthorityOverloadConfig {
             max_transaction_manager_queue_length: default_max_transaction_manager_queue_length(),
             max_transaction_manager_per_object_queue_length:
                 default_max_transaction_manager_per_object_queue_length(),
+            steady_load_shedding_reduction_percentage:
+                default_steady_load_shedding_reduction_percentage(),
+            execution_rate_ratio_for_comparison: default_execution_rate_ratio_for_comparison(),
+            additional_load_shedding_percentage: default_additional_load_shedding_percentage(),
+            load_shedding_seed_update_interval: default_load_shedding_seed_update_interval(),
         }
     }
 }
@@ -1340,6 +1416,81 @@ pub struct StateDebugDumpConfig {
     pub dump_file_directory: Option<PathBuf>,
 }
 
+/// The kinds of epoch change that can be delivered to a webhook.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EpochChangeEvent {
+    /// The node has executed the last checkpoint of an epoch.
+    EpochEnded,
+    /// The node has finished reconfiguring and is operating in a new epoch.
+    Reconfigured,
+    /// The new epoch runs a different protocol version from the previous one.
+    ProtocolUpgraded,
+}
+
+/// A URL that epoch change notifications are POSTed to, as JSON.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct EpochChangeWebhookConfig {
+    pub url: String,
+
+    /// Shared secret used to sign each payload. If set, requests carry an
+    /// `X-Sui-Webhook-Signature` header containing the hex-encoded HMAC-SHA3-256 of the request
+    /// body, keyed by this secret.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+
+    /// The events to deliver to this webhook. If empty, all events are delivered.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<EpochChangeEvent>,
+}
+
+/// Where and how to stream events to, for lightweight integrations that don't want to run an
+/// indexer. Events are written one per line, in the order they were emitted.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct EventFirehoseConfig {
+    pub sink: EventFirehoseSink,
+
+    #[serde(default)]
+    pub format: EventFirehoseFormat,
+
+    /// Only stream events with one of these Move event types (e.g. `0x2::display::DisplayCreated`).
+    /// If empty, events of all types are streamed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub event_types: Vec<String>,
+
+    /// Only stream events from transactions sent by one of these addresses. If empty, events
+    /// from all senders are streamed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub senders: Vec<SuiAddress>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum EventFirehoseSink {
+    /// Append to a file, creating it if it does not exist. This can also be a named pipe.
+    File(PathBuf),
+    /// Connect to a TCP listener, reconnecting if the connection drops.
+    Tcp(SocketAddr),
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum EventFirehoseFormat {
+    /// Each line is an event in the same JSON representation as the JSON-RPC API.
+    #[default]
+    Json,
+    /// Each line is the Base64 encoding of the BCS-serialized `(EventID, Event)` pair.
+    Bcs,
+}
+
+impl EpochChangeWebhookConfig {
+    pub fn wants(&self, event: EpochChangeEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
@@ -1349,7 +1500,7 @@ mod tests {
     use sui_keys::keypair_file::{write_authority_keypair_to_file, write_keypair_to_file};
     use sui_types::crypto::{get_key_pair_from_rng, AuthorityKeyPair, NetworkKeyPair, SuiKeyPair};
 
-    use super::Genesis;
+    use super::{CustomOidcProviderConfig, Genesis};
     use crate::NodeConfig;
 
     #[test]
@@ -1369,6 +1520,42 @@ mod tests {
         let _template: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
     }
 
+    #[test]
+    fn zklogin_custom_oidc_providers() {
+        const TEMPLATE: &str = include_str!("../data/fullnode-template.yaml");
+        let yaml = format!(
+            "{TEMPLATE}
+zklogin-custom-oidc-providers:
+  - iss: https://auth.example.com
+    jwks-uri: https://auth.example.com/.well-known/jwks.json
+    cache-ttl-seconds: 600
+  - iss: https://login.example.org
+    jwks-uri: https://login.example.org/jwks
+"
+        );
+
+        let config: NodeConfig = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(
+            config.zklogin_custom_oidc_providers,
+            vec![
+                CustomOidcProviderConfig {
+                    iss: "https://auth.example.com".to_owned(),
+                    jwks_uri: "https://auth.example.com/.well-known/jwks.json".to_owned(),
+                    cache_ttl_seconds: Some(600),
+                },
+                CustomOidcProviderConfig {
+                    iss: "https://login.example.org".to_owned(),
+                    jwks_uri: "https://login.example.org/jwks".to_owned(),
+                    cache_ttl_seconds: None,
+                },
+            ],
+        );
+
+        // Custom providers are optional.
+        let config: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+        assert!(config.zklogin_custom_oidc_providers.is_empty());
+    }
+
     /// Tests that a legacy validator config (captured on 12/06/2024) can be parsed.
     #[test]
     fn legacy_validator_config() {
@@ -13,6 +13,12 @@ pub struct NodeConfigMetrics {
     tx_deny_config_num_denied_objects: IntGauge,
     tx_deny_config_num_denied_packages: IntGauge,
     tx_deny_config_num_denied_addresses: IntGauge,
+    overload_config_execution_queue_latency_soft_limit_ms: IntGauge,
+    overload_config_execution_queue_latency_hard_limit_ms: IntGauge,
+    overload_config_max_load_shedding_percentage: IntGauge,
+    overload_config_max_txn_age_in_queue_ms: IntGauge,
+    overload_config_max_transaction_manager_queue_length: IntGauge,
+    overload_config_max_transaction_manager_per_object_queue_length: IntGauge,
 }
 
 impl NodeConfigMetrics {
@@ -60,6 +66,42 @@ impl NodeConfigMetrics {
                 registry
             )
             .unwrap(),
+            overload_config_execution_queue_latency_soft_limit_ms: register_int_gauge_with_registry!(
+                "overload_config_execution_queue_latency_soft_limit_ms",
+                "Execution queueing latency at which load shedding starts, in milliseconds",
+                registry
+            )
+            .unwrap(),
+            overload_config_execution_queue_latency_hard_limit_ms: register_int_gauge_with_registry!(
+                "overload_config_execution_queue_latency_hard_limit_ms",
+                "Execution queueing latency at which aggressive load shedding starts, in milliseconds",
+                registry
+            )
+            .unwrap(),
+            overload_config_max_load_shedding_percentage: register_int_gauge_with_registry!(
+                "overload_config_max_load_shedding_percentage",
+                "Maximum percentage of transactions shed when overloaded",
+                registry
+            )
+            .unwrap(),
+            overload_config_max_txn_age_in_queue_ms: register_int_gauge_with_registry!(
+                "overload_config_max_txn_age_in_queue_ms",
+                "Age in milliseconds past which queued transactions cause new ones to be rejected",
+                registry
+            )
+            .unwrap(),
+            overload_config_max_transaction_manager_queue_length: register_int_gauge_with_registry!(
+                "overload_config_max_transaction_manager_queue_length",
+                "Transaction manager queue length past which new transactions are rejected",
+                registry
+            )
+            .unwrap(),
+            overload_config_max_transaction_manager_per_object_queue_length: register_int_gauge_with_registry!(
+                "overload_config_max_transaction_manager_per_object_queue_length",
+                "Per-object transaction manager queue length past which new transactions are rejected",
+                registry
+            )
+            .unwrap(),
         };
         Arc::new(this)
     }
@@ -79,5 +121,27 @@ impl NodeConfigMetrics {
             .set(config.transaction_deny_config.get_package_deny_set().len() as i64);
         self.tx_deny_config_num_denied_addresses
             .set(config.transaction_deny_config.get_address_deny_set().len() as i64);
+
+        let overload_config = &config.authority_overload_config;
+        self.overload_config_execution_queue_latency_soft_limit_ms
+            .set(
+                overload_config
+                    .execution_queue_latency_soft_limit
+                    .as_millis() as i64,
+            );
+        self.overload_config_execution_queue_latency_hard_limit_ms
+            .set(
+                overload_config
+                    .execution_queue_latency_hard_limit
+                    .as_millis() as i64,
+            );
+        self.overload_config_max_load_shedding_percentage
+            .set(overload_config.max_load_shedding_percentage as i64);
+        self.overload_config_max_txn_age_in_queue_ms
+            .set(overload_config.max_txn_age_in_queue.as_millis() as i64);
+        self.overload_config_max_transaction_manager_queue_length
+            .set(overload_config.max_transaction_manager_queue_length as i64);
+        self.overload_config_max_transaction_manager_per_object_queue_length
+            .set(overload_config.max_transaction_manager_per_object_queue_length as i64);
     }
 }
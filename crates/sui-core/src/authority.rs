@@ -166,6 +166,7 @@ use crate::state_accumulator::{AccumulatorStore, StateAccumulator, WrappedObject
 use crate::subscription_handler::SubscriptionHandler;
 use crate::transaction_input_loader::TransactionInputLoader;
 use crate::transaction_manager::TransactionManager;
+use crate::validator_drain::ValidatorDrainInfo;
 
 #[cfg(msim)]
 pub use crate::checkpoints::checkpoint_executor::utils::{
@@ -831,6 +832,9 @@ pub struct AuthorityState {
     /// Current overload status in this authority. Updated periodically.
     pub overload_info: AuthorityOverloadInfo,
 
+    /// Whether an operator has asked this authority to drain ahead of maintenance.
+    pub drain_info: ValidatorDrainInfo,
+
     pub validator_tx_finalizer: Option<Arc<ValidatorTxFinalizer<NetworkAuthorityClient>>>,
 
     /// The chain identifier is derived from the digest of the genesis checkpoint.
@@ -1133,7 +1137,11 @@ impl AuthorityState {
             .overload_info
             .load_shedding_percentage
             .load(Ordering::Relaxed);
-        overload_monitor_accept_tx(load_shedding_percentage, tx_data.digest())
+        overload_monitor_accept_tx(
+            self.overload_config(),
+            load_shedding_percentage,
+            tx_data.digest(),
+        )
     }
 
     fn update_overload_metrics(&self, source: &str) {
@@ -2939,6 +2947,7 @@ impl AuthorityState {
             db_checkpoint_config: db_checkpoint_config.clone(),
             config,
             overload_info: AuthorityOverloadInfo::default(),
+            drain_info: ValidatorDrainInfo::default(),
             validator_tx_finalizer,
             chain_identifier,
             congestion_tracker: Arc::new(CongestionTracker::new()),
@@ -3246,6 +3255,7 @@ impl AuthorityState {
             .await?;
         assert_eq!(new_epoch_store.epoch(), new_epoch);
         self.transaction_manager.reconfigure(new_epoch);
+        self.drain_info.end_epoch(new_epoch);
         *execution_lock = new_epoch;
         // drop execution_lock after epoch store was updated
         // see also assert in AuthorityState::process_certificate
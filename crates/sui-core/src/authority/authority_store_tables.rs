@@ -8,7 +8,7 @@ use std::path::Path;
 use sui_types::accumulator::Accumulator;
 use sui_types::base_types::SequenceNumber;
 use sui_types::digests::TransactionEventsDigest;
-use sui_types::effects::TransactionEffects;
+use sui_types::effects::{TransactionEffects, TransactionEvents};
 use sui_types::storage::{FullObjectKey, MarkerValue};
 use tracing::error;
 use typed_store::metrics::SamplingInterval;
@@ -380,6 +380,18 @@ impl AuthorityPerpetualTables {
         Ok(self.effects.get(&effect_digest)?)
     }
 
+    pub fn get_events(
+        &self,
+        digest: &TransactionEventsDigest,
+    ) -> SuiResult<Option<TransactionEvents>> {
+        let data = self
+            .events
+            .safe_range_iter((*digest, 0)..=(*digest, usize::MAX))
+            .map(|result| result.map(|(_, event)| event))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((!data.is_empty()).then_some(TransactionEvents { data }))
+    }
+
     // DEPRECATED as the backing table has been moved to authority_per_epoch_store.
     // Please do not add new accessors/callsites.
     pub fn get_checkpoint_sequence_number(
@@ -502,6 +514,27 @@ impl AuthorityPerpetualTables {
         Ok(())
     }
 
+    pub fn insert_executed_transaction_test_only(
+        &self,
+        transaction: sui_types::transaction::VerifiedTransaction,
+        effects: TransactionEffects,
+    ) -> SuiResult {
+        let tx_digest = *transaction.digest();
+        let effects_digest = effects.digest();
+        let mut wb = self.transactions.batch();
+        wb.insert_batch(
+            &self.transactions,
+            std::iter::once((tx_digest, transaction.serializable())),
+        )?
+        .insert_batch(&self.effects, std::iter::once((effects_digest, effects)))?
+        .insert_batch(
+            &self.executed_effects,
+            std::iter::once((tx_digest, effects_digest)),
+        )?;
+        wb.write()?;
+        Ok(())
+    }
+
     // fallible get object methods for sui-tool, which may need to attempt to read a corrupted database
     pub fn get_object_fallible(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
         let obj_entry = self
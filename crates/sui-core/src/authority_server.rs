@@ -34,7 +34,9 @@ use sui_types::messages_grpc::{
 };
 use sui_types::multiaddr::Multiaddr;
 use sui_types::sui_system_state::SuiSystemState;
-use sui_types::traffic_control::{ClientIdSource, PolicyConfig, RemoteFirewallConfig, Weight};
+use sui_types::traffic_control::{
+    ClientIdSource, MethodGroup, PolicyConfig, RemoteFirewallConfig, Weight,
+};
 use sui_types::{effects::TransactionEffectsAPI, messages_grpc::HandleTransactionRequestV2};
 use sui_types::{error::*, transaction::*};
 use sui_types::{
@@ -178,6 +180,8 @@ pub struct ValidatorServiceMetrics {
 
     num_rejected_tx_in_epoch_boundary: IntCounter,
     num_rejected_cert_in_epoch_boundary: IntCounter,
+    num_rejected_tx_during_drain: IntCounter,
+    num_rejected_cert_during_drain: IntCounter,
     num_rejected_tx_during_overload: IntCounterVec,
     num_rejected_cert_during_overload: IntCounterVec,
     connection_ip_not_found: IntCounter,
@@ -292,6 +296,18 @@ impl ValidatorServiceMetrics {
                 registry,
             )
             .unwrap(),
+            num_rejected_tx_during_drain: register_int_counter_with_registry!(
+                "validator_service_num_rejected_tx_during_drain",
+                "Number of rejected transaction while the validator is draining",
+                registry,
+            )
+            .unwrap(),
+            num_rejected_cert_during_drain: register_int_counter_with_registry!(
+                "validator_service_num_rejected_cert_during_drain",
+                "Number of rejected transaction certificate while the validator is draining",
+                registry,
+            )
+            .unwrap(),
             num_rejected_tx_during_overload: register_int_counter_vec_with_registry!(
                 "validator_service_num_rejected_tx_during_overload",
                 "Number of rejected transaction due to system overload",
@@ -430,6 +446,12 @@ impl ValidatorService {
 
         transaction.validity_check(epoch_store.protocol_config(), epoch_store.epoch())?;
 
+        // A draining validator does not sign new transactions, so that it does not take on any
+        // new work ahead of maintenance.
+        state.drain_info.check_not_draining().tap_err(|_| {
+            metrics.num_rejected_tx_during_drain.inc();
+        })?;
+
         // When authority is overloaded and decide to reject this tx, we still lock the object
         // and ask the client to retry in the future. This is because without locking, the
         // input objects can be locked by a different tx in the future, however, the input objects
@@ -518,6 +540,10 @@ impl ValidatorService {
 
         transaction.validity_check(epoch_store.protocol_config(), epoch_store.epoch())?;
 
+        state.drain_info.check_not_draining().tap_err(|_| {
+            metrics.num_rejected_tx_during_drain.inc();
+        })?;
+
         // Check system overload
         let overload_check_res = self.state.check_system_overload(
             &*consensus_adapter,
@@ -681,6 +707,11 @@ impl ValidatorService {
         }
 
         // 2) Verify the certificates.
+        // A draining validator only serves certificates that it has already executed.
+        self.state.drain_info.check_not_draining().tap_err(|_| {
+            self.metrics.num_rejected_cert_during_drain.inc();
+        })?;
+
         // Check system overload
         for certificate in &certificates {
             let overload_check_res = self.state.check_system_overload(
@@ -1301,9 +1332,16 @@ impl ValidatorService {
         }
     }
 
-    async fn handle_traffic_req(&self, client: Option<IpAddr>) -> Result<(), tonic::Status> {
+    async fn handle_traffic_req(
+        &self,
+        client: Option<IpAddr>,
+        method_group: MethodGroup,
+    ) -> Result<(), tonic::Status> {
         if let Some(traffic_controller) = &self.traffic_controller {
-            if !traffic_controller.check(&client, &None).await {
+            if !traffic_controller
+                .check_method_group(&client, &None, Some(method_group))
+                .await
+            {
                 // Entity in blocklist
                 Err(tonic::Status::from_error(SuiError::TooManyRequests.into()))
             } else {
@@ -1317,6 +1355,7 @@ impl ValidatorService {
     fn handle_traffic_resp<T>(
         &self,
         client: Option<IpAddr>,
+        method_group: MethodGroup,
         wrapped_response: WrappedServiceResponse<T>,
     ) -> Result<tonic::Response<T>, tonic::Status> {
         let (error, spam_weight, unwrapped_response) = match wrapped_response {
@@ -1338,6 +1377,7 @@ impl ValidatorService {
                     (error_weight, error_type)
                 }),
                 spam_weight,
+                method_group: Some(method_group),
                 timestamp: SystemTime::now(),
             })
         }
@@ -1378,7 +1418,7 @@ fn normalize(err: SuiError) -> Weight {
 /// unless it is necessary to override the return value.
 #[macro_export]
 macro_rules! handle_with_decoration {
-    ($self:ident, $func_name:ident, $request:ident) => {{
+    ($self:ident, $func_name:ident, $request:ident, $method_group:expr) => {{
         if $self.client_id_source.is_none() {
             return $self.$func_name($request).await.map(|(result, _)| result);
         }
@@ -1386,11 +1426,13 @@ macro_rules! handle_with_decoration {
         let client = $self.get_client_ip_addr(&$request, $self.client_id_source.as_ref().unwrap());
 
         // check if either IP is blocked, in which case return early
-        $self.handle_traffic_req(client.clone()).await?;
+        $self
+            .handle_traffic_req(client.clone(), $method_group)
+            .await?;
 
         // handle traffic tallying
         let wrapped_response = $self.$func_name($request).await;
-        $self.handle_traffic_resp(client, wrapped_response)
+        $self.handle_traffic_resp(client, $method_group, wrapped_response)
     }};
 }
 
@@ -1407,7 +1449,12 @@ impl Validator for ValidatorService {
         spawn_monitored_task!(async move {
             // NB: traffic tally wrapping handled within the task rather than on task exit
             // to prevent an attacker from subverting traffic control by severing the connection
-            handle_with_decoration!(validator_service, transaction_impl, request)
+            handle_with_decoration!(
+                validator_service,
+                transaction_impl,
+                request,
+                MethodGroup::Execution
+            )
         })
         .await
         .unwrap()
@@ -1424,7 +1471,12 @@ impl Validator for ValidatorService {
         spawn_monitored_task!(async move {
             // NB: traffic tally wrapping handled within the task rather than on task exit
             // to prevent an attacker from subverting traffic control by severing the connection
-            handle_with_decoration!(validator_service, transaction_v2_impl, request)
+            handle_with_decoration!(
+                validator_service,
+                transaction_v2_impl,
+                request,
+                MethodGroup::Execution
+            )
         })
         .await
         .unwrap()
@@ -1441,7 +1493,12 @@ impl Validator for ValidatorService {
         spawn_monitored_task!(async move {
             // NB: traffic tally wrapping handled within the task rather than on task exit
             // to prevent an attacker from subverting traffic control by severing the connection.
-            handle_with_decoration!(validator_service, submit_certificate_impl, request)
+            handle_with_decoration!(
+                validator_service,
+                submit_certificate_impl,
+                request,
+                MethodGroup::Execution
+            )
         })
         .await
         .unwrap()
@@ -1451,55 +1508,75 @@ impl Validator for ValidatorService {
         &self,
         request: tonic::Request<CertifiedTransaction>,
     ) -> Result<tonic::Response<HandleCertificateResponseV2>, tonic::Status> {
-        handle_with_decoration!(self, handle_certificate_v2_impl, request)
+        handle_with_decoration!(
+            self,
+            handle_certificate_v2_impl,
+            request,
+            MethodGroup::Execution
+        )
     }
 
     async fn handle_certificate_v3(
         &self,
         request: tonic::Request<HandleCertificateRequestV3>,
     ) -> Result<tonic::Response<HandleCertificateResponseV3>, tonic::Status> {
-        handle_with_decoration!(self, handle_certificate_v3_impl, request)
+        handle_with_decoration!(
+            self,
+            handle_certificate_v3_impl,
+            request,
+            MethodGroup::Execution
+        )
     }
 
     async fn handle_soft_bundle_certificates_v3(
         &self,
         request: tonic::Request<HandleSoftBundleCertificatesRequestV3>,
     ) -> Result<tonic::Response<HandleSoftBundleCertificatesResponseV3>, tonic::Status> {
-        handle_with_decoration!(self, handle_soft_bundle_certificates_v3_impl, request)
+        handle_with_decoration!(
+            self,
+            handle_soft_bundle_certificates_v3_impl,
+            request,
+            MethodGroup::Execution
+        )
     }
 
     async fn object_info(
         &self,
         request: tonic::Request<ObjectInfoRequest>,
     ) -> Result<tonic::Response<ObjectInfoResponse>, tonic::Status> {
-        handle_with_decoration!(self, object_info_impl, request)
+        handle_with_decoration!(self, object_info_impl, request, MethodGroup::Read)
     }
 
     async fn transaction_info(
         &self,
         request: tonic::Request<TransactionInfoRequest>,
     ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
-        handle_with_decoration!(self, transaction_info_impl, request)
+        handle_with_decoration!(self, transaction_info_impl, request, MethodGroup::Read)
     }
 
     async fn checkpoint(
         &self,
         request: tonic::Request<CheckpointRequest>,
     ) -> Result<tonic::Response<CheckpointResponse>, tonic::Status> {
-        handle_with_decoration!(self, checkpoint_impl, request)
+        handle_with_decoration!(self, checkpoint_impl, request, MethodGroup::Read)
     }
 
     async fn checkpoint_v2(
         &self,
         request: tonic::Request<CheckpointRequestV2>,
     ) -> Result<tonic::Response<CheckpointResponseV2>, tonic::Status> {
-        handle_with_decoration!(self, checkpoint_v2_impl, request)
+        handle_with_decoration!(self, checkpoint_v2_impl, request, MethodGroup::Read)
     }
 
     async fn get_system_state_object(
         &self,
         request: tonic::Request<SystemStateRequest>,
     ) -> Result<tonic::Response<SuiSystemState>, tonic::Status> {
-        handle_with_decoration!(self, get_system_state_object_impl, request)
+        handle_with_decoration!(
+            self,
+            get_system_state_object_impl,
+            request,
+            MethodGroup::Read
+        )
     }
 }
@@ -11,10 +11,14 @@ use pretty_assertions::assert_str_eq;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use roaring::RoaringBitmap;
+use serde::Serialize;
 use serde_reflection::{Registry, Result, Samples, Tracer, TracerConfig};
 use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::{fs::File, io::Write};
+use sui_protocol_config::ProtocolVersion;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{
     AggregateAuthoritySignature, AuthorityQuorumSignInfo, AuthorityStrongQuorumSignInfo,
@@ -304,8 +308,39 @@ enum Action {
     Print,
     Test,
     Record,
+    /// Export the formats as a self-describing schema, for generating serializers in other
+    /// languages.
+    Export,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Yaml,
+    Json,
+}
+
+/// A schema describing the BCS encoding of Sui's core types, for use by code generators.
+#[derive(Serialize)]
+struct Schema {
+    /// The protocol version the schema was exported for.
+    protocol_version: u64,
+
+    /// The names of the formats for the top-level types that clients (de)serialize.
+    roots: BTreeMap<&'static str, &'static str>,
+
+    formats: Registry,
+}
+
+/// Top-level types in the exported schema, by the name of their format in the registry.
+const SCHEMA_ROOTS: &[(&str, &str)] = &[
+    ("transaction_data", "TransactionData"),
+    ("sender_signed_data", "SenderSignedData"),
+    ("transaction_effects", "TransactionEffects"),
+    ("transaction_events", "TransactionEvents"),
+    ("object", "Object"),
+    ("checkpoint_summary", "CheckpointSummary"),
+];
+
 #[derive(Debug, Parser)]
 #[clap(
     name = "Sui format generator",
@@ -314,10 +349,27 @@ enum Action {
 struct Options {
     #[clap(value_enum, default_value = "Print", ignore_case = true)]
     action: Action,
+
+    /// Encoding of the exported schema.
+    #[clap(long, value_enum, default_value = "json", ignore_case = true)]
+    format: ExportFormat,
+
+    /// Protocol version to export the schema for. Defaults to the latest version this binary
+    /// supports. Earlier versions are served from the schemas recorded while they were the latest.
+    #[clap(long)]
+    protocol_version: Option<u64>,
+
+    /// Where to write the exported schema. Defaults to stdout.
+    #[clap(long, short)]
+    output: Option<PathBuf>,
 }
 
 const FILE_PATH: &str = "sui-core/tests/staged/sui.yaml";
 
+/// Formats recorded for each protocol version, as `<version>.yaml`. `record` (re-)writes the entry
+/// for the latest protocol version, so a version's entry is final once a later version exists.
+const SCHEMAS_PATH: &str = "sui-core/tests/staged/schemas";
+
 fn main() {
     let options = Options::parse();
     let registry = get_registry().unwrap();
@@ -330,11 +382,92 @@ fn main() {
             let content = serde_yaml::to_string(&registry).unwrap();
             let mut f = File::create(FILE_PATH).unwrap();
             writeln!(f, "{}", content).unwrap();
+
+            std::fs::create_dir_all(SCHEMAS_PATH).unwrap();
+            let path =
+                Path::new(SCHEMAS_PATH).join(format!("{}.yaml", ProtocolVersion::MAX.as_u64()));
+            let mut f = File::create(path).unwrap();
+            writeln!(f, "{}", content).unwrap();
         }
         Action::Test => {
             let reference = std::fs::read_to_string(FILE_PATH).unwrap();
             let content: String = serde_yaml::to_string(&registry).unwrap() + "\n";
             assert_str_eq!(&reference, &content);
+
+            // The latest protocol version's schema is still open, so it must track the formats.
+            let path =
+                Path::new(SCHEMAS_PATH).join(format!("{}.yaml", ProtocolVersion::MAX.as_u64()));
+            if let Ok(recorded) = std::fs::read_to_string(path) {
+                assert_str_eq!(&recorded, &content);
+            }
+        }
+        Action::Export => {
+            let content = export_schema(registry, &options).unwrap();
+            match &options.output {
+                Some(path) => std::fs::write(path, content).unwrap(),
+                None => println!("{content}"),
+            }
         }
     }
 }
+
+fn export_schema(latest: Registry, options: &Options) -> anyhow::Result<String> {
+    let protocol_version = options
+        .protocol_version
+        .unwrap_or(ProtocolVersion::MAX.as_u64());
+
+    let supported = ProtocolVersion::MIN.as_u64()..=ProtocolVersion::MAX.as_u64();
+    anyhow::ensure!(
+        supported.contains(&protocol_version),
+        "Protocol version {protocol_version} is not supported, expected one of {supported:?}",
+    );
+
+    let registry = if protocol_version == ProtocolVersion::MAX.as_u64() {
+        latest
+    } else {
+        recorded_registry(Path::new(SCHEMAS_PATH), protocol_version)?
+    };
+
+    for (_, name) in SCHEMA_ROOTS {
+        anyhow::ensure!(registry.contains_key(*name), "No format traced for {name}");
+    }
+
+    let schema = Schema {
+        protocol_version,
+        roots: SCHEMA_ROOTS.iter().copied().collect(),
+        formats: registry,
+    };
+
+    Ok(match options.format {
+        ExportFormat::Yaml => serde_yaml::to_string(&schema)?,
+        ExportFormat::Json => serde_json::to_string_pretty(&schema)?,
+    })
+}
+
+/// The formats in effect at `protocol_version`: those recorded for the latest protocol version at
+/// or before it (versions that did not change any formats have no entry of their own).
+fn recorded_registry(dir: &Path, protocol_version: u64) -> anyhow::Result<Registry> {
+    let mut recorded = None;
+    for entry in std::fs::read_dir(dir).into_iter().flatten() {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "yaml") {
+            let version = path
+                .file_stem()
+                .and_then(|s| s.to_str()?.parse::<u64>().ok());
+            if let Some(version) = version.filter(|v| *v <= protocol_version) {
+                if recorded.as_ref().map_or(true, |(v, _)| *v < version) {
+                    recorded = Some((version, path));
+                }
+            }
+        }
+    }
+
+    let Some((_, path)) = recorded else {
+        anyhow::bail!(
+            "No formats recorded in {} for protocol version {protocol_version} or earlier",
+            dir.display(),
+        );
+    };
+
+    Ok(serde_yaml::from_str(&std::fs::read_to_string(path)?)?)
+}
@@ -40,13 +40,6 @@ impl AuthorityOverloadInfo {
     }
 }
 
-const STEADY_OVERLOAD_REDUCTION_PERCENTAGE: u32 = 10;
-const EXECUTION_RATE_RATIO_FOR_COMPARISON: f64 = 0.95;
-const ADDITIONAL_LOAD_SHEDDING: f64 = 0.02;
-
-// The update interval of the random seed used to determine whether a txn should be rejected.
-const SEED_UPDATE_DURATION_SECS: u64 = 30;
-
 // Monitors the overload signals in `authority_state` periodically, and updates its `overload_info`
 // when the signals indicates overload.
 pub async fn overload_monitor(
@@ -126,7 +119,11 @@ fn check_authority_overload(
 
 // Calculates the percentage of transactions to drop in order to reduce execution queue.
 // Returns the integer percentage between 0 and 100.
-fn calculate_load_shedding_percentage(txn_ready_rate: f64, execution_rate: f64) -> u32 {
+fn calculate_load_shedding_percentage(
+    config: &AuthorityOverloadConfig,
+    txn_ready_rate: f64,
+    execution_rate: f64,
+) -> u32 {
     // When transaction ready rate is practically 0, we aren't adding more load to the
     // execution driver, so no shedding.
     // TODO: consensus handler or transaction manager can also be overloaded.
@@ -136,16 +133,15 @@ fn calculate_load_shedding_percentage(txn_ready_rate: f64, execution_rate: f64)
 
     // Deflate the execution rate to account for the case that execution_rate is close to
     // txn_ready_rate.
-    if execution_rate * EXECUTION_RATE_RATIO_FOR_COMPARISON > txn_ready_rate {
+    let ratio = config.execution_rate_ratio_for_comparison;
+    if execution_rate * ratio > txn_ready_rate {
         return 0;
     }
 
     // In order to maintain execution queue length, we need to drop at least (1 - executionRate / readyRate).
-    // To reduce the queue length, here we add 10% more transactions to drop.
-    (((1.0 - execution_rate * EXECUTION_RATE_RATIO_FOR_COMPARISON / txn_ready_rate)
-        + ADDITIONAL_LOAD_SHEDDING)
-        .min(1.0)
-        * 100.0)
+    // To reduce the queue length, here we drop some more transactions.
+    let additional_load_shedding = config.additional_load_shedding_percentage as f64 / 100.0;
+    (((1.0 - execution_rate * ratio / txn_ready_rate) + additional_load_shedding).min(1.0) * 100.0)
         .round() as u32
 }
 
@@ -169,7 +165,7 @@ fn check_overload_signals(
     let additional_load_shedding_percentage;
     if queueing_latency > config.execution_queue_latency_hard_limit {
         let calculated_load_shedding_percentage =
-            calculate_load_shedding_percentage(txn_ready_rate, execution_rate);
+            calculate_load_shedding_percentage(config, txn_ready_rate, execution_rate);
 
         additional_load_shedding_percentage = if calculated_load_shedding_percentage > 0
             || txn_ready_rate >= config.safe_transaction_ready_rate as f64
@@ -183,7 +179,7 @@ fn check_overload_signals(
         };
     } else if queueing_latency > config.execution_queue_latency_soft_limit {
         additional_load_shedding_percentage =
-            calculate_load_shedding_percentage(txn_ready_rate, execution_rate);
+            calculate_load_shedding_percentage(config, txn_ready_rate, execution_rate);
     } else {
         additional_load_shedding_percentage = 0;
     }
@@ -197,12 +193,11 @@ fn check_overload_signals(
         current_load_shedding_percentage
             + (100 - current_load_shedding_percentage) * additional_load_shedding_percentage / 100
     } else if txn_ready_rate > config.safe_transaction_ready_rate as f64
-        && current_load_shedding_percentage > 10
+        && current_load_shedding_percentage > config.steady_load_shedding_reduction_percentage
     {
         // We don't need to shed more load. However, the enqueue rate is still not minimal.
-        // We gradually reduce load shedding percentage (10% at a time) to gracefully accept
-        // more load.
-        current_load_shedding_percentage - STEADY_OVERLOAD_REDUCTION_PERCENTAGE
+        // We gradually reduce load shedding percentage to gracefully accept more load.
+        current_load_shedding_percentage - config.steady_load_shedding_reduction_percentage
     } else {
         // The current transaction ready rate is considered very low. Turn off load shedding mode.
         0
@@ -232,25 +227,27 @@ fn should_reject_tx(
 
 // Checks if we can accept the transaction with `tx_digest`.
 pub fn overload_monitor_accept_tx(
+    config: &AuthorityOverloadConfig,
     load_shedding_percentage: u32,
     tx_digest: TransactionDigest,
 ) -> SuiResult {
     // Derive a random seed from the epoch time for transaction selection. Changing the seed every
-    // `SEED_UPDATE_DURATION_SECS` interval allows rejected transaction's retry to have a chance
+    // `load_shedding_seed_update_interval` allows rejected transaction's retry to have a chance
     // to go through in the future.
     // Also, using the epoch time instead of randomly generating a seed allows that all validators
     // makes the same decision.
+    let seed_update_secs = config.load_shedding_seed_update_interval.as_secs().max(1);
     let temporal_seed = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("Sui did not exist prior to 1970")
         .as_secs()
-        / SEED_UPDATE_DURATION_SECS;
+        / seed_update_secs;
 
     if should_reject_tx(load_shedding_percentage, tx_digest, temporal_seed) {
-        // TODO: using `SEED_UPDATE_DURATION_SECS` is a safe suggestion that the time based seed
+        // TODO: using the seed update interval is a safe suggestion that the time based seed
         // is definitely different by then. However, a shorter suggestion may be available.
         fp_bail!(SuiError::ValidatorOverloadedRetryAfter {
-            retry_after_secs: SEED_UPDATE_DURATION_SECS
+            retry_after_secs: seed_update_secs
         });
     }
     Ok(())
@@ -323,13 +320,20 @@ mod tests {
 
     #[test]
     pub fn test_calculate_load_shedding_ratio() {
-        assert_eq!(calculate_load_shedding_percentage(95.0, 100.1), 0);
-        assert_eq!(calculate_load_shedding_percentage(95.0, 100.0), 2);
-        assert_eq!(calculate_load_shedding_percentage(100.0, 100.0), 7);
-        assert_eq!(calculate_load_shedding_percentage(110.0, 100.0), 16);
-        assert_eq!(calculate_load_shedding_percentage(180.0, 100.0), 49);
-        assert_eq!(calculate_load_shedding_percentage(100.0, 0.0), 100);
-        assert_eq!(calculate_load_shedding_percentage(0.0, 1.0), 0);
+        let config = AuthorityOverloadConfig::default();
+        assert_eq!(calculate_load_shedding_percentage(&config, 95.0, 100.1), 0);
+        assert_eq!(calculate_load_shedding_percentage(&config, 95.0, 100.0), 2);
+        assert_eq!(calculate_load_shedding_percentage(&config, 100.0, 100.0), 7);
+        assert_eq!(
+            calculate_load_shedding_percentage(&config, 110.0, 100.0),
+            16
+        );
+        assert_eq!(
+            calculate_load_shedding_percentage(&config, 180.0, 100.0),
+            49
+        );
+        assert_eq!(calculate_load_shedding_percentage(&config, 100.0, 0.0), 100);
+        assert_eq!(calculate_load_shedding_percentage(&config, 0.0, 1.0), 0);
     }
 
     #[test]
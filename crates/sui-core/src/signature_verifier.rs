@@ -16,7 +16,9 @@ use std::sync::Arc;
 use sui_types::digests::SenderSignedDataDigest;
 use sui_types::digests::ZKLoginInputsDigest;
 use sui_types::signature_verification::{
+    batch_verify_sender_signed_data_message_signatures,
     verify_sender_signed_data_message_signatures, VerifiedDigestCache,
+    DEFAULT_USER_SIGNATURE_BATCH_SIZE,
 };
 use sui_types::transaction::SenderSignedData;
 use sui_types::{
@@ -108,6 +110,10 @@ pub struct SignatureVerifier {
 
     queue: Mutex<CertBuffer>,
     pub metrics: Arc<SignatureVerifierMetrics>,
+
+    /// Number of plain user signatures of the same scheme to verify together when verifying
+    /// many transactions at once.
+    user_signature_batch_size: usize,
 }
 
 /// Contains two parameters to pass in to verify a ZkLogin signature.
@@ -156,6 +162,7 @@ impl SignatureVerifier {
             jwks: Default::default(),
             queue: Mutex::new(CertBuffer::new(batch_size)),
             metrics,
+            user_signature_batch_size: DEFAULT_USER_SIGNATURE_BATCH_SIZE,
             zk_login_params: ZkLoginParams {
                 supported_providers,
                 env,
@@ -187,6 +194,13 @@ impl SignatureVerifier {
         )
     }
 
+    /// Set the number of plain user signatures of the same scheme that are verified together when
+    /// verifying many transactions at once (see [Self::multi_verify_txs]).
+    pub fn with_user_signature_batch_size(mut self, batch_size: usize) -> Self {
+        self.user_signature_batch_size = batch_size;
+        self
+    }
+
     /// Verifies all certs, returns Ok only if all are valid.
     pub fn verify_certs_and_checkpoints(
         &self,
@@ -200,8 +214,9 @@ impl SignatureVerifier {
 
         // Verify only the user sigs of certificates that were not cached already, since whenever we
         // insert a certificate into the cache, it is already verified.
-        for cert in &certs {
-            self.verify_tx(cert.data())?;
+        let txs: Vec<_> = certs.iter().map(|cert| cert.data()).collect();
+        for result in self.multi_verify_txs(&txs) {
+            result?;
         }
         batch_verify_all_certificates_and_checkpoints(&self.committee, &certs, &checkpoints)?;
         self.certificate_cache
@@ -402,6 +417,50 @@ impl SignatureVerifier {
         )
     }
 
+    /// Verifies the user signatures of many transactions, batching the verification of plain
+    /// signatures across transactions. Returns a result for each transaction, in order.
+    pub fn multi_verify_txs(&self, signed_txs: &[&SenderSignedData]) -> Vec<SuiResult> {
+        let mut results: Vec<SuiResult> = vec![Ok(()); signed_txs.len()];
+        let (indices, uncached): (Vec<_>, Vec<_>) = signed_txs
+            .iter()
+            .enumerate()
+            .filter(|(_, tx)| !self.signed_data_cache.is_cached(&tx.full_message_digest()))
+            .map(|(i, tx)| (i, *tx))
+            .unzip();
+
+        if uncached.is_empty() {
+            return results;
+        }
+
+        let verify_params = VerifyParams::new(
+            self.jwks.read().clone(),
+            self.zk_login_params.supported_providers.clone(),
+            self.zk_login_params.env,
+            self.zk_login_params.verify_legacy_zklogin_address,
+            self.zk_login_params.accept_zklogin_in_multisig,
+            self.zk_login_params.zklogin_max_epoch_upper_bound_delta,
+        );
+
+        let verified = batch_verify_sender_signed_data_message_signatures(
+            &uncached,
+            self.committee.epoch(),
+            &verify_params,
+            self.zklogin_inputs_cache.clone(),
+            self.user_signature_batch_size,
+        );
+
+        let mut digests = vec![];
+        for ((i, tx), result) in indices.into_iter().zip(uncached).zip(verified) {
+            if result.is_ok() {
+                digests.push(tx.full_message_digest());
+            }
+            results[i] = result;
+        }
+
+        self.signed_data_cache.cache_digests(digests);
+        results
+    }
+
     pub fn clear_signature_cache(&self) {
         self.certificate_cache.clear();
         self.signed_data_cache.clear();
@@ -9,6 +9,7 @@ pub mod policies;
 use dashmap::DashMap;
 use fs::File;
 use prometheus::IntGauge;
+use std::collections::HashMap;
 use std::fs;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::ops::Add;
@@ -23,7 +24,9 @@ use mysten_metrics::spawn_monitored_task;
 use rand::Rng;
 use std::fmt::Debug;
 use std::time::{Duration, Instant, SystemTime};
-use sui_types::traffic_control::{PolicyConfig, PolicyType, RemoteFirewallConfig, Weight};
+use sui_types::traffic_control::{
+    MethodGroup, PolicyConfig, PolicyType, RemoteFirewallConfig, Weight,
+};
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
 use tracing::{debug, error, info, trace, warn};
@@ -39,9 +42,51 @@ struct Blocklists {
     proxied_clients: Blocklist,
 }
 
+/// Blocklists for each method group with its own policies, plus the blocklists shared by all
+/// other requests.
+#[derive(Clone)]
+struct GroupedBlocklists {
+    default: Blocklists,
+    groups: Arc<HashMap<MethodGroup, Blocklists>>,
+}
+
+impl GroupedBlocklists {
+    fn new(policy_config: &PolicyConfig) -> Self {
+        Self {
+            default: Blocklists::new(),
+            groups: Arc::new(
+                policy_config
+                    .method_group_policies
+                    .keys()
+                    .map(|group| (*group, Blocklists::new()))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn for_group(&self, method_group: Option<MethodGroup>) -> &Blocklists {
+        method_group
+            .and_then(|group| self.groups.get(&group))
+            .unwrap_or(&self.default)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Blocklists> {
+        std::iter::once(&self.default).chain(self.groups.values())
+    }
+}
+
+impl Blocklists {
+    fn new() -> Self {
+        Self {
+            clients: Arc::new(DashMap::new()),
+            proxied_clients: Arc::new(DashMap::new()),
+        }
+    }
+}
+
 #[derive(Clone)]
 enum Acl {
-    Blocklists(Blocklists),
+    Blocklists(GroupedBlocklists),
     /// If this variant is set, then we do no tallying or running
     /// of background tasks, and instead simply block all IPs not
     /// in the allowlist on calls to `check`. The allowlist should
@@ -123,10 +168,7 @@ impl TrafficController {
         metrics
             .deadmans_switch_enabled
             .set(mem_drainfile_present as i64);
-        let blocklists = Blocklists {
-            clients: Arc::new(DashMap::new()),
-            proxied_clients: Arc::new(DashMap::new()),
-        };
+        let blocklists = GroupedBlocklists::new(&policy_config);
         let tally_loop_blocklists = blocklists.clone();
         let clear_loop_blocklists = blocklists.clone();
         let tally_loop_metrics = metrics.clone();
@@ -207,6 +249,17 @@ impl TrafficController {
 
     /// Handle check with dry-run mode considered
     pub async fn check(&self, client: &Option<IpAddr>, proxied_client: &Option<IpAddr>) -> bool {
+        self.check_method_group(client, proxied_client, None).await
+    }
+
+    /// Like `check`, but for a request to a method in `method_group`. If the group has its own
+    /// policies, only blocks issued by those policies apply.
+    pub async fn check_method_group(
+        &self,
+        client: &Option<IpAddr>,
+        proxied_client: &Option<IpAddr>,
+        method_group: Option<MethodGroup>,
+    ) -> bool {
         let check_with_dry_run_maybe = |allowed| -> bool {
             match (allowed, self.dry_run_mode()) {
                 // check succeeded
@@ -229,7 +282,7 @@ impl TrafficController {
             }
             Acl::Blocklists(blocklists) => {
                 let allowed = self
-                    .check_blocklists(blocklists, client, proxied_client)
+                    .check_blocklists(blocklists.for_group(method_group), client, proxied_client)
                     .await;
                 check_with_dry_run_maybe(allowed)
             }
@@ -296,35 +349,64 @@ impl TrafficController {
 /// never checked again. This function runs periodically to clear out any
 /// such stale IPs. This also ensures that the blocklist length metric
 /// accurately reflects TTL.
-async fn run_clear_blocklists_loop(blocklists: Blocklists, metrics: Arc<TrafficControllerMetrics>) {
+async fn run_clear_blocklists_loop(
+    blocklists: GroupedBlocklists,
+    metrics: Arc<TrafficControllerMetrics>,
+) {
     loop {
         tokio::time::sleep(Duration::from_secs(3)).await;
         let now = SystemTime::now();
-        blocklists.clients.retain(|_, expiration| now < *expiration);
-        blocklists
-            .proxied_clients
-            .retain(|_, expiration| now < *expiration);
-        metrics
-            .connection_ip_blocklist_len
-            .set(blocklists.clients.len() as i64);
+        let (mut clients_len, mut proxied_clients_len) = (0, 0);
+        for blocklists in blocklists.iter() {
+            blocklists.clients.retain(|_, expiration| now < *expiration);
+            blocklists
+                .proxied_clients
+                .retain(|_, expiration| now < *expiration);
+            clients_len += blocklists.clients.len();
+            proxied_clients_len += blocklists.proxied_clients.len();
+        }
+        metrics.connection_ip_blocklist_len.set(clients_len as i64);
         metrics
             .proxy_ip_blocklist_len
-            .set(blocklists.proxied_clients.len() as i64);
+            .set(proxied_clients_len as i64);
     }
 }
 
+/// Spam and error policies for a method group, along with the blocklists they populate.
+struct MethodGroupPolicies {
+    spam_policy: TrafficControlPolicy,
+    error_policy: TrafficControlPolicy,
+    blocklists: Arc<Blocklists>,
+}
+
 async fn run_tally_loop(
     mut receiver: mpsc::Receiver<TrafficTally>,
     policy_config: PolicyConfig,
     fw_config: Option<RemoteFirewallConfig>,
-    blocklists: Blocklists,
+    blocklists: GroupedBlocklists,
     metrics: Arc<TrafficControllerMetrics>,
     mut mem_drainfile_present: bool,
 ) {
     let mut spam_policy = TrafficControlPolicy::from_spam_config(policy_config.clone()).await;
     let mut error_policy = TrafficControlPolicy::from_error_config(policy_config.clone()).await;
-    let spam_blocklists = Arc::new(blocklists.clone());
-    let error_blocklists = Arc::new(blocklists);
+    let default_blocklists = Arc::new(blocklists.default.clone());
+    let mut group_policies = HashMap::new();
+    for (group, config) in &policy_config.method_group_policies {
+        let policies = MethodGroupPolicies {
+            spam_policy: TrafficControlPolicy::from_config(
+                config.spam_policy_type.clone(),
+                policy_config.clone(),
+            )
+            .await,
+            error_policy: TrafficControlPolicy::from_config(
+                config.error_policy_type.clone(),
+                policy_config.clone(),
+            )
+            .await,
+            blocklists: Arc::new(blocklists.for_group(Some(*group)).clone()),
+        };
+        group_policies.insert(*group, policies);
+    }
     let node_fw_client = fw_config
         .as_ref()
         .map(|fw_config| NodeFWClient::new(fw_config.remote_fw_url.clone()));
@@ -341,14 +423,28 @@ async fn run_tally_loop(
                 metrics.tallies.inc();
                 match received {
                     Some(tally) => {
+                        // Tallies for method groups with their own policies are kept apart
+                        // from everything else.
+                        let (spam_policy, error_policy, blocklists) = match tally
+                            .method_group
+                            .and_then(|group| group_policies.get_mut(&group))
+                        {
+                            Some(group) => (
+                                &mut group.spam_policy,
+                                &mut group.error_policy,
+                                group.blocklists.clone(),
+                            ),
+                            None => (&mut spam_policy, &mut error_policy, default_blocklists.clone()),
+                        };
+
                         // TODO: spawn a task to handle tallying concurrently
                         if let Err(err) = handle_spam_tally(
-                            &mut spam_policy,
+                            spam_policy,
                             &policy_config,
                             &node_fw_client,
                             &fw_config,
                             tally.clone(),
-                            spam_blocklists.clone(),
+                            blocklists.clone(),
                             metrics.clone(),
                             mem_drainfile_present,
                         )
@@ -356,12 +452,12 @@ async fn run_tally_loop(
                             warn!("Error handling spam tally: {}", err);
                         }
                         if let Err(err) = handle_error_tally(
-                            &mut error_policy,
+                            error_policy,
                             &policy_config,
                             &node_fw_client,
                             &fw_config,
                             tally,
-                            error_blocklists.clone(),
+                            blocklists,
                             metrics.clone(),
                             mem_drainfile_present,
                         )
@@ -869,3 +965,53 @@ pub fn parse_ip(ip: &str) -> Option<IpAddr> {
             })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use sui_macros::sim_test;
+    use sui_types::traffic_control::MethodGroupPolicyConfig;
+
+    #[sim_test]
+    async fn test_method_group_policies_are_isolated() {
+        let policy_config = PolicyConfig {
+            connection_blocklist_ttl_sec: 60,
+            spam_policy_type: PolicyType::NoOp,
+            spam_sample_rate: Weight::one(),
+            dry_run: false,
+            method_group_policies: BTreeMap::from([(
+                MethodGroup::Read,
+                MethodGroupPolicyConfig {
+                    spam_policy_type: PolicyType::TestNConnIP(3),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let controller = TrafficController::init_for_test(policy_config, None);
+        let client = Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5)));
+
+        for _ in 0..3 {
+            controller.tally(
+                TrafficTally::new(client, None, None, Weight::one())
+                    .with_method_group(MethodGroup::Read),
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // The client is blocked from reads, but can still execute transactions, and make
+        // requests that are not in any group.
+        assert!(
+            !controller
+                .check_method_group(&client, &None, Some(MethodGroup::Read))
+                .await
+        );
+        assert!(
+            controller
+                .check_method_group(&client, &None, Some(MethodGroup::Execution))
+                .await
+        );
+        assert!(controller.check(&client, &None).await);
+    }
+}
@@ -13,7 +13,9 @@ use std::fmt::Debug;
 use std::hash::Hash;
 use std::time::Duration;
 use std::time::{Instant, SystemTime};
-use sui_types::traffic_control::{FreqThresholdConfig, PolicyConfig, PolicyType, Weight};
+use sui_types::traffic_control::{
+    FreqThresholdConfig, MethodGroup, PolicyConfig, PolicyType, Weight,
+};
 use tracing::{info, trace};
 
 const HIGHEST_RATES_CAPACITY: usize = 20;
@@ -228,6 +230,8 @@ pub struct TrafficTally {
     pub through_fullnode: Option<IpAddr>,
     pub error_info: Option<(Weight, String)>,
     pub spam_weight: Weight,
+    /// The group of the method that was called, if it has its own policies.
+    pub method_group: Option<MethodGroup>,
     pub timestamp: SystemTime,
 }
 
@@ -243,9 +247,15 @@ impl TrafficTally {
             through_fullnode,
             error_info,
             spam_weight,
+            method_group: None,
             timestamp: SystemTime::now(),
         }
     }
+
+    pub fn with_method_group(mut self, method_group: MethodGroup) -> Self {
+        self.method_group = Some(method_group);
+        self
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -543,6 +553,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))),
             error_info: None,
             spam_weight: Weight::one(),
+            method_group: None,
             timestamp: SystemTime::now(),
         };
         let bob = TrafficTally {
@@ -550,6 +561,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(4, 3, 2, 1))),
             error_info: None,
             spam_weight: Weight::one(),
+            method_group: None,
             timestamp: SystemTime::now(),
         };
         let charlie = TrafficTally {
@@ -557,6 +569,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8))),
             error_info: None,
             spam_weight: Weight::one(),
+            method_group: None,
             timestamp: SystemTime::now(),
         };
 
@@ -18,7 +18,7 @@ use sui_types::messages_checkpoint::{
     CheckpointContents, CheckpointSummary, SignedCheckpointSummary,
 };
 use sui_types::signature_verification::VerifiedDigestCache;
-use sui_types::transaction::CertifiedTransaction;
+use sui_types::transaction::{CertifiedTransaction, Transaction};
 
 // TODO consolidate with `gen_certs` in batch_verification_bench.rs
 fn gen_certs(
@@ -175,3 +175,49 @@ async fn test_async_verifier() {
 
     join_all(tasks).await;
 }
+
+#[tokio::test]
+async fn test_multi_verify_txs() {
+    use fastcrypto_zkp::bn254::zk_login_api::ZkLoginEnv;
+
+    let (committee, _) = Committee::new_simple_test_committee();
+    let registry = Registry::new();
+    let metrics = SignatureVerifierMetrics::new(&registry);
+    let verifier = SignatureVerifier::new(
+        Arc::new(committee),
+        metrics,
+        vec![],
+        ZkLoginEnv::Test,
+        true,
+        true,
+        Some(30),
+    )
+    .with_user_signature_batch_size(4);
+
+    let (receiver, _): (_, AccountKeyPair) = get_key_pair();
+    let (sender, sender_sec): (_, AccountKeyPair) = get_key_pair();
+    let mut txs: Vec<_> = (0..10)
+        .map(|_| make_dummy_tx(receiver, sender, &sender_sec))
+        .collect();
+
+    // Sign one transaction with the signature from another, so that it fails verification.
+    txs[5] = Transaction::from_generic_sig_data(
+        txs[5].data().intent_message().value.clone(),
+        txs[6].data().tx_signatures().to_vec(),
+    );
+
+    let data: Vec<_> = txs.iter().map(|tx| tx.data()).collect();
+    let results = verifier.multi_verify_txs(&data);
+    for (i, result) in results.iter().enumerate() {
+        assert_eq!(
+            result.is_err(),
+            i == 5,
+            "Unexpected result for {i}: {result:?}"
+        );
+    }
+
+    // Valid transactions are cached, the invalid transaction is still rejected.
+    let results = verifier.multi_verify_txs(&data);
+    assert!(results[5].is_err());
+    verifier.verify_tx(txs[0].data()).unwrap();
+}
@@ -0,0 +1,186 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use sui_types::committee::EpochId;
+use sui_types::error::{SuiError, SuiResult};
+use tracing::info;
+
+/// Sentinel stored in `ValidatorDrainInfo::drain_epoch` when no drain has been requested.
+const NOT_DRAINING: u64 = u64::MAX;
+
+/// Tracks whether an operator has asked this validator to drain ahead of maintenance.
+///
+/// While draining, the validator stops signing new transactions and stops accepting new
+/// certificates from clients, but keeps participating in consensus and checkpointing so that work
+/// already in flight completes. Once the network moves past the epoch in which the drain was
+/// requested, the drain is complete: the validator resumes normal operation and is ready to hand its
+/// keys over to a replacement machine.
+#[derive(Debug)]
+pub struct ValidatorDrainInfo {
+    /// The epoch in which the drain was requested, or `NOT_DRAINING`.
+    drain_epoch: AtomicU64,
+
+    /// The epoch in which the most recently completed drain was requested, or `NOT_DRAINING`.
+    drained_epoch: AtomicU64,
+}
+
+/// A point-in-time view of the drain, reported through the admin interface.
+#[derive(Debug, Clone)]
+pub struct ValidatorDrainStatus {
+    /// Whether the validator is currently rejecting new user transactions and certificates.
+    pub draining: bool,
+
+    /// The epoch in which the ongoing or most recently completed drain was requested, if any.
+    pub drain_epoch: Option<EpochId>,
+
+    /// The epoch the validator is currently operating in.
+    pub current_epoch: EpochId,
+
+    /// Certificates this validator submitted to consensus that have not been sequenced yet.
+    pub pending_consensus_certificates: usize,
+
+    /// Whether the validator has crossed the epoch boundary after the drain was requested, meaning
+    /// it no longer holds any in-flight work from that epoch and its keys can be handed over.
+    pub ready_for_handover: bool,
+}
+
+impl ValidatorDrainInfo {
+    /// Start draining as of `epoch`. Calling this again while already draining keeps the earliest
+    /// requested epoch.
+    pub fn start(&self, epoch: EpochId) {
+        let prev = self.drain_epoch.fetch_min(epoch, Ordering::SeqCst);
+        if prev == NOT_DRAINING {
+            self.drained_epoch.store(NOT_DRAINING, Ordering::SeqCst);
+            info!(epoch, "Validator drain started");
+        }
+    }
+
+    /// Stop draining, and resume accepting user transactions and certificates.
+    pub fn cancel(&self) {
+        self.drained_epoch.store(NOT_DRAINING, Ordering::SeqCst);
+        let prev = self.drain_epoch.swap(NOT_DRAINING, Ordering::SeqCst);
+        if prev != NOT_DRAINING {
+            info!(epoch = prev, "Validator drain cancelled");
+        }
+    }
+
+    /// Called when the validator reconfigures to `new_epoch`. A drain requested in an earlier epoch
+    /// is complete once its epoch has ended, so the validator stops rejecting requests, and reports
+    /// that it is ready for handover.
+    pub fn end_epoch(&self, new_epoch: EpochId) {
+        let Some(epoch) = self.drain_epoch().filter(|e| *e < new_epoch) else {
+            return;
+        };
+
+        if self
+            .drain_epoch
+            .compare_exchange(epoch, NOT_DRAINING, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            self.drained_epoch.store(epoch, Ordering::SeqCst);
+            info!(
+                epoch,
+                new_epoch, "Validator drain complete, ready for handover"
+            );
+        }
+    }
+
+    /// The epoch in which the drain was requested, if the validator is draining.
+    pub fn drain_epoch(&self) -> Option<EpochId> {
+        let epoch = self.drain_epoch.load(Ordering::SeqCst);
+        (epoch != NOT_DRAINING).then_some(epoch)
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.drain_epoch().is_some()
+    }
+
+    /// Fails with `ValidatorHaltedAtEpochEnd` if the validator is draining, so that clients retry
+    /// their request against other validators, as they would at an epoch boundary.
+    pub fn check_not_draining(&self) -> SuiResult {
+        if self.is_draining() {
+            Err(SuiError::ValidatorHaltedAtEpochEnd)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn status(
+        &self,
+        current_epoch: EpochId,
+        pending_consensus_certificates: usize,
+    ) -> ValidatorDrainStatus {
+        let drain_epoch = self.drain_epoch();
+        let drained_epoch = self.drained_epoch.load(Ordering::SeqCst);
+        let drained_epoch = (drained_epoch != NOT_DRAINING).then_some(drained_epoch);
+        ValidatorDrainStatus {
+            draining: drain_epoch.is_some(),
+            drain_epoch: drain_epoch.or(drained_epoch),
+            current_epoch,
+            pending_consensus_certificates,
+            ready_for_handover: drain_epoch.is_none() && drained_epoch.is_some(),
+        }
+    }
+}
+
+impl Default for ValidatorDrainInfo {
+    fn default() -> Self {
+        Self {
+            drain_epoch: AtomicU64::new(NOT_DRAINING),
+            drained_epoch: AtomicU64::new(NOT_DRAINING),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drain_lifecycle() {
+        let info = ValidatorDrainInfo::default();
+        assert!(!info.is_draining());
+        assert!(info.check_not_draining().is_ok());
+
+        info.start(3);
+        info.start(4);
+        assert_eq!(info.drain_epoch(), Some(3));
+        assert!(matches!(
+            info.check_not_draining(),
+            Err(SuiError::ValidatorHaltedAtEpochEnd)
+        ));
+
+        let status = info.status(3, 2);
+        assert!(status.draining);
+        assert!(!status.ready_for_handover);
+
+        info.cancel();
+        assert!(!info.is_draining());
+        assert!(!info.status(3, 0).ready_for_handover);
+    }
+
+    #[test]
+    fn test_drain_ends_with_epoch() {
+        let info = ValidatorDrainInfo::default();
+        info.start(3);
+
+        // Reconfiguring into the epoch the drain was requested in does not end it.
+        info.end_epoch(3);
+        assert!(info.is_draining());
+
+        info.end_epoch(4);
+        assert!(!info.is_draining());
+        assert!(info.check_not_draining().is_ok());
+
+        let status = info.status(4, 0);
+        assert!(!status.draining);
+        assert_eq!(status.drain_epoch, Some(3));
+        assert!(status.ready_for_handover);
+
+        // Starting another drain resets the handover status.
+        info.start(4);
+        assert!(!info.status(4, 0).ready_for_handover);
+    }
+}
@@ -22,3 +22,28 @@ cargo -q run --example generate-format -- print > crates/sui-core/tests/staged/s
         "
     );
 }
+
+#[test]
+#[cfg_attr(msim, ignore)]
+fn test_export_protocol_version() {
+    let export = |version: u64| {
+        std::process::Command::new("cargo")
+            .current_dir("..")
+            .args(["run", "--example", "generate-format", "--", "export"])
+            .args(["--protocol-version", &version.to_string()])
+            .output()
+            .expect("failed to execute process")
+    };
+
+    let max = sui_protocol_config::ProtocolVersion::MAX.as_u64();
+    let output = export(max);
+    assert!(output.status.success(), "{output:?}");
+
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(schema["protocol_version"], max);
+    assert_eq!(schema["roots"]["transaction_data"], "TransactionData");
+    assert!(schema["formats"]["TransactionData"].is_object());
+
+    // Versions this binary does not know about have no schema.
+    assert!(!export(max + 1).status.success());
+}
@@ -0,0 +1,219 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! OHLCV candles per pool, aggregated from order fills as they are indexed. Each write folds the
+//! fills it inserted into candles for every configured interval, which are then merged into the
+//! candles already stored, so candles stay correct even when checkpoints are indexed out of order.
+
+use std::collections::BTreeMap;
+
+use diesel::dsl::sql;
+use diesel::pg::Pg;
+use diesel::sql_types::BigInt;
+use diesel::upsert::excluded;
+use diesel::ExpressionMethods;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+
+use crate::models::{OhlcvCandle, OrderFill};
+use crate::schema::ohlcv_candles;
+
+/// Default lengths of the intervals, in seconds, that candles are aggregated over: 1m, 5m, 15m,
+/// 1h, 4h and 1d.
+pub const DEFAULT_CANDLE_INTERVALS_SECS: [u64; 6] = [60, 300, 900, 3600, 14400, 86400];
+
+/// Aggregate `fills` into a candle per pool, interval, and bucket in that interval.
+pub(crate) fn aggregate(fills: &[OrderFill], intervals_secs: &[u64]) -> Vec<OhlcvCandle> {
+    let mut fills: Vec<_> = fills.iter().collect();
+    fills.sort_by_key(|f| (f.checkpoint_timestamp_ms, f.checkpoint));
+
+    let mut candles: BTreeMap<(&str, i64, i64), OhlcvCandle> = BTreeMap::new();
+    for fill in fills {
+        let timestamp_ms = fill.checkpoint_timestamp_ms;
+        for interval_secs in intervals_secs {
+            let interval_ms = *interval_secs as i64 * 1000;
+            let bucket_start_ms = timestamp_ms - timestamp_ms.rem_euclid(interval_ms);
+
+            candles
+                .entry((&fill.pool_id, *interval_secs as i64, bucket_start_ms))
+                .and_modify(|c| {
+                    c.high = c.high.max(fill.price);
+                    c.low = c.low.min(fill.price);
+                    c.close = fill.price;
+                    c.close_timestamp_ms = timestamp_ms;
+                    c.base_volume += fill.base_quantity;
+                    c.quote_volume += fill.quote_quantity;
+                    c.trade_count += 1;
+                })
+                .or_insert_with(|| OhlcvCandle {
+                    pool_id: fill.pool_id.clone(),
+                    interval_secs: *interval_secs as i64,
+                    bucket_start_ms,
+                    open: fill.price,
+                    high: fill.price,
+                    low: fill.price,
+                    close: fill.price,
+                    base_volume: fill.base_quantity,
+                    quote_volume: fill.quote_quantity,
+                    trade_count: 1,
+                    open_timestamp_ms: timestamp_ms,
+                    close_timestamp_ms: timestamp_ms,
+                });
+        }
+    }
+
+    candles.into_values().collect()
+}
+
+/// Merge `candles` into the candles already stored for the same pools, intervals and buckets.
+pub(crate) async fn upsert<C: AsyncConnection<Backend = Pg>>(
+    conn: &mut C,
+    candles: &[OhlcvCandle],
+) -> Result<usize, diesel::result::Error> {
+    use ohlcv_candles::dsl as c;
+
+    if candles.is_empty() {
+        return Ok(0);
+    }
+
+    diesel::insert_into(ohlcv_candles::table)
+        .values(candles)
+        .on_conflict((c::pool_id, c::interval_secs, c::bucket_start_ms))
+        .do_update()
+        .set((
+            c::open.eq(sql::<BigInt>(
+                "CASE WHEN excluded.open_timestamp_ms < ohlcv_candles.open_timestamp_ms \
+                 THEN excluded.open ELSE ohlcv_candles.open END",
+            )),
+            c::close.eq(sql::<BigInt>(
+                "CASE WHEN excluded.close_timestamp_ms >= ohlcv_candles.close_timestamp_ms \
+                 THEN excluded.close ELSE ohlcv_candles.close END",
+            )),
+            c::open_timestamp_ms.eq(sql::<BigInt>(
+                "LEAST(excluded.open_timestamp_ms, ohlcv_candles.open_timestamp_ms)",
+            )),
+            c::close_timestamp_ms.eq(sql::<BigInt>(
+                "GREATEST(excluded.close_timestamp_ms, ohlcv_candles.close_timestamp_ms)",
+            )),
+            c::high.eq(sql::<BigInt>("GREATEST(excluded.high, ohlcv_candles.high)")),
+            c::low.eq(sql::<BigInt>("LEAST(excluded.low, ohlcv_candles.low)")),
+            c::base_volume.eq(c::base_volume + excluded(c::base_volume)),
+            c::quote_volume.eq(c::quote_volume + excluded(c::quote_volume)),
+            c::trade_count.eq(c::trade_count + excluded(c::trade_count)),
+        ))
+        .execute(conn)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE_MS: i64 = 60_000;
+
+    fn fill(pool_id: &str, checkpoint: i64, timestamp_ms: i64, price: i64) -> OrderFill {
+        OrderFill {
+            event_digest: format!("{checkpoint}{timestamp_ms}"),
+            digest: String::new(),
+            sender: String::new(),
+            checkpoint,
+            checkpoint_timestamp_ms: timestamp_ms,
+            package: String::new(),
+            pool_id: pool_id.to_owned(),
+            maker_order_id: String::new(),
+            taker_order_id: String::new(),
+            maker_client_order_id: 0,
+            taker_client_order_id: 0,
+            price,
+            taker_fee: 0,
+            taker_fee_is_deep: false,
+            maker_fee: 0,
+            maker_fee_is_deep: false,
+            taker_is_bid: true,
+            base_quantity: 10,
+            quote_quantity: price * 10,
+            maker_balance_manager_id: String::new(),
+            taker_balance_manager_id: String::new(),
+            onchain_timestamp: timestamp_ms,
+        }
+    }
+
+    /// `(interval_secs, bucket_start_ms, open, high, low, close, trade_count)` for each candle.
+    fn summarize(candles: &[OhlcvCandle]) -> Vec<(i64, i64, i64, i64, i64, i64, i64)> {
+        candles
+            .iter()
+            .map(|c| {
+                (
+                    c.interval_secs,
+                    c.bucket_start_ms,
+                    c.open,
+                    c.high,
+                    c.low,
+                    c.close,
+                    c.trade_count,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_buckets_at_interval_edges() {
+        // Fills on either side of the one minute boundary, and one exactly on it.
+        let fills = vec![
+            fill("pool", 1, MINUTE_MS - 1, 100),
+            fill("pool", 2, MINUTE_MS, 200),
+            fill("pool", 3, 2 * MINUTE_MS - 1, 150),
+            fill("pool", 4, 5 * MINUTE_MS, 300),
+        ];
+
+        let candles = aggregate(&fills, &[60, 300]);
+        assert_eq!(
+            summarize(&candles),
+            vec![
+                (60, 0, 100, 100, 100, 100, 1),
+                (60, MINUTE_MS, 200, 200, 150, 150, 2),
+                (60, 5 * MINUTE_MS, 300, 300, 300, 300, 1),
+                (300, 0, 100, 200, 100, 150, 3),
+                (300, 5 * MINUTE_MS, 300, 300, 300, 300, 1),
+            ]
+        );
+
+        let candle = &candles[1];
+        assert_eq!(candle.open_timestamp_ms, MINUTE_MS);
+        assert_eq!(candle.close_timestamp_ms, 2 * MINUTE_MS - 1);
+        assert_eq!(candle.base_volume, 20);
+        assert_eq!(candle.quote_volume, 3500);
+    }
+
+    #[test]
+    fn test_out_of_order_fills() {
+        // Fills are ordered by time before they are aggregated, so open and close don't depend on
+        // the order fills were indexed in.
+        let fills = vec![
+            fill("pool", 3, 3000, 30),
+            fill("pool", 1, 1000, 10),
+            fill("pool", 2, 2000, 20),
+        ];
+
+        let candles = aggregate(&fills, &[60]);
+        assert_eq!(summarize(&candles), vec![(60, 0, 10, 30, 10, 30, 3)]);
+    }
+
+    #[test]
+    fn test_buckets_per_pool() {
+        let fills = vec![fill("a", 1, 1000, 10), fill("b", 1, 1000, 20)];
+
+        let candles = aggregate(&fills, &[60]);
+        let pools: Vec<_> = candles
+            .iter()
+            .map(|c| (c.pool_id.as_str(), c.open))
+            .collect();
+        assert_eq!(pools, vec![("a", 10), ("b", 20)]);
+    }
+
+    #[test]
+    fn test_negative_timestamps_round_down() {
+        // Buckets start at multiples of the interval, even before the epoch.
+        let candles = aggregate(&[fill("pool", 1, -1, 10)], &[60]);
+        assert_eq!(candles[0].bucket_start_ms, -MINUTE_MS);
+    }
+}
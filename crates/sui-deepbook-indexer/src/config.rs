@@ -4,6 +4,8 @@
 use serde::{Deserialize, Serialize};
 use std::env;
 
+use crate::candles::DEFAULT_CANDLE_INTERVALS_SECS;
+
 /// config as loaded from `config.yaml`.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct IndexerConfig {
@@ -18,10 +20,28 @@ pub struct IndexerConfig {
     pub concurrency: u64,
     pub metric_port: u16,
     pub service_port: u16,
+    /// Lengths of the intervals, in seconds, that OHLCV candles are aggregated over, per pool.
+    #[serde(default = "default_candle_intervals_secs")]
+    pub candle_intervals_secs: Vec<u64>,
+    /// Only provide this to periodically snapshot the order book of every pool.
+    #[serde(default)]
+    pub order_book_snapshots: Option<OrderBookSnapshotConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OrderBookSnapshotConfig {
+    /// Number of price levels to record on each side of the book.
+    pub depth: u64,
+    /// How often to take a snapshot of each pool's order book, in seconds.
+    pub interval_secs: u64,
 }
 
 impl sui_config::Config for IndexerConfig {}
 
+pub fn default_candle_intervals_secs() -> Vec<u64> {
+    DEFAULT_CANDLE_INTERVALS_SECS.to_vec()
+}
+
 pub fn default_db_url() -> String {
     env::var("DB_URL").expect("db_url must be set in config or via the $DB_URL env var")
 }
@@ -1,13 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod candles;
 pub mod config;
 pub mod error;
 pub mod events;
 pub mod metrics;
 pub mod models;
+pub mod order_book;
 pub mod postgres_manager;
 pub mod schema;
 pub mod server;
 pub mod sui_deepbook_indexer;
+pub mod trade_stream;
 pub mod types;
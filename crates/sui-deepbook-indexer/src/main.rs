@@ -13,6 +13,7 @@ use sui_config::Config;
 use sui_data_ingestion_core::DataIngestionMetrics;
 use sui_deepbook_indexer::config::IndexerConfig;
 use sui_deepbook_indexer::metrics::DeepBookIndexerMetrics;
+use sui_deepbook_indexer::order_book::run_order_book_snapshots;
 use sui_deepbook_indexer::postgres_manager::get_connection_pool;
 use sui_deepbook_indexer::server::run_server;
 use sui_deepbook_indexer::sui_deepbook_indexer::PgDeepbookPersistent;
@@ -66,6 +67,7 @@ async fn main() -> Result<()> {
         ProgressSavingPolicy::OutOfOrderSaveAfterDuration(OutOfOrderSaveAfterDurationPolicy::new(
             tokio::time::Duration::from_secs(30),
         )),
+        config.candle_intervals_secs.clone(),
     );
 
     let sui_client = Arc::new(
@@ -75,7 +77,7 @@ async fn main() -> Result<()> {
     );
     let sui_checkpoint_datasource = SuiCheckpointDatasource::new(
         config.remote_store_url,
-        sui_client,
+        sui_client.clone(),
         config.concurrency as usize,
         config
             .checkpoints_path
@@ -90,6 +92,10 @@ async fn main() -> Result<()> {
         SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), config.service_port);
     run_server(service_address, datastore.clone());
 
+    if let Some(snapshot_config) = config.order_book_snapshots {
+        run_order_book_snapshots(snapshot_config, datastore.clone(), sui_client);
+    }
+
     let indexer = IndexerBuilder::new(
         "SuiDeepBookIndexer",
         sui_checkpoint_datasource,
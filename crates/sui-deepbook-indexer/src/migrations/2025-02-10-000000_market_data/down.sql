@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS order_book_snapshots;
+DROP TABLE IF EXISTS ohlcv_candles;
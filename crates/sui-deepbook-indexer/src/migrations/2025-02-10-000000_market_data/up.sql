@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS ohlcv_candles
+(
+    pool_id                     TEXT         NOT NULL,
+    interval_secs               BIGINT       NOT NULL,
+    bucket_start_ms             BIGINT       NOT NULL,
+    open                        BIGINT       NOT NULL,
+    high                        BIGINT       NOT NULL,
+    low                         BIGINT       NOT NULL,
+    close                       BIGINT       NOT NULL,
+    base_volume                 BIGINT       NOT NULL,
+    quote_volume                BIGINT       NOT NULL,
+    trade_count                 BIGINT       NOT NULL,
+    open_timestamp_ms           BIGINT       NOT NULL,
+    close_timestamp_ms          BIGINT       NOT NULL,
+    PRIMARY KEY (pool_id, interval_secs, bucket_start_ms)
+);
+
+CREATE TABLE IF NOT EXISTS order_book_snapshots
+(
+    pool_id                     TEXT         NOT NULL,
+    timestamp_ms                BIGINT       NOT NULL,
+    depth                       BIGINT       NOT NULL,
+    bids                        JSONB        NOT NULL,
+    asks                        JSONB        NOT NULL,
+    PRIMARY KEY (pool_id, timestamp_ms)
+);
@@ -8,8 +8,9 @@ use serde::Serialize;
 use sui_indexer_builder::{Task, LIVE_TASK_TARGET_CHECKPOINT};
 
 use crate::schema::{
-    balances, balances_summary, flashloans, order_fills, order_updates, pool_prices, pools,
-    progress_store, proposals, rebates, stakes, sui_error_transactions, trade_params_update, votes,
+    balances, balances_summary, flashloans, ohlcv_candles, order_book_snapshots, order_fills,
+    order_updates, pool_prices, pools, progress_store, proposals, rebates, stakes,
+    sui_error_transactions, trade_params_update, votes,
 };
 
 #[derive(Queryable, Selectable, Insertable, Identifiable, Debug)]
@@ -62,6 +63,36 @@ pub struct OrderFill {
     pub onchain_timestamp: i64,
 }
 
+#[derive(Queryable, Selectable, Insertable, Identifiable, Debug)]
+#[diesel(table_name = ohlcv_candles, primary_key(pool_id, interval_secs, bucket_start_ms))]
+pub struct OhlcvCandle {
+    pub pool_id: String,
+    pub interval_secs: i64,
+    pub bucket_start_ms: i64,
+    pub open: i64,
+    pub high: i64,
+    pub low: i64,
+    pub close: i64,
+    pub base_volume: i64,
+    pub quote_volume: i64,
+    pub trade_count: i64,
+    /// Timestamp of the fill that set the open price.
+    pub open_timestamp_ms: i64,
+    /// Timestamp of the fill that set the close price.
+    pub close_timestamp_ms: i64,
+}
+
+#[derive(Queryable, Selectable, Insertable, Identifiable, Debug)]
+#[diesel(table_name = order_book_snapshots, primary_key(pool_id, timestamp_ms))]
+pub struct OrderBookSnapshot {
+    pub pool_id: String,
+    pub timestamp_ms: i64,
+    pub depth: i64,
+    /// Price levels as `[price, quantity]` pairs, best price first.
+    pub bids: serde_json::Value,
+    pub asks: serde_json::Value,
+}
+
 #[derive(Queryable)]
 pub struct OrderFillSummary {
     pub pool_id: String,
@@ -0,0 +1,276 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Level 2 order book data, read from a pool's on-chain state, and a task that periodically
+//! records snapshots of every pool's order book.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use diesel::{QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+use sui_json_rpc_types::{SuiObjectData, SuiObjectDataOptions, SuiObjectResponse};
+use sui_sdk::SuiClient;
+use sui_types::{
+    base_types::{ObjectID, ObjectRef, SuiAddress},
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{Argument, CallArg, Command, ObjectArg, ProgrammableMoveCall, TransactionKind},
+    SUI_CLOCK_OBJECT_ID,
+};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::{
+    config::OrderBookSnapshotConfig,
+    error::DeepBookError,
+    models::{OrderBookSnapshot, Pools},
+    schema,
+    server::{parse_type_input, DEEPBOOK_PACKAGE_ID, LEVEL2_FUNCTION, LEVEL2_MODULE},
+    sui_deepbook_indexer::PgDeepbookPersistent,
+};
+
+/// Price levels on each side of a pool's order book, as `(price, quantity)` pairs in on-chain
+/// units, best price first.
+#[derive(Debug, Default)]
+pub struct Level2 {
+    pub bids: Vec<(u64, u64)>,
+    pub asks: Vec<(u64, u64)>,
+}
+
+/// Read up to `ticks_from_mid` price levels on each side of the order book of the pool at
+/// `pool_id`, trading `base_asset` for `quote_asset`.
+pub async fn level2(
+    sui_client: &SuiClient,
+    pool_id: &str,
+    base_asset: &str,
+    quote_asset: &str,
+    ticks_from_mid: u64,
+) -> Result<Level2, DeepBookError> {
+    let pool_address = ObjectID::from_hex_literal(pool_id)?;
+    let mut ptb = ProgrammableTransactionBuilder::new();
+
+    let pool_object_ref = object_ref(sui_client, pool_address).await?;
+    ptb.input(CallArg::Object(ObjectArg::ImmOrOwnedObject(
+        pool_object_ref,
+    )))?;
+
+    let input_argument = CallArg::Pure(bcs::to_bytes(&ticks_from_mid).map_err(|_| {
+        DeepBookError::InternalError("Failed to serialize ticks_from_mid".to_string())
+    })?);
+    ptb.input(input_argument)?;
+
+    let clock_object_ref = object_ref(sui_client, SUI_CLOCK_OBJECT_ID).await?;
+    ptb.input(CallArg::Object(ObjectArg::ImmOrOwnedObject(
+        clock_object_ref,
+    )))?;
+
+    let package = ObjectID::from_hex_literal(DEEPBOOK_PACKAGE_ID)
+        .map_err(|e| DeepBookError::InternalError(format!("Invalid DeepBook package ID: {}", e)))?;
+
+    ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+        package,
+        module: LEVEL2_MODULE.to_string(),
+        function: LEVEL2_FUNCTION.to_string(),
+        type_arguments: vec![
+            parse_type_input(base_asset)?,
+            parse_type_input(quote_asset)?,
+        ],
+        arguments: vec![Argument::Input(0), Argument::Input(1), Argument::Input(2)],
+    })));
+
+    let tx = TransactionKind::ProgrammableTransaction(ptb.finish());
+    let result = sui_client
+        .read_api()
+        .dev_inspect_transaction_block(SuiAddress::default(), tx, None, None, None)
+        .await?;
+
+    let results = result.results.ok_or(DeepBookError::InternalError(
+        "No results from dev_inspect_transaction_block".to_string(),
+    ))?;
+
+    let return_values = &results
+        .first()
+        .ok_or(DeepBookError::InternalError(
+            "No return values for order book".to_string(),
+        ))?
+        .return_values;
+
+    let levels = |idx: usize, name: &str| -> Result<Vec<u64>, DeepBookError> {
+        let (bytes, _) = return_values
+            .get(idx)
+            .ok_or_else(|| DeepBookError::InternalError(format!("No {name} data found")))?;
+
+        bcs::from_bytes(bytes)
+            .map_err(|_| DeepBookError::InternalError(format!("Failed to deserialize {name}")))
+    };
+
+    let bid_prices = levels(0, "bid prices")?;
+    let bid_quantities = levels(1, "bid quantities")?;
+    let ask_prices = levels(2, "ask prices")?;
+    let ask_quantities = levels(3, "ask quantities")?;
+
+    Ok(Level2::new(
+        (bid_prices, bid_quantities),
+        (ask_prices, ask_quantities),
+        ticks_from_mid,
+    ))
+}
+
+impl Level2 {
+    /// Pair up the prices and quantities on each side of the book, keeping at most `depth` levels
+    /// per side.
+    fn new(
+        (bid_prices, bid_quantities): (Vec<u64>, Vec<u64>),
+        (ask_prices, ask_quantities): (Vec<u64>, Vec<u64>),
+        depth: u64,
+    ) -> Self {
+        let depth = depth as usize;
+        Self {
+            bids: bid_prices
+                .into_iter()
+                .zip(bid_quantities)
+                .take(depth)
+                .collect(),
+            asks: ask_prices
+                .into_iter()
+                .zip(ask_quantities)
+                .take(depth)
+                .collect(),
+        }
+    }
+}
+
+/// Periodically record a snapshot of every pool's order book, `config.depth` price levels deep on
+/// each side.
+pub fn run_order_book_snapshots(
+    config: OrderBookSnapshotConfig,
+    state: PgDeepbookPersistent,
+    sui_client: Arc<SuiClient>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            "Snapshotting order books every {}s, at depth {}",
+            config.interval_secs, config.depth
+        );
+
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = snapshot_order_books(&config, &state, &sui_client).await {
+                warn!("Failed to snapshot order books: {:?}", e);
+            }
+        }
+    })
+}
+
+async fn snapshot_order_books(
+    config: &OrderBookSnapshotConfig,
+    state: &PgDeepbookPersistent,
+    sui_client: &SuiClient,
+) -> Result<(), DeepBookError> {
+    let connection = &mut state.pool.get().await?;
+    let pools: Vec<Pools> = schema::pools::table
+        .select(Pools::as_select())
+        .load(connection)
+        .await?;
+
+    let mut snapshots = vec![];
+    for pool in pools {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| DeepBookError::InternalError("System time error".to_string()))?
+            .as_millis() as i64;
+
+        let level2 = match level2(
+            sui_client,
+            &pool.pool_id,
+            &pool.base_asset_id,
+            &pool.quote_asset_id,
+            config.depth,
+        )
+        .await
+        {
+            Ok(level2) => level2,
+            Err(e) => {
+                warn!("Failed to read order book for {}: {:?}", pool.pool_name, e);
+                continue;
+            }
+        };
+
+        snapshots.push(OrderBookSnapshot {
+            pool_id: pool.pool_id,
+            timestamp_ms,
+            depth: config.depth as i64,
+            bids: serde_json::to_value(level2.bids)?,
+            asks: serde_json::to_value(level2.asks)?,
+        });
+    }
+
+    if snapshots.is_empty() {
+        return Ok(());
+    }
+
+    diesel::insert_into(schema::order_book_snapshots::table)
+        .values(&snapshots)
+        .on_conflict_do_nothing()
+        .execute(connection)
+        .await?;
+
+    Ok(())
+}
+
+async fn object_ref(sui_client: &SuiClient, id: ObjectID) -> Result<ObjectRef, DeepBookError> {
+    let object: SuiObjectResponse = sui_client
+        .read_api()
+        .get_object_with_options(id, SuiObjectDataOptions::full_content())
+        .await?;
+
+    let data: &SuiObjectData = object.data.as_ref().ok_or_else(|| {
+        DeepBookError::InternalError(format!("Missing data in object response for {id}"))
+    })?;
+
+    Ok((data.object_id, data.version, data.digest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level2_truncated_to_depth() {
+        let level2 = Level2::new(
+            (vec![105, 104, 103, 102], vec![1, 2, 3, 4]),
+            (vec![106, 107, 108], vec![5, 6, 7]),
+            2,
+        );
+
+        assert_eq!(level2.bids, vec![(105, 1), (104, 2)]);
+        assert_eq!(level2.asks, vec![(106, 5), (107, 6)]);
+    }
+
+    #[test]
+    fn test_level2_shallow_book() {
+        // A book with fewer levels than requested is returned in full, and a side with fewer
+        // quantities than prices only includes complete levels.
+        let level2 = Level2::new((vec![105, 104], vec![1, 2]), (vec![106, 107], vec![5]), 10);
+
+        assert_eq!(level2.bids, vec![(105, 1), (104, 2)]);
+        assert_eq!(level2.asks, vec![(106, 5)]);
+    }
+
+    #[test]
+    fn test_level2_snapshot_round_trip() {
+        // Snapshots store each side of the book as JSON, which is read back by the server.
+        let level2 = Level2::new((vec![u64::MAX, 1], vec![2, 3]), (vec![], vec![]), 1);
+
+        let bids = serde_json::to_value(&level2.bids).unwrap();
+        let asks = serde_json::to_value(&level2.asks).unwrap();
+        assert_eq!(bids, serde_json::json!([[u64::MAX, 2]]));
+        assert_eq!(asks, serde_json::json!([]));
+
+        let bids: Vec<(u64, u64)> = serde_json::from_value(bids).unwrap();
+        assert_eq!(bids, level2.bids);
+    }
+}
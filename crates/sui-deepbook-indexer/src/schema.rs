@@ -34,6 +34,33 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    ohlcv_candles (pool_id, interval_secs, bucket_start_ms) {
+        pool_id -> Text,
+        interval_secs -> Int8,
+        bucket_start_ms -> Int8,
+        open -> Int8,
+        high -> Int8,
+        low -> Int8,
+        close -> Int8,
+        base_volume -> Int8,
+        quote_volume -> Int8,
+        trade_count -> Int8,
+        open_timestamp_ms -> Int8,
+        close_timestamp_ms -> Int8,
+    }
+}
+
+diesel::table! {
+    order_book_snapshots (pool_id, timestamp_ms) {
+        pool_id -> Text,
+        timestamp_ms -> Int8,
+        depth -> Int8,
+        bids -> Jsonb,
+        asks -> Jsonb,
+    }
+}
+
 diesel::table! {
     order_fills (event_digest) {
         event_digest -> Text,
@@ -240,6 +267,8 @@ diesel::table! {
 diesel::allow_tables_to_appear_in_same_query!(
     balances,
     flashloans,
+    ohlcv_candles,
+    order_book_snapshots,
     order_fills,
     order_updates,
     pool_prices,
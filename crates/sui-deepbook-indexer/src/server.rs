@@ -3,9 +3,11 @@
 
 use crate::{
     error::DeepBookError,
-    models::{BalancesSummary, OrderFillSummary, Pools},
+    models::{BalancesSummary, OhlcvCandle, OrderBookSnapshot, OrderFillSummary, Pools},
+    order_book,
     schema::{self},
     sui_deepbook_indexer::PgDeepbookPersistent,
+    trade_stream::trade_stream,
 };
 use axum::http::Method;
 use axum::{
@@ -19,7 +21,7 @@ use diesel::dsl::{max, min};
 use diesel::BoolExpressionMethods;
 use diesel::QueryDsl;
 use diesel::{ExpressionMethods, SelectableHelper};
-use diesel_async::RunQueryDsl;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
 use serde_json::Value;
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{collections::HashMap, net::SocketAddr};
@@ -50,6 +52,7 @@ pub const ALL_HISTORICAL_VOLUME_PATH: &str = "/all_historical_volume";
 pub const GET_NET_DEPOSITS: &str = "/get_net_deposits/:asset_ids/:timestamp";
 pub const TICKER_PATH: &str = "/ticker";
 pub const TRADES_PATH: &str = "/trades/:pool_name";
+pub const TRADE_STREAM_PATH: &str = "/trades/:pool_name/stream";
 pub const ORDER_UPDATES_PATH: &str = "/order_updates/:pool_name";
 pub const TRADE_COUNT_PATH: &str = "/trade_count";
 pub const ASSETS_PATH: &str = "/assets";
@@ -66,6 +69,11 @@ pub const DEEP_TREASURY_ID: &str =
 pub const DEEP_SUPPLY_MODULE: &str = "deep";
 pub const DEEP_SUPPLY_FUNCTION: &str = "total_supply";
 pub const DEEP_SUPPLY_PATH: &str = "/deep_supply";
+pub const OHLCV_PATH: &str = "/ohlcv/:pool_name";
+pub const ORDERBOOK_SNAPSHOTS_PATH: &str = "/orderbook_snapshots/:pool_name";
+
+/// Maximum number of candles or order book snapshots returned by a single query.
+const MAX_MARKET_DATA_LIMIT: i64 = 1000;
 
 pub fn run_server(socket_address: SocketAddr, state: PgDeepbookPersistent) -> JoinHandle<()> {
     tokio::spawn(async move {
@@ -97,11 +105,14 @@ pub(crate) fn make_router(state: PgDeepbookPersistent) -> Router {
         .route(GET_NET_DEPOSITS, get(get_net_deposits))
         .route(TICKER_PATH, get(ticker))
         .route(TRADES_PATH, get(trades))
+        .route(TRADE_STREAM_PATH, get(trade_stream))
         .route(TRADE_COUNT_PATH, get(trade_count))
         .route(ORDER_UPDATES_PATH, get(order_updates))
         .route(ASSETS_PATH, get(assets))
         .route(SUMMARY_PATH, get(summary))
         .route(DEEP_SUPPLY_PATH, get(deep_supply))
+        .route(OHLCV_PATH, get(ohlcv))
+        .route(ORDERBOOK_SNAPSHOTS_PATH, get(orderbook_snapshots))
         .layer(cors)
         .with_state(state)
 }
@@ -1088,7 +1099,7 @@ async fn trade_count(
     Ok(Json(result))
 }
 
-fn calculate_trade_id(maker_id: &str, taker_id: &str) -> Result<u128, DeepBookError> {
+pub(crate) fn calculate_trade_id(maker_id: &str, taker_id: &str) -> Result<u128, DeepBookError> {
     // Parse maker_id and taker_id as u128
     let maker_id = maker_id
         .parse::<u128>()
@@ -1218,181 +1229,225 @@ async fn orderbook(
     let base_decimals = base_decimals as u8;
     let quote_decimals = quote_decimals as u8;
 
-    let pool_address = ObjectID::from_hex_literal(&pool_id)?;
-
     let sui_client = SuiClientBuilder::default().build(SUI_MAINNET_URL).await?;
-    let mut ptb = ProgrammableTransactionBuilder::new();
+    let level2 = order_book::level2(
+        &sui_client,
+        &pool_id,
+        &base_asset_id,
+        &quote_asset_id,
+        ticks_from_mid,
+    )
+    .await?;
 
-    let pool_object: SuiObjectResponse = sui_client
-        .read_api()
-        .get_object_with_options(pool_address, SuiObjectDataOptions::full_content())
-        .await?;
-    let pool_data: &SuiObjectData =
-        pool_object
-            .data
-            .as_ref()
-            .ok_or(DeepBookError::InternalError(format!(
-                "Missing data in pool object response for '{}'",
-                pool_name
-            )))?;
-    let pool_object_ref: ObjectRef = (pool_data.object_id, pool_data.version, pool_data.digest);
-
-    let pool_input = CallArg::Object(ObjectArg::ImmOrOwnedObject(pool_object_ref));
-    ptb.input(pool_input)?;
-
-    let input_argument = CallArg::Pure(bcs::to_bytes(&ticks_from_mid).map_err(|_| {
-        DeepBookError::InternalError("Failed to serialize ticks_from_mid".to_string())
-    })?);
-    ptb.input(input_argument)?;
+    let mut result = HashMap::new();
 
-    let sui_clock_object_id = ObjectID::from_hex_literal(
-        "0x0000000000000000000000000000000000000000000000000000000000000006",
-    )?;
-    let sui_clock_object: SuiObjectResponse = sui_client
-        .read_api()
-        .get_object_with_options(sui_clock_object_id, SuiObjectDataOptions::full_content())
-        .await?;
-    let clock_data: &SuiObjectData =
-        sui_clock_object
-            .data
-            .as_ref()
-            .ok_or(DeepBookError::InternalError(
-                "Missing data in clock object response".to_string(),
-            ))?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| DeepBookError::InternalError("System time error".to_string()))?
+        .as_millis() as i64;
+    result.insert("timestamp".to_string(), Value::from(timestamp.to_string()));
+    result.insert(
+        "bids".to_string(),
+        levels_to_json(&level2.bids, base_decimals, quote_decimals),
+    );
+    result.insert(
+        "asks".to_string(),
+        levels_to_json(&level2.asks, base_decimals, quote_decimals),
+    );
 
-    let sui_clock_object_ref: ObjectRef =
-        (clock_data.object_id, clock_data.version, clock_data.digest);
+    Ok(Json(result))
+}
 
-    let clock_input = CallArg::Object(ObjectArg::ImmOrOwnedObject(sui_clock_object_ref));
-    ptb.input(clock_input)?;
+/// Format `(price, quantity)` levels as `[price, quantity]` pairs of decimal strings.
+fn levels_to_json(levels: &[(u64, u64)], base_decimals: u8, quote_decimals: u8) -> Value {
+    let price_factor = 10u64.pow((9 - base_decimals + quote_decimals).into());
+    let quantity_factor = 10u64.pow((base_decimals).into());
+    Value::Array(
+        levels
+            .iter()
+            .map(|(price, quantity)| {
+                Value::Array(vec![
+                    Value::from((*price as f64 / price_factor as f64).to_string()),
+                    Value::from((*quantity as f64 / quantity_factor as f64).to_string()),
+                ])
+            })
+            .collect(),
+    )
+}
 
-    let base_coin_type = parse_type_input(&base_asset_id)?;
-    let quote_coin_type = parse_type_input(&quote_asset_id)?;
+/// OHLCV candles for a pool, at one of the intervals the indexer aggregates candles over.
+async fn ohlcv(
+    Path(pool_name): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+    State(state): State<PgDeepbookPersistent>,
+) -> Result<Json<Vec<HashMap<String, Value>>>, DeepBookError> {
+    let interval_secs = params
+        .get("interval")
+        .map(|v| v.parse::<u64>())
+        .transpose()
+        .map_err(|_| {
+            DeepBookError::InternalError("Interval must be a number of seconds".to_string())
+        })?
+        .unwrap_or(3600);
 
-    let package = ObjectID::from_hex_literal(DEEPBOOK_PACKAGE_ID)
-        .map_err(|e| DeepBookError::InternalError(format!("Invalid pool ID: {}", e)))?;
-    let module = LEVEL2_MODULE.to_string();
-    let function = LEVEL2_FUNCTION.to_string();
+    if !state.candle_intervals_secs.contains(&interval_secs) {
+        return Err(DeepBookError::InternalError(format!(
+            "Interval must be one of {:?}",
+            state.candle_intervals_secs
+        )));
+    }
 
-    ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
-        package,
-        module,
-        function,
-        type_arguments: vec![base_coin_type, quote_coin_type],
-        arguments: vec![Argument::Input(0), Argument::Input(1), Argument::Input(2)],
-    })));
+    let (start_time, end_time, limit) = market_data_range(&params, 100)?;
 
-    let builder = ptb.finish();
-    let tx = TransactionKind::ProgrammableTransaction(builder);
+    let connection = &mut state.pool.get().await?;
+    let (pool_id, base_decimals, quote_decimals) = pool_decimals(connection, &pool_name).await?;
 
-    let result = sui_client
-        .read_api()
-        .dev_inspect_transaction_block(SuiAddress::default(), tx, None, None, None)
+    let candles = schema::ohlcv_candles::table
+        .filter(schema::ohlcv_candles::pool_id.eq(pool_id))
+        .filter(schema::ohlcv_candles::interval_secs.eq(interval_secs as i64))
+        .filter(schema::ohlcv_candles::bucket_start_ms.between(start_time, end_time))
+        .order_by(schema::ohlcv_candles::bucket_start_ms.desc())
+        .limit(limit)
+        .select(OhlcvCandle::as_select())
+        .load(connection)
         .await?;
 
-    let mut binding = result.results.ok_or(DeepBookError::InternalError(
-        "No results from dev_inspect_transaction_block".to_string(),
-    ))?;
-    let bid_prices = &binding
-        .first_mut()
-        .ok_or(DeepBookError::InternalError(
-            "No return values for bid prices".to_string(),
-        ))?
-        .return_values
-        .first_mut()
-        .ok_or(DeepBookError::InternalError(
-            "No bid price data found".to_string(),
-        ))?
-        .0;
-    let bid_parsed_prices: Vec<u64> = bcs::from_bytes(bid_prices).map_err(|_| {
-        DeepBookError::InternalError("Failed to deserialize bid prices".to_string())
-    })?;
-    let bid_quantities = &binding
-        .first_mut()
-        .ok_or(DeepBookError::InternalError(
-            "No return values for bid quantities".to_string(),
-        ))?
-        .return_values
-        .get(1)
-        .ok_or(DeepBookError::InternalError(
-            "No bid quantity data found".to_string(),
-        ))?
-        .0;
-    let bid_parsed_quantities: Vec<u64> = bcs::from_bytes(bid_quantities).map_err(|_| {
-        DeepBookError::InternalError("Failed to deserialize bid quantities".to_string())
-    })?;
+    let base_factor = 10u64.pow(base_decimals as u32) as f64;
+    let quote_factor = 10u64.pow(quote_decimals as u32) as f64;
+    let price_factor = 10u64.pow((9 - base_decimals + quote_decimals) as u32) as f64;
 
-    let ask_prices = &binding
-        .first_mut()
-        .ok_or(DeepBookError::InternalError(
-            "No return values for ask prices".to_string(),
-        ))?
-        .return_values
-        .get(2)
-        .ok_or(DeepBookError::InternalError(
-            "No ask price data found".to_string(),
-        ))?
-        .0;
-    let ask_parsed_prices: Vec<u64> = bcs::from_bytes(ask_prices).map_err(|_| {
-        DeepBookError::InternalError("Failed to deserialize ask prices".to_string())
-    })?;
-    let ask_quantities = &binding
-        .first_mut()
-        .ok_or(DeepBookError::InternalError(
-            "No return values for ask quantities".to_string(),
-        ))?
-        .return_values
-        .get(3)
-        .ok_or(DeepBookError::InternalError(
-            "No ask quantity data found".to_string(),
-        ))?
-        .0;
-    let ask_parsed_quantities: Vec<u64> = bcs::from_bytes(ask_quantities).map_err(|_| {
-        DeepBookError::InternalError("Failed to deserialize ask quantities".to_string())
-    })?;
+    Ok(Json(
+        candles
+            .into_iter()
+            .map(|c| {
+                HashMap::from([
+                    ("timestamp".to_string(), Value::from(c.bucket_start_ms)),
+                    (
+                        "open".to_string(),
+                        Value::from(c.open as f64 / price_factor),
+                    ),
+                    (
+                        "high".to_string(),
+                        Value::from(c.high as f64 / price_factor),
+                    ),
+                    ("low".to_string(), Value::from(c.low as f64 / price_factor)),
+                    (
+                        "close".to_string(),
+                        Value::from(c.close as f64 / price_factor),
+                    ),
+                    (
+                        "base_volume".to_string(),
+                        Value::from(c.base_volume as f64 / base_factor),
+                    ),
+                    (
+                        "quote_volume".to_string(),
+                        Value::from(c.quote_volume as f64 / quote_factor),
+                    ),
+                    ("trade_count".to_string(), Value::from(c.trade_count)),
+                ])
+            })
+            .collect(),
+    ))
+}
 
-    let mut result = HashMap::new();
+/// Snapshots of a pool's order book, recorded by the indexer, latest first.
+async fn orderbook_snapshots(
+    Path(pool_name): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+    State(state): State<PgDeepbookPersistent>,
+) -> Result<Json<Vec<HashMap<String, Value>>>, DeepBookError> {
+    let (start_time, end_time, limit) = market_data_range(&params, 1)?;
 
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|_| DeepBookError::InternalError("System time error".to_string()))?
-        .as_millis() as i64;
-    result.insert("timestamp".to_string(), Value::from(timestamp.to_string()));
+    let connection = &mut state.pool.get().await?;
+    let (pool_id, base_decimals, quote_decimals) = pool_decimals(connection, &pool_name).await?;
 
-    let bids: Vec<Value> = bid_parsed_prices
-        .into_iter()
-        .zip(bid_parsed_quantities.into_iter())
-        .take(ticks_from_mid as usize)
-        .map(|(price, quantity)| {
-            let price_factor = 10u64.pow((9 - base_decimals + quote_decimals).into());
-            let quantity_factor = 10u64.pow((base_decimals).into());
-            Value::Array(vec![
-                Value::from((price as f64 / price_factor as f64).to_string()),
-                Value::from((quantity as f64 / quantity_factor as f64).to_string()),
-            ])
-        })
-        .collect();
-    result.insert("bids".to_string(), Value::Array(bids));
+    let snapshots = schema::order_book_snapshots::table
+        .filter(schema::order_book_snapshots::pool_id.eq(pool_id))
+        .filter(schema::order_book_snapshots::timestamp_ms.between(start_time, end_time))
+        .order_by(schema::order_book_snapshots::timestamp_ms.desc())
+        .limit(limit)
+        .select(OrderBookSnapshot::as_select())
+        .load(connection)
+        .await?;
 
-    let asks: Vec<Value> = ask_parsed_prices
+    snapshots
         .into_iter()
-        .zip(ask_parsed_quantities.into_iter())
-        .take(ticks_from_mid as usize)
-        .map(|(price, quantity)| {
-            let price_factor = 10u64.pow((9 - base_decimals + quote_decimals).into());
-            let quantity_factor = 10u64.pow((base_decimals).into());
-            Value::Array(vec![
-                Value::from((price as f64 / price_factor as f64).to_string()),
-                Value::from((quantity as f64 / quantity_factor as f64).to_string()),
-            ])
+        .map(|snapshot| {
+            let bids: Vec<(u64, u64)> = serde_json::from_value(snapshot.bids)?;
+            let asks: Vec<(u64, u64)> = serde_json::from_value(snapshot.asks)?;
+            Ok(HashMap::from([
+                ("timestamp".to_string(), Value::from(snapshot.timestamp_ms)),
+                ("depth".to_string(), Value::from(snapshot.depth)),
+                (
+                    "bids".to_string(),
+                    levels_to_json(&bids, base_decimals, quote_decimals),
+                ),
+                (
+                    "asks".to_string(),
+                    levels_to_json(&asks, base_decimals, quote_decimals),
+                ),
+            ]))
         })
-        .collect();
-    result.insert("asks".to_string(), Value::Array(asks));
+        .collect::<Result<_, DeepBookError>>()
+        .map(Json)
+}
 
-    Ok(Json(result))
+/// Parse the `start_time` and `end_time` (in seconds) and `limit` query parameters for market
+/// data, returning the time range in milliseconds. The range defaults to the last 24 hours.
+fn market_data_range(
+    params: &HashMap<String, String>,
+    default_limit: i64,
+) -> Result<(i64, i64, i64), DeepBookError> {
+    let end_time = params
+        .get("end_time")
+        .and_then(|v| v.parse::<i64>().ok())
+        .map(|t| t * 1000) // Convert to milliseconds
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as i64
+        });
+
+    let start_time = params
+        .get("start_time")
+        .and_then(|v| v.parse::<i64>().ok())
+        .map(|t| t * 1000) // Convert to milliseconds
+        .unwrap_or_else(|| end_time - 24 * 60 * 60 * 1000);
+
+    let limit = params
+        .get("limit")
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(default_limit);
+
+    if !(1..=MAX_MARKET_DATA_LIMIT).contains(&limit) {
+        return Err(DeepBookError::InternalError(format!(
+            "Limit must be between 1 and {MAX_MARKET_DATA_LIMIT}"
+        )));
+    }
+
+    Ok((start_time, end_time, limit))
+}
+
+/// Look up the ID of the pool called `pool_name`, and the decimals of its base and quote assets.
+pub(crate) async fn pool_decimals(
+    connection: &mut AsyncPgConnection,
+    pool_name: &str,
+) -> Result<(String, u8, u8), DeepBookError> {
+    let (pool_id, base_decimals, quote_decimals) = schema::pools::table
+        .filter(schema::pools::pool_name.eq(pool_name))
+        .select((
+            schema::pools::pool_id,
+            schema::pools::base_asset_decimals,
+            schema::pools::quote_asset_decimals,
+        ))
+        .first::<(String, i16, i16)>(connection)
+        .await
+        .map_err(|_| DeepBookError::InternalError(format!("Pool '{}' not found", pool_name)))?;
+
+    Ok((pool_id, base_decimals as u8, quote_decimals as u8))
 }
 
-/// DEEP total supply
 async fn deep_supply() -> Result<Json<u64>, DeepBookError> {
     let sui_client = SuiClientBuilder::default().build(SUI_MAINNET_URL).await?;
     let mut ptb = ProgrammableTransactionBuilder::new();
@@ -1506,7 +1561,49 @@ async fn get_net_deposits(
     Ok(Json(net_deposits))
 }
 
-fn parse_type_input(type_str: &str) -> Result<TypeInput, DeepBookError> {
+pub(crate) fn parse_type_input(type_str: &str) -> Result<TypeInput, DeepBookError> {
     let type_tag = TypeTag::from_str(type_str)?;
     Ok(TypeInput::from(type_tag))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_levels_to_json() {
+        // With 6 base decimals and 6 quote decimals, prices are scaled by 10^9 and quantities by
+        // 10^6.
+        let levels = levels_to_json(
+            &[(1_500_000_000, 2_000_000), (1_000_000_000, 500_000)],
+            6,
+            6,
+        );
+        assert_eq!(levels, serde_json::json!([["1.5", "2"], ["1", "0.5"]]),);
+    }
+
+    #[test]
+    fn test_market_data_range() {
+        let (start, end, limit) =
+            market_data_range(&params(&[("start_time", "60"), ("end_time", "120")]), 5).unwrap();
+        assert_eq!((start, end, limit), (60_000, 120_000, 5));
+
+        // The range defaults to the 24 hours before the end time.
+        let (start, end, _) = market_data_range(&params(&[("end_time", "86400")]), 1).unwrap();
+        assert_eq!((start, end), (0, 86_400_000));
+
+        let limit = MAX_MARKET_DATA_LIMIT.to_string();
+        assert!(market_data_range(&params(&[("limit", &limit)]), 1).is_ok());
+
+        let limit = (MAX_MARKET_DATA_LIMIT + 1).to_string();
+        assert!(market_data_range(&params(&[("limit", &limit)]), 1).is_err());
+        assert!(market_data_range(&params(&[("limit", "0")]), 1).is_err());
+    }
+}
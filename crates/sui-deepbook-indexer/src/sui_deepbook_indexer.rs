@@ -9,6 +9,7 @@ use diesel::{OptionalExtension, QueryDsl, SelectableHelper};
 use diesel_async::scoped_futures::ScopedFutureExt;
 use diesel_async::AsyncConnection;
 use diesel_async::RunQueryDsl;
+use std::sync::Arc;
 use sui_indexer_builder::progress::ProgressSavingPolicy;
 use sui_types::base_types::ObjectID;
 use sui_types::transaction::{Command, TransactionDataAPI};
@@ -34,24 +35,35 @@ use crate::schema::{
     balances, flashloans, order_fills, order_updates, pool_prices, proposals, rebates, stakes,
     sui_error_transactions, trade_params_update, votes,
 };
+use crate::trade_stream::TradePublisher;
 use crate::types::{
     Balances, Flashloan, OrderFill, OrderUpdate, OrderUpdateStatus, PoolPrice, ProcessedTxnData,
     Proposals, Rebates, Stakes, SuiTxnError, TradeParamsUpdate, Votes,
 };
-use crate::{models, schema};
+use crate::{candles, models, schema};
 
 /// Persistent layer impl
 #[derive(Clone)]
 pub struct PgDeepbookPersistent {
     pub pool: PgPool,
     save_progress_policy: ProgressSavingPolicy,
+    /// Lengths of the intervals, in seconds, that OHLCV candles are aggregated over.
+    pub(crate) candle_intervals_secs: Arc<[u64]>,
+    /// Fills are published here as they are indexed, for the trade stream.
+    pub(crate) trades: TradePublisher,
 }
 
 impl PgDeepbookPersistent {
-    pub fn new(pool: PgPool, save_progress_policy: ProgressSavingPolicy) -> Self {
+    pub fn new(
+        pool: PgPool,
+        save_progress_policy: ProgressSavingPolicy,
+        candle_intervals_secs: Vec<u64>,
+    ) -> Self {
         Self {
             pool,
             save_progress_policy,
+            candle_intervals_secs: candle_intervals_secs.into(),
+            trades: TradePublisher::new(),
         }
     }
 
@@ -111,8 +123,9 @@ impl Persistent<ProcessedTxnData> for PgDeepbookPersistent {
             }
         }
 
+        let candle_intervals_secs = self.candle_intervals_secs.clone();
         let connection = &mut self.pool.get().await?;
-        connection
+        let inserted_fills = connection
             .transaction(|conn| {
                 async move {
                     // Create async tasks for each batch insert
@@ -126,14 +139,6 @@ impl Persistent<ProcessedTxnData> for PgDeepbookPersistent {
                                 .execute(conn),
                         );
                     }
-                    if !order_fills_batch.is_empty() {
-                        tasks.push(
-                            diesel::insert_into(order_fills::table)
-                                .values(&order_fills_batch)
-                                .on_conflict_do_nothing()
-                                .execute(conn),
-                        );
-                    }
                     if !flashloans_batch.is_empty() {
                         tasks.push(
                             diesel::insert_into(flashloans::table)
@@ -210,11 +215,31 @@ impl Persistent<ProcessedTxnData> for PgDeepbookPersistent {
                     // Execute all tasks concurrently
                     let _: Vec<_> = future::try_join_all(tasks).await?;
 
-                    Ok(())
+                    // Only fills that were not already indexed contribute to candles and the
+                    // trade stream, so that they are not double counted when checkpoints are
+                    // re-processed.
+                    let mut inserted: Vec<models::OrderFill> = vec![];
+                    if !order_fills_batch.is_empty() {
+                        inserted = diesel::insert_into(order_fills::table)
+                            .values(&order_fills_batch)
+                            .on_conflict_do_nothing()
+                            .returning(models::OrderFill::as_returning())
+                            .get_results(conn)
+                            .await?;
+
+                        let candles = candles::aggregate(&inserted, &candle_intervals_secs);
+                        candles::upsert(conn, &candles).await?;
+                    }
+
+                    Ok::<_, Error>(inserted)
                 }
                 .scope_boxed()
             })
-            .await
+            .await?;
+
+        // Fills are only published once they have been committed.
+        self.trades.publish(inserted_fills);
+        Ok(())
     }
 }
 
@@ -0,0 +1,309 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A live stream of a pool's fills, published by the indexer as it writes them, and served to
+//! clients as server-sent events.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::Stream;
+use serde_json::json;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+use crate::{
+    error::DeepBookError,
+    models::OrderFill,
+    server::{calculate_trade_id, pool_decimals},
+    sui_deepbook_indexer::PgDeepbookPersistent,
+};
+
+/// Number of fills buffered for each subscriber, before a slow subscriber starts to lag.
+const TRADE_STREAM_CAPACITY: usize = 4096;
+
+/// Publishes fills to every subscribed trade stream, as they are indexed.
+#[derive(Clone)]
+pub struct TradePublisher {
+    sender: broadcast::Sender<Arc<OrderFill>>,
+}
+
+impl TradePublisher {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(TRADE_STREAM_CAPACITY);
+        Self { sender }
+    }
+
+    /// Publish fills that have been committed to the database. Fills are dropped if there are no
+    /// subscribers.
+    pub fn publish(&self, fills: Vec<OrderFill>) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+
+        for fill in fills {
+            // Sending only fails if every subscriber has disconnected in the meantime.
+            let _ = self.sender.send(Arc::new(fill));
+        }
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Arc<OrderFill>> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for TradePublisher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stream fills in the pool named `pool_name`, as they are indexed. Each fill is sent as a `trade`
+/// event, identified by the digest of the event that emitted it. Fills are sent in the order they
+/// are indexed, which need not be the order they happened in (e.g. while the indexer is
+/// backfilling), so clients should rely on the timestamp and checkpoint included in each event.
+///
+/// If the client falls too far behind, fills are skipped and a `lagged` event is sent with the
+/// number of fills that were missed, which can be recovered from the trades endpoint.
+pub(crate) async fn trade_stream(
+    Path(pool_name): Path<String>,
+    State(state): State<PgDeepbookPersistent>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, DeepBookError> {
+    let connection = &mut state.pool.get().await?;
+    let (pool_id, base_decimals, quote_decimals) = pool_decimals(connection, &pool_name).await?;
+
+    let base_factor = 10u64.pow(base_decimals as u32) as f64;
+    let quote_factor = 10u64.pow(quote_decimals as u32) as f64;
+    let price_factor = 10u64.pow((9 - base_decimals + quote_decimals) as u32) as f64;
+
+    let mut trades = state.trades.subscribe();
+    let stream = async_stream::stream! {
+        loop {
+            let fill = match trades.recv().await {
+                Ok(fill) => fill,
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("Trade stream for {pool_name} lagged by {missed} fills");
+                    yield Ok(Event::default().event("lagged").data(missed.to_string()));
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            if fill.pool_id != pool_id {
+                continue;
+            }
+
+            let trade_id =
+                calculate_trade_id(&fill.maker_order_id, &fill.taker_order_id).unwrap_or(0);
+            let trade_type = if fill.taker_is_bid { "buy" } else { "sell" };
+
+            let data = json!({
+                "trade_id": trade_id.to_string(),
+                "maker_order_id": fill.maker_order_id,
+                "taker_order_id": fill.taker_order_id,
+                "maker_balance_manager_id": fill.maker_balance_manager_id,
+                "taker_balance_manager_id": fill.taker_balance_manager_id,
+                "price": fill.price as f64 / price_factor,
+                "base_volume": fill.base_quantity as f64 / base_factor,
+                "quote_volume": fill.quote_quantity as f64 / quote_factor,
+                "timestamp": fill.checkpoint_timestamp_ms as u64,
+                "checkpoint": fill.checkpoint as u64,
+                "type": trade_type,
+            });
+
+            yield Ok(Event::default()
+                .id(fill.event_digest.clone())
+                .event("trade")
+                .data(data.to_string()));
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::response::IntoResponse;
+    use diesel::ExpressionMethods;
+    use diesel_async::RunQueryDsl;
+    use diesel_migrations::{embed_migrations, EmbeddedMigrations};
+    use futures::StreamExt;
+    use sui_indexer_builder::indexer_builder::Persistent;
+    use sui_indexer_builder::progress::{OutOfOrderSaveAfterDurationPolicy, ProgressSavingPolicy};
+    use sui_pg_db::{temp::TempDb, Db, DbArgs};
+
+    use super::*;
+    use crate::postgres_manager::get_connection_pool;
+    use crate::schema::pools;
+    use crate::types::{self, ProcessedTxnData};
+
+    const MIGRATIONS: EmbeddedMigrations = embed_migrations!("src/migrations");
+
+    const POOL_ID: &str = "0x1";
+    const OTHER_POOL_ID: &str = "0x2";
+
+    fn fill(pool_id: &str, event_digest: &str, taker_is_bid: bool) -> ProcessedTxnData {
+        ProcessedTxnData::OrderFill(types::OrderFill {
+            digest: "digest".to_string(),
+            event_digest: event_digest.to_string(),
+            sender: "0xa".to_string(),
+            checkpoint: 10,
+            checkpoint_timestamp_ms: 1_000,
+            package: "0xdee9".to_string(),
+            pool_id: pool_id.to_string(),
+            maker_order_id: 1,
+            taker_order_id: 2,
+            maker_client_order_id: 0,
+            taker_client_order_id: 0,
+            price: 2_000_000,
+            taker_is_bid,
+            taker_fee: 0,
+            taker_fee_is_deep: false,
+            maker_fee: 0,
+            maker_fee_is_deep: false,
+            base_quantity: 3_000_000_000,
+            quote_quantity: 6_000_000,
+            maker_balance_manager_id: "0xb".to_string(),
+            taker_balance_manager_id: "0xc".to_string(),
+            onchain_timestamp: 1_000,
+        })
+    }
+
+    /// Set up an indexer database with two pools, where `pool_name` has 9 base and 6 quote
+    /// decimals.
+    async fn persistent(db: &TempDb) -> PgDeepbookPersistent {
+        let url = db.database().url();
+        Db::for_write(DbArgs::new_for_testing(url.clone()))
+            .await
+            .unwrap()
+            .run_migrations(MIGRATIONS)
+            .await
+            .unwrap();
+
+        let state = PgDeepbookPersistent::new(
+            get_connection_pool(url.to_string()).await,
+            ProgressSavingPolicy::OutOfOrderSaveAfterDuration(
+                OutOfOrderSaveAfterDurationPolicy::new(Duration::from_secs(30)),
+            ),
+            vec![60],
+        );
+
+        let conn = &mut state.pool.get().await.unwrap();
+        for (pool_id, pool_name) in [(POOL_ID, "SUI_USDC"), (OTHER_POOL_ID, "DEEP_USDC")] {
+            diesel::insert_into(pools::table)
+                .values((
+                    pools::pool_id.eq(pool_id),
+                    pools::pool_name.eq(pool_name),
+                    pools::base_asset_id.eq("0x3"),
+                    pools::base_asset_decimals.eq(9i16),
+                    pools::base_asset_symbol.eq("BASE"),
+                    pools::base_asset_name.eq("Base"),
+                    pools::quote_asset_id.eq("0x4"),
+                    pools::quote_asset_decimals.eq(6i16),
+                    pools::quote_asset_symbol.eq("USDC"),
+                    pools::quote_asset_name.eq("USDC"),
+                    pools::min_size.eq(1),
+                    pools::lot_size.eq(1),
+                    pools::tick_size.eq(1),
+                ))
+                .execute(conn)
+                .await
+                .unwrap();
+        }
+
+        state
+    }
+
+    /// Read the next event off an SSE response body, as text.
+    async fn next_event(
+        body: &mut (impl futures::Stream<Item = Result<axum::body::Bytes, axum::Error>> + Unpin),
+    ) -> String {
+        let chunk = tokio::time::timeout(Duration::from_secs(10), body.next())
+            .await
+            .expect("Timed out waiting for an event")
+            .expect("Stream ended")
+            .unwrap();
+        String::from_utf8(chunk.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_receives_trade_after_commit() {
+        let db = TempDb::new().unwrap();
+        let state = persistent(&db).await;
+
+        let sse = trade_stream(Path("SUI_USDC".to_string()), State(state.clone()))
+            .await
+            .unwrap();
+        let mut body = sse.into_response().into_body().into_data_stream();
+
+        // Fills in other pools are not sent to this stream.
+        state
+            .write(vec![
+                fill(OTHER_POOL_ID, "other", true),
+                fill(POOL_ID, "e1", true),
+            ])
+            .await
+            .unwrap();
+
+        let event = next_event(&mut body).await;
+        assert!(event.contains("event: trade\n"), "{event}");
+        assert!(event.contains("id: e1\n"), "{event}");
+
+        let data = event
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let data: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(data["trade_id"], "3");
+        assert_eq!(data["type"], "buy");
+        assert_eq!(data["price"], 2.0);
+        assert_eq!(data["base_volume"], 3.0);
+        assert_eq!(data["quote_volume"], 6.0);
+        assert_eq!(data["checkpoint"], 10);
+        assert_eq!(data["timestamp"], 1_000);
+
+        // Re-indexing a fill does not send it again: The next event is for the new fill.
+        state
+            .write(vec![fill(POOL_ID, "e1", true), fill(POOL_ID, "e2", false)])
+            .await
+            .unwrap();
+
+        let event = next_event(&mut body).await;
+        assert!(event.contains("id: e2\n"), "{event}");
+        assert!(event.contains(r#""type":"sell""#), "{event}");
+    }
+
+    #[tokio::test]
+    async fn test_trade_stream_unknown_pool() {
+        let db = TempDb::new().unwrap();
+        let state = persistent(&db).await;
+
+        let result = trade_stream(Path("UNKNOWN".to_string()), State(state)).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_publish_without_subscribers() {
+        let publisher = TradePublisher::new();
+        let ProcessedTxnData::OrderFill(fill) = fill(POOL_ID, "e1", true) else {
+            unreachable!();
+        };
+
+        // Fills published before anyone subscribes are dropped, rather than buffered.
+        publisher.publish(vec![fill.to_db()]);
+        let mut trades = publisher.subscribe();
+        assert!(matches!(
+            trades.try_recv(),
+            Err(broadcast::error::TryRecvError::Empty)
+        ));
+
+        publisher.publish(vec![fill.to_db()]);
+        assert_eq!(trades.try_recv().unwrap().event_digest, "e1");
+    }
+}
@@ -78,6 +78,22 @@ async fn basic_reconfig_end_to_end_test() {
     test_cluster.trigger_reconfiguration().await;
 }
 
+#[sim_test]
+async fn advance_clock_reconfig_test() {
+    let test_cluster = TestClusterBuilder::new()
+        .with_epoch_duration_ms(60 * 60 * 1000)
+        .build()
+        .await;
+
+    // Moving the clock past the end of the epoch triggers the epoch change, without waiting for
+    // the epoch duration to pass.
+    test_cluster.advance_clock(Duration::from_secs(60 * 60));
+    let system_state = test_cluster
+        .wait_for_epoch_with_timeout(Some(1), Duration::from_secs(30))
+        .await;
+    assert_eq!(system_state.epoch(), 1);
+}
+
 #[sim_test]
 async fn test_transaction_expiration() {
     let test_cluster = TestClusterBuilder::new().build().await;
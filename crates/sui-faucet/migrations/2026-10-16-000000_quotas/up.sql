@@ -0,0 +1,10 @@
+-- Number of requests each identity has made in its current quota window.
+CREATE TABLE IF NOT EXISTS quotas
+(
+    identity                    TEXT         PRIMARY KEY,
+    window_start_ms             BIGINT       NOT NULL,
+    requests_used               BIGINT       NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS quotas_window_start_ms
+ON quotas (window_start_ms);
@@ -0,0 +1,21 @@
+-- Every coin sent by the faucet, for auditing its usage.
+CREATE TABLE IF NOT EXISTS grants
+(
+    id                          BIGSERIAL    PRIMARY KEY,
+    recipient                   BYTEA        NOT NULL,
+    amount                      BIGINT       NOT NULL,
+    timestamp_ms                BIGINT       NOT NULL,
+    -- The identity the request was counted against, if it was authenticated.
+    identity                    TEXT,
+    coin_id                     BYTEA        NOT NULL,
+    tx_digest                   BYTEA        NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS grants_timestamp_ms
+ON grants (timestamp_ms);
+
+CREATE INDEX IF NOT EXISTS grants_recipient
+ON grants (recipient, timestamp_ms);
+
+CREATE INDEX IF NOT EXISTS grants_identity
+ON grants (identity, timestamp_ms);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS grants_coin_type;
+
+ALTER TABLE grants
+DROP COLUMN IF EXISTS coin_type;
@@ -0,0 +1,11 @@
+-- The type of coin each grant sent, as a canonical type tag. Grants recorded before custom coins
+-- were tracked were all SUI.
+ALTER TABLE grants
+ADD COLUMN IF NOT EXISTS coin_type TEXT NOT NULL
+DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+
+ALTER TABLE grants
+ALTER COLUMN coin_type DROP DEFAULT;
+
+CREATE INDEX IF NOT EXISTS grants_coin_type
+ON grants (coin_type, timestamp_ms);
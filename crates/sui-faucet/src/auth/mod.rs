@@ -0,0 +1,288 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Abuse prevention for the faucet's web route, in authenticated mode. Every request is first
+//! checked by a [`Verifier`] (a captcha, or an OAuth login), which identifies who is making the
+//! request, and then counted against that identity's quota in a [`QuotaStore`].
+
+use std::net::SocketAddr;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use axum::http::{HeaderMap, StatusCode};
+use clap::ValueEnum;
+use once_cell::sync::Lazy;
+
+use crate::{FaucetConfig, FaucetError};
+
+pub(crate) use captcha::CaptchaVerifier;
+pub(crate) use oauth::OAuthVerifier;
+pub(crate) use quota::{InMemoryQuotaStore, PgQuotaStore};
+
+mod captcha;
+mod oauth;
+mod quota;
+
+static CLOUDFLARE_TURNSTILE_URL: Lazy<Option<String>> =
+    Lazy::new(|| std::env::var("CLOUDFLARE_TURNSTILE_URL").ok());
+
+static TURNSTILE_SECRET_KEY: Lazy<Option<String>> =
+    Lazy::new(|| std::env::var("TURNSTILE_SECRET_KEY").ok());
+
+static HCAPTCHA_SECRET_KEY: Lazy<Option<String>> =
+    Lazy::new(|| std::env::var("HCAPTCHA_SECRET_KEY").ok());
+
+/// How requests to the web route are verified, in authenticated mode.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AuthBackend {
+    /// Cloudflare Turnstile captcha, whose token is passed in the `X-Turnstile-Token` header.
+    /// Quotas are tracked per IP address.
+    #[default]
+    Turnstile,
+
+    /// hCaptcha, whose token is passed in the `X-HCaptcha-Token` header. Quotas are tracked per IP
+    /// address.
+    Hcaptcha,
+
+    /// Discord OAuth, whose access token is passed as a bearer token in the `Authorization`
+    /// header. Quotas are tracked per Discord user.
+    Discord,
+
+    /// GitHub OAuth, whose access token is passed as a bearer token in the `Authorization`
+    /// header. Quotas are tracked per GitHub user.
+    Github,
+}
+
+/// The error returned to a request that did not pass abuse prevention.
+pub(crate) type Rejection = (StatusCode, FaucetError);
+
+/// Checks that a request is allowed to use the faucet.
+#[async_trait]
+pub(crate) trait Verifier: Send + Sync {
+    /// Verify the request from `addr` with `headers`, returning the identity that it should be
+    /// counted against.
+    async fn verify(&self, addr: SocketAddr, headers: &HeaderMap) -> Result<String, Rejection>;
+}
+
+/// Tracks how many requests each identity has made.
+#[async_trait]
+pub(crate) trait QuotaStore: Send + Sync {
+    /// Count a request against `identity`'s quota, failing if it has been used up.
+    async fn acquire(&self, identity: &str) -> Result<(), Rejection>;
+
+    /// Forget identities whose quotas have been reset.
+    async fn cleanup(&self) -> anyhow::Result<()>;
+}
+
+/// Combines a [`Verifier`] and a [`QuotaStore`] to check requests.
+pub(crate) struct RequestGuard {
+    verifier: Box<dyn Verifier>,
+    quota: Box<dyn QuotaStore>,
+}
+
+impl RequestGuard {
+    pub(crate) fn new(verifier: Box<dyn Verifier>, quota: Box<dyn QuotaStore>) -> Self {
+        Self { verifier, quota }
+    }
+
+    /// Set-up the guard selected by `config`. Captcha secrets are read from the environment
+    /// (`CLOUDFLARE_TURNSTILE_URL` and `TURNSTILE_SECRET_KEY` for Turnstile, and
+    /// `HCAPTCHA_SECRET_KEY` for hCaptcha).
+    pub(crate) async fn from_config(config: &FaucetConfig) -> anyhow::Result<Self> {
+        let verifier: Box<dyn Verifier> = match config.auth_backend {
+            AuthBackend::Turnstile => {
+                let (Some(url), Some(secret)) =
+                    (&*CLOUDFLARE_TURNSTILE_URL, &*TURNSTILE_SECRET_KEY)
+                else {
+                    anyhow::bail!(
+                        "Both CLOUDFLARE_TURNSTILE_URL and TURNSTILE_SECRET_KEY env vars must be \
+                         set for testnet deployment (--authenticated flag was set)"
+                    );
+                };
+
+                Box::new(CaptchaVerifier::turnstile(url.clone(), secret.clone()))
+            }
+
+            AuthBackend::Hcaptcha => {
+                let secret = HCAPTCHA_SECRET_KEY.clone().context(
+                    "HCAPTCHA_SECRET_KEY env var must be set to use the hcaptcha auth backend",
+                )?;
+
+                Box::new(CaptchaVerifier::hcaptcha(secret))
+            }
+
+            AuthBackend::Discord => Box::new(OAuthVerifier::discord()),
+            AuthBackend::Github => Box::new(OAuthVerifier::github()),
+        };
+
+        let quota: Box<dyn QuotaStore> = if let Some(url) = &config.quota_database_url {
+            Box::new(
+                PgQuotaStore::new(
+                    url.clone(),
+                    config.max_requests_per_ip,
+                    config.reset_time_interval(),
+                )
+                .await?,
+            )
+        } else {
+            Box::new(InMemoryQuotaStore::new(
+                config.max_requests_per_ip,
+                config.reset_time_interval(),
+            ))
+        };
+
+        Ok(Self::new(verifier, quota))
+    }
+
+    /// Verify the request and count it against its identity's quota, returning the identity.
+    pub(crate) async fn check(
+        &self,
+        addr: SocketAddr,
+        headers: &HeaderMap,
+    ) -> Result<String, Rejection> {
+        let identity = self.verifier.verify(addr, headers).await?;
+        self.quota.acquire(&identity).await?;
+        Ok(identity)
+    }
+
+    pub(crate) async fn cleanup(&self) -> anyhow::Result<()> {
+        self.quota.cleanup().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const MAX_REQUESTS_PER_IP: u64 = 3;
+    const RESET_TIME_INTERVAL: Duration = Duration::from_secs(5);
+
+    async fn setup_mock_cloudflare() -> MockServer {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "success": true, "error-codes": [] })),
+            )
+            .mount(&mock_server)
+            .await;
+
+        mock_server
+    }
+
+    fn turnstile_guard(url: String) -> RequestGuard {
+        RequestGuard::new(
+            Box::new(CaptchaVerifier::turnstile(url, "test_secret".to_string())),
+            Box::new(InMemoryQuotaStore::new(
+                MAX_REQUESTS_PER_IP,
+                RESET_TIME_INTERVAL,
+            )),
+        )
+    }
+
+    fn headers(name: &'static str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, value.parse().unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_token_validation_and_limits() {
+        let mock_server = setup_mock_cloudflare().await;
+        let guard = turnstile_guard(mock_server.uri());
+        let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+        let headers = headers("X-Turnstile-Token", "test_token");
+
+        // Use up all requests, the identity is the IP address.
+        for _ in 0..MAX_REQUESTS_PER_IP {
+            let identity = guard.check(ip, &headers).await.unwrap();
+            assert_eq!(identity, "127.0.0.1");
+        }
+
+        // Next request should fail due to limit
+        let (status, _) = guard.check(ip, &headers).await.unwrap_err();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_missing_token() {
+        let mock_server = setup_mock_cloudflare().await;
+        let guard = turnstile_guard(mock_server.uri());
+        let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+
+        let (status, error) = guard.check(ip, &HeaderMap::new()).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error, FaucetError::MissingTurnstileTokenHeader);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_response() {
+        let mock_server = MockServer::start().await;
+
+        // Setup mock for invalid token
+        Mock::given(method("POST"))
+            .and(path("/siteverify"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": false,
+                "error-codes": ["invalid-input-response"]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let guard = turnstile_guard(format!("{}/siteverify", mock_server.uri()));
+        let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+        let headers = headers("X-Turnstile-Token", "invalid_token");
+
+        let (status, _) = guard.check(ip, &headers).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_oauth_identity() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .and(header("Authorization", "Bearer good_token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 1234 })))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+
+        let guard = RequestGuard::new(
+            Box::new(OAuthVerifier::new(
+                "github",
+                format!("{}/user", mock_server.uri()),
+            )),
+            Box::new(InMemoryQuotaStore::new(1, RESET_TIME_INTERVAL)),
+        );
+
+        // Quotas are tracked per user, regardless of IP address.
+        let ip0 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+        let ip1 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)), 8080);
+        let good = headers("Authorization", "Bearer good_token");
+        let bad = headers("Authorization", "Bearer bad_token");
+
+        assert_eq!(guard.check(ip0, &good).await.unwrap(), "github:1234");
+
+        let (status, _) = guard.check(ip1, &good).await.unwrap_err();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+        let (status, _) = guard.check(ip0, &bad).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = guard.check(ip0, &HeaderMap::new()).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::SocketAddr;
+
+use async_trait::async_trait;
+use axum::http::{header::AUTHORIZATION, HeaderMap, StatusCode};
+use serde::Deserialize;
+use tracing::error;
+
+use super::{Rejection, Verifier};
+use crate::FaucetError;
+
+const DISCORD_USER_URL: &str = "https://discord.com/api/users/@me";
+const GITHUB_USER_URL: &str = "https://api.github.com/user";
+
+/// Only lets through requests from users logged in with an OAuth provider. The request carries
+/// the user's access token, which is exchanged for their user ID at the provider's user endpoint,
+/// and requests are identified by that ID.
+pub(crate) struct OAuthVerifier {
+    client: reqwest::Client,
+    provider: &'static str,
+    user_url: String,
+}
+
+/// The part of the provider's user response that identifies the user. Discord returns IDs as
+/// strings, and GitHub as numbers.
+#[derive(Deserialize, Debug)]
+struct User {
+    id: serde_json::Value,
+}
+
+impl OAuthVerifier {
+    pub(crate) fn new(provider: &'static str, user_url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            provider,
+            user_url,
+        }
+    }
+
+    pub(crate) fn discord() -> Self {
+        Self::new("discord", DISCORD_USER_URL.to_string())
+    }
+
+    pub(crate) fn github() -> Self {
+        Self::new("github", GITHUB_USER_URL.to_string())
+    }
+
+    fn unauthorized(&self, reason: &str) -> Rejection {
+        (
+            StatusCode::UNAUTHORIZED,
+            FaucetError::Unauthorized(format!("{}: {reason}", self.provider)),
+        )
+    }
+}
+
+#[async_trait]
+impl Verifier for OAuthVerifier {
+    async fn verify(&self, _addr: SocketAddr, headers: &HeaderMap) -> Result<String, Rejection> {
+        let Some(token) = headers
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+        else {
+            return Err(self.unauthorized("Missing bearer token"));
+        };
+
+        let resp = match self
+            .client
+            .get(&self.user_url)
+            .bearer_auth(token)
+            // GitHub rejects requests without a user agent.
+            .header(reqwest::header::USER_AGENT, "sui-faucet")
+            .send()
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) => {
+                error!(
+                    provider = self.provider,
+                    "OAuth user request failed: {:?}", e
+                );
+                return Err((StatusCode::INTERNAL_SERVER_ERROR, FaucetError::internal(e)));
+            }
+        };
+
+        let status = resp.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(self.unauthorized("Invalid access token"));
+        } else if !status.is_success() {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                FaucetError::Internal(format!("{} user request failed: {status}", self.provider)),
+            ));
+        }
+
+        let user = match resp.json::<User>().await {
+            Ok(user) => user,
+            Err(e) => {
+                error!(
+                    provider = self.provider,
+                    "Failed to parse user response: {:?}", e
+                );
+                return Err((StatusCode::INTERNAL_SERVER_ERROR, FaucetError::internal(e)));
+            }
+        };
+
+        let id = match user.id {
+            serde_json::Value::String(id) => id,
+            serde_json::Value::Number(id) => id.to_string(),
+            _ => return Err(self.unauthorized("Unrecognized user ID")),
+        };
+
+        Ok(format!("{}:{id}", self.provider))
+    }
+}
@@ -0,0 +1,437 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use dashmap::{mapref::entry::Entry, DashMap};
+use diesel::{prelude::*, sql_query, sql_types::BigInt, sql_types::Text};
+use diesel_async::RunQueryDsl;
+use sui_pg_db::Db;
+use url::Url;
+
+use super::{QuotaStore, Rejection};
+use crate::{
+    db::{self, now_ms},
+    schema::quotas,
+    FaucetError,
+};
+
+/// Keeps track of every identity's requests in memory. Quotas are reset when the faucet restarts.
+#[derive(Debug)]
+pub(crate) struct InMemoryQuotaStore {
+    data: DashMap<String, RequestInfo>,
+    reset_time_interval: Duration,
+    max_requests: u64,
+}
+
+/// Request's metadata
+#[derive(Debug, Clone)]
+struct RequestInfo {
+    /// When the first request from this identity was made. In case of resetting the identity's
+    /// metadata, this field will be updated with the new current time.
+    timestamp: Instant,
+    requests_used: u64,
+}
+
+/// Keeps track of every identity's requests in Postgres, so that quotas survive restarts, and
+/// can be shared between faucet instances.
+pub(crate) struct PgQuotaStore {
+    db: Db,
+    reset_time_interval: Duration,
+    max_requests: u64,
+}
+
+impl InMemoryQuotaStore {
+    pub(crate) fn new(max_requests: u64, reset_time_interval: Duration) -> Self {
+        Self {
+            data: DashMap::new(),
+            reset_time_interval,
+            max_requests,
+        }
+    }
+}
+
+impl PgQuotaStore {
+    /// Connect to the database at `database_url`, creating the quota table if necessary.
+    pub(crate) async fn new(
+        database_url: Url,
+        max_requests: u64,
+        reset_time_interval: Duration,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            db: db::connect(database_url).await?,
+            reset_time_interval,
+            max_requests,
+        })
+    }
+
+    fn reset_time_interval_ms(&self) -> i64 {
+        self.reset_time_interval.as_millis() as i64
+    }
+}
+
+#[async_trait]
+impl QuotaStore for InMemoryQuotaStore {
+    async fn acquire(&self, identity: &str) -> Result<(), Rejection> {
+        match self.data.entry(identity.to_string()) {
+            Entry::Vacant(entry) => {
+                entry.insert(RequestInfo {
+                    timestamp: Instant::now(),
+                    requests_used: 1,
+                });
+            }
+
+            Entry::Occupied(mut entry) => {
+                let info = entry.get_mut();
+                let elapsed = info.timestamp.elapsed();
+
+                if elapsed >= self.reset_time_interval {
+                    info.timestamp = Instant::now();
+                    info.requests_used = 1;
+                } else if info.requests_used >= self.max_requests {
+                    return Err(too_many_requests(self.reset_time_interval - elapsed));
+                } else {
+                    info.requests_used += 1;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// This function iterates through the stored identities and removes those which are now
+    /// eligible to make new requests.
+    async fn cleanup(&self) -> anyhow::Result<()> {
+        // keep only those identities that are still under time limit.
+        self.data
+            .retain(|_, info| info.timestamp.elapsed() < self.reset_time_interval);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl QuotaStore for PgQuotaStore {
+    async fn acquire(&self, identity: &str) -> Result<(), Rejection> {
+        let internal =
+            |e: anyhow::Error| (StatusCode::INTERNAL_SERVER_ERROR, FaucetError::internal(e));
+        let mut conn = self.db.connect().await.map_err(internal)?;
+
+        let now_ms = now_ms();
+        let reset_ms = self.reset_time_interval_ms();
+
+        // Start a new window for identities that have not been seen before, or whose window has
+        // expired, and otherwise count the request against the current window, if there is room
+        // in it.
+        let acquired = sql_query(
+            r#"
+            INSERT INTO quotas (identity, window_start_ms, requests_used)
+            VALUES ($1, $2, 1)
+            ON CONFLICT (identity) DO UPDATE SET
+                window_start_ms = CASE
+                    WHEN quotas.window_start_ms <= $2 - $3 THEN $2
+                    ELSE quotas.window_start_ms
+                END,
+                requests_used = CASE
+                    WHEN quotas.window_start_ms <= $2 - $3 THEN 1
+                    ELSE quotas.requests_used + 1
+                END
+            WHERE quotas.window_start_ms <= $2 - $3 OR quotas.requests_used < $4
+            "#,
+        )
+        .bind::<Text, _>(identity)
+        .bind::<BigInt, _>(now_ms)
+        .bind::<BigInt, _>(reset_ms)
+        .bind::<BigInt, _>(self.max_requests as i64)
+        .execute(&mut conn)
+        .await
+        .map_err(|e| internal(e.into()))?;
+
+        if acquired > 0 {
+            return Ok(());
+        }
+
+        let window_start_ms: i64 = quotas::table
+            .select(quotas::window_start_ms)
+            .filter(quotas::identity.eq(identity))
+            .get_result(&mut conn)
+            .await
+            .map_err(|e| internal(e.into()))?;
+
+        let remaining_ms = (window_start_ms + reset_ms - now_ms).max(0);
+        Err(too_many_requests(Duration::from_millis(
+            remaining_ms as u64,
+        )))
+    }
+
+    async fn cleanup(&self) -> anyhow::Result<()> {
+        let mut conn = self.db.connect().await?;
+        diesel::delete(quotas::table)
+            .filter(quotas::window_start_ms.le(now_ms() - self.reset_time_interval_ms()))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+}
+
+fn too_many_requests(remaining: Duration) -> Rejection {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        FaucetError::TooManyRequests(format!(
+            "You can request a new token in {}",
+            secs_to_human_readable(remaining.as_secs())
+        )),
+    )
+}
+
+/// Format seconds to human readable format.
+fn secs_to_human_readable(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let seconds = seconds % 60;
+
+    if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use sui_pg_db::temp::TempDb;
+
+    use super::*;
+
+    const MAX_REQUESTS: u64 = 3;
+    const RESET_TIME_INTERVAL: Duration = Duration::from_secs(5);
+
+    #[tokio::test]
+    async fn test_reset_after_interval() {
+        let store = InMemoryQuotaStore::new(MAX_REQUESTS, RESET_TIME_INTERVAL);
+        let identity = "127.0.0.1";
+
+        // Use up all requests
+        for _ in 0..MAX_REQUESTS {
+            assert!(store.acquire(identity).await.is_ok());
+        }
+
+        // Try one more, it should fail
+        let result = store.acquire(identity).await;
+        assert!(result.unwrap_err().0 == StatusCode::TOO_MANY_REQUESTS);
+        assert!(!store.data.is_empty());
+
+        tokio::time::sleep(RESET_TIME_INTERVAL + Duration::from_secs(3)).await;
+        // Trigger cleanup
+        store.cleanup().await.unwrap();
+        assert!(store.data.is_empty());
+
+        // Should be able to make new requests
+        assert!(store.acquire(identity).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests() {
+        let store = Arc::new(InMemoryQuotaStore::new(MAX_REQUESTS, RESET_TIME_INTERVAL));
+
+        // Spawn tasks for each identity to make requests concurrently
+        let mut handles = vec![];
+        for idx in 0..10u64 {
+            let store = store.clone();
+            let handle = tokio::spawn(async move {
+                // Add some random delay to simulate real-world conditions
+                tokio::time::sleep(Duration::from_millis(idx * 50)).await;
+
+                let identity = format!("127.0.0.{idx}");
+                let mut results = vec![];
+                // Each identity tries to make MAX_REQUESTS + 1 requests
+                for _ in 0..=MAX_REQUESTS {
+                    results.push(store.acquire(&identity).await);
+                }
+                (identity, results)
+            });
+            handles.push(handle);
+        }
+
+        // Wait for all tasks to complete and check results
+        let all_results = futures::future::join_all(handles).await;
+
+        for result in all_results {
+            let (identity, results) = result.unwrap();
+
+            // First MAX_REQUESTS requests should succeed
+            for (idx, result) in results.iter().enumerate().take(MAX_REQUESTS as usize) {
+                assert!(
+                    result.is_ok(),
+                    "Request {idx} for {identity} should succeed"
+                );
+            }
+
+            // The last request (MAX_REQUESTS + 1) should fail
+            assert!(
+                results[MAX_REQUESTS as usize].is_err(),
+                "Request {MAX_REQUESTS} for {identity} should fail",
+            );
+        }
+
+        // Verify the data in the DashMap
+        assert_eq!(store.data.len(), 10, "Should have 10 identities in the map");
+
+        for info in store.data.iter() {
+            assert_eq!(
+                info.requests_used, MAX_REQUESTS,
+                "Each identity should have used exactly MAX_REQUESTS requests"
+            );
+        }
+    }
+
+    async fn pg_store(db: &TempDb, reset_time_interval: Duration) -> PgQuotaStore {
+        PgQuotaStore::new(
+            db.database().url().clone(),
+            MAX_REQUESTS,
+            reset_time_interval,
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn requests_used(store: &PgQuotaStore, identity: &str) -> Option<i64> {
+        let mut conn = store.db.connect().await.unwrap();
+        quotas::table
+            .select(quotas::requests_used)
+            .filter(quotas::identity.eq(identity))
+            .get_result(&mut conn)
+            .await
+            .optional()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pg_reset_after_interval() {
+        let db = TempDb::new().unwrap();
+        let interval = Duration::from_secs(2);
+        let store = pg_store(&db, interval).await;
+        let identity = "127.0.0.1";
+
+        for _ in 0..MAX_REQUESTS {
+            assert!(store.acquire(identity).await.is_ok());
+        }
+
+        let (status, _) = store.acquire(identity).await.unwrap_err();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+        // Rejected requests are not counted against the window.
+        assert_eq!(
+            requests_used(&store, identity).await,
+            Some(MAX_REQUESTS as i64)
+        );
+
+        // Cleaning up before the window expires keeps the identity's quota.
+        store.cleanup().await.unwrap();
+        assert_eq!(
+            requests_used(&store, identity).await,
+            Some(MAX_REQUESTS as i64)
+        );
+
+        tokio::time::sleep(interval + Duration::from_millis(500)).await;
+        store.cleanup().await.unwrap();
+        assert_eq!(requests_used(&store, identity).await, None);
+
+        assert!(store.acquire(identity).await.is_ok());
+        assert_eq!(requests_used(&store, identity).await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_pg_window_expires_without_cleanup() {
+        let db = TempDb::new().unwrap();
+        let interval = Duration::from_secs(2);
+        let store = pg_store(&db, interval).await;
+        let identity = "127.0.0.1";
+
+        for _ in 0..MAX_REQUESTS {
+            assert!(store.acquire(identity).await.is_ok());
+        }
+        assert!(store.acquire(identity).await.is_err());
+
+        // An expired window is replaced by the next request, even if it was never cleaned up.
+        tokio::time::sleep(interval + Duration::from_millis(500)).await;
+        assert!(store.acquire(identity).await.is_ok());
+        assert_eq!(requests_used(&store, identity).await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_pg_concurrent_requests() {
+        let db = TempDb::new().unwrap();
+
+        // Two stores sharing a database, as two faucet instances would.
+        let stores = [
+            Arc::new(pg_store(&db, RESET_TIME_INTERVAL).await),
+            Arc::new(pg_store(&db, RESET_TIME_INTERVAL).await),
+        ];
+
+        // Every identity races many more requests than its quota across both stores, including
+        // its very first request, which creates its row.
+        let attempts = 4 * MAX_REQUESTS as usize;
+        let mut handles = vec![];
+        for idx in 0..5 {
+            let identity = format!("127.0.0.{idx}");
+            for attempt in 0..attempts {
+                let store = stores[attempt % stores.len()].clone();
+                let identity = identity.clone();
+                handles.push(tokio::spawn(async move {
+                    let result = store.acquire(&identity).await;
+                    (identity, result)
+                }));
+            }
+        }
+
+        let mut acquired: BTreeMap<String, u64> = BTreeMap::new();
+        for handle in handles {
+            let (identity, result) = handle.await.unwrap();
+            match result {
+                Ok(()) => *acquired.entry(identity).or_default() += 1,
+                Err((status, _)) => assert_eq!(status, StatusCode::TOO_MANY_REQUESTS),
+            }
+        }
+
+        assert_eq!(
+            acquired.len(),
+            5,
+            "Every identity should get some requests through"
+        );
+        for (identity, count) in acquired {
+            assert_eq!(
+                count, MAX_REQUESTS,
+                "Exactly MAX_REQUESTS requests for {identity} should succeed"
+            );
+            assert_eq!(
+                requests_used(&stores[0], &identity).await,
+                Some(MAX_REQUESTS as i64)
+            );
+        }
+    }
+
+    #[test]
+    fn test_secs_to_human_readable() {
+        // Test seconds only
+        assert_eq!(secs_to_human_readable(45), "45s");
+        assert_eq!(secs_to_human_readable(1), "1s");
+
+        // Test minutes and seconds
+        assert_eq!(secs_to_human_readable(65), "1m 5s");
+        assert_eq!(secs_to_human_readable(3599), "59m 59s");
+
+        // Test hours, minutes, and seconds
+        assert_eq!(secs_to_human_readable(3600), "1h 0m 0s");
+        assert_eq!(secs_to_human_readable(3661), "1h 1m 1s");
+        assert_eq!(secs_to_human_readable(7384), "2h 3m 4s");
+
+        // Test edge case
+        assert_eq!(secs_to_human_readable(0), "0s");
+    }
+}
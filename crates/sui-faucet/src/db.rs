@@ -0,0 +1,31 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use diesel_migrations::{embed_migrations, EmbeddedMigrations};
+use sui_pg_db::{Db, DbArgs};
+use url::Url;
+
+pub(crate) const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+/// Connect to the faucet's database at `database_url`, and bring its schema up-to-date.
+pub(crate) async fn connect(database_url: Url) -> anyhow::Result<Db> {
+    let db = Db::for_write(DbArgs {
+        database_url,
+        ..Default::default()
+    })
+    .await?;
+
+    db.run_migrations(MIGRATIONS).await?;
+    Ok(db)
+}
+
+/// The current time, in milliseconds since the Unix epoch, as timestamps are stored in the
+/// database.
+pub(crate) fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
@@ -8,6 +8,12 @@ pub enum FaucetError {
     #[error("Missing X-Turnstile-Token header. For testnet tokens, please use the Web UI: https://faucet.sui.io")]
     MissingTurnstileTokenHeader,
 
+    #[error("Missing {0} header")]
+    MissingAuthHeader(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
     #[error("Request limit exceeded. {0}")]
     TooManyRequests(String),
 
@@ -51,6 +57,15 @@ pub enum FaucetError {
 
     #[error("Invalid user agent: {0}")]
     InvalidUserAgent(String),
+
+    #[error("Invalid recipients: {0}")]
+    InvalidRecipients(String),
+
+    #[error("Faucet does not serve coin type `{0}`")]
+    UnknownCoinType(String),
+
+    #[error("Daily limit for coin type `{0}` has been reached. Please try again tomorrow.")]
+    DailyCapExceeded(String),
 }
 
 impl FaucetError {
@@ -0,0 +1,287 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coin types other than SUI that the faucet can send, for teams testing dapps denominated in
+//! their own tokens. Each coin is either minted with a `TreasuryCap` owned by the faucet, or
+//! transferred from the faucet's own balance of that coin, and the total amount sent per day is
+//! capped.
+
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sui_types::{base_types::ObjectID, parse_sui_type_tag, TypeTag};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::FaucetError;
+
+const MS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// How the faucet sends a custom coin, as read from the custom coins config file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct CustomCoinConfig {
+    /// The coin's type, e.g. `0x123::usdc::USDC`.
+    pub coin_type: String,
+
+    /// The amount of each coin sent per request.
+    pub amount: u64,
+
+    /// The number of coins sent per request.
+    #[serde(default = "default_num_coins")]
+    pub num_coins: usize,
+
+    /// The maximum total amount of this coin sent per day (UTC), across all requests.
+    pub daily_cap: u64,
+
+    /// A `TreasuryCap` for the coin, owned by the faucet. If this is set, coins are minted,
+    /// otherwise they are transferred from the faucet's balance.
+    #[serde(default)]
+    pub treasury_cap: Option<ObjectID>,
+}
+
+/// The custom coins served by a faucet, and how much of each has been sent today.
+#[derive(Debug, Default)]
+pub(crate) struct CustomCoins {
+    coins: BTreeMap<TypeTag, CustomCoin>,
+}
+
+#[derive(Debug)]
+pub(crate) struct CustomCoin {
+    pub(crate) config: CustomCoinConfig,
+    pub(crate) coin_type: TypeTag,
+
+    /// Held while a request for this coin is in flight, so that requests do not try to use the
+    /// same coins or treasury cap concurrently.
+    in_flight: Mutex<()>,
+
+    usage: parking_lot::Mutex<DailyUsage>,
+}
+
+#[derive(Debug, Default)]
+struct DailyUsage {
+    /// The day that usage is being counted for, in days since the Unix epoch.
+    day: u64,
+
+    /// The amount sent on that day.
+    sent: u64,
+}
+
+/// The custom coins a faucet serves, and how much of each it can still send today.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCoinStatus {
+    pub coin_type: String,
+    pub amount: u64,
+    pub num_coins: usize,
+    pub daily_cap: u64,
+    pub remaining_today: u64,
+}
+
+impl CustomCoins {
+    /// Read the custom coins config (a YAML list of [`CustomCoinConfig`]s) at `path`.
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open custom coins config {}", path.display()))?;
+        let configs: Vec<CustomCoinConfig> = serde_yaml::from_reader(file)
+            .with_context(|| format!("Failed to parse custom coins config {}", path.display()))?;
+        Self::new(configs)
+    }
+
+    pub(crate) fn new(configs: Vec<CustomCoinConfig>) -> anyhow::Result<Self> {
+        let mut coins = BTreeMap::new();
+        for config in configs {
+            let coin_type = parse_sui_type_tag(&config.coin_type)
+                .with_context(|| format!("Invalid custom coin type {}", config.coin_type))?;
+
+            anyhow::ensure!(
+                config.num_coins > 0 && config.amount > 0,
+                "Custom coin {} must send at least one coin, with a non-zero amount",
+                config.coin_type,
+            );
+
+            let coin = CustomCoin {
+                config,
+                coin_type: coin_type.clone(),
+                in_flight: Mutex::new(()),
+                usage: parking_lot::Mutex::new(DailyUsage::default()),
+            };
+
+            if let Some(dupe) = coins.insert(coin_type, coin) {
+                anyhow::bail!("Custom coin {} configured twice", dupe.config.coin_type);
+            }
+        }
+
+        Ok(Self { coins })
+    }
+
+    /// Look up the custom coin with type `coin_type`.
+    pub(crate) fn get(&self, coin_type: &str) -> Result<&CustomCoin, FaucetError> {
+        parse_sui_type_tag(coin_type)
+            .ok()
+            .and_then(|tag| self.coins.get(&tag))
+            .ok_or_else(|| FaucetError::UnknownCoinType(coin_type.to_string()))
+    }
+
+    pub(crate) fn status(&self, now_ms: u64) -> Vec<CustomCoinStatus> {
+        self.coins
+            .values()
+            .map(|coin| CustomCoinStatus {
+                coin_type: coin.config.coin_type.clone(),
+                amount: coin.config.amount,
+                num_coins: coin.config.num_coins,
+                daily_cap: coin.config.daily_cap,
+                remaining_today: coin.remaining(now_ms),
+            })
+            .collect()
+    }
+}
+
+impl CustomCoin {
+    /// The total amount of this coin sent per request.
+    pub(crate) fn amount_per_request(&self) -> u64 {
+        self.config
+            .amount
+            .saturating_mul(self.config.num_coins as u64)
+    }
+
+    /// The amounts of each coin sent per request.
+    pub(crate) fn amounts(&self) -> Vec<u64> {
+        vec![self.config.amount; self.config.num_coins]
+    }
+
+    /// Wait for exclusive access to this coin, to send it.
+    pub(crate) async fn lock(&self) -> MutexGuard<'_, ()> {
+        self.in_flight.lock().await
+    }
+
+    /// Count a request at `now_ms` against this coin's daily cap, failing if sending it would
+    /// exceed the cap.
+    pub(crate) fn reserve(&self, now_ms: u64) -> Result<(), FaucetError> {
+        let mut usage = self.usage.lock();
+        let day = now_ms / MS_PER_DAY;
+        if usage.day != day {
+            usage.day = day;
+            usage.sent = 0;
+        }
+
+        let amount = self.amount_per_request();
+        if usage.sent.saturating_add(amount) > self.config.daily_cap {
+            return Err(FaucetError::DailyCapExceeded(self.config.coin_type.clone()));
+        }
+
+        usage.sent += amount;
+        Ok(())
+    }
+
+    /// Give back a reservation made by [`Self::reserve`] at `now_ms`, if sending failed.
+    pub(crate) fn release(&self, now_ms: u64) {
+        let mut usage = self.usage.lock();
+        if usage.day == now_ms / MS_PER_DAY {
+            usage.sent = usage.sent.saturating_sub(self.amount_per_request());
+        }
+    }
+
+    /// The amount of this coin that can still be sent on the day containing `now_ms`.
+    fn remaining(&self, now_ms: u64) -> u64 {
+        let usage = self.usage.lock();
+        let sent = if usage.day == now_ms / MS_PER_DAY {
+            usage.sent
+        } else {
+            0
+        };
+
+        self.config.daily_cap.saturating_sub(sent)
+    }
+}
+
+fn default_num_coins() -> usize {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COIN_TYPE: &str = "0x123::usdc::USDC";
+
+    fn coins(daily_cap: u64) -> CustomCoins {
+        CustomCoins::new(vec![CustomCoinConfig {
+            coin_type: COIN_TYPE.to_string(),
+            amount: 100,
+            num_coins: 2,
+            daily_cap,
+            treasury_cap: None,
+        }])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let configs: Vec<CustomCoinConfig> = serde_yaml::from_str(
+            r#"
+            - coin-type: "0x123::usdc::USDC"
+              amount: 1000
+              daily-cap: 1000000
+            - coin-type: "0x456::deep::DEEP"
+              amount: 10
+              num-coins: 5
+              daily-cap: 500
+              treasury-cap: "0x0000000000000000000000000000000000000000000000000000000000000789"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(configs[0].num_coins, 1);
+        assert_eq!(configs[0].treasury_cap, None);
+        assert_eq!(configs[1].num_coins, 5);
+        assert_eq!(
+            configs[1].treasury_cap,
+            Some(ObjectID::from_hex_literal("0x789").unwrap())
+        );
+
+        let coins = CustomCoins::new(configs).unwrap();
+        assert!(coins.get("0x0123::usdc::USDC").is_ok());
+        assert_eq!(
+            coins.get("0x123::usdc::USDT").unwrap_err(),
+            FaucetError::UnknownCoinType("0x123::usdc::USDT".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_reject_duplicate_coins() {
+        let config = CustomCoinConfig {
+            coin_type: COIN_TYPE.to_string(),
+            amount: 100,
+            num_coins: 1,
+            daily_cap: 1000,
+            treasury_cap: None,
+        };
+
+        assert!(CustomCoins::new(vec![config.clone(), config]).is_err());
+    }
+
+    #[test]
+    fn test_daily_cap() {
+        let coins = coins(500);
+        let coin = coins.get(COIN_TYPE).unwrap();
+        let now = 10 * MS_PER_DAY;
+
+        // Each request sends 200, so only two fit under the cap.
+        coin.reserve(now).unwrap();
+        coin.reserve(now + 1).unwrap();
+        assert_eq!(
+            coin.reserve(now + 2).unwrap_err(),
+            FaucetError::DailyCapExceeded(COIN_TYPE.to_string()),
+        );
+
+        // Failed requests don't count against the cap.
+        coin.release(now + 3);
+        coin.reserve(now + 4).unwrap();
+        assert_eq!(coins.status(now)[0].remaining_today, 100);
+
+        // The cap resets the next day.
+        coin.reserve(now + MS_PER_DAY).unwrap();
+        assert_eq!(coins.status(now + MS_PER_DAY)[0].remaining_today, 300);
+    }
+}
@@ -1,16 +1,20 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::FaucetError;
+use crate::{AuthBackend, FaucetError};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use uuid::Uuid;
 
+mod custom_coins;
 mod simple_faucet;
 mod write_ahead_log;
-pub use self::simple_faucet::SimpleFaucet;
+pub(crate) use self::custom_coins::CustomCoins;
+pub use self::custom_coins::{CustomCoinConfig, CustomCoinStatus};
+pub use self::simple_faucet::{SimpleFaucet, MAX_MULTI_RECIPIENT_COINS};
 use clap::Parser;
-use std::{net::Ipv4Addr, path::PathBuf, sync::Arc};
+use std::{net::Ipv4Addr, path::PathBuf, sync::Arc, time::Duration};
+use url::Url;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FaucetReceipt {
@@ -73,12 +77,35 @@ pub trait Faucet {
         amounts: &[u64],
     ) -> Result<BatchFaucetReceipt, FaucetError>;
 
+    /// Send `Coin<SUI>` of the specified amounts to each of the recipients, in a single
+    /// transaction, returning the coins each recipient was sent.
+    async fn send_to_many(
+        &self,
+        id: Uuid,
+        recipients: &[SuiAddress],
+        amounts: &[u64],
+    ) -> Result<Vec<(SuiAddress, FaucetReceipt)>, FaucetError>;
+
     /// Get the status of a batch_send request
     async fn get_batch_send_status(&self, task_id: Uuid) -> Result<BatchSendStatus, FaucetError>;
+
+    /// Send the configured amount of the custom coin `coin_type` to the recipient, counting it
+    /// against the coin's daily cap.
+    async fn send_custom_coin(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        coin_type: &str,
+    ) -> Result<FaucetReceipt, FaucetError>;
+
+    /// The custom coins this faucet serves, and how much of each it can still send today.
+    fn custom_coins(&self) -> Vec<CustomCoinStatus>;
 }
 
 pub const DEFAULT_AMOUNT: u64 = 1_000_000_000;
 pub const DEFAULT_NUM_OF_COINS: usize = 1;
+pub const DEFAULT_COIN_POOL_COIN_BALANCE: u64 = 100 * DEFAULT_AMOUNT;
+pub const DEFAULT_MAX_MULTI_RECIPIENT_AMOUNT: u64 = 100 * DEFAULT_AMOUNT;
 
 #[derive(Parser, Clone)]
 #[clap(
@@ -126,12 +153,56 @@ pub struct FaucetConfig {
     #[clap(long, action = clap::ArgAction::Set, default_value_t = false)]
     pub batch_enabled: bool,
 
+    /// Number of coins to keep in the faucet's gas pool. A background task periodically merges
+    /// coins that were removed from the pool for having too small a balance back into it, and if
+    /// this is set, also splits coins off large coins in the pool to top it up to this size.
+    #[clap(long)]
+    pub coin_pool_target_size: Option<usize>,
+
+    /// Balance of the coins split off to top up the gas pool, in MIST.
+    #[clap(long, default_value_t = DEFAULT_COIN_POOL_COIN_BALANCE)]
+    pub coin_pool_coin_balance: u64,
+
+    /// Interval between rebalancing the gas pool, in seconds.
+    #[clap(long, default_value_t = 60)]
+    pub coin_pool_interval_secs: u64,
+
+    /// The most SUI sent by a single `/v1/gas_many` request, across all its recipients, in MIST.
+    #[clap(long, default_value_t = DEFAULT_MAX_MULTI_RECIPIENT_AMOUNT)]
+    pub max_multi_recipient_amount: u64,
+
+    /// YAML file listing the coin types other than SUI that the faucet serves, with the amount
+    /// sent per request, and the total amount sent per day, for each.
+    #[clap(long)]
+    pub custom_coins_config: Option<PathBuf>,
+
     /// Testnet faucet requires authentication via the Web UI at <https://faucet.sui.io>
     /// This flag is used to indicate that authentication mode is enabled.
     #[clap(long)]
     pub authenticated: bool,
 
-    /// Maximum number of requests per IP address. This is used for the authenticated mode.
+    /// How requests from the Web UI are verified, in authenticated mode.
+    #[clap(long, value_enum, default_value_t = AuthBackend::default())]
+    pub auth_backend: AuthBackend,
+
+    /// Database to track per-identity request quotas in, for authenticated mode. If this is not
+    /// set, quotas are tracked in memory, and are reset when the faucet restarts.
+    #[clap(long)]
+    pub quota_database_url: Option<Url>,
+
+    /// Database to record every coin the faucet sends in, for auditing. Recorded grants can be
+    /// queried through the `/v1/grants` routes, which require the `GRANT_LEDGER_API_KEY` env var
+    /// to be set, and passed as a bearer token.
+    #[clap(long)]
+    pub grant_database_url: Option<Url>,
+
+    /// Number of days to keep recorded grants for. Grants are kept indefinitely if this is not
+    /// set.
+    #[clap(long)]
+    pub grant_retention_days: Option<u64>,
+
+    /// Maximum number of requests per identity (IP address, or OAuth user). This is used for the
+    /// authenticated mode.
     #[clap(long, default_value_t = 3)]
     pub max_requests_per_ip: u64,
 
@@ -169,7 +240,16 @@ impl Default for FaucetConfig {
             batch_request_size: 500,
             ttl_expiration: 300,
             batch_enabled: false,
+            coin_pool_target_size: None,
+            coin_pool_coin_balance: DEFAULT_COIN_POOL_COIN_BALANCE,
+            coin_pool_interval_secs: 60,
+            max_multi_recipient_amount: DEFAULT_MAX_MULTI_RECIPIENT_AMOUNT,
+            custom_coins_config: None,
             authenticated: false,
+            auth_backend: AuthBackend::default(),
+            quota_database_url: None,
+            grant_database_url: None,
+            grant_retention_days: None,
             max_requests_per_ip: 3,
             replenish_quota_interval_ms: 10,
             reset_time_interval_secs: 3600 * 12,
@@ -177,3 +257,14 @@ impl Default for FaucetConfig {
         }
     }
 }
+
+impl FaucetConfig {
+    pub fn reset_time_interval(&self) -> Duration {
+        Duration::from_secs(self.reset_time_interval_secs)
+    }
+
+    pub fn grant_retention(&self) -> Option<Duration> {
+        self.grant_retention_days
+            .map(|days| Duration::from_secs(days * 24 * 60 * 60))
+    }
+}
@@ -9,7 +9,6 @@ use mysten_metrics::spawn_monitored_task;
 use prometheus::Registry;
 use shared_crypto::intent::Intent;
 use std::collections::HashMap;
-#[cfg(test)]
 use std::collections::HashSet;
 use std::fmt;
 use std::path::Path;
@@ -29,9 +28,11 @@ use sui_sdk::wallet_context::WalletContext;
 use sui_types::object::Owner;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::{
-    base_types::{ObjectID, SuiAddress, TransactionDigest},
-    gas_coin::GasCoin,
-    transaction::{Transaction, TransactionData},
+    base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest},
+    coin::COIN_MODULE_NAME,
+    gas_coin::{GasCoin, GAS},
+    transaction::{Argument, Command, ObjectArg, Transaction, TransactionData},
+    Identifier, SUI_FRAMEWORK_PACKAGE_ID,
 };
 use tokio::sync::{
     mpsc::{self, Receiver, Sender},
@@ -41,10 +42,11 @@ use tokio::time::{timeout, Duration};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use super::custom_coins::{CustomCoin, CustomCoins};
 use super::write_ahead_log::WriteAheadLog;
 use crate::{
-    BatchFaucetReceipt, BatchSendStatus, BatchSendStatusType, CoinInfo, Faucet, FaucetConfig,
-    FaucetError, FaucetReceipt,
+    db::now_ms, BatchFaucetReceipt, BatchSendStatus, BatchSendStatusType, CoinInfo,
+    CustomCoinStatus, Faucet, FaucetConfig, FaucetError, FaucetReceipt,
 };
 
 pub struct SimpleFaucet {
@@ -61,6 +63,12 @@ pub struct SimpleFaucet {
     task_id_cache: Mutex<TtlCache<Uuid, BatchSendStatus>>,
     ttl_expiration: u64,
     coin_amount: u64,
+    /// The most SUI sent by a single multi-recipient request, across all its recipients.
+    max_multi_recipient_amount: u64,
+    custom_coins: CustomCoins,
+    /// Coins that were removed from the gas pool for having too small a balance, which the coin
+    /// pool manager merges back into the pool.
+    dust_coins: parking_lot::Mutex<HashSet<ObjectID>>,
     /// Shuts down the batch transfer task. Used only in testing.
     #[allow(unused)]
     batch_transfer_shutdown: parking_lot::Mutex<Option<oneshot::Sender<()>>>,
@@ -94,6 +102,14 @@ const DEFAULT_GAS_COMPUTATION_BUCKET: u64 = 10_000_000;
 const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
 const RECV_TIMEOUT: Duration = Duration::from_secs(5);
 const BATCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// The most dust coins merged back into the gas pool in one transaction.
+const MAX_DUST_COINS_PER_MERGE: usize = 200;
+/// The most coins that can be sent in a single multi-recipient request, bounded by the number of
+/// arguments a single `SplitCoins` command accepts.
+pub const MAX_MULTI_RECIPIENT_COINS: usize = 500;
+/// The most coins from the gas pool used to pay for a single multi-recipient request, bounded by
+/// the number of gas payment objects a transaction accepts.
+const MAX_GAS_PAYMENT_COINS: usize = 256;
 
 impl SimpleFaucet {
     pub async fn new(
@@ -105,6 +121,11 @@ impl SimpleFaucet {
         let (coins, active_address) = find_gas_coins_and_address(&mut wallet, &config).await?;
         info!("Starting faucet with address: {:?}", active_address);
 
+        let custom_coins = match &config.custom_coins_config {
+            Some(path) => CustomCoins::load(path).map_err(FaucetError::internal)?,
+            None => CustomCoins::default(),
+        };
+
         let metrics = FaucetMetrics::new(prometheus_registry);
         // set initial balance when faucet starts
         let balance = coins.iter().map(|coin| coin.0.balance.value()).sum::<u64>();
@@ -113,7 +134,9 @@ impl SimpleFaucet {
         let wal = WriteAheadLog::open(wal_path);
         let mut pending = vec![];
 
-        let (producer, consumer) = mpsc::channel(coins.len());
+        // Leave room in the gas pool for the coins the coin pool manager splits off.
+        let (producer, consumer) =
+            mpsc::channel(coins.len() + config.coin_pool_target_size.unwrap_or(0));
         let (batch_producer, batch_consumer) = mpsc::channel(coins.len());
 
         let (sender, mut receiver) =
@@ -180,6 +203,9 @@ impl SimpleFaucet {
             task_id_cache: TtlCache::new(config.max_request_per_second as usize * 60 * 10).into(),
             ttl_expiration: config.ttl_expiration,
             coin_amount: config.amount,
+            max_multi_recipient_amount: config.max_multi_recipient_amount,
+            custom_coins,
+            dust_coins: parking_lot::Mutex::new(HashSet::new()),
             batch_transfer_shutdown: parking_lot::Mutex::new(Some(batch_transfer_shutdown)),
         };
 
@@ -211,6 +237,28 @@ impl SimpleFaucet {
                 }
             }
         });
+
+        // Dust coins are always merged back into the pool, but the pool is only topped up if it has
+        // a target size.
+        let target_size = config.coin_pool_target_size.unwrap_or(0);
+        let pool_clone = Arc::downgrade(&arc_faucet);
+        let interval = Duration::from_secs(config.coin_pool_interval_secs);
+        let coin_balance = config.coin_pool_coin_balance;
+        spawn_monitored_task!(async move {
+            info!("Starting task to manage the gas coin pool.");
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(faucet) = pool_clone.upgrade() else {
+                    info!("Faucet has shut down already. Exiting ...");
+                    return;
+                };
+
+                if let Err(e) = faucet.rebalance_coin_pool(target_size, coin_balance).await {
+                    warn!("Failed to rebalance the gas coin pool: {e}");
+                }
+            }
+        });
+
         // Retrying all the pending transactions from the WAL, before continuing.  Ignore return
         // values -- if the executions failed, the pending coins will simply remain in the WAL, and
         // not recycled.
@@ -479,10 +527,11 @@ impl SimpleFaucet {
                     let sui_used = balances
                         .iter()
                         .find(|balance| {
-                            balance
-                                .owner
-                                .get_address_owner_address()
-                                .is_ok_and(|address| address == self.active_address)
+                            GAS::is_gas_type(&balance.coin_type)
+                                && balance
+                                    .owner
+                                    .get_address_owner_address()
+                                    .is_ok_and(|address| address == self.active_address)
                         })
                         .map(|b| b.amount)
                         .unwrap_or_else(|| 0);
@@ -541,7 +590,7 @@ impl SimpleFaucet {
 
             GasCoinResponse::GasCoinWithInsufficientBalance(coin_id) => {
                 warn!(?uuid, ?coin_id, "Insufficient balance, removing from pool");
-                self.metrics.total_discarded_coins.inc();
+                self.discard_dust_coin(coin_id);
                 self.transfer_gases(amounts, recipient, uuid).await
             }
 
@@ -556,19 +605,522 @@ impl SimpleFaucet {
         }
     }
 
+    /// Send `amounts` to each of `recipients` in a single transaction. The transaction is paid for
+    /// with as many coins from the gas pool as it takes to cover the batch, which are merged into
+    /// the first, so batches can be served even if no single coin in the pool covers them.
+    async fn transfer_gases_to_many(
+        &self,
+        amounts: &[u64],
+        recipients: &[SuiAddress],
+        uuid: Uuid,
+    ) -> Result<Vec<(SuiAddress, FaucetReceipt)>, FaucetError> {
+        let total_amount = amounts.iter().sum::<u64>() * recipients.len() as u64;
+        let gas_cost = self.get_gas_cost().await?;
+        let coins = self.gather_gas_coins(total_amount + gas_cost, uuid).await?;
+
+        let tx_data = match self
+            .build_multi_pay_sui_txn(&coins, recipients, amounts, gas_cost)
+            .await
+        {
+            Ok(tx_data) => tx_data,
+            Err(e) => {
+                self.recycle_gas_coins(&coins, uuid).await;
+                return Err(FaucetError::internal(e));
+            }
+        };
+
+        // The other coins are merged into the first, which is the only one left to track in the
+        // WAL, and recycle afterwards. As with batches, there is no single recipient to record.
+        let coin_id = coins[0];
+        let recipient = SuiAddress::ZERO;
+        let reserved = self
+            .wal
+            .lock()
+            .await
+            .reserve(uuid, coin_id, recipient, tx_data.clone());
+
+        if let Err(e) = reserved {
+            self.recycle_gas_coins(&coins, uuid).await;
+            return Err(FaucetError::internal(e));
+        }
+
+        let response = self
+            .sign_and_execute_txn(uuid, recipient, coin_id, tx_data, false)
+            .await?;
+        self.metrics
+            .total_coin_requests_succeeded
+            .add(recipients.len() as i64);
+        self.check_and_map_multi_transfer_gas_result(response, recipients, amounts)
+    }
+
+    /// Pull coins from the gas pool until their combined balance covers `total_amount`, using at
+    /// most [`MAX_GAS_PAYMENT_COINS`]. Coins that do not cover it on their own are kept rather than
+    /// discarded, and if the pool cannot cover `total_amount` at all, every coin pulled is returned
+    /// to it.
+    async fn gather_gas_coins(
+        &self,
+        total_amount: u64,
+        uuid: Uuid,
+    ) -> Result<Vec<ObjectID>, FaucetError> {
+        let mut coins = vec![];
+        let mut balance = 0;
+
+        while balance < total_amount && coins.len() < MAX_GAS_PAYMENT_COINS {
+            let Some(coin_id) = self.pop_gas_coin(uuid).await else {
+                break;
+            };
+
+            match self.get_gas_coin_and_check_faucet_owner(coin_id).await {
+                Ok(Some(gas_coin)) => {
+                    info!(?uuid, ?coin_id, "balance: {}", gas_coin.value());
+                    balance += gas_coin.value();
+                    coins.push(coin_id);
+                }
+
+                Ok(None) => {
+                    warn!(?uuid, ?coin_id, "Invalid, removing from pool");
+                    self.metrics.total_discarded_coins.inc();
+                }
+
+                Err(e) => {
+                    error!(?uuid, ?coin_id, "Fullnode read error: {e:?}");
+                    coins.push(coin_id);
+                    self.recycle_gas_coins(&coins, uuid).await;
+                    return Err(FaucetError::FullnodeReadingError(format!(
+                        "unknown gas coin {coin_id:?}"
+                    )));
+                }
+            }
+        }
+
+        if balance >= total_amount {
+            return Ok(coins);
+        }
+
+        warn!(
+            ?uuid,
+            coins = coins.len(),
+            balance,
+            total_amount,
+            "Gas pool cannot cover request, returning coins to the pool"
+        );
+
+        self.recycle_gas_coins(&coins, uuid).await;
+        Err(if coins.is_empty() {
+            FaucetError::NoGasCoinAvailable
+        } else {
+            FaucetError::InsuffientBalance
+        })
+    }
+
+    /// Send `coin` to `recipient`, paying for gas with a coin from the gas pool.
+    #[async_recursion]
+    async fn transfer_custom_coins(
+        &self,
+        coin: &CustomCoin,
+        recipient: SuiAddress,
+        uuid: Uuid,
+    ) -> Result<(TransactionDigest, Vec<ObjectID>), FaucetError> {
+        let gas_cost = self.get_gas_cost().await?;
+
+        match self.prepare_gas_coin(gas_cost, uuid, false).await {
+            GasCoinResponse::ValidGasCoin(coin_id) => {
+                let tx_data = self
+                    .build_custom_coin_txn(coin_id, coin, recipient, gas_cost)
+                    .await
+                    .map_err(FaucetError::internal);
+
+                let tx_data = match tx_data {
+                    Ok(tx_data) => tx_data,
+                    Err(e) => {
+                        self.recycle_gas_coin(coin_id, uuid).await;
+                        return Err(e);
+                    }
+                };
+
+                {
+                    let mut wal = self.wal.lock().await;
+                    wal.reserve(uuid, coin_id, recipient, tx_data.clone())
+                        .map_err(FaucetError::internal)?;
+                }
+
+                let response = self
+                    .sign_and_execute_txn(uuid, recipient, coin_id, tx_data, false)
+                    .await?;
+                self.check_and_map_transfer_gas_result(response, coin.config.num_coins, recipient)
+                    .await
+            }
+
+            GasCoinResponse::UnknownGasCoin(coin_id) => {
+                self.recycle_gas_coin(coin_id, uuid).await;
+                Err(FaucetError::FullnodeReadingError(format!(
+                    "unknown gas coin {coin_id:?}"
+                )))
+            }
+
+            GasCoinResponse::GasCoinWithInsufficientBalance(coin_id) => {
+                warn!(?uuid, ?coin_id, "Insufficient balance, removing from pool");
+                self.discard_dust_coin(coin_id);
+                self.transfer_custom_coins(coin, recipient, uuid).await
+            }
+
+            GasCoinResponse::InvalidGasCoin(coin_id) => {
+                warn!(?uuid, ?coin_id, "Invalid, removing from pool");
+                self.metrics.total_discarded_coins.inc();
+                self.transfer_custom_coins(coin, recipient, uuid).await
+            }
+
+            GasCoinResponse::NoGasCoinAvailable => Err(FaucetError::NoGasCoinAvailable),
+        }
+    }
+
     async fn recycle_gas_coin(&self, coin_id: ObjectID, uuid: Uuid) {
         // Once transactions are done, in despite of success or failure,
         // we put back the coins. The producer should never wait indefinitely,
         // in that the channel is initialized with big enough capacity.
         let producer = self.producer.lock().await;
         info!(?uuid, ?coin_id, "Got producer lock and recycling coin");
-        producer
-            .try_send(coin_id)
-            .expect("unexpected - queue is large enough to hold all coins");
+        if producer.try_send(coin_id).is_err() {
+            // This can only happen if the coin pool manager has added more coins to the pool than
+            // it has room for. Treat the coin as dust, so that the manager merges it into another.
+            warn!(?uuid, ?coin_id, "Gas pool is full, removing coin from pool");
+            self.discard_dust_coin(coin_id);
+            return;
+        }
         self.metrics.total_available_coins.inc();
         info!(?uuid, ?coin_id, "Recycled coin");
     }
 
+    async fn recycle_gas_coins(&self, coin_ids: &[ObjectID], uuid: Uuid) {
+        for coin_id in coin_ids {
+            self.recycle_gas_coin(*coin_id, uuid).await;
+        }
+    }
+
+    /// Remove a coin from the gas pool for having too small a balance. It remains owned by the
+    /// faucet, and will be merged back into the pool by the coin pool manager, if it is running.
+    fn discard_dust_coin(&self, coin_id: ObjectID) {
+        self.metrics.total_discarded_coins.inc();
+        self.dust_coins.lock().insert(coin_id);
+    }
+
+    /// Top the gas pool up to `target_size` coins, by splitting coins of `coin_balance` off a
+    /// coin from the pool, and merge dust coins that were removed from the pool back into it, so
+    /// that requests keep finding coins that are large enough to serve them, as the faucet's
+    /// balance fragments. If the pool has run dry, it is re-seeded from the dust coins.
+    async fn rebalance_coin_pool(
+        &self,
+        target_size: usize,
+        coin_balance: u64,
+    ) -> Result<(), FaucetError> {
+        let available = self.metrics.total_available_coins.get().max(0) as usize;
+        let deficit = target_size.saturating_sub(available);
+        if deficit == 0 && self.dust_coins.lock().is_empty() {
+            return Ok(());
+        }
+
+        let uuid = Uuid::new_v4();
+        let gas_cost = self.get_gas_cost().await?;
+        let mut seeded = false;
+        let gas_coins = match self.prepare_gas_coin(gas_cost, uuid, false).await {
+            GasCoinResponse::ValidGasCoin(coin_id) => vec![coin_id],
+
+            GasCoinResponse::UnknownGasCoin(coin_id) => {
+                self.recycle_gas_coin(coin_id, uuid).await;
+                return Err(FaucetError::FullnodeReadingError(format!(
+                    "unknown gas coin {coin_id:?}"
+                )));
+            }
+
+            GasCoinResponse::GasCoinWithInsufficientBalance(coin_id) => {
+                warn!(?uuid, ?coin_id, "Insufficient balance, removing from pool");
+                self.discard_dust_coin(coin_id);
+                return Ok(());
+            }
+
+            GasCoinResponse::InvalidGasCoin(coin_id) => {
+                warn!(?uuid, ?coin_id, "Invalid, removing from pool");
+                self.metrics.total_discarded_coins.inc();
+                return Ok(());
+            }
+
+            GasCoinResponse::NoGasCoinAvailable => {
+                let seed = self.take_seed_coins(gas_cost).await;
+                if seed.is_empty() {
+                    return Err(FaucetError::NoGasCoinAvailable);
+                }
+
+                info!(
+                    ?uuid,
+                    ?seed,
+                    "Gas pool is empty, re-seeding it from dust coins"
+                );
+                seeded = true;
+                seed
+            }
+        };
+
+        let dust: Vec<ObjectID> = {
+            let mut dust_coins = self.dust_coins.lock();
+            let dust: Vec<_> = dust_coins
+                .iter()
+                .take(MAX_DUST_COINS_PER_MERGE)
+                .copied()
+                .collect();
+            for coin_id in &dust {
+                dust_coins.remove(coin_id);
+            }
+            dust
+        };
+
+        let tx_data = match self
+            .build_rebalance_txn(&gas_coins, &dust, deficit, coin_balance, gas_cost)
+            .await
+        {
+            Ok(Some(tx_data)) => tx_data,
+            Ok(None) => {
+                self.return_rebalance_coins(&gas_coins, seeded, dust, uuid)
+                    .await;
+                return Ok(());
+            }
+            Err(e) => {
+                warn!(
+                    ?uuid,
+                    ?gas_coins,
+                    ?dust,
+                    "Failed to build rebalance txn: {e}"
+                );
+                self.return_rebalance_coins(&gas_coins, seeded, dust, uuid)
+                    .await;
+                return Err(FaucetError::internal(e));
+            }
+        };
+
+        // The other gas coins are merged into the first, which is the only one left to track in
+        // the WAL, and recycle afterwards.
+        let coin_id = gas_coins[0];
+        let reserved =
+            self.wal
+                .lock()
+                .await
+                .reserve(uuid, coin_id, self.active_address, tx_data.clone());
+
+        if let Err(e) = reserved {
+            warn!(
+                ?uuid,
+                ?gas_coins,
+                ?dust,
+                "Failed to reserve coins in WAL: {e}"
+            );
+            self.return_rebalance_coins(&gas_coins, seeded, dust, uuid)
+                .await;
+            return Err(FaucetError::internal(e));
+        }
+
+        let response = match self
+            .sign_and_execute_txn(uuid, self.active_address, coin_id, tx_data, false)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                // The transaction stays in the WAL to be retried, and it may still use the coins
+                // it was built with, so they cannot be handed out again until it has.
+                warn!(
+                    ?uuid,
+                    ?gas_coins,
+                    ?dust,
+                    "Rebalance txn failed, coins are held until it is retried: {e}"
+                );
+                return Err(e);
+            }
+        };
+
+        // Dust coins that were not merged (because the transaction failed, or its effects can't
+        // be read) are tried again next time. Coins that were merged no longer exist, and are
+        // skipped then.
+        let Some(effects) = response.effects else {
+            warn!(
+                ?uuid,
+                ?dust,
+                "Missing effects for rebalance txn, returning dust coins"
+            );
+            self.dust_coins.lock().extend(dust);
+            return Err(FaucetError::ParseTransactionResponseError(format!(
+                "effects field missing for txn {}",
+                response.digest
+            )));
+        };
+
+        if effects.status().is_err() {
+            warn!(?uuid, ?dust, "Rebalance txn failed, returning dust coins");
+            self.dust_coins.lock().extend(dust);
+            return Err(FaucetError::Transfer(format!(
+                "Rebalancing gas pool failed in txn {}",
+                response.digest
+            )));
+        }
+
+        let created = effects.created();
+        info!(
+            ?uuid,
+            merged = dust.len() + gas_coins.len() - 1,
+            split = created.len(),
+            "Rebalanced gas coin pool"
+        );
+
+        for coin in created {
+            self.recycle_gas_coin(coin.object_id(), uuid).await;
+        }
+
+        Ok(())
+    }
+
+    /// Return the coins taken for a rebalance that did not happen: The gas coin goes back to the
+    /// pool, unless it was `seeded` from dust coins, in which case it goes back to the dust coins,
+    /// along with `dust`.
+    async fn return_rebalance_coins(
+        &self,
+        gas_coins: &[ObjectID],
+        seeded: bool,
+        mut dust: Vec<ObjectID>,
+        uuid: Uuid,
+    ) {
+        if seeded {
+            dust.extend(gas_coins);
+        } else {
+            self.recycle_gas_coins(gas_coins, uuid).await;
+        }
+
+        self.dust_coins.lock().extend(dust);
+    }
+
+    /// Take dust coins, largest first, until they can pay for a rebalance costing `gas_cost`
+    /// together, so that an empty pool can be re-seeded from them. Returns no coins if all the
+    /// dust coins together can't cover it.
+    async fn take_seed_coins(&self, gas_cost: u64) -> Vec<ObjectID> {
+        let dust: Vec<ObjectID> = self.dust_coins.lock().iter().copied().collect();
+        let balances =
+            futures::future::join_all(dust.iter().map(|id| self.get_dust_coin(*id))).await;
+
+        let mut candidates: Vec<_> = dust
+            .into_iter()
+            .zip(balances)
+            .filter_map(|(id, coin)| Some((id, coin?.1)))
+            .collect();
+        candidates.sort_by_key(|(_, balance)| std::cmp::Reverse(*balance));
+
+        let mut seed = vec![];
+        let mut total = 0;
+        for (id, balance) in candidates.into_iter().take(MAX_GAS_PAYMENT_COINS) {
+            seed.push(id);
+            total += balance;
+            if total >= gas_cost {
+                let mut dust_coins = self.dust_coins.lock();
+                for id in &seed {
+                    dust_coins.remove(id);
+                }
+                return seed;
+            }
+        }
+
+        warn!(
+            total,
+            gas_cost, "Dust coins cannot cover the gas to re-seed the gas pool"
+        );
+        vec![]
+    }
+
+    /// Build a transaction that pays for gas with `gas_coins` (merged into the first), merges the
+    /// `dust` coins into the gas coin, and splits up to `deficit` coins of `coin_balance` off it,
+    /// leaving at least `coin_balance` on the gas coin. Returns `None` if there is nothing to do.
+    async fn build_rebalance_txn(
+        &self,
+        gas_coins: &[ObjectID],
+        dust: &[ObjectID],
+        deficit: usize,
+        coin_balance: u64,
+        budget: u64,
+    ) -> Result<Option<TransactionData>, anyhow::Error> {
+        let mut total = 0;
+        let mut gas_payment = vec![];
+        for coin_id in gas_coins {
+            let Some(gas_coin) = self.get_gas_coin_and_check_faucet_owner(*coin_id).await? else {
+                anyhow::bail!("Gas coin {coin_id} is no longer owned by the faucet");
+            };
+
+            total += gas_coin.value();
+            gas_payment.push(self.wallet.get_object_ref(*coin_id).await?);
+        }
+
+        let dust_coins =
+            futures::future::join_all(dust.iter().map(|id| self.get_dust_coin(*id))).await;
+
+        let mut dust_refs = vec![];
+        for (object_ref, value) in dust_coins.into_iter().flatten() {
+            total += value;
+            dust_refs.push(object_ref);
+        }
+
+        let splits = (total.saturating_sub(budget) / coin_balance.max(1))
+            .saturating_sub(1)
+            .min(deficit as u64) as usize;
+
+        if splits == 0 && dust_refs.is_empty() && gas_payment.len() == 1 {
+            return Ok(None);
+        }
+
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            if !dust_refs.is_empty() {
+                let dust_args = dust_refs
+                    .into_iter()
+                    .map(|r| builder.obj(ObjectArg::ImmOrOwnedObject(r)))
+                    .collect::<Result<_, _>>()?;
+                builder.command(Command::MergeCoins(Argument::GasCoin, dust_args));
+            }
+
+            if splits > 0 {
+                let amounts = (0..splits)
+                    .map(|_| builder.pure(coin_balance))
+                    .collect::<Result<_, _>>()?;
+
+                let Argument::Result(split) =
+                    builder.command(Command::SplitCoins(Argument::GasCoin, amounts))
+                else {
+                    unreachable!("commands always return a result");
+                };
+
+                let coins = (0..splits)
+                    .map(|i| Argument::NestedResult(split, i as u16))
+                    .collect();
+                builder.transfer_args(self.active_address, coins);
+            }
+
+            builder.finish()
+        };
+
+        let gas_price = self.wallet.get_reference_gas_price().await?;
+        Ok(Some(TransactionData::new_programmable(
+            self.active_address,
+            gas_payment,
+            pt,
+            budget,
+            gas_price,
+        )))
+    }
+
+    /// Fetch the reference and balance of a dust coin, if it is still a gas coin owned by the
+    /// faucet.
+    async fn get_dust_coin(&self, coin_id: ObjectID) -> Option<(ObjectRef, u64)> {
+        let coin = self
+            .get_gas_coin_and_check_faucet_owner(coin_id)
+            .await
+            .ok()
+            .flatten()?;
+        let object_ref = self.wallet.get_object_ref(coin_id).await.ok()?;
+        Some((object_ref, coin.value()))
+    }
+
     async fn recycle_gas_coin_for_batch(&self, coin_id: ObjectID, uuid: Uuid) {
         // Once transactions are done, in despite of success or failure,
         // we put back the coins. The producer should never wait indefinitely,
@@ -690,6 +1242,61 @@ impl SimpleFaucet {
             })
     }
 
+    /// Build a transaction sending `coin` to `recipient`, either by minting it with its treasury
+    /// cap, or by splitting it off the faucet's own coins.
+    async fn build_custom_coin_txn(
+        &self,
+        gas_coin_id: ObjectID,
+        coin: &CustomCoin,
+        recipient: SuiAddress,
+        budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let gas_payment = self.wallet.get_object_ref(gas_coin_id).await?;
+        let gas_price = self.wallet.get_reference_gas_price().await?;
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        if let Some(treasury_cap) = coin.config.treasury_cap {
+            let cap = self.wallet.get_object_ref(treasury_cap).await?;
+            let cap = builder.obj(ObjectArg::ImmOrOwnedObject(cap))?;
+            for amount in coin.amounts() {
+                let amount = builder.pure(amount)?;
+                let minted = builder.programmable_move_call(
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    COIN_MODULE_NAME.to_owned(),
+                    Identifier::new("mint")?,
+                    vec![coin.coin_type.clone()],
+                    vec![cap, amount],
+                );
+                builder.transfer_arg(recipient, minted);
+            }
+        } else {
+            let client = self.wallet.get_client().await?;
+            let coins = client
+                .coin_read_api()
+                .select_coins(
+                    self.active_address,
+                    Some(coin.coin_type.to_canonical_string(/* with_prefix */ true)),
+                    coin.amount_per_request() as u128,
+                    vec![],
+                )
+                .await?;
+
+            builder.pay(
+                coins.iter().map(|c| c.object_ref()).collect(),
+                vec![recipient; coin.config.num_coins],
+                coin.amounts(),
+            )?;
+        }
+
+        Ok(TransactionData::new_programmable(
+            self.active_address,
+            vec![gas_payment],
+            builder.finish(),
+            budget,
+            gas_price,
+        ))
+    }
+
     async fn check_and_map_transfer_gas_result(
         &self,
         res: SuiTransactionBlockResponse,
@@ -741,16 +1348,124 @@ impl SimpleFaucet {
                 let recipients = vec![recipient; amounts.len()];
                 builder.pay_sui(recipients, amounts)?;
             }
-            builder.finish()
-        };
+            builder.finish()
+        };
+
+        Ok(TransactionData::new_programmable(
+            signer,
+            vec![gas_payment],
+            pt,
+            budget,
+            gas_price,
+        ))
+    }
+
+    /// Build a transaction that pays for gas with `coin_ids` (which are merged into the first), and
+    /// splits `amounts` for each of `recipients` off the gas coin in one `SplitCoins` command, and
+    /// then transfers each recipient their coins.
+    async fn build_multi_pay_sui_txn(
+        &self,
+        coin_ids: &[ObjectID],
+        recipients: &[SuiAddress],
+        amounts: &[u64],
+        budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let gas_payment = futures::future::try_join_all(
+            coin_ids
+                .iter()
+                .map(|coin_id| self.wallet.get_object_ref(*coin_id)),
+        )
+        .await?;
+        let gas_price = self.wallet.get_reference_gas_price().await?;
+
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            let mut split_amounts = Vec::with_capacity(recipients.len() * amounts.len());
+            for _ in recipients {
+                for amount in amounts {
+                    split_amounts.push(builder.pure(*amount)?);
+                }
+            }
+
+            let Argument::Result(split) =
+                builder.command(Command::SplitCoins(Argument::GasCoin, split_amounts))
+            else {
+                unreachable!("commands always return a result");
+            };
+
+            for (i, recipient) in recipients.iter().enumerate() {
+                let coins = (0..amounts.len())
+                    .map(|j| Argument::NestedResult(split, (i * amounts.len() + j) as u16))
+                    .collect();
+                builder.transfer_args(*recipient, coins);
+            }
+
+            builder.finish()
+        };
+
+        Ok(TransactionData::new_programmable(
+            self.active_address,
+            gas_payment,
+            pt,
+            budget,
+            gas_price,
+        ))
+    }
+
+    fn check_and_map_multi_transfer_gas_result(
+        &self,
+        res: SuiTransactionBlockResponse,
+        recipients: &[SuiAddress],
+        amounts: &[u64],
+    ) -> Result<Vec<(SuiAddress, FaucetReceipt)>, FaucetError> {
+        let created = res
+            .effects
+            .ok_or_else(|| {
+                FaucetError::ParseTransactionResponseError(format!(
+                    "effects field missing for txn {}",
+                    res.digest
+                ))
+            })?
+            .created()
+            .to_vec();
+
+        let mut address_coins_map: HashMap<SuiAddress, Vec<ObjectID>> = HashMap::new();
+        for created_coin_owner_ref in &created {
+            if let Ok(owner) = created_coin_owner_ref.owner.get_owner_address() {
+                address_coins_map
+                    .entry(owner)
+                    .or_default()
+                    .push(created_coin_owner_ref.object_id());
+            }
+        }
 
-        Ok(TransactionData::new_programmable(
-            signer,
-            vec![gas_payment],
-            pt,
-            budget,
-            gas_price,
-        ))
+        // The same address may appear more than once in the request, in which case it is handed
+        // out its coins in turn.
+        let mut results = Vec::with_capacity(recipients.len());
+        for recipient in recipients {
+            let coins = address_coins_map.entry(*recipient).or_default();
+            if coins.len() < amounts.len() {
+                return Err(FaucetError::CoinAmountTransferredIncorrect(format!(
+                    "Transaction should create {} new coins for {recipient}, but got {}",
+                    amounts.len(),
+                    coins.len(),
+                )));
+            }
+
+            let sent = coins
+                .drain(..amounts.len())
+                .zip(amounts)
+                .map(|(id, amount)| CoinInfo {
+                    id,
+                    transfer_tx_digest: res.digest,
+                    amount: *amount,
+                })
+                .collect();
+
+            results.push((*recipient, FaucetReceipt { sent }));
+        }
+
+        Ok(results)
     }
 
     async fn check_and_map_batch_transfer_gas_result(
@@ -959,6 +1674,80 @@ impl Faucet for SimpleFaucet {
             None => Err(FaucetError::Internal("task id not found".to_string())),
         }
     }
+
+    async fn send_to_many(
+        &self,
+        id: Uuid,
+        recipients: &[SuiAddress],
+        amounts: &[u64],
+    ) -> Result<Vec<(SuiAddress, FaucetReceipt)>, FaucetError> {
+        info!(recipients = recipients.len(), uuid = ?id, ?amounts, "Getting multi-recipient faucet request");
+
+        let coins = recipients.len() * amounts.len();
+        if coins == 0 || coins > MAX_MULTI_RECIPIENT_COINS {
+            return Err(FaucetError::InvalidRecipients(format!(
+                "Requested {coins} coins, but between 1 and {MAX_MULTI_RECIPIENT_COINS} coins can \
+                 be sent per request"
+            )));
+        }
+
+        let max_amount = self.max_multi_recipient_amount;
+        let total_amount = amounts
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .and_then(|total| total.checked_mul(recipients.len() as u64))
+            .filter(|total| *total <= max_amount);
+
+        if total_amount.is_none() {
+            return Err(FaucetError::InvalidRecipients(format!(
+                "Requested more than the {max_amount} MIST that can be sent per request"
+            )));
+        }
+
+        let results = self.transfer_gases_to_many(amounts, recipients, id).await?;
+        info!(uuid = ?id, recipients = recipients.len(), "Multi-recipient PaySui txn succeeded");
+        Ok(results)
+    }
+
+    async fn send_custom_coin(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        coin_type: &str,
+    ) -> Result<FaucetReceipt, FaucetError> {
+        info!(?recipient, uuid = ?id, coin_type, "Getting custom coin request");
+        let coin = self.custom_coins.get(coin_type)?;
+
+        // Only one request per coin is served at a time, so that they don't race to use the same
+        // coins, or treasury cap.
+        let _guard = coin.lock().await;
+        let now_ms = now_ms() as u64;
+        coin.reserve(now_ms)?;
+
+        let (digest, coin_ids) = match self.transfer_custom_coins(coin, recipient, id).await {
+            Ok(transferred) => transferred,
+            Err(e) => {
+                coin.release(now_ms);
+                return Err(e);
+            }
+        };
+
+        info!(uuid = ?id, ?recipient, ?digest, coin_type, "Custom coin txn succeeded");
+        let sent = coin_ids
+            .into_iter()
+            .map(|coin_id| CoinInfo {
+                amount: coin.config.amount,
+                id: coin_id,
+                transfer_tx_digest: digest,
+            })
+            .collect();
+
+        Ok(FaucetReceipt { sent })
+    }
+
+    fn custom_coins(&self) -> Vec<CustomCoinStatus> {
+        self.custom_coins.status(now_ms() as u64)
+    }
 }
 
 pub async fn batch_gather(
@@ -1081,7 +1870,7 @@ pub async fn batch_transfer_gases(
 
             GasCoinResponse::GasCoinWithInsufficientBalance(coin_id) => {
                 warn!(?uuid, ?coin_id, "Insufficient balance, removing from pool");
-                faucet.metrics.total_discarded_coins.inc();
+                faucet.discard_dust_coin(coin_id);
                 // Continue the loop to retry preparing the gas coin
                 continue;
             }
@@ -2003,6 +2792,347 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_send_to_many() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let config: FaucetConfig = Default::default();
+        let amount_to_send = config.amount;
+        let context = test_cluster.wallet;
+
+        let prom_registry = Registry::new();
+        let tmp = tempfile::tempdir().unwrap();
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+
+        // The same address can be funded more than once.
+        let mut recipients: Vec<SuiAddress> = (0..3)
+            .map(|_| SuiAddress::random_for_testing_only())
+            .collect();
+        recipients.push(recipients[0]);
+
+        let amounts = vec![amount_to_send; 2];
+        let results = faucet
+            .send_to_many(Uuid::new_v4(), &recipients, &amounts)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), recipients.len());
+        let mut coin_ids = HashSet::new();
+        let mut digests = HashSet::new();
+        for ((recipient, receipt), expected) in results.iter().zip(&recipients) {
+            assert_eq!(recipient, expected);
+            assert_eq!(receipt.sent.len(), amounts.len());
+            for coin in &receipt.sent {
+                assert_eq!(coin.amount, amount_to_send);
+                assert!(coin_ids.insert(coin.id));
+                digests.insert(coin.transfer_tx_digest);
+            }
+        }
+
+        // Everything was sent in one transaction.
+        assert_eq!(digests.len(), 1);
+
+        // Requests that are too large are rejected up-front.
+        let too_many = vec![SuiAddress::random_for_testing_only(); MAX_MULTI_RECIPIENT_COINS];
+        let err = faucet
+            .send_to_many(Uuid::new_v4(), &too_many, &amounts)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, FaucetError::InvalidRecipients(_)));
+
+        // As are requests for more than the faucet sends per request in total.
+        let too_much = vec![SuiAddress::random_for_testing_only(); 2];
+        let err = faucet
+            .send_to_many(
+                Uuid::new_v4(),
+                &too_much,
+                &[crate::DEFAULT_MAX_MULTI_RECIPIENT_AMOUNT],
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, FaucetError::InvalidRecipients(_)));
+    }
+
+    #[tokio::test]
+    async fn test_send_to_many_across_coins() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let address = test_cluster.get_address_0();
+        let mut context = test_cluster.wallet;
+        let gas_coins = context
+            .get_all_gas_objects_owned_by_address(address)
+            .await
+            .unwrap();
+
+        let config = FaucetConfig::default();
+        let client = context.get_client().await.unwrap();
+        let gas_budget = 50_000_000;
+        let rgp = context.get_reference_gas_price().await.unwrap();
+        let gas_cost = rgp * DEFAULT_GAS_COMPUTATION_BUCKET;
+
+        // Leave the faucet with three coins that can each cover the gas for a request, and a few
+        // coins besides, but not the whole batch.
+        let coin_value = gas_cost + 5 * config.amount;
+        let tx_kind = client
+            .transaction_builder()
+            .split_coin_tx_kind(gas_coins[0].0, Some(vec![coin_value; 3]), None)
+            .await
+            .unwrap();
+        let tx_data = client
+            .transaction_builder()
+            .tx_data(address, tx_kind, gas_budget, rgp, vec![], None)
+            .await
+            .unwrap();
+        execute_tx(&mut context, tx_data).await.unwrap();
+
+        let destination_address = SuiAddress::random_for_testing_only();
+        for gas in &gas_coins {
+            let tx_data = client
+                .transaction_builder()
+                .transfer_sui(address, gas.0, gas_budget, destination_address, None)
+                .await
+                .unwrap();
+            execute_tx(&mut context, tx_data).await.unwrap();
+        }
+
+        let small_coins = context.gas_objects(address).await.unwrap();
+        assert_eq!(small_coins.len(), 3);
+        assert!(small_coins
+            .iter()
+            .all(|(balance, _)| *balance == coin_value));
+
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config.clone(),
+        )
+        .await
+        .unwrap();
+        faucet.shutdown_batch_send_task();
+
+        // A batch that no single coin covers is paid for by merging coins.
+        let recipients: Vec<_> = (0..8)
+            .map(|_| SuiAddress::random_for_testing_only())
+            .collect();
+        let results = faucet
+            .send_to_many(Uuid::new_v4(), &recipients, &[config.amount])
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), recipients.len());
+        for ((recipient, receipt), expected) in results.iter().zip(&recipients) {
+            assert_eq!(recipient, expected);
+            assert_eq!(receipt.sent.len(), 1);
+            assert_eq!(receipt.sent[0].amount, config.amount);
+        }
+
+        // No coin was discarded along the way, the coin that was merged into the other left the
+        // pool, and the coin that paid for the batch was returned to it.
+        assert_eq!(faucet.metrics.total_discarded_coins.get(), 0);
+        assert!(faucet.dust_coins.lock().is_empty());
+        assert_eq!(faucet.metrics.total_available_coins.get(), 2);
+        assert!(faucet.wal.lock().await.log.is_empty());
+
+        // A batch the whole pool cannot cover fails, and returns every coin to the pool.
+        let recipients: Vec<_> = (0..20)
+            .map(|_| SuiAddress::random_for_testing_only())
+            .collect();
+        let err = faucet
+            .send_to_many(Uuid::new_v4(), &recipients, &[config.amount])
+            .await
+            .unwrap_err();
+        assert_eq!(err, FaucetError::InsuffientBalance);
+        assert_eq!(faucet.metrics.total_discarded_coins.get(), 0);
+        assert_eq!(faucet.metrics.total_available_coins.get(), 2);
+
+        let faucet: &mut SimpleFaucet = &mut Arc::try_unwrap(faucet).unwrap();
+        assert_eq!(faucet.drain_gas_queue(2).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_coin_pool() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let address = test_cluster.get_address_0();
+        let context = test_cluster.wallet;
+        let coin_balance = 10 * crate::DEFAULT_AMOUNT;
+        let initial_coins = context
+            .get_all_gas_objects_owned_by_address(address)
+            .await
+            .unwrap()
+            .len();
+
+        // Set the interval high enough that the background task doesn't interfere.
+        let target_size = initial_coins + 3;
+        let config = FaucetConfig {
+            coin_pool_target_size: Some(target_size),
+            coin_pool_coin_balance: coin_balance,
+            coin_pool_interval_secs: 3600,
+            ..Default::default()
+        };
+
+        let prom_registry = Registry::new();
+        let tmp = tempfile::tempdir().unwrap();
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+
+        // Remove a coin from the pool, as though it was too small to use.
+        let uuid = Uuid::new_v4();
+        let dust = faucet.pop_gas_coin(uuid).await.unwrap();
+        faucet.discard_dust_coin(dust);
+        assert_eq!(
+            faucet.metrics.total_available_coins.get() as usize,
+            initial_coins - 1
+        );
+
+        // Rebalancing merges the dust back in, and tops the pool up to its target size.
+        faucet
+            .rebalance_coin_pool(target_size, coin_balance)
+            .await
+            .unwrap();
+
+        assert!(faucet.dust_coins.lock().is_empty());
+        assert_eq!(
+            faucet.metrics.total_available_coins.get() as usize,
+            target_size
+        );
+        assert!(faucet.get_coin(dust).await.unwrap().is_none());
+
+        // Once the pool is at its target size, there is nothing to do.
+        faucet
+            .rebalance_coin_pool(target_size, coin_balance)
+            .await
+            .unwrap();
+        assert_eq!(
+            faucet.metrics.total_available_coins.get() as usize,
+            target_size
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_merges_dust_without_target_size() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let context = test_cluster.wallet;
+
+        // The pool manager runs even without a target size, to merge dust coins.
+        let config = FaucetConfig {
+            coin_pool_interval_secs: 3600,
+            ..Default::default()
+        };
+
+        let prom_registry = Registry::new();
+        let tmp = tempfile::tempdir().unwrap();
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config.clone(),
+        )
+        .await
+        .unwrap();
+        let initial_coins = faucet.metrics.total_available_coins.get();
+
+        let dust = faucet.pop_gas_coin(Uuid::new_v4()).await.unwrap();
+        faucet.discard_dust_coin(dust);
+
+        let target_size = config.coin_pool_target_size.unwrap_or(0);
+        faucet
+            .rebalance_coin_pool(target_size, config.coin_pool_coin_balance)
+            .await
+            .unwrap();
+
+        // The dust was merged into another coin, which went back to the pool, but nothing was
+        // split off it.
+        assert!(faucet.dust_coins.lock().is_empty());
+        assert!(faucet.get_coin(dust).await.unwrap().is_none());
+        assert_eq!(
+            faucet.metrics.total_available_coins.get(),
+            initial_coins - 1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_reseeds_empty_pool() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let context = test_cluster.wallet;
+        let coin_balance = 10 * crate::DEFAULT_AMOUNT;
+        let target_size = 3;
+
+        let config = FaucetConfig {
+            coin_pool_target_size: Some(target_size),
+            coin_pool_coin_balance: coin_balance,
+            coin_pool_interval_secs: 3600,
+            ..Default::default()
+        };
+
+        let prom_registry = Registry::new();
+        let tmp = tempfile::tempdir().unwrap();
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+        faucet.shutdown_batch_send_task();
+
+        // Empty the pool, as though every coin in it had turned out to be too small to use.
+        let uuid = Uuid::new_v4();
+        let mut dust = vec![];
+        while faucet.metrics.total_available_coins.get() > 0 {
+            let coin_id = faucet.pop_gas_coin(uuid).await.unwrap();
+            faucet.discard_dust_coin(coin_id);
+            dust.push(coin_id);
+        }
+
+        // The pool is rebuilt from the dust: One of the dust coins pays for the rebalance, and is
+        // returned to the pool with the rest merged into it, and the coins split off it.
+        faucet
+            .rebalance_coin_pool(target_size, coin_balance)
+            .await
+            .unwrap();
+
+        assert!(faucet.dust_coins.lock().is_empty());
+        assert_eq!(
+            faucet.metrics.total_available_coins.get() as usize,
+            target_size + 1
+        );
+
+        let remaining: Vec<_> =
+            futures::future::join_all(dust.iter().map(|id| faucet.get_coin(*id)))
+                .await
+                .into_iter()
+                .map(|coin| coin.unwrap())
+                .filter(Option::is_some)
+                .collect();
+        assert_eq!(remaining.len(), 1);
+
+        // The re-seeded pool serves requests again.
+        let receipt = faucet
+            .send(
+                Uuid::new_v4(),
+                SuiAddress::random_for_testing_only(),
+                &[crate::DEFAULT_AMOUNT],
+            )
+            .await
+            .unwrap();
+        assert_eq!(receipt.sent.len(), 1);
+    }
+
     async fn test_send_interface_has_success_status(faucet: &impl Faucet) {
         let recipient = SuiAddress::random_for_testing_only();
         let amounts = vec![1, 2, 3];
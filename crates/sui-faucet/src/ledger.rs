@@ -0,0 +1,705 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A record of every coin the faucet has sent, kept in Postgres so that operators can audit and
+//! analyze its usage, through the query and reporting routes served alongside the faucet.
+
+use std::time::Duration;
+
+use diesel::{
+    prelude::*,
+    sql_query,
+    sql_types::{BigInt, Text},
+};
+use diesel_async::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use sui_pg_db::Db;
+use sui_types::{
+    base_types::{ObjectID, SuiAddress, TransactionDigest},
+    gas_coin::GAS,
+    parse_sui_type_tag, TypeTag,
+};
+use url::Url;
+
+use crate::{
+    db::{self, now_ms},
+    schema::grants,
+    FaucetReceipt,
+};
+
+/// Maximum number of grants returned by a single query.
+const MAX_QUERY_LIMIT: i64 = 1000;
+
+/// Number of identities included in a usage summary.
+const TOP_IDENTITIES: i64 = 10;
+
+pub(crate) struct GrantLedger {
+    db: Db,
+    retention: Option<Duration>,
+}
+
+/// A coin sent by the faucet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Grant {
+    pub id: i64,
+    pub recipient: SuiAddress,
+    pub amount: u64,
+    pub timestamp_ms: i64,
+    /// The identity the request was counted against, if it was authenticated.
+    pub identity: Option<String>,
+    pub coin_id: ObjectID,
+    pub transfer_tx_digest: TransactionDigest,
+
+    /// The type of the coin sent, as a canonical type tag.
+    pub coin_type: String,
+}
+
+/// Filters for querying grants. Grants are returned newest first.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantQuery {
+    pub recipient: Option<SuiAddress>,
+    pub identity: Option<String>,
+
+    /// Only include grants of this coin type.
+    pub coin_type: Option<String>,
+
+    /// Only include grants at or after this time.
+    pub from_ms: Option<i64>,
+
+    /// Only include grants before this time.
+    pub to_ms: Option<i64>,
+
+    /// Only include grants with IDs less than this, to fetch the next page of results.
+    pub cursor: Option<i64>,
+
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantPage {
+    pub grants: Vec<Grant>,
+
+    /// Cursor to pass to fetch the next page, if there may be more grants.
+    pub next_cursor: Option<i64>,
+}
+
+/// Time range and coin type to summarize usage over.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryQuery {
+    /// Amounts of different coin types are not comparable, so usage is summarized for one coin
+    /// type at a time. Defaults to SUI.
+    pub coin_type: Option<String>,
+    pub from_ms: Option<i64>,
+    pub to_ms: Option<i64>,
+}
+
+/// Aggregate usage of the faucet over some time range.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantSummary {
+    pub grants: u64,
+    pub amount: u64,
+    pub unique_recipients: u64,
+    pub unique_identities: u64,
+
+    /// The identities that were granted the most, by amount.
+    pub top_identities: Vec<IdentityUsage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, QueryableByName)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityUsage {
+    #[diesel(sql_type = Text)]
+    pub identity: String,
+    #[diesel(sql_type = BigInt)]
+    pub grants: i64,
+    #[diesel(sql_type = BigInt)]
+    pub amount: i64,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = grants)]
+struct StoredGrant {
+    id: i64,
+    recipient: Vec<u8>,
+    amount: i64,
+    timestamp_ms: i64,
+    identity: Option<String>,
+    coin_id: Vec<u8>,
+    tx_digest: Vec<u8>,
+    coin_type: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = grants)]
+struct NewGrant<'a> {
+    recipient: Vec<u8>,
+    amount: i64,
+    timestamp_ms: i64,
+    identity: Option<&'a str>,
+    coin_id: Vec<u8>,
+    tx_digest: Vec<u8>,
+    coin_type: &'a str,
+}
+
+#[derive(QueryableByName)]
+struct StoredSummary {
+    #[diesel(sql_type = BigInt)]
+    grants: i64,
+    #[diesel(sql_type = BigInt)]
+    amount: i64,
+    #[diesel(sql_type = BigInt)]
+    unique_recipients: i64,
+    #[diesel(sql_type = BigInt)]
+    unique_identities: i64,
+}
+
+impl GrantLedger {
+    /// Connect to the ledger at `database_url`. If `retention` is set, grants older than it are
+    /// removed by [`GrantLedger::prune`].
+    pub(crate) async fn new(
+        database_url: Url,
+        retention: Option<Duration>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            db: db::connect(database_url).await?,
+            retention,
+        })
+    }
+
+    /// Record the coins of type `coin_type` in `receipt` as sent to `recipient`, for a request
+    /// counted against `identity`.
+    pub(crate) async fn record(
+        &self,
+        recipient: SuiAddress,
+        identity: Option<&str>,
+        coin_type: &TypeTag,
+        receipt: &FaucetReceipt,
+    ) -> anyhow::Result<()> {
+        if receipt.sent.is_empty() {
+            return Ok(());
+        }
+
+        let timestamp_ms = now_ms();
+        let coin_type = coin_type.to_canonical_string(/* with_prefix */ true);
+        let values: Vec<_> = receipt
+            .sent
+            .iter()
+            .map(|coin| NewGrant {
+                recipient: recipient.to_vec(),
+                amount: coin.amount as i64,
+                timestamp_ms,
+                identity,
+                coin_id: coin.id.to_vec(),
+                tx_digest: coin.transfer_tx_digest.inner().to_vec(),
+                coin_type: &coin_type,
+            })
+            .collect();
+
+        let mut conn = self.db.connect().await?;
+        diesel::insert_into(grants::table)
+            .values(values)
+            .execute(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn query(&self, query: &GrantQuery) -> anyhow::Result<GrantPage> {
+        let limit = query
+            .limit
+            .unwrap_or(MAX_QUERY_LIMIT)
+            .clamp(1, MAX_QUERY_LIMIT);
+
+        let mut select = grants::table
+            .select(StoredGrant::as_select())
+            .order_by(grants::id.desc())
+            .limit(limit)
+            .into_boxed();
+
+        if let Some(recipient) = query.recipient {
+            select = select.filter(grants::recipient.eq(recipient.to_vec()));
+        }
+
+        if let Some(identity) = &query.identity {
+            select = select.filter(grants::identity.eq(identity.clone()));
+        }
+
+        if let Some(coin_type) = &query.coin_type {
+            select = select.filter(grants::coin_type.eq(canonical_coin_type(coin_type)?));
+        }
+
+        if let Some(from_ms) = query.from_ms {
+            select = select.filter(grants::timestamp_ms.ge(from_ms));
+        }
+
+        if let Some(to_ms) = query.to_ms {
+            select = select.filter(grants::timestamp_ms.lt(to_ms));
+        }
+
+        if let Some(cursor) = query.cursor {
+            select = select.filter(grants::id.lt(cursor));
+        }
+
+        let mut conn = self.db.connect().await?;
+        let stored: Vec<StoredGrant> = select.load(&mut conn).await?;
+
+        let next_cursor = (stored.len() as i64 == limit)
+            .then(|| stored.last().map(|g| g.id))
+            .flatten();
+
+        let grants = stored
+            .into_iter()
+            .map(Grant::try_from)
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(GrantPage {
+            grants,
+            next_cursor,
+        })
+    }
+
+    pub(crate) async fn summary(&self, query: &SummaryQuery) -> anyhow::Result<GrantSummary> {
+        let from_ms = query.from_ms.unwrap_or(0);
+        let to_ms = query.to_ms.unwrap_or(i64::MAX);
+        let coin_type = match &query.coin_type {
+            Some(coin_type) => canonical_coin_type(coin_type)?,
+            None => GAS::type_tag().to_canonical_string(/* with_prefix */ true),
+        };
+
+        let mut conn = self.db.connect().await?;
+
+        let summary: StoredSummary = sql_query(
+            r#"
+            SELECT
+                COUNT(*) AS grants,
+                COALESCE(SUM(amount), 0)::BIGINT AS amount,
+                COUNT(DISTINCT recipient) AS unique_recipients,
+                COUNT(DISTINCT identity) AS unique_identities
+            FROM
+                grants
+            WHERE
+                $1 <= timestamp_ms AND timestamp_ms < $2
+            AND coin_type = $3
+            "#,
+        )
+        .bind::<BigInt, _>(from_ms)
+        .bind::<BigInt, _>(to_ms)
+        .bind::<Text, _>(&coin_type)
+        .get_result(&mut conn)
+        .await?;
+
+        let top_identities: Vec<IdentityUsage> = sql_query(
+            r#"
+            SELECT
+                identity,
+                COUNT(*) AS grants,
+                SUM(amount)::BIGINT AS amount
+            FROM
+                grants
+            WHERE
+                $1 <= timestamp_ms AND timestamp_ms < $2
+            AND coin_type = $3
+            AND identity IS NOT NULL
+            GROUP BY
+                identity
+            ORDER BY
+                amount DESC
+            LIMIT
+                $4
+            "#,
+        )
+        .bind::<BigInt, _>(from_ms)
+        .bind::<BigInt, _>(to_ms)
+        .bind::<Text, _>(&coin_type)
+        .bind::<BigInt, _>(TOP_IDENTITIES)
+        .load(&mut conn)
+        .await?;
+
+        Ok(GrantSummary {
+            grants: summary.grants as u64,
+            amount: summary.amount as u64,
+            unique_recipients: summary.unique_recipients as u64,
+            unique_identities: summary.unique_identities as u64,
+            top_identities,
+        })
+    }
+
+    /// Remove grants that are older than the retention period, returning how many were removed.
+    pub(crate) async fn prune(&self) -> anyhow::Result<usize> {
+        let Some(retention) = self.retention else {
+            return Ok(0);
+        };
+
+        let mut conn = self.db.connect().await?;
+        let cutoff_ms = now_ms() - retention.as_millis() as i64;
+        Ok(diesel::delete(grants::table)
+            .filter(grants::timestamp_ms.lt(cutoff_ms))
+            .execute(&mut conn)
+            .await?)
+    }
+}
+
+impl TryFrom<StoredGrant> for Grant {
+    type Error = anyhow::Error;
+
+    fn try_from(stored: StoredGrant) -> anyhow::Result<Self> {
+        Ok(Self {
+            id: stored.id,
+            recipient: SuiAddress::from_bytes(&stored.recipient)?,
+            amount: stored.amount as u64,
+            timestamp_ms: stored.timestamp_ms,
+            identity: stored.identity,
+            coin_id: ObjectID::from_bytes(&stored.coin_id)?,
+            transfer_tx_digest: TransactionDigest::try_from(stored.tx_digest.as_slice())?,
+            coin_type: stored.coin_type,
+        })
+    }
+}
+
+/// Coin types are recorded as canonical type tags, so filters on them need to be normalized in
+/// the same way to match.
+pub(crate) fn canonical_coin_type(coin_type: &str) -> anyhow::Result<String> {
+    Ok(parse_sui_type_tag(coin_type)?.to_canonical_string(/* with_prefix */ true))
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_pg_db::temp::TempDb;
+
+    use crate::CoinInfo;
+
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    fn sui() -> TypeTag {
+        GAS::type_tag()
+    }
+
+    fn usdc() -> TypeTag {
+        parse_sui_type_tag("0x123::usdc::USDC").unwrap()
+    }
+
+    fn receipt(amounts: &[u64]) -> FaucetReceipt {
+        FaucetReceipt {
+            sent: amounts
+                .iter()
+                .map(|&amount| CoinInfo {
+                    amount,
+                    id: ObjectID::random(),
+                    transfer_tx_digest: TransactionDigest::random(),
+                })
+                .collect(),
+        }
+    }
+
+    async fn ledger(retention: Option<Duration>) -> (TempDb, GrantLedger) {
+        let db = TempDb::new().unwrap();
+        let ledger = GrantLedger::new(db.database().url().clone(), retention)
+            .await
+            .unwrap();
+        (db, ledger)
+    }
+
+    /// Record a grant with an explicit timestamp, bypassing [`GrantLedger::record`].
+    async fn record_at(ledger: &GrantLedger, recipient: SuiAddress, timestamp_ms: i64) {
+        let mut conn = ledger.db.connect().await.unwrap();
+        diesel::insert_into(grants::table)
+            .values(NewGrant {
+                recipient: recipient.to_vec(),
+                amount: 1,
+                timestamp_ms,
+                identity: None,
+                coin_id: ObjectID::random().to_vec(),
+                tx_digest: TransactionDigest::random().inner().to_vec(),
+                coin_type: &sui().to_canonical_string(true),
+            })
+            .execute(&mut conn)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_record_and_query() {
+        let (_db, ledger) = ledger(None).await;
+        let alice = SuiAddress::random_for_testing_only();
+        let bob = SuiAddress::random_for_testing_only();
+
+        let alice_receipt = receipt(&[100, 200]);
+        ledger
+            .record(alice, Some("alice@example.com"), &sui(), &alice_receipt)
+            .await
+            .unwrap();
+        ledger
+            .record(bob, None, &sui(), &receipt(&[300]))
+            .await
+            .unwrap();
+
+        // Receipts that did not send anything are not recorded.
+        ledger
+            .record(bob, None, &sui(), &receipt(&[]))
+            .await
+            .unwrap();
+
+        let page = ledger.query(&GrantQuery::default()).await.unwrap();
+        assert_eq!(page.grants.len(), 3);
+        assert_eq!(page.next_cursor, None);
+
+        // Newest first.
+        let ids: Vec<_> = page.grants.iter().map(|g| g.id).collect();
+        assert!(ids.windows(2).all(|w| w[0] > w[1]));
+        assert_eq!(page.grants[0].recipient, bob);
+        assert_eq!(page.grants[0].amount, 300);
+        assert_eq!(page.grants[0].identity, None);
+
+        let page = ledger
+            .query(&GrantQuery {
+                recipient: Some(alice),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let mut sent: Vec<_> = page
+            .grants
+            .iter()
+            .map(|g| (g.amount, g.coin_id, g.transfer_tx_digest))
+            .collect();
+        sent.sort();
+
+        let mut expect: Vec<_> = alice_receipt
+            .sent
+            .iter()
+            .map(|c| (c.amount, c.id, c.transfer_tx_digest))
+            .collect();
+        expect.sort();
+
+        assert_eq!(sent, expect);
+        assert!(page
+            .grants
+            .iter()
+            .all(|g| g.identity.as_deref() == Some("alice@example.com")));
+
+        let page = ledger
+            .query(&GrantQuery {
+                identity: Some("alice@example.com".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(page.grants.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pagination() {
+        let (_db, ledger) = ledger(None).await;
+        let recipient = SuiAddress::random_for_testing_only();
+        ledger
+            .record(recipient, None, &sui(), &receipt(&[1, 2, 3, 4, 5]))
+            .await
+            .unwrap();
+
+        let mut query = GrantQuery {
+            limit: Some(2),
+            ..Default::default()
+        };
+
+        let mut pages = vec![];
+        loop {
+            let page = ledger.query(&query).await.unwrap();
+            pages.push(page.grants.iter().map(|g| g.id).collect::<Vec<_>>());
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+
+        assert_eq!(
+            pages.iter().map(|p| p.len()).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+
+        // Pages don't overlap, and together cover every grant, newest first.
+        let ids: Vec<_> = pages.into_iter().flatten().collect();
+        assert_eq!(ids.len(), 5);
+        assert!(ids.windows(2).all(|w| w[0] > w[1]));
+
+        // A page that ends exactly at the last grant still offers a cursor, and the page after it
+        // is empty.
+        let page = ledger
+            .query(&GrantQuery {
+                limit: Some(5),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let cursor = page.next_cursor.unwrap();
+        let page = ledger
+            .query(&GrantQuery {
+                cursor: Some(cursor),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(page.grants.is_empty());
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn test_query_time_range() {
+        let (_db, ledger) = ledger(None).await;
+        let recipient = SuiAddress::random_for_testing_only();
+        for timestamp_ms in [1000, 2000, 3000] {
+            record_at(&ledger, recipient, timestamp_ms).await;
+        }
+
+        let page = ledger
+            .query(&GrantQuery {
+                from_ms: Some(2000),
+                to_ms: Some(3000),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let timestamps: Vec<_> = page.grants.iter().map(|g| g.timestamp_ms).collect();
+        assert_eq!(timestamps, vec![2000]);
+    }
+
+    #[tokio::test]
+    async fn test_summary() {
+        let (_db, ledger) = ledger(None).await;
+        let alice = SuiAddress::random_for_testing_only();
+        let bob = SuiAddress::random_for_testing_only();
+
+        ledger
+            .record(alice, Some("a"), &sui(), &receipt(&[100, 200]))
+            .await
+            .unwrap();
+        ledger
+            .record(bob, Some("b"), &sui(), &receipt(&[50]))
+            .await
+            .unwrap();
+        ledger
+            .record(bob, None, &sui(), &receipt(&[10]))
+            .await
+            .unwrap();
+
+        let summary = ledger.summary(&SummaryQuery::default()).await.unwrap();
+        assert_eq!(
+            summary,
+            GrantSummary {
+                grants: 4,
+                amount: 360,
+                unique_recipients: 2,
+                unique_identities: 2,
+                top_identities: vec![
+                    IdentityUsage {
+                        identity: "a".to_owned(),
+                        grants: 2,
+                        amount: 300,
+                    },
+                    IdentityUsage {
+                        identity: "b".to_owned(),
+                        grants: 1,
+                        amount: 50,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_coin_types() {
+        let (_db, ledger) = ledger(None).await;
+        let alice = SuiAddress::random_for_testing_only();
+
+        ledger
+            .record(alice, Some("a"), &sui(), &receipt(&[100]))
+            .await
+            .unwrap();
+        ledger
+            .record(alice, Some("a"), &usdc(), &receipt(&[5, 5]))
+            .await
+            .unwrap();
+
+        let page = ledger.query(&GrantQuery::default()).await.unwrap();
+        assert_eq!(page.grants.len(), 3);
+
+        // Coin types are matched on their canonical form, however they are written.
+        let page = ledger
+            .query(&GrantQuery {
+                coin_type: Some("0x0123::usdc::USDC".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(page.grants.len(), 2);
+        assert!(page
+            .grants
+            .iter()
+            .all(|g| g.coin_type == usdc().to_canonical_string(true)));
+
+        // Summaries default to SUI, and never mix amounts of different coin types.
+        let summary = ledger.summary(&SummaryQuery::default()).await.unwrap();
+        assert_eq!((summary.grants, summary.amount), (1, 100));
+
+        let summary = ledger
+            .summary(&SummaryQuery {
+                coin_type: Some("0x123::usdc::USDC".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!((summary.grants, summary.amount), (2, 10));
+        assert_eq!(summary.top_identities[0].amount, 10);
+
+        assert!(ledger
+            .query(&GrantQuery {
+                coin_type: Some("not a type".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_prune_expired_grants() {
+        let (_db, ledger) = ledger(Some(HOUR)).await;
+        let recipient = SuiAddress::random_for_testing_only();
+
+        let now = now_ms();
+        record_at(&ledger, recipient, now - 2 * HOUR.as_millis() as i64).await;
+        record_at(&ledger, recipient, now - HOUR.as_millis() as i64 / 2).await;
+        ledger
+            .record(recipient, None, &sui(), &receipt(&[1]))
+            .await
+            .unwrap();
+
+        assert_eq!(ledger.prune().await.unwrap(), 1);
+        assert_eq!(ledger.prune().await.unwrap(), 0);
+
+        let page = ledger.query(&GrantQuery::default()).await.unwrap();
+        assert_eq!(page.grants.len(), 2);
+        assert!(page
+            .grants
+            .iter()
+            .all(|g| g.timestamp_ms >= now - HOUR.as_millis() as i64));
+    }
+
+    #[tokio::test]
+    async fn test_prune_without_retention() {
+        let (_db, ledger) = ledger(None).await;
+        let recipient = SuiAddress::random_for_testing_only();
+        record_at(&ledger, recipient, 0).await;
+
+        assert_eq!(ledger.prune().await.unwrap(), 0);
+        let page = ledger.query(&GrantQuery::default()).await.unwrap();
+        assert_eq!(page.grants.len(), 1);
+    }
+}
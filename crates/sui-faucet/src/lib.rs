@@ -1,18 +1,24 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod auth;
+mod db;
 mod errors;
 mod faucet;
+mod ledger;
 mod metrics;
 mod requests;
 mod responses;
+mod schema;
 mod server;
 
 pub mod metrics_layer;
 pub use metrics_layer::*;
 
+pub use auth::AuthBackend;
 pub use errors::FaucetError;
 pub use faucet::*;
+pub use ledger::{Grant, GrantPage, GrantQuery, GrantSummary, IdentityUsage, SummaryQuery};
 pub use requests::*;
 pub use responses::*;
 pub use server::{create_wallet_context, start_faucet};
@@ -8,6 +8,8 @@ use sui_types::base_types::SuiAddress;
 pub enum FaucetRequest {
     FixedAmountRequest(FixedAmountRequest),
     GetBatchSendStatusRequest(GetBatchSendStatusRequest),
+    CustomCoinRequest(CustomCoinRequest),
+    MultiRecipientRequest(MultiRecipientRequest),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -15,6 +17,17 @@ pub struct FixedAmountRequest {
     pub recipient: SuiAddress,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomCoinRequest {
+    pub recipient: SuiAddress,
+    pub coin_type: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MultiRecipientRequest {
+    pub recipients: Vec<SuiAddress>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetBatchSendStatusRequest {
     pub task_id: String,
@@ -27,6 +40,20 @@ impl FaucetRequest {
         })
     }
 
+    pub fn new_custom_coin_request(
+        recipient: impl Into<SuiAddress>,
+        coin_type: impl Into<String>,
+    ) -> Self {
+        Self::CustomCoinRequest(CustomCoinRequest {
+            recipient: recipient.into(),
+            coin_type: coin_type.into(),
+        })
+    }
+
+    pub fn new_multi_recipient_request(recipients: Vec<SuiAddress>) -> Self {
+        Self::MultiRecipientRequest(MultiRecipientRequest { recipients })
+    }
+
     pub fn new_get_batch_send_status_request(task_id: impl Into<String>) -> Self {
         Self::GetBatchSendStatusRequest(GetBatchSendStatusRequest {
             task_id: task_id.into(),
@@ -3,6 +3,7 @@
 
 use crate::*;
 use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -30,6 +31,45 @@ impl From<FaucetReceipt> for FaucetResponse {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiRecipientFaucetResponse {
+    /// The coins sent to each recipient, in the order they were requested.
+    pub results: Vec<RecipientResult>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipientResult {
+    pub recipient: SuiAddress,
+    pub transferred_gas_objects: Vec<CoinInfo>,
+}
+
+impl From<FaucetError> for MultiRecipientFaucetResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            error: Some(e.to_string()),
+            results: vec![],
+        }
+    }
+}
+
+impl From<Vec<(SuiAddress, FaucetReceipt)>> for MultiRecipientFaucetResponse {
+    fn from(v: Vec<(SuiAddress, FaucetReceipt)>) -> Self {
+        Self {
+            results: v
+                .into_iter()
+                .map(|(recipient, receipt)| RecipientResult {
+                    recipient,
+                    transferred_gas_objects: receipt.sent,
+                })
+                .collect(),
+            error: None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchFaucetResponse {
@@ -2,13 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    AppState, BatchFaucetResponse, BatchStatusFaucetResponse, FaucetConfig, FaucetError,
-    FaucetRequest, FaucetResponse, FixedAmountRequest, RequestMetricsLayer,
+    AppState, BatchFaucetResponse, BatchSendStatusType, BatchStatusFaucetResponse,
+    CustomCoinStatus, FaucetConfig, FaucetError, FaucetReceipt, FaucetRequest, FaucetResponse,
+    FixedAmountRequest, GrantPage, GrantQuery, GrantSummary, MultiRecipientFaucetResponse,
+    RequestMetricsLayer, SummaryQuery,
 };
 use axum::{
     error_handling::HandleErrorLayer,
-    extract::{ConnectInfo, Host, Path},
-    http::{header::HeaderMap, StatusCode},
+    extract::{ConnectInfo, Host, Path, Query},
+    http::{
+        header::{HeaderMap, AUTHORIZATION},
+        StatusCode,
+    },
     response::{IntoResponse, Redirect, Response},
     routing::{get, post},
     BoxError, Extension, Json, Router,
@@ -21,23 +26,23 @@ use std::{
     net::{IpAddr, SocketAddr},
     path::PathBuf,
     sync::Arc,
-    time::{Duration, Instant},
+    time::Duration,
 };
 use sui_config::SUI_CLIENT_CONFIG;
 use sui_sdk::wallet_context::WalletContext;
+use sui_types::{base_types::SuiAddress, gas_coin::GAS, parse_sui_type_tag, TypeTag};
 use tower::ServiceBuilder;
 use tower_governor::{
     governor::GovernorConfigBuilder, key_extractor::GlobalKeyExtractor, GovernorLayer,
 };
 use tower_http::cors::{Any, CorsLayer};
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::auth::RequestGuard;
 use crate::faucet::Faucet;
-use dashmap::{mapref::entry::Entry, DashMap};
-use serde::Deserialize;
+use crate::ledger::GrantLedger;
 
-use anyhow::ensure;
 use once_cell::sync::Lazy;
 
 const DEFAULT_FAUCET_WEB_APP_URL: &str = "https://faucet.sui.io";
@@ -48,174 +53,41 @@ static FAUCET_WEB_APP_URL: Lazy<String> = Lazy::new(|| {
         .unwrap_or_else(|| DEFAULT_FAUCET_WEB_APP_URL.to_string())
 });
 
-static CLOUDFLARE_TURNSTILE_URL: Lazy<Option<String>> =
-    Lazy::new(|| std::env::var("CLOUDFLARE_TURNSTILE_URL").ok());
-
-static TURNSTILE_SECRET_KEY: Lazy<Option<String>> =
-    Lazy::new(|| std::env::var("TURNSTILE_SECRET_KEY").ok());
-
 static DISCORD_BOT_PWD: Lazy<String> =
     Lazy::new(|| std::env::var("DISCORD_BOT_PWD").unwrap_or_else(|_| "".to_string()));
 
-/// Keep track of every IP address' requests.
-#[derive(Debug)]
-struct RequestsManager {
-    data: Arc<DashMap<IpAddr, RequestInfo>>,
-    reset_time_interval: Duration,
-    max_requests_per_ip: u64,
-    cloudflare_turnstile_url: String,
-    turnstile_secret_key: String,
-}
-
-/// Request's metadata
-#[derive(Debug, Clone)]
-struct RequestInfo {
-    /// When the first request from this IP address was made. In case of resetting the IP addresses
-    /// metadata, this field will be updated with the new current time.
-    timestamp: Instant,
-    requests_used: u64,
-}
-
-/// Struct to deserialize token verification response from Cloudflare
-#[derive(Deserialize, Debug)]
-struct TurnstileValidationResponse {
-    success: bool,
-    #[serde(rename = "error-codes")]
-    error_codes: Vec<String>,
-}
-
-impl RequestsManager {
-    /// Initialize a new RequestsManager
-    fn new(
-        max_requests_per_ip: u64,
-        reset_time_interval_secs: Duration,
-        cloudflare_turnstile_url: String,
-        turnstile_secret_key: String,
-    ) -> Self {
-        Self {
-            data: Arc::new(DashMap::new()),
-            reset_time_interval: reset_time_interval_secs,
-            max_requests_per_ip,
-            cloudflare_turnstile_url,
-            turnstile_secret_key,
-        }
-    }
-
-    /// Validates a turnstile token
-    /// - against Cloudflare turnstile's server to ensure token was issued by turnstile
-    /// - against the IP address' request count
-    async fn validate_turnstile_token(
-        &self,
-        addr: SocketAddr,
-        token: &str,
-    ) -> Result<(), (StatusCode, FaucetError)> {
-        let ip = addr.ip();
-        let req = reqwest::Client::new();
-        let params = [
-            ("secret", self.turnstile_secret_key.as_str()),
-            ("response", token),
-            ("remoteip", &ip.to_string()),
-        ];
-
-        // Make the POST request
-        let resp = match req
-            .post(&self.cloudflare_turnstile_url)
-            .form(&params)
-            .send()
-            .await
-        {
-            Ok(resp) => resp,
-            Err(e) => {
-                error!("Cloudflare turnstile request failed: {:?}", e);
-                return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    FaucetError::Internal(e.to_string()),
-                ));
-            }
-        };
-
-        // Check if the request was successful.
-        if !resp.status().is_success() {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                FaucetError::Internal("Verification failed".to_string()),
-            ));
-        }
-
-        let body = match resp.json::<TurnstileValidationResponse>().await {
-            Ok(body) => body,
-            Err(e) => {
-                error!("Failed to parse token validation response: {:?}", e);
-                return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    FaucetError::Internal(e.to_string()),
-                ));
-            }
-        };
-
-        if !body.success {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                FaucetError::Internal(format!("Token verification failed: {:?}", body.error_codes)),
-            ));
-        }
+static GRANT_LEDGER_API_KEY: Lazy<Option<String>> =
+    Lazy::new(|| std::env::var("GRANT_LEDGER_API_KEY").ok());
 
-        match self.data.entry(ip) {
-            Entry::Vacant(entry) => {
-                entry.insert(RequestInfo {
-                    timestamp: Instant::now(),
-                    requests_used: 1,
-                });
-            }
+/// How often expired grants are removed from the ledger.
+const GRANT_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
-            Entry::Occupied(mut entry) => {
-                let token = entry.get_mut();
-                let elapsed = token.timestamp.elapsed();
-
-                if elapsed >= self.reset_time_interval {
-                    token.timestamp = Instant::now();
-                    token.requests_used = 1;
-                } else if token.requests_used >= self.max_requests_per_ip {
-                    return Err((
-                        StatusCode::TOO_MANY_REQUESTS,
-                        FaucetError::TooManyRequests(format!(
-                            "You can request a new token in {}",
-                            secs_to_human_readable((self.reset_time_interval - elapsed).as_secs())
-                        )),
-                    ));
-                } else {
-                    token.requests_used += 1;
-                }
-            }
-        }
-
-        Ok(())
-    }
-
-    /// This function iterates through the stored IPs and removes those IP addresses which are now
-    /// eligible to make new requests.
-    fn cleanup_expired_tokens(&self) {
-        // keep only those IP addresses that are still under time limit.
-        self.data
-            .retain(|_, info| info.timestamp.elapsed() < self.reset_time_interval);
-    }
-}
+/// How often the status of a batch request is checked, to record its grants once it succeeds.
+const BATCH_STATUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 pub async fn start_faucet(
     app_state: Arc<AppState>,
     concurrency_limit: usize,
     prometheus_registry: &Registry,
 ) -> Result<(), anyhow::Error> {
-    let (cloudflare_turnstile_url, turnstile_secret_key) = if app_state.config.authenticated {
-        ensure!(TURNSTILE_SECRET_KEY.is_some() && CLOUDFLARE_TURNSTILE_URL.is_some(),
-                "Both CLOUDFLARE_TURNSTILE_URL and TURNSTILE_SECRET_KEY env vars must be set for testnet deployment (--authenticated flag was set)");
+    let request_guard = if app_state.config.authenticated {
+        Some(Arc::new(
+            RequestGuard::from_config(&app_state.config).await?,
+        ))
+    } else {
+        None
+    };
 
-        (
-            CLOUDFLARE_TURNSTILE_URL.as_ref().unwrap().to_string(),
-            TURNSTILE_SECRET_KEY.as_ref().unwrap().to_string(),
-        )
+    let ledger = if let Some(url) = &app_state.config.grant_database_url {
+        if GRANT_LEDGER_API_KEY.is_none() {
+            anyhow::bail!("GRANT_LEDGER_API_KEY env var must be set to enable the grant ledger");
+        }
+
+        Some(Arc::new(
+            GrantLedger::new(url.clone(), app_state.config.grant_retention()).await?,
+        ))
     } else {
-        ("".to_string(), "".to_string())
+        None
     };
 
     // TODO: restrict access if needed
@@ -231,19 +103,11 @@ pub async fn start_faucet(
         max_request_per_second,
         wal_retry_interval,
         replenish_quota_interval_ms,
-        reset_time_interval_secs,
         rate_limiter_cleanup_interval_secs,
-        max_requests_per_ip,
+        grant_retention_days,
         ..
     } = app_state.config;
 
-    let token_manager = Arc::new(RequestsManager::new(
-        max_requests_per_ip,
-        Duration::from_secs(reset_time_interval_secs),
-        cloudflare_turnstile_url,
-        turnstile_secret_key,
-    ));
-
     let governor_cfg = Arc::new(
         GovernorConfigBuilder::default()
             .const_per_millisecond(replenish_quota_interval_ms)
@@ -258,24 +122,33 @@ pub async fn start_faucet(
     let global_limited_routes = Router::new()
         .route("/gas", post(request_gas))
         .route("/v1/gas", post(batch_request_gas))
+        .route("/v1/gas_many", post(request_gas_many))
+        .route("/v1/custom_coin", post(request_custom_coin))
         .layer(GovernorLayer {
             config: governor_cfg.clone(),
         });
 
-    // This has its own rate limiter via the RequestManager
+    // This has its own rate limiter via the RequestGuard
     let faucet_web_routes = Router::new().route("/v1/faucet_web_gas", post(batch_faucet_web_gas));
     // Routes with no rate limit
     let unrestricted_routes = Router::new()
         .route("/", get(redirect))
         .route("/health", get(health))
         .route("/v1/faucet_discord", post(batch_faucet_discord))
-        .route("/v1/status/:task_id", get(request_status));
+        .route("/v1/status/:task_id", get(request_status))
+        .route("/v1/custom_coins", get(list_custom_coins));
+
+    // Routes for auditing the faucet's usage, which are authenticated by an API key.
+    let ledger_routes = Router::new()
+        .route("/v1/grants", get(query_grants))
+        .route("/v1/grants/summary", get(summarize_grants));
 
     // Combine all routes
     let app = Router::new()
         .merge(global_limited_routes)
         .merge(unrestricted_routes)
         .merge(faucet_web_routes)
+        .merge(ledger_routes)
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(handle_error))
@@ -284,7 +157,8 @@ pub async fn start_faucet(
                 .buffer(request_buffer_size)
                 .concurrency_limit(concurrency_limit)
                 .layer(Extension(app_state.clone()))
-                .layer(Extension(token_manager.clone()))
+                .layer(Extension(request_guard.clone()))
+                .layer(Extension(ledger.clone()))
                 .layer(cors)
                 .into_inner(),
         );
@@ -298,13 +172,30 @@ pub async fn start_faucet(
         }
     });
 
-    spawn_monitored_task!(async move {
-        info!("Starting task to clear banned ip addresses.");
-        loop {
-            tokio::time::sleep(Duration::from_secs(rate_limiter_cleanup_interval_secs)).await;
-            token_manager.cleanup_expired_tokens();
-        }
-    });
+    if let Some(request_guard) = request_guard {
+        spawn_monitored_task!(async move {
+            info!("Starting task to clear expired request quotas.");
+            loop {
+                tokio::time::sleep(Duration::from_secs(rate_limiter_cleanup_interval_secs)).await;
+                if let Err(e) = request_guard.cleanup().await {
+                    warn!("Failed to clear expired request quotas: {e}");
+                }
+            }
+        });
+    }
+
+    if let Some(ledger) = ledger.filter(|_| grant_retention_days.is_some()) {
+        spawn_monitored_task!(async move {
+            info!("Starting task to prune expired grants.");
+            loop {
+                tokio::time::sleep(GRANT_PRUNE_INTERVAL).await;
+                match ledger.prune().await {
+                    Ok(pruned) => info!("Pruned {pruned} expired grants."),
+                    Err(e) => warn!("Failed to prune expired grants: {e}"),
+                }
+            }
+        });
+    }
 
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
@@ -341,6 +232,7 @@ async fn redirect(Host(host): Host) -> Response {
 async fn batch_faucet_discord(
     headers: HeaderMap,
     Extension(state): Extension<Arc<AppState>>,
+    Extension(ledger): Extension<Option<Arc<GrantLedger>>>,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     if state.config.authenticated {
@@ -375,34 +267,25 @@ async fn batch_faucet_discord(
         );
     };
 
-    batch_request_spawn_task(request, state).await
+    batch_request_spawn_task(request, None, state, ledger).await
 }
 
 /// Handler for requests coming from the frontend faucet web app.
 async fn batch_faucet_web_gas(
     headers: HeaderMap,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    Extension(token_manager): Extension<Arc<RequestsManager>>,
+    Extension(request_guard): Extension<Option<Arc<RequestGuard>>>,
     Extension(state): Extension<Arc<AppState>>,
+    Extension(ledger): Extension<Option<Arc<GrantLedger>>>,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
-    if state.config.authenticated {
-        let Some(token) = headers
-            .get("X-Turnstile-Token")
-            .and_then(|v| v.to_str().ok())
-        else {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(BatchFaucetResponse::from(
-                    FaucetError::MissingTurnstileTokenHeader,
-                )),
-            );
-        };
-
-        let validation = token_manager.validate_turnstile_token(addr, token).await;
-
-        if let Err((status_code, faucet_error)) = validation {
-            return (status_code, Json(BatchFaucetResponse::from(faucet_error)));
+    let mut identity = None;
+    if let Some(request_guard) = request_guard {
+        match request_guard.check(addr, &headers).await {
+            Ok(id) => identity = Some(id),
+            Err((status_code, faucet_error)) => {
+                return (status_code, Json(BatchFaucetResponse::from(faucet_error)));
+            }
         }
     }
 
@@ -415,28 +298,45 @@ async fn batch_faucet_web_gas(
         );
     };
 
-    batch_request_spawn_task(request, state).await
+    batch_request_spawn_task(request, identity, state, ledger).await
 }
 
 // helper method
 async fn batch_request_spawn_task(
     request: FixedAmountRequest,
+    identity: Option<String>,
     state: Arc<AppState>,
+    ledger: Option<Arc<GrantLedger>>,
 ) -> (StatusCode, Json<BatchFaucetResponse>) {
+    let recipient = request.recipient;
+    let task_state = state.clone();
     let result = spawn_monitored_task!(async move {
-        state
+        task_state
             .faucet
             .batch_send(
                 Uuid::new_v4(),
-                request.recipient,
-                &vec![state.config.amount; state.config.num_coins],
+                recipient,
+                &vec![task_state.config.amount; task_state.config.num_coins],
             )
             .await
     })
     .await
     .unwrap();
     match result {
-        Ok(v) => (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(v))),
+        Ok(v) => {
+            if let Some(ledger) = ledger {
+                match Uuid::parse_str(&v.task) {
+                    Ok(task_id) => {
+                        spawn_monitored_task!(record_batch_grants(
+                            task_id, recipient, identity, state, ledger
+                        ));
+                    }
+                    Err(e) => warn!(task = v.task, "Failed to parse batch task ID: {e}"),
+                }
+            }
+
+            (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(v)))
+        }
         Err(v) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(BatchFaucetResponse::from(v)),
@@ -444,9 +344,62 @@ async fn batch_request_spawn_task(
     }
 }
 
+/// Wait for the batch request `task_id` to be served, and record the coins it sent in the
+/// `ledger`. Gives up if the request is not served before it expires.
+async fn record_batch_grants(
+    task_id: Uuid,
+    recipient: SuiAddress,
+    identity: Option<String>,
+    state: Arc<AppState>,
+    ledger: Arc<GrantLedger>,
+) {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(state.config.ttl_expiration);
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(BATCH_STATUS_POLL_INTERVAL).await;
+        let status = match state.faucet.get_batch_send_status(task_id).await {
+            Ok(status) => status,
+            Err(e) => {
+                warn!(uuid = ?task_id, "Failed to get batch status: {e}");
+                continue;
+            }
+        };
+
+        match status.status {
+            BatchSendStatusType::INPROGRESS => continue,
+            BatchSendStatusType::DISCARDED => return,
+            BatchSendStatusType::SUCCEEDED => {
+                if let Some(receipt) = status.transferred_gas_objects {
+                    let identity = identity.as_deref();
+                    let sui = GAS::type_tag();
+                    record_grants(&ledger, task_id, recipient, identity, &sui, &receipt).await;
+                }
+                return;
+            }
+        }
+    }
+
+    warn!(uuid = ?task_id, "Batch request expired before its grants could be recorded");
+}
+
+/// Record the coins of type `coin_type` in `receipt` in the `ledger`. Failures are logged rather
+/// than returned, so that they do not fail the request.
+async fn record_grants(
+    ledger: &GrantLedger,
+    id: Uuid,
+    recipient: SuiAddress,
+    identity: Option<&str>,
+    coin_type: &TypeTag,
+    receipt: &FaucetReceipt,
+) {
+    if let Err(e) = ledger.record(recipient, identity, coin_type, receipt).await {
+        warn!(uuid = ?id, "Failed to record grants: {e}");
+    }
+}
+
 /// handler for batch_request_gas requests
 async fn batch_request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    Extension(ledger): Extension<Option<Arc<GrantLedger>>>,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     let id = Uuid::new_v4();
@@ -463,7 +416,7 @@ async fn batch_request_gas(
     };
 
     if state.config.batch_enabled {
-        batch_request_spawn_task(request, state).await
+        batch_request_spawn_task(request, None, state, ledger).await
     } else {
         // TODO (jian): remove this feature gate when batch has proven to be baked long enough
         info!(uuid = ?id, "Falling back to v1 implementation");
@@ -481,8 +434,12 @@ async fn batch_request_gas(
         .unwrap();
 
         match result {
-            Ok(_) => {
+            Ok(receipt) => {
                 info!(uuid =?id, "Request is successfully served");
+                if let Some(ledger) = ledger {
+                    let sui = GAS::type_tag();
+                    record_grants(&ledger, id, request.recipient, None, &sui, &receipt).await;
+                }
                 (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(id)))
             }
             Err(v) => {
@@ -527,6 +484,7 @@ async fn request_status(
 /// handler for all the request_gas requests
 async fn request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    Extension(ledger): Extension<Option<Arc<GrantLedger>>>,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     // ID for traceability
@@ -538,14 +496,21 @@ async fn request_gas(
             // We spawn a tokio task for this such that connection drop will not interrupt
             // it and impact the recycling of coins
             spawn_monitored_task!(async move {
-                state
+                let result = state
                     .faucet
                     .send(
                         id,
                         requests.recipient,
                         &vec![state.config.amount; state.config.num_coins],
                     )
-                    .await
+                    .await;
+
+                if let (Ok(receipt), Some(ledger)) = (&result, ledger) {
+                    let sui = GAS::type_tag();
+                    record_grants(&ledger, id, requests.recipient, None, &sui, receipt).await;
+                }
+
+                result
             })
             .await
             .unwrap()
@@ -574,6 +539,236 @@ async fn request_gas(
     }
 }
 
+/// handler for requests funding many recipients at once
+async fn request_gas_many(
+    headers: HeaderMap,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Extension(request_guard): Extension<Option<Arc<RequestGuard>>>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(ledger): Extension<Option<Arc<GrantLedger>>>,
+    Json(payload): Json<FaucetRequest>,
+) -> impl IntoResponse {
+    // ID for traceability
+    let id = Uuid::new_v4();
+    info!(uuid = ?id, "Got new multi-recipient gas request.");
+
+    // A single request can fund many recipients, so it is counted against the caller's quota, on
+    // top of the global rate limit.
+    let mut identity = None;
+    if let Some(request_guard) = request_guard {
+        match request_guard.check(addr, &headers).await {
+            Ok(checked) => identity = Some(checked),
+            Err((status_code, faucet_error)) => {
+                return (
+                    status_code,
+                    Json(MultiRecipientFaucetResponse::from(faucet_error)),
+                );
+            }
+        }
+    }
+
+    let FaucetRequest::MultiRecipientRequest(request) = payload else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(MultiRecipientFaucetResponse::from(FaucetError::Internal(
+                "Input Error.".to_string(),
+            ))),
+        );
+    };
+
+    // We spawn a tokio task for this such that connection drop will not interrupt it and impact
+    // the recycling of coins
+    let result = spawn_monitored_task!(async move {
+        let result = state
+            .faucet
+            .send_to_many(
+                id,
+                &request.recipients,
+                &vec![state.config.amount; state.config.num_coins],
+            )
+            .await;
+
+        if let (Ok(results), Some(ledger)) = (&result, ledger) {
+            let identity = identity.as_deref();
+            let sui = GAS::type_tag();
+            for (recipient, receipt) in results {
+                record_grants(&ledger, id, *recipient, identity, &sui, receipt).await;
+            }
+        }
+
+        result
+    })
+    .await
+    .unwrap();
+
+    match result {
+        Ok(v) => {
+            info!(uuid =?id, "Request is successfully served");
+            (
+                StatusCode::CREATED,
+                Json(MultiRecipientFaucetResponse::from(v)),
+            )
+        }
+        Err(v) => {
+            warn!(uuid =?id, "Failed to request gas: {:?}", v);
+            let status = match v {
+                FaucetError::InvalidRecipients(_) => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(MultiRecipientFaucetResponse::from(v)))
+        }
+    }
+}
+
+/// handler for requests for custom coins
+async fn request_custom_coin(
+    headers: HeaderMap,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Extension(request_guard): Extension<Option<Arc<RequestGuard>>>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(ledger): Extension<Option<Arc<GrantLedger>>>,
+    Json(payload): Json<FaucetRequest>,
+) -> impl IntoResponse {
+    // ID for traceability
+    let id = Uuid::new_v4();
+    info!(uuid = ?id, "Got new custom coin request.");
+
+    let mut identity = None;
+    if let Some(request_guard) = request_guard {
+        match request_guard.check(addr, &headers).await {
+            Ok(checked) => identity = Some(checked),
+            Err((status_code, faucet_error)) => {
+                return (status_code, Json(FaucetResponse::from(faucet_error)));
+            }
+        }
+    }
+
+    let FaucetRequest::CustomCoinRequest(request) = payload else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(FaucetResponse::from(FaucetError::Internal(
+                "Input Error.".to_string(),
+            ))),
+        );
+    };
+
+    // We spawn a tokio task for this such that connection drop will not interrupt it and impact
+    // the recycling of coins
+    let result = spawn_monitored_task!(async move {
+        let result = state
+            .faucet
+            .send_custom_coin(id, request.recipient, &request.coin_type)
+            .await;
+
+        // The coin type parses if the request was served.
+        let coin_type = parse_sui_type_tag(&request.coin_type).ok();
+        if let (Ok(receipt), Some(ledger), Some(coin_type)) = (&result, ledger, coin_type) {
+            let identity = identity.as_deref();
+            record_grants(
+                &ledger,
+                id,
+                request.recipient,
+                identity,
+                &coin_type,
+                receipt,
+            )
+            .await;
+        }
+
+        result
+    })
+    .await
+    .unwrap();
+
+    match result {
+        Ok(v) => {
+            info!(uuid =?id, "Request is successfully served");
+            (StatusCode::CREATED, Json(FaucetResponse::from(v)))
+        }
+        Err(v) => {
+            warn!(uuid =?id, "Failed to request custom coin: {:?}", v);
+            let status = match v {
+                FaucetError::UnknownCoinType(_) => StatusCode::BAD_REQUEST,
+                FaucetError::DailyCapExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(FaucetResponse::from(v)))
+        }
+    }
+}
+
+/// handler for listing the custom coins the faucet serves
+async fn list_custom_coins(
+    Extension(state): Extension<Arc<AppState>>,
+) -> Json<Vec<CustomCoinStatus>> {
+    Json(state.faucet.custom_coins())
+}
+
+/// handler for querying the grants recorded in the ledger
+async fn query_grants(
+    headers: HeaderMap,
+    Extension(ledger): Extension<Option<Arc<GrantLedger>>>,
+    Query(query): Query<GrantQuery>,
+) -> Result<Json<GrantPage>, (StatusCode, String)> {
+    let ledger = check_ledger_access(&headers, ledger, GRANT_LEDGER_API_KEY.as_deref())?;
+    check_coin_type(query.coin_type.as_deref())?;
+    let page = ledger
+        .query(&query)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(page))
+}
+
+/// handler for summarizing the grants recorded in the ledger
+async fn summarize_grants(
+    headers: HeaderMap,
+    Extension(ledger): Extension<Option<Arc<GrantLedger>>>,
+    Query(query): Query<SummaryQuery>,
+) -> Result<Json<GrantSummary>, (StatusCode, String)> {
+    let ledger = check_ledger_access(&headers, ledger, GRANT_LEDGER_API_KEY.as_deref())?;
+    check_coin_type(query.coin_type.as_deref())?;
+    let summary = ledger
+        .summary(&query)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(summary))
+}
+
+/// Reject coin type filters that are not valid type tags as bad requests, rather than failing the
+/// query.
+fn check_coin_type(coin_type: Option<&str>) -> Result<(), (StatusCode, String)> {
+    match coin_type.map(parse_sui_type_tag) {
+        Some(Err(e)) => Err((StatusCode::BAD_REQUEST, format!("Invalid coin type: {e}"))),
+        _ => Ok(()),
+    }
+}
+
+/// Check that the ledger is enabled, and that the request carries its API key, `key`, as a bearer
+/// token.
+fn check_ledger_access(
+    headers: &HeaderMap,
+    ledger: Option<Arc<GrantLedger>>,
+    key: Option<&str>,
+) -> Result<Arc<GrantLedger>, (StatusCode, String)> {
+    let (Some(ledger), Some(key)) = (ledger, key) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            "Grant ledger is not enabled".to_string(),
+        ));
+    };
+
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    if token != Some(key) {
+        return Err((StatusCode::UNAUTHORIZED, "Invalid API key".to_string()));
+    }
+
+    Ok(ledger)
+}
+
 pub fn create_wallet_context(
     timeout_secs: u64,
     config_dir: PathBuf,
@@ -601,229 +796,120 @@ async fn handle_error(error: BoxError) -> impl IntoResponse {
     )
 }
 
-/// Format seconds to human readable format.
-fn secs_to_human_readable(seconds: u64) -> String {
-    let hours = seconds / 3600;
-    let minutes = (seconds % 3600) / 60;
-    let seconds = seconds % 60;
-
-    if hours > 0 {
-        format!("{}h {}m {}s", hours, minutes, seconds)
-    } else if minutes > 0 {
-        format!("{}m {}s", minutes, seconds)
-    } else {
-        format!("{}s", seconds)
-    }
-}
-
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use serde_json::json;
-    use std::net::{IpAddr, Ipv4Addr};
-    use std::time::Duration;
-    use wiremock::matchers::{method, path};
-    use wiremock::{Mock, MockServer, ResponseTemplate};
-
-    const MAX_REQUESTS_PER_IP: u64 = 3;
-    const RESET_TIME_INTERVAL: Duration = Duration::from_secs(5);
-
-    async fn setup_mock_cloudflare() -> MockServer {
-        let mock_server = MockServer::start().await;
-
-        Mock::given(method("POST"))
-            .respond_with(
-                ResponseTemplate::new(200)
-                    .set_body_json(json!({ "success": true, "error-codes": [] })),
-            )
-            .mount(&mock_server)
-            .await;
+    use axum::http::HeaderValue;
+    use sui_pg_db::temp::TempDb;
 
-        mock_server
-    }
+    use super::*;
 
-    #[tokio::test]
-    async fn test_token_validation_and_limits() {
-        // Start mock server
-        let mock_server = setup_mock_cloudflare().await;
-        let manager = RequestsManager::new(
-            MAX_REQUESTS_PER_IP,
-            RESET_TIME_INTERVAL,
-            mock_server.uri(),
-            "test_secret".to_string(),
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
         );
-        let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
-        let token = "test_token";
-
-        // First request should succeed
-        let result = manager.validate_turnstile_token(ip, token).await;
-        assert!(result.is_ok());
+        headers
+    }
 
-        // Use up remaining requests
-        for _ in 1..manager.max_requests_per_ip {
-            let result = manager.validate_turnstile_token(ip, token).await;
-            assert!(result.is_ok());
+    fn status<T>(result: Result<T, (StatusCode, String)>) -> StatusCode {
+        match result {
+            Ok(_) => StatusCode::OK,
+            Err((status, _)) => status,
         }
-
-        // Next request should fail due to limit
-        let result = manager.validate_turnstile_token(ip, token).await;
-        assert!(result.is_err());
     }
 
     #[tokio::test]
-    async fn test_token_reset_after_interval() {
-        let mock_server = setup_mock_cloudflare().await;
-        let manager = RequestsManager::new(
-            MAX_REQUESTS_PER_IP,
-            RESET_TIME_INTERVAL,
-            mock_server.uri(),
-            "test_secret".to_string(),
+    async fn test_ledger_access() {
+        let db = TempDb::new().unwrap();
+        let ledger = Arc::new(
+            GrantLedger::new(db.database().url().clone(), None)
+                .await
+                .unwrap(),
         );
 
-        let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
-        let token = "test_token";
-
-        // Use up all requests
-        for _ in 0..manager.max_requests_per_ip {
-            let result = manager.validate_turnstile_token(ip, token).await;
-            assert!(result.is_ok());
-        }
-
-        // Try one more, it should fail
-        let result = manager.validate_turnstile_token(ip, token).await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().0 == StatusCode::TOO_MANY_REQUESTS);
-        assert!(!manager.data.is_empty());
-
-        tokio::time::sleep(RESET_TIME_INTERVAL + Duration::from_secs(3)).await;
-        // Trigger cleanup
-        manager.cleanup_expired_tokens();
-
-        // Should be able to make new requests
-        let result = manager.validate_turnstile_token(ip, token).await;
-        assert!(result.is_ok());
-    }
-
-    #[tokio::test]
-    async fn test_invalid_token_response() {
-        let mock_server = MockServer::start().await;
-
-        // Setup mock for invalid token
-        Mock::given(method("POST"))
-            .and(path("/siteverify"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "success": false,
-                "error-codes": ["invalid-input-response"]
-            })))
-            .mount(&mock_server)
-            .await;
+        let access = |headers: HeaderMap, ledger: Option<Arc<GrantLedger>>, key: Option<&str>| {
+            status(check_ledger_access(&headers, ledger, key))
+        };
 
-        let manager = RequestsManager::new(
-            MAX_REQUESTS_PER_IP,
-            RESET_TIME_INTERVAL,
-            mock_server.uri(),
-            "test_secret".to_string(),
+        let some = || Some(ledger.clone());
+        assert_eq!(access(bearer("key"), some(), Some("key")), StatusCode::OK);
+        assert_eq!(
+            access(bearer("wrong"), some(), Some("key")),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            access(HeaderMap::new(), some(), Some("key")),
+            StatusCode::UNAUTHORIZED
         );
-        let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
-        let token = "invalid_token";
 
-        let result = manager.validate_turnstile_token(ip, token).await;
-        assert!(result.is_err());
+        // The routes are hidden unless both the ledger and its API key are configured.
+        assert_eq!(
+            access(bearer("key"), None, Some("key")),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(access(bearer("key"), some(), None), StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn test_concurrent_ip_requests() {
-        let mock_server = setup_mock_cloudflare().await;
-        let manager = Arc::new(RequestsManager::new(
-            MAX_REQUESTS_PER_IP,
-            RESET_TIME_INTERVAL,
-            mock_server.uri(),
-            "test_secret".to_string(),
-        ));
-
-        // Create 10 different IP addresses
-        let ips: Vec<SocketAddr> = (0..10)
-            .map(|i| SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, i as u8)), 8080))
-            .collect();
-
-        let token = "test_token";
-
-        // Spawn tasks for each IP to make requests concurrently
-        let mut handles = vec![];
-
-        for (idx, &ip) in ips.iter().enumerate() {
-            let manager = manager.clone();
-            let handle = tokio::spawn(async move {
-                // Add some random delay to simulate real-world conditions
-                tokio::time::sleep(Duration::from_millis(idx as u64 * 50)).await;
-
-                let mut results = vec![];
-                // Each IP tries to make MAX_REQUESTS_PER_IP + 1 requests
-                for _ in 0..=MAX_REQUESTS_PER_IP {
-                    let result = manager.validate_turnstile_token(ip, token).await;
-                    results.push(result);
-                }
-                (ip, results)
-            });
-            handles.push(handle);
-        }
-
-        // Wait for all tasks to complete and check results
-        let all_results = futures::future::join_all(handles).await;
-
-        for result in all_results {
-            let (ip, results) = result.unwrap();
-
-            // First MAX_REQUESTS_PER_IP requests should succeed
-            for (idx, _) in results
-                .iter()
-                .enumerate()
-                .take(MAX_REQUESTS_PER_IP as usize)
-            {
-                assert!(
-                    results[idx].is_ok(),
-                    "Request {} for IP {} should succeed",
-                    idx,
-                    ip
-                );
-            }
+    async fn test_record_grants() {
+        let db = TempDb::new().unwrap();
+        let ledger = Arc::new(
+            GrantLedger::new(db.database().url().clone(), None)
+                .await
+                .unwrap(),
+        );
 
-            // The last request (MAX_REQUESTS_PER_IP + 1) should fail
-            assert!(
-                results[MAX_REQUESTS_PER_IP as usize].is_err(),
-                "Request {} for IP {} should fail",
-                MAX_REQUESTS_PER_IP,
-                ip
-            );
-        }
+        let recipient = SuiAddress::random_for_testing_only();
+        let receipt = FaucetReceipt {
+            sent: (1..=3)
+                .map(|amount| crate::CoinInfo {
+                    amount,
+                    id: sui_types::base_types::ObjectID::random(),
+                    transfer_tx_digest: sui_types::digests::TransactionDigest::random(),
+                })
+                .collect(),
+        };
 
-        // Verify the data in the DashMap
-        assert_eq!(manager.data.len(), 10, "Should have 10 IPs in the map");
+        let usdc = parse_sui_type_tag("0x123::usdc::USDC").unwrap();
+        record_grants(
+            &ledger,
+            Uuid::new_v4(),
+            recipient,
+            Some("id"),
+            &usdc,
+            &receipt,
+        )
+        .await;
 
-        for info in manager.data.iter() {
-            assert_eq!(
-                info.requests_used, MAX_REQUESTS_PER_IP,
-                "Each IP should have used exactly MAX_REQUESTS_PER_IP requests"
-            );
-        }
+        let page = ledger
+            .query(&GrantQuery {
+                recipient: Some(recipient),
+                limit: Some(2),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(page.grants.len(), 2);
+        assert!(page.next_cursor.is_some());
+        assert!(page
+            .grants
+            .iter()
+            .all(|g| g.recipient == recipient && g.identity.as_deref() == Some("id")));
+        assert!(page
+            .grants
+            .iter()
+            .all(|g| g.coin_type == usdc.to_canonical_string(true)));
     }
 
     #[test]
-    fn test_secs_to_human_readable() {
-        // Test seconds only
-        assert_eq!(secs_to_human_readable(45), "45s");
-        assert_eq!(secs_to_human_readable(1), "1s");
-
-        // Test minutes and seconds
-        assert_eq!(secs_to_human_readable(65), "1m 5s");
-        assert_eq!(secs_to_human_readable(3599), "59m 59s");
-
-        // Test hours, minutes, and seconds
-        assert_eq!(secs_to_human_readable(3600), "1h 0m 0s");
-        assert_eq!(secs_to_human_readable(3661), "1h 1m 1s");
-        assert_eq!(secs_to_human_readable(7384), "2h 3m 4s");
-
-        // Test edge case
-        assert_eq!(secs_to_human_readable(0), "0s");
+    fn test_check_coin_type() {
+        assert!(check_coin_type(None).is_ok());
+        assert!(check_coin_type(Some("0x2::sui::SUI")).is_ok());
+        assert_eq!(
+            status(check_coin_type(Some("not a type"))),
+            StatusCode::BAD_REQUEST
+        );
     }
 }
@@ -13,12 +13,11 @@ use crate::types::sui_address::SuiAddress;
 use crate::types::type_filter::ExactTypeFilter;
 use async_graphql::*;
 use im::hashmap::HashMap as ImHashMap;
-use shared_crypto::intent::{
-    AppId, Intent, IntentMessage, IntentScope, IntentVersion, PersonalMessage,
-};
+use shared_crypto::intent::{Intent, IntentMessage};
 use sui_types::authenticator_state::{ActiveJwk, AuthenticatorStateInner};
 use sui_types::crypto::ToFromBytes;
 use sui_types::dynamic_field::{DynamicFieldType, Field};
+use sui_types::personal_message::personal_message_intent;
 use sui_types::signature::GenericSignature;
 use sui_types::signature::VerifyParams;
 use sui_types::signature_verification::VerifiedDigestCache;
@@ -147,15 +146,7 @@ pub(crate) async fn verify_zklogin_signature(
             }
         }
         ZkLoginIntentScope::PersonalMessage => {
-            let data = PersonalMessage { message: bytes };
-            let intent_msg = IntentMessage::new(
-                Intent {
-                    scope: IntentScope::PersonalMessage,
-                    version: IntentVersion::V0,
-                    app_id: AppId::Sui,
-                },
-                data,
-            );
+            let intent_msg = personal_message_intent(bytes);
 
             let sig = GenericSignature::ZkLoginAuthenticator(zklogin_sig);
             match sig.verify_authenticator(
@@ -50,3 +50,22 @@ public fun add_domain(
     forward_registry.add(domain, name_record);
     target_address.do!(|t| reverse_registry.add(t, domain));
 }
+
+public fun set_data(
+    forward_registry: &mut Table<Domain, NameRecord>,
+    labels: vector<String>,
+    key: String,
+    value: String,
+) {
+    let name_record = forward_registry.borrow_mut(domain::new(labels));
+    name_record.data.insert(key, value);
+}
+
+/// A stand-in for an NFT that a name's owner can use as its avatar.
+public struct Avatar has key, store {
+    id: UID,
+}
+
+public fun mint_avatar(recipient: address, ctx: &mut TxContext) {
+    transfer::public_transfer(Avatar { id: object::new(ctx) }, recipient)
+}
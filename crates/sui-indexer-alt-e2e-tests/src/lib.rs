@@ -10,7 +10,7 @@ use std::{
 use anyhow::{bail, Context};
 use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
 use diesel_async::RunQueryDsl;
-use simulacrum::Simulacrum;
+use simulacrum::{Simulacrum, SimulatorStore};
 use sui_indexer_alt::{config::IndexerConfig, setup_indexer};
 use sui_indexer_alt_framework::{ingestion::ClientArgs, schema::watermarks, IndexerArgs};
 use sui_indexer_alt_jsonrpc::{
@@ -22,6 +22,7 @@ use sui_pg_db::{
 };
 use sui_types::{
     base_types::{ObjectRef, SuiAddress},
+    committee::EpochId,
     crypto::AccountKeyPair,
     effects::{TransactionEffects, TransactionEffectsAPI},
     error::ExecutionError,
@@ -174,6 +175,23 @@ impl FullCluster {
         self.executor.advance_clock(duration)
     }
 
+    /// Close the current epoch, creating its final checkpoint (including any transactions executed
+    /// since the last checkpoint), and wait for the off-chain services to ingest it. Returns the
+    /// new epoch.
+    pub async fn advance_epoch(&mut self) -> EpochId {
+        self.executor.advance_epoch(/* create_random_state */ false);
+
+        let checkpoint = SimulatorStore::get_highest_checkpint(self.executor.store())
+            .expect("Advancing the epoch creates a checkpoint");
+
+        self.offchain
+            .wait_for_checkpoint(checkpoint.sequence_number, Duration::from_secs(10))
+            .await
+            .expect("Timed out waiting for a checkpoint");
+
+        checkpoint.epoch + 1
+    }
+
     /// Create a new checkpoint containing the transactions executed since the last checkpoint that
     /// was created, and wait for the off-chain services to ingest it. Returns the checkpoint
     /// contents.
@@ -289,6 +307,8 @@ impl OffchainCluster {
             rpc_args,
             system_package_task_args,
             rpc_config,
+            None,
+            true,
             registry,
             cancel.child_token(),
         )
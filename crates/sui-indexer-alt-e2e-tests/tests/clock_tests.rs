@@ -0,0 +1,44 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for controlling time in a simulated network: Advancing the clock and the epoch on demand,
+//! so that time-dependent logic can be tested deterministically.
+
+use std::time::Duration;
+
+use sui_indexer_alt_e2e_tests::FullCluster;
+
+#[tokio::test]
+async fn test_advance_clock() {
+    let mut cluster = FullCluster::new().await.unwrap();
+    let before = cluster.create_checkpoint().await;
+
+    cluster.advance_clock(Duration::from_secs(60 * 60));
+    let after = cluster.create_checkpoint().await;
+
+    assert_eq!(after.timestamp_ms, before.timestamp_ms + 60 * 60 * 1000);
+    assert_eq!(after.epoch, before.epoch);
+
+    cluster.stopped().await;
+}
+
+#[tokio::test]
+async fn test_advance_epoch() {
+    let mut cluster = FullCluster::new().await.unwrap();
+    let before = cluster.create_checkpoint().await;
+
+    cluster.advance_clock(Duration::from_secs(60));
+    let epoch = cluster.advance_epoch().await;
+    assert_eq!(epoch, before.epoch + 1);
+
+    // The next checkpoint belongs to the new epoch, and carries on from the advanced clock.
+    let after = cluster.create_checkpoint().await;
+    assert_eq!(after.epoch, epoch);
+    assert_eq!(after.timestamp_ms, before.timestamp_ms + 60 * 1000);
+    assert_eq!(
+        cluster.latest_checkpoint().await.unwrap(),
+        Some(after.sequence_number)
+    );
+
+    cluster.stopped().await;
+}
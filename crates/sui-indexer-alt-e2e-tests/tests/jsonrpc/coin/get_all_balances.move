@@ -103,3 +103,21 @@ module Test::real {
   "method": "suix_getAllBalances",
   "params": ["@{B}"]
 }
+
+//# run-jsonrpc
+{
+  "method": "suix_getBalance",
+  "params": ["@{A}", "@{Test}::fake::FAKE"]
+}
+
+//# run-jsonrpc
+{
+  "method": "suix_getBalance",
+  "params": ["@{B}"]
+}
+
+//# run-jsonrpc
+{
+  "method": "suix_getBalance",
+  "params": ["@{B}", "@{Test}::real::REAL"]
+}
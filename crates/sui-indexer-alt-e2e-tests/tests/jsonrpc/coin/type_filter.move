@@ -65,3 +65,15 @@ module Test::fake {
   "method": "suix_getCoins",
   "params": ["@{B}", "@{Test}::fake::FAKE", null, 1]
 }
+
+//# run-jsonrpc
+{
+  "method": "suix_getAllCoins",
+  "params": ["@{A}", null, 3]
+}
+
+//# run-jsonrpc
+{
+  "method": "suix_getAllCoins",
+  "params": ["@{A}", "LAf1ziBqof/7k5NcqufzUSO/RxFzvCtC93xmoDUEMGDAcQRmYWtlBEZBS0UAIEMsfIfGCbXJ5M8ggWpNKTuhH9bBNhOhP/zEsXIgd5ByAQAAAAAAAAADAAAAAAAAAA=="]
+}
@@ -49,3 +49,9 @@
   "method": "suix_getLatestSuiSystemState",
   "params": []
 }
+
+//# run-jsonrpc
+{
+  "method": "suix_getValidatorsApy",
+  "params": []
+}
@@ -13,6 +13,10 @@
 //  8. Attempting to get a function from a move object (not a package)
 //  9. Attempting to get a function from a module that doesn't exist.
 // 10. Attempting to get a function that doesn't exist in its module.
+// 11. A struct introduced in v2 of a package.
+// 12. Attempting to get a struct that doesn't exist in its module.
+// 13. Attempting to get a module that doesn't exist.
+// 14. The argument types of a function, classified as pure values or objects.
 
 //# publish --upgradeable --sender A
 module P::M {
@@ -111,3 +115,27 @@ module P::M {
   "method": "sui_getNormalizedMoveFunction",
   "params": ["@{P}", "M", "qux"]
 }
+
+//# run-jsonrpc
+{
+  "method": "sui_getNormalizedMoveStruct",
+  "params": ["@{P}", "M", "O2"]
+}
+
+//# run-jsonrpc
+{
+  "method": "sui_getNormalizedMoveStruct",
+  "params": ["@{P}", "M", "O3"]
+}
+
+//# run-jsonrpc
+{
+  "method": "sui_getNormalizedMoveModule",
+  "params": ["@{P}", "N"]
+}
+
+//# run-jsonrpc
+{
+  "method": "sui_getMoveFunctionArgTypes",
+  "params": ["@{P}", "M", "foo"]
+}
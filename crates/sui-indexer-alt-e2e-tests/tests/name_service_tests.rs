@@ -19,7 +19,7 @@ use sui_indexer_alt_jsonrpc::{
 use sui_move_build::BuildConfig;
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
-    effects::TransactionEffectsAPI,
+    effects::{TransactionEffects, TransactionEffectsAPI},
     programmable_transaction_builder::ProgrammableTransactionBuilder,
     transaction::{ObjectArg, Transaction, TransactionData},
 };
@@ -269,6 +269,242 @@ async fn test_resolve_subdomain_no_parent() {
     c.cluster.stopped().await;
 }
 
+/// A leaf nested under a sub-domain that has its own registration is controlled by that
+/// sub-domain's expiry, not the top-level domain's.
+#[tokio::test]
+async fn test_resolve_nested_subdomain() {
+    let mut c = SuiNSCluster::new().await;
+
+    let parent_nft = ObjectID::random();
+    let nft = ObjectID::random();
+    let target = SuiAddress::random_for_testing_only();
+    let parent_expiry_ms = 10000;
+    let expiry_ms = 1000;
+
+    c.add_domain(parent_nft, &["sui", "foo"], None, parent_expiry_ms)
+        .await
+        .expect("Failed to add domain");
+
+    c.add_domain(nft, &["sui", "foo", "bar"], None, expiry_ms)
+        .await
+        .expect("Failed to add subdomain");
+
+    c.add_domain(nft, &["sui", "foo", "bar", "baz"], Some(target), 0)
+        .await
+        .expect("Failed to add nested subdomain");
+
+    c.cluster.create_checkpoint().await;
+
+    assert_resolved!(target, c.resolve_address("baz.bar.foo.sui").await.unwrap());
+    assert_resolved!(target, c.resolve_address("baz.bar@foo").await.unwrap());
+
+    c.cluster.advance_clock(Duration::from_millis(expiry_ms));
+    c.cluster.create_checkpoint().await;
+
+    assert_invalid_params!(c.resolve_address("baz.bar.foo.sui").await.unwrap());
+
+    c.cluster.stopped().await;
+}
+
+/// Names deeper than can be registered are rejected.
+#[tokio::test]
+async fn test_resolve_too_deep() {
+    let mut c = SuiNSCluster::new().await;
+    c.cluster.create_checkpoint().await;
+
+    assert_invalid_params!(c
+        .resolve_address("a.b.c.d.e.f.g.h.i.foo.sui")
+        .await
+        .unwrap());
+
+    c.cluster.stopped().await;
+}
+
+/// Resolving a name's status reports its expiry, and stops resolving its address once it has
+/// expired, instead of failing.
+#[tokio::test]
+async fn test_resolve_status_expiry() {
+    let mut c = SuiNSCluster::new().await;
+
+    let nft = ObjectID::random();
+    let target = SuiAddress::random_for_testing_only();
+    let expiry_ms = 1000;
+    c.add_domain(nft, &["sui", "foo"], Some(target), expiry_ms)
+        .await
+        .expect("Failed to add domain");
+
+    c.cluster.create_checkpoint().await;
+
+    let resp = c.resolve_status("foo.sui").await.unwrap();
+    assert_eq!(
+        resp["result"],
+        json!({
+            "address": target,
+            "expirationTimestampMs": expiry_ms.to_string(),
+            "status": "active",
+        }),
+        "Unexpected response: {resp:#?}",
+    );
+
+    c.cluster.advance_clock(Duration::from_millis(expiry_ms));
+    c.cluster.create_checkpoint().await;
+
+    let resp = c.resolve_status("foo.sui").await.unwrap();
+    assert_eq!(
+        resp["result"],
+        json!({
+            "address": null,
+            "expirationTimestampMs": expiry_ms.to_string(),
+            "status": "expired",
+        }),
+        "Unexpected response: {resp:#?}",
+    );
+
+    c.cluster.stopped().await;
+}
+
+/// Names keep resolving during their grace period, after they expire.
+#[tokio::test]
+async fn test_resolve_grace_period() {
+    let expiry_ms = 1000;
+    let grace_period_ms = 10000;
+    let mut c = SuiNSCluster::new_with_grace_period(grace_period_ms).await;
+
+    let nft = ObjectID::random();
+    let target = SuiAddress::random_for_testing_only();
+    c.add_domain(nft, &["sui", "foo"], Some(target), expiry_ms)
+        .await
+        .expect("Failed to add domain");
+
+    c.add_domain(nft, &["sui", "foo", "bar"], Some(target), 0)
+        .await
+        .expect("Failed to add subdomain");
+
+    c.cluster.advance_clock(Duration::from_millis(expiry_ms));
+    c.cluster.create_checkpoint().await;
+
+    assert_resolved!(target, c.resolve_address("foo.sui").await.unwrap());
+    assert_resolved!(target, c.resolve_address("bar.foo.sui").await.unwrap());
+    let resp = c.resolve_status("bar.foo.sui").await.unwrap();
+    assert_eq!(resp["result"]["status"], "gracePeriod", "{resp:#?}");
+
+    c.cluster
+        .advance_clock(Duration::from_millis(grace_period_ms));
+    c.cluster.create_checkpoint().await;
+
+    assert_invalid_params!(c.resolve_address("foo.sui").await.unwrap());
+    assert_invalid_params!(c.resolve_address("bar.foo.sui").await.unwrap());
+
+    c.cluster.stopped().await;
+}
+
+/// A name's full record includes all of its data, and is returned even after it expires.
+#[tokio::test]
+async fn test_get_record() {
+    let mut c = SuiNSCluster::new().await;
+
+    let nft = ObjectID::random();
+    let target = SuiAddress::random_for_testing_only();
+    let expiry_ms = 1000;
+    c.add_domain(nft, &["sui", "foo"], Some(target), expiry_ms)
+        .await
+        .expect("Failed to add domain");
+
+    c.set_data(&["sui", "foo"], "content_hash", "ipfs://foo")
+        .await
+        .expect("Failed to set content hash");
+
+    c.set_data(&["sui", "foo"], "bio", "hello")
+        .await
+        .expect("Failed to set custom data");
+
+    c.cluster.advance_clock(Duration::from_millis(expiry_ms));
+    c.cluster.create_checkpoint().await;
+
+    let resp = c
+        .request("suix_getNameServiceRecord", "@foo")
+        .await
+        .unwrap();
+    assert_eq!(
+        resp["result"],
+        json!({
+            "name": "foo.sui",
+            "nftId": nft,
+            "targetAddress": target,
+            "expirationTimestampMs": expiry_ms.to_string(),
+            "status": "expired",
+            "data": {
+                "bio": "hello",
+                "content_hash": "ipfs://foo",
+            },
+        }),
+        "Unexpected response: {resp:#?}",
+    );
+
+    c.cluster.stopped().await;
+}
+
+/// A name's profile only includes its avatar if it is owned by the address the name resolves to.
+#[tokio::test]
+async fn test_get_profile() {
+    let mut c = SuiNSCluster::new().await;
+
+    let nft = ObjectID::random();
+    let target = SuiAddress::random_for_testing_only();
+    let stranger = SuiAddress::random_for_testing_only();
+    c.add_domain(nft, &["sui", "foo"], Some(target), 1000)
+        .await
+        .expect("Failed to add domain");
+
+    c.add_domain(nft, &["sui", "foo", "bar"], Some(target), 0)
+        .await
+        .expect("Failed to add subdomain");
+
+    let avatar = c.mint_avatar(target).await.expect("Failed to mint avatar");
+    let not_owned = c
+        .mint_avatar(stranger)
+        .await
+        .expect("Failed to mint avatar");
+
+    c.set_data(&["sui", "foo"], "avatar", &avatar.to_string())
+        .await
+        .expect("Failed to set avatar");
+
+    c.set_data(&["sui", "foo", "bar"], "avatar", &not_owned.to_string())
+        .await
+        .expect("Failed to set avatar");
+
+    c.set_data(&["sui", "foo", "bar"], "walrus_site_id", "0x123")
+        .await
+        .expect("Failed to set walrus site");
+
+    c.cluster.create_checkpoint().await;
+
+    let resp = c
+        .request("suix_getNameServiceProfile", "foo.sui")
+        .await
+        .unwrap();
+    assert_eq!(resp["result"]["shortName"], "@foo", "{resp:#?}");
+    assert_eq!(resp["result"]["address"], json!(target), "{resp:#?}");
+    assert_eq!(resp["result"]["avatar"], json!(avatar), "{resp:#?}");
+    assert!(
+        resp["result"]["avatarType"]
+            .as_str()
+            .is_some_and(|t| t.ends_with("::suins::Avatar")),
+        "{resp:#?}"
+    );
+
+    let resp = c
+        .request("suix_getNameServiceProfile", "bar.foo.sui")
+        .await
+        .unwrap();
+    assert_eq!(resp["result"]["shortName"], "bar@foo", "{resp:#?}");
+    assert!(resp["result"]["avatar"].is_null(), "{resp:#?}");
+    assert_eq!(resp["result"]["walrusSiteId"], "0x123", "{resp:#?}");
+
+    c.cluster.stopped().await;
+}
+
 struct SuiNSCluster {
     cluster: FullCluster,
     config: NameServiceConfig,
@@ -284,6 +520,12 @@ impl SuiNSCluster {
     /// Set-up transactions are run using a burner address that is funded by requesting gas from
     /// the executor.
     async fn new() -> Self {
+        Self::new_with_grace_period(0).await
+    }
+
+    /// Like [`SuiNSCluster::new`], but names keep resolving for `grace_period_ms` after they
+    /// expire.
+    async fn new_with_grace_period(grace_period_ms: u64) -> Self {
         // (1) Spin up the simulator to run transactions.
         let mut sim = Simulacrum::new();
 
@@ -387,9 +629,8 @@ impl SuiNSCluster {
         // (7) Configure the RPC to read from the mock SuiNS package. Everything else is configured
         // according to defaults.
         let config = NameServiceConfig {
-            package_address: package_address.into(),
-            registry_id,
-            reverse_registry_id,
+            grace_period_ms,
+            ..NameServiceConfig::new(package_address.into(), registry_id, reverse_registry_id)
         };
 
         let rpc_config = RpcConfig {
@@ -421,9 +662,6 @@ impl SuiNSCluster {
 
     /// Introduce a new domain to the registry (and the reverse registry, if it has a target
     /// address).
-    ///
-    /// Transactions are run using a burner address that is funded by requesting gas from the
-    /// executor.
     async fn add_domain(
         &mut self,
         nft: ObjectID,
@@ -431,11 +669,6 @@ impl SuiNSCluster {
         target: Option<SuiAddress>,
         expiration_timestamp_ms: u64,
     ) -> anyhow::Result<()> {
-        let (sender, kp, gas) = self
-            .cluster
-            .funded_account(DEFAULT_GAS_BUDGET)
-            .expect("failed to get account");
-
         let mut builder = ProgrammableTransactionBuilder::new();
 
         let forward_registry = builder.obj(self.forward_registry)?;
@@ -460,6 +693,77 @@ impl SuiNSCluster {
             ],
         );
 
+        self.execute(builder)
+            .await
+            .context("add domain transaction failed")?;
+
+        Ok(())
+    }
+
+    /// Set `key` to `value` in the data of an existing domain's record.
+    async fn set_data(&mut self, labels: &[&str], key: &str, value: &str) -> anyhow::Result<()> {
+        let mut builder = ProgrammableTransactionBuilder::new();
+
+        let forward_registry = builder.obj(self.forward_registry)?;
+        let labels = builder.pure(labels)?;
+        let key = builder.pure(key)?;
+        let value = builder.pure(value)?;
+
+        builder.programmable_move_call(
+            self.config.package_address.into(),
+            ident_str!("suins").to_owned(),
+            ident_str!("set_data").to_owned(),
+            vec![],
+            vec![forward_registry, labels, key, value],
+        );
+
+        self.execute(builder)
+            .await
+            .context("set data transaction failed")?;
+
+        Ok(())
+    }
+
+    /// Create an object owned by `recipient` that it can use as an avatar, returning its ID.
+    async fn mint_avatar(&mut self, recipient: SuiAddress) -> anyhow::Result<ObjectID> {
+        let mut builder = ProgrammableTransactionBuilder::new();
+
+        let recipient = builder.pure(recipient)?;
+        builder.programmable_move_call(
+            self.config.package_address.into(),
+            ident_str!("suins").to_owned(),
+            ident_str!("mint_avatar").to_owned(),
+            vec![],
+            vec![recipient],
+        );
+
+        let fx = self
+            .execute(builder)
+            .await
+            .context("mint avatar transaction failed")?;
+
+        let ((id, _, _), _) = fx
+            .created()
+            .into_iter()
+            .next()
+            .context("Couldn't find avatar")?;
+
+        Ok(id)
+    }
+
+    /// Run the transaction built by `builder`, failing if it did not succeed.
+    ///
+    /// Transactions are run using a burner address that is funded by requesting gas from the
+    /// executor.
+    async fn execute(
+        &mut self,
+        builder: ProgrammableTransactionBuilder,
+    ) -> anyhow::Result<TransactionEffects> {
+        let (sender, kp, gas) = self
+            .cluster
+            .funded_account(DEFAULT_GAS_BUDGET)
+            .expect("failed to get account");
+
         let data = TransactionData::new_programmable(
             sender,
             vec![gas],
@@ -471,19 +775,28 @@ impl SuiNSCluster {
         let (fx, _) = self
             .cluster
             .execute_transaction(Transaction::from_data_and_signer(data, vec![&kp]))
-            .expect("Failed to execute add domain transaction");
+            .expect("Failed to execute transaction");
 
-        ensure!(fx.status().is_ok(), "add domain transaction failed");
+        ensure!(fx.status().is_ok(), "transaction failed");
 
-        Ok(())
+        Ok(fx)
     }
 
     /// Send a JSON-RPC request to the cluster to resolve the given SuiNS name.
     async fn resolve_address(&self, name: &str) -> anyhow::Result<Value> {
+        self.request("suix_resolveNameServiceAddress", name).await
+    }
+
+    /// Send a JSON-RPC request to the cluster to resolve the given SuiNS name's status.
+    async fn resolve_status(&self, name: &str) -> anyhow::Result<Value> {
+        self.request("suix_resolveNameServiceStatus", name).await
+    }
+
+    async fn request(&self, method: &str, name: &str) -> anyhow::Result<Value> {
         let query = json!({
             "jsonrpc": "2.0",
             "id": 1,
-            "method": "suix_resolveNameServiceAddress",
+            "method": method,
             "params": [name],
         });
 
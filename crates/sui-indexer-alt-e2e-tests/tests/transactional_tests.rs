@@ -123,7 +123,10 @@ async fn cluster(config: &OffChainConfig) -> Arc<OffchainCluster> {
         ..Default::default()
     });
 
-    let rpc_config = RpcConfig::example();
+    // Tests observe the system state changing between consecutive requests, so it can't be served
+    // from the cache.
+    let mut rpc_config = RpcConfig::example();
+    rpc_config.governance.system_state_cache_ttl_ms = Some(0);
 
     Arc::new(
         OffchainCluster::new(
@@ -0,0 +1,10 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use sui_indexer_alt_jsonrpc::fuzz;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = fuzz::object_query(data);
+});
@@ -0,0 +1,32 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Write seed inputs for each fuzz target to `corpus/<target>/`, or to the directory passed as the
+//! first argument, where `cargo fuzz run` will pick them up.
+
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use sui_indexer_alt_jsonrpc::fuzz;
+
+fn main() -> std::io::Result<()> {
+    let root = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("corpus"));
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for (target, input) in fuzz::corpus() {
+        let dir = root.join(target);
+        fs::create_dir_all(&dir)?;
+
+        let count = counts.entry(target).or_default();
+        fs::write(dir.join(format!("seed-{count}")), input)?;
+        *count += 1;
+    }
+
+    for (target, count) in counts {
+        println!("Wrote {count} seed(s) for {target}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use sui_open_rpc::Module;
+use sui_open_rpc_macros::open_rpc;
+
+use crate::{
+    context::Context,
+    error::{InternalContext, RpcError},
+};
+
+use super::rpc_module::RpcModule;
+
+#[open_rpc(namespace = "sui", tag = "Read API")]
+#[rpc(server, namespace = "sui")]
+trait ChainApi {
+    /// Return the first four bytes of the chain's genesis checkpoint digest.
+    #[method(name = "getChainIdentifier")]
+    async fn get_chain_identifier(&self) -> RpcResult<String>;
+}
+
+pub(crate) struct Chain(pub Context);
+
+#[async_trait::async_trait]
+impl ChainApiServer for Chain {
+    async fn get_chain_identifier(&self) -> RpcResult<String> {
+        let Self(ctx) = self;
+        let chain_identifier: Result<_, RpcError> =
+            ctx.chain_identifier().await.map_err(Into::into);
+        Ok(chain_identifier
+            .with_internal_context(|| "Failed to fetch chain identifier")?
+            .to_string())
+    }
+}
+
+impl RpcModule for Chain {
+    fn schema(&self) -> Module {
+        ChainApiOpenRpc::module_doc()
+    }
+
+    fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+        self.into_rpc()
+    }
+}
@@ -3,19 +3,35 @@
 
 use anyhow::Context as _;
 
+use diesel::{ExpressionMethods, QueryDsl};
+use fastcrypto::encoding::Base64;
+use futures::future;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use sui_json_rpc_types::Checkpoint;
+use serde::{Deserialize, Serialize};
+use sui_indexer_alt_schema::schema::watermarks;
+use sui_json_rpc_types::{Checkpoint, CheckpointPage};
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
-use sui_types::sui_serde::BigInt;
+use sui_types::{
+    effects::{TransactionEffectsAPI, TransactionEvents},
+    full_checkpoint_content::{CheckpointData, CheckpointTransaction},
+    messages_checkpoint::CertifiedCheckpointSummary,
+    sui_serde::BigInt,
+    transaction::Transaction,
+};
 
 use crate::{
     context::Context,
+    data::objects::VersionedObjectKey,
     error::{invalid_params, InternalContext, RpcError},
+    paginate,
 };
 
 use super::rpc_module::RpcModule;
 
+/// The pipeline whose watermark bounds the checkpoints that can be paginated over.
+const PIPELINE: &str = "kv_checkpoints";
+
 #[open_rpc(namespace = "sui", tag = "Checkpoints API")]
 #[rpc(server, namespace = "sui")]
 trait CheckpointsApi {
@@ -26,14 +42,59 @@ trait CheckpointsApi {
         /// Checkpoint sequence number.
         seq: BigInt<u64>,
     ) -> RpcResult<Checkpoint>;
+
+    /// Return a page of checkpoints, in ascending or descending order of sequence number.
+    #[method(name = "getCheckpoints")]
+    async fn get_checkpoints(
+        &self,
+        /// An optional paging cursor. If provided, the query will start from the next item after
+        /// the specified cursor. Default to start from the first item if not specified.
+        cursor: Option<BigInt<u64>>,
+        /// Maximum item returned per page, default to the configured default page size if not
+        /// specified.
+        limit: Option<usize>,
+        /// query result ordering, default to false (ascending order), oldest record first.
+        descending_order: bool,
+    ) -> RpcResult<CheckpointPage>;
+
+    /// Return the sequence number of the latest checkpoint that has been indexed, and is visible
+    /// to this RPC.
+    #[method(name = "getLatestCheckpointSequenceNumber")]
+    async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<BigInt<u64>>;
+
+    /// Return the full contents of a checkpoint -- its summary, contents, and every transaction
+    /// in it, with their effects, events, and input and output objects -- as Base64-encoded BCS,
+    /// in the format that custom indexers read checkpoints in.
+    #[method(name = "getCheckpointData")]
+    async fn get_checkpoint_data(
+        &self,
+        /// Checkpoint sequence number.
+        seq: BigInt<u64>,
+    ) -> RpcResult<Base64>;
 }
 
 pub(crate) struct Checkpoints(pub Context);
 
-#[derive(thiserror::Error, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckpointsConfig {
+    /// The default page size limit when querying checkpoints, if none is provided.
+    pub default_page_size: usize,
+
+    /// The largest acceptable page size when querying checkpoints. Requesting a page larger than
+    /// this is a user error.
+    pub max_page_size: usize,
+}
+
+#[derive(thiserror::Error, Debug)]
 enum Error {
     #[error("Checkpoint {0} not found")]
     NotFound(u64),
+
+    #[error("Pagination issue: {0}")]
+    Pagination(#[from] paginate::Error),
+
+    #[error("Transactions or objects from checkpoint {0} have been pruned")]
+    Pruned(u64),
 }
 
 #[async_trait::async_trait]
@@ -44,6 +105,41 @@ impl CheckpointsApiServer for Checkpoints {
             format!("Failed to fetch checkpoint at sequence number {seq:?}")
         })?)
     }
+
+    async fn get_checkpoints(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> RpcResult<CheckpointPage> {
+        let Self(ctx) = self;
+        Ok(
+            checkpoints(ctx, cursor.map(|c| *c), limit, descending_order)
+                .await
+                .with_internal_context(|| "Failed to fetch checkpoints")?,
+        )
+    }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<BigInt<u64>> {
+        let Self(ctx) = self;
+        Ok(latest_checkpoint(ctx)
+            .await
+            .with_internal_context(|| "Failed to fetch latest checkpoint")?
+            .into())
+    }
+
+    async fn get_checkpoint_data(&self, seq: BigInt<u64>) -> RpcResult<Base64> {
+        let Self(ctx) = self;
+        let data = checkpoint_data(ctx, *seq).await.with_internal_context(|| {
+            format!("Failed to fetch data for checkpoint at sequence number {seq:?}")
+        })?;
+
+        let bytes = bcs::to_bytes(&data)
+            .context("Failed to serialize checkpoint data")
+            .map_err(RpcError::<Error>::from)?;
+
+        Ok(Base64::from_bytes(&bytes))
+    }
 }
 
 impl RpcModule for Checkpoints {
@@ -56,6 +152,15 @@ impl RpcModule for Checkpoints {
     }
 }
 
+impl Default for CheckpointsConfig {
+    fn default() -> Self {
+        Self {
+            default_page_size: 50,
+            max_page_size: 100,
+        }
+    }
+}
+
 /// Load a checkpoint and prepare it for presentation as a JSON-RPC response.
 async fn response(ctx: &Context, seq: u64) -> Result<Checkpoint, RpcError<Error>> {
     let (summary, contents, signature) = ctx
@@ -67,3 +172,160 @@ async fn response(ctx: &Context, seq: u64) -> Result<Checkpoint, RpcError<Error>
 
     Ok(Checkpoint::from((summary, contents, signature.signature)))
 }
+
+/// Load a checkpoint along with all its transactions and the objects they read and wrote, from the
+/// key-value store.
+async fn checkpoint_data(ctx: &Context, seq: u64) -> Result<CheckpointData, RpcError<Error>> {
+    let (summary, contents, signature) = ctx
+        .kv_loader()
+        .load_one_checkpoint(seq)
+        .await
+        .context("Failed to load checkpoint")?
+        .ok_or_else(|| invalid_params(Error::NotFound(seq)))?;
+
+    let txs = future::join_all(
+        contents
+            .iter()
+            .map(|digests| ctx.kv_loader().load_one_transaction(digests.transaction)),
+    )
+    .await;
+
+    let mut transactions = Vec::with_capacity(txs.len());
+    for tx in txs {
+        let tx = tx
+            .context("Failed to load transaction")?
+            .ok_or_else(|| invalid_params(Error::Pruned(seq)))?;
+
+        let effects = tx.effects()?;
+        let events = effects
+            .events_digest()
+            .is_some()
+            .then(|| tx.events().map(|data| TransactionEvents { data }))
+            .transpose()?;
+
+        let changes = effects.object_changes();
+        let input_keys: Vec<_> = changes
+            .iter()
+            .filter_map(|c| Some(VersionedObjectKey(c.id, c.input_version?.value())))
+            .collect();
+
+        let output_keys: Vec<_> = changes
+            .iter()
+            .filter_map(|c| Some(VersionedObjectKey(c.id, c.output_version?.value())))
+            .collect();
+
+        let objects = ctx
+            .kv_loader()
+            .load_many_objects(input_keys.iter().chain(&output_keys).copied().collect())
+            .await
+            .context("Failed to load objects")?;
+
+        let fetch = |keys: &[VersionedObjectKey]| {
+            keys.iter()
+                .map(|key| objects.get(key).cloned())
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| invalid_params(Error::Pruned(seq)))
+        };
+
+        transactions.push(CheckpointTransaction {
+            transaction: Transaction::from_generic_sig_data(tx.data()?, tx.signatures()?),
+            effects,
+            events,
+            input_objects: fetch(&input_keys)?,
+            output_objects: fetch(&output_keys)?,
+        });
+    }
+
+    Ok(CheckpointData {
+        checkpoint_summary: CertifiedCheckpointSummary::new_from_data_and_sig(summary, signature),
+        checkpoint_contents: contents,
+        transactions,
+    })
+}
+
+/// Load a page of checkpoints, starting after `cursor`, from the range of checkpoints that are
+/// available to read (checkpoints that have been pruned are skipped).
+async fn checkpoints(
+    ctx: &Context,
+    cursor: Option<u64>,
+    limit: Option<usize>,
+    descending: bool,
+) -> Result<CheckpointPage, RpcError<Error>> {
+    let config = ctx.config().checkpoints.clone();
+    let limit = limit.unwrap_or(config.default_page_size);
+    if limit > config.max_page_size {
+        return Err(invalid_params(Error::from(
+            paginate::Error::ExceededMaxPageSize {
+                requested: limit,
+                max: config.max_page_size,
+            },
+        )));
+    }
+
+    let Some((cp_lo, cp_hi)) = available_range(ctx).await? else {
+        return Ok(CheckpointPage::empty());
+    };
+
+    // Fetch one more checkpoint than requested, to detect whether there is a next page.
+    let mut seqs: Vec<u64> = if descending {
+        let hi = cursor.map_or(Some(cp_hi), |c| c.checked_sub(1));
+        match hi {
+            Some(hi) => (cp_lo..=hi.min(cp_hi)).rev().take(limit + 1).collect(),
+            None => vec![],
+        }
+    } else {
+        let lo = cursor.map_or(cp_lo, |c| c.saturating_add(1).max(cp_lo));
+        (lo..=cp_hi).take(limit + 1).collect()
+    };
+
+    let has_next_page = seqs.len() > limit;
+    seqs.truncate(limit);
+
+    let data = future::join_all(seqs.iter().map(|seq| response(ctx, *seq)))
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(CheckpointPage {
+        next_cursor: seqs.last().map(|seq| (*seq).into()),
+        data,
+        has_next_page,
+    })
+}
+
+/// The sequence number of the latest checkpoint visible to the RPC. It is an internal error for no
+/// checkpoints to have been indexed yet.
+async fn latest_checkpoint(ctx: &Context) -> Result<u64, RpcError<Error>> {
+    let (_, cp_hi) = available_range(ctx)
+        .await?
+        .context("No checkpoints have been indexed")?;
+
+    Ok(cp_hi)
+}
+
+/// The inclusive range of checkpoints that are available to read, according to the watermark for
+/// the pipeline that writes checkpoints, if it has written any.
+async fn available_range(ctx: &Context) -> Result<Option<(u64, u64)>, RpcError<Error>> {
+    use watermarks::dsl as w;
+
+    let mut conn = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to database")?;
+
+    let query = w::watermarks
+        .select((w::reader_lo, w::checkpoint_hi_inclusive))
+        .filter(w::pipeline.eq(PIPELINE));
+
+    let range: Vec<(i64, i64)> = conn
+        .results(query)
+        .await
+        .context("Failed to fetch checkpoint watermark")?;
+
+    Ok(range
+        .into_iter()
+        .next()
+        .map(|(lo, hi)| (lo as u64, hi as u64))
+        .filter(|(lo, hi)| lo <= hi))
+}
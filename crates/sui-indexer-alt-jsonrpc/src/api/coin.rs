@@ -1,31 +1,40 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
 
-use anyhow::Context as _;
+use anyhow::{bail, ensure, Context as _};
 use diesel::prelude::*;
-use diesel::sql_types::Bool;
+use diesel::sql_types::{Bool, Text};
 use futures::future;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use move_core_types::language_storage::TypeTag;
+use lru::LruCache;
+use move_core_types::{identifier::IdentStr, language_storage::TypeTag};
 use serde::{Deserialize, Serialize};
 use sui_indexer_alt_schema::objects::StoredCoinOwnerKind;
-use sui_indexer_alt_schema::schema::coin_balance_buckets;
-use sui_json_rpc_types::{Balance, Coin, Page as PageResponse};
+use sui_indexer_alt_schema::schema::{coin_balance_buckets, obj_info, sum_balances, watermarks};
+use sui_json_rpc_types::{Balance, Coin, Page as PageResponse, SuiCoinMetadata};
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
 use sui_sql_macro::sql;
 use sui_types::object::Object;
 use sui_types::{
+    balance::Supply,
     base_types::{ObjectID, SuiAddress},
-    gas_coin::GAS,
+    coin::{TreasuryCap, COIN_METADATA_STRUCT_NAME, COIN_MODULE_NAME, COIN_TREASURE_CAP_NAME},
+    gas_coin::{GAS, TOTAL_SUPPLY_MIST},
+    SUI_FRAMEWORK_ADDRESS,
 };
 
 use crate::{
     context::Context,
-    data::objects::load_latest,
+    data::{objects::load_latest, pg_reader::Connection},
     error::{invalid_params, InternalContext, RpcError},
+    move_registry::resolve_type,
     paginate::{BcsCursor, Cursor as _, Page},
 };
 
@@ -49,6 +58,29 @@ trait CoinsApi {
         limit: Option<usize>,
     ) -> RpcResult<PageResponse<Coin, String>>;
 
+    /// Return Coin objects of all types owned by an address. Coins are grouped by type, so a page
+    /// may span the end of one coin type and the start of the next.
+    #[method(name = "getAllCoins")]
+    async fn get_all_coins(
+        &self,
+        /// the owner's Sui address
+        owner: SuiAddress,
+        /// optional paging cursor
+        cursor: Option<String>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+    ) -> RpcResult<PageResponse<Coin, String>>;
+
+    /// Return the total coin balance for one coin type, owned by the address owner.
+    #[method(name = "getBalance")]
+    async fn get_balance(
+        &self,
+        /// the owner's Sui address
+        owner: SuiAddress,
+        /// optional type name for the coin, defaulting to 0x2::sui::SUI
+        coin_type: Option<String>,
+    ) -> RpcResult<Balance>;
+
     /// Return the total coin balance for all coin types, owned by the address owner.
     #[method(name = "getAllBalances")]
     async fn get_all_balances(
@@ -56,9 +88,33 @@ trait CoinsApi {
         /// the owner's Sui address
         owner: SuiAddress,
     ) -> RpcResult<Vec<Balance>>;
+
+    /// Return metadata (e.g., symbol, decimals) for a coin. Returns `null` if the coin type does
+    /// not have a `CoinMetadata` object, or it has been wrapped or deleted.
+    #[method(name = "getCoinMetadata")]
+    async fn get_coin_metadata(
+        &self,
+        /// type name for the coin (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC)
+        coin_type: String,
+    ) -> RpcResult<Option<SuiCoinMetadata>>;
+
+    /// Return the total supply for a coin. SUI's supply is fixed, and other coins' supplies are
+    /// read from their `TreasuryCap`, which must not be wrapped or deleted.
+    #[method(name = "getTotalSupply")]
+    async fn get_total_supply(
+        &self,
+        /// type name for the coin (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC)
+        coin_type: String,
+    ) -> RpcResult<Supply>;
 }
 
-pub(crate) struct Coins(pub Context, pub CoinsConfig);
+pub(crate) struct Coins(pub Context, pub CoinMetadataCache);
+
+/// Coin metadata that has already been loaded, keyed by coin type. Metadata is effectively
+/// immutable once a coin has been published, so entries are never invalidated, only evicted to
+/// make room for others.
+#[derive(Clone)]
+pub(crate) struct CoinMetadataCache(Arc<Mutex<LruCache<TypeTag, SuiCoinMetadata>>>);
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CoinsConfig {
@@ -68,6 +124,9 @@ pub struct CoinsConfig {
     /// The largest acceptable page size when querying coins. Requesting a page larger than
     /// this is a user error.
     pub max_page_size: usize,
+
+    /// The number of coin types to cache metadata for. This is only read on start-up.
+    pub metadata_cache_size: usize,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -75,19 +134,32 @@ pub(crate) enum Error {
     #[error("Pagination issue: {0}")]
     Pagination(#[from] crate::paginate::Error),
 
-    #[error("Failed to parse type {0:?}: {1}")]
-    BadType(String, anyhow::Error),
+    #[error(transparent)]
+    MoveRegistry(#[from] crate::move_registry::Error),
+
+    #[error("Cannot find the TreasuryCap for coin {0}")]
+    TreasuryCapNotFound(String),
 }
 
 #[derive(Queryable, Debug, Serialize, Deserialize)]
 #[diesel(table_name = coin_balance_buckets)]
-struct BalanceCursor {
+pub(crate) struct BalanceCursor {
     object_id: Vec<u8>,
     cp_sequence_number: u64,
     coin_balance_bucket: u64,
 }
 
-type Cursor = BcsCursor<BalanceCursor>;
+pub(crate) type Cursor = BcsCursor<BalanceCursor>;
+
+/// Cursor for paginating through coins of all types, which additionally records the coin type of
+/// the last coin on the page.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AllCoinsCursor {
+    coin_type: Vec<u8>,
+    object_id: Vec<u8>,
+    cp_sequence_number: u64,
+    coin_balance_bucket: u64,
+}
 
 #[async_trait::async_trait]
 impl CoinsApiServer for Coins {
@@ -98,18 +170,18 @@ impl CoinsApiServer for Coins {
         cursor: Option<String>,
         limit: Option<usize>,
     ) -> RpcResult<PageResponse<Coin, String>> {
+        let Self(ctx, _) = self;
+        let config = ctx.config();
+
         let coin_type_tag = if let Some(coin_type) = coin_type {
-            sui_types::parse_sui_type_tag(&coin_type)
-                .map_err(|e| invalid_params(Error::BadType(coin_type, e)))?
+            resolve_type::<Error>(ctx, &config.move_registry, &coin_type).await?
         } else {
             GAS::type_tag()
         };
 
-        let Self(ctx, config) = self;
-
         let page: Page<Cursor> = Page::from_params::<Error>(
-            config.default_page_size,
-            config.max_page_size,
+            config.coins.default_page_size,
+            config.coins.max_page_size,
             cursor,
             limit,
             None,
@@ -117,58 +189,97 @@ impl CoinsApiServer for Coins {
 
         // We get all the qualified coin ids first.
         let coin_id_page = filter_coins(ctx, owner, Some(coin_type_tag), Some(page)).await?;
+        Ok(coins_page(ctx, coin_id_page).await?)
+    }
+
+    async fn get_all_coins(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<PageResponse<Coin, String>> {
+        let Self(ctx, _) = self;
+        let config = ctx.config();
+
+        let page: Page<BcsCursor<AllCoinsCursor>> = Page::from_params::<Error>(
+            config.coins.default_page_size,
+            config.coins.max_page_size,
+            cursor,
+            limit,
+            None,
+        )?;
+
+        let coin_id_page = filter_all_coins(ctx, owner, page).await?;
+        Ok(coins_page(ctx, coin_id_page).await?)
+    }
 
-        let coin_futures = coin_id_page.data.iter().map(|id| coin_response(ctx, *id));
+    async fn get_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+    ) -> RpcResult<Balance> {
+        let Self(ctx, _) = self;
+        let config = ctx.config();
+
+        let coin_type_tag = if let Some(coin_type) = coin_type {
+            resolve_type::<Error>(ctx, &config.move_registry, &coin_type).await?
+        } else {
+            GAS::type_tag()
+        };
 
-        let coins = future::join_all(coin_futures)
+        let balances = balances(ctx, owner, Some(&coin_type_tag))
             .await
-            .into_iter()
-            .zip(coin_id_page.data)
-            .map(|(r, id)| r.with_internal_context(|| format!("Failed to get object {id}")))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(PageResponse {
-            data: coins,
-            next_cursor: coin_id_page.next_cursor,
-            has_next_page: coin_id_page.has_next_page,
-        })
+            .with_internal_context(|| format!("Failed to get balance for {owner}"))?;
+
+        // An address that owns no coins of this type has a zero balance.
+        Ok(balances.into_iter().next().unwrap_or_else(|| Balance {
+            coin_type: coin_type_tag.to_canonical_string(/* with_prefix */ true),
+            coin_object_count: 0,
+            total_balance: 0,
+            locked_balance: HashMap::new(),
+        }))
     }
 
     async fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
         let Self(ctx, _) = self;
-        let coin_ids = filter_coins(ctx, owner, None, None).await?;
-        let coin_futures = coin_ids
-            .data
-            .iter()
-            .map(|id| object_with_coin_data(ctx, *id));
-        let coins = future::join_all(coin_futures)
+        Ok(balances(ctx, owner, None)
             .await
-            .into_iter()
-            .zip(coin_ids.data)
-            .map(|(r, c)| {
-                let id = c;
-                r.with_internal_context(|| format!("Failed to get object {id}"))
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        // Using a BTreeMap so that the ordering of keys is deterministic.
-        let mut balance_map: BTreeMap<String, (usize, u128)> = BTreeMap::new();
-        for (_, coin_type, balance) in coins {
-            let entry = balance_map.entry(coin_type).or_insert((0, 0));
-            entry.0 += 1;
-            entry.1 += balance as u128;
-        }
-        let balances: Vec<Balance> = balance_map
-            .into_iter()
-            .map(|(coin_type, (coin_object_count, total_balance))| Balance {
-                coin_type,
-                coin_object_count,
-                total_balance,
-                // LockedCoin is deprecated
-                locked_balance: HashMap::new(),
-            })
-            .collect();
-        Ok(balances)
+            .with_internal_context(|| format!("Failed to get balances for {owner}"))?)
+    }
+
+    async fn get_coin_metadata(&self, coin_type: String) -> RpcResult<Option<SuiCoinMetadata>> {
+        let Self(ctx, cache) = self;
+        let config = ctx.config();
+
+        let coin_type_tag = resolve_type::<Error>(ctx, &config.move_registry, &coin_type).await?;
+        Ok(coin_metadata(ctx, cache, coin_type_tag)
+            .await
+            .with_internal_context(|| format!("Failed to get metadata for coin {coin_type}"))?)
+    }
+
+    async fn get_total_supply(&self, coin_type: String) -> RpcResult<Supply> {
+        let Self(ctx, _) = self;
+        let config = ctx.config();
+
+        let coin_type_tag = resolve_type::<Error>(ctx, &config.move_registry, &coin_type).await?;
+        Ok(total_supply(ctx, coin_type_tag)
+            .await
+            .with_internal_context(|| format!("Failed to get total supply for coin {coin_type}"))?)
+    }
+}
+
+impl CoinMetadataCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self(Arc::new(Mutex::new(LruCache::new(capacity))))
+    }
+
+    fn get(&self, coin_type: &TypeTag) -> Option<SuiCoinMetadata> {
+        self.0.lock().unwrap().get(coin_type).cloned()
+    }
+
+    fn insert(&self, coin_type: TypeTag, metadata: SuiCoinMetadata) {
+        self.0.lock().unwrap().put(coin_type, metadata);
     }
 }
 
@@ -187,6 +298,7 @@ impl Default for CoinsConfig {
         Self {
             default_page_size: 50,
             max_page_size: 100,
+            metadata_cache_size: 10_000,
         }
     }
 }
@@ -308,6 +420,142 @@ async fn filter_coins(
     })
 }
 
+/// Fetch coins of all types owned by `owner`, ordered by coin type, and then in the same order as
+/// [filter_coins] within each type.
+async fn filter_all_coins(
+    ctx: &Context,
+    owner: SuiAddress,
+    page: Page<BcsCursor<AllCoinsCursor>>,
+) -> Result<PageResponse<ObjectID, String>, RpcError<Error>> {
+    use coin_balance_buckets::dsl as cb;
+
+    let (candidates, newer) = diesel::alias!(
+        coin_balance_buckets as candidates,
+        coin_balance_buckets as newer
+    );
+
+    macro_rules! candidates {
+        ($field:ident) => {
+            candidates.field(cb::$field)
+        };
+    }
+
+    macro_rules! newer {
+        ($field:ident) => {
+            newer.field(cb::$field)
+        };
+    }
+
+    let mut query = candidates
+        .select((
+            candidates!(coin_type).assume_not_null(),
+            candidates!(object_id),
+            candidates!(cp_sequence_number),
+            candidates!(coin_balance_bucket).assume_not_null(),
+        ))
+        .left_join(
+            newer.on(candidates!(object_id)
+                .eq(newer!(object_id))
+                .and(candidates!(cp_sequence_number).lt(newer!(cp_sequence_number)))),
+        )
+        .filter(newer!(object_id).is_null())
+        .filter(candidates!(owner_kind).eq(StoredCoinOwnerKind::Fastpath))
+        .filter(candidates!(owner_id).eq(owner.to_vec()))
+        .order_by(candidates!(coin_type))
+        .then_order_by(candidates!(coin_balance_bucket).desc())
+        .then_order_by(candidates!(cp_sequence_number).desc())
+        .then_order_by(candidates!(object_id).desc())
+        .limit(page.limit + 1)
+        .into_boxed();
+
+    // Coin types are ordered ascending, but coins within a type are ordered descending, so the
+    // cursor can't be compared as a single tuple.
+    if let Some(c) = page.cursor {
+        // The predicate is parenthesized as a whole, so that its disjunction does not escape the
+        // conjunction with the other filters.
+        query = query.filter(sql!(as Bool,
+            "(\
+                candidates.coin_type > {Bytea} OR (\
+                    candidates.coin_type = {Bytea} AND \
+                    (\
+                        candidates.coin_balance_bucket, \
+                        candidates.cp_sequence_number, \
+                        candidates.object_id\
+                    ) < ({SmallInt}, {BigInt}, {Bytea})\
+                )\
+            )",
+            c.coin_type.clone(),
+            c.coin_type.clone(),
+            c.coin_balance_bucket as i16,
+            c.cp_sequence_number as i64,
+            c.object_id.clone(),
+        ));
+    }
+
+    let mut results: Vec<(Vec<u8>, Vec<u8>, i64, i16)> = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to database")?
+        .results(query)
+        .await
+        .context("Failed to query coins")?;
+
+    let has_next_page = results.len() > page.limit as usize;
+    if has_next_page {
+        results.truncate(page.limit as usize);
+    }
+
+    let next_cursor = results
+        .last()
+        .map(
+            |(coin_type, object_id, cp_sequence_number, coin_balance_bucket)| {
+                BcsCursor(AllCoinsCursor {
+                    coin_type: coin_type.clone(),
+                    object_id: object_id.clone(),
+                    cp_sequence_number: *cp_sequence_number as u64,
+                    coin_balance_bucket: *coin_balance_bucket as u64,
+                })
+                .encode()
+            },
+        )
+        .transpose()
+        .context("Failed to encode cursor")?;
+
+    let ids = results
+        .iter()
+        .map(|(_, object_id, _, _)| ObjectID::from_bytes(object_id))
+        .collect::<Result<Vec<_>, _>>()
+        .context("Failed to parse object id")?;
+
+    Ok(PageResponse {
+        data: ids,
+        next_cursor,
+        has_next_page,
+    })
+}
+
+/// Load the coins for a page of coin object IDs, preserving the page's cursor.
+async fn coins_page(
+    ctx: &Context,
+    coin_id_page: PageResponse<ObjectID, String>,
+) -> Result<PageResponse<Coin, String>, RpcError<Error>> {
+    let coin_futures = coin_id_page.data.iter().map(|id| coin_response(ctx, *id));
+
+    let coins = future::join_all(coin_futures)
+        .await
+        .into_iter()
+        .zip(coin_id_page.data)
+        .map(|(r, id)| r.with_internal_context(|| format!("Failed to get object {id}")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(PageResponse {
+        data: coins,
+        next_cursor: coin_id_page.next_cursor,
+        has_next_page: coin_id_page.has_next_page,
+    })
+}
+
 async fn coin_response(ctx: &Context, id: ObjectID) -> Result<Coin, RpcError<Error>> {
     let (object, coin_type, balance) = object_with_coin_data(ctx, id).await?;
 
@@ -343,3 +591,283 @@ async fn object_with_coin_data(
         .to_canonical_string(/* with_prefix */ true);
     Ok((object, coin_type, coin.balance.value()))
 }
+
+/// The balances of coins owned by `owner`, aggregated by coin type, optionally only for coins of
+/// type `coin_type`. Coin types that the owner holds no coins of are omitted, and balances are
+/// ordered by coin type.
+///
+/// Balances are read from the `sum_balances` summary table, which is only trusted once its pipeline
+/// has been backfilled (see [`check_sum_balances`]).
+async fn balances(
+    ctx: &Context,
+    owner: SuiAddress,
+    coin_type: Option<&TypeTag>,
+) -> Result<Vec<Balance>, RpcError<Error>> {
+    use sum_balances::dsl as b;
+
+    let mut conn = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to database")?;
+
+    check_sum_balances(&mut conn).await?;
+
+    // Balances are NUMERIC, and are read as strings to avoid pulling in support for arbitrary
+    // precision decimals.
+    let mut query = b::sum_balances
+        .select((
+            b::coin_type,
+            b::coin_count,
+            sql!(as Text, "total_balance::TEXT"),
+        ))
+        .filter(b::owner_id.eq(owner.to_vec()))
+        .into_boxed();
+
+    if let Some(coin_type) = coin_type {
+        let serialized_coin_type =
+            bcs::to_bytes(coin_type).context("Failed to serialize coin type tag")?;
+        query = query.filter(b::coin_type.eq(serialized_coin_type));
+    }
+
+    let rows: Vec<(Vec<u8>, i64, String)> = conn
+        .results(query)
+        .await
+        .context("Failed to query balances")?;
+
+    let mut balances = Vec::with_capacity(rows.len());
+    for (coin_type, coin_count, total_balance) in rows {
+        let coin_type: TypeTag =
+            bcs::from_bytes(&coin_type).context("Failed to deserialize coin type")?;
+
+        let total_balance: u128 = total_balance
+            .parse()
+            .with_context(|| format!("Failed to parse balance {total_balance:?}"))?;
+
+        balances.push(Balance {
+            coin_type: coin_type.to_canonical_string(/* with_prefix */ true),
+            coin_object_count: coin_count as usize,
+            total_balance,
+            // LockedCoin is deprecated
+            locked_balance: HashMap::new(),
+        });
+    }
+
+    balances.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+    Ok(balances)
+}
+
+/// `sum_balances` is maintained by applying each checkpoint's changes to the balances from the
+/// previous checkpoint, so it is only correct once its pipeline has indexed every checkpoint from
+/// genesis. When the pipeline is first added to an existing indexer, it is backfilled from genesis
+/// while the other pipelines carry on from where they were. Until the backfill reaches the range of
+/// checkpoints that `coin_balance_buckets` serves coins from, its balances are out of date, so they
+/// are not served.
+async fn check_sum_balances(conn: &mut Connection<'_>) -> anyhow::Result<()> {
+    use watermarks::dsl as w;
+
+    let query = w::watermarks
+        .select((w::pipeline, w::checkpoint_hi_inclusive, w::reader_lo))
+        .filter(w::pipeline.eq_any(["sum_balances", "coin_balance_buckets"]));
+
+    let watermarks: HashMap<String, (i64, i64)> = conn
+        .results(query)
+        .await
+        .context("Failed to fetch balance watermarks")?
+        .into_iter()
+        .map(|(pipeline, hi, lo)| (pipeline, (hi, lo)))
+        .collect();
+
+    let Some((balances_hi, _)) = watermarks.get("sum_balances") else {
+        bail!("Balances are unavailable: sum_balances has not been indexed");
+    };
+
+    if let Some((_, coins_lo)) = watermarks.get("coin_balance_buckets") {
+        ensure!(
+            balances_hi >= coins_lo,
+            "Balances are unavailable: sum_balances is being backfilled \
+             (at checkpoint {balances_hi}, needs to reach {coins_lo})",
+        );
+    }
+
+    Ok(())
+}
+
+/// Load the metadata for coins of type `coin_type`, from its `CoinMetadata<T>` object, unless it
+/// is already cached. Returns `None` if there is no live metadata object for the coin type. Only
+/// metadata that was found is cached, because metadata for a coin type that has not been published
+/// yet may appear later.
+async fn coin_metadata(
+    ctx: &Context,
+    cache: &CoinMetadataCache,
+    coin_type: TypeTag,
+) -> Result<Option<SuiCoinMetadata>, RpcError<Error>> {
+    if let Some(metadata) = cache.get(&coin_type) {
+        return Ok(Some(metadata));
+    }
+
+    let Some(object) = coin_object(ctx, COIN_METADATA_STRUCT_NAME, &coin_type).await? else {
+        return Ok(None);
+    };
+
+    let metadata =
+        SuiCoinMetadata::try_from(object).context("Failed to deserialize coin metadata")?;
+
+    cache.insert(coin_type, metadata.clone());
+    Ok(Some(metadata))
+}
+
+/// The total supply of coins of type `coin_type`. SUI's supply is fixed, and the supply of other
+/// coins is tracked by their `TreasuryCap<T>`.
+async fn total_supply(ctx: &Context, coin_type: TypeTag) -> Result<Supply, RpcError<Error>> {
+    if coin_type == GAS::type_tag() {
+        return Ok(Supply {
+            value: TOTAL_SUPPLY_MIST,
+        });
+    }
+
+    let Some(object) = coin_object(ctx, COIN_TREASURE_CAP_NAME, &coin_type).await? else {
+        return Err(invalid_params(Error::TreasuryCapNotFound(
+            coin_type.to_canonical_string(/* with_prefix */ true),
+        )));
+    };
+
+    let treasury_cap =
+        TreasuryCap::try_from(object).context("Failed to deserialize treasury cap")?;
+
+    Ok(treasury_cap.total_supply)
+}
+
+/// The latest version of the `0x2::coin::{name}<coin_type>` object, if there is one, and it is
+/// live.
+async fn coin_object(
+    ctx: &Context,
+    name: &IdentStr,
+    coin_type: &TypeTag,
+) -> Result<Option<Object>, RpcError<Error>> {
+    use obj_info::dsl as o;
+
+    let instantiation =
+        bcs::to_bytes(&vec![coin_type.clone()]).context("Failed to serialize coin type")?;
+
+    let query = o::obj_info
+        .select(o::object_id)
+        .filter(o::package.eq(SUI_FRAMEWORK_ADDRESS.to_vec()))
+        .filter(o::module.eq(COIN_MODULE_NAME.to_string()))
+        .filter(o::name.eq(name.to_string()))
+        .filter(o::instantiation.eq(instantiation))
+        .order_by(o::cp_sequence_number.desc())
+        .limit(1);
+
+    let ids: Vec<Vec<u8>> = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to database")?
+        .results(query)
+        .await
+        .with_context(|| format!("Failed to query for 0x2::coin::{name} object"))?;
+
+    let Some(id) = ids.first() else {
+        return Ok(None);
+    };
+
+    let id = ObjectID::from_bytes(id).context("Failed to parse object id")?;
+    Ok(load_latest(ctx, id).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::Registry;
+    use sui_indexer_alt_schema::{objects::StoredCoinBalanceBucket, MIGRATIONS};
+    use sui_pg_db::{temp::TempDb, Db, DbArgs};
+
+    use crate::{config::RpcConfig, metrics::RpcMetrics};
+
+    use super::*;
+
+    /// A context reading from `db`, and a connection pool to write test data to it with.
+    async fn context(db: &TempDb) -> (Context, Db) {
+        let url = db.database().url().clone();
+        let writer = Db::for_write(DbArgs::new_for_testing(url.clone()))
+            .await
+            .unwrap();
+        writer.run_migrations(MIGRATIONS).await.unwrap();
+
+        let (config, _) = RpcConfig::default().finish_service(true).unwrap();
+        let registry = Registry::new();
+        let ctx = Context::new(
+            DbArgs::new_for_testing(url),
+            None,
+            config,
+            RpcMetrics::new(&registry),
+            &registry,
+        )
+        .await
+        .unwrap();
+
+        (ctx, writer)
+    }
+
+    fn bucket(owner: SuiAddress, coin_type: &TypeTag, bucket: i16) -> StoredCoinBalanceBucket {
+        StoredCoinBalanceBucket {
+            object_id: ObjectID::random().to_vec(),
+            cp_sequence_number: 1,
+            owner_kind: Some(StoredCoinOwnerKind::Fastpath),
+            owner_id: Some(owner.to_vec()),
+            coin_type: Some(bcs::to_bytes(coin_type).unwrap()),
+            coin_balance_bucket: Some(bucket),
+        }
+    }
+
+    /// Page through all of `owner`'s coins, `limit` at a time.
+    async fn all_coins(ctx: &Context, owner: SuiAddress, limit: usize) -> Vec<ObjectID> {
+        let mut ids = vec![];
+        let mut cursor = None;
+        loop {
+            let page = Page::from_params::<Error>(limit, limit, cursor, None, None).unwrap();
+            let page = filter_all_coins(ctx, owner, page).await.unwrap();
+            ids.extend(page.data);
+            if !page.has_next_page {
+                return ids;
+            }
+
+            cursor = page.next_cursor;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_all_coins_cursor_stays_with_owner() {
+        let db = TempDb::new().unwrap();
+        let (ctx, writer) = context(&db).await;
+
+        let owner = SuiAddress::random_for_testing_only();
+        let other = SuiAddress::random_for_testing_only();
+        let sui = GAS::type_tag();
+
+        // Another owner holds coins of the same type, in lower buckets, which sort after the
+        // owner's coins within the type.
+        let rows = vec![
+            bucket(owner, &sui, 5),
+            bucket(owner, &sui, 3),
+            bucket(other, &sui, 2),
+            bucket(other, &sui, 1),
+        ];
+
+        let mut conn = writer.connect().await.unwrap();
+        diesel_async::RunQueryDsl::execute(
+            diesel::insert_into(coin_balance_buckets::table).values(&rows),
+            &mut conn,
+        )
+        .await
+        .unwrap();
+
+        let expect: Vec<_> = rows[..2]
+            .iter()
+            .map(|r| ObjectID::from_bytes(&r.object_id).unwrap())
+            .collect();
+
+        assert_eq!(all_coins(&ctx, owner, 1).await, expect);
+        assert_eq!(all_coins(&ctx, owner, 10).await, expect);
+    }
+}
@@ -2,16 +2,31 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{anyhow, Context as _};
+use diesel::{sql_types::Bool, BoolExpressionMethods, ExpressionMethods, JoinOnDsl, QueryDsl};
+use fastcrypto::encoding::{Base64, Encoding};
+use futures::future;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sui_indexer_alt_schema::{objects::StoredOwnerKind, schema::obj_info};
 use sui_json::SuiJsonValue;
-use sui_json_rpc_types::{SuiObjectDataOptions, SuiObjectResponse};
+use sui_json_rpc_types::{
+    DynamicFieldInfo as DynamicFieldInfoResponse, Page as PageResponse, SuiMoveValue,
+    SuiObjectDataOptions, SuiObjectResponse,
+};
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
+use sui_sql_macro::sql;
 use sui_types::{
     base_types::ObjectID,
-    dynamic_field::{derive_dynamic_field_id, DynamicFieldInfo, DynamicFieldName},
+    dynamic_field::{
+        derive_dynamic_field_id,
+        visitor::{FieldVisitor, ValueMetadata},
+        DynamicFieldInfo, DynamicFieldName, DynamicFieldType,
+    },
     error::SuiObjectResponseError,
-    object::Object,
+    object::{bounded_visitor::BoundedVisitor, Object},
     TypeTag,
 };
 use tokio::try_join;
@@ -19,7 +34,8 @@ use tokio::try_join;
 use crate::{
     context::Context,
     data::objects::load_latest,
-    error::{invalid_params, rpc_bail, RpcError},
+    error::{invalid_params, rpc_bail, InternalContext, RpcError},
+    paginate::{BcsCursor, Cursor as _, Page},
 };
 
 use super::{objects, rpc_module::RpcModule};
@@ -35,11 +51,49 @@ trait DynamicFieldsApi {
         parent_object_id: ObjectID,
         /// The Name of the dynamic field
         name: DynamicFieldName,
+        /// How the value of the name is encoded, default to JSON if not specified.
+        encoding: Option<NameEncoding>,
     ) -> RpcResult<SuiObjectResponse>;
+
+    /// Return the list of dynamic field objects owned by an object.
+    #[method(name = "getDynamicFields")]
+    async fn get_dynamic_fields(
+        &self,
+        /// The ID of the parent object
+        parent_object_id: ObjectID,
+        /// An optional paging cursor. If provided, the query will start from the next item after
+        /// the specified cursor. Default to start from the first item if not specified.
+        cursor: Option<String>,
+        /// Maximum item returned per page, default to the configured default page size if not
+        /// specified.
+        limit: Option<usize>,
+    ) -> RpcResult<PageResponse<DynamicFieldInfoResponse, String>>;
 }
 
 pub struct DynamicFields(pub Context);
 
+/// How the value of a dynamic field's name is encoded in a request.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum NameEncoding {
+    /// The value is JSON, interpreted according to the name's type.
+    #[default]
+    Json,
+
+    /// The value is a Base64 string, holding the name serialized as BCS.
+    Bcs,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DynamicFieldsConfig {
+    /// The default page size limit when querying dynamic fields, if none is provided.
+    pub default_page_size: usize,
+
+    /// The largest acceptable page size when querying dynamic fields. Requesting a page larger
+    /// than this is a user error.
+    pub max_page_size: usize,
+}
+
 #[derive(thiserror::Error, Debug)]
 enum Error {
     #[error("Bad dynamic field name: {0}")]
@@ -50,17 +104,63 @@ enum Error {
 
     #[error("Could not serialize dynamic field name as {0}: {1}")]
     TypeMismatch(TypeTag, anyhow::Error),
+
+    #[error("Could not deserialize dynamic field name as {0}: {1}")]
+    BcsMismatch(TypeTag, anyhow::Error),
+
+    #[error("Pagination issue: {0}")]
+    Pagination(#[from] crate::paginate::Error),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct FieldCursor {
+    object_id: Vec<u8>,
+    cp_sequence_number: u64,
 }
 
+type Cursor = BcsCursor<FieldCursor>;
+
 #[async_trait::async_trait]
 impl DynamicFieldsApiServer for DynamicFields {
     async fn get_dynamic_field_object(
         &self,
         parent_object_id: ObjectID,
         name: DynamicFieldName,
+        encoding: Option<NameEncoding>,
     ) -> RpcResult<SuiObjectResponse> {
         let Self(ctx) = self;
-        Ok(dynamic_field_object_response(ctx, parent_object_id, name).await?)
+        let encoding = encoding.unwrap_or_default();
+        Ok(dynamic_field_object_response(ctx, parent_object_id, name, encoding).await?)
+    }
+
+    async fn get_dynamic_fields(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<PageResponse<DynamicFieldInfoResponse, String>> {
+        let Self(ctx) = self;
+
+        let page = dynamic_field_ids(ctx, parent_object_id, cursor, limit)
+            .await
+            .with_internal_context(|| {
+                format!("Failed to fetch dynamic fields of {parent_object_id}")
+            })?;
+
+        let infos = future::join_all(page.data.iter().map(|id| dynamic_field_info(ctx, *id)))
+            .await
+            .into_iter()
+            .zip(&page.data)
+            .map(|(r, id)| {
+                r.with_internal_context(|| format!("Failed to get dynamic field info for {id}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(PageResponse {
+            data: infos.into_iter().map(Into::into).collect(),
+            next_cursor: page.next_cursor,
+            has_next_page: page.has_next_page,
+        })
     }
 }
 
@@ -74,10 +174,20 @@ impl RpcModule for DynamicFields {
     }
 }
 
+impl Default for DynamicFieldsConfig {
+    fn default() -> Self {
+        Self {
+            default_page_size: 50,
+            max_page_size: 100,
+        }
+    }
+}
+
 async fn dynamic_field_object_response(
     ctx: &Context,
     parent_object_id: ObjectID,
     name: DynamicFieldName,
+    encoding: NameEncoding,
 ) -> Result<SuiObjectResponse, RpcError<Error>> {
     let layout = ctx
         .package_resolver()
@@ -124,10 +234,30 @@ async fn dynamic_field_object_response(
             }
         })?;
 
-    let bytes = SuiJsonValue::new(name.value)
-        .map_err(|e| invalid_params(Error::BadName(e)))?
-        .to_bcs_bytes(&layout)
-        .map_err(|e| invalid_params(Error::TypeMismatch(name.type_.clone(), e)))?;
+    let bytes = match encoding {
+        NameEncoding::Json => SuiJsonValue::new(name.value)
+            .map_err(|e| invalid_params(Error::BadName(e)))?
+            .to_bcs_bytes(&layout)
+            .map_err(|e| invalid_params(Error::TypeMismatch(name.type_.clone(), e)))?,
+
+        NameEncoding::Bcs => {
+            let Value::String(encoded) = &name.value else {
+                return Err(invalid_params(Error::BadName(anyhow!(
+                    "Expected a Base64 string"
+                ))));
+            };
+
+            let bytes =
+                Base64::decode(encoded).map_err(|e| invalid_params(Error::BadName(anyhow!(e))))?;
+
+            // Check that the bytes are a valid value of the name's type, so that malformed names
+            // are reported as such, rather than as missing fields.
+            BoundedVisitor::deserialize_value(&bytes, &layout)
+                .map_err(|e| invalid_params(Error::BcsMismatch(name.type_.clone(), e)))?;
+
+            bytes
+        }
+    };
 
     let df = load_df(ctx, parent_object_id, &name.type_, &bytes);
     let dof = load_dof(ctx, parent_object_id, &name.type_, &bytes);
@@ -203,3 +333,179 @@ async fn load_dof(
         .await
         .context("Failed to load dynamic field object")?)
 }
+
+/// Fetch the IDs of a page of the `Field` objects owned by `parent_id`, in descending order of the
+/// checkpoint they were last modified at, along with a cursor pointing to the last result (if
+/// there are any results).
+async fn dynamic_field_ids(
+    ctx: &Context,
+    parent_id: ObjectID,
+    cursor: Option<String>,
+    limit: Option<usize>,
+) -> Result<PageResponse<ObjectID, String>, RpcError<Error>> {
+    use obj_info::dsl as o;
+
+    let (candidates, newer) = diesel::alias!(obj_info as candidates, obj_info as newer);
+
+    macro_rules! candidates {
+        ($($field:ident),*) => {
+            candidates.fields(($(o::$field),*))
+        };
+    }
+
+    macro_rules! newer {
+        ($($field:ident),*) => {
+            newer.fields(($(o::$field),*))
+        };
+    }
+
+    let config = ctx.config();
+    let page: Page<Cursor> = Page::from_params(
+        config.dynamic_fields.default_page_size,
+        config.dynamic_fields.max_page_size,
+        cursor,
+        limit,
+        None,
+    )?;
+
+    let mut query = candidates
+        .select(candidates!(object_id, cp_sequence_number))
+        .left_join(
+            newer.on(candidates!(object_id)
+                .eq(newer!(object_id))
+                .and(candidates!(cp_sequence_number).lt(newer!(cp_sequence_number)))),
+        )
+        .filter(newer!(object_id).is_null())
+        .filter(candidates!(owner_kind).eq(StoredOwnerKind::Object))
+        .filter(candidates!(owner_id).eq(parent_id.to_vec()))
+        .order_by(candidates!(cp_sequence_number).desc())
+        .then_order_by(candidates!(object_id).desc())
+        .limit(page.limit + 1)
+        .into_boxed();
+
+    if let Some(c) = page.cursor {
+        query = query.filter(sql!(as Bool,
+            "(candidates.cp_sequence_number, candidates.object_id) < ({BigInt}, {Bytea})",
+            c.cp_sequence_number as i64,
+            c.object_id.clone(),
+        ));
+    }
+
+    let mut results: Vec<(Vec<u8>, i64)> = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to the database")?
+        .results(query)
+        .await
+        .context("Failed to fetch object info")?;
+
+    let has_next_page = results.len() > page.limit as usize;
+    if has_next_page {
+        results.truncate(page.limit as usize);
+    }
+
+    let next_cursor = results
+        .last()
+        .map(|(o, c)| {
+            BcsCursor(FieldCursor {
+                object_id: o.clone(),
+                cp_sequence_number: *c as u64,
+            })
+            .encode()
+        })
+        .transpose()
+        .context("Failed to encode next cursor")?;
+
+    let data: Vec<ObjectID> = results
+        .into_iter()
+        .map(|(o, _)| ObjectID::from_bytes(o))
+        .collect::<Result<Vec<_>, _>>()
+        .context("Failed to deserialize Object IDs")?;
+
+    Ok(PageResponse {
+        data,
+        next_cursor,
+        has_next_page,
+    })
+}
+
+/// Load the latest version of the `Field` object with ID `field_id`, and describe the dynamic
+/// field it represents. For dynamic object fields, the ID, version, digest, and type in the
+/// description are those of the object the field points to.
+async fn dynamic_field_info(
+    ctx: &Context,
+    field_id: ObjectID,
+) -> Result<DynamicFieldInfo, RpcError<Error>> {
+    let object = load_latest(ctx, field_id)
+        .await
+        .context("Failed to load dynamic field")?
+        .context("Could not find latest content for dynamic field")?;
+
+    let Some(move_object) = object.data.try_as_move() else {
+        rpc_bail!("Dynamic field at {field_id} is not a Move Object");
+    };
+
+    let type_: TypeTag = move_object.type_().clone().into();
+    let layout = ctx
+        .package_resolver()
+        .type_layout(type_.clone())
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to resolve type layout for {}",
+                type_.to_canonical_display(/* with_prefix */ true)
+            )
+        })?;
+
+    let field = FieldVisitor::deserialize(move_object.contents(), &layout)
+        .with_context(|| format!("Failed to deserialize dynamic field at {field_id}"))?;
+
+    let name_type: TypeTag = field.name_layout.into();
+    let name_value = BoundedVisitor::deserialize_value(field.name_bytes, field.name_layout)
+        .context("Failed to deserialize dynamic field name")?;
+
+    let name = DynamicFieldName {
+        type_: name_type,
+        value: SuiMoveValue::from(name_value).to_json_value(),
+    };
+
+    let bcs_name = field.name_bytes.to_owned();
+
+    let value_metadata = field
+        .value_metadata()
+        .context("Failed to extract dynamic field value")?;
+
+    Ok(match value_metadata {
+        ValueMetadata::DynamicField(value_type) => DynamicFieldInfo {
+            name,
+            bcs_name,
+            type_: DynamicFieldType::DynamicField,
+            object_type: value_type.to_canonical_string(/* with_prefix */ true),
+            object_id: object.id(),
+            version: object.version(),
+            digest: object.digest(),
+        },
+
+        ValueMetadata::DynamicObjectField(value_id) => {
+            let value = load_latest(ctx, value_id)
+                .await
+                .context("Failed to load dynamic field object")?
+                .context("Could not find latest content for dynamic field object")?;
+
+            let Some(value_type) = value.type_() else {
+                rpc_bail!("Dynamic field object at {value_id} is not a Move Object");
+            };
+
+            DynamicFieldInfo {
+                name,
+                bcs_name,
+                type_: DynamicFieldType::DynamicObject,
+                object_type: value_type.to_canonical_string(/* with_prefix */ true),
+                object_id: value.id(),
+                version: value.version(),
+                digest: value.digest(),
+            }
+        }
+    })
+}
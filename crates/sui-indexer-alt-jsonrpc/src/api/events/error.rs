@@ -0,0 +1,22 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_types::digests::TransactionDigest;
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Transaction {0} from cursor not found")]
+    CursorNotFound(TransactionDigest),
+
+    #[error("Filter has depth {depth}, exceeding maximum {max}")]
+    FilterTooDeep { depth: usize, max: usize },
+
+    #[error("Pagination issue: {0}")]
+    Pagination(#[from] crate::paginate::Error),
+
+    #[error("Events from transaction {0} have been pruned")]
+    PrunedTransaction(TransactionDigest),
+
+    #[error("{0} filter is not supported")]
+    UnsupportedFilter(&'static str),
+}
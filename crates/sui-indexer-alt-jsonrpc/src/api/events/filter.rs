@@ -0,0 +1,508 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use anyhow::Context as _;
+use diesel::{
+    expression::is_aggregate, pg::Pg, sql_types::Bool, BoolExpressionMethods, BoxableExpression,
+    ExpressionMethods, QueryDsl,
+};
+use futures::future;
+use move_core_types::language_storage::StructTag;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sui_indexer_alt_schema::schema::ev_struct_inst;
+use sui_json_rpc_types::EventPage;
+use sui_sql_macro::sql;
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    digests::TransactionDigest,
+    event::{Event, EventID},
+    sui_serde::{BigInt, SuiStructTag},
+    Identifier,
+};
+
+use crate::{
+    data::tx_digests::TxDigestKey,
+    error::{invalid_params, RpcError},
+};
+
+use super::{error::Error, response, Context, EventsConfig};
+
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+pub(crate) enum EventFilter {
+    /// Query for events that match all of the filters. An empty list matches every event.
+    All(Vec<EventFilter>),
+    /// Query for events that match any of the filters. An empty list matches no events.
+    Any(Vec<EventFilter>),
+    /// Query by sender address.
+    Sender(SuiAddress),
+    /// Query by the digest of the transaction that emitted the event.
+    Transaction(TransactionDigest),
+    /// Query by the module that emitted the event, which may be different from the module that
+    /// defines the event's type.
+    MoveModule {
+        /// The package that contains the module.
+        package: ObjectID,
+        /// The module name.
+        #[schemars(with = "String")]
+        #[serde_as(as = "DisplayFromStr")]
+        module: Identifier,
+    },
+    /// Query by the event's type. If the type is generic and no type parameters are given, events
+    /// of every instantiation of the type match.
+    MoveEventType(
+        #[schemars(with = "String")]
+        #[serde_as(as = "SuiStructTag")]
+        StructTag,
+    ),
+    /// Query by the module that defines the event's type.
+    MoveEventModule {
+        /// The package that contains the module.
+        package: ObjectID,
+        /// The module name.
+        #[schemars(with = "String")]
+        #[serde_as(as = "DisplayFromStr")]
+        module: Identifier,
+    },
+    /// Query by the time the event was emitted. Accepted for compatibility with fullnodes, but not
+    /// supported.
+    #[serde(rename_all = "camelCase")]
+    TimeRange {
+        /// Left endpoint of the time interval, milliseconds since epoch, inclusive.
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "BigInt<u64>")]
+        start_time: u64,
+        /// Right endpoint of the time interval, milliseconds since epoch, exclusive.
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "BigInt<u64>")]
+        end_time: u64,
+    },
+}
+
+/// A boolean SQL expression over the rows of `ev_struct_inst`.
+type Condition = Box<
+    dyn BoxableExpression<ev_struct_inst::table, Pg, (), is_aggregate::Never, SqlType = Bool>
+        + Send,
+>;
+
+/// The sequence numbers of the transactions that a query refers to (by cursor, or by filter), or
+/// `None` for transactions that do not exist.
+type TxSequenceNumbers = HashMap<TransactionDigest, Option<u64>>;
+
+/// Fetch a page of events that match `filter`, starting after the event pointed to by `cursor`
+/// (exclusive).
+///
+/// Transactions that emitted matching events are found using the event lookup tables, and their
+/// events are loaded from the key-value store and filtered again, because the lookup tables only
+/// record which transactions may contain matching events, not which of their events matched.
+pub(super) async fn events(
+    ctx: &Context,
+    config: &EventsConfig,
+    filter: &EventFilter,
+    cursor: Option<EventID>,
+    limit: Option<usize>,
+    descending: bool,
+) -> Result<EventPage, RpcError<Error>> {
+    let limit = limit.unwrap_or(config.default_page_size);
+    if limit > config.max_page_size {
+        return Err(invalid_params(Error::from(
+            crate::paginate::Error::ExceededMaxPageSize {
+                requested: limit,
+                max: config.max_page_size,
+            },
+        )));
+    }
+
+    let depth = filter.depth();
+    if depth > config.max_filter_depth {
+        return Err(invalid_params(Error::FilterTooDeep {
+            depth,
+            max: config.max_filter_depth,
+        }));
+    }
+
+    filter.validate()?;
+
+    let mut digests = vec![];
+    if let Some(cursor) = &cursor {
+        digests.push(cursor.tx_digest);
+    }
+
+    filter.transactions(&mut digests);
+    let seqs = tx_sequence_numbers(ctx, digests).await?;
+
+    // Reading starts from the cursor's transaction, which may have events left to return.
+    let mut bound = match &cursor {
+        Some(cursor) => Some(
+            seqs.get(&cursor.tx_digest)
+                .copied()
+                .flatten()
+                .ok_or_else(|| invalid_params(Error::CursorNotFound(cursor.tx_digest)))?,
+        ),
+        None => None,
+    };
+
+    // One transaction for the cursor, which may have no events left to return, and one more than
+    // the limit, to detect whether there is a next page.
+    let batch = limit + 2;
+
+    // Gather one more event than requested, to detect whether there is a next page. Candidate
+    // transactions are fetched in batches until there are enough events, because filters on the
+    // emitting module are only applied per transaction by the lookup tables, so a candidate may
+    // not contain any matching events.
+    let mut matched = vec![];
+    'batches: loop {
+        let candidates = transactions(ctx, filter, &seqs, bound, batch, descending).await?;
+
+        let txs = ctx
+            .kv_loader()
+            .load_many_transactions(candidates.iter().map(|(_, digest)| *digest).collect())
+            .await
+            .context("Failed to load transactions")?;
+
+        for &(_, digest) in &candidates {
+            // Transactions found through the lookup tables must have been pruned, if they are
+            // missing.
+            let Some(tx) = txs.get(&digest) else {
+                return Err(invalid_params(Error::PrunedTransaction(digest)));
+            };
+
+            let mut events: Vec<_> = tx.events()?.into_iter().enumerate().collect();
+            if descending {
+                events.reverse();
+            }
+
+            for (ix, event) in events {
+                if let Some(cursor) = &cursor {
+                    let seq = ix as u64;
+                    if cursor.tx_digest == digest
+                        && ((descending && seq >= cursor.event_seq)
+                            || (!descending && seq <= cursor.event_seq))
+                    {
+                        continue;
+                    }
+                }
+
+                if !filter.matches(digest, &event) {
+                    continue;
+                }
+
+                matched.push((digest, ix, event, tx.timestamp_ms()));
+                if matched.len() > limit {
+                    break 'batches;
+                }
+            }
+        }
+
+        // Stop if there are no more candidates to read.
+        let (last, _) = match candidates.last() {
+            Some(last) if candidates.len() == batch => *last,
+            _ => break,
+        };
+
+        bound = if descending {
+            match last.checked_sub(1) {
+                Some(next) => Some(next),
+                None => break,
+            }
+        } else {
+            Some(last + 1)
+        };
+    }
+
+    let has_next_page = matched.len() > limit;
+    matched.truncate(limit);
+
+    let data = future::join_all(
+        matched
+            .into_iter()
+            .map(|(digest, ix, event, timestamp_ms)| {
+                response::event(ctx, digest, ix, event, Some(timestamp_ms))
+            }),
+    )
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(EventPage {
+        next_cursor: data.last().map(|e| e.id.clone()).or(cursor),
+        data,
+        has_next_page,
+    })
+}
+
+impl EventFilter {
+    /// The depth of this filter: 1 for a simple filter, and one more than the deepest filter
+    /// nested inside an `All` or `Any` filter.
+    pub(crate) fn depth(&self) -> usize {
+        match self {
+            EventFilter::All(filters) | EventFilter::Any(filters) => {
+                1 + filters.iter().map(Self::depth).max().unwrap_or(0)
+            }
+
+            EventFilter::Sender(_)
+            | EventFilter::Transaction(_)
+            | EventFilter::MoveModule { .. }
+            | EventFilter::MoveEventType(_)
+            | EventFilter::MoveEventModule { .. }
+            | EventFilter::TimeRange { .. } => 1,
+        }
+    }
+
+    /// Check that every filter nested inside this one is supported.
+    fn validate(&self) -> Result<(), RpcError<Error>> {
+        match self {
+            EventFilter::All(filters) | EventFilter::Any(filters) => {
+                filters.iter().try_for_each(Self::validate)
+            }
+
+            EventFilter::TimeRange { .. } => {
+                Err(invalid_params(Error::UnsupportedFilter("TimeRange")))
+            }
+
+            EventFilter::Sender(_)
+            | EventFilter::Transaction(_)
+            | EventFilter::MoveModule { .. }
+            | EventFilter::MoveEventType(_)
+            | EventFilter::MoveEventModule { .. } => Ok(()),
+        }
+    }
+
+    /// Gather the digests of the transactions that this filter refers to, into `digests`.
+    fn transactions(&self, digests: &mut Vec<TransactionDigest>) {
+        match self {
+            EventFilter::All(filters) | EventFilter::Any(filters) => {
+                for filter in filters {
+                    filter.transactions(digests);
+                }
+            }
+
+            EventFilter::Transaction(digest) => digests.push(*digest),
+
+            EventFilter::Sender(_)
+            | EventFilter::MoveModule { .. }
+            | EventFilter::MoveEventType(_)
+            | EventFilter::MoveEventModule { .. }
+            | EventFilter::TimeRange { .. } => {}
+        }
+    }
+
+    /// Whether `event`, emitted by the transaction with digest `digest`, matches this filter. This
+    /// is used to pick out the events that match from the transactions that the lookup tables
+    /// identified.
+    fn matches(&self, digest: TransactionDigest, event: &Event) -> bool {
+        use EventFilter as F;
+        match self {
+            F::All(filters) => filters.iter().all(|f| f.matches(digest, event)),
+
+            F::Any(filters) => filters.iter().any(|f| f.matches(digest, event)),
+
+            F::Sender(sender) => event.sender == *sender,
+
+            F::Transaction(d) => *d == digest,
+
+            F::MoveModule { package, module } => {
+                event.package_id == *package && event.transaction_module == *module
+            }
+
+            F::MoveEventType(type_) => {
+                event.type_.address == type_.address
+                    && event.type_.module == type_.module
+                    && event.type_.name == type_.name
+                    && (type_.type_params.is_empty()
+                        || event.type_.type_params == type_.type_params)
+            }
+
+            F::MoveEventModule { package, module } => {
+                ObjectID::from(event.type_.address) == *package && event.type_.module == *module
+            }
+
+            F::TimeRange { .. } => false,
+        }
+    }
+
+    /// The condition that rows in `ev_struct_inst` must satisfy for their transactions to be
+    /// candidates for containing events that match this filter. An empty `All` matches every row,
+    /// and an empty `Any` matches none.
+    fn condition(&self, seqs: &TxSequenceNumbers) -> anyhow::Result<Condition> {
+        use ev_struct_inst::dsl as e;
+        use EventFilter as F;
+
+        Ok(match self {
+            F::All(filters) => {
+                let mut condition: Condition = Box::new(sql!(as Bool, "TRUE"));
+                for filter in filters {
+                    condition = Box::new(condition.and(filter.condition(seqs)?));
+                }
+
+                condition
+            }
+
+            F::Any(filters) => {
+                let mut condition: Condition = Box::new(sql!(as Bool, "FALSE"));
+                for filter in filters {
+                    condition = Box::new(condition.or(filter.condition(seqs)?));
+                }
+
+                condition
+            }
+
+            F::Sender(sender) => Box::new(e::sender.eq(sender.to_vec())),
+
+            F::Transaction(digest) => match seqs.get(digest).copied().flatten() {
+                Some(seq) => Box::new(e::tx_sequence_number.eq(seq as i64)),
+                None => Box::new(sql!(as Bool, "FALSE")),
+            },
+
+            // The emitting module is recorded per transaction in `ev_emit_mod`, rather than per
+            // event.
+            F::MoveModule { package, module } => Box::new(sql!(as Bool,
+                "EXISTS ( \
+                    SELECT 1 FROM ev_emit_mod m \
+                    WHERE m.tx_sequence_number = ev_struct_inst.tx_sequence_number \
+                    AND m.package = {Bytea} \
+                    AND m.module = {Text} \
+                )",
+                package.to_vec(),
+                module.to_string(),
+            )),
+
+            F::MoveEventType(type_) => {
+                let mut condition: Condition = Box::new(
+                    e::package
+                        .eq(type_.address.to_vec())
+                        .and(e::module.eq(type_.module.to_string()))
+                        .and(e::name.eq(type_.name.to_string())),
+                );
+
+                // A type without type parameters matches every instantiation of the type.
+                if !type_.type_params.is_empty() {
+                    let instantiation = bcs::to_bytes(&type_.type_params)
+                        .context("Failed to serialize type parameters")?;
+                    condition = Box::new(condition.and(e::instantiation.eq(instantiation)));
+                }
+
+                condition
+            }
+
+            F::MoveEventModule { package, module } => Box::new(
+                e::package
+                    .eq(package.to_vec())
+                    .and(e::module.eq(module.to_string())),
+            ),
+
+            F::TimeRange { .. } => Box::new(sql!(as Bool, "FALSE")),
+        })
+    }
+}
+
+/// The sequence numbers and digests of at most `limit` transactions that may contain events that
+/// match `filter`, in the order they should be read in, starting from (and including) the
+/// transaction at sequence number `bound`, if there is one.
+async fn transactions(
+    ctx: &Context,
+    filter: &EventFilter,
+    seqs: &TxSequenceNumbers,
+    bound: Option<u64>,
+    limit: usize,
+    descending: bool,
+) -> Result<Vec<(u64, TransactionDigest)>, RpcError<Error>> {
+    use ev_struct_inst::dsl as e;
+
+    // Every event is recorded in `ev_struct_inst`, so it contains every transaction that emitted
+    // an event.
+    let mut query = e::ev_struct_inst
+        .select(e::tx_sequence_number)
+        .filter(filter.condition(seqs)?)
+        .distinct()
+        .limit(limit as i64)
+        .into_boxed();
+
+    if let Some(tx) = bound {
+        if descending {
+            query = query.filter(e::tx_sequence_number.le(tx as i64));
+        } else {
+            query = query.filter(e::tx_sequence_number.ge(tx as i64));
+        }
+    }
+
+    if descending {
+        query = query.order(e::tx_sequence_number.desc());
+    } else {
+        query = query.order(e::tx_sequence_number.asc());
+    }
+
+    let rows: Vec<i64> = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to the database")?
+        .results(query)
+        .await
+        .context("Failed to fetch transaction sequence numbers")?;
+
+    let digests = ctx
+        .pg_loader()
+        .load_many(rows.iter().map(|&seq| TxDigestKey(seq as u64)))
+        .await
+        .context("Failed to load transaction digests")?;
+
+    let mut data = Vec::with_capacity(rows.len());
+    for seq in rows {
+        let bytes = digests
+            .get(&TxDigestKey(seq as u64))
+            .with_context(|| format!("Missing transaction digest for transaction {seq}"))?
+            .tx_digest
+            .as_slice();
+
+        let digest = TransactionDigest::try_from(bytes)
+            .context("Failed to deserialize transaction digest")?;
+
+        data.push((seq as u64, digest));
+    }
+
+    Ok(data)
+}
+
+/// The sequence numbers of the transactions with digests `digests`, found from their positions in
+/// their checkpoints.
+async fn tx_sequence_numbers(
+    ctx: &Context,
+    digests: Vec<TransactionDigest>,
+) -> Result<TxSequenceNumbers, RpcError<Error>> {
+    let txs = ctx
+        .kv_loader()
+        .load_many_transactions(digests.clone())
+        .await
+        .context("Failed to load transactions")?;
+
+    let mut seqs = HashMap::new();
+    for digest in digests {
+        let Some(tx) = txs.get(&digest) else {
+            seqs.insert(digest, None);
+            continue;
+        };
+
+        let cp_sequence_number = tx.cp_sequence_number();
+        let (summary, contents, _) = ctx
+            .kv_loader()
+            .load_one_checkpoint(cp_sequence_number)
+            .await
+            .context("Failed to load checkpoint")?
+            .with_context(|| format!("Checkpoint {cp_sequence_number} not found"))?;
+
+        let ix = contents
+            .iter()
+            .position(|digests| digests.transaction == digest)
+            .with_context(|| format!("Transaction {digest} not found in its checkpoint"))?;
+
+        let cp_lo = summary.network_total_transactions - contents.inner().len() as u64;
+        seqs.insert(digest, Some(cp_lo + ix as u64));
+    }
+
+    Ok(seqs)
+}
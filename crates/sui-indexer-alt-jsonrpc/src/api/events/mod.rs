@@ -0,0 +1,184 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{sync::Arc, time::Duration};
+
+use futures::stream;
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+    server::PendingSubscriptionSink,
+};
+use sui_json_rpc_types::{EventFilter, EventPage, Filter, SuiEvent};
+use sui_open_rpc::Module;
+use sui_open_rpc_macros::open_rpc;
+use sui_types::event::EventID;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::debug;
+
+use crate::{context::Context, error::InternalContext, subscription::SubscriptionManager};
+
+use super::rpc_module::RpcModule;
+
+pub(crate) mod error;
+pub(crate) mod filter;
+pub(crate) mod response;
+
+#[open_rpc(namespace = "suix", tag = "Query Events API")]
+#[rpc(server, namespace = "suix")]
+trait QueryEventsApi {
+    /// Query events that match a filter (by sender, emitting module, event type, etc). Returns a
+    /// paginated list of events, in the order they were emitted.
+    ///
+    /// If a cursor is provided, the query will start from the event after the one pointed to by
+    /// this cursor, otherwise pagination starts from the first event that matches the filter. The
+    /// definition of "first" is changed by the `descending_order` parameter, which is optional,
+    /// and defaults to false, meaning that the oldest event is shown first.
+    #[method(name = "queryEvents")]
+    async fn query_events(
+        &self,
+        /// The event query criteria.
+        query: filter::EventFilter,
+        /// Cursor to start paginating from.
+        cursor: Option<EventID>,
+        /// Maximum number of events to return per page.
+        limit: Option<usize>,
+        /// Order of results, defaulting to ascending order (false), by sequence on-chain.
+        descending_order: Option<bool>,
+    ) -> RpcResult<EventPage>;
+}
+
+#[open_rpc(namespace = "suix", tag = "Events API")]
+#[rpc(server, namespace = "suix")]
+trait EventsApi {
+    /// Subscribe to a stream of events, as they are emitted by transactions in new checkpoints.
+    /// Only events that match the filter are sent to the subscriber.
+    #[subscription(name = "subscribeEvent", item = SuiEvent)]
+    fn subscribe_event(
+        &self,
+        /// The filter criteria of the event stream.
+        filter: EventFilter,
+    ) -> SubscriptionResult;
+}
+
+pub(crate) struct QueryEvents(pub Context);
+
+/// Event subscriptions, fed by the events broadcast by a [super::feed::Feed].
+pub(crate) struct Events(
+    pub SubscriptionManager,
+    pub broadcast::Sender<Arc<SuiEvent>>,
+);
+
+#[derive(Clone, Debug)]
+pub struct EventsConfig {
+    /// The default page size limit when querying events, if none is provided.
+    pub default_page_size: usize,
+
+    /// The largest acceptable page size when querying events. Requesting a page larger than this
+    /// is a user error.
+    pub max_page_size: usize,
+
+    /// The maximum depth of an events query filter, counting each level of `All` and `Any`
+    /// filters, as well as the simple filters nested inside them.
+    pub max_filter_depth: usize,
+
+    /// How long to wait between checks for new transactions, which need to be sent to transaction
+    /// subscribers, and whose events need to be sent to event subscribers.
+    pub subscription_poll_interval: Duration,
+
+    /// The maximum number of transactions to fetch for subscribers at once.
+    pub subscription_max_batch_size: usize,
+
+    /// The number of events that can be waiting to be sent to a subscriber. Subscribers that fall
+    /// further behind than this have their subscription closed. This is only read on start-up.
+    pub subscription_buffer_size: usize,
+}
+
+#[async_trait::async_trait]
+impl QueryEventsApiServer for QueryEvents {
+    async fn query_events(
+        &self,
+        query: filter::EventFilter,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<EventPage> {
+        let Self(ctx) = self;
+        let config = ctx.config();
+        Ok(filter::events(
+            ctx,
+            &config.events,
+            &query,
+            cursor,
+            limit,
+            descending_order.unwrap_or(false),
+        )
+        .await
+        .with_internal_context(|| "Failed to query events")?)
+    }
+}
+
+impl EventsApiServer for Events {
+    fn subscribe_event(
+        &self,
+        pending: PendingSubscriptionSink,
+        filter: EventFilter,
+    ) -> SubscriptionResult {
+        let Self(subscriptions, feed) = self;
+
+        let events = stream::unfold((feed.subscribe(), filter), |(mut rx, filter)| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) if filter.matches(&event) => {
+                        return Some((SuiEvent::clone(&event), (rx, filter)))
+                    }
+
+                    Ok(_) => continue,
+
+                    Err(RecvError::Lagged(skipped)) => {
+                        debug!(skipped, "Event subscriber fell behind the event feed");
+                        return None;
+                    }
+
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+
+        subscriptions.subscribe(pending, events);
+        Ok(())
+    }
+}
+
+impl RpcModule for QueryEvents {
+    fn schema(&self) -> Module {
+        QueryEventsApiOpenRpc::module_doc()
+    }
+
+    fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+        self.into_rpc()
+    }
+}
+
+impl RpcModule for Events {
+    fn schema(&self) -> Module {
+        EventsApiOpenRpc::module_doc()
+    }
+
+    fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+        self.into_rpc()
+    }
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            default_page_size: 50,
+            max_page_size: 100,
+            max_filter_depth: 3,
+            subscription_poll_interval: Duration::from_millis(500),
+            subscription_max_batch_size: 1000,
+            subscription_buffer_size: 10_000,
+        }
+    }
+}
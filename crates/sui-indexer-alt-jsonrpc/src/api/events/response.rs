@@ -0,0 +1,42 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Context as _};
+use move_core_types::annotated_value::{MoveDatatypeLayout, MoveTypeLayout};
+use sui_json_rpc_types::SuiEvent;
+use sui_types::{digests::TransactionDigest, event::Event};
+
+use crate::context::Context;
+
+/// Convert `event`, the `ix`-th event emitted by transaction `digest`, into its JSON-RPC
+/// representation, resolving its type's layout to display its contents. The timestamp is only
+/// missing for events from transactions that have not been included in a checkpoint (i.e. those
+/// from dry runs).
+pub(crate) async fn event(
+    ctx: &Context,
+    digest: TransactionDigest,
+    ix: usize,
+    event: Event,
+    timestamp_ms: Option<u64>,
+) -> anyhow::Result<SuiEvent> {
+    let layout = match ctx
+        .package_resolver()
+        .type_layout(event.type_.clone().into())
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to resolve layout for {}",
+                event.type_.to_canonical_display(/* with_prefix */ true)
+            )
+        })? {
+        MoveTypeLayout::Struct(s) => MoveDatatypeLayout::Struct(s),
+        MoveTypeLayout::Enum(e) => MoveDatatypeLayout::Enum(e),
+        _ => bail!(
+            "Event {ix} is not a struct or enum: {}",
+            event.type_.to_canonical_string(/* with_prefix */ true)
+        ),
+    };
+
+    SuiEvent::try_from(event, digest, ix as u64, timestamp_ms, layout)
+        .with_context(|| format!("Failed to convert Event {ix} into response"))
+}
@@ -0,0 +1,223 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use diesel::{ExpressionMethods, QueryDsl};
+use futures::future;
+use sui_indexer_alt_schema::schema::watermarks;
+use sui_json_rpc_types::SuiEvent;
+use sui_types::digests::TransactionDigest;
+use tokio::{sync::broadcast, task::JoinHandle, time};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    context::Context,
+    data::{kv_loader::TransactionContents, tx_digests::TxDigestKey},
+};
+
+use super::events::response;
+
+/// The pipelines whose watermarks decide which transactions are ready to be sent to subscribers:
+/// Transactions are only sent once their digests and balance changes have both been committed, so
+/// that subscribers can ask for any part of the transaction's response.
+const PIPELINES: [&str; 2] = ["tx_digests", "tx_balance_changes"];
+
+/// Background task that follows transactions as they are committed to the database, and
+/// broadcasts them to transaction subscribers, and the events they emitted to event subscribers.
+pub(crate) struct Feed {
+    /// Access to the database and package resolver.
+    context: Context,
+    /// Channel that events are broadcast on.
+    events: broadcast::Sender<Arc<SuiEvent>>,
+    /// Channel that transactions are broadcast on.
+    transactions: broadcast::Sender<Arc<TransactionContents>>,
+    /// Signal to cancel the task.
+    cancel: CancellationToken,
+}
+
+impl Feed {
+    pub(crate) fn new(context: Context, cancel: CancellationToken) -> Self {
+        let config = context.config();
+        let (events, _) = broadcast::channel(config.events.subscription_buffer_size);
+        let (transactions, _) = broadcast::channel(config.transactions.subscription_buffer_size);
+        Self {
+            context,
+            events,
+            transactions,
+            cancel,
+        }
+    }
+
+    /// The channel that events are broadcast on, for subscribers to receive events from.
+    pub(crate) fn events(&self) -> broadcast::Sender<Arc<SuiEvent>> {
+        self.events.clone()
+    }
+
+    /// The channel that transactions are broadcast on, for subscribers to receive transactions
+    /// from.
+    pub(crate) fn transactions(&self) -> broadcast::Sender<Arc<TransactionContents>> {
+        self.transactions.clone()
+    }
+
+    /// Start a new task that regularly polls the database for newly committed transactions, and
+    /// broadcasts them, and their events. The feed starts from the latest transaction at the time
+    /// it starts, and skips ahead whenever there are no subscribers.
+    ///
+    /// This operation consumes the `self` and returns a handle to the spawned tokio task. The task
+    /// will continue to run until its cancellation token is triggered.
+    pub(crate) fn run(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let Self {
+                context,
+                events,
+                transactions,
+                cancel,
+            } = self;
+
+            // The sequence number of the next transaction to broadcast.
+            let mut next_tx = None;
+
+            loop {
+                let interval = context.config().events.subscription_poll_interval;
+
+                tokio::select! {
+                    _ = cancel.cancelled() => {
+                        info!("Shutdown signal received, terminating subscription feed");
+                        break;
+                    }
+
+                    _ = time::sleep(interval) => {
+                        match poll(&context, &events, &transactions, next_tx).await {
+                            Ok(next) => next_tx = next,
+                            Err(e) => warn!("Failed to fetch transactions for subscribers: {e:#}"),
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Broadcast transactions that have been committed since `next_tx`, and their events, and return
+/// the sequence number of the transaction to continue from next time.
+async fn poll(
+    ctx: &Context,
+    events: &broadcast::Sender<Arc<SuiEvent>>,
+    transactions: &broadcast::Sender<Arc<TransactionContents>>,
+    next_tx: Option<u64>,
+) -> anyhow::Result<Option<u64>> {
+    let Some(tx_hi) = tx_hi(ctx).await? else {
+        return Ok(next_tx);
+    };
+
+    let subscribed = events.receiver_count() > 0 || transactions.receiver_count() > 0;
+
+    // The watermark could appear to go backwards if it is read from a replica that is behind the
+    // replica it was read from last time.
+    let mut next = match next_tx {
+        Some(next) if subscribed => next,
+        Some(next) => return Ok(Some(next.max(tx_hi))),
+        None => return Ok(Some(tx_hi)),
+    };
+
+    while next < tx_hi {
+        let batch_size = ctx.config().events.subscription_max_batch_size as u64;
+        let hi = tx_hi.min(next + batch_size);
+
+        let stored = ctx
+            .pg_loader()
+            .load_many((next..hi).map(TxDigestKey))
+            .await
+            .context("Failed to load transaction digests")?;
+
+        // Stop at the first transaction whose digest isn't available yet.
+        let digests = (next..hi)
+            .map_while(|seq| stored.get(&TxDigestKey(seq)))
+            .map(|s| TransactionDigest::try_from(s.tx_digest.as_slice()))
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to deserialize transaction digests")?;
+
+        let txs = future::join_all(
+            digests
+                .iter()
+                .map(|d| ctx.kv_loader().load_one_transaction(*d)),
+        )
+        .await;
+
+        for (digest, tx) in digests.into_iter().zip(txs) {
+            // The transaction may not have been written to the key-value store yet, in which case
+            // it will be sent next time.
+            let Some(tx) = tx.context("Failed to load transaction")? else {
+                return Ok(Some(next));
+            };
+
+            // Events from a transaction that cannot be converted are skipped, rather than holding
+            // up the events from every transaction after it. Sending only fails if there are no
+            // subscribers left.
+            if events.receiver_count() > 0 {
+                match tx_events(ctx, digest, &tx).await {
+                    Ok(tx_events) => {
+                        for event in tx_events {
+                            let _ = events.send(Arc::new(event));
+                        }
+                    }
+
+                    Err(e) => warn!(%digest, "Failed to send events to subscribers: {e:#}"),
+                }
+            }
+
+            let _ = transactions.send(Arc::new(tx));
+            next += 1;
+        }
+
+        if next < hi {
+            break;
+        }
+    }
+
+    Ok(Some(next))
+}
+
+/// The exclusive upper bound on the sequence numbers of transactions that have been committed by
+/// all the pipelines the feed depends on, if they have all committed any transactions.
+async fn tx_hi(ctx: &Context) -> anyhow::Result<Option<u64>> {
+    use watermarks::dsl as w;
+
+    let mut conn = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to database")?;
+
+    let query = w::watermarks
+        .select(w::tx_hi)
+        .filter(w::pipeline.eq_any(PIPELINES));
+
+    let tx_hi: Vec<i64> = conn
+        .results(query)
+        .await
+        .context("Failed to fetch latest transaction")?;
+
+    if tx_hi.len() < PIPELINES.len() {
+        return Ok(None);
+    }
+
+    Ok(tx_hi.into_iter().min().map(|tx_hi| tx_hi as u64))
+}
+
+/// All the events emitted by `tx`, converted for presentation to subscribers.
+async fn tx_events(
+    ctx: &Context,
+    digest: TransactionDigest,
+    tx: &TransactionContents,
+) -> anyhow::Result<Vec<SuiEvent>> {
+    let mut events = vec![];
+    for (ix, event) in tx.events()?.into_iter().enumerate() {
+        events.push(response::event(ctx, digest, ix, event, Some(tx.timestamp_ms())).await?);
+    }
+
+    Ok(events)
+}
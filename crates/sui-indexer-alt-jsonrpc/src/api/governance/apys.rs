@@ -0,0 +1,127 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context as _;
+use diesel::{ExpressionMethods, QueryDsl};
+use sui_indexer_alt_schema::schema::kv_epoch_starts;
+use sui_json_rpc_types::{ValidatorApy, ValidatorApys};
+use sui_types::{
+    base_types::ObjectID,
+    sui_system_state::{
+        sui_system_state_summary::SuiSystemStateSummary, PoolTokenExchangeRate, SuiSystemState,
+        SuiSystemStateTrait,
+    },
+};
+
+use crate::{context::Context, error::RpcError};
+
+/// APYs outside this range are assumed to be outliers (e.g. caused by slashing, or a pool with
+/// very little stake) and are excluded from the average.
+const APY_RANGE: (f64, f64) = (0.0, 0.1);
+
+/// Calculate the APY of each currently active validator, averaged over (up to) the last `window`
+/// epochs. Each validator's staking pool exchange rate at the start of each epoch is read from the
+/// system state recorded at the start of that epoch.
+pub(super) async fn validators_apy(ctx: &Context, window: u64) -> Result<ValidatorApys, RpcError> {
+    use kv_epoch_starts::dsl as e;
+
+    // Fetching one more epoch than the window, so that there are `window` pairs of consecutive
+    // epochs to calculate APYs from.
+    let system_states: Vec<Vec<u8>> = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to the database")?
+        .results(
+            e::kv_epoch_starts
+                .select(e::system_state)
+                .order(e::epoch.desc())
+                .limit(window as i64 + 1),
+        )
+        .await
+        .context("Failed to fetch system states")?;
+
+    // Summaries are in descending epoch order.
+    let summaries = system_states
+        .iter()
+        .map(|bytes| {
+            let system_state: SuiSystemState =
+                bcs::from_bytes(bytes).context("Failed to deserialize system state")?;
+            Ok(system_state.into_sui_system_state_summary())
+        })
+        .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+    let Some(latest) = summaries.first() else {
+        return Ok(ValidatorApys {
+            apys: vec![],
+            epoch: 0,
+        });
+    };
+
+    let apys = latest
+        .active_validators
+        .iter()
+        .map(|validator| ValidatorApy {
+            address: validator.sui_address,
+            apy: average_apy(&summaries, latest.stake_subsidy_start_epoch, |summary| {
+                exchange_rate(summary, validator.staking_pool_id)
+            }),
+        })
+        .collect();
+
+    Ok(ValidatorApys {
+        apys,
+        epoch: latest.epoch,
+    })
+}
+
+/// The average APY across consecutive pairs of `summaries` (which are in descending epoch order),
+/// starting from the epoch that stake subsidies started in, using `rate` to find the pool's
+/// exchange rate in each summary. Returns zero if there are no pairs to calculate an APY from.
+fn average_apy(
+    summaries: &[SuiSystemStateSummary],
+    stake_subsidy_start_epoch: u64,
+    rate: impl Fn(&SuiSystemStateSummary) -> Option<PoolTokenExchangeRate>,
+) -> f64 {
+    let apys: Vec<_> = summaries
+        .windows(2)
+        .filter_map(|pair| {
+            let [newer, older] = pair else {
+                return None;
+            };
+
+            // Epochs may be missing (e.g. because they were pruned), in which case the rates on
+            // either side of the gap can't be compared.
+            if older.epoch < stake_subsidy_start_epoch || older.epoch + 1 != newer.epoch {
+                return None;
+            }
+
+            // APY_e = (ER_e / ER_e+1) ^ 365 - 1
+            let apy = (rate(older)?.rate() / rate(newer)?.rate()).powf(365.0) - 1.0;
+            (APY_RANGE.0 < apy && apy < APY_RANGE.1).then_some(apy)
+        })
+        .collect();
+
+    if apys.is_empty() {
+        0.0
+    } else {
+        apys.iter().sum::<f64>() / apys.len() as f64
+    }
+}
+
+/// The exchange rate of the staking pool with ID `pool_id` at the start of `summary`'s epoch, if
+/// its validator was active then.
+fn exchange_rate(
+    summary: &SuiSystemStateSummary,
+    pool_id: ObjectID,
+) -> Option<PoolTokenExchangeRate> {
+    let validator = summary
+        .active_validators
+        .iter()
+        .find(|v| v.staking_pool_id == pool_id)?;
+
+    Some(PoolTokenExchangeRate::new(
+        validator.staking_pool_sui_balance,
+        validator.pool_token_balance,
+    ))
+}
@@ -0,0 +1,16 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_types::base_types::ObjectID;
+
+#[derive(thiserror::Error, Debug)]
+pub(super) enum Error {
+    #[error("Requested {requested} keys, exceeding maximum {max}")]
+    TooManyKeys { requested: usize, max: usize },
+
+    #[error("Cannot find StakedSui object {0}")]
+    StakeNotFound(ObjectID),
+
+    #[error("Object {0} is not a StakedSui object")]
+    NotAStake(ObjectID),
+}
@@ -0,0 +1,355 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use diesel::{ExpressionMethods, QueryDsl};
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use sui_indexer_alt_schema::schema::kv_epoch_starts;
+use sui_json_rpc_types::{DelegatedStake, ValidatorApys};
+use sui_open_rpc::Module;
+use sui_open_rpc_macros::open_rpc;
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    dynamic_field::{derive_dynamic_field_id, Field},
+    sui_serde::BigInt,
+    sui_system_state::{
+        sui_system_state_inner_v1::SuiSystemStateInnerV1,
+        sui_system_state_inner_v2::SuiSystemStateInnerV2,
+        sui_system_state_summary::SuiSystemStateSummary, SuiSystemStateTrait,
+        SuiSystemStateWrapper,
+    },
+    TypeTag, SUI_SYSTEM_STATE_OBJECT_ID,
+};
+
+use crate::{
+    context::Context,
+    data::objects::load_latest_deserialized,
+    error::{invalid_params, rpc_bail, InternalContext, RpcError},
+};
+
+use super::rpc_module::RpcModule;
+
+use self::error::Error;
+
+mod apys;
+mod error;
+mod stakes;
+
+#[open_rpc(namespace = "suix", tag = "Governance API")]
+#[rpc(server, namespace = "suix")]
+trait GovernanceApi {
+    /// Return the reference gas price for the network as of the latest epoch.
+    #[method(name = "getReferenceGasPrice")]
+    async fn get_reference_gas_price(&self) -> RpcResult<BigInt<u64>>;
+
+    /// Return a summary of the latest version of the Sui System State object (0x5), on-chain.
+    #[method(name = "getLatestSuiSystemState")]
+    async fn get_latest_sui_system_state(&self) -> RpcResult<SuiSystemStateSummary>;
+
+    /// Return all the stakes owned by an address, grouped by the staking pool they were delegated
+    /// to, with estimated rewards for stakes that are active.
+    #[method(name = "getStakes")]
+    async fn get_stakes(
+        &self,
+        /// the owner's Sui address
+        owner: SuiAddress,
+    ) -> RpcResult<Vec<DelegatedStake>>;
+
+    /// Return the stakes with the given IDs, grouped by the staking pool they were delegated to.
+    /// Stakes that have since been withdrawn are reported as unstaked. The number of IDs that can
+    /// be requested at once is limited in the same way as `sui_multiGetObjects`.
+    #[method(name = "getStakesByIds")]
+    async fn get_stakes_by_ids(
+        &self,
+        /// the IDs of the StakedSui objects
+        staked_sui_ids: Vec<ObjectID>,
+    ) -> RpcResult<Vec<DelegatedStake>>;
+
+    /// Return the APY of each active validator, averaged over recent epochs.
+    #[method(name = "getValidatorsApy")]
+    async fn get_validators_apy(&self) -> RpcResult<ValidatorApys>;
+}
+
+pub(crate) struct Governance(pub Context, pub SystemStateCache, pub RgpCache);
+
+/// The most recently loaded value, and when it was loaded.
+#[derive(Clone)]
+pub(crate) struct TtlCache<T>(Arc<Mutex<Option<(Instant, T)>>>);
+
+/// The system state only changes at epoch boundaries and when stake is added or withdrawn, so it
+/// is safe to serve a slightly stale summary for a short while.
+pub(crate) type SystemStateCache = TtlCache<SuiSystemStateSummary>;
+
+/// The reference gas price only changes at epoch boundaries, and every transaction builder asks for
+/// it, so it is served from a cache rather than read from the database on every request.
+pub(crate) type RgpCache = TtlCache<u64>;
+
+#[derive(Clone, Debug)]
+pub struct GovernanceConfig {
+    /// How long a system state summary can be served from the cache before it is reloaded. A TTL
+    /// of zero disables caching.
+    pub system_state_cache_ttl: Duration,
+
+    /// The number of most recent epochs to average validator APYs over.
+    pub apy_window: u64,
+
+    /// How long the reference gas price can be served from the cache before it is reloaded. A TTL
+    /// of zero disables caching.
+    pub rgp_cache_ttl: Duration,
+}
+
+#[async_trait::async_trait]
+impl GovernanceApiServer for Governance {
+    async fn get_reference_gas_price(&self) -> RpcResult<BigInt<u64>> {
+        let Self(ctx, _, rgp_cache) = self;
+        Ok(rgp(ctx, rgp_cache).await?.into())
+    }
+
+    async fn get_latest_sui_system_state(&self) -> RpcResult<SuiSystemStateSummary> {
+        let Self(ctx, cache, _) = self;
+        Ok(system_state(ctx, cache).await?)
+    }
+
+    async fn get_stakes(&self, owner: SuiAddress) -> RpcResult<Vec<DelegatedStake>> {
+        let Self(ctx, cache, _) = self;
+        let system_state = system_state(ctx, cache).await?;
+        Ok(stakes::owned_stakes(ctx, &system_state, owner)
+            .await
+            .with_internal_context(|| format!("Failed to get stakes for {owner}"))?)
+    }
+
+    async fn get_stakes_by_ids(
+        &self,
+        staked_sui_ids: Vec<ObjectID>,
+    ) -> RpcResult<Vec<DelegatedStake>> {
+        let Self(ctx, cache, _) = self;
+        let config = ctx.config();
+        if staked_sui_ids.len() > config.objects.max_multi_get_objects {
+            return Err(invalid_params(Error::TooManyKeys {
+                requested: staked_sui_ids.len(),
+                max: config.objects.max_multi_get_objects,
+            })
+            .into());
+        }
+
+        let system_state = system_state(ctx, cache).await?;
+        Ok(stakes::stakes_by_ids(ctx, &system_state, &staked_sui_ids)
+            .await
+            .with_internal_context(|| "Failed to get stakes by ID")?)
+    }
+
+    async fn get_validators_apy(&self) -> RpcResult<ValidatorApys> {
+        let Self(ctx, ..) = self;
+        let window = ctx.config().governance.apy_window;
+        Ok(apys::validators_apy(ctx, window)
+            .await
+            .with_internal_context(|| "Failed to calculate validator APYs")?)
+    }
+}
+
+impl<T: Clone> TtlCache<T> {
+    /// The cached value, if it was loaded less than `ttl` ago.
+    fn get(&self, ttl: Duration) -> Option<T> {
+        let cached = self.0.lock().unwrap();
+        let (loaded, value) = cached.as_ref()?;
+        (loaded.elapsed() < ttl).then(|| value.clone())
+    }
+
+    fn insert(&self, value: T) {
+        *self.0.lock().unwrap() = Some((Instant::now(), value));
+    }
+}
+
+impl<T> Default for TtlCache<T> {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(None)))
+    }
+}
+
+impl RpcModule for Governance {
+    fn schema(&self) -> Module {
+        GovernanceApiOpenRpc::module_doc()
+    }
+
+    fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+        self.into_rpc()
+    }
+}
+
+impl Default for GovernanceConfig {
+    fn default() -> Self {
+        Self {
+            system_state_cache_ttl: Duration::from_secs(1),
+            apy_window: 30,
+            rgp_cache_ttl: Duration::from_secs(1),
+        }
+    }
+}
+
+/// The reference gas price of the latest epoch, served from `cache` if it was loaded recently
+/// enough.
+async fn rgp(ctx: &Context, cache: &RgpCache) -> Result<u64, RpcError> {
+    let ttl = ctx.config().governance.rgp_cache_ttl;
+    if let Some(rgp) = cache.get(ttl) {
+        return Ok(rgp);
+    }
+
+    let rgp = latest_rgp(ctx).await?;
+    cache.insert(rgp);
+    Ok(rgp)
+}
+
+/// Read the reference gas price of the latest epoch from the start of epoch data.
+async fn latest_rgp(ctx: &Context) -> Result<u64, RpcError> {
+    use kv_epoch_starts::dsl as e;
+
+    let mut conn = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to the database")?;
+
+    let rgp: i64 = conn
+        .first(
+            e::kv_epoch_starts
+                .select(e::reference_gas_price)
+                .order(e::epoch.desc()),
+        )
+        .await
+        .context("Failed to fetch the reference gas price")?;
+
+    Ok(rgp as u64)
+}
+
+/// The latest system state summary, served from `cache` if it was loaded recently enough.
+async fn system_state(
+    ctx: &Context,
+    cache: &SystemStateCache,
+) -> Result<SuiSystemStateSummary, RpcError> {
+    let ttl = ctx.config().governance.system_state_cache_ttl;
+    if let Some(summary) = cache.get(ttl) {
+        return Ok(summary);
+    }
+
+    let summary = latest_sui_system_state_response(ctx).await?;
+    cache.insert(summary.clone());
+    Ok(summary)
+}
+
+/// Load data and generate response for `getLatestSuiSystemState`.
+async fn latest_sui_system_state_response(
+    ctx: &Context,
+) -> Result<SuiSystemStateSummary, RpcError> {
+    let wrapper: SuiSystemStateWrapper = load_latest_deserialized(ctx, SUI_SYSTEM_STATE_OBJECT_ID)
+        .await
+        .context("Failed to fetch system state wrapper object")?;
+
+    let inner_id = derive_dynamic_field_id(
+        SUI_SYSTEM_STATE_OBJECT_ID,
+        &TypeTag::U64,
+        &bcs::to_bytes(&wrapper.version).context("Failed to serialize system state version")?,
+    )
+    .context("Failed to derive inner system state field ID")?;
+
+    Ok(match wrapper.version {
+        1 => load_latest_deserialized::<Field<u64, SuiSystemStateInnerV1>>(ctx, inner_id)
+            .await
+            .context("Failed to fetch inner system state object")?
+            .value
+            .into_sui_system_state_summary(),
+        2 => load_latest_deserialized::<Field<u64, SuiSystemStateInnerV2>>(ctx, inner_id)
+            .await
+            .context("Failed to fetch inner system state object")?
+            .value
+            .into_sui_system_state_summary(),
+        v => rpc_bail!("Unexpected inner system state version: {v}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::Registry;
+    use sui_indexer_alt_schema::{epochs::StoredEpochStart, MIGRATIONS};
+    use sui_pg_db::{temp::TempDb, Db, DbArgs};
+
+    use crate::{config::RpcConfig, metrics::RpcMetrics};
+
+    use super::*;
+
+    /// A context reading from `db`, and a connection pool to write test data to it with.
+    async fn context(db: &TempDb) -> (Context, Db) {
+        let url = db.database().url().clone();
+        let writer = Db::for_write(DbArgs::new_for_testing(url.clone()))
+            .await
+            .unwrap();
+        writer.run_migrations(MIGRATIONS).await.unwrap();
+
+        let (config, _) = RpcConfig::default().finish_service(true).unwrap();
+        let registry = Registry::new();
+        let ctx = Context::new(
+            DbArgs::new_for_testing(url),
+            None,
+            config,
+            RpcMetrics::new(&registry),
+            &registry,
+        )
+        .await
+        .unwrap();
+
+        (ctx, writer)
+    }
+
+    async fn start_epoch(writer: &Db, epoch: i64, rgp: i64) {
+        let row = StoredEpochStart {
+            epoch,
+            protocol_version: 1,
+            cp_lo: epoch * 100,
+            start_timestamp_ms: epoch * 1000,
+            reference_gas_price: rgp,
+            system_state: vec![],
+        };
+
+        let mut conn = writer.connect().await.unwrap();
+        diesel_async::RunQueryDsl::execute(
+            diesel::insert_into(kv_epoch_starts::table).values(&row),
+            &mut conn,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rgp_from_latest_epoch() {
+        let db = TempDb::new().unwrap();
+        let (ctx, writer) = context(&db).await;
+
+        // There is no reference gas price before the first epoch has been indexed.
+        assert!(latest_rgp(&ctx).await.is_err());
+
+        start_epoch(&writer, 0, 1000).await;
+        start_epoch(&writer, 1, 750).await;
+        assert_eq!(latest_rgp(&ctx).await.unwrap(), 750);
+    }
+
+    #[tokio::test]
+    async fn test_rgp_cache() {
+        let db = TempDb::new().unwrap();
+        let (ctx, writer) = context(&db).await;
+        let cache = RgpCache::default();
+
+        start_epoch(&writer, 0, 1000).await;
+        assert_eq!(rgp(&ctx, &cache).await.unwrap(), 1000);
+
+        // The next epoch's price is not visible until the cached price expires.
+        start_epoch(&writer, 1, 750).await;
+        assert_eq!(rgp(&ctx, &cache).await.unwrap(), 1000);
+        assert_eq!(cache.get(Duration::ZERO), None);
+        assert_eq!(latest_rgp(&ctx).await.unwrap(), 750);
+    }
+}
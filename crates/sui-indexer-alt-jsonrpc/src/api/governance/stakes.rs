@@ -0,0 +1,524 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    future::Future,
+};
+
+use anyhow::{bail, Context as _};
+use diesel::{BoolExpressionMethods, ExpressionMethods, JoinOnDsl, QueryDsl};
+use futures::future;
+use serde::{de::DeserializeOwned, Serialize};
+use sui_indexer_alt_schema::{objects::StoredOwnerKind, schema::obj_info};
+use sui_json_rpc_types::{DelegatedStake, Stake, StakeStatus};
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    dynamic_field::{derive_dynamic_field_id, Field},
+    governance::{StakedSui, STAKED_SUI_STRUCT_NAME, STAKING_POOL_MODULE_NAME},
+    id::ID,
+    object::Object,
+    sui_system_state::{
+        sui_system_state_inner_v1::ValidatorV1, sui_system_state_summary::SuiSystemStateSummary,
+        PoolTokenExchangeRate, ValidatorWrapper,
+    },
+    MoveTypeTagTrait, TypeTag, SUI_SYSTEM_ADDRESS,
+};
+
+use crate::{
+    context::Context,
+    data::objects::{load_latest, load_live},
+    error::{invalid_params, RpcError},
+};
+
+use super::error::Error;
+
+/// How many epochs to look for exchange rates in at once, when looking for the latest rate before
+/// an epoch that has no rate recorded.
+const RATE_WINDOW: u64 = 16;
+
+/// The parts of a validator's details needed to report on stakes delegated to its staking pool.
+struct PoolValidator {
+    address: SuiAddress,
+    exchange_rates_id: ObjectID,
+
+    /// The epoch whose exchange rate is used to value stakes in this pool: The current epoch for
+    /// active pools, or the epoch the pool was deactivated in, for inactive pools.
+    rate_epoch: u64,
+
+    /// The epoch the pool was activated in. The pool has no exchange rates from before this epoch.
+    activation_epoch: u64,
+}
+
+/// A request for the exchange rate of a staking pool in some epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct RateQuery {
+    /// The ID of the pool's table of exchange rates.
+    exchange_rates_id: ObjectID,
+
+    /// The earliest epoch that the pool could have an exchange rate for.
+    floor: u64,
+
+    epoch: u64,
+}
+
+/// All the stakes currently owned by `owner`, grouped by staking pool.
+pub(super) async fn owned_stakes(
+    ctx: &Context,
+    system_state: &SuiSystemStateSummary,
+    owner: SuiAddress,
+) -> Result<Vec<DelegatedStake>, RpcError<Error>> {
+    use obj_info::dsl as o;
+
+    let (candidates, newer) = diesel::alias!(obj_info as candidates, obj_info as newer);
+
+    macro_rules! candidates {
+        ($field:ident) => {
+            candidates.field(o::$field)
+        };
+    }
+
+    macro_rules! newer {
+        ($field:ident) => {
+            newer.field(o::$field)
+        };
+    }
+
+    let query = candidates
+        .select(candidates!(object_id))
+        .left_join(
+            newer.on(candidates!(object_id)
+                .eq(newer!(object_id))
+                .and(candidates!(cp_sequence_number).lt(newer!(cp_sequence_number)))),
+        )
+        .filter(newer!(object_id).is_null())
+        .filter(candidates!(owner_kind).eq(StoredOwnerKind::Address))
+        .filter(candidates!(owner_id).eq(owner.to_vec()))
+        .filter(candidates!(package).eq(SUI_SYSTEM_ADDRESS.to_vec()))
+        .filter(candidates!(module).eq(STAKING_POOL_MODULE_NAME.to_string()))
+        .filter(candidates!(name).eq(STAKED_SUI_STRUCT_NAME.to_string()));
+
+    let ids: Vec<Vec<u8>> = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to the database")?
+        .results(query)
+        .await
+        .context("Failed to fetch StakedSui object IDs")?;
+
+    let ids = ids
+        .iter()
+        .map(ObjectID::from_bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .context("Failed to deserialize StakedSui object IDs")?;
+
+    let objects = future::try_join_all(ids.iter().map(|id| load_latest(ctx, *id))).await?;
+
+    let mut stakes = Vec::with_capacity(objects.len());
+    for (id, object) in ids.into_iter().zip(objects) {
+        let object = object.with_context(|| format!("Failed to load StakedSui object {id}"))?;
+        stakes.push((staked_sui(&object)?, true));
+    }
+
+    delegated_stakes(ctx, system_state, stakes).await
+}
+
+/// The stakes with IDs `ids`, grouped by staking pool. Stakes that are no longer live are reported
+/// as unstaked, based on their contents before they were withdrawn.
+pub(super) async fn stakes_by_ids(
+    ctx: &Context,
+    system_state: &SuiSystemStateSummary,
+    ids: &[ObjectID],
+) -> Result<Vec<DelegatedStake>, RpcError<Error>> {
+    let live = future::try_join_all(ids.iter().map(|id| load_live(ctx, *id))).await?;
+
+    let mut stakes = Vec::with_capacity(ids.len());
+    for (id, object) in ids.iter().zip(live) {
+        let (object, exists) = match object {
+            Some(object) => (object, true),
+            None => match load_latest(ctx, *id).await? {
+                Some(object) => (object, false),
+                None => return Err(invalid_params(Error::StakeNotFound(*id))),
+            },
+        };
+
+        stakes.push((staked_sui(&object)?, exists));
+    }
+
+    delegated_stakes(ctx, system_state, stakes).await
+}
+
+/// Group `stakes` by staking pool, and estimate the rewards for each stake that is still live and
+/// active, based on how the pool's exchange rate has changed since the stake was activated.
+async fn delegated_stakes(
+    ctx: &Context,
+    system_state: &SuiSystemStateSummary,
+    stakes: Vec<(StakedSui, bool)>,
+) -> Result<Vec<DelegatedStake>, RpcError<Error>> {
+    let mut pools: BTreeMap<ObjectID, Vec<(StakedSui, bool)>> = BTreeMap::new();
+    for (stake, exists) in stakes {
+        pools
+            .entry(stake.pool_id())
+            .or_default()
+            .push((stake, exists));
+    }
+
+    let validators = future::try_join_all(pools.keys().map(|pool_id| async move {
+        pool_validator(ctx, system_state, *pool_id)
+            .await
+            .with_context(|| format!("Failed to find validator for staking pool {pool_id}"))
+    }))
+    .await?;
+
+    // Gather the exchange rates needed to value all the stakes, so that they can be loaded
+    // together: The rate that each pool is valued at now, and the rates that each active stake was
+    // activated at.
+    let mut queries = BTreeSet::new();
+    for (validator, stakes) in validators.iter().zip(pools.values()) {
+        let query = |epoch| RateQuery {
+            exchange_rates_id: validator.exchange_rates_id,
+            floor: validator.activation_epoch,
+            epoch,
+        };
+
+        queries.insert(query(validator.rate_epoch));
+        for (stake, exists) in stakes {
+            if *exists && stake.activation_epoch() <= system_state.epoch {
+                queries.insert(query(stake.activation_epoch()));
+            }
+        }
+    }
+
+    let rates = latest_rates(queries, |exchange_rates_id, epoch| {
+        exchange_rate(ctx, exchange_rates_id, epoch)
+    })
+    .await
+    .context("Failed to load exchange rates")?;
+
+    let mut delegated_stakes = Vec::with_capacity(pools.len());
+    for (validator, (pool_id, stakes)) in validators.into_iter().zip(pools) {
+        let rate = |epoch| {
+            rates.get(&RateQuery {
+                exchange_rates_id: validator.exchange_rates_id,
+                floor: validator.activation_epoch,
+                epoch,
+            })
+        };
+
+        let current_rate = rate(validator.rate_epoch);
+
+        let mut delegations = Vec::with_capacity(stakes.len());
+        for (stake, exists) in stakes {
+            let status = if !exists {
+                StakeStatus::Unstaked
+            } else if system_state.epoch < stake.activation_epoch() {
+                StakeStatus::Pending
+            } else if let Some(current_rate) = current_rate {
+                // Stakes activated before the pool recorded any exchange rates are valued as if
+                // they were activated at genesis.
+                let stake_rate = rate(stake.activation_epoch()).cloned().unwrap_or_default();
+                StakeStatus::Active {
+                    estimated_reward: estimated_reward(
+                        stake.principal(),
+                        &stake_rate,
+                        current_rate,
+                    ),
+                }
+            } else {
+                // The pool has not recorded any exchange rates, so its stakes have not earned any
+                // rewards.
+                StakeStatus::Active {
+                    estimated_reward: 0,
+                }
+            };
+
+            delegations.push(Stake {
+                staked_sui_id: stake.id(),
+                stake_request_epoch: stake.request_epoch(),
+                stake_active_epoch: stake.activation_epoch(),
+                principal: stake.principal(),
+                status,
+            });
+        }
+
+        delegated_stakes.push(DelegatedStake {
+            validator_address: validator.address,
+            staking_pool: pool_id,
+            stakes: delegations,
+        });
+    }
+
+    Ok(delegated_stakes)
+}
+
+/// The reward earned by `principal` staked when the pool's exchange rate was `stake_rate`, now
+/// that its rate is `current_rate`.
+fn estimated_reward(
+    principal: u64,
+    stake_rate: &PoolTokenExchangeRate,
+    current_rate: &PoolTokenExchangeRate,
+) -> u64 {
+    let reward = ((stake_rate.rate() / current_rate.rate()) - 1.0) * principal as f64;
+    reward.round().max(0.0) as u64
+}
+
+/// Interpret `object` as a `StakedSui`, or fail with a user error if it is some other object.
+fn staked_sui(object: &Object) -> Result<StakedSui, RpcError<Error>> {
+    StakedSui::try_from(object).map_err(|_| invalid_params(Error::NotAStake(object.id())))
+}
+
+/// Find the validator that runs the staking pool with ID `pool_id`, among the active validators,
+/// or in the table of pools that have been deactivated.
+async fn pool_validator(
+    ctx: &Context,
+    system_state: &SuiSystemStateSummary,
+    pool_id: ObjectID,
+) -> Result<PoolValidator, anyhow::Error> {
+    if let Some(validator) = system_state
+        .active_validators
+        .iter()
+        .find(|v| v.staking_pool_id == pool_id)
+    {
+        return Ok(PoolValidator {
+            address: validator.sui_address,
+            exchange_rates_id: validator.exchange_rates_id,
+            rate_epoch: system_state.epoch,
+            activation_epoch: validator.staking_pool_activation_epoch.unwrap_or(0),
+        });
+    }
+
+    let wrapper: ValidatorWrapper = load_field(
+        ctx,
+        system_state.inactive_pools_id,
+        ID::get_type_tag(),
+        &ID::new(pool_id),
+    )
+    .await?
+    .context("Staking pool is neither active nor inactive")?;
+
+    let versioned = wrapper.inner;
+    let validator = match versioned.version {
+        1 => load_field::<u64, ValidatorV1>(
+            ctx,
+            versioned.id.id.bytes,
+            TypeTag::U64,
+            &versioned.version,
+        )
+        .await?
+        .context("Failed to find inner validator")?
+        .into_sui_validator_summary(),
+        v => bail!("Unexpected inner validator version: {v}"),
+    };
+
+    Ok(PoolValidator {
+        address: validator.sui_address,
+        exchange_rates_id: validator.exchange_rates_id,
+        rate_epoch: validator
+            .staking_pool_deactivation_epoch
+            .unwrap_or(system_state.epoch),
+        activation_epoch: validator.staking_pool_activation_epoch.unwrap_or(0),
+    })
+}
+
+/// The exchange rate recorded for a staking pool in `epoch`, from the pool's table of exchange
+/// rates at `exchange_rates_id`, if one was recorded.
+async fn exchange_rate(
+    ctx: &Context,
+    exchange_rates_id: ObjectID,
+    epoch: u64,
+) -> Result<Option<PoolTokenExchangeRate>, anyhow::Error> {
+    load_field(ctx, exchange_rates_id, TypeTag::U64, &epoch).await
+}
+
+/// For each of `queries`, the latest exchange rate that its pool recorded at or before its epoch
+/// (but not before its floor), fetched using `load`. This is how the fullnode values stakes when
+/// there is no rate recorded for the epoch itself (e.g. because the network was in safe mode).
+/// Queries with no such rate are omitted.
+///
+/// Rates are loaded for all queries at once: first the rates for the queries' epochs, and then, for
+/// the queries that have not found a rate yet, windows of the [RATE_WINDOW] epochs before the
+/// epochs that have been searched so far.
+async fn latest_rates<R, F, Fut>(
+    queries: BTreeSet<RateQuery>,
+    load: F,
+) -> Result<HashMap<RateQuery, R>, anyhow::Error>
+where
+    R: Clone,
+    F: Fn(ObjectID, u64) -> Fut,
+    Fut: Future<Output = Result<Option<R>, anyhow::Error>>,
+{
+    let mut found = HashMap::new();
+
+    // Every rate that has been looked for so far, including the ones that were not recorded, so
+    // that no epoch is loaded more than once.
+    let mut loaded: HashMap<(ObjectID, u64), Option<R>> = HashMap::new();
+
+    // Each query that has not found a rate yet, and the latest epoch it has not searched yet.
+    let mut pending: Vec<(RateQuery, u64)> = queries.into_iter().map(|q| (q, q.epoch)).collect();
+    let mut window = 1;
+
+    while !pending.is_empty() {
+        let range = |q: &RateQuery, hi: u64| hi.saturating_sub(window - 1).max(q.floor)..=hi;
+
+        let mut probes = BTreeSet::new();
+        for (q, hi) in &pending {
+            probes.extend(
+                range(q, *hi)
+                    .map(|epoch| (q.exchange_rates_id, epoch))
+                    .filter(|probe| !loaded.contains_key(probe)),
+            );
+        }
+
+        let rates =
+            future::try_join_all(probes.iter().map(|(id, epoch)| load(*id, *epoch))).await?;
+        loaded.extend(probes.into_iter().zip(rates));
+
+        pending.retain_mut(|(q, hi)| {
+            let range = range(q, *hi);
+            let start = *range.start();
+
+            if let Some(rate) = range
+                .rev()
+                .find_map(|epoch| loaded.get(&(q.exchange_rates_id, epoch))?.as_ref())
+            {
+                found.insert(*q, rate.clone());
+                return false;
+            }
+
+            // Keep searching earlier epochs, unless there are none left to search.
+            if start <= q.floor || start > *hi {
+                return false;
+            }
+
+            *hi = start - 1;
+            true
+        });
+
+        window = RATE_WINDOW;
+    }
+
+    Ok(found)
+}
+
+/// Load the value of the dynamic field on `parent` with the given name, if it exists.
+async fn load_field<K: Serialize + DeserializeOwned, V: DeserializeOwned>(
+    ctx: &Context,
+    parent: ObjectID,
+    key_type: TypeTag,
+    key: &K,
+) -> Result<Option<V>, anyhow::Error> {
+    let key_bytes = bcs::to_bytes(key).context("Failed to serialize dynamic field name")?;
+    let field_id = derive_dynamic_field_id(parent, &key_type, &key_bytes)
+        .context("Failed to derive dynamic field ID")?;
+
+    let Some(object) = load_live(ctx, field_id).await? else {
+        return Ok(None);
+    };
+
+    let move_object = object.data.try_as_move().context("Not a Move object")?;
+    let field: Field<K, V> =
+        bcs::from_bytes(move_object.contents()).context("Failed to deserialize dynamic field")?;
+
+    Ok(Some(field.value))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Look up `queries` in `rates`, a map from (pool, epoch) to rate, returning the rate found
+    /// for each query, and every (pool, epoch) that was loaded, in order.
+    async fn find(
+        rates: &BTreeMap<(ObjectID, u64), u64>,
+        queries: &[RateQuery],
+    ) -> (HashMap<RateQuery, u64>, Vec<(ObjectID, u64)>) {
+        let loads = Mutex::new(vec![]);
+        let found = latest_rates(queries.iter().copied().collect(), |id, epoch| {
+            loads.lock().unwrap().push((id, epoch));
+            future::ready(Ok(rates.get(&(id, epoch)).copied()))
+        })
+        .await
+        .unwrap();
+
+        (found, loads.into_inner().unwrap())
+    }
+
+    fn query(exchange_rates_id: ObjectID, floor: u64, epoch: u64) -> RateQuery {
+        RateQuery {
+            exchange_rates_id,
+            floor,
+            epoch,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exact_rates() {
+        let pool = ObjectID::random();
+        let rates = BTreeMap::from([((pool, 3), 30), ((pool, 4), 40), ((pool, 5), 50)]);
+
+        let queries = [query(pool, 0, 3), query(pool, 0, 5)];
+        let (found, loads) = find(&rates, &queries).await;
+
+        assert_eq!(found[&queries[0]], 30);
+        assert_eq!(found[&queries[1]], 50);
+
+        // Rates that exist are loaded directly.
+        assert_eq!(loads.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_latest_earlier_rate() {
+        let pool = ObjectID::random();
+        let other = ObjectID::random();
+
+        // Pool has no rates for epochs 11 to 39 (e.g. because the network was in safe mode).
+        let rates = BTreeMap::from([((pool, 10), 100), ((pool, 40), 400), ((other, 7), 70)]);
+
+        let queries = [
+            query(pool, 0, 10),
+            query(pool, 0, 25),
+            query(pool, 0, 39),
+            query(pool, 0, 45),
+            query(other, 5, 30),
+        ];
+
+        let (found, loads) = find(&rates, &queries).await;
+
+        assert_eq!(found[&queries[0]], 100);
+        assert_eq!(found[&queries[1]], 100);
+        assert_eq!(found[&queries[2]], 100);
+        assert_eq!(found[&queries[3]], 400);
+        assert_eq!(found[&queries[4]], 70);
+
+        // Epochs are only loaded once, even if several queries search them.
+        let unique: BTreeSet<_> = loads.iter().collect();
+        assert_eq!(unique.len(), loads.len());
+    }
+
+    #[tokio::test]
+    async fn test_no_earlier_rate() {
+        let pool = ObjectID::random();
+        let rates = BTreeMap::from([((pool, 3), 30), ((pool, 50), 500)]);
+
+        // Queries do not search before their floor, or find rates from later epochs.
+        let queries = [query(pool, 5, 40), query(pool, 0, 2)];
+        let (found, loads) = find(&rates, &queries).await;
+
+        assert!(found.is_empty());
+        assert!(loads.iter().all(|(_, epoch)| *epoch <= 40));
+        assert!(!loads.contains(&(pool, 3)));
+    }
+
+    #[test]
+    fn test_estimated_reward() {
+        let stake_rate = PoolTokenExchangeRate::new(1_000, 1_000);
+        let current_rate = PoolTokenExchangeRate::new(1_100, 1_000);
+        assert_eq!(estimated_reward(1_000, &stake_rate, &current_rate), 100);
+
+        // Rewards are never negative, even if the rate has moved against the stake.
+        assert_eq!(estimated_reward(1_000, &current_rate, &stake_rate), 0);
+    }
+}
@@ -1,12 +1,18 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod chain;
 pub(crate) mod checkpoints;
 pub(crate) mod coin;
 pub(crate) mod dynamic_fields;
+pub(crate) mod events;
+pub(crate) mod feed;
 pub(crate) mod governance;
 pub(crate) mod move_utils;
 pub(crate) mod name_service;
 pub(crate) mod objects;
+pub(crate) mod protocol_config;
 pub(crate) mod rpc_module;
 pub(crate) mod transactions;
+pub(crate) mod webhooks;
+pub(crate) mod write;
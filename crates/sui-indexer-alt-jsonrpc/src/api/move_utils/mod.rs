@@ -1,11 +1,22 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use sui_json_rpc_types::SuiMoveNormalizedFunction;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use sui_json_rpc_types::{
+    MoveFunctionArgType, SuiMoveNormalizedFunction, SuiMoveNormalizedModule,
+    SuiMoveNormalizedStruct,
+};
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
-use sui_types::base_types::ObjectID;
+use sui_types::base_types::{ObjectID, SequenceNumber};
 
 use crate::context::Context;
 
@@ -17,6 +28,16 @@ mod response;
 #[open_rpc(namespace = "sui", tag = "Move APIs")]
 #[rpc(server, namespace = "sui")]
 trait MoveApi {
+    /// Return the argument types of a Move function, classifying each one as a pure value, or an
+    /// object passed by value or by reference.
+    #[method(name = "getMoveFunctionArgTypes")]
+    async fn get_move_function_arg_types(
+        &self,
+        package: ObjectID,
+        module: String,
+        function: String,
+    ) -> RpcResult<Vec<MoveFunctionArgType>>;
+
     #[method(name = "getNormalizedMoveFunction")]
     async fn get_normalized_move_function(
         &self,
@@ -24,21 +45,116 @@ trait MoveApi {
         module_name: String,
         function_name: String,
     ) -> RpcResult<SuiMoveNormalizedFunction>;
+
+    /// Return a structured representation of a Move module.
+    #[method(name = "getNormalizedMoveModule")]
+    async fn get_normalized_move_module(
+        &self,
+        package: ObjectID,
+        module_name: String,
+    ) -> RpcResult<SuiMoveNormalizedModule>;
+
+    /// Return structured representations of all modules in the given package.
+    #[method(name = "getNormalizedMoveModulesByPackage")]
+    async fn get_normalized_move_modules_by_package(
+        &self,
+        package: ObjectID,
+    ) -> RpcResult<BTreeMap<String, SuiMoveNormalizedModule>>;
+
+    /// Return a structured representation of a Move struct.
+    #[method(name = "getNormalizedMoveStruct")]
+    async fn get_normalized_move_struct(
+        &self,
+        package: ObjectID,
+        module_name: String,
+        struct_name: String,
+    ) -> RpcResult<SuiMoveNormalizedStruct>;
 }
 
-pub(crate) struct MoveUtils(pub Context);
+pub(crate) struct MoveUtils(pub Context, pub NormalizedModulesCache);
+
+/// All the modules in a package, normalized, keyed by module name.
+type NormalizedModules = Arc<BTreeMap<String, SuiMoveNormalizedModule>>;
+
+/// Packages whose modules have already been normalized, keyed by the package's ID and version.
+/// Packages are immutable once published, except for system packages, which are upgraded in
+/// place, so the version is part of the key, and entries are only ever evicted to make room for
+/// others.
+#[derive(Clone)]
+pub(crate) struct NormalizedModulesCache(
+    Arc<Mutex<LruCache<(ObjectID, SequenceNumber), NormalizedModules>>>,
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MoveUtilsConfig {
+    /// The number of packages to cache normalized modules for. This is only read on start-up.
+    pub normalized_cache_size: usize,
+}
 
 #[async_trait::async_trait]
 impl MoveApiServer for MoveUtils {
+    async fn get_move_function_arg_types(
+        &self,
+        package: ObjectID,
+        module: String,
+        function: String,
+    ) -> RpcResult<Vec<MoveFunctionArgType>> {
+        let Self(ctx, _) = self;
+        Ok(response::arg_types(ctx, package, &module, &function).await?)
+    }
+
     async fn get_normalized_move_function(
         &self,
         package: ObjectID,
         module_name: String,
         function_name: String,
     ) -> RpcResult<SuiMoveNormalizedFunction> {
-        let Self(ctx) = self;
+        let Self(ctx, _) = self;
         Ok(response::function(ctx, package, &module_name, &function_name).await?)
     }
+
+    async fn get_normalized_move_module(
+        &self,
+        package: ObjectID,
+        module_name: String,
+    ) -> RpcResult<SuiMoveNormalizedModule> {
+        let Self(ctx, cache) = self;
+        Ok(response::module(ctx, cache, package, &module_name).await?)
+    }
+
+    async fn get_normalized_move_modules_by_package(
+        &self,
+        package: ObjectID,
+    ) -> RpcResult<BTreeMap<String, SuiMoveNormalizedModule>> {
+        let Self(ctx, cache) = self;
+        let modules = response::modules(ctx, cache, package).await?;
+        Ok(modules.as_ref().clone())
+    }
+
+    async fn get_normalized_move_struct(
+        &self,
+        package: ObjectID,
+        module_name: String,
+        struct_name: String,
+    ) -> RpcResult<SuiMoveNormalizedStruct> {
+        let Self(ctx, cache) = self;
+        Ok(response::struct_(ctx, cache, package, &module_name, &struct_name).await?)
+    }
+}
+
+impl NormalizedModulesCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self(Arc::new(Mutex::new(LruCache::new(capacity))))
+    }
+
+    fn get(&self, key: &(ObjectID, SequenceNumber)) -> Option<NormalizedModules> {
+        self.0.lock().unwrap().get(key).cloned()
+    }
+
+    fn insert(&self, key: (ObjectID, SequenceNumber), modules: NormalizedModules) {
+        self.0.lock().unwrap().put(key, modules);
+    }
 }
 
 impl RpcModule for MoveUtils {
@@ -50,3 +166,11 @@ impl RpcModule for MoveUtils {
         self.into_rpc()
     }
 }
+
+impl Default for MoveUtilsConfig {
+    fn default() -> Self {
+        Self {
+            normalized_cache_size: 1000,
+        }
+    }
+}
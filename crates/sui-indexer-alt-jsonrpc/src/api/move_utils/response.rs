@@ -1,21 +1,27 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::{collections::BTreeMap, sync::Arc};
+
 use anyhow::anyhow;
 use move_binary_format::file_format::{Ability, AbilitySet, Visibility};
 use sui_json_rpc_types::{
-    SuiMoveAbility, SuiMoveAbilitySet, SuiMoveNormalizedFunction, SuiMoveNormalizedType,
-    SuiMoveVisibility,
+    MoveFunctionArgType, ObjectValueKind, SuiMoveAbility, SuiMoveAbilitySet,
+    SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct,
+    SuiMoveNormalizedType, SuiMoveVisibility,
+};
+use sui_package_resolver::{
+    error::Error as ResolverError, FunctionDef, OpenSignature, OpenSignatureBody, PackageStore,
+    Reference,
 };
-use sui_package_resolver::{FunctionDef, OpenSignature, OpenSignatureBody, Reference};
-use sui_types::{base_types::ObjectID, Identifier};
+use sui_types::{base_types::ObjectID, move_package::normalize_deserialized_modules, Identifier};
 
 use crate::{
     error::{invalid_params, RpcError},
     Context,
 };
 
-use super::error::Error;
+use super::{error::Error, NormalizedModules, NormalizedModulesCache};
 
 /// Load information about a function, and convert it into a JSON-RPC response.
 pub(super) async fn function(
@@ -38,46 +44,157 @@ pub(super) async fn function(
         .package_resolver()
         .function_signature(*package, module, name)
         .await
-        .map_err(|e| {
-            use sui_package_resolver::error::Error as PRE;
-            match &e {
-                // These errors can be triggered by passing a type that doesn't exist for the
-                // dynamic field name.
-                PRE::NotAPackage(_)
-                | PRE::PackageNotFound(_)
-                | PRE::ModuleNotFound(_, _)
-                | PRE::FunctionNotFound(_, _, _) => invalid_params(E::NotFound(e)),
-
-                // These errors can be triggered by requesting a type whose layout is too large
-                // (requires too may resources to resolve)
-                PRE::TooManyTypeNodes(_, _)
-                | PRE::TooManyTypeParams(_, _)
-                | PRE::TypeParamNesting(_, _) => invalid_params(E::ResolutionLimit(e)),
-
-                // The other errors are a form of internal error.
-                PRE::Bcs(_)
-                | PRE::Store { .. }
-                | PRE::DatatypeNotFound(_, _, _)
-                | PRE::Deserialize(_)
-                | PRE::EmptyPackage(_)
-                | PRE::InputTypeConflict(_, _, _)
-                | PRE::LinkageNotFound(_)
-                | PRE::NoTypeOrigin(_, _, _)
-                | PRE::NotAnIdentifier(_)
-                | PRE::TypeArityMismatch(_, _)
-                | PRE::TypeParamOOB(_, _)
-                | PRE::UnexpectedReference
-                | PRE::UnexpectedSigner
-                | PRE::UnexpectedError(_)
-                | PRE::ValueNesting(_) => {
-                    RpcError::from(anyhow!(e).context("Failed to resolve type layout"))
-                }
-            }
-        })?;
+        .map_err(resolver_error)?;
 
     Ok(normalized_function(&sig))
 }
 
+/// Load a function's signature, and classify each of its parameters by how it would be passed to
+/// the function: As a pure value, or as an object, by value or by reference.
+pub(super) async fn arg_types(
+    ctx: &Context,
+    package: ObjectID,
+    module: &str,
+    name: &str,
+) -> Result<Vec<MoveFunctionArgType>, RpcError<Error>> {
+    use MoveFunctionArgType as A;
+    use ObjectValueKind as K;
+    use SuiMoveNormalizedType as T;
+
+    let sig = function(ctx, package, module, name).await?;
+
+    // Any reference is treated as a reference to an object, and any struct as an object passed
+    // by value, to match the fullnode's classification.
+    Ok(sig
+        .parameters
+        .iter()
+        .map(|param| match param {
+            T::Struct { .. } => A::Object(K::ByValue),
+            T::Reference(_) => A::Object(K::ByImmutableReference),
+            T::MutableReference(_) => A::Object(K::ByMutableReference),
+            _ => A::Pure,
+        })
+        .collect())
+}
+
+/// Load all the modules in `package`, normalized, from `cache` if they have been normalized
+/// before, or from the package resolver otherwise.
+pub(super) async fn modules(
+    ctx: &Context,
+    cache: &NormalizedModulesCache,
+    package: ObjectID,
+) -> Result<NormalizedModules, RpcError<Error>> {
+    // Packages are always fetched through the resolver, even if their modules have been cached,
+    // to detect when a system package has been upgraded (in-place) since it was cached.
+    let pkg = ctx
+        .package_resolver()
+        .package_store()
+        .fetch(*package)
+        .await
+        .map_err(resolver_error)?;
+
+    let key = (package, pkg.version());
+    if let Some(modules) = cache.get(&key) {
+        return Ok(modules);
+    }
+
+    let modules: BTreeMap<_, SuiMoveNormalizedModule> =
+        normalize_deserialized_modules(pkg.modules().values().map(|m| m.bytecode()))
+            .into_iter()
+            .map(|(name, module)| (name, module.into()))
+            .collect();
+
+    let modules = Arc::new(modules);
+    cache.insert(key, modules.clone());
+    Ok(modules)
+}
+
+/// Load a single normalized module from `package`.
+pub(super) async fn module(
+    ctx: &Context,
+    cache: &NormalizedModulesCache,
+    package: ObjectID,
+    module: &str,
+) -> Result<SuiMoveNormalizedModule, RpcError<Error>> {
+    if !Identifier::is_valid(module) {
+        return Err(invalid_params(Error::BadIdentifier(module.to_owned())));
+    }
+
+    modules(ctx, cache, package)
+        .await?
+        .get(module)
+        .cloned()
+        .ok_or_else(|| {
+            let e = ResolverError::ModuleNotFound(package.into(), module.to_owned());
+            invalid_params(Error::NotFound(e))
+        })
+}
+
+/// Load a single normalized struct, from `module` in `package`.
+pub(super) async fn struct_(
+    ctx: &Context,
+    cache: &NormalizedModulesCache,
+    package: ObjectID,
+    module_name: &str,
+    name: &str,
+) -> Result<SuiMoveNormalizedStruct, RpcError<Error>> {
+    if !Identifier::is_valid(name) {
+        return Err(invalid_params(Error::BadIdentifier(name.to_owned())));
+    }
+
+    module(ctx, cache, package, module_name)
+        .await?
+        .structs
+        .remove(name)
+        .ok_or_else(|| {
+            let e = ResolverError::DatatypeNotFound(
+                package.into(),
+                module_name.to_owned(),
+                name.to_owned(),
+            );
+            invalid_params(Error::NotFound(e))
+        })
+}
+
+/// Classify an error from the package resolver as either a user error or an internal error.
+fn resolver_error(e: ResolverError) -> RpcError<Error> {
+    use Error as E;
+    use ResolverError as PRE;
+    match &e {
+        // These errors can be triggered by passing a type that doesn't exist for the
+        // dynamic field name.
+        PRE::NotAPackage(_)
+        | PRE::PackageNotFound(_)
+        | PRE::ModuleNotFound(_, _)
+        | PRE::FunctionNotFound(_, _, _) => invalid_params(E::NotFound(e)),
+
+        // These errors can be triggered by requesting a type whose layout is too large
+        // (requires too may resources to resolve)
+        PRE::TooManyTypeNodes(_, _)
+        | PRE::TooManyTypeParams(_, _)
+        | PRE::TypeParamNesting(_, _) => invalid_params(E::ResolutionLimit(e)),
+
+        // The other errors are a form of internal error.
+        PRE::Bcs(_)
+        | PRE::Store { .. }
+        | PRE::DatatypeNotFound(_, _, _)
+        | PRE::Deserialize(_)
+        | PRE::EmptyPackage(_)
+        | PRE::InputTypeConflict(_, _, _)
+        | PRE::LinkageNotFound(_)
+        | PRE::NoTypeOrigin(_, _, _)
+        | PRE::NotAnIdentifier(_)
+        | PRE::TypeArityMismatch(_, _)
+        | PRE::TypeParamOOB(_, _)
+        | PRE::UnexpectedReference
+        | PRE::UnexpectedSigner
+        | PRE::UnexpectedError(_)
+        | PRE::ValueNesting(_) => {
+            RpcError::from(anyhow!(e).context("Failed to resolve type layout"))
+        }
+    }
+}
+
 fn normalized_function(sig: &FunctionDef) -> SuiMoveNormalizedFunction {
     SuiMoveNormalizedFunction {
         visibility: visibility(sig.visibility),
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use sui_name_service::NameServiceConfig;
+use sui_json_rpc_types::{SuiNameProfile, SuiNameRecord, SuiNameResolution};
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::SuiAddress;
@@ -26,18 +26,71 @@ trait NameServiceApi {
         /// The name to resolve
         name: String,
     ) -> RpcResult<Option<SuiAddress>>;
+
+    /// Resolve a SuiNS name, reporting when its registration expires, and whether it has
+    /// expired, instead of failing for expired names. Names keep resolving to their address for a
+    /// grace period after they expire.
+    #[method(name = "resolveNameServiceStatus")]
+    async fn resolve_name_service_status(
+        &self,
+        /// The name to resolve
+        name: String,
+    ) -> RpcResult<SuiNameResolution>;
+
+    /// Fetch a SuiNS name's full record, including its avatar, content hash, and any custom
+    /// data, whether or not it has expired.
+    #[method(name = "getNameServiceRecord")]
+    async fn get_name_service_record(
+        &self,
+        /// The name to fetch the record for
+        name: String,
+    ) -> RpcResult<SuiNameRecord>;
+
+    /// Fetch what is needed to render a profile card for a SuiNS name: the address it resolves
+    /// to, its avatar (if it is owned by that address), and its website.
+    #[method(name = "getNameServiceProfile")]
+    async fn get_name_service_profile(
+        &self,
+        /// The name to fetch the profile for
+        name: String,
+    ) -> RpcResult<SuiNameProfile>;
 }
 
-pub(crate) struct NameService(pub Context, pub NameServiceConfig);
+pub(crate) struct NameService(pub Context);
 
 #[async_trait::async_trait]
 impl NameServiceApiServer for NameService {
     async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<SuiAddress>> {
-        let Self(ctx, config) = self;
-        Ok(response::resolved_address(ctx, config, &name)
+        let Self(ctx) = self;
+        let config = ctx.config();
+        Ok(response::resolved_address(ctx, &config.name_service, &name)
             .await
             .with_internal_context(|| format!("Resolving SuiNS name {name:?}"))?)
     }
+
+    async fn resolve_name_service_status(&self, name: String) -> RpcResult<SuiNameResolution> {
+        let Self(ctx) = self;
+        let config = ctx.config();
+        Ok(response::resolution(ctx, &config.name_service, &name)
+            .await
+            .with_internal_context(|| format!("Resolving status of SuiNS name {name:?}"))?)
+    }
+
+    async fn get_name_service_record(&self, name: String) -> RpcResult<SuiNameRecord> {
+        let Self(ctx) = self;
+        let config = ctx.config();
+        Ok(response::record(ctx, &config.name_service, &name)
+            .await
+            .with_internal_context(|| format!("Fetching record for SuiNS name {name:?}"))?)
+    }
+
+    async fn get_name_service_profile(&self, name: String) -> RpcResult<SuiNameProfile> {
+        let Self(ctx) = self;
+        let config = ctx.config();
+        Ok(response::profile(ctx, &config.name_service, &name)
+            .await
+            .with_internal_context(|| format!("Fetching profile for SuiNS name {name:?}"))?)
+    }
 }
 
 impl RpcModule for NameService {
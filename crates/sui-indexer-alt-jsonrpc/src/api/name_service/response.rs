@@ -1,12 +1,18 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::str::FromStr;
+
 use anyhow::Context as _;
 use diesel::{ExpressionMethods, QueryDsl};
 use futures::future::OptionFuture;
 use sui_indexer_alt_schema::schema::watermarks;
-use sui_name_service::{Domain, NameRecord, NameServiceConfig, NameServiceError};
-use sui_types::base_types::SuiAddress;
+use sui_json_rpc_types::{SuiNameProfile, SuiNameRecord, SuiNameResolution, SuiNameStatus};
+use sui_name_service::{
+    Domain, DomainFormat, NameRecord, NameServiceConfig, NameStatus, AVATAR_KEY, CONTENT_HASH_KEY,
+    WALRUS_SITE_ID_KEY,
+};
+use sui_types::base_types::{ObjectID, SuiAddress};
 use tokio::join;
 
 use crate::{
@@ -18,7 +24,7 @@ use crate::{
 use super::Error;
 
 /// Attempt to to translate the given SuiNS `name` to its address, as long as the mapping exists,
-/// and it hasn't expired.
+/// and it hasn't expired (beyond its grace period).
 pub(super) async fn resolved_address(
     ctx: &Context,
     config: &NameServiceConfig,
@@ -26,10 +32,184 @@ pub(super) async fn resolved_address(
 ) -> Result<Option<SuiAddress>, RpcError<Error>> {
     use Error as E;
 
+    let records = name_records(ctx, config, name).await?;
+
+    let parent_record = if records.domain.is_leaf_record() {
+        let Some(parent_record) = records.parent else {
+            // If the domain object exists but the parent object does not, it could indicate the
+            // sub-domain has expired because the parent has been re-registered.
+            return Err(invalid_params(E::NotFound(
+                records.name.parent().to_string(),
+            )));
+        };
+
+        Some(parent_record)
+    } else {
+        None
+    };
+
+    records
+        .domain
+        .resolve(
+            parent_record.as_ref(),
+            records.timestamp_ms,
+            config.grace_period_ms,
+        )
+        .map_err(|e| invalid_params(E::NameService(e)))
+}
+
+/// Resolve the given SuiNS `name`, reporting whether its registration has expired, and when,
+/// instead of failing for expired names.
+pub(super) async fn resolution(
+    ctx: &Context,
+    config: &NameServiceConfig,
+    name: &str,
+) -> Result<SuiNameResolution, RpcError<Error>> {
+    let records = name_records(ctx, config, name).await?;
+    let status = records.status(config);
+
+    Ok(SuiNameResolution {
+        address: records.resolved_address(status),
+        expiration_timestamp_ms: records.expiration_timestamp_ms(),
+        status,
+    })
+}
+
+/// Fetch the full record for the given SuiNS `name`, regardless of whether it has expired.
+pub(super) async fn record(
+    ctx: &Context,
+    config: &NameServiceConfig,
+    name: &str,
+) -> Result<SuiNameRecord, RpcError<Error>> {
+    let records = name_records(ctx, config, name).await?;
+    let status = records.status(config);
+    let expiration_timestamp_ms = records.expiration_timestamp_ms();
+
+    let NameRecord {
+        nft_id,
+        target_address,
+        data,
+        ..
+    } = records.domain;
+
+    Ok(SuiNameRecord {
+        name: records.name.to_string(),
+        nft_id: nft_id.bytes,
+        target_address,
+        expiration_timestamp_ms,
+        status,
+        data: data
+            .contents
+            .into_iter()
+            .map(|entry| (entry.key, entry.value))
+            .collect(),
+    })
+}
+
+/// Gather what is needed to render a profile card for the given SuiNS `name`. The avatar is only
+/// included if it is still owned by the address the name resolves to.
+pub(super) async fn profile(
+    ctx: &Context,
+    config: &NameServiceConfig,
+    name: &str,
+) -> Result<SuiNameProfile, RpcError<Error>> {
+    let records = name_records(ctx, config, name).await?;
+    let status = records.status(config);
+    let address = records.resolved_address(status);
+
+    let mut profile = SuiNameProfile {
+        name: records.name.format(DomainFormat::Dot),
+        short_name: records.name.format(DomainFormat::At),
+        address,
+        avatar: None,
+        avatar_type: None,
+        content_hash: None,
+        walrus_site_id: None,
+        expiration_timestamp_ms: records.expiration_timestamp_ms(),
+        status,
+    };
+
+    if status == SuiNameStatus::Expired {
+        return Ok(profile);
+    }
+
+    let data = &records.domain.data;
+    profile.content_hash = data.get(&CONTENT_HASH_KEY.to_owned()).cloned();
+    profile.walrus_site_id = data.get(&WALRUS_SITE_ID_KEY.to_owned()).cloned();
+
+    let avatar_id = data
+        .get(&AVATAR_KEY.to_owned())
+        .and_then(|id| ObjectID::from_str(id).ok());
+
+    if let (Some(address), Some(avatar_id)) = (address, avatar_id) {
+        let avatar = load_live(ctx, avatar_id)
+            .await
+            .context("Failed to fetch avatar")?;
+
+        if let Some(avatar) = avatar.filter(|a| a.get_single_owner() == Some(address)) {
+            profile.avatar = Some(avatar_id);
+            profile.avatar_type = avatar
+                .struct_tag()
+                .map(|tag| tag.to_canonical_string(/* with_prefix */ true));
+        }
+    }
+
+    Ok(profile)
+}
+
+/// The records needed to resolve a name, as of the latest checkpoint.
+struct NameRecords {
+    name: Domain,
+    timestamp_ms: u64,
+    domain: NameRecord,
+    /// The record for the domain's parent, if the domain is a sub-domain, and its parent exists.
+    parent: Option<NameRecord>,
+}
+
+impl NameRecords {
+    fn status(&self, config: &NameServiceConfig) -> SuiNameStatus {
+        match self.domain.status(
+            self.parent.as_ref(),
+            self.timestamp_ms,
+            config.grace_period_ms,
+        ) {
+            NameStatus::Active => SuiNameStatus::Active,
+            NameStatus::GracePeriod => SuiNameStatus::GracePeriod,
+            NameStatus::Expired => SuiNameStatus::Expired,
+        }
+    }
+
+    fn expiration_timestamp_ms(&self) -> Option<u64> {
+        self.domain
+            .effective_expiration_timestamp_ms(self.parent.as_ref())
+    }
+
+    /// The address the name resolves to, given its `status`: Expired names don't resolve.
+    fn resolved_address(&self, status: SuiNameStatus) -> Option<SuiAddress> {
+        if status == SuiNameStatus::Expired {
+            None
+        } else {
+            self.domain.target_address
+        }
+    }
+}
+
+/// Fetch the record for the given SuiNS `name`, failing if it does not exist.
+async fn name_records(
+    ctx: &Context,
+    config: &NameServiceConfig,
+    name: &str,
+) -> Result<NameRecords, RpcError<Error>> {
+    use Error as E;
+
     let domain: Domain = name
         .parse()
         .map_err(|e| invalid_params(E::NameService(e)))?;
 
+    config
+        .validate_depth(&domain)
+        .map_err(|e| invalid_params(E::NameService(e)))?;
+
     let domain_record_id = config.record_field_id(&domain);
     let parent_record_id = config.record_field_id(&domain.parent());
 
@@ -40,8 +220,9 @@ pub(super) async fn resolved_address(
         .into();
 
     // Fetch the current timestamp, the domain record. If the domain being resolved is a
-    // sub-domain, then also fetch the parent record, because its expiry is controlled by its
-    // parent's.
+    // sub-domain, then also fetch the parent record, because if it is a leaf, its expiry is
+    // controlled by its parent's. Leaves can only be created under nodes, which carry their own
+    // expiry, so there is no need to look further up, however deeply the domain is nested.
     let (timestamp_ms, domain_object, parent_object) =
         join!(latest_timestamp_ms(ctx), domain_object, parent_object);
 
@@ -54,41 +235,20 @@ pub(super) async fn resolved_address(
     let domain_record =
         NameRecord::try_from(domain_object).context("Failed to deserialize domain record")?;
 
-    // If the domain being fetched is not a leaf node, then check its expiry directly.
-    if !domain_record.is_leaf_record() {
-        return if !domain_record.is_node_expired(timestamp_ms) {
-            Ok(domain_record.target_address)
-        } else {
-            return Err(invalid_params(E::NameService(
-                NameServiceError::NameExpired,
-            )));
-        };
-    }
-
-    // Otherwise its expiry depends on its parent's record: It must exist, and the domain's record
-    // must recognise the fetched parent as its parent, and the parent must not be expired.
-    let Some(parent_object) = parent_object
+    let parent_record = parent_object
         .transpose()
         .context("Failed to fetch parent record")?
         .flatten()
-    else {
-        // If the domain object exists but the parent object does not, it could indicate the
-        // sub-domain has expired because the parent has been re-registered.
-        return Err(invalid_params(E::NotFound(domain.parent().to_string())));
-    };
-
-    let parent_record =
-        NameRecord::try_from(parent_object).context("Failed to deserialize parent record")?;
+        .map(NameRecord::try_from)
+        .transpose()
+        .context("Failed to deserialize parent record")?;
 
-    if parent_record.is_valid_leaf_parent(&domain_record)
-        && !parent_record.is_node_expired(timestamp_ms)
-    {
-        Ok(domain_record.target_address)
-    } else {
-        Err(invalid_params(E::NameService(
-            NameServiceError::NameExpired,
-        )))
-    }
+    Ok(NameRecords {
+        name: domain,
+        timestamp_ms,
+        domain: domain_record,
+        parent: parent_record,
+    })
 }
 
 /// Fetch the latest timestamp from the database, based on the watermark for the `obj_info`
@@ -0,0 +1,316 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::Context as _;
+use move_core_types::{annotated_value::MoveValue, language_storage::StructTag};
+use sui_json_rpc_types::{DisplayFieldsResponse, SuiMoveStruct, SuiMoveValue, SuiMoveVariant};
+use sui_types::{
+    display::DisplayVersionUpdatedEvent, error::SuiObjectResponseError, object::Object, TypeTag,
+};
+
+use crate::{
+    context::Context,
+    data::displays::DisplayKey,
+    error::{rpc_bail, RpcError},
+};
+
+use super::response::struct_layout;
+
+/// Reasons a single field of a `Display` could not be rendered. These are reported in the
+/// response's `display.error`, rather than failing the request.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+enum Error {
+    #[error("Display template value cannot be empty")]
+    EmptyField,
+
+    #[error("Display template value {0} exceeds the maximum nesting depth of {1}")]
+    TooDeep(String, usize),
+
+    #[error("Field value {0} cannot be found in struct")]
+    FieldNotFound(String),
+
+    #[error("Field value {0} does not refer to a struct or enum field")]
+    NotAField(String),
+
+    #[error("Vector is not supported as a Display value {0}")]
+    Vector(String),
+}
+
+/// Render the `Display` for `object`'s type, using the object's contents. Packages and objects
+/// whose types do not have a `Display` are rendered without any fields. Fields that fail to render
+/// are left out, and the reasons they failed are reported in the response's error.
+pub(crate) async fn object_display(
+    ctx: &Context,
+    object: &Object,
+) -> Result<DisplayFieldsResponse, RpcError> {
+    let Some(move_object) = object.data.try_as_move() else {
+        return Ok(DisplayFieldsResponse {
+            data: None,
+            error: None,
+        });
+    };
+
+    let type_: StructTag = move_object.type_().clone().into();
+    let Some(template) = template(ctx, &type_).await? else {
+        return Ok(DisplayFieldsResponse {
+            data: None,
+            error: None,
+        });
+    };
+
+    let layout = struct_layout(ctx, TypeTag::Struct(Box::new(type_))).await?;
+    let move_struct = move_object
+        .to_move_struct(&layout)
+        .context("Failed to deserialize object contents")?;
+
+    let SuiMoveValue::Struct(move_struct) = MoveValue::Struct(move_struct).into() else {
+        rpc_bail!("Object contents did not deserialize as a struct");
+    };
+
+    let max_depth = ctx.config().objects.max_display_field_depth;
+    Ok(render(&template, &move_struct, max_depth))
+}
+
+/// The latest `Display` template for `type_`, served from the cache if it was loaded recently
+/// enough, or `None` if the type does not have a `Display`.
+async fn template(
+    ctx: &Context,
+    type_: &StructTag,
+) -> Result<Option<Arc<DisplayVersionUpdatedEvent>>, RpcError> {
+    let cache = ctx.display_cache();
+    let ttl = ctx.config().objects.display_cache_ttl;
+    if let Some(template) = cache.get(type_, ttl) {
+        return Ok(template);
+    }
+
+    let stored = ctx
+        .pg_loader()
+        .load_one(DisplayKey(type_.clone()))
+        .await
+        .context("Failed to load Display template")?;
+
+    let template = stored
+        .map(|stored| bcs::from_bytes::<DisplayVersionUpdatedEvent>(&stored.display))
+        .transpose()
+        .context("Failed to deserialize Display template")?
+        .map(Arc::new);
+
+    cache.insert(type_.clone(), template.clone());
+    Ok(template)
+}
+
+/// Render every field in `template` using values from `move_struct`.
+fn render(
+    template: &DisplayVersionUpdatedEvent,
+    move_struct: &SuiMoveStruct,
+    max_depth: usize,
+) -> DisplayFieldsResponse {
+    let mut data = BTreeMap::new();
+    let mut errors = vec![];
+
+    for entry in &template.fields.contents {
+        match parse_template(&entry.value, move_struct, max_depth) {
+            Ok(value) => {
+                data.insert(entry.key.clone(), value);
+            }
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+
+    let error = (!errors.is_empty()).then(|| SuiObjectResponseError::DisplayError {
+        error: errors.join("; "),
+    });
+
+    DisplayFieldsResponse {
+        data: Some(data),
+        error,
+    }
+}
+
+/// Substitute every `{field.path}` expression in `template` with the value it refers to in
+/// `move_struct`. Braces and backslashes can be escaped with a backslash to be output literally.
+fn parse_template(
+    template: &str,
+    move_struct: &SuiMoveStruct,
+    max_depth: usize,
+) -> Result<String, Error> {
+    let mut output = String::new();
+    let mut var_name = String::new();
+    let mut in_braces = false;
+    let mut escaped = false;
+
+    for ch in template.chars() {
+        match ch {
+            '\\' if !escaped => {
+                escaped = true;
+                continue;
+            }
+
+            '{' if !escaped => {
+                // An unterminated expression is output as-is.
+                if in_braces {
+                    output.push('{');
+                    output.push_str(&var_name);
+                }
+
+                in_braces = true;
+                var_name.clear();
+            }
+
+            '}' if !escaped && in_braces => {
+                in_braces = false;
+                output.push_str(&field_value(move_struct, &var_name, max_depth)?);
+            }
+
+            _ if in_braces => var_name.push(ch),
+            _ => output.push(ch),
+        }
+
+        escaped = false;
+    }
+
+    if in_braces {
+        output.push('{');
+        output.push_str(&var_name);
+    }
+
+    Ok(output)
+}
+
+/// Follow the dot-separated `var_name` through the fields of `move_struct` (and any structs or
+/// enums nested inside it), and render the value at the end of the path as a string.
+fn field_value(
+    move_struct: &SuiMoveStruct,
+    var_name: &str,
+    max_depth: usize,
+) -> Result<String, Error> {
+    if var_name.is_empty() {
+        return Err(Error::EmptyField);
+    }
+
+    let parts: Vec<_> = var_name.split('.').collect();
+    if parts.len() > max_depth {
+        return Err(Error::TooDeep(var_name.to_owned(), max_depth));
+    }
+
+    let mut fields = struct_fields(move_struct);
+    let mut value = None;
+    for part in parts {
+        let Some(current) = fields else {
+            return Err(Error::NotAField(var_name.to_owned()));
+        };
+
+        let next = current
+            .get(part)
+            .ok_or_else(|| Error::FieldNotFound(var_name.to_owned()))?;
+
+        fields = match next {
+            SuiMoveValue::Struct(s) => struct_fields(s),
+            SuiMoveValue::Variant(SuiMoveVariant { fields, .. }) => Some(fields),
+            _ => None,
+        };
+
+        value = Some(next);
+    }
+
+    match value {
+        None => Err(Error::EmptyField),
+        Some(SuiMoveValue::Option(o)) => match o.as_ref() {
+            Some(v) => Ok(v.to_string()),
+            None => Ok(String::new()),
+        },
+        Some(SuiMoveValue::Vector(_)) => Err(Error::Vector(var_name.to_owned())),
+        Some(v) => Ok(v.to_string()),
+    }
+}
+
+/// The named fields of `move_struct`, if it has any.
+fn struct_fields(move_struct: &SuiMoveStruct) -> Option<&BTreeMap<String, SuiMoveValue>> {
+    match move_struct {
+        SuiMoveStruct::WithTypes { fields, .. } | SuiMoveStruct::WithFields(fields) => Some(fields),
+        SuiMoveStruct::Runtime(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(fields: Vec<(&str, SuiMoveValue)>) -> SuiMoveStruct {
+        SuiMoveStruct::WithFields(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name.to_owned(), value))
+                .collect(),
+        )
+    }
+
+    fn nft() -> SuiMoveStruct {
+        fields(vec![
+            ("name", SuiMoveValue::String("Sui".to_owned())),
+            ("level", SuiMoveValue::Number(42)),
+            (
+                "inner",
+                SuiMoveValue::Struct(fields(vec![("url", SuiMoveValue::String("a.b".into()))])),
+            ),
+            ("tag", SuiMoveValue::Option(Box::new(None))),
+            ("tags", SuiMoveValue::Vector(vec![])),
+        ])
+    }
+
+    #[test]
+    fn test_render_fields() {
+        let nft = nft();
+        assert_eq!(
+            parse_template("{name} is level {level}", &nft, 10).unwrap(),
+            "Sui is level 42",
+        );
+
+        assert_eq!(
+            parse_template("https://{inner.url}/", &nft, 10).unwrap(),
+            "https://a.b/"
+        );
+
+        assert_eq!(parse_template("[{tag}]", &nft, 10).unwrap(), "[]");
+    }
+
+    #[test]
+    fn test_render_escapes() {
+        let nft = nft();
+        assert_eq!(
+            parse_template(r"\{name\} is {name}", &nft, 10).unwrap(),
+            "{name} is Sui"
+        );
+
+        assert_eq!(parse_template(r"a\\b", &nft, 10).unwrap(), r"a\b");
+        assert_eq!(parse_template("{name", &nft, 10).unwrap(), "{name");
+    }
+
+    #[test]
+    fn test_render_errors() {
+        let nft = nft();
+        assert_eq!(parse_template("{}", &nft, 10), Err(Error::EmptyField));
+
+        assert_eq!(
+            parse_template("{missing}", &nft, 10),
+            Err(Error::FieldNotFound("missing".to_owned())),
+        );
+
+        assert_eq!(
+            parse_template("{name.len}", &nft, 10),
+            Err(Error::NotAField("name.len".to_owned())),
+        );
+
+        assert_eq!(
+            parse_template("{tags}", &nft, 10),
+            Err(Error::Vector("tags".to_owned())),
+        );
+
+        assert_eq!(
+            parse_template("{inner.url}", &nft, 1),
+            Err(Error::TooDeep("inner.url".to_owned(), 1)),
+        );
+    }
+}
@@ -8,4 +8,13 @@ pub(super) enum Error {
 
     #[error("Requested {requested} keys, exceeding maximum {max}")]
     TooManyKeys { requested: usize, max: usize },
+
+    #[error("Filter has {nodes} nodes, exceeding maximum {max}")]
+    FilterTooComplex { nodes: usize, max: usize },
+
+    #[error("Type {0:?} is not a struct type")]
+    NotAStruct(String),
+
+    #[error(transparent)]
+    MoveRegistry(#[from] crate::move_registry::Error),
 }
@@ -2,22 +2,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Context as _;
-use diesel::{sql_types::Bool, BoolExpressionMethods, ExpressionMethods, JoinOnDsl, QueryDsl};
+use diesel::{
+    expression::is_aggregate, pg::Pg, sql_types::Bool, BoolExpressionMethods, BoxableExpression,
+    ExpressionMethods, JoinOnDsl, QueryDsl,
+};
+use futures::future::{BoxFuture, FutureExt};
 use move_core_types::language_storage::StructTag;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
 use sui_indexer_alt_schema::{objects::StoredOwnerKind, schema::obj_info};
 use sui_json_rpc_types::{Page as PageResponse, SuiObjectDataOptions};
 use sui_sql_macro::sql;
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
-    sui_serde::SuiStructTag,
     Identifier, TypeTag,
 };
 
 use crate::{
-    error::RpcError,
+    error::{invalid_params, RpcError},
+    move_registry::resolve_type,
     paginate::{BcsCursor, Cursor as _, Page},
     Context,
 };
@@ -33,7 +36,6 @@ pub(crate) struct SuiObjectResponseQuery {
     pub options: Option<SuiObjectDataOptions>,
 }
 
-#[serde_as]
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub(crate) enum SuiObjectDataFilter {
     /// Query by the object type's package.
@@ -46,56 +48,192 @@ pub(crate) enum SuiObjectDataFilter {
         #[schemars(with = "String")]
         module: Identifier,
     },
-    /// Query by the object's type.
-    StructType(
-        #[serde_as(as = "SuiStructTag")]
-        #[schemars(with = "String")]
-        StructTag,
-    ),
+    /// Query by the object's type. Packages can be referred to by their Move Registry names, as
+    /// well as their addresses.
+    StructType(String),
+    /// Query for objects that match all of the filters.
+    MatchAll(Vec<SuiObjectDataFilter>),
+    /// Query for objects that match any of the filters.
+    MatchAny(Vec<SuiObjectDataFilter>),
 }
 
 #[derive(Clone, Serialize, Deserialize)]
-struct ObjectCursor {
+pub(crate) struct ObjectCursor {
     object_id: Vec<u8>,
     cp_sequence_number: u64,
 }
 
-type Cursor = BcsCursor<ObjectCursor>;
+pub(crate) type Cursor = BcsCursor<ObjectCursor>;
 type ObjectIDs = PageResponse<ObjectID, String>;
 
+/// A boolean SQL expression over the candidate objects of an owned objects query.
+type Condition<QS> =
+    Box<dyn BoxableExpression<QS, Pg, (), is_aggregate::Never, SqlType = Bool> + Send>;
+
+/// A filter with any Move Registry names resolved to package addresses.
+enum ResolvedFilter {
+    Type(TypeFilter),
+    All(Vec<ResolvedFilter>),
+    Any(Vec<ResolvedFilter>),
+}
+
+/// A filter on the objects' types.
+struct TypeFilter {
+    package: ObjectID,
+    module: Option<String>,
+    name: Option<String>,
+    type_params: Option<Vec<TypeTag>>,
+}
+
 impl SuiObjectDataFilter {
-    fn package(&self) -> ObjectID {
+    /// The number of filters in this filter, including itself and any filters nested inside it.
+    pub(crate) fn nodes(&self) -> usize {
         match self {
-            SuiObjectDataFilter::Package(p) => *p,
-            SuiObjectDataFilter::MoveModule { package, .. } => *package,
-            SuiObjectDataFilter::StructType(tag) => tag.address.into(),
+            SuiObjectDataFilter::Package(_)
+            | SuiObjectDataFilter::MoveModule { .. }
+            | SuiObjectDataFilter::StructType(_) => 1,
+
+            SuiObjectDataFilter::MatchAll(filters) | SuiObjectDataFilter::MatchAny(filters) => {
+                1 + filters.iter().map(Self::nodes).sum::<usize>()
+            }
         }
     }
 
-    fn module(&self) -> Option<&str> {
-        match self {
-            SuiObjectDataFilter::Package(_) => None,
-            SuiObjectDataFilter::MoveModule { module, .. } => Some(module.as_str()),
-            SuiObjectDataFilter::StructType(tag) => Some(tag.module.as_str()),
+    fn resolve<'a>(
+        &'a self,
+        ctx: &'a Context,
+    ) -> BoxFuture<'a, Result<ResolvedFilter, RpcError<Error>>> {
+        async move {
+            Ok(match self {
+                SuiObjectDataFilter::MatchAll(filters) => {
+                    let mut resolved = Vec::with_capacity(filters.len());
+                    for filter in filters {
+                        resolved.push(filter.resolve(ctx).await?);
+                    }
+
+                    ResolvedFilter::All(resolved)
+                }
+
+                SuiObjectDataFilter::MatchAny(filters) => {
+                    let mut resolved = Vec::with_capacity(filters.len());
+                    for filter in filters {
+                        resolved.push(filter.resolve(ctx).await?);
+                    }
+
+                    ResolvedFilter::Any(resolved)
+                }
+
+                filter => ResolvedFilter::Type(filter.resolve_type(ctx).await?),
+            })
         }
+        .boxed()
     }
 
-    fn name(&self) -> Option<&str> {
-        match self {
-            SuiObjectDataFilter::Package(_) => None,
-            SuiObjectDataFilter::MoveModule { .. } => None,
-            SuiObjectDataFilter::StructType(tag) => Some(tag.name.as_str()),
-        }
+    async fn resolve_type(&self, ctx: &Context) -> Result<TypeFilter, RpcError<Error>> {
+        Ok(match self {
+            SuiObjectDataFilter::Package(p) => TypeFilter {
+                package: *p,
+                module: None,
+                name: None,
+                type_params: None,
+            },
+
+            SuiObjectDataFilter::MoveModule { package, module } => TypeFilter {
+                package: *package,
+                module: Some(module.to_string()),
+                name: None,
+                type_params: None,
+            },
+
+            SuiObjectDataFilter::StructType(type_) => {
+                let TypeTag::Struct(tag) =
+                    resolve_type(ctx, &ctx.config().move_registry, type_).await?
+                else {
+                    return Err(invalid_params(Error::NotAStruct(type_.clone())));
+                };
+
+                let StructTag {
+                    address,
+                    module,
+                    name,
+                    type_params,
+                } = *tag;
+
+                TypeFilter {
+                    package: address.into(),
+                    module: Some(module.to_string()),
+                    name: Some(name.to_string()),
+                    type_params: (!type_params.is_empty()).then_some(type_params),
+                }
+            }
+
+            SuiObjectDataFilter::MatchAll(_) | SuiObjectDataFilter::MatchAny(_) => {
+                unreachable!("Compound filters are resolved by `resolve`")
+            }
+        })
     }
+}
 
-    fn type_params(&self) -> Option<&[TypeTag]> {
-        match self {
-            SuiObjectDataFilter::Package(_) => None,
-            SuiObjectDataFilter::MoveModule { .. } => None,
-            SuiObjectDataFilter::StructType(tag) => {
-                (!tag.type_params.is_empty()).then(|| &tag.type_params[..])
+impl ResolvedFilter {
+    /// The condition that candidate objects must satisfy to match this filter. An empty `MatchAll`
+    /// matches every object, and an empty `MatchAny` matches none.
+    fn condition<QS>(self) -> anyhow::Result<Condition<QS>> {
+        Ok(match self {
+            ResolvedFilter::Type(filter) => filter.condition()?,
+
+            ResolvedFilter::All(filters) => {
+                let mut condition: Condition<QS> = Box::new(sql!(as Bool, "TRUE"));
+                for filter in filters {
+                    condition = Box::new(condition.and(filter.condition()?));
+                }
+
+                condition
+            }
+
+            ResolvedFilter::Any(filters) => {
+                let mut condition: Condition<QS> = Box::new(sql!(as Bool, "FALSE"));
+                for filter in filters {
+                    condition = Box::new(condition.or(filter.condition()?));
+                }
+
+                condition
             }
+        })
+    }
+}
+
+impl TypeFilter {
+    fn condition<QS>(self) -> anyhow::Result<Condition<QS>> {
+        let TypeFilter {
+            package,
+            module,
+            name,
+            type_params,
+        } = self;
+
+        let mut condition: Condition<QS> = Box::new(sql!(as Bool,
+            "candidates.package = {Bytea}",
+            package.to_vec(),
+        ));
+
+        if let Some(module) = module {
+            condition =
+                Box::new(condition.and(sql!(as Bool, "candidates.module = {Text}", module)));
+        }
+
+        if let Some(name) = name {
+            condition = Box::new(condition.and(sql!(as Bool, "candidates.name = {Text}", name)));
         }
+
+        if let Some(type_params) = type_params {
+            let bytes = bcs::to_bytes(&type_params).context("Failed to serialize type params")?;
+            condition = Box::new(condition.and(sql!(as Bool,
+                "candidates.instantiation = {Bytea}",
+                bytes,
+            )));
+        }
+
+        Ok(condition)
     }
 }
 
@@ -157,22 +295,16 @@ pub(super) async fn owned_objects(
         ));
     }
 
-    let filter = filter.as_ref();
-    if let Some(package) = filter.map(|f| f.package()) {
-        query = query.filter(candidates!(package).eq(package.into_bytes()));
-    }
-
-    if let Some(module) = filter.and_then(|f| f.module()) {
-        query = query.filter(candidates!(module).eq(module));
-    }
-
-    if let Some(name) = filter.and_then(|f| f.name()) {
-        query = query.filter(candidates!(name).eq(name));
-    }
+    if let Some(filter) = filter {
+        let nodes = filter.nodes();
+        if nodes > config.max_filter_nodes {
+            return Err(invalid_params(Error::FilterTooComplex {
+                nodes,
+                max: config.max_filter_nodes,
+            }));
+        }
 
-    if let Some(type_params) = filter.and_then(|f| f.type_params()) {
-        let bytes = bcs::to_bytes(type_params).context("Failed to serialize type params")?;
-        query = query.filter(candidates!(instantiation).eq(bytes));
+        query = query.filter(filter.resolve(ctx).await?.condition()?);
     }
 
     let mut results: Vec<(Vec<u8>, i64)> = ctx
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::time::Duration;
+
 use filter::SuiObjectResponseQuery;
 use futures::future;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
@@ -21,8 +23,9 @@ use super::rpc_module::RpcModule;
 
 use self::error::Error;
 
+pub(crate) mod display;
 mod error;
-mod filter;
+pub(crate) mod filter;
 pub(crate) mod response;
 
 #[open_rpc(namespace = "sui", tag = "Objects API")]
@@ -109,9 +112,9 @@ trait QueryObjectsApi {
     ) -> RpcResult<Page<SuiObjectResponse, String>>;
 }
 
-pub(crate) struct Objects(pub Context, pub ObjectsConfig);
+pub(crate) struct Objects(pub Context);
 
-pub(crate) struct QueryObjects(pub Context, pub ObjectsConfig);
+pub(crate) struct QueryObjects(pub Context);
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ObjectsConfig {
@@ -124,6 +127,21 @@ pub struct ObjectsConfig {
     /// The largest acceptable page size when querying transactions. Requesting a page larger than
     /// this is a user error.
     pub max_page_size: usize,
+
+    /// The maximum number of filters in an owned objects query, counting the filters nested
+    /// inside `MatchAll` and `MatchAny` filters, as well as the filters themselves.
+    pub max_filter_nodes: usize,
+
+    /// The number of types to cache `Display` templates for. This is only read on start-up.
+    pub display_cache_size: usize,
+
+    /// How long a type's `Display` template can be served from the cache before it is reloaded,
+    /// to pick up on-chain updates to it. A TTL of zero disables caching.
+    pub display_cache_ttl: Duration,
+
+    /// The maximum number of nested fields a `Display` template can access in a single
+    /// expression, e.g. `{a.b.c}` accesses three.
+    pub max_display_field_depth: usize,
 }
 
 #[async_trait::async_trait]
@@ -133,7 +151,7 @@ impl ObjectsApiServer for Objects {
         object_id: ObjectID,
         options: Option<SuiObjectDataOptions>,
     ) -> RpcResult<SuiObjectResponse> {
-        let Self(ctx, _) = self;
+        let Self(ctx) = self;
         let options = options.unwrap_or_default();
         Ok(response::live_object(ctx, object_id, &options)
             .await
@@ -147,11 +165,12 @@ impl ObjectsApiServer for Objects {
         object_ids: Vec<ObjectID>,
         options: Option<SuiObjectDataOptions>,
     ) -> RpcResult<Vec<SuiObjectResponse>> {
-        let Self(ctx, config) = self;
-        if object_ids.len() > config.max_multi_get_objects {
+        let Self(ctx) = self;
+        let config = ctx.config();
+        if object_ids.len() > config.objects.max_multi_get_objects {
             return Err(invalid_params(Error::TooManyKeys {
                 requested: object_ids.len(),
-                max: config.max_multi_get_objects,
+                max: config.objects.max_multi_get_objects,
             })
             .into());
         }
@@ -178,7 +197,7 @@ impl ObjectsApiServer for Objects {
         version: SequenceNumber,
         options: Option<SuiObjectDataOptions>,
     ) -> RpcResult<SuiPastObjectResponse> {
-        let Self(ctx, _) = self;
+        let Self(ctx) = self;
         let options = options.unwrap_or_default();
         Ok(response::past_object(ctx, object_id, version, &options)
             .await
@@ -195,11 +214,12 @@ impl ObjectsApiServer for Objects {
         past_objects: Vec<SuiGetPastObjectRequest>,
         options: Option<SuiObjectDataOptions>,
     ) -> RpcResult<Vec<SuiPastObjectResponse>> {
-        let Self(ctx, config) = self;
-        if past_objects.len() > config.max_multi_get_objects {
+        let Self(ctx) = self;
+        let config = ctx.config();
+        if past_objects.len() > config.objects.max_multi_get_objects {
             return Err(invalid_params(Error::TooManyKeys {
                 requested: past_objects.len(),
-                max: config.max_multi_get_objects,
+                max: config.objects.max_multi_get_objects,
             })
             .into());
         }
@@ -232,7 +252,8 @@ impl QueryObjectsApiServer for QueryObjects {
         cursor: Option<String>,
         limit: Option<usize>,
     ) -> RpcResult<Page<SuiObjectResponse, String>> {
-        let Self(ctx, confige) = self;
+        let Self(ctx) = self;
+        let config = ctx.config();
 
         let query = query.unwrap_or_default();
 
@@ -240,7 +261,8 @@ impl QueryObjectsApiServer for QueryObjects {
             data: object_ids,
             next_cursor,
             has_next_page,
-        } = filter::owned_objects(ctx, confige, address, &query.filter, cursor, limit).await?;
+        } = filter::owned_objects(ctx, &config.objects, address, &query.filter, cursor, limit)
+            .await?;
 
         let options = query.options.unwrap_or_default();
 
@@ -291,6 +313,10 @@ impl Default for ObjectsConfig {
             max_multi_get_objects: 50,
             default_page_size: 50,
             max_page_size: 100,
+            max_filter_nodes: 10,
+            display_cache_size: 10_000,
+            display_cache_ttl: Duration::from_secs(10),
+            max_display_field_depth: 10,
         }
     }
 }
@@ -3,13 +3,14 @@
 
 use anyhow::Context as _;
 use futures::future::OptionFuture;
-use move_core_types::annotated_value::MoveTypeLayout;
+use move_core_types::annotated_value::{MoveStructLayout, MoveTypeLayout};
 use sui_json_rpc_types::{
-    SuiData, SuiObjectData, SuiObjectDataOptions, SuiObjectResponse, SuiParsedData,
+    SuiData, SuiObjectData, SuiObjectDataOptions, SuiObjectRef, SuiObjectResponse, SuiParsedData,
     SuiPastObjectResponse, SuiRawData,
 };
 use sui_types::{
     base_types::{ObjectID, ObjectType, SequenceNumber},
+    digests::ObjectDigest,
     error::SuiObjectResponseError,
     object::{Data, Object},
     TypeTag,
@@ -18,10 +19,15 @@ use tokio::join;
 
 use crate::{
     context::Context,
-    data::{object_info::LatestObjectInfoKey, objects::load_latest},
+    data::{
+        object_info::LatestObjectInfoKey, object_versions::LatestObjectVersionKey,
+        objects::load_latest,
+    },
     error::{rpc_bail, InternalContext, RpcError},
 };
 
+use super::display;
+
 /// Fetch the necessary data from the stores in `ctx` and transform it to build a response for a
 /// the latest version of an object, identified by its ID, according to the response `options`.
 pub(super) async fn live_object(
@@ -77,24 +83,60 @@ pub(super) async fn latest_object(
 
 /// Fetch the necessary data from the stores in `ctx` and transform it to build a response for a
 /// past object identified by its ID and version, according to the response `options`.
+///
+/// If the version was not found, the response distinguishes between objects that do not exist at
+/// all, versions that are newer than the latest version of the object, and versions that are
+/// otherwise missing (e.g. because they were pruned, or never existed).
 pub(super) async fn past_object(
     ctx: &Context,
     object_id: ObjectID,
     version: SequenceNumber,
     options: &SuiObjectDataOptions,
 ) -> Result<SuiPastObjectResponse, RpcError> {
-    let Some(object) = ctx
+    match ctx
         .kv_loader()
-        .load_one_object(object_id, version.value())
+        .load_one_object_or_removed(object_id, version.value())
         .await
         .context("Failed to load object from store")?
+    {
+        Some(Some(object)) => {
+            return Ok(SuiPastObjectResponse::VersionFound(
+                object_data_with_options(ctx, object, options).await?,
+            ));
+        }
+
+        // The store does not record whether the object was deleted or wrapped, so it is always
+        // reported as deleted.
+        Some(None) => {
+            return Ok(SuiPastObjectResponse::ObjectDeleted(SuiObjectRef {
+                object_id,
+                version,
+                digest: ObjectDigest::OBJECT_DIGEST_DELETED,
+            }));
+        }
+
+        None => {}
+    }
+
+    let Some(latest) = ctx
+        .pg_loader()
+        .load_one(LatestObjectVersionKey(object_id))
+        .await
+        .context("Failed to load latest object version")?
     else {
-        return Ok(SuiPastObjectResponse::VersionNotFound(object_id, version));
+        return Ok(SuiPastObjectResponse::ObjectNotExists(object_id));
     };
 
-    Ok(SuiPastObjectResponse::VersionFound(
-        object_data_with_options(ctx, object, options).await?,
-    ))
+    let latest_version = SequenceNumber::from_u64(latest.object_version as u64);
+    if version > latest_version {
+        return Ok(SuiPastObjectResponse::VersionTooHigh {
+            object_id,
+            asked_version: version,
+            latest_version,
+        });
+    }
+
+    Ok(SuiPastObjectResponse::VersionNotFound(object_id, version))
 }
 
 /// Extract a representation of the object according to its response options.
@@ -120,7 +162,12 @@ pub(crate) async fn object_data_with_options(
         .then(|| object_data::<SuiRawData>(ctx, &object))
         .into();
 
-    let (content, bcs) = join!(content, bcs);
+    let display: OptionFuture<_> = options
+        .show_display
+        .then(|| display::object_display(ctx, &object))
+        .into();
+
+    let (content, bcs, display) = join!(content, bcs, display);
 
     let content = content
         .transpose()
@@ -130,6 +177,10 @@ pub(crate) async fn object_data_with_options(
         .transpose()
         .internal_context("Failed to deserialize object to BCS")?;
 
+    let display = display
+        .transpose()
+        .internal_context("Failed to render object Display")?;
+
     Ok(SuiObjectData {
         object_id: object.id(),
         version: object.version(),
@@ -138,7 +189,7 @@ pub(crate) async fn object_data_with_options(
         owner,
         previous_transaction,
         storage_rebate,
-        display: None,
+        display,
         content,
         bcs,
     })
@@ -151,25 +202,34 @@ async fn object_data<D: SuiData>(ctx: &Context, object: &Object) -> Result<D, Rp
         Data::Package(move_package) => D::try_from_package(move_package)?,
 
         Data::Move(move_object) => {
-            let type_: TypeTag = move_object.type_().clone().into();
-            let MoveTypeLayout::Struct(layout) = ctx
-                .package_resolver()
-                .type_layout(type_.clone())
-                .await
-                .with_context(|| {
-                    format!(
-                        "Failed to resolve type layout for {}",
-                        type_.to_canonical_display(/*with_prefix */ true)
-                    )
-                })?
-            else {
-                rpc_bail!(
-                    "Type {} is not a struct",
-                    type_.to_canonical_display(/*with_prefix */ true)
-                );
-            };
-
-            D::try_from_object(move_object, *layout)?
+            let layout = struct_layout(ctx, move_object.type_().clone().into()).await?;
+            D::try_from_object(move_object, layout)?
         }
     })
 }
+
+/// Resolve the layout of `type_`, which is expected to be a struct, because it is the type of an
+/// object.
+pub(super) async fn struct_layout(
+    ctx: &Context,
+    type_: TypeTag,
+) -> Result<MoveStructLayout, RpcError> {
+    let MoveTypeLayout::Struct(layout) = ctx
+        .package_resolver()
+        .type_layout(type_.clone())
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to resolve type layout for {}",
+                type_.to_canonical_display(/*with_prefix */ true)
+            )
+        })?
+    else {
+        rpc_bail!(
+            "Type {} is not a struct",
+            type_.to_canonical_display(/*with_prefix */ true)
+        );
+    };
+
+    Ok(*layout)
+}
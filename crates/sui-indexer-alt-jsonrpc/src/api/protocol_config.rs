@@ -0,0 +1,102 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context as _;
+use diesel::{ExpressionMethods, QueryDsl};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use sui_indexer_alt_schema::schema::kv_epoch_starts;
+use sui_json_rpc_types::ProtocolConfigResponse;
+use sui_open_rpc::Module;
+use sui_open_rpc_macros::open_rpc;
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
+use sui_types::sui_serde::BigInt;
+
+use crate::{
+    context::Context,
+    error::{invalid_params, RpcError},
+};
+
+use super::rpc_module::RpcModule;
+
+#[open_rpc(namespace = "sui", tag = "Read API")]
+#[rpc(server, namespace = "sui")]
+trait ProtocolConfigApi {
+    /// Return the protocol config table for the given version number. If the version number is
+    /// not specified, the protocol config for the current epoch is returned.
+    #[method(name = "getProtocolConfig")]
+    async fn get_protocol_config(
+        &self,
+        /// An optional protocol version specifier. If omitted, the latest protocol config table
+        /// for the current epoch is returned.
+        version: Option<BigInt<u64>>,
+    ) -> RpcResult<ProtocolConfigResponse>;
+}
+
+pub(crate) struct ProtocolConfigs(pub Context);
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error(
+        "Unsupported protocol version requested. Min supported: {}, max supported: {}",
+        ProtocolVersion::MIN.as_u64(),
+        ProtocolVersion::MAX.as_u64()
+    )]
+    UnsupportedVersion,
+}
+
+#[async_trait::async_trait]
+impl ProtocolConfigApiServer for ProtocolConfigs {
+    async fn get_protocol_config(
+        &self,
+        version: Option<BigInt<u64>>,
+    ) -> RpcResult<ProtocolConfigResponse> {
+        let Self(ctx) = self;
+        Ok(protocol_config_response(ctx, version.map(|v| *v)).await?)
+    }
+}
+
+impl RpcModule for ProtocolConfigs {
+    fn schema(&self) -> Module {
+        ProtocolConfigApiOpenRpc::module_doc()
+    }
+
+    fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+        self.into_rpc()
+    }
+}
+
+/// Load data and generate response for `getProtocolConfig`. Protocol configs can differ between
+/// chains, so the config is chosen based on the chain being served.
+async fn protocol_config_response(
+    ctx: &Context,
+    version: Option<u64>,
+) -> Result<ProtocolConfigResponse, RpcError<Error>> {
+    use kv_epoch_starts::dsl as e;
+
+    let version = match version {
+        Some(version) => version,
+        None => {
+            let version: i64 = ctx
+                .pg_reader()
+                .connect()
+                .await
+                .context("Failed to connect to the database")?
+                .first(
+                    e::kv_epoch_starts
+                        .select(e::protocol_version)
+                        .order(e::epoch.desc()),
+                )
+                .await
+                .context("Failed to fetch the current protocol version")?;
+
+            version as u64
+        }
+    };
+
+    let chain = ctx.chain_identifier().await?.chain();
+
+    let config = ProtocolConfig::get_for_version_if_supported(version.into(), chain)
+        .ok_or_else(|| invalid_params(Error::UnsupportedVersion))?;
+
+    Ok(config.into())
+}
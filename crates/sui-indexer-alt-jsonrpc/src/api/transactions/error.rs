@@ -4,7 +4,7 @@
 use sui_types::{base_types::ObjectID, digests::TransactionDigest};
 
 #[derive(thiserror::Error, Debug)]
-pub(super) enum Error {
+pub(crate) enum Error {
     #[error("Cannot filter by function name {function:?} without specifying a module")]
     MissingModule { function: String },
 
@@ -14,6 +14,12 @@ pub(super) enum Error {
     #[error("Pagination issue: {0}")]
     Pagination(#[from] crate::paginate::Error),
 
+    #[error("Filter is not supported: {0} pipeline is not available")]
+    PipelineDisabled(&'static str),
+
+    #[error("Requested {requested} keys, exceeding maximum {max}")]
+    TooManyKeys { requested: usize, max: usize },
+
     #[error("Balance changes for transaction {0} have been pruned")]
     PrunedBalanceChanges(TransactionDigest),
 
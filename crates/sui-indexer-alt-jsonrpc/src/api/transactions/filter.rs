@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::ops::Range;
+
 use anyhow::Context as _;
 use diesel::{
     expression::{
@@ -23,8 +25,11 @@ use sui_sql_macro::sql;
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
     digests::TransactionDigest,
+    effects::{TransactionEffects, TransactionEffectsAPI},
     messages_checkpoint::CheckpointSequenceNumber,
+    object::Owner,
     sui_serde::{BigInt, Readable},
+    transaction::{TransactionData, TransactionDataAPI},
 };
 
 use crate::{
@@ -65,15 +70,25 @@ pub(crate) enum TransactionFilter {
     },
     /// Query for transactions that touch this object.
     AffectedObject(ObjectID),
+    /// Query for transactions that touch this object. Accepted for compatibility with fullnodes,
+    /// and treated the same as `AffectedObject`: Objects that were only read by a transaction are
+    /// not indexed.
+    InputObject(ObjectID),
+    /// Query for transactions that touch this object (created it, modified it, deleted it, or
+    /// wrapped it). Treated the same as `AffectedObject`.
+    ChangedObject(ObjectID),
     /// Query by sender address.
     FromAddress(SuiAddress),
+    /// Query by recipient address: transactions that affected this address, by changing objects
+    /// that it owns (this includes transactions it sent, which change its gas coin).
+    ToAddress(SuiAddress),
     /// Query by sender and recipient address.
     FromAndToAddress { from: SuiAddress, to: SuiAddress },
     /// Query transactions that have a given address as sender or recipient.
     FromOrToAddress { addr: SuiAddress },
 }
 
-type Cursor = JsonCursor<u64>;
+pub(crate) type Cursor = JsonCursor<u64>;
 type Digests = PageResponse<TransactionDigest, String>;
 
 /// Fetch the digests for a page of transactions that satisfy the given `filter` and pagination
@@ -95,6 +110,16 @@ pub(super) async fn transactions(
         descending_order,
     )?;
 
+    let pipeline = filter
+        .as_ref()
+        .map_or(Some("tx_digests"), TransactionFilter::pipeline);
+
+    if let Some(pipeline) = pipeline {
+        if config.disabled_pipelines.contains(pipeline) {
+            return Err(invalid_params(Error::PipelineDisabled(pipeline)));
+        }
+    }
+
     use TransactionFilter as F;
     match filter {
         None => all_transactions(ctx, &page).await,
@@ -107,10 +132,14 @@ pub(super) async fn transactions(
             function,
         }) => tx_calls(ctx, &page, package, module.as_ref(), function.as_ref()).await,
 
-        Some(F::AffectedObject(object)) => tx_affected_objects(ctx, &page, *object).await,
+        Some(F::AffectedObject(object) | F::InputObject(object) | F::ChangedObject(object)) => {
+            tx_affected_objects(ctx, &page, *object).await
+        }
 
         Some(F::FromAddress(from)) => tx_affected_addresses(ctx, &page, Some(*from), *from).await,
 
+        Some(F::ToAddress(to)) => tx_affected_addresses(ctx, &page, None, *to).await,
+
         Some(F::FromAndToAddress { from, to }) => {
             tx_affected_addresses(ctx, &page, Some(*from), *to).await
         }
@@ -119,6 +148,81 @@ pub(super) async fn transactions(
     }
 }
 
+impl TransactionFilter {
+    /// Check that the filter is well-formed, before it is used to match transactions in a
+    /// subscription. It is an error to supply a package and function, but no module.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if let TransactionFilter::MoveFunction {
+            module: None,
+            function: Some(function),
+            ..
+        } = self
+        {
+            return Err(Error::MissingModule {
+                function: function.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The indexer pipeline whose table is queried to find transactions matching this filter, if
+    /// any.
+    pub(crate) fn pipeline(&self) -> Option<&'static str> {
+        use TransactionFilter as F;
+        match self {
+            F::Checkpoint(_) => None,
+            F::MoveFunction { .. } => Some("tx_calls"),
+            F::AffectedObject(_) | F::InputObject(_) | F::ChangedObject(_) => {
+                Some("tx_affected_objects")
+            }
+            F::FromAddress(_)
+            | F::ToAddress(_)
+            | F::FromAndToAddress { .. }
+            | F::FromOrToAddress { .. } => Some("tx_affected_addresses"),
+        }
+    }
+
+    /// Whether the transaction with input `data` and `effects`, from checkpoint `checkpoint`,
+    /// matches this filter. Transactions are matched the same way as they are when querying the
+    /// indexed tables.
+    pub(crate) fn matches(
+        &self,
+        checkpoint: u64,
+        data: &TransactionData,
+        effects: &TransactionEffects,
+    ) -> bool {
+        use TransactionFilter as F;
+        match self {
+            F::Checkpoint(seq) => *seq == checkpoint,
+
+            F::MoveFunction {
+                package,
+                module,
+                function,
+            } => data.move_calls().into_iter().any(|(p, m, f)| {
+                p == package
+                    && module.as_ref().map_or(true, |module| module == m)
+                    && function.as_ref().map_or(true, |function| function == f)
+            }),
+
+            F::AffectedObject(object) | F::InputObject(object) | F::ChangedObject(object) => {
+                effects.object_changes().iter().any(|o| o.id == *object)
+            }
+
+            F::FromAddress(from) => data.sender() == *from,
+
+            F::ToAddress(to) => affected_addresses(data, effects).any(|a| a == *to),
+
+            F::FromAndToAddress { from, to } => {
+                data.sender() == *from && affected_addresses(data, effects).any(|a| a == *to)
+            }
+
+            F::FromOrToAddress { addr } => affected_addresses(data, effects).any(|a| a == *addr),
+        }
+    }
+}
+
 /// Fetch a page of transaction digests without filtering them.
 async fn all_transactions(ctx: &Context, page: &Page<Cursor>) -> Result<Digests, RpcError<Error>> {
     use tx_digests::dsl as d;
@@ -158,26 +262,10 @@ async fn by_checkpoint(
     let cp_hi = summary.network_total_transactions;
     let cp_lo = summary.network_total_transactions - contents.inner().len() as u64;
 
-    let Page {
-        cursor,
-        limit,
-        descending,
-    } = page;
-
-    // Transaction sequence number bounds from the page
-    let pg_lo: u64;
-    let pg_hi: u64;
-    if *descending {
-        pg_hi = cursor.as_ref().map(|c| c.0).map_or(cp_hi, |c| c.min(cp_hi));
-        pg_lo = pg_hi.saturating_sub(1 + *limit as u64).max(cp_lo);
-    } else {
-        pg_lo = cursor
-            .as_ref()
-            .map(|c| c.0.saturating_add(1))
-            .map_or(cp_lo, |c| c.max(cp_lo));
-
-        pg_hi = pg_lo.saturating_add(1 + *limit as u64).min(cp_hi);
-    }
+    let Range {
+        start: pg_lo,
+        end: pg_hi,
+    } = checkpoint_range(page, cp_lo, cp_hi);
 
     let digests = contents.inner();
     let mut results = Vec::with_capacity(pg_hi.saturating_sub(pg_lo) as usize);
@@ -193,11 +281,37 @@ async fn by_checkpoint(
         results.push((tx as i64, digest));
     }
 
-    if *descending {
+    if page.descending {
         results.reverse();
     }
 
-    from_digests(*limit, results)
+    from_digests(page.limit, results)
+}
+
+/// The range of transaction sequence numbers to fetch for `page`, from a checkpoint containing
+/// the transactions from `cp_lo` (inclusive) to `cp_hi` (exclusive). The range includes at most
+/// one more transaction than the page's limit, to determine whether there is a next page, and is
+/// empty if the cursor is outside the checkpoint.
+pub(crate) fn checkpoint_range(page: &Page<Cursor>, cp_lo: u64, cp_hi: u64) -> Range<u64> {
+    let Page {
+        cursor,
+        limit,
+        descending,
+    } = page;
+
+    if *descending {
+        let pg_hi = cursor.as_ref().map(|c| c.0).map_or(cp_hi, |c| c.min(cp_hi));
+        let pg_lo = pg_hi.saturating_sub(1 + *limit as u64).max(cp_lo);
+        pg_lo..pg_hi
+    } else {
+        let pg_lo = cursor
+            .as_ref()
+            .map(|c| c.0.saturating_add(1))
+            .map_or(cp_lo, |c| c.max(cp_lo));
+
+        let pg_hi = pg_lo.saturating_add(1 + *limit as u64).min(cp_hi);
+        pg_lo..pg_hi
+    }
 }
 
 /// Fetch a page of transaction digests that called the described function(s). Functions can be
@@ -442,3 +556,20 @@ fn from_digests(limit: i64, mut rows: Vec<(i64, Vec<u8>)>) -> Result<Digests, Rp
         has_next_page,
     })
 }
+
+/// The addresses that a transaction affected: Its sender, its gas owner, and the owners of the
+/// objects it changed.
+fn affected_addresses(
+    data: &TransactionData,
+    effects: &TransactionEffects,
+) -> impl Iterator<Item = SuiAddress> {
+    let recipients = effects
+        .all_changed_objects()
+        .into_iter()
+        .filter_map(|(_, owner, _)| match owner {
+            Owner::AddressOwner(address) => Some(address),
+            _ => None,
+        });
+
+    recipients.chain([data.sender(), data.gas_owner()])
+}
@@ -1,26 +1,39 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use futures::future;
-use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use std::{collections::BTreeSet, sync::Arc};
+
+use futures::{future, stream};
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+    server::PendingSubscriptionSink,
+};
 use serde::{Deserialize, Serialize};
 use sui_json_rpc_types::{Page, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions};
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
-use sui_types::digests::TransactionDigest;
+use sui_types::{digests::TransactionDigest, effects::TransactionEffectsAPI};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
 
-use self::{error::Error, filter::SuiTransactionBlockResponseQuery};
+use self::{
+    error::Error,
+    filter::{SuiTransactionBlockResponseQuery, TransactionFilter},
+};
 
 use crate::{
     context::Context,
-    error::{rpc_bail, InternalContext, RpcError},
+    data::kv_loader::TransactionContents,
+    error::{invalid_params, rpc_bail, InternalContext, RpcError},
+    subscription::SubscriptionManager,
 };
 
 use super::rpc_module::RpcModule;
 
-mod error;
-mod filter;
-mod response;
+pub(crate) mod error;
+pub(crate) mod filter;
+pub(crate) mod response;
 
 #[open_rpc(namespace = "sui", tag = "Transactions API")]
 #[rpc(server, namespace = "sui")]
@@ -34,6 +47,17 @@ trait TransactionsApi {
         /// Options controlling the output format.
         options: SuiTransactionBlockResponseOptions,
     ) -> RpcResult<SuiTransactionBlockResponse>;
+
+    /// Fetch multiple transactions by their transaction digests. Responses are returned in the
+    /// same order as the digests, and it is an error for any of the transactions not to be found.
+    #[method(name = "multiGetTransactionBlocks")]
+    async fn multi_get_transaction_blocks(
+        &self,
+        /// The digests of the queried transactions.
+        digests: Vec<TransactionDigest>,
+        /// Options controlling the output format.
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> RpcResult<Vec<SuiTransactionBlockResponse>>;
 }
 
 #[open_rpc(namespace = "suix", tag = "Query Transactions API")]
@@ -64,18 +88,54 @@ trait QueryTransactionsApi {
     ) -> RpcResult<Page<SuiTransactionBlockResponse, String>>;
 }
 
+#[open_rpc(namespace = "suix", tag = "Transaction Subscription API")]
+#[rpc(server, namespace = "suix")]
+trait TransactionSubscriptionApi {
+    /// Subscribe to a stream of transactions, as they are committed in new checkpoints. Only
+    /// transactions that match the filter (by sender, recipient, function called, etc) are sent to
+    /// the subscriber.
+    #[subscription(name = "subscribeTransaction", item = SuiTransactionBlockResponse)]
+    async fn subscribe_transaction(
+        &self,
+        /// The filter criteria of the transaction stream.
+        filter: TransactionFilter,
+        /// Options controlling the output format, by default only the digest is included.
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> SubscriptionResult;
+}
+
 pub(crate) struct Transactions(pub Context);
 
-pub(crate) struct QueryTransactions(pub Context, pub TransactionsConfig);
+pub(crate) struct QueryTransactions(pub Context);
+
+/// Transaction subscriptions, fed by the transactions broadcast by a [super::feed::Feed].
+pub(crate) struct TransactionSubscriptions(
+    pub Context,
+    pub SubscriptionManager,
+    pub broadcast::Sender<Arc<TransactionContents>>,
+);
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransactionsConfig {
+    /// The maximum number of transactions that can be fetched in a single multi-get request.
+    pub max_multi_get_transactions: usize,
+
     /// The default page size limit when querying transactions, if none is provided.
     pub default_page_size: usize,
 
     /// The largest acceptable page size when querying transactions. Requesting a page larger than
     /// this is a user error.
     pub max_page_size: usize,
+
+    /// Indexer pipelines that are not available to query (e.g. because the indexer that writes to
+    /// the database does not run them). Queries whose filters need one of these pipelines' tables
+    /// are rejected.
+    pub disabled_pipelines: BTreeSet<String>,
+
+    /// The number of transactions that can be waiting to be sent to a subscriber. Subscribers that
+    /// fall further behind than this have their subscription closed. This is only read on
+    /// start-up.
+    pub subscription_buffer_size: usize,
 }
 
 #[async_trait::async_trait]
@@ -90,6 +150,27 @@ impl TransactionsApiServer for Transactions {
             .await
             .with_internal_context(|| format!("Failed to get transaction {digest}"))?)
     }
+
+    async fn multi_get_transaction_blocks(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> RpcResult<Vec<SuiTransactionBlockResponse>> {
+        let Self(ctx) = self;
+        let config = ctx.config();
+        if digests.len() > config.transactions.max_multi_get_transactions {
+            return Err(invalid_params(Error::TooManyKeys {
+                requested: digests.len(),
+                max: config.transactions.max_multi_get_transactions,
+            })
+            .into());
+        }
+
+        let options = options.unwrap_or_default();
+        Ok(response::transactions(ctx, &digests, &options)
+            .await
+            .with_internal_context(|| "Failed to get transactions")?)
+    }
 }
 
 #[async_trait::async_trait]
@@ -101,7 +182,8 @@ impl QueryTransactionsApiServer for QueryTransactions {
         limit: Option<usize>,
         descending_order: Option<bool>,
     ) -> RpcResult<Page<SuiTransactionBlockResponse, String>> {
-        let Self(ctx, config) = self;
+        let Self(ctx) = self;
+        let config = ctx.config();
 
         let Page {
             data: digests,
@@ -109,7 +191,7 @@ impl QueryTransactionsApiServer for QueryTransactions {
             has_next_page,
         } = filter::transactions(
             ctx,
-            config,
+            &config.transactions,
             &query.filter,
             cursor.clone(),
             limit,
@@ -144,6 +226,67 @@ impl QueryTransactionsApiServer for QueryTransactions {
     }
 }
 
+#[async_trait::async_trait]
+impl TransactionSubscriptionApiServer for TransactionSubscriptions {
+    async fn subscribe_transaction(
+        &self,
+        pending: PendingSubscriptionSink,
+        filter: TransactionFilter,
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> SubscriptionResult {
+        let Self(ctx, subscriptions, feed) = self;
+
+        if let Err(e) = filter.validate() {
+            pending.reject(invalid_params(e)).await;
+            return Ok(());
+        }
+
+        let state = (
+            feed.subscribe(),
+            ctx.clone(),
+            filter,
+            options.unwrap_or_default(),
+        );
+
+        let transactions = stream::unfold(state, |(mut rx, ctx, filter, options)| async move {
+            loop {
+                let tx = match rx.recv().await {
+                    Ok(tx) => tx,
+
+                    Err(RecvError::Lagged(skipped)) => {
+                        debug!(skipped, "Transaction subscriber fell behind the feed");
+                        return None;
+                    }
+
+                    Err(RecvError::Closed) => return None,
+                };
+
+                // Transactions that cannot be decoded or converted are skipped, rather than
+                // closing the subscription.
+                let (Ok(data), Ok(effects)) = (tx.data(), tx.effects()) else {
+                    warn!("Failed to decode transaction for subscriber");
+                    continue;
+                };
+
+                if !filter.matches(tx.cp_sequence_number(), &data, &effects) {
+                    continue;
+                }
+
+                match response::committed_transaction(&ctx, &tx, &options).await {
+                    Ok(response) => return Some((response, (rx, ctx, filter, options))),
+                    Err(e) => {
+                        let digest = effects.transaction_digest();
+                        warn!(%digest, "Failed to send transaction to subscriber: {e}");
+                    }
+                }
+            }
+        });
+
+        subscriptions.subscribe(pending, transactions);
+        Ok(())
+    }
+}
+
 impl RpcModule for Transactions {
     fn schema(&self) -> Module {
         TransactionsApiOpenRpc::module_doc()
@@ -164,11 +307,24 @@ impl RpcModule for QueryTransactions {
     }
 }
 
+impl RpcModule for TransactionSubscriptions {
+    fn schema(&self) -> Module {
+        TransactionSubscriptionApiOpenRpc::module_doc()
+    }
+
+    fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+        self.into_rpc()
+    }
+}
+
 impl Default for TransactionsConfig {
     fn default() -> Self {
         Self {
+            max_multi_get_transactions: 50,
             default_page_size: 50,
             max_page_size: 100,
+            disabled_pipelines: BTreeSet::new(),
+            subscription_buffer_size: 10_000,
         }
     }
 }
@@ -4,13 +4,12 @@
 use std::str::FromStr;
 
 use anyhow::Context as _;
-use futures::future::OptionFuture;
-use move_core_types::annotated_value::{MoveDatatypeLayout, MoveTypeLayout};
+use futures::future::{self, OptionFuture};
 use sui_indexer_alt_schema::transactions::{BalanceChange, StoredTxBalanceChange};
 use sui_json_rpc_types::{
-    BalanceChange as SuiBalanceChange, ObjectChange as SuiObjectChange, SuiEvent,
-    SuiTransactionBlock, SuiTransactionBlockData, SuiTransactionBlockEffects,
-    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    BalanceChange as SuiBalanceChange, ObjectChange as SuiObjectChange, SuiTransactionBlock,
+    SuiTransactionBlockData, SuiTransactionBlockEffects, SuiTransactionBlockEvents,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_types::{
     base_types::{ObjectID, SequenceNumber},
@@ -25,6 +24,7 @@ use sui_types::{
 use tokio::join;
 
 use crate::{
+    api,
     context::Context,
     data::{
         kv_loader::TransactionContents, objects::VersionedObjectKey,
@@ -65,12 +65,101 @@ pub(super) async fn transaction(
         None => None,
     };
 
+    response(ctx, &tx, stored_bc, options).await
+}
+
+/// Fetch the necessary data from the stores in `ctx` and transform it to build responses for the
+/// transactions identified by `digests`, according to the response `options`. Responses are
+/// returned in the same order as `digests`, and it is an error for any of them to be missing.
+///
+/// Transactions (and their balance changes, if requested) are fetched in a single batch, rather
+/// than one at a time.
+pub(super) async fn transactions(
+    ctx: &Context,
+    digests: &[TransactionDigest],
+    options: &SuiTransactionBlockResponseOptions,
+) -> Result<Vec<SuiTransactionBlockResponse>, RpcError<Error>> {
+    let txs = ctx.kv_loader().load_many_transactions(digests.to_vec());
+    let stored_bcs: OptionFuture<_> = options
+        .show_balance_changes
+        .then(|| {
+            ctx.pg_loader()
+                .load_many(digests.iter().copied().map(TxBalanceChangeKey))
+        })
+        .into();
+
+    let (txs, stored_bcs) = join!(txs, stored_bcs);
+
+    let txs = txs.context("Failed to fetch transactions from store")?;
+    let mut stored_bcs = stored_bcs
+        .transpose()
+        .context("Failed to fetch balance changes from store")?;
+
+    let mut responses = Vec::with_capacity(digests.len());
+    for digest in digests {
+        let tx = txs
+            .get(digest)
+            .ok_or_else(|| invalid_params(Error::NotFound(*digest)))?;
+
+        let stored_bc = stored_bcs
+            .as_mut()
+            .map(|bcs| {
+                bcs.remove(&TxBalanceChangeKey(*digest))
+                    .ok_or_else(|| invalid_params(Error::PrunedBalanceChanges(*digest)))
+            })
+            .transpose()?;
+
+        responses.push(response(ctx, tx, stored_bc, options));
+    }
+
+    future::try_join_all(responses).await
+}
+
+/// Build a response for `tx`, a transaction that has just been committed, according to the
+/// response `options`, fetching its balance changes if they were requested.
+pub(crate) async fn committed_transaction(
+    ctx: &Context,
+    tx: &TransactionContents,
+    options: &SuiTransactionBlockResponseOptions,
+) -> Result<SuiTransactionBlockResponse, RpcError<Error>> {
+    let digest = tx.digest()?;
+
+    let stored_bc = if options.show_balance_changes {
+        let stored_bc = ctx
+            .pg_loader()
+            .load_one(TxBalanceChangeKey(digest))
+            .await
+            .context("Failed to fetch balance changes from store")?
+            .ok_or_else(|| invalid_params(Error::PrunedBalanceChanges(digest)))?;
+
+        Some(stored_bc)
+    } else {
+        None
+    };
+
+    response(ctx, tx, stored_bc, options).await
+}
+
+/// Transform the transaction's stored form, and its balance changes (if they were fetched), into
+/// a response, according to the response `options`.
+///
+/// The transaction's checkpoint and timestamp are always included, as they are available from its
+/// stored form. Each of the other parts of the response is only extracted (and any additional
+/// data it needs is only fetched) if its option was set.
+async fn response(
+    ctx: &Context,
+    tx: &TransactionContents,
+    stored_bc: Option<StoredTxBalanceChange>,
+    options: &SuiTransactionBlockResponseOptions,
+) -> Result<SuiTransactionBlockResponse, RpcError<Error>> {
     let digest = tx.digest()?;
 
     let mut response = SuiTransactionBlockResponse::new(digest);
+    response.timestamp_ms = Some(tx.timestamp_ms());
+    response.checkpoint = Some(tx.cp_sequence_number());
 
     if options.show_input {
-        response.transaction = Some(input(ctx, &tx).await?);
+        response.transaction = Some(input(ctx, tx).await?);
     }
 
     if options.show_raw_input {
@@ -78,7 +167,7 @@ pub(super) async fn transaction(
     }
 
     if options.show_effects {
-        response.effects = Some(effects(&tx)?);
+        response.effects = Some(effects(tx)?);
     }
 
     if options.show_raw_effects {
@@ -86,7 +175,7 @@ pub(super) async fn transaction(
     }
 
     if options.show_events {
-        response.events = Some(events(ctx, digest, &tx).await?);
+        response.events = Some(events(ctx, digest, tx).await?);
     }
 
     if let Some(changes) = stored_bc {
@@ -94,7 +183,7 @@ pub(super) async fn transaction(
     }
 
     if options.show_object_changes {
-        response.object_changes = Some(object_changes(ctx, digest, &tx).await?);
+        response.object_changes = Some(object_changes(ctx, digest, tx).await?);
     }
 
     Ok(response)
@@ -109,9 +198,12 @@ async fn input(
     let tx_signatures: Vec<GenericSignature> = tx.signatures()?;
 
     Ok(SuiTransactionBlock {
-        data: SuiTransactionBlockData::try_from_with_package_resolver(data, ctx.package_resolver())
-            .await
-            .context("Failed to resolve types in transaction data")?,
+        data: SuiTransactionBlockData::try_from_with_package_resolver(
+            data,
+            &ctx.package_resolver(),
+        )
+        .await
+        .context("Failed to resolve types in transaction data")?,
         tx_signatures,
     })
 }
@@ -134,29 +226,9 @@ async fn events(
     let mut sui_events = Vec::with_capacity(events.len());
 
     for (ix, event) in events.into_iter().enumerate() {
-        let layout = match ctx
-            .package_resolver()
-            .type_layout(event.type_.clone().into())
-            .await
-            .with_context(|| {
-                format!(
-                    "Failed to resolve layout for {}",
-                    event.type_.to_canonical_display(/* with_prefix */ true)
-                )
-            })? {
-            MoveTypeLayout::Struct(s) => MoveDatatypeLayout::Struct(s),
-            MoveTypeLayout::Enum(e) => MoveDatatypeLayout::Enum(e),
-            _ => rpc_bail!(
-                "Event {ix} is not a struct or enum: {}",
-                event.type_.to_canonical_string(/* with_prefix */ true)
-            ),
-        };
-
         let sui_event =
-            SuiEvent::try_from(event, digest, ix as u64, Some(tx.timestamp_ms()), layout)
-                .with_context(|| format!("Failed to convert Event {ix} into response"))?;
-
-        sui_events.push(sui_event)
+            api::events::response::event(ctx, digest, ix, event, Some(tx.timestamp_ms())).await?;
+        sui_events.push(sui_event);
     }
 
     Ok(SuiTransactionBlockEvents { data: sui_events })
@@ -0,0 +1,450 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use fastcrypto::{
+    encoding::{Encoding, Hex},
+    hmac::{hmac_sha3_256, HmacKey},
+    traits::ToFromBytes,
+};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect,
+};
+use serde::Serialize;
+use sui_json_rpc_types::{
+    Filter, SuiEvent, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_types::sui_serde::BigInt;
+use tokio::{
+    sync::{
+        broadcast::{self, error::RecvError},
+        Semaphore,
+    },
+    task::JoinHandle,
+    time::{self, MissedTickBehavior},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::{
+    api::{feed::Feed, transactions::response},
+    context::Context,
+    data::kv_loader::TransactionContents,
+    metrics::RpcMetrics,
+};
+
+use super::{is_public, store::WebhookStore, WebhookFilter};
+
+/// Header carrying the hex-encoded HMAC-SHA3-256 of the request body, keyed by the webhook's
+/// secret.
+const SIGNATURE_HEADER: &str = "X-Sui-Webhook-Signature";
+
+/// Background task that delivers the events and transactions broadcast by the [Feed] to the
+/// webhooks whose filters they match.
+///
+/// Deliveries are retried with exponential backoff, but are otherwise best-effort: Notifications
+/// are not persisted, so they are lost if the service restarts before they are delivered, and
+/// notifications are dropped if the task falls too far behind the feed.
+pub(crate) struct WebhookDelivery {
+    /// Access to the database and package resolver, to build transaction responses.
+    context: Context,
+    /// The registry of webhooks to deliver to.
+    store: WebhookStore,
+    /// Events broadcast by the feed.
+    events: broadcast::Receiver<Arc<SuiEvent>>,
+    /// Transactions broadcast by the feed.
+    transactions: broadcast::Receiver<Arc<TransactionContents>>,
+    client: reqwest::Client,
+    metrics: Arc<RpcMetrics>,
+    /// Signal to cancel the task.
+    cancel: CancellationToken,
+}
+
+/// Resolves the hosts of webhook URLs, leaving out private, loopback and link-local addresses, so
+/// that a webhook whose host starts resolving to such an address after it was registered cannot be
+/// used to reach the service's own network.
+struct PublicResolver;
+
+/// A webhook from the registry, ready to deliver notifications to.
+struct Webhook {
+    id: u64,
+    url: String,
+    filter: WebhookFilter,
+    secret: Arc<[u8]>,
+}
+
+/// The JSON body POSTed to webhooks.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Notification<'a> {
+    webhook_id: BigInt<u64>,
+
+    #[serde(flatten)]
+    item: Item<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Item<'a> {
+    Event(&'a SuiEvent),
+    Transaction(&'a SuiTransactionBlockResponse),
+}
+
+impl WebhookDelivery {
+    pub(crate) fn new(
+        context: Context,
+        store: WebhookStore,
+        feed: &Feed,
+        metrics: Arc<RpcMetrics>,
+        cancel: CancellationToken,
+    ) -> Self {
+        Self {
+            context,
+            store,
+            events: feed.events().subscribe(),
+            transactions: feed.transactions().subscribe(),
+            client: client(context.config().webhooks.allow_private),
+            metrics,
+            cancel,
+        }
+    }
+
+    /// Start a new task that delivers notifications to webhooks, re-reading the registry of
+    /// webhooks periodically.
+    ///
+    /// This operation consumes the `self` and returns a handle to the spawned tokio task. The task
+    /// will continue to run until its cancellation token is triggered.
+    pub(crate) fn run(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let Self {
+                context,
+                store,
+                mut events,
+                mut transactions,
+                client,
+                metrics,
+                cancel,
+            } = self;
+
+            let config = context.config().webhooks.clone();
+            let permits = Arc::new(Semaphore::new(config.max_concurrent_deliveries));
+
+            let mut refresh = time::interval(config.refresh_interval);
+            refresh.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            let mut webhooks: Vec<Webhook> = vec![];
+
+            loop {
+                // Notifications for one webhook are built and delivered in a task of their own, so
+                // that slow webhooks do not hold up the others.
+                let notify = |webhook: &Webhook, item: Payload| {
+                    let Ok(permit) = permits.clone().try_acquire_owned() else {
+                        metrics.webhook_notifications_dropped.inc();
+                        return;
+                    };
+
+                    let context = context.clone();
+                    let store = store.clone();
+                    let client = client.clone();
+                    let metrics = metrics.clone();
+                    let id = webhook.id;
+                    let url = webhook.url.clone();
+                    let secret = webhook.secret.clone();
+
+                    tokio::spawn(async move {
+                        let _permit = permit;
+                        match item.body(&context, id).await {
+                            Ok(body) => {
+                                let delivered =
+                                    deliver(&context, &client, &metrics, &url, &secret, body).await;
+                                record(&context, &store, id, delivered).await;
+                            }
+                            Err(e) => {
+                                warn!(id, "Failed to build webhook notification: {e:#}");
+                                metrics.webhook_notifications_dropped.inc();
+                            }
+                        }
+                    });
+                };
+
+                tokio::select! {
+                    _ = cancel.cancelled() => {
+                        info!("Shutdown signal received, terminating webhook delivery");
+                        break;
+                    }
+
+                    _ = refresh.tick() => match load(&store).await {
+                        Ok(loaded) => webhooks = loaded,
+                        Err(e) => warn!("Failed to refresh webhooks: {e:#}"),
+                    },
+
+                    event = events.recv() => match event {
+                        Ok(event) => {
+                            for webhook in webhooks.iter() {
+                                if let WebhookFilter::Event(filter) = &webhook.filter {
+                                    if filter.matches(&event) {
+                                        notify(webhook, Payload::Event(event.clone()));
+                                    }
+                                }
+                            }
+                        }
+
+                        Err(RecvError::Lagged(skipped)) => {
+                            warn!(skipped, "Webhook delivery fell behind the event feed");
+                            metrics.webhook_notifications_dropped.inc_by(skipped);
+                        }
+
+                        Err(RecvError::Closed) => break,
+                    },
+
+                    tx = transactions.recv() => match tx {
+                        Ok(tx) => {
+                            let wanted = webhooks
+                                .iter()
+                                .any(|w| matches!(w.filter, WebhookFilter::Transaction { .. }));
+
+                            if !wanted {
+                                continue;
+                            }
+
+                            // Transactions that cannot be decoded are skipped.
+                            let (Ok(data), Ok(effects)) = (tx.data(), tx.effects()) else {
+                                warn!("Failed to decode transaction for webhooks");
+                                continue;
+                            };
+
+                            for webhook in webhooks.iter() {
+                                if let WebhookFilter::Transaction { filter, options } =
+                                    &webhook.filter
+                                {
+                                    if filter.matches(tx.cp_sequence_number(), &data, &effects) {
+                                        let options = options.clone().unwrap_or_default();
+                                        notify(webhook, Payload::Transaction(tx.clone(), options));
+                                    }
+                                }
+                            }
+                        }
+
+                        Err(RecvError::Lagged(skipped)) => {
+                            warn!(skipped, "Webhook delivery fell behind the transaction feed");
+                            metrics.webhook_notifications_dropped.inc_by(skipped);
+                        }
+
+                        Err(RecvError::Closed) => break,
+                    },
+                }
+            }
+        })
+    }
+}
+
+/// An item to notify a webhook of, before it has been converted into the notification's body.
+enum Payload {
+    Event(Arc<SuiEvent>),
+    Transaction(Arc<TransactionContents>, SuiTransactionBlockResponseOptions),
+}
+
+impl Payload {
+    /// The JSON body of the notification to webhook `id` about this item.
+    async fn body(self, ctx: &Context, id: u64) -> anyhow::Result<Vec<u8>> {
+        let webhook_id = id.into();
+        let body = match self {
+            Payload::Event(event) => serde_json::to_vec(&Notification {
+                webhook_id,
+                item: Item::Event(&event),
+            }),
+
+            Payload::Transaction(tx, options) => {
+                let response = response::committed_transaction(ctx, &tx, &options)
+                    .await
+                    .context("Failed to build transaction response")?;
+
+                serde_json::to_vec(&Notification {
+                    webhook_id,
+                    item: Item::Transaction(&response),
+                })
+            }
+        };
+
+        body.context("Failed to serialize notification")
+    }
+}
+
+/// Read the webhooks from the registry. Webhooks whose filters cannot be decoded are skipped.
+async fn load(store: &WebhookStore) -> anyhow::Result<Vec<Webhook>> {
+    let mut webhooks = vec![];
+    for stored in store.all().await? {
+        let id = stored.webhook_id as u64;
+
+        let filter = match serde_json::from_str(&stored.filter) {
+            Ok(filter) => filter,
+            Err(e) => {
+                warn!(id, "Failed to decode webhook filter: {e}");
+                continue;
+            }
+        };
+
+        webhooks.push(Webhook {
+            id,
+            url: stored.url,
+            filter,
+            secret: stored.secret.into(),
+        });
+    }
+
+    Ok(webhooks)
+}
+
+/// The client that notifications are delivered with. Redirects are not followed, and unless
+/// `allow_private` is set, hosts are only connected to on their public addresses, so that webhooks
+/// can only reach the addresses that they were checked against when they were registered.
+fn client(allow_private: bool) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder().redirect(redirect::Policy::none());
+    if !allow_private {
+        builder = builder.dns_resolver(Arc::new(PublicResolver));
+    }
+
+    builder.build().expect("Failed to build webhook client")
+}
+
+/// POST `body` to `url`, signed with `secret`, retrying with exponential backoff on failure.
+/// Returns whether the notification was delivered.
+async fn deliver(
+    ctx: &Context,
+    client: &reqwest::Client,
+    metrics: &RpcMetrics,
+    url: &str,
+    secret: &[u8],
+    body: Vec<u8>,
+) -> bool {
+    let config = ctx.config().webhooks.clone();
+    let signature = match HmacKey::from_bytes(secret) {
+        Ok(key) => Hex::encode(hmac_sha3_256(&key, &body).to_vec()),
+        Err(e) => {
+            warn!(url, "Invalid webhook secret: {e}");
+            metrics.webhook_deliveries_failed.inc();
+            return false;
+        }
+    };
+
+    for attempt in 1..=config.max_attempts {
+        let request = client
+            .post(url)
+            .timeout(config.request_timeout)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, &signature)
+            .body(body.clone());
+
+        match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(_) => {
+                debug!(url, "Delivered webhook notification");
+                metrics.webhook_deliveries_succeeded.inc();
+                return true;
+            }
+
+            Err(e) => {
+                debug!(url, attempt, "Failed to deliver webhook notification: {e}");
+                if attempt < config.max_attempts {
+                    time::sleep(backoff(config.retry_backoff, attempt)).await;
+                }
+            }
+        }
+    }
+
+    warn!(url, "Giving up on webhook notification");
+    metrics.webhook_deliveries_failed.inc();
+    false
+}
+
+/// Record whether a notification was `delivered` to webhook `id`, unregistering the webhook if it
+/// has failed too many times in a row.
+async fn record(ctx: &Context, store: &WebhookStore, id: u64, delivered: bool) {
+    let max_failures = ctx.config().webhooks.max_failures;
+    let recorded = if delivered {
+        store.record_success(id).await
+    } else {
+        store.record_failure(id, max_failures).await.map(|pruned| {
+            if pruned {
+                info!(
+                    id,
+                    max_failures, "Unregistered webhook after repeated failures"
+                );
+            }
+        })
+    };
+
+    if let Err(e) = recorded {
+        warn!(id, "Failed to record webhook delivery: {e:#}");
+    }
+}
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(format!("{} has no public addresses", name.as_str()).into());
+            }
+
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// How long to wait after failed attempt number `attempt` (starting from 1), before trying again.
+fn backoff(initial: Duration, attempt: u32) -> Duration {
+    initial.saturating_mul(1 << (attempt - 1).min(16))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sui_types::digests::TransactionDigest;
+
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let initial = Duration::from_secs(1);
+        assert_eq!(backoff(initial, 1), Duration::from_secs(1));
+        assert_eq!(backoff(initial, 2), Duration::from_secs(2));
+        assert_eq!(backoff(initial, 4), Duration::from_secs(8));
+    }
+
+    #[tokio::test]
+    async fn test_public_resolver() {
+        // Loopback addresses are never connected to, even if the host they belong to has
+        // already been registered as a webhook.
+        let err = PublicResolver
+            .resolve("localhost".parse().unwrap())
+            .await
+            .err()
+            .unwrap();
+
+        assert!(err.to_string().contains("no public addresses"), "{err}");
+    }
+
+    #[test]
+    fn test_notification() {
+        let response = SuiTransactionBlockResponse::new(TransactionDigest::ZERO);
+        let notification = serde_json::to_value(Notification {
+            webhook_id: 42.into(),
+            item: Item::Transaction(&response),
+        })
+        .unwrap();
+
+        assert_eq!(notification["webhookId"], json!("42"));
+        assert_eq!(
+            notification["transaction"]["digest"],
+            json!(TransactionDigest::ZERO.to_string())
+        );
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::transactions;
+
+#[derive(thiserror::Error, Debug)]
+pub(super) enum Error {
+    #[error("Invalid webhook URL {0:?}: {1}")]
+    InvalidUrl(String, url::ParseError),
+
+    #[error("Webhook URL {0:?} must use HTTPS")]
+    InsecureUrl(String),
+
+    #[error("Could not resolve webhook URL {0:?}")]
+    UnresolvedUrl(String),
+
+    #[error("Webhook URL {0:?} must not point to a private, loopback, or link-local address")]
+    PrivateUrl(String),
+
+    #[error("Invalid webhook secret, expected a hex-encoded string")]
+    InvalidSecret,
+
+    #[error("Too many webhooks registered with this API key, the maximum is {0}")]
+    TooManyWebhooks(usize),
+
+    #[error(transparent)]
+    Filter(#[from] transactions::error::Error),
+}
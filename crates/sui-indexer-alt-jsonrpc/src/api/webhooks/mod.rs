@@ -0,0 +1,360 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use fastcrypto::encoding::{Encoding, Hex};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, types::ErrorObject, Extensions};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sui_indexer_alt_schema::webhooks::NewWebhook;
+use sui_json_rpc_types::{EventFilter, SuiTransactionBlockResponseOptions};
+use sui_open_rpc::Module;
+use sui_open_rpc_macros::open_rpc;
+use sui_types::sui_serde::BigInt;
+use url::Url;
+
+use crate::{
+    auth::ApiKeyId,
+    context::Context,
+    error::{invalid_params, InternalContext, RpcError, UNAUTHORIZED_CODE},
+};
+
+use self::{error::Error, store::WebhookStore};
+
+use super::{rpc_module::RpcModule, transactions::filter::TransactionFilter};
+
+pub(crate) mod delivery;
+mod error;
+pub(crate) mod store;
+
+/// Number of random bytes in the secrets that notifications are signed with.
+const SECRET_LEN: usize = 32;
+
+#[open_rpc(namespace = "suix", tag = "Webhooks API")]
+#[rpc(server, namespace = "suix")]
+trait WebhooksApi {
+    /// Register a URL to be notified of events or transactions that match a filter, as they are
+    /// indexed. Notifications are POSTed to the URL as JSON, and are retried if the URL does not
+    /// respond successfully.
+    ///
+    /// Notifications are signed with the secret that this method returns: Their
+    /// `X-Sui-Webhook-Signature` header holds the hex-encoded HMAC-SHA3-256 of the request body,
+    /// keyed by the secret.
+    ///
+    /// Registering a webhook requires an API key, and each API key can only register a limited
+    /// number of webhooks. Webhooks that repeatedly fail to accept notifications are unregistered.
+    #[method(name = "registerWebhook", with_extensions)]
+    async fn register_webhook(
+        &self,
+        /// The URL to POST notifications to.
+        url: String,
+        /// Which events or transactions to send to the webhook.
+        filter: WebhookFilter,
+    ) -> RpcResult<WebhookRegistration>;
+
+    /// Stop sending notifications to a webhook. Returns whether a webhook was unregistered.
+    #[method(name = "unregisterWebhook")]
+    async fn unregister_webhook(
+        &self,
+        /// The ID of the webhook, returned when it was registered.
+        id: BigInt<u64>,
+        /// The secret returned when the webhook was registered, to prove that the caller
+        /// registered it.
+        secret: String,
+    ) -> RpcResult<bool>;
+}
+
+pub(crate) struct Webhooks(pub Context, pub WebhookStore);
+
+/// Which notifications a webhook receives.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum WebhookFilter {
+    /// Events that match the filter.
+    Event(EventFilter),
+
+    /// Transactions that match the filter, formatted according to the response options (by
+    /// default only the digest is included).
+    Transaction {
+        filter: TransactionFilter,
+        options: Option<SuiTransactionBlockResponseOptions>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WebhookRegistration {
+    /// Identifies the webhook, to unregister it.
+    pub id: BigInt<u64>,
+
+    /// The hex-encoded key that notifications to the webhook are signed with.
+    pub secret: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhooksConfig {
+    /// Whether clients can register webhooks, and notifications are delivered to them. Clients
+    /// need an API key to register webhooks (see `AuthConfig`). This is only read on start-up.
+    pub enabled: bool,
+
+    /// The maximum number of webhooks that can be registered with each API key at once.
+    pub max_webhooks_per_key: usize,
+
+    /// Whether webhooks can be registered with plain HTTP URLs, rather than HTTPS (e.g. for local
+    /// testing).
+    pub allow_http: bool,
+
+    /// Whether webhooks can be registered with URLs that point to private, loopback, or link-local
+    /// addresses (e.g. for local testing). Otherwise, such webhooks are rejected, so that clients
+    /// cannot use the service to reach hosts on its own network. Whether notifications are
+    /// delivered to such addresses is only read on start-up.
+    pub allow_private: bool,
+
+    /// How often to re-read the registered webhooks from the database, to pick up webhooks that
+    /// were registered or unregistered (possibly with other instances of the service). This is
+    /// only read on start-up.
+    pub refresh_interval: Duration,
+
+    /// How long to wait for a webhook to respond to a notification.
+    pub request_timeout: Duration,
+
+    /// How many times to try delivering a notification, before giving up on it.
+    pub max_attempts: u32,
+
+    /// How long to wait before retrying a failed delivery. This doubles with each attempt.
+    pub retry_backoff: Duration,
+
+    /// Webhooks are unregistered once this many notifications in a row could not be delivered to
+    /// them (after retries).
+    pub max_failures: u32,
+
+    /// The maximum number of notifications being delivered at once. Notifications that cannot be
+    /// delivered because too many deliveries are in flight are dropped. This is only read on
+    /// start-up.
+    pub max_concurrent_deliveries: usize,
+}
+
+#[async_trait::async_trait]
+impl WebhooksApiServer for Webhooks {
+    async fn register_webhook(
+        &self,
+        ext: &Extensions,
+        url: String,
+        filter: WebhookFilter,
+    ) -> RpcResult<WebhookRegistration> {
+        let Self(ctx, store) = self;
+        let Some(ApiKeyId(owner)) = ext.get::<ApiKeyId>() else {
+            return Err(ErrorObject::owned(
+                UNAUTHORIZED_CODE,
+                "Registering a webhook requires an API key",
+                None::<()>,
+            ));
+        };
+
+        Ok(register(ctx, store, owner, url, filter)
+            .await
+            .with_internal_context(|| "Failed to register webhook")?)
+    }
+
+    async fn unregister_webhook(&self, id: BigInt<u64>, secret: String) -> RpcResult<bool> {
+        let Self(_, store) = self;
+        Ok(unregister(store, *id, &secret)
+            .await
+            .with_internal_context(|| format!("Failed to unregister webhook {}", *id))?)
+    }
+}
+
+impl RpcModule for Webhooks {
+    fn schema(&self) -> Module {
+        WebhooksApiOpenRpc::module_doc()
+    }
+
+    fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+        self.into_rpc()
+    }
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_webhooks_per_key: 100,
+            allow_http: false,
+            allow_private: false,
+            refresh_interval: Duration::from_secs(10),
+            request_timeout: Duration::from_secs(10),
+            max_attempts: 5,
+            retry_backoff: Duration::from_secs(1),
+            max_failures: 10,
+            max_concurrent_deliveries: 100,
+        }
+    }
+}
+
+/// Check that `url` and `filter` describe a valid webhook, and add it to the registry on behalf of
+/// API key `owner`, with a new secret to sign its notifications with.
+async fn register(
+    ctx: &Context,
+    store: &WebhookStore,
+    owner: &str,
+    url: String,
+    filter: WebhookFilter,
+) -> Result<WebhookRegistration, RpcError<Error>> {
+    let config = ctx.config().webhooks.clone();
+
+    let parsed = Url::parse(&url).map_err(|e| invalid_params(Error::InvalidUrl(url.clone(), e)))?;
+    match parsed.scheme() {
+        "https" => {}
+        "http" if config.allow_http => {}
+        _ => return Err(invalid_params(Error::InsecureUrl(url))),
+    }
+
+    if !config.allow_private {
+        let addrs = resolve(&parsed)
+            .await
+            .map_err(|_| invalid_params(Error::UnresolvedUrl(url.clone())))?;
+
+        if addrs.is_empty() {
+            return Err(invalid_params(Error::UnresolvedUrl(url)));
+        }
+
+        if !addrs.into_iter().all(is_public) {
+            return Err(invalid_params(Error::PrivateUrl(url)));
+        }
+    }
+
+    if let WebhookFilter::Transaction { filter, .. } = &filter {
+        filter
+            .validate()
+            .map_err(|e| invalid_params(Error::from(e)))?;
+    }
+
+    let secret: [u8; SECRET_LEN] = rand::random();
+    let webhook = NewWebhook {
+        url,
+        filter: serde_json::to_string(&filter).context("Failed to serialize filter")?,
+        secret: secret.to_vec(),
+        owner: owner.to_owned(),
+    };
+
+    let Some(id) = store.insert(webhook, config.max_webhooks_per_key).await? else {
+        return Err(invalid_params(Error::TooManyWebhooks(
+            config.max_webhooks_per_key,
+        )));
+    };
+
+    Ok(WebhookRegistration {
+        id: id.into(),
+        secret: Hex::encode(secret),
+    })
+}
+
+/// Remove webhook `id` from the registry, if `secret` is its secret.
+async fn unregister(store: &WebhookStore, id: u64, secret: &str) -> Result<bool, RpcError<Error>> {
+    let secret = Hex::decode(secret).map_err(|_| invalid_params(Error::InvalidSecret))?;
+    Ok(store.delete(id, &secret).await?)
+}
+
+/// The addresses that `url`'s host resolves to.
+async fn resolve(url: &Url) -> anyhow::Result<Vec<IpAddr>> {
+    let host = url.host_str().context("URL has no host")?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = url.port_or_known_default().context("URL has no port")?;
+
+    let addrs = tokio::net::lookup_host((host, port))
+        .await
+        .with_context(|| format!("Failed to resolve {host}"))?;
+
+    Ok(addrs.map(|addr| addr.ip()).collect())
+}
+
+/// Whether `ip` is an address on the public internet, rather than a private, loopback, link-local,
+/// or otherwise special-purpose address.
+pub(crate) fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    let shared = a == 100 && (b & 0xc0) == 64;
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || shared
+        || a == 0)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    let unique_local = (first & 0xfe00) == 0xfc00;
+    let link_local = (first & 0xffc0) == 0xfe80;
+
+    !(ip.is_unspecified() || ip.is_loopback() || ip.is_multicast() || unique_local || link_local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public() {
+        let public = ["1.1.1.1", "8.8.8.8", "2606:4700:4700::1111"];
+        for ip in public {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+
+        let private = [
+            "0.0.0.0",
+            "10.1.2.3",
+            "100.64.0.1",
+            "127.0.0.1",
+            "169.254.169.254",
+            "172.16.0.1",
+            "192.168.1.1",
+            "255.255.255.255",
+            "::",
+            "::1",
+            "::ffff:127.0.0.1",
+            "fc00::1",
+            "fd12:3456::1",
+            "fe80::1",
+        ];
+
+        for ip in private {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let url = Url::parse("https://127.0.0.1:8443/hook").unwrap();
+        assert_eq!(
+            resolve(&url).await.unwrap(),
+            vec![IpAddr::from([127, 0, 0, 1])]
+        );
+
+        let url = Url::parse("http://[::1]/hook").unwrap();
+        assert_eq!(
+            resolve(&url).await.unwrap(),
+            vec![IpAddr::from(Ipv6Addr::LOCALHOST)]
+        );
+
+        let url = Url::parse("https://localhost/hook").unwrap();
+        assert!(!resolve(&url).await.unwrap().into_iter().any(is_public));
+    }
+}
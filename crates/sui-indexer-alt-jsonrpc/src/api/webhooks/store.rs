@@ -0,0 +1,235 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context as _;
+use diesel::{sql_query, sql_types::Text, ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+use sui_indexer_alt_schema::{
+    schema::rpc_webhooks,
+    webhooks::{NewWebhook, StoredWebhook},
+};
+use sui_pg_db::{Db, DbArgs};
+
+/// Webhooks are registered and unregistered rarely, so their connection pool can be much smaller
+/// than the pool used to serve reads.
+const POOL_SIZE: u32 = 4;
+
+/// The registry of webhooks, in the database. Unlike the rest of the service, the registry needs
+/// write access to the database, so it has its own connection pool.
+#[derive(Clone)]
+pub(crate) struct WebhookStore {
+    db: Db,
+}
+
+impl WebhookStore {
+    pub(crate) async fn new(db_args: DbArgs) -> anyhow::Result<Self> {
+        let db = Db::for_write(DbArgs {
+            db_connection_pool_size: POOL_SIZE,
+            ..db_args
+        })
+        .await
+        .context("Failed to connect to the webhook registry")?;
+
+        Ok(Self { db })
+    }
+
+    /// Add `webhook` to the registry, returning its ID, or `None` if its owner already has `limit`
+    /// webhooks registered.
+    ///
+    /// Registrations for the same owner are serialized by a lock in the database, so that
+    /// concurrent registrations (possibly with other instances of the service) cannot exceed the
+    /// limit between them.
+    pub(crate) async fn insert(
+        &self,
+        webhook: NewWebhook,
+        limit: usize,
+    ) -> anyhow::Result<Option<u64>> {
+        use rpc_webhooks::dsl as w;
+
+        let mut conn = self.db.connect().await?;
+        conn.transaction::<_, anyhow::Error, _>(|conn| {
+            async move {
+                sql_query("SELECT pg_advisory_xact_lock(hashtext($1))")
+                    .bind::<Text, _>(&webhook.owner)
+                    .execute(conn)
+                    .await
+                    .context("Failed to lock owner's webhooks")?;
+
+                let count: i64 = w::rpc_webhooks
+                    .filter(w::owner.eq(&webhook.owner))
+                    .count()
+                    .get_result(conn)
+                    .await
+                    .context("Failed to count owner's webhooks")?;
+
+                if count >= limit as i64 {
+                    return Ok(None);
+                }
+
+                let id: i64 = diesel::insert_into(w::rpc_webhooks)
+                    .values(&webhook)
+                    .returning(w::webhook_id)
+                    .get_result(conn)
+                    .await
+                    .context("Failed to register webhook")?;
+
+                Ok(Some(id as u64))
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+
+    /// Remove webhook `id` from the registry, if it was registered with `secret`. Returns whether
+    /// a webhook was removed.
+    pub(crate) async fn delete(&self, id: u64, secret: &[u8]) -> anyhow::Result<bool> {
+        use rpc_webhooks::dsl as w;
+
+        let mut conn = self.db.connect().await?;
+        let deleted = diesel::delete(w::rpc_webhooks)
+            .filter(w::webhook_id.eq(id as i64))
+            .filter(w::secret.eq(secret))
+            .execute(&mut conn)
+            .await
+            .context("Failed to unregister webhook")?;
+
+        Ok(deleted > 0)
+    }
+
+    /// Record that a notification was delivered to webhook `id`, resetting its run of failures.
+    pub(crate) async fn record_success(&self, id: u64) -> anyhow::Result<()> {
+        use rpc_webhooks::dsl as w;
+
+        let mut conn = self.db.connect().await?;
+        diesel::update(w::rpc_webhooks)
+            .filter(w::webhook_id.eq(id as i64))
+            .filter(w::failures.gt(0))
+            .set(w::failures.eq(0))
+            .execute(&mut conn)
+            .await
+            .context("Failed to record webhook delivery")?;
+
+        Ok(())
+    }
+
+    /// Record that a notification could not be delivered to webhook `id`, and remove it from the
+    /// registry if that makes `max_failures` failures in a row. Returns whether the webhook was
+    /// removed.
+    pub(crate) async fn record_failure(&self, id: u64, max_failures: u32) -> anyhow::Result<bool> {
+        use rpc_webhooks::dsl as w;
+
+        let mut conn = self.db.connect().await?;
+        diesel::update(w::rpc_webhooks)
+            .filter(w::webhook_id.eq(id as i64))
+            .set(w::failures.eq(w::failures + 1))
+            .execute(&mut conn)
+            .await
+            .context("Failed to record webhook failure")?;
+
+        let deleted = diesel::delete(w::rpc_webhooks)
+            .filter(w::webhook_id.eq(id as i64))
+            .filter(w::failures.ge(max_failures as i32))
+            .execute(&mut conn)
+            .await
+            .context("Failed to prune webhook")?;
+
+        Ok(deleted > 0)
+    }
+
+    /// All the webhooks in the registry.
+    pub(crate) async fn all(&self) -> anyhow::Result<Vec<StoredWebhook>> {
+        use rpc_webhooks::dsl as w;
+
+        let mut conn = self.db.connect().await?;
+        w::rpc_webhooks
+            .select(StoredWebhook::as_select())
+            .load(&mut conn)
+            .await
+            .context("Failed to load webhooks")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use sui_indexer_alt_schema::MIGRATIONS;
+    use sui_pg_db::temp::TempDb;
+
+    use super::*;
+
+    async fn store(db: &TempDb) -> WebhookStore {
+        let store = WebhookStore::new(DbArgs::new_for_testing(db.database().url().clone()))
+            .await
+            .unwrap();
+
+        store.db.run_migrations(MIGRATIONS).await.unwrap();
+        store
+    }
+
+    fn webhook(owner: &str) -> NewWebhook {
+        NewWebhook {
+            url: "https://example.com/hook".to_owned(),
+            filter: "{}".to_owned(),
+            secret: vec![42; 32],
+            owner: owner.to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quota_per_owner() {
+        let db = TempDb::new().unwrap();
+        let store = store(&db).await;
+
+        assert!(store.insert(webhook("a"), 2).await.unwrap().is_some());
+        assert!(store.insert(webhook("a"), 2).await.unwrap().is_some());
+        assert!(store.insert(webhook("a"), 2).await.unwrap().is_none());
+
+        // Other owners have a quota of their own.
+        assert!(store.insert(webhook("b"), 2).await.unwrap().is_some());
+
+        // Unregistering a webhook frees up space in its owner's quota.
+        let id = store.all().await.unwrap()[0].webhook_id as u64;
+        assert!(store.delete(id, &[42; 32]).await.unwrap());
+        assert!(store.insert(webhook("a"), 2).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_registrations() {
+        let db = TempDb::new().unwrap();
+        let store = Arc::new(store(&db).await);
+
+        let registrations = (0..10).map(|_| {
+            let store = store.clone();
+            tokio::spawn(async move { store.insert(webhook("a"), 3).await.unwrap() })
+        });
+
+        let mut registered = 0;
+        for registration in registrations {
+            if registration.await.unwrap().is_some() {
+                registered += 1;
+            }
+        }
+
+        assert_eq!(registered, 3);
+        assert_eq!(store.all().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_prune_failing_webhooks() {
+        let db = TempDb::new().unwrap();
+        let store = store(&db).await;
+        let id = store.insert(webhook("a"), 10).await.unwrap().unwrap();
+
+        // A successful delivery resets the run of failures.
+        assert!(!store.record_failure(id, 3).await.unwrap());
+        assert!(!store.record_failure(id, 3).await.unwrap());
+        store.record_success(id).await.unwrap();
+        assert!(!store.record_failure(id, 3).await.unwrap());
+        assert!(!store.record_failure(id, 3).await.unwrap());
+
+        // The webhook is removed on its third failure in a row.
+        assert!(store.record_failure(id, 3).await.unwrap());
+        assert!(store.all().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,815 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, time::Duration};
+
+use anyhow::Context as _;
+use fastcrypto::encoding::Base64;
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::{ErrorObject, ErrorObjectOwned},
+};
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use sui_json_rpc_types::{
+    DevInspectArgs, DevInspectResults, DisplayFieldsResponse, DryRunTransactionBlockResponse,
+    SuiEvent, SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockEvents,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_open_rpc::Module;
+use sui_open_rpc_macros::open_rpc;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    digests::TransactionDigest,
+    event::Event,
+    gas::GasCostSummary,
+    quorum_driver_types::ExecuteTransactionRequestType,
+    sui_serde::BigInt,
+};
+use tokio::time;
+use tracing::debug;
+use url::Url;
+
+use crate::{
+    api::{events::response::event, objects::display::object_display},
+    context::Context,
+    data::objects::VersionedObjectKey,
+    error::{invalid_params, rpc_bail, InternalContext, RpcError},
+};
+
+use super::rpc_module::RpcModule;
+
+#[open_rpc(namespace = "sui", tag = "Write API")]
+#[rpc(server, namespace = "sui")]
+trait WriteApi {
+    /// Execute the transaction and wait for results if desired. The transaction is forwarded to a
+    /// fullnode for execution, and the fullnode's response is returned as-is.
+    ///
+    /// Request types:
+    /// 1. WaitForEffectsCert: waits for TransactionEffectsCert and then return to client.
+    ///    This mode is a proxy for transaction finality.
+    /// 2. WaitForLocalExecution: waits for TransactionEffectsCert and make sure the transaction
+    ///    has been indexed by this service before returning the client, so that subsequent reads
+    ///    see its effects. If it is not indexed in time, `confirmedLocalExecution` is false.
+    #[method(name = "executeTransactionBlock")]
+    async fn execute_transaction_block(
+        &self,
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        tx_bytes: Base64,
+        /// A list of signatures (`flag || signature || pubkey` bytes, as base-64 encoded string).
+        /// Signature is committed to the intent message of the transaction data, as base-64
+        /// encoded string.
+        signatures: Vec<Base64>,
+        /// options for specifying the content to be returned
+        options: Option<SuiTransactionBlockResponseOptions>,
+        /// The request type, derived from `SuiTransactionBlockResponseOptions` if None
+        request_type: Option<ExecuteTransactionRequestType>,
+    ) -> RpcResult<SuiTransactionBlockResponse>;
+
+    /// Runs the transaction in dev-inspect mode. Which allows for nearly any transaction (or Move
+    /// call) with any arguments. Detailed results are provided, including both the transaction
+    /// effects and any return values. Gas is not charged, so the sender does not need to own any
+    /// gas coins. Like dry runs, the transaction is run by a fullnode, and its events are rendered
+    /// by this service.
+    #[method(name = "devInspectTransactionBlock")]
+    async fn dev_inspect_transaction_block(
+        &self,
+        sender_address: SuiAddress,
+        /// BCS encoded TransactionKind(as opposed to TransactionData, which include gasBudget and
+        /// gasPrice)
+        tx_bytes: Base64,
+        /// Gas is not charged, but gas usage is still calculated. Default to use reference gas
+        /// price
+        gas_price: Option<BigInt<u64>>,
+        /// The epoch to perform the call. Will be set from the system state object if not provided
+        epoch: Option<BigInt<u64>>,
+        /// Additional arguments including gas_budget, gas_objects, gas_sponsor and skip_checks.
+        additional_args: Option<DevInspectArgs>,
+    ) -> RpcResult<DevInspectResults>;
+
+    /// Return transaction execution effects including the gas cost summary, while the effects are
+    /// not committed to the chain. The transaction is simulated by a fullnode, and its events are
+    /// rendered using this service's package resolver, to match how events are presented by its
+    /// other methods. Events whose types this service cannot resolve (e.g. from a package that the
+    /// transaction publishes) are returned as the fullnode rendered them.
+    ///
+    /// The response also includes the Display of each object that the transaction would modify,
+    /// wrap or delete, rendered from the object's contents as of the version the transaction
+    /// reads. Objects that the transaction creates are not included, because the fullnode's
+    /// response does not include their contents.
+    #[method(name = "dryRunTransactionBlock")]
+    async fn dry_run_transaction_block(
+        &self,
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        tx_bytes: Base64,
+    ) -> RpcResult<DryRunResponse>;
+}
+
+#[open_rpc(namespace = "suix", tag = "Gas API")]
+#[rpc(server, namespace = "suix")]
+trait GasApi {
+    /// Estimate the gas cost of a transaction by dry-running it, and recommend a gas budget for
+    /// it. The recommended budget covers the transaction's net gas cost (computation and storage,
+    /// less its storage rebate), with a safety margin added to its computation cost, and is never
+    /// less than the computation cost with the margin.
+    #[method(name = "estimateGas")]
+    async fn estimate_gas(
+        &self,
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        /// The transaction's own gas budget must be high enough for the dry run to succeed.
+        tx_bytes: Base64,
+    ) -> RpcResult<GasEstimate>;
+}
+
+/// Forwards transactions to a fullnode for execution, dry runs, or dev-inspect. The client is
+/// shared between requests, so that connections to the fullnode can be re-used.
+pub(crate) struct Write(pub Context, pub reqwest::Client);
+
+pub(crate) struct Gas(pub Context, pub reqwest::Client);
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GasEstimate {
+    /// The gas the transaction used when it was dry-run.
+    pub gas_used: GasCostSummary,
+
+    /// The gas budget to set for the transaction.
+    pub recommended_budget: BigInt<u64>,
+}
+
+/// The fullnode's response to a dry run, with the Display of the transaction's input objects
+/// rendered by this service.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DryRunResponse {
+    #[serde(flatten)]
+    pub response: DryRunTransactionBlockResponse,
+
+    /// The Display of each object that the transaction modifies, as of the version it reads,
+    /// keyed by object ID. Objects that could not be loaded or rendered are left out.
+    pub input_displays: BTreeMap<ObjectID, DisplayFieldsResponse>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExecutionConfig {
+    /// The fullnode that transactions are forwarded to for execution, dry runs, dev-inspect, and
+    /// gas estimation. Whether these methods are offered at all is decided on start-up, based on
+    /// whether this is set.
+    pub fullnode_url: Option<Url>,
+
+    /// How long to wait for the fullnode to respond to a forwarded request.
+    pub request_timeout: Duration,
+
+    /// The safety margin added to a transaction's computation cost when recommending a gas
+    /// budget for it, as a percentage of the computation cost.
+    pub gas_budget_margin_percent: u64,
+
+    /// How long to wait for a transaction executed with `WaitForLocalExecution` to be indexed by
+    /// this service, before responding without confirming local execution.
+    pub local_execution_timeout: Duration,
+
+    /// How often to check whether a transaction executed with `WaitForLocalExecution` has been
+    /// indexed yet.
+    pub local_execution_interval: Duration,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Dry run failed, so gas could not be estimated: {0}")]
+    DryRunFailed(String),
+}
+
+/// The parts of a JSON-RPC response from the fullnode that are passed on to the client.
+#[derive(Deserialize)]
+struct Response<T> {
+    result: Option<T>,
+    error: Option<ResponseError>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+    code: i32,
+    message: String,
+    data: Option<serde_json::Value>,
+}
+
+#[async_trait::async_trait]
+impl WriteApiServer for Write {
+    async fn execute_transaction_block(
+        &self,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+        options: Option<SuiTransactionBlockResponseOptions>,
+        request_type: Option<ExecuteTransactionRequestType>,
+    ) -> RpcResult<SuiTransactionBlockResponse> {
+        let Self(ctx, client) = self;
+        let params = json!([tx_bytes, signatures, options, request_type]);
+        let mut response: SuiTransactionBlockResponse =
+            forward(ctx, client, "sui_executeTransactionBlock", params)
+                .await
+                .with_internal_context(|| "Failed to forward transaction for execution")??;
+
+        // The fullnode's local execution does not guarantee that the transaction is visible to
+        // reads through this service, so wait for it to be indexed here as well.
+        if let Some(ExecuteTransactionRequestType::WaitForLocalExecution) = request_type {
+            let indexed = wait_for_local_execution(ctx, response.digest)
+                .await
+                .with_internal_context(|| {
+                    format!(
+                        "Failed to wait for transaction {} to be indexed",
+                        response.digest
+                    )
+                })?;
+
+            response.confirmed_local_execution = Some(indexed);
+        }
+
+        Ok(response)
+    }
+
+    async fn dev_inspect_transaction_block(
+        &self,
+        sender_address: SuiAddress,
+        tx_bytes: Base64,
+        gas_price: Option<BigInt<u64>>,
+        epoch: Option<BigInt<u64>>,
+        additional_args: Option<DevInspectArgs>,
+    ) -> RpcResult<DevInspectResults> {
+        let Self(ctx, client) = self;
+        let params = json!([sender_address, tx_bytes, gas_price, epoch, additional_args]);
+        let mut response: DevInspectResults =
+            forward(ctx, client, "sui_devInspectTransactionBlock", params)
+                .await
+                .with_internal_context(|| "Failed to forward transaction for dev-inspect")??;
+
+        response.events = render_events(ctx, response.events).await;
+
+        Ok(response)
+    }
+
+    async fn dry_run_transaction_block(&self, tx_bytes: Base64) -> RpcResult<DryRunResponse> {
+        let Self(ctx, client) = self;
+        let params = json!([tx_bytes]);
+        let mut response: DryRunTransactionBlockResponse =
+            forward(ctx, client, "sui_dryRunTransactionBlock", params)
+                .await
+                .with_internal_context(|| "Failed to forward transaction for dry run")??;
+
+        response.events = render_events(ctx, response.events).await;
+
+        let inputs = response.effects.modified_at_versions();
+        let input_displays = input_displays(ctx, inputs)
+            .await
+            .with_internal_context(|| "Failed to render dry run's input objects")?;
+
+        Ok(DryRunResponse {
+            response,
+            input_displays,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl GasApiServer for Gas {
+    async fn estimate_gas(&self, tx_bytes: Base64) -> RpcResult<GasEstimate> {
+        let Self(ctx, client) = self;
+        let params = json!([tx_bytes]);
+        let response: DryRunTransactionBlockResponse =
+            forward(ctx, client, "sui_dryRunTransactionBlock", params)
+                .await
+                .with_internal_context(|| "Failed to forward transaction for dry run")??;
+
+        Ok(gas_estimate(ctx, response)?)
+    }
+}
+
+impl RpcModule for Write {
+    fn schema(&self) -> Module {
+        WriteApiOpenRpc::module_doc()
+    }
+
+    fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+        self.into_rpc()
+    }
+}
+
+impl RpcModule for Gas {
+    fn schema(&self) -> Module {
+        GasApiOpenRpc::module_doc()
+    }
+
+    fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+        self.into_rpc()
+    }
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            fullnode_url: None,
+            request_timeout: Duration::from_secs(60),
+            gas_budget_margin_percent: 10,
+            local_execution_timeout: Duration::from_secs(10),
+            local_execution_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Summarize the gas used by the dry run in `response`, and recommend a budget based on it. It is
+/// a user error for the dry run to have failed, as its gas usage would not be representative.
+fn gas_estimate(
+    ctx: &Context,
+    response: DryRunTransactionBlockResponse,
+) -> Result<GasEstimate, RpcError<Error>> {
+    if let SuiExecutionStatus::Failure { error } = response.effects.status() {
+        return Err(invalid_params(Error::DryRunFailed(error.clone())));
+    }
+
+    let gas_used = response.effects.gas_cost_summary().clone();
+    let margin = ctx.config().execution.gas_budget_margin_percent;
+
+    Ok(GasEstimate {
+        recommended_budget: recommended_budget(&gas_used, margin).into(),
+        gas_used,
+    })
+}
+
+/// The gas budget to recommend for a transaction that used `gas_used`: Its net gas cost, with a
+/// margin of `margin_percent` added to its computation cost, and at least its computation cost
+/// with that margin, which must be covered by the budget even if the rebate exceeds the cost of
+/// storage.
+fn recommended_budget(gas_used: &GasCostSummary, margin_percent: u64) -> u64 {
+    let computation = gas_used.computation_cost as u128;
+    let computation = computation + computation * margin_percent as u128 / 100;
+    let budget = (computation + gas_used.storage_cost as u128)
+        .saturating_sub(gas_used.storage_rebate as u128)
+        .max(computation);
+
+    budget.try_into().unwrap_or(u64::MAX)
+}
+
+/// Poll the store until the transaction with digest `digest` can be read from it, or until the
+/// configured timeout elapses. Returns whether the transaction was found in time.
+async fn wait_for_local_execution(
+    ctx: &Context,
+    digest: TransactionDigest,
+) -> Result<bool, RpcError> {
+    let config = ctx.config().execution.clone();
+
+    let poll = async {
+        let mut interval = time::interval(config.local_execution_interval);
+        loop {
+            interval.tick().await;
+            let tx = ctx
+                .kv_loader()
+                .load_one_transaction(digest)
+                .await
+                .context("Failed to check for transaction")?;
+
+            if tx.is_some() {
+                return Ok::<_, anyhow::Error>(());
+            }
+        }
+    };
+
+    match time::timeout(config.local_execution_timeout, poll).await {
+        Ok(found) => {
+            found?;
+            Ok(true)
+        }
+        Err(_) => Ok(false),
+    }
+}
+
+/// Re-render the contents of `events` reported by the fullnode from their BCS representation,
+/// using the local package resolver, keeping the fullnode's rendering of events that cannot be
+/// rendered locally.
+async fn render_events(
+    ctx: &Context,
+    events: SuiTransactionBlockEvents,
+) -> SuiTransactionBlockEvents {
+    let mut data = Vec::with_capacity(events.data.len());
+    for sui_event in events.data {
+        let rendered = sui_event.clone();
+        let SuiEvent {
+            id,
+            package_id,
+            transaction_module,
+            sender,
+            type_,
+            bcs,
+            timestamp_ms,
+            ..
+        } = sui_event;
+
+        let native = Event {
+            package_id,
+            transaction_module,
+            sender,
+            type_,
+            contents: bcs.into_bytes(),
+        };
+
+        let ix = id.event_seq as usize;
+        match event(ctx, id.tx_digest, ix, native, timestamp_ms).await {
+            Ok(event) => data.push(event),
+
+            // The fullnode has already rendered the event, so if it cannot be rendered locally
+            // (e.g. because its type is from a package that only exists in the dry run), the
+            // fullnode's rendering is used instead.
+            Err(e) => {
+                debug!(ix, "Failed to render event locally: {e:#}");
+                data.push(rendered);
+            }
+        }
+    }
+
+    SuiTransactionBlockEvents { data }
+}
+
+/// Render the Display of each object in `inputs`, identified by its ID and the version that the
+/// transaction reads. Objects that are not in the store (e.g. because they have been pruned, or
+/// have not been indexed yet) are left out, as are objects whose Display cannot be rendered
+/// locally.
+async fn input_displays(
+    ctx: &Context,
+    inputs: Vec<(ObjectID, SequenceNumber)>,
+) -> Result<BTreeMap<ObjectID, DisplayFieldsResponse>, RpcError> {
+    let keys = inputs
+        .into_iter()
+        .map(|(id, version)| VersionedObjectKey(id, version.value()))
+        .collect();
+
+    let objects = ctx
+        .kv_loader()
+        .load_many_objects(keys)
+        .await
+        .context("Failed to load input objects")?;
+
+    let mut displays = BTreeMap::new();
+    for (VersionedObjectKey(id, _), object) in objects {
+        match object_display(ctx, &object).await {
+            Ok(display) => {
+                displays.insert(id, display);
+            }
+
+            Err(e) => debug!(%id, "Failed to render input object Display: {e:#}"),
+        }
+    }
+
+    Ok(displays)
+}
+
+/// Send a JSON-RPC request for `method` with `params` to the configured fullnode, and return its
+/// response. Errors reported by the fullnode are returned unchanged (in the inner result), so that
+/// clients see the same errors they would if they had talked to the fullnode directly. Failing to
+/// reach the fullnode, or to understand its response, is an internal error.
+async fn forward<T: DeserializeOwned>(
+    ctx: &Context,
+    client: &reqwest::Client,
+    method: &str,
+    params: serde_json::Value,
+) -> Result<Result<T, ErrorObjectOwned>, RpcError> {
+    let config = ctx.config().execution.clone();
+    let url = config
+        .fullnode_url
+        .context("No fullnode configured to execute transactions")?;
+
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+
+    let response = client
+        .post(url)
+        .timeout(config.request_timeout)
+        .json(&body)
+        .send()
+        .await
+        .context("Failed to send request to fullnode")?;
+
+    let response: Response<T> = response
+        .json()
+        .await
+        .context("Failed to deserialize response from fullnode")?;
+
+    match response {
+        Response {
+            result: Some(result),
+            ..
+        } => Ok(Ok(result)),
+        Response {
+            error:
+                Some(ResponseError {
+                    code,
+                    message,
+                    data,
+                }),
+            ..
+        } => Ok(Err(ErrorObject::owned(code, message, data))),
+        _ => rpc_bail!("Fullnode responded without a result or an error"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::Registry;
+    use sui_indexer_alt_schema::{
+        objects::StoredObject,
+        schema::{kv_objects, kv_transactions},
+        transactions::StoredTransaction,
+        MIGRATIONS,
+    };
+    use sui_pg_db::{temp::TempDb, Db, DbArgs};
+    use sui_types::object::Object;
+    use wiremock::{
+        matchers::{body_partial_json, method},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use crate::{config::RpcConfig, metrics::RpcMetrics};
+
+    use super::*;
+
+    /// A context reading from `db`, forwarding requests to `fullnode`, with the `[execution]`
+    /// settings in `execution` applied on top. Also returns a connection pool to write test data
+    /// to the database with.
+    async fn context(db: &TempDb, fullnode: &MockServer, execution: &str) -> (Context, Db) {
+        let url = db.database().url().clone();
+        let writer = Db::for_write(DbArgs::new_for_testing(url.clone()))
+            .await
+            .unwrap();
+        writer.run_migrations(MIGRATIONS).await.unwrap();
+
+        let config: RpcConfig = toml::from_str(&format!(
+            "[execution]\nfullnode-url = \"{}\"\n{execution}",
+            fullnode.uri()
+        ))
+        .unwrap();
+
+        let (config, _) = config.finish_service(true).unwrap();
+        let registry = Registry::new();
+        let ctx = Context::new(
+            DbArgs::new_for_testing(url),
+            None,
+            config,
+            RpcMetrics::new(&registry),
+            &registry,
+        )
+        .await
+        .unwrap();
+
+        (ctx, writer)
+    }
+
+    /// Respond to every JSON-RPC request `fullnode` receives with `response`.
+    async fn respond_with(fullnode: &MockServer, response: ResponseTemplate) {
+        Mock::given(method("POST"))
+            .respond_with(response)
+            .mount(fullnode)
+            .await;
+    }
+
+    fn rpc_response(body: serde_json::Value) -> ResponseTemplate {
+        let mut body = body;
+        body["jsonrpc"] = json!("2.0");
+        body["id"] = json!(1);
+        ResponseTemplate::new(200).set_body_json(body)
+    }
+
+    /// A fullnode response to executing the transaction with digest `digest`.
+    fn executed(digest: TransactionDigest) -> ResponseTemplate {
+        let response = SuiTransactionBlockResponse::new(digest);
+        rpc_response(json!({ "result": response }))
+    }
+
+    async fn execute(
+        ctx: &Context,
+        request_type: ExecuteTransactionRequestType,
+    ) -> SuiTransactionBlockResponse {
+        let write = Write(ctx.clone(), reqwest::Client::new());
+        write
+            .execute_transaction_block(Base64::from_bytes(&[]), vec![], None, Some(request_type))
+            .await
+            .unwrap()
+    }
+
+    async fn index_transaction(writer: &Db, digest: TransactionDigest) {
+        let row = StoredTransaction {
+            tx_digest: digest.inner().to_vec(),
+            cp_sequence_number: 0,
+            timestamp_ms: 0,
+            raw_transaction: vec![],
+            raw_effects: vec![],
+            events: vec![],
+            user_signatures: vec![],
+        };
+
+        let mut conn = writer.connect().await.unwrap();
+        diesel_async::RunQueryDsl::execute(
+            diesel::insert_into(kv_transactions::table).values(&row),
+            &mut conn,
+        )
+        .await
+        .unwrap();
+    }
+
+    fn gas(computation_cost: u64, storage_cost: u64, storage_rebate: u64) -> GasCostSummary {
+        GasCostSummary::new(computation_cost, storage_cost, storage_rebate, 0)
+    }
+
+    #[test]
+    fn test_recommended_budget() {
+        // The margin only applies to computation.
+        assert_eq!(recommended_budget(&gas(1000, 2000, 500), 10), 2600);
+        assert_eq!(recommended_budget(&gas(1000, 2000, 500), 0), 2500);
+
+        // The budget always covers computation, even if the rebate outweighs storage costs.
+        assert_eq!(recommended_budget(&gas(1000, 100, 5000), 10), 1100);
+
+        // Large costs saturate, instead of overflowing.
+        assert_eq!(recommended_budget(&gas(u64::MAX, 0, 0), 10), u64::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_forward_result() {
+        let db = TempDb::new().unwrap();
+        let fullnode = MockServer::start().await;
+        let (ctx, _) = context(&db, &fullnode, "").await;
+
+        // The request is sent to the fullnode with the method and parameters it was forwarded
+        // with, and the result is passed back as-is.
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({
+                "method": "sui_dryRunTransactionBlock",
+                "params": ["AQI="],
+            })))
+            .respond_with(rpc_response(json!({ "result": { "foo": 42 } })))
+            .mount(&fullnode)
+            .await;
+
+        let client = reqwest::Client::new();
+        let params = json!(["AQI="]);
+        let result: serde_json::Value =
+            forward(&ctx, &client, "sui_dryRunTransactionBlock", params)
+                .await
+                .unwrap()
+                .unwrap();
+
+        assert_eq!(result, json!({ "foo": 42 }));
+    }
+
+    #[tokio::test]
+    async fn test_forward_error() {
+        let db = TempDb::new().unwrap();
+        let fullnode = MockServer::start().await;
+        let (ctx, _) = context(&db, &fullnode, "").await;
+
+        let error = json!({ "code": -32002, "message": "Invalid user signature", "data": [1, 2] });
+        respond_with(&fullnode, rpc_response(json!({ "error": error }))).await;
+
+        // Errors reported by the fullnode are passed back to the client unchanged.
+        let client = reqwest::Client::new();
+        let err =
+            forward::<serde_json::Value>(&ctx, &client, "sui_executeTransactionBlock", json!([]))
+                .await
+                .unwrap()
+                .unwrap_err();
+
+        assert_eq!(err.code(), -32002);
+        assert_eq!(err.message(), "Invalid user signature");
+        assert_eq!(err.data().map(|d| d.get()), Some("[1,2]"));
+    }
+
+    #[tokio::test]
+    async fn test_forward_timeout() {
+        let db = TempDb::new().unwrap();
+        let fullnode = MockServer::start().await;
+        let (ctx, _) = context(&db, &fullnode, "request-timeout-ms = 100").await;
+
+        let response = rpc_response(json!({ "result": {} })).set_delay(Duration::from_secs(5));
+        respond_with(&fullnode, response).await;
+
+        // A fullnode that does not respond in time is an internal error.
+        let client = reqwest::Client::new();
+        let result =
+            forward::<serde_json::Value>(&ctx, &client, "sui_dryRunTransactionBlock", json!([]))
+                .await;
+
+        assert!(matches!(result, Err(RpcError::InternalError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_forward_malformed_response() {
+        let db = TempDb::new().unwrap();
+        let client = reqwest::Client::new();
+
+        for response in [
+            ResponseTemplate::new(200).set_body_string("not json"),
+            ResponseTemplate::new(502).set_body_string("Bad Gateway"),
+            rpc_response(json!({})),
+            rpc_response(json!({ "result": "not an object" })),
+        ] {
+            let fullnode = MockServer::start().await;
+            let (ctx, _) = context(&db, &fullnode, "").await;
+            respond_with(&fullnode, response).await;
+
+            // Responses that cannot be understood are internal errors, not errors to pass on.
+            let result =
+                forward::<GasCostSummary>(&ctx, &client, "sui_dryRunTransactionBlock", json!([]))
+                    .await;
+
+            assert!(matches!(result, Err(RpcError::InternalError(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_local_execution_found() {
+        let db = TempDb::new().unwrap();
+        let fullnode = MockServer::start().await;
+        let (ctx, writer) = context(&db, &fullnode, "").await;
+
+        let digest = TransactionDigest::random();
+        respond_with(&fullnode, executed(digest)).await;
+
+        // The transaction is indexed while execution is waiting for it.
+        let indexer = tokio::spawn(async move {
+            time::sleep(Duration::from_millis(300)).await;
+            index_transaction(&writer, digest).await;
+        });
+
+        let response = execute(&ctx, ExecuteTransactionRequestType::WaitForLocalExecution).await;
+        indexer.await.unwrap();
+
+        assert_eq!(response.digest, digest);
+        assert_eq!(response.confirmed_local_execution, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_local_execution_timeout() {
+        let db = TempDb::new().unwrap();
+        let fullnode = MockServer::start().await;
+        let execution = "local-execution-timeout-ms = 300\nlocal-execution-interval-ms = 50";
+        let (ctx, _) = context(&db, &fullnode, execution).await;
+
+        let digest = TransactionDigest::random();
+        respond_with(&fullnode, executed(digest)).await;
+
+        // The transaction is never indexed, so execution is not confirmed locally, but the
+        // fullnode's response is still returned.
+        let response = execute(&ctx, ExecuteTransactionRequestType::WaitForLocalExecution).await;
+        assert_eq!(response.digest, digest);
+        assert_eq!(response.confirmed_local_execution, Some(false));
+
+        // Without waiting for local execution, the fullnode's response is returned as-is.
+        let response = execute(&ctx, ExecuteTransactionRequestType::WaitForEffectsCert).await;
+        assert_eq!(response.confirmed_local_execution, None);
+    }
+
+    #[tokio::test]
+    async fn test_input_displays() {
+        let db = TempDb::new().unwrap();
+        let fullnode = MockServer::start().await;
+        let (ctx, writer) = context(&db, &fullnode, "").await;
+
+        let owner = SuiAddress::random_for_testing_only();
+        let coin = Object::with_id_owner_gas_for_testing(ObjectID::random(), owner, 1000);
+        let row = StoredObject {
+            object_id: coin.id().to_vec(),
+            object_version: coin.version().value() as i64,
+            serialized_object: Some(bcs::to_bytes(&coin).unwrap()),
+        };
+
+        let mut conn = writer.connect().await.unwrap();
+        diesel_async::RunQueryDsl::execute(
+            diesel::insert_into(kv_objects::table).values(&row),
+            &mut conn,
+        )
+        .await
+        .unwrap();
+
+        // The coin's type has no Display, so it is rendered without any fields. The other input
+        // is not in the store, so it is left out.
+        let missing = ObjectID::random();
+        let inputs = vec![
+            (coin.id(), coin.version()),
+            (missing, SequenceNumber::from_u64(1)),
+        ];
+
+        let displays = input_displays(&ctx, inputs).await.unwrap();
+        assert_eq!(
+            displays,
+            BTreeMap::from([(
+                coin.id(),
+                DisplayFieldsResponse {
+                    data: None,
+                    error: None,
+                }
+            )])
+        );
+    }
+}
@@ -32,10 +32,46 @@ pub enum Command {
 
         /// Path to the RPC's configuration TOML file. If one is not provided, the default values for
         /// the configuration will be set.
+        ///
+        /// The file can include other configuration files, which it is merged on top of, with
+        /// `include = ["base.toml", ...]`. Fields in the configuration can be overridden by
+        /// environment variables, such as `SUI_RPC__OBJECTS__MAX_PAGE_SIZE=100`. Secrets can be
+        /// kept out of the file by referring to environment variables (`"${VAR}"`), or to files
+        /// that contain them (`"file:/path/to/secret"`).
+        ///
+        /// Limits in the configuration (such as page sizes) are reloaded without restarting the
+        /// service when it receives SIGHUP, or when this file is modified.
         #[arg(long)]
         config: Option<PathBuf>,
+
+        /// Treat unrecognized fields in the configuration as errors, rather than ignoring them
+        /// with a warning. This catches typos in field names, which would otherwise cause the
+        /// field to silently fall back to its default value.
+        #[arg(long)]
+        strict_config: bool,
+
+        /// Print the effective configuration -- the configuration after all includes, overrides
+        /// and defaults have been applied -- to STDOUT, and exit without starting the service. API
+        /// keys and passwords in replica URLs are redacted.
+        #[arg(long)]
+        dump_effective_config: bool,
     },
 
     /// Output the contents of the default configuration to STDOUT.
     GenerateConfig,
+
+    /// Check that the RPC service could start with its configuration: that the configuration is
+    /// valid, that the database and Bigtable (if configured) are reachable, and that the objects
+    /// it refers to exist on-chain. Outputs a report to STDOUT as JSON, and exits with an error if
+    /// any of the checks failed.
+    ValidateConfig {
+        /// Path to the RPC's configuration TOML file. If one is not provided, the default values
+        /// for the configuration will be validated.
+        #[arg(long)]
+        config: Option<PathBuf>,
+
+        /// Treat unrecognized fields in the configuration as errors.
+        #[arg(long)]
+        strict_config: bool,
+    },
 }
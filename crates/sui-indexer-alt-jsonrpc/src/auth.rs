@@ -0,0 +1,200 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::future::{self, BoxFuture, FutureExt};
+use jsonrpsee::server::HttpBody;
+use serde_json::json;
+use tower_layer::Layer;
+
+use crate::error::UNAUTHORIZED_CODE;
+
+/// Header that clients can use to pass their API key, as an alternative to `Authorization`.
+const API_KEY: &str = "x-api-key";
+
+/// Which clients are allowed to access the service.
+#[derive(Clone, Default)]
+pub struct AuthConfig {
+    /// Map from API key to the ID of the key, or `None` if the service does not require clients to
+    /// authenticate.
+    pub keys: Option<HashMap<String, String>>,
+}
+
+/// The ID of the API key that a request was authenticated with. This is attached to requests as
+/// an extension, so that it can be used to tag metrics and logs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ApiKeyId(pub String);
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub(crate) enum AuthError {
+    #[error("Missing API key, pass it in the Authorization or X-API-Key header")]
+    Missing,
+
+    #[error("Invalid API key")]
+    Invalid,
+}
+
+/// Tower Layer that adds middleware to reject HTTP requests that do not carry a known API key,
+/// either as a bearer token in their `Authorization` header, or in their `X-API-Key` header.
+#[derive(Clone)]
+pub(crate) struct Authenticator {
+    #[allow(clippy::type_complexity)]
+    authenticate: Arc<dyn Fn(Option<&str>) -> Result<Option<ApiKeyId>, AuthError> + Send + Sync>,
+}
+
+/// The Tower Service responsible for authenticating requests to the wrapped HTTP service.
+#[derive(Clone)]
+pub(crate) struct AuthService<S> {
+    authenticator: Authenticator,
+    inner: S,
+}
+
+impl AuthConfig {
+    /// Check the API `key` that a request was made with. Returns the ID of the key if the request
+    /// is authenticated, `None` if authentication is not required, or an error if the request
+    /// needed a valid key and did not have one.
+    pub(crate) fn authenticate(&self, key: Option<&str>) -> Result<Option<ApiKeyId>, AuthError> {
+        let Some(keys) = &self.keys else {
+            return Ok(None);
+        };
+
+        let key = key.ok_or(AuthError::Missing)?;
+        let id = keys.get(key).ok_or(AuthError::Invalid)?;
+        Ok(Some(ApiKeyId(id.clone())))
+    }
+}
+
+impl fmt::Debug for AuthConfig {
+    /// Only the IDs of keys are shown, so that keys do not end up in logs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ids = self
+            .keys
+            .as_ref()
+            .map(|keys| keys.values().collect::<Vec<_>>());
+
+        f.debug_struct("AuthConfig").field("key_ids", &ids).finish()
+    }
+}
+
+impl Authenticator {
+    /// Create a new layer that checks the API key of each request by calling `authenticate`.
+    /// This is done on every request, so that keys can be added or removed while the service is
+    /// running.
+    pub(crate) fn new(
+        authenticate: impl Fn(Option<&str>) -> Result<Option<ApiKeyId>, AuthError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self {
+            authenticate: Arc::new(authenticate),
+        }
+    }
+}
+
+impl Default for Authenticator {
+    /// A layer that does not require requests to be authenticated.
+    fn default() -> Self {
+        Self::new(|_| Ok(None))
+    }
+}
+
+impl<S> Layer<S> for Authenticator {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            authenticator: self.clone(),
+            inner,
+        }
+    }
+}
+
+impl<S, B> tower::Service<http::Request<B>> for AuthService<S>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<HttpBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+        let headers = request.headers();
+        let bearer = headers
+            .get(http::header::AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "));
+
+        let api_key = headers.get(API_KEY).and_then(|h| h.to_str().ok());
+
+        match (self.authenticator.authenticate)(bearer.or(api_key).map(str::trim)) {
+            Ok(None) => self.inner.call(request).boxed(),
+
+            Ok(Some(id)) => {
+                request.extensions_mut().insert(id);
+                self.inner.call(request).boxed()
+            }
+
+            Err(e) => future::ready(Ok(unauthorized(e))).boxed(),
+        }
+    }
+}
+
+/// An HTTP 401 response, with a JSON-RPC error body explaining why the request was rejected.
+fn unauthorized(error: AuthError) -> http::Response<HttpBody> {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": UNAUTHORIZED_CODE,
+            "message": error.to_string(),
+        },
+        "id": null,
+    });
+
+    http::Response::builder()
+        .status(http::StatusCode::UNAUTHORIZED)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(http::header::WWW_AUTHENTICATE, "Bearer")
+        .body(HttpBody::from(body.to_string()))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authenticate() {
+        let config = AuthConfig {
+            keys: Some(HashMap::from([("s3cr3t".to_owned(), "indexer".to_owned())])),
+        };
+
+        assert_eq!(
+            config.authenticate(Some("s3cr3t")),
+            Ok(Some(ApiKeyId("indexer".to_owned())))
+        );
+        assert_eq!(config.authenticate(Some("guess")), Err(AuthError::Invalid));
+        assert_eq!(config.authenticate(None), Err(AuthError::Missing));
+
+        // Keys are not shown when the configuration is logged.
+        assert!(!format!("{config:?}").contains("s3cr3t"));
+    }
+
+    #[test]
+    fn test_no_auth() {
+        let config = AuthConfig::default();
+        assert_eq!(config.authenticate(None), Ok(None));
+        assert_eq!(config.authenticate(Some("anything")), Ok(None));
+    }
+}
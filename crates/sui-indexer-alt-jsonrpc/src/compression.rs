@@ -0,0 +1,51 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use tower_http::compression::{predicate::SizeAbove, CompressionLayer};
+
+/// How responses are compressed, for clients that accept compressed responses (by sending an
+/// `Accept-Encoding` header).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompressionConfig {
+    /// The encodings that responses can be compressed with. If this is empty, responses are never
+    /// compressed. If a client accepts more than one of these encodings, its preference (quality
+    /// value) decides between them.
+    pub codecs: Vec<Codec>,
+
+    /// Responses smaller than this many bytes are not compressed, because compressing them would
+    /// not save enough to be worth the effort.
+    pub min_size: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Codec {
+    Gzip,
+    Zstd,
+    Br,
+    Deflate,
+}
+
+impl CompressionConfig {
+    /// The layer that compresses responses. Responses pass through it uncompressed if no codecs
+    /// are enabled, or the client does not accept any of them.
+    pub(crate) fn layer(&self) -> CompressionLayer<SizeAbove> {
+        let enabled = |codec| self.codecs.contains(&codec);
+        CompressionLayer::new()
+            .gzip(enabled(Codec::Gzip))
+            .zstd(enabled(Codec::Zstd))
+            .br(enabled(Codec::Br))
+            .deflate(enabled(Codec::Deflate))
+            .compress_when(SizeAbove::new(self.min_size))
+    }
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            codecs: vec![Codec::Gzip, Codec::Zstd],
+            min_size: 1024,
+        }
+    }
+}
@@ -0,0 +1,255 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use tokio::{sync::Notify, task::JoinHandle, time};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{BigtableConfig, RpcConfig, ServiceConfig},
+    context::Context,
+    subscription::SubscriptionsConfig,
+};
+
+/// How often to check whether the configuration files have been modified.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Background task responsible for reloading the service's configuration when it receives SIGHUP,
+/// or when its configuration file is modified. Limits (like page sizes, the package resolver's
+/// limits, and per-method timeouts) are swapped in without restarting the service. Changes to the
+/// parts of the configuration that are only read on start-up (like the Bigtable, CORS and database
+/// configuration) still require a restart.
+pub(crate) struct ConfigWatcher {
+    /// The context to reload the configuration into.
+    context: Context,
+    /// Path to the configuration TOML file.
+    path: PathBuf,
+    /// Whether unrecognized fields in the configuration are treated as errors.
+    strict: bool,
+    /// The Bigtable configuration the service started with.
+    bigtable_config: Option<BigtableConfig>,
+    /// Signal to cancel the task.
+    cancel: CancellationToken,
+}
+
+impl ConfigWatcher {
+    pub(crate) fn new(
+        context: Context,
+        path: PathBuf,
+        strict: bool,
+        bigtable_config: Option<BigtableConfig>,
+        cancel: CancellationToken,
+    ) -> Self {
+        Self {
+            context,
+            path,
+            strict,
+            bigtable_config,
+            cancel,
+        }
+    }
+
+    /// Start a new task that watches for SIGHUP and polls the configuration file for changes,
+    /// reloading the configuration when either happens. If the new configuration can't be read,
+    /// the service continues with its current configuration.
+    ///
+    /// This operation consumes the `self` and returns a handle to the spawned tokio task. The task
+    /// will continue to run until its cancellation token is triggered.
+    pub(crate) fn run(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let Self {
+                context,
+                path,
+                strict,
+                bigtable_config,
+                cancel,
+            } = self;
+
+            let hangup = Arc::new(Notify::new());
+            let h_hangup = listen_for_hangup(hangup.clone(), cancel.clone());
+
+            // The configuration is reloaded when any of the files it was read from (the file at
+            // `path` and the files it includes) are modified.
+            let paths = RpcConfig::read_with_sources(&path)
+                .await
+                .map_or_else(|_| vec![path.clone()], |(_, sources)| sources);
+
+            let mut sources = Sources::new(paths).await;
+            let mut interval = time::interval(POLL_INTERVAL);
+
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => {
+                        info!("Shutdown signal received, terminating config watcher");
+                        break;
+                    }
+
+                    _ = hangup.notified() => {
+                        info!(path = %path.display(), "Received SIGHUP, reloading configuration");
+                        sources.refresh().await;
+                        let reloaded = reload(&context, &path, strict, &bigtable_config).await;
+                        if let Some(paths) = reloaded {
+                            sources.track(paths).await;
+                        }
+                    }
+
+                    _ = interval.tick() => {
+                        if !sources.changed().await {
+                            continue;
+                        }
+
+                        info!(path = %path.display(), "Configuration file changed, reloading");
+                        let reloaded = reload(&context, &path, strict, &bigtable_config).await;
+                        if let Some(paths) = reloaded {
+                            sources.track(paths).await;
+                        }
+                    }
+                }
+            }
+
+            let _ = h_hangup.await;
+        })
+    }
+}
+
+/// Re-read the configuration at `path` and swap it into `context`. Returns the files that the
+/// configuration was read from, if it was reloaded successfully.
+async fn reload(
+    context: &Context,
+    path: &Path,
+    strict: bool,
+    bigtable_config: &Option<BigtableConfig>,
+) -> Option<Vec<PathBuf>> {
+    let finished = RpcConfig::read_with_sources(path)
+        .await
+        .and_then(|(rpc_config, sources)| Ok((rpc_config.finish_service(strict)?, sources)));
+
+    let ((config, next_bigtable_config), sources) = match finished {
+        Ok(finished) => finished,
+        Err(e) => {
+            error!("Failed to reload configuration, keeping current configuration: {e:#}");
+            return None;
+        }
+    };
+
+    let instance_id = |c: &Option<BigtableConfig>| c.as_ref().map(|c| c.instance_id.clone());
+    if instance_id(&next_bigtable_config) != instance_id(bigtable_config) {
+        warn!("Changes to the Bigtable configuration are only applied on restart");
+    }
+
+    if config.cors != context.config().cors {
+        warn!("Changes to the CORS configuration are only applied on restart");
+    }
+
+    if config.compression != context.config().compression {
+        warn!("Changes to the compression configuration are only applied on restart");
+    }
+
+    let subscriptions = SubscriptionsConfig {
+        send_timeout: context.config().subscriptions.send_timeout,
+        ..config.subscriptions.clone()
+    };
+
+    if subscriptions != context.config().subscriptions {
+        warn!(
+            "Changes to subscription limits (other than the send timeout) are only applied on \
+             restart"
+        );
+    }
+
+    let buffer_sizes = |c: &ServiceConfig| {
+        (
+            c.events.subscription_buffer_size,
+            c.transactions.subscription_buffer_size,
+        )
+    };
+
+    if buffer_sizes(&config) != buffer_sizes(&context.config()) {
+        warn!("Changes to subscription buffer sizes are only applied on restart");
+    }
+
+    if config.db != context.config().db {
+        warn!("Changes to the database configuration are only applied on restart");
+    }
+
+    let webhooks_startup = |c: &ServiceConfig| {
+        let w = &c.webhooks;
+        (
+            w.enabled,
+            w.allow_private,
+            w.refresh_interval,
+            w.max_concurrent_deliveries,
+        )
+    };
+
+    if webhooks_startup(&config) != webhooks_startup(&context.config()) {
+        warn!(
+            "Changes to whether webhooks are enabled, whether notifications can be delivered to \
+             private addresses, how often webhooks are refreshed, and how many deliveries can be \
+             in flight are only applied on restart"
+        );
+    }
+
+    if config.stream != context.config().stream {
+        warn!("Changes to the stream configuration are only applied on restart");
+    }
+
+    info!("Reloaded configuration: {config:#?}");
+    context.reload(config);
+    Some(sources)
+}
+
+/// The files that the configuration was read from, and when they were last modified.
+struct Sources {
+    paths: Vec<PathBuf>,
+    modified: Vec<Option<SystemTime>>,
+}
+
+impl Sources {
+    async fn new(paths: Vec<PathBuf>) -> Self {
+        let modified = modified(&paths).await;
+        Self { paths, modified }
+    }
+
+    /// Record the current modification times of the files.
+    async fn refresh(&mut self) {
+        self.modified = modified(&self.paths).await;
+    }
+
+    /// Check whether any of the files have been modified since they were last checked. Changes
+    /// are ignored while the main configuration file does not exist.
+    async fn changed(&mut self) -> bool {
+        let next = modified(&self.paths).await;
+        if next.first().is_some_and(Option::is_some) && next != self.modified {
+            self.modified = next;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Start watching `paths` instead, if the set of files the configuration is read from has
+    /// changed.
+    async fn track(&mut self, paths: Vec<PathBuf>) {
+        if paths != self.paths {
+            *self = Self::new(paths).await;
+        }
+    }
+}
+
+/// The last modified times of each of the files in `paths`, if they exist.
+async fn modified(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    let mut modified = Vec::with_capacity(paths.len());
+    for path in paths {
+        let metadata = tokio::fs::metadata(path).await.ok();
+        modified.push(metadata.and_then(|m| m.modified().ok()));
+    }
+
+    modified
+}
@@ -1,21 +1,28 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
+use anyhow::Context as _;
+use arc_swap::ArcSwap;
 use async_graphql::dataloader::DataLoader;
+use diesel::QueryDsl;
 use prometheus::Registry;
+use sui_indexer_alt_schema::schema::kv_genesis;
 use sui_package_resolver::Resolver;
 use sui_pg_db::DbArgs;
+use sui_types::digests::{ChainIdentifier, CheckpointDigest};
+use tokio::sync::OnceCell;
 
 use crate::{
-    config::BigtableConfig,
+    config::{BigtableConfig, ServiceConfig},
     data::{
         bigtable_reader::BigtableReader,
+        displays::DisplayCache,
         error::Error,
         kv_loader::KvLoader,
-        package_resolver::{DbPackageStore, PackageCache, PackageResolver},
-        pg_reader::PgReader,
+        package_resolver::{DbPackageStore, PackageCache, PackageResolver, SharedPackageCache},
+        pg_reader::{Database, PgReader},
     },
     metrics::RpcMetrics,
 };
@@ -28,16 +35,34 @@ pub(crate) struct Context {
 
     /// Access to the database for performing point look-ups. Access is through the same connection
     /// pool as `reader`, but this interface groups multiple point lookups into a single database
-    /// query.
-    pg_loader: Arc<DataLoader<PgReader>>,
+    /// query. There is a loader for each database (the primary, and each replica), so that a
+    /// request's look-ups are served by the same database as the rest of its reads.
+    pg_loaders: Arc<HashMap<Database, Arc<DataLoader<PgReader>>>>,
 
     /// Access to the kv store for performing point look-ups. This may either be backed by Bigtable
-    /// or Postgres db, depending on the configuration.
-    kv_loader: KvLoader,
+    /// or Postgres db, depending on the configuration. Like `pg_loaders`, there is one for each
+    /// database (which are all the same if it is backed by Bigtable).
+    kv_loaders: Arc<HashMap<Database, KvLoader>>,
 
     /// Access to the database for accessing information about types from their packages (again
-    /// through the same connection pool as `reader`).
-    package_resolver: PackageResolver,
+    /// through the same connection pool as `reader`). The resolver is replaced when its limits are
+    /// reloaded, but its cache of packages is shared between all versions of it.
+    package_resolver: Arc<ArcSwap<Resolver<SharedPackageCache>>>,
+
+    /// The package cache used by the package resolver.
+    package_cache: SharedPackageCache,
+
+    /// Limits and other configuration read on each request, which can be reloaded while the
+    /// service is running.
+    config: Arc<ArcSwap<ServiceConfig>>,
+
+    /// The identifier of the chain being served, derived from its genesis checkpoint's digest.
+    /// This never changes, so it is loaded from the database once, on first use.
+    chain_identifier: Arc<OnceCell<ChainIdentifier>>,
+
+    /// `Display` templates that have been loaded recently, shared between all requests that render
+    /// objects' `Display`s.
+    display_cache: DisplayCache,
 }
 
 impl Context {
@@ -45,50 +70,126 @@ impl Context {
     pub(crate) async fn new(
         db_args: DbArgs,
         bigtable_config: Option<BigtableConfig>,
-        limits: sui_package_resolver::Limits,
+        config: ServiceConfig,
         metrics: Arc<RpcMetrics>,
         registry: &Registry,
     ) -> Result<Self, Error> {
-        let pg_reader = PgReader::new(db_args, metrics, registry).await?;
-        let pg_loader = Arc::new(pg_reader.as_data_loader());
+        let pg_reader = PgReader::new(db_args, &config.db, metrics, registry).await?;
+        let pg_loaders: HashMap<_, _> = pg_reader
+            .databases()
+            .map(|db| (db, Arc::new(pg_reader.pinned_to(db).as_data_loader())))
+            .collect();
 
-        let kv_loader = if let Some(config) = bigtable_config {
+        let bigtable_loader = if let Some(config) = bigtable_config {
             let bigtable_reader = BigtableReader::new(config.instance_id).await?;
-            KvLoader::new_with_bigtable(Arc::new(bigtable_reader.as_data_loader()))
+            Some(KvLoader::new_with_bigtable(Arc::new(
+                bigtable_reader.as_data_loader(),
+            )))
         } else {
-            KvLoader::new_with_pg(pg_loader.clone())
+            None
         };
 
-        let store = PackageCache::new(DbPackageStore::new(pg_loader.clone()));
-        let package_resolver = Arc::new(Resolver::new_with_limits(store, limits));
+        let kv_loaders = pg_loaders
+            .iter()
+            .map(|(db, pg_loader)| {
+                let kv_loader = bigtable_loader
+                    .clone()
+                    .unwrap_or_else(|| KvLoader::new_with_pg(pg_loader.clone()));
+                (*db, kv_loader)
+            })
+            .collect();
+
+        // Packages never change once they are published, and are cached across requests, so they
+        // are always loaded from the primary, which has every package that any replica has.
+        let primary_loader = pg_loaders[&Database::Primary].clone();
+        let package_cache =
+            SharedPackageCache::new(PackageCache::new(DbPackageStore::new(primary_loader)));
+        let package_resolver =
+            Resolver::new_with_limits(package_cache.clone(), config.package_resolver.clone());
+
+        let display_cache = DisplayCache::new(config.objects.display_cache_size);
 
         Ok(Self {
             pg_reader,
-            pg_loader,
-            kv_loader,
-            package_resolver,
+            pg_loaders: Arc::new(pg_loaders),
+            kv_loaders: Arc::new(kv_loaders),
+            package_resolver: Arc::new(ArcSwap::from_pointee(package_resolver)),
+            package_cache,
+            config: Arc::new(ArcSwap::from_pointee(config)),
+            chain_identifier: Arc::new(OnceCell::new()),
+            display_cache,
         })
     }
 
+    /// Replace the configuration read on each request. Requests that are already in flight
+    /// continue to use the configuration they started with.
+    pub(crate) fn reload(&self, config: ServiceConfig) {
+        let package_resolver =
+            Resolver::new_with_limits(self.package_cache.clone(), config.package_resolver.clone());
+
+        self.package_resolver.store(Arc::new(package_resolver));
+        self.config.store(Arc::new(config));
+    }
+
     /// For performing arbitrary SQL queries on the Postgres db.
     pub(crate) fn pg_reader(&self) -> &PgReader {
         &self.pg_reader
     }
 
-    /// For performing point look-ups on the Postgres db only.
+    /// For performing point look-ups on the Postgres db only, from the same database as the rest
+    /// of the request's reads.
     pub(crate) fn pg_loader(&self) -> &Arc<DataLoader<PgReader>> {
-        &self.pg_loader
+        &self.pg_loaders[&self.pg_reader.database()]
     }
 
     /// For performing point look-ups on the kv store.
     /// Depends on the configuration of the indexer, the kv store may be backed by
     /// eitherBigtable or Postgres.
     pub(crate) fn kv_loader(&self) -> &KvLoader {
-        &self.kv_loader
+        &self.kv_loaders[&self.pg_reader.database()]
     }
 
     /// For querying type and function signature information.
-    pub(crate) fn package_resolver(&self) -> &PackageResolver {
-        &self.package_resolver
+    pub(crate) fn package_resolver(&self) -> PackageResolver {
+        self.package_resolver.load_full()
+    }
+
+    /// The current configuration, including limits on requests, and where to look up Move
+    /// Registry names.
+    pub(crate) fn config(&self) -> Arc<ServiceConfig> {
+        self.config.load_full()
+    }
+
+    /// For looking up `Display` templates that have been loaded recently.
+    pub(crate) fn display_cache(&self) -> &DisplayCache {
+        &self.display_cache
+    }
+
+    /// The identifier of the chain being served. The first call reads the genesis checkpoint's
+    /// digest from the database, and subsequent calls are served from memory. It is an error to
+    /// call this before the genesis checkpoint has been indexed.
+    pub(crate) async fn chain_identifier(&self) -> anyhow::Result<ChainIdentifier> {
+        use kv_genesis::dsl as g;
+
+        let chain_identifier = self
+            .chain_identifier
+            .get_or_try_init(|| async {
+                let genesis_digest: Vec<u8> = self
+                    .pg_reader
+                    .connect()
+                    .await
+                    .context("Failed to connect to the database")?
+                    .first(g::kv_genesis.select(g::genesis_digest))
+                    .await
+                    .context("Failed to fetch the genesis digest")?;
+
+                let digest = CheckpointDigest::try_from(genesis_digest)
+                    .context("Failed to parse genesis digest")?;
+
+                Ok::<_, anyhow::Error>(ChainIdentifier::from(digest))
+            })
+            .await?;
+
+        Ok(*chain_identifier)
     }
 }
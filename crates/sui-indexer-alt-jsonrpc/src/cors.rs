@@ -0,0 +1,127 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Origin that allows requests from any origin.
+const ANY_ORIGIN: &str = "*";
+
+/// Which cross-origin requests browsers should allow to the service (e.g. from dApps calling the
+/// RPC directly).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorsConfig {
+    /// Origins that can make requests to the service, or `*` to allow requests from any origin.
+    /// If this is empty, no CORS headers are added to responses, and browsers will not allow
+    /// cross-origin requests.
+    pub allowed_origins: Vec<String>,
+
+    /// HTTP methods that cross-origin requests can use.
+    pub allowed_methods: Vec<String>,
+
+    /// Headers that cross-origin requests can set.
+    pub allowed_headers: Vec<String>,
+
+    /// How long browsers can cache the response to a pre-flight request for.
+    pub max_age: Duration,
+}
+
+impl CorsConfig {
+    /// The layer that adds CORS headers to responses, and responds to pre-flight requests, or
+    /// `None` if CORS is disabled. Fails if any of the origins, methods, or headers are not valid.
+    pub(crate) fn layer(&self) -> anyhow::Result<Option<CorsLayer>> {
+        if self.allowed_origins.is_empty() {
+            return Ok(None);
+        }
+
+        let origins = if self.allowed_origins.iter().any(|o| o == ANY_ORIGIN) {
+            AllowOrigin::any()
+        } else {
+            AllowOrigin::list(
+                self.allowed_origins
+                    .iter()
+                    .map(|o| {
+                        HeaderValue::from_str(o).with_context(|| format!("Invalid origin {o:?}"))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+            )
+        };
+
+        let methods = self
+            .allowed_methods
+            .iter()
+            .map(|m| {
+                Method::from_bytes(m.as_bytes()).with_context(|| format!("Invalid method {m:?}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let headers = self
+            .allowed_headers
+            .iter()
+            .map(|h| {
+                HeaderName::from_bytes(h.as_bytes())
+                    .with_context(|| format!("Invalid header {h:?}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Some(
+            CorsLayer::new()
+                .allow_origin(origins)
+                .allow_methods(methods)
+                .allow_headers(headers)
+                .max_age(self.max_age),
+        ))
+    }
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec![],
+            allowed_methods: vec![Method::POST.to_string()],
+            allowed_headers: vec![http::header::CONTENT_TYPE.to_string()],
+            max_age: Duration::from_secs(3600),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cors_disabled_by_default() {
+        assert!(CorsConfig::default().layer().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cors_layer() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://example.com".to_owned()],
+            ..Default::default()
+        };
+
+        assert!(config.layer().unwrap().is_some());
+
+        let any = CorsConfig {
+            allowed_origins: vec![ANY_ORIGIN.to_owned()],
+            ..Default::default()
+        };
+
+        assert!(any.layer().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_cors_invalid() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://example.com".to_owned()],
+            allowed_headers: vec!["not a header".to_owned()],
+            ..Default::default()
+        };
+
+        assert!(config.layer().is_err());
+    }
+}
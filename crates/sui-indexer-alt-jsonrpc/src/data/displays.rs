@@ -0,0 +1,100 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_graphql::dataloader::Loader;
+use diesel::{ExpressionMethods, QueryDsl};
+use lru::LruCache;
+use move_core_types::language_storage::StructTag;
+use sui_indexer_alt_schema::{displays::StoredDisplay, schema::sum_displays};
+use sui_types::display::DisplayVersionUpdatedEvent;
+
+use super::pg_reader::PgReader;
+use crate::data::error::Error;
+
+/// Key for fetching the latest version of the `Display` for a type, by that type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct DisplayKey(pub StructTag);
+
+/// `Display` templates that have been loaded recently, keyed by the type they display, along with
+/// when they were loaded. Templates can be updated on-chain, so entries are only served for a
+/// limited time. The absence of a template is cached as well, because most types do not have one.
+#[derive(Clone)]
+pub(crate) struct DisplayCache(
+    Arc<Mutex<LruCache<StructTag, (Instant, Option<Arc<DisplayVersionUpdatedEvent>>)>>>,
+);
+
+#[async_trait::async_trait]
+impl Loader<DisplayKey> for PgReader {
+    type Value = StoredDisplay;
+    type Error = Arc<Error>;
+
+    async fn load(
+        &self,
+        keys: &[DisplayKey],
+    ) -> Result<HashMap<DisplayKey, StoredDisplay>, Self::Error> {
+        use sum_displays::dsl as d;
+
+        if keys.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let types: BTreeSet<_> = keys
+            .iter()
+            .map(|k| bcs::to_bytes(&k.0))
+            .collect::<Result<_, _>>()
+            .map_err(|e| Arc::new(Error::Serde(e.into())))?;
+
+        let mut conn = self.connect().await.map_err(Arc::new)?;
+        let displays: Vec<StoredDisplay> = conn
+            .results(d::sum_displays.filter(d::object_type.eq_any(types)))
+            .await
+            .map_err(Arc::new)?;
+
+        let type_to_stored: HashMap<_, _> = displays
+            .into_iter()
+            .map(|stored| (stored.object_type.clone(), stored))
+            .collect();
+
+        Ok(keys
+            .iter()
+            .filter_map(|key| {
+                let type_ = bcs::to_bytes(&key.0).ok()?;
+                Some((key.clone(), type_to_stored.get(&type_).cloned()?))
+            })
+            .collect())
+    }
+}
+
+impl DisplayCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self(Arc::new(Mutex::new(LruCache::new(capacity))))
+    }
+
+    /// The cached template for `type_`, if it was loaded less than `ttl` ago. The outer option is
+    /// `None` on a cache miss, and the inner option is `None` if the type has no template.
+    pub(crate) fn get(
+        &self,
+        type_: &StructTag,
+        ttl: Duration,
+    ) -> Option<Option<Arc<DisplayVersionUpdatedEvent>>> {
+        let mut cache = self.0.lock().unwrap();
+        let (loaded, display) = cache.get(type_)?;
+        (loaded.elapsed() < ttl).then(|| display.clone())
+    }
+
+    pub(crate) fn insert(
+        &self,
+        type_: StructTag,
+        display: Option<Arc<DisplayVersionUpdatedEvent>>,
+    ) {
+        self.0.lock().unwrap().put(type_, (Instant::now(), display));
+    }
+}
@@ -72,6 +72,33 @@ impl KvLoader {
         }
     }
 
+    /// Like [Self::load_one_object], but distinguishes between a version of the object that was
+    /// not found (`None`), and a version that was found, recording that the object was deleted or
+    /// wrapped at that version (`Some(None)`). Only the Postgres store records deletions and wraps.
+    pub(crate) async fn load_one_object_or_removed(
+        &self,
+        id: ObjectID,
+        version: u64,
+    ) -> Result<Option<Option<Object>>, Arc<Error>> {
+        let key = VersionedObjectKey(id, version);
+        match self {
+            Self::Bigtable(loader) => Ok(loader.load_one(key).await?.map(Some)),
+            Self::Pg(loader) => loader
+                .load_one(key)
+                .await?
+                .map(|stored| {
+                    stored
+                        .serialized_object
+                        .map(|serialized_object| {
+                            bcs::from_bytes(serialized_object.as_slice())
+                                .map_err(|e| Arc::new(Error::Serde(e.into())))
+                        })
+                        .transpose()
+                })
+                .transpose(),
+        }
+    }
+
     pub(crate) async fn load_many_objects(
         &self,
         keys: Vec<VersionedObjectKey>,
@@ -144,6 +171,27 @@ impl KvLoader {
             Self::Pg(loader) => Ok(loader.load_one(key).await?.map(TransactionContents::Pg)),
         }
     }
+
+    pub(crate) async fn load_many_transactions(
+        &self,
+        digests: Vec<TransactionDigest>,
+    ) -> Result<HashMap<TransactionDigest, TransactionContents>, Arc<Error>> {
+        let keys = digests.into_iter().map(TransactionKey);
+        match self {
+            Self::Bigtable(loader) => Ok(loader
+                .load_many(keys)
+                .await?
+                .into_iter()
+                .map(|(TransactionKey(digest), tx)| (digest, TransactionContents::Bigtable(tx)))
+                .collect()),
+            Self::Pg(loader) => Ok(loader
+                .load_many(keys)
+                .await?
+                .into_iter()
+                .map(|(TransactionKey(digest), tx)| (digest, TransactionContents::Pg(tx)))
+                .collect()),
+        }
+    }
 }
 
 impl TransactionContents {
@@ -227,6 +275,13 @@ impl TransactionContents {
         }
     }
 
+    pub(crate) fn cp_sequence_number(&self) -> u64 {
+        match self {
+            Self::Pg(stored) => stored.cp_sequence_number as u64,
+            Self::Bigtable(kv) => kv.checkpoint_number,
+        }
+    }
+
     pub(crate) fn timestamp_ms(&self) -> u64 {
         match self {
             Self::Pg(stored) => stored.timestamp_ms as u64,
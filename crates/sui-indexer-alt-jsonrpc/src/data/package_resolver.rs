@@ -3,6 +3,7 @@
 
 use std::{
     collections::{BTreeSet, HashMap},
+    ops::Deref,
     sync::Arc,
 };
 
@@ -19,9 +20,14 @@ use super::pg_reader::PgReader;
 const STORE: &str = "PostgreSQL";
 
 pub(crate) type PackageCache = PackageStoreWithLruCache<DbPackageStore>;
-pub(crate) type PackageResolver = Arc<Resolver<PackageCache>>;
+pub(crate) type PackageResolver = Arc<Resolver<SharedPackageCache>>;
 pub(crate) struct DbPackageStore(Arc<DataLoader<PgReader>>);
 
+/// A package cache that can be shared between resolvers, so that the resolver can be replaced (to
+/// change its limits) without losing the packages it has cached.
+#[derive(Clone)]
+pub(crate) struct SharedPackageCache(Arc<PackageCache>);
+
 #[derive(Copy, Clone, Hash, Eq, PartialEq, Debug)]
 struct PackageKey(AccountAddress);
 
@@ -31,6 +37,27 @@ impl DbPackageStore {
     }
 }
 
+impl SharedPackageCache {
+    pub fn new(cache: PackageCache) -> Self {
+        Self(Arc::new(cache))
+    }
+}
+
+#[async_trait::async_trait]
+impl PackageStore for SharedPackageCache {
+    async fn fetch(&self, id: AccountAddress) -> Result<Arc<Package>> {
+        self.0.fetch(id).await
+    }
+}
+
+impl Deref for SharedPackageCache {
+    type Target = PackageCache;
+
+    fn deref(&self) -> &PackageCache {
+        &self.0
+    }
+}
+
 #[async_trait::async_trait]
 impl PackageStore for DbPackageStore {
     async fn fetch(&self, id: AccountAddress) -> Result<Arc<Package>> {
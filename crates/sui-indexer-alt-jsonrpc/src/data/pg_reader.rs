@@ -1,8 +1,18 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
+use std::{
+    cell::Cell,
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
+use anyhow::Context as _;
 use async_graphql::dataloader::DataLoader;
 use diesel::deserialize::FromSqlRow;
 use diesel::expression::QueryMetadata;
@@ -10,22 +20,117 @@ use diesel::pg::Pg;
 use diesel::query_builder::{Query, QueryFragment, QueryId};
 use diesel::query_dsl::methods::LimitDsl;
 use diesel::query_dsl::CompatibleType;
+use diesel::sql_types::{BigInt, Nullable};
+use diesel::{sql_query, QueryableByName};
 use diesel_async::RunQueryDsl;
+use futures::future;
 use prometheus::Registry;
 use sui_indexer_alt_metrics::db::DbConnectionStatsCollector;
 use sui_pg_db as db;
-use tracing::debug;
+use tokio::{task::JoinHandle, time};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+use url::Url;
 
 use crate::data::error::Error;
 use crate::metrics::RpcMetrics;
+use crate::timeout;
+
+/// The timestamp of the latest checkpoint written by any of the indexer's pipelines, or NULL if
+/// nothing has been indexed yet. Replica lag is measured by comparing this between the primary and
+/// each replica, rather than by asking the replica how much of the WAL it has received, which it
+/// has no way of knowing if it has stopped receiving WAL altogether.
+const WATERMARK_QUERY: &str = "
+    SELECT
+        MAX(timestamp_ms_hi_inclusive) AS timestamp_ms
+    FROM
+        watermarks
+";
+
+/// Tuning for the reader's connection pools. Fields that are not set fall back to the values from
+/// the command-line (for the pool size and acquire timeout), or the pool's defaults. Replicas are
+/// configured with the same settings as the primary.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DbConfig {
+    /// Maximum number of connections in the pool.
+    pub pool_size: Option<u32>,
+
+    /// Minimum number of idle connections the pool tries to keep open.
+    pub min_idle: Option<u32>,
+
+    /// How long to wait for a connection from the pool before giving up.
+    pub acquire_timeout: Option<Duration>,
+
+    /// Queries that run for longer than this are cancelled by the database.
+    pub statement_timeout: Option<Duration>,
+
+    /// Connections that have been idle for longer than this are closed.
+    pub idle_timeout: Option<Duration>,
+
+    /// Read replicas of the primary database (the one passed on the command-line). Queries are
+    /// spread across replicas, and only sent to the primary if there are no replicas, or none of
+    /// them are available.
+    pub replicas: Vec<Url>,
+
+    /// Replicas that are further behind the primary than this stop receiving queries until they
+    /// catch up.
+    pub max_replica_lag: Duration,
+
+    /// How often to check how far behind the primary each replica is.
+    pub replica_lag_interval: Duration,
+}
+
+tokio::task_local! {
+    /// The database that the request being served by this task reads from. It is chosen on the
+    /// request's first read, so that all of its reads see the same state of the database, rather
+    /// than moving between replicas that have caught up with the primary to different extents.
+    static PINNED: Cell<Option<Database>>;
+}
+
+/// A database that queries can be sent to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Database {
+    Primary,
+    /// The replica at this index in the configured list of replicas.
+    Replica(usize),
+}
+
 /// This wrapper type exists to perform error conversion between the data fetching layer and the
 /// RPC layer, metrics collection, and debug logging of database queries.
 #[derive(Clone)]
 pub(crate) struct PgReader {
+    /// The primary database.
     db: db::Db,
+    replicas: Arc<[Replica]>,
+    /// The index of the replica to try first for the next choice of database.
+    next_replica: Arc<AtomicUsize>,
+    /// If set, this reader only reads from this database (or the primary, if it is a replica that
+    /// cannot be connected to), regardless of the request being served. Data loaders are shared
+    /// between requests, so they are pinned to a database this way, instead of per request.
+    pinned: Option<Database>,
+    max_replica_lag: Duration,
+    replica_lag_interval: Duration,
+    /// The statement timeout configured for the pool, which connections are reset to when they are
+    /// not being used to serve a request with a deadline.
+    statement_timeout: Option<Duration>,
     metrics: Arc<RpcMetrics>,
 }
 
+struct Replica {
+    db: db::Db,
+    /// The replica's URL, with its password redacted, to identify it in logs.
+    name: String,
+    /// Whether the replica is too far behind the primary (or otherwise unavailable) to serve
+    /// queries.
+    lagging: AtomicBool,
+}
+
+#[derive(QueryableByName)]
+struct Watermark {
+    #[diesel(sql_type = Nullable<BigInt>)]
+    timestamp_ms: Option<i64>,
+}
+
 pub(crate) struct Connection<'p> {
     conn: db::Connection<'p>,
     metrics: Arc<RpcMetrics>,
@@ -33,11 +138,55 @@ pub(crate) struct Connection<'p> {
 
 impl PgReader {
     pub(crate) async fn new(
-        db_args: db::DbArgs,
+        mut db_args: db::DbArgs,
+        config: &DbConfig,
         metrics: Arc<RpcMetrics>,
         registry: &Registry,
     ) -> Result<Self, Error> {
-        let db = db::Db::for_read(db_args).await.map_err(Error::PgCreate)?;
+        if let Some(pool_size) = config.pool_size {
+            db_args.db_connection_pool_size = pool_size;
+        }
+
+        if let Some(acquire_timeout) = config.acquire_timeout {
+            db_args.connection_timeout_ms = acquire_timeout.as_millis() as u64;
+        }
+
+        let pool_config = db::PoolConfig {
+            min_idle: config.min_idle,
+            idle_timeout: config.idle_timeout,
+            statement_timeout: config.statement_timeout,
+        };
+
+        let mut replicas = vec![];
+        for (i, url) in config.replicas.iter().enumerate() {
+            let replica_args = db::DbArgs {
+                database_url: url.clone(),
+                ..db_args.clone()
+            };
+
+            let name = redacted(url);
+            let db = db::Db::for_read_with_pool(replica_args, pool_config.clone())
+                .await
+                .with_context(|| format!("Failed to connect to replica {name}"))
+                .map_err(Error::PgCreate)?;
+
+            registry
+                .register(Box::new(DbConnectionStatsCollector::new(
+                    Some(&format!("rpc_db_replica_{i}")),
+                    db.clone(),
+                )))
+                .map_err(|e| Error::PgCreate(e.into()))?;
+
+            replicas.push(Replica {
+                db,
+                name,
+                lagging: AtomicBool::new(false),
+            });
+        }
+
+        let db = db::Db::for_read_with_pool(db_args, pool_config)
+            .await
+            .map_err(Error::PgCreate)?;
 
         registry
             .register(Box::new(DbConnectionStatsCollector::new(
@@ -46,7 +195,16 @@ impl PgReader {
             )))
             .map_err(|e| Error::PgCreate(e.into()))?;
 
-        Ok(Self { db, metrics })
+        Ok(Self {
+            db,
+            replicas: replicas.into(),
+            next_replica: Arc::new(AtomicUsize::new(0)),
+            pinned: None,
+            max_replica_lag: config.max_replica_lag,
+            replica_lag_interval: config.replica_lag_interval,
+            statement_timeout: config.statement_timeout,
+            metrics,
+        })
     }
 
     /// Create a data loader backed by this reader.
@@ -54,12 +212,254 @@ impl PgReader {
         DataLoader::new(self.clone(), tokio::spawn)
     }
 
+    /// All the databases this reader can read from: the primary, followed by each replica.
+    pub(crate) fn databases(&self) -> impl Iterator<Item = Database> {
+        std::iter::once(Database::Primary).chain((0..self.replicas.len()).map(Database::Replica))
+    }
+
+    /// A reader that shares this reader's connection pools, but only reads from `database`.
+    pub(crate) fn pinned_to(&self, database: Database) -> Self {
+        Self {
+            pinned: Some(database),
+            ..self.clone()
+        }
+    }
+
+    /// The database that the next connection is made to: the reader's own database if it is
+    /// pinned, otherwise the database pinned for the request being served (chosen now, if this is
+    /// its first read), or a fresh choice for every connection outside of requests.
+    pub(crate) fn database(&self) -> Database {
+        if let Some(database) = self.pinned {
+            return database;
+        }
+
+        PINNED
+            .try_with(|pinned| {
+                let database = pinned.get().unwrap_or_else(|| self.choose());
+                pinned.set(Some(database));
+                database
+            })
+            .unwrap_or_else(|_| self.choose())
+    }
+
+    /// Get a connection to the database that this read should go to (see [`Self::database`]).
+    /// Falls back to the primary if the connection to a replica fails, and reads for the rest of
+    /// the request go to the primary as well (it is at least as up-to-date as any replica).
+    ///
+    /// Queries on the connection are cancelled by the database if they are still running at the
+    /// deadline of the request being served (see [`timeout::REQUEST_DEADLINE`]).
     pub(crate) async fn connect(&self) -> Result<Connection<'_>, Error> {
+        let mut conn = 'conn: {
+            if let Database::Replica(i) = self.database() {
+                let replica = &self.replicas[i];
+                match replica.db.connect().await {
+                    Ok(conn) => break 'conn conn,
+                    Err(e) => {
+                        warn!(
+                            replica = replica.name.as_str(),
+                            "Failed to connect to replica: {e:#}"
+                        );
+
+                        let _ = PINNED.try_with(|pinned| pinned.set(Some(Database::Primary)));
+                    }
+                }
+            }
+
+            self.db.connect().await.map_err(Error::PgConnect)?
+        };
+
+        self.set_statement_timeout(&mut conn).await?;
         Ok(Connection {
-            conn: self.db.connect().await.map_err(Error::PgConnect)?,
+            conn,
             metrics: self.metrics.clone(),
         })
     }
+
+    /// Limit how long queries on `conn` can run for, to the time left until the current request's
+    /// deadline, or the pool's statement timeout, whichever is sooner. Connections are shared
+    /// between requests, so this is set every time one is handed out, even if there is no
+    /// deadline, to undo the limit set for a previous request.
+    async fn set_statement_timeout(&self, conn: &mut db::Connection<'_>) -> Result<(), Error> {
+        let remaining = timeout::deadline()
+            .map(|deadline| deadline.saturating_duration_since(time::Instant::now()));
+
+        let limit = match (remaining, self.statement_timeout) {
+            (Some(remaining), Some(configured)) => Some(remaining.min(configured)),
+            (remaining, configured) => remaining.or(configured),
+        };
+
+        // A timeout of zero disables the timeout, so requests past their deadline are limited to
+        // the shortest timeout instead.
+        let millis = limit.map_or(0, |limit| limit.as_millis().max(1));
+        sql_query(format!("SET statement_timeout = {millis}"))
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Start a task that regularly checks how far behind the primary each replica is, and stops
+    /// sending queries to replicas that are lagging by more than the configured maximum, until they
+    /// catch up. Returns `None` if there are no replicas to monitor.
+    ///
+    /// The task will continue to run until its cancellation token is triggered.
+    pub(crate) fn monitor_replicas(&self, cancel: CancellationToken) -> Option<JoinHandle<()>> {
+        if self.replicas.is_empty() {
+            return None;
+        }
+
+        let reader = self.clone();
+        Some(tokio::spawn(async move {
+            let mut interval = time::interval(reader.replica_lag_interval);
+
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => {
+                        info!("Shutdown signal received, terminating replica lag monitor");
+                        break;
+                    }
+
+                    _ = interval.tick() => {
+                        let primary = match watermark(&reader.db).await {
+                            Ok(primary) => primary,
+                            Err(e) => {
+                                warn!("Failed to read the primary's watermark: {e:#}");
+                                continue;
+                            }
+                        };
+
+                        future::join_all(
+                            reader.replicas.iter().map(|r| reader.check_lag(r, primary)),
+                        )
+                        .await;
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Choose a database to read from, taking turns between replicas that are keeping up with the
+    /// primary. Falls back to the primary if there are no such replicas.
+    fn choose(&self) -> Database {
+        let n = self.replicas.len();
+        if n == 0 {
+            return Database::Primary;
+        }
+
+        let start = self.next_replica.fetch_add(1, Ordering::Relaxed);
+        (0..n)
+            .map(|i| (start + i) % n)
+            .find(|i| !self.replicas[*i].lagging.load(Ordering::Relaxed))
+            .map_or(Database::Primary, Database::Replica)
+    }
+
+    /// Measure how far behind the primary `replica` is, given the primary's watermark timestamp,
+    /// and update whether it is lagging. Replicas whose lag cannot be measured, including replicas
+    /// that have not caught up with any of the primary's watermarks, are treated as lagging.
+    async fn check_lag(&self, replica: &Replica, primary: Option<i64>) {
+        let lag = async {
+            let Some(primary) = primary else {
+                // Nothing has been indexed yet, so there is nothing to fall behind on.
+                return anyhow::Ok(Duration::ZERO);
+            };
+
+            let Some(replica) = watermark(&replica.db).await? else {
+                anyhow::bail!("Replica has no watermarks");
+            };
+
+            anyhow::Ok(Duration::from_millis(
+                primary.saturating_sub(replica).max(0) as u64,
+            ))
+        }
+        .await;
+
+        let lagging = match &lag {
+            Ok(lag) => *lag > self.max_replica_lag,
+            Err(e) => {
+                warn!(
+                    replica = replica.name.as_str(),
+                    "Failed to check replica lag: {e:#}"
+                );
+                true
+            }
+        };
+
+        let was_lagging = replica.lagging.swap(lagging, Ordering::Relaxed);
+        match (was_lagging, lagging, lag) {
+            (false, true, Ok(lag)) => warn!(
+                replica = replica.name.as_str(),
+                ?lag,
+                "Replica is lagging, sending its queries elsewhere",
+            ),
+
+            (true, false, Ok(lag)) => info!(
+                replica = replica.name.as_str(),
+                ?lag,
+                "Replica has caught up, sending it queries again",
+            ),
+
+            _ => {}
+        }
+    }
+}
+
+impl Default for DbConfig {
+    fn default() -> Self {
+        Self {
+            pool_size: None,
+            min_idle: None,
+            acquire_timeout: None,
+            statement_timeout: None,
+            idle_timeout: None,
+            replicas: vec![],
+            max_replica_lag: Duration::from_secs(10),
+            replica_lag_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl fmt::Debug for DbConfig {
+    /// Passwords in replica URLs are redacted, so that they do not end up in logs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let replicas: Vec<_> = self.replicas.iter().map(redacted).collect();
+        f.debug_struct("DbConfig")
+            .field("pool_size", &self.pool_size)
+            .field("min_idle", &self.min_idle)
+            .field("acquire_timeout", &self.acquire_timeout)
+            .field("statement_timeout", &self.statement_timeout)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("replicas", &replicas)
+            .field("max_replica_lag", &self.max_replica_lag)
+            .field("replica_lag_interval", &self.replica_lag_interval)
+            .finish()
+    }
+}
+
+/// Serve `request` with all of its reads pinned to the same database (see [`PgReader::database`]).
+pub(crate) async fn pinned<F: Future>(request: F) -> F::Output {
+    PINNED.scope(Cell::new(None), request).await
+}
+
+/// `url` with its password (if it has one) replaced.
+/// The timestamp of the latest checkpoint indexed in `db` (see [`WATERMARK_QUERY`]).
+async fn watermark(db: &db::Db) -> anyhow::Result<Option<i64>> {
+    let mut conn = db.connect().await?;
+    let Watermark { timestamp_ms } = sql_query(WATERMARK_QUERY)
+        .get_result::<Watermark>(&mut conn)
+        .await
+        .context("Failed to query watermark")?;
+
+    Ok(timestamp_ms)
+}
+
+/// `url`, with its password (if any) redacted.
+pub(crate) fn redacted(url: &Url) -> String {
+    let mut url = url.clone();
+    if url.password().is_some() {
+        let _ = url.set_password(Some("********"));
+    }
+
+    url.to_string()
 }
 
 impl<'p> Connection<'p> {
@@ -109,3 +509,288 @@ impl<'p> Connection<'p> {
         Ok(res?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use diesel::sql_types::Text;
+    use diesel_async::{AsyncConnection, AsyncPgConnection};
+    use sui_pg_db::temp::TempDb;
+
+    use super::*;
+
+    #[derive(QueryableByName)]
+    struct Name {
+        #[diesel(sql_type = Text)]
+        name: String,
+    }
+
+    async fn reader(db: &TempDb, config: DbConfig) -> PgReader {
+        let registry = Registry::new();
+        let db_args = db::DbArgs {
+            database_url: db.database().url().clone(),
+            connection_timeout_ms: 1000,
+            ..Default::default()
+        };
+
+        PgReader::new(db_args, &config, RpcMetrics::new(&registry), &registry)
+            .await
+            .unwrap()
+    }
+
+    /// Run `sql` against the database at `url`.
+    async fn execute(url: &Url, sql: &str) {
+        let mut conn = AsyncPgConnection::establish(url.as_str()).await.unwrap();
+        sql_query(sql).execute(&mut conn).await.unwrap();
+    }
+
+    /// Label the database at `url` with `name`, so that tests can tell which database a
+    /// connection is to.
+    async fn label(url: &Url, name: &str) {
+        execute(
+            url,
+            &format!("CREATE TABLE name AS SELECT '{name}'::TEXT AS name"),
+        )
+        .await;
+    }
+
+    /// Set the latest watermark timestamp in the database at `url`, creating a stand-in for the
+    /// indexer's watermarks table if it does not exist yet.
+    async fn set_watermark(url: &Url, timestamp_ms: i64) {
+        execute(
+            url,
+            "CREATE TABLE IF NOT EXISTS watermarks (
+                pipeline TEXT PRIMARY KEY,
+                timestamp_ms_hi_inclusive BIGINT NOT NULL
+            )",
+        )
+        .await;
+
+        execute(
+            url,
+            &format!(
+                "INSERT INTO watermarks VALUES ('pipeline', {timestamp_ms})
+                 ON CONFLICT (pipeline) DO UPDATE
+                 SET timestamp_ms_hi_inclusive = EXCLUDED.timestamp_ms_hi_inclusive"
+            ),
+        )
+        .await;
+    }
+
+    /// Create a database called `name` on the same server as `db`, labelled with its name, to
+    /// stand in for a replica.
+    async fn replica(db: &TempDb, name: &str) -> Url {
+        let primary = db.database().url();
+        execute(primary, &format!("CREATE DATABASE {name}")).await;
+
+        let mut url = primary.clone();
+        url.set_path(name);
+        label(&url, name).await;
+        url
+    }
+
+    /// Check the lag of `reader`'s first replica against its primary, and report whether it is
+    /// lagging.
+    async fn is_lagging(reader: &PgReader) -> bool {
+        let primary = watermark(&reader.db).await.unwrap();
+        reader.check_lag(&reader.replicas[0], primary).await;
+        reader.replicas[0].lagging.load(Ordering::Relaxed)
+    }
+
+    /// Remove the replica called `name`, disconnecting any connections to it.
+    async fn drop_replica(db: &TempDb, name: &str) {
+        let primary = db.database().url();
+        execute(primary, &format!("DROP DATABASE {name} WITH (FORCE)")).await;
+    }
+
+    /// The label of the database that `reader` connects to next.
+    async fn name(reader: &PgReader) -> String {
+        let mut conn = reader.connect().await.unwrap();
+        let Name { name } = sql_query("SELECT name FROM name")
+            .get_result(&mut conn.conn)
+            .await
+            .unwrap();
+        name
+    }
+
+    async fn sleep(conn: &mut Connection<'_>, secs: f64) -> Result<(), diesel::result::Error> {
+        sql_query(format!("SELECT pg_sleep({secs})"))
+            .execute(&mut conn.conn)
+            .await
+            .map(|_| ())
+    }
+
+    #[tokio::test]
+    async fn test_request_deadline_cancels_queries() {
+        let db = TempDb::new().unwrap();
+        let reader = reader(&db, DbConfig::default()).await;
+
+        let deadline = time::Instant::now() + Duration::from_millis(200);
+        let res = timeout::REQUEST_DEADLINE
+            .scope(deadline, async {
+                let mut conn = reader.connect().await.unwrap();
+                sleep(&mut conn, 2.0).await
+            })
+            .await;
+
+        let err = res.unwrap_err().to_string();
+        assert!(err.contains("statement timeout"), "{err}");
+
+        // Outside of the request, the connection is no longer limited by its deadline.
+        let mut conn = reader.connect().await.unwrap();
+        sleep(&mut conn, 0.5).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_configured_statement_timeout() {
+        let db = TempDb::new().unwrap();
+        let config = DbConfig {
+            statement_timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+
+        let reader = reader(&db, config).await;
+
+        // The configured timeout applies outside of requests...
+        let mut conn = reader.connect().await.unwrap();
+        assert!(sleep(&mut conn, 2.0).await.is_err());
+        drop(conn);
+
+        // ...and caps requests with later deadlines.
+        let deadline = time::Instant::now() + Duration::from_secs(10);
+        let res = timeout::REQUEST_DEADLINE
+            .scope(deadline, async {
+                let mut conn = reader.connect().await.unwrap();
+                sleep(&mut conn, 2.0).await
+            })
+            .await;
+
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_requests_pinned_to_replica() {
+        let db = TempDb::new().unwrap();
+        label(db.database().url(), "primary").await;
+        let config = DbConfig {
+            replicas: vec![replica(&db, "r1").await, replica(&db, "r2").await],
+            ..Default::default()
+        };
+
+        let reader = reader(&db, config).await;
+
+        // Outside of a request, reads take turns between replicas.
+        let first = name(&reader).await;
+        let second = name(&reader).await;
+        assert_ne!(first, second);
+        assert_ne!(first, "primary");
+        assert_ne!(second, "primary");
+
+        // Within a request, every read goes to the same replica.
+        let names = pinned(async {
+            let mut names = vec![];
+            for _ in 0..4 {
+                names.push(name(&reader).await);
+            }
+            names
+        })
+        .await;
+
+        assert_ne!(names[0], "primary");
+        assert!(names.iter().all(|n| *n == names[0]), "{names:?}");
+
+        // A reader pinned to a replica always reads from it, inside or outside of a request.
+        let r2 = reader.pinned_to(Database::Replica(1));
+        assert_eq!(name(&r2).await, "r2");
+        assert_eq!(name(&r2).await, "r2");
+        assert_eq!(pinned(name(&r2)).await, "r2");
+    }
+
+    #[tokio::test]
+    async fn test_lagging_replica() {
+        let db = TempDb::new().unwrap();
+        label(db.database().url(), "primary").await;
+        let r1_url = replica(&db, "r1").await;
+        let config = DbConfig {
+            replicas: vec![r1_url.clone()],
+            ..Default::default()
+        };
+
+        let reader = reader(&db, config).await;
+        let primary = db.database().url();
+        let r1 = &reader.replicas[0];
+
+        // A replica that is keeping up is not marked as lagging, and is read from.
+        set_watermark(primary, 100_000).await;
+        set_watermark(&r1_url, 100_000).await;
+        assert!(!is_lagging(&reader).await);
+        assert_eq!(name(&reader).await, "r1");
+
+        // A replica whose watermark stops moving is lagging once the primary gets far enough ahead
+        // of it, even though the replica itself has no way of knowing that it is behind.
+        set_watermark(primary, 105_000).await;
+        assert!(!is_lagging(&reader).await);
+        set_watermark(primary, 120_000).await;
+        assert!(is_lagging(&reader).await);
+        assert_eq!(name(&reader).await, "primary");
+
+        // ...until it catches up again.
+        set_watermark(&r1_url, 119_000).await;
+        assert!(!is_lagging(&reader).await);
+        assert_eq!(name(&reader).await, "r1");
+
+        // A replica whose lag cannot be measured is marked as lagging, and reads go to the primary
+        // instead.
+        drop_replica(&db, "r1").await;
+        assert!(is_lagging(&reader).await);
+        assert!(r1.lagging.load(Ordering::Relaxed));
+        assert_eq!(reader.database(), Database::Primary);
+        assert_eq!(name(&reader).await, "primary");
+        assert_eq!(pinned(name(&reader)).await, "primary");
+    }
+
+    #[tokio::test]
+    async fn test_replica_without_watermarks() {
+        let db = TempDb::new().unwrap();
+        label(db.database().url(), "primary").await;
+        let config = DbConfig {
+            replicas: vec![replica(&db, "r1").await],
+            ..Default::default()
+        };
+
+        let reader = reader(&db, config).await;
+
+        // Nothing has been indexed on the primary, so the replica can't be behind it.
+        set_watermark(db.database().url(), 0).await;
+        execute(db.database().url(), "DELETE FROM watermarks").await;
+        assert!(!is_lagging(&reader).await);
+
+        // The replica has not seen any of the primary's watermarks.
+        set_watermark(db.database().url(), 100_000).await;
+        assert!(is_lagging(&reader).await);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_to_primary() {
+        let db = TempDb::new().unwrap();
+        label(db.database().url(), "primary").await;
+        let config = DbConfig {
+            replicas: vec![replica(&db, "r1").await],
+            ..Default::default()
+        };
+
+        let reader = reader(&db, config).await;
+
+        pinned(async {
+            assert_eq!(name(&reader).await, "r1");
+            assert_eq!(reader.database(), Database::Replica(0));
+
+            // The replica disappears part way through the request, so the read falls back to the
+            // primary, and the rest of the request's reads stay there.
+            drop_replica(&db, "r1").await;
+            assert_eq!(name(&reader).await, "primary");
+            assert_eq!(reader.database(), Database::Primary);
+            assert_eq!(name(&reader).await, "primary");
+        })
+        .await;
+    }
+}
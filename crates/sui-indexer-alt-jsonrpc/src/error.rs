@@ -9,6 +9,21 @@ use jsonrpsee::types::{
     ErrorObject,
 };
 
+/// Error code for requests that were not served within their method's deadline (see
+/// `MethodTimeoutsConfig`).
+pub(crate) const REQUEST_TIMEOUT_CODE: i32 = -32060;
+
+/// Error code for requests to methods that the operator has disabled (see `MethodsConfig`).
+pub(crate) const METHOD_DISABLED_CODE: i32 = -32061;
+
+/// Error code for requests that were rejected because the client, or the service as a whole, has
+/// exceeded its rate limit (see `RateLimitConfig`).
+pub(crate) const RATE_LIMITED_CODE: i32 = -32029;
+
+/// Error code for requests that were rejected because they did not carry a valid API key (see
+/// `AuthConfig`).
+pub(crate) const UNAUTHORIZED_CODE: i32 = -32062;
+
 /// Like anyhow's `bail!`, but for returning an internal error.
 macro_rules! rpc_bail {
     ($($arg:tt)*) => {
@@ -0,0 +1,349 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Entrypoints for fuzzing the RPC's request parsing layer: pagination cursors and limits, query
+//! filters, and type strings (which may refer to packages by their Move Registry names).
+//!
+//! Each entrypoint accepts arbitrary bytes and must reject malformed inputs by returning an error,
+//! rather than panicking. Entrypoints also check the invariants that later stages of request
+//! handling rely on (e.g. that page sizes are bounded), and panic if they are violated, so that
+//! the fuzzer reports them. None of them need a database, so they can be run in-process.
+//!
+//! The fuzz targets that call these entrypoints, and a corpus builder that seeds them with
+//! [`corpus`], live in the `fuzz` directory at the root of this crate.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail};
+use fastcrypto::encoding::{Base64, Encoding};
+use sui_types::base_types::ObjectID;
+
+use crate::{
+    api::{
+        coin::{self, CoinsConfig},
+        objects::{filter as objects, ObjectsConfig},
+        transactions::{filter as transactions, TransactionsConfig},
+    },
+    move_registry,
+    paginate::{self, Cursor, Page},
+};
+
+/// Size of the fixed-size header at the start of a [`page`] input.
+const PAGE_HEADER_SIZE: usize = 26;
+
+/// Flags in the second byte of a [`page`] input's header.
+const HAS_LIMIT: u8 = 1 << 0;
+const HAS_ORDER: u8 = 1 << 1;
+const DESCENDING: u8 = 1 << 2;
+const HAS_CURSOR: u8 = 1 << 3;
+
+/// The paginated APIs whose parameters are fuzzed by [`page`], each with its own cursor format.
+#[derive(Clone, Copy, Debug)]
+pub enum PageKind {
+    /// `suix_getOwnedObjects`, with a BCS cursor.
+    Objects = 0,
+    /// `suix_getCoins` and `suix_getAllCoins`, with a BCS cursor.
+    Coins = 1,
+    /// `suix_queryTransactionBlocks`, with a JSON cursor.
+    Transactions = 2,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error(transparent)]
+struct PageError(#[from] paginate::Error);
+
+/// Fuzz the pagination parameters (cursor, limit, and ordering) of paginated RPC methods.
+///
+/// Inputs start with a fixed-size header, followed by the cursor:
+///
+/// - byte 0: the [`PageKind`] to interpret the parameters as (modulo the number of kinds).
+/// - byte 1: flags, controlling which of the optional parameters are supplied.
+/// - bytes 2..10: the limit, as a little-endian `u64`.
+/// - bytes 10..26: transaction sequence number bounds of a checkpoint, as little-endian `u64`s,
+///   for paginating transactions within a checkpoint.
+/// - the rest: the cursor, which must be UTF-8, as it is passed to the RPC as a string.
+pub fn page(data: &[u8]) -> anyhow::Result<()> {
+    let Some((header, cursor)) = data.split_at_checked(PAGE_HEADER_SIZE) else {
+        bail!("Input is shorter than the page header");
+    };
+
+    let flags = header[1];
+    let limit = (flags & HAS_LIMIT != 0).then(|| read_u64(&header[2..10]) as usize);
+    let descending = (flags & HAS_ORDER != 0).then_some(flags & DESCENDING != 0);
+    let cursor = if flags & HAS_CURSOR != 0 {
+        Some(std::str::from_utf8(cursor)?.to_owned())
+    } else {
+        None
+    };
+
+    match header[0] % 3 {
+        0 => {
+            let config = ObjectsConfig::default();
+            check_page::<objects::Cursor>(
+                config.default_page_size,
+                config.max_page_size,
+                cursor,
+                limit,
+                descending,
+            )?;
+        }
+
+        1 => {
+            let config = CoinsConfig::default();
+            check_page::<coin::Cursor>(
+                config.default_page_size,
+                config.max_page_size,
+                cursor,
+                limit,
+                descending,
+            )?;
+        }
+
+        _ => {
+            let config = TransactionsConfig::default();
+            let page = check_page::<transactions::Cursor>(
+                config.default_page_size,
+                config.max_page_size,
+                cursor,
+                limit,
+                descending,
+            )?;
+
+            let (lo, hi) = (read_u64(&header[10..18]), read_u64(&header[18..26]));
+            let (cp_lo, cp_hi) = (lo.min(hi), lo.max(hi));
+            let range = transactions::checkpoint_range(&page, cp_lo, cp_hi);
+
+            assert!(
+                range.is_empty() || (cp_lo <= range.start && range.end <= cp_hi),
+                "Range {range:?} is outside checkpoint {cp_lo}..{cp_hi}",
+            );
+
+            assert!(
+                range.end.saturating_sub(range.start) <= page.limit as u64 + 1,
+                "Range {range:?} exceeds page limit {}",
+                page.limit,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Fuzz the query for `suix_getOwnedObjects`, as JSON. Filters on struct types (including those
+/// nested in compound filters) are also fuzzed as [`type_string`]s.
+pub fn object_query(data: &[u8]) -> anyhow::Result<()> {
+    let query: objects::SuiObjectResponseQuery = serde_json::from_slice(data)?;
+    if let Some(filter) = &query.filter {
+        object_filter(filter)?;
+    }
+
+    Ok(())
+}
+
+/// Fuzz the query for `suix_queryTransactionBlocks`, as JSON.
+pub fn transaction_query(data: &[u8]) -> anyhow::Result<()> {
+    let _: transactions::SuiTransactionBlockResponseQuery = serde_json::from_slice(data)?;
+    Ok(())
+}
+
+/// Fuzz type strings, as accepted by methods that filter by type, which may refer to packages by
+/// their Move Registry names. Names are all resolved to the same placeholder address.
+pub fn type_string(data: &[u8]) -> anyhow::Result<()> {
+    let type_ = std::str::from_utf8(data)?;
+    let names = move_registry::parse_names(type_)?;
+
+    let resolved: HashMap<_, _> = names
+        .into_iter()
+        .map(|(name, _)| (name, ObjectID::ZERO))
+        .collect();
+
+    let replaced = move_registry::replace_names(type_, &resolved)
+        .expect("Failed to replace names that were found when parsing");
+
+    sui_types::parse_sui_type_tag(&replaced).expect("Type with resolved names failed to parse");
+    Ok(())
+}
+
+/// Valid inputs for each fuzz target, paired with the name of the target. Seeding the fuzzer with
+/// these inputs helps it get past the outer layers of each format (Base64, JSON, etc) quickly.
+pub fn corpus() -> Vec<(&'static str, Vec<u8>)> {
+    let mut corpus = vec![];
+
+    let object_cursor = Base64::encode(bcs::to_bytes(&(vec![0x42u8; 32], 1000u64)).unwrap());
+    let coin_cursor = Base64::encode(bcs::to_bytes(&(vec![0x42u8; 32], 1000u64, 3u64)).unwrap());
+    let tx_cursor = Base64::encode(serde_json::to_vec(&1000u64).unwrap());
+
+    for (kind, cursor) in [
+        (PageKind::Objects, object_cursor),
+        (PageKind::Coins, coin_cursor),
+        (PageKind::Transactions, tx_cursor),
+    ] {
+        corpus.push(("page", page_input(kind, None, None, None)));
+        corpus.push((
+            "page",
+            page_input(kind, Some(10), Some(true), Some(&cursor)),
+        ));
+        corpus.push((
+            "page",
+            page_input(kind, Some(50), Some(false), Some(&cursor)),
+        ));
+    }
+
+    for query in [
+        r#"{}"#,
+        r#"{"filter":{"Package":"0x2"}}"#,
+        r#"{"filter":{"MoveModule":{"package":"0x2","module":"coin"}}}"#,
+        r#"{"filter":{"StructType":"0x2::coin::Coin<0x2::sui::SUI>"},"options":{"showType":true}}"#,
+        r#"{"filter":{"StructType":"@mysten/sui::coin::Coin<@mysten/sui/1::sui::SUI>"}}"#,
+        r#"{"filter":{"MatchAny":[{"Package":"0x2"},{"MatchAll":[{"StructType":"0x3::s::S"}]}]}}"#,
+    ] {
+        corpus.push(("object_query", query.as_bytes().to_vec()));
+    }
+
+    for query in [
+        r#"{}"#,
+        r#"{"filter":{"Checkpoint":"1000"}}"#,
+        r#"{"filter":{"MoveFunction":{"package":"0x2","module":"coin","function":"join"}}}"#,
+        r#"{"filter":{"AffectedObject":"0x5"},"options":{"showEffects":true}}"#,
+        r#"{"filter":{"FromAddress":"0x42"}}"#,
+        r#"{"filter":{"FromAndToAddress":{"from":"0x42","to":"0x43"}}}"#,
+        r#"{"filter":{"FromOrToAddress":{"addr":"0x42"}}}"#,
+    ] {
+        corpus.push(("transaction_query", query.as_bytes().to_vec()));
+    }
+
+    for type_ in [
+        "u64",
+        "vector<address>",
+        "0x2::coin::Coin<0x2::sui::SUI>",
+        "0x2::table::Table<address, vector<0x1::string::String>>",
+        "@mysten/sui::coin::Coin<@mysten/sui/2::sui::SUI>",
+    ] {
+        corpus.push(("type_string", type_.as_bytes().to_vec()));
+    }
+
+    corpus
+}
+
+/// Interpret the pagination parameters as a page of cursors of type `C`, and check that the page
+/// is within its size limits, and its cursor survives being re-encoded.
+fn check_page<C: Cursor>(
+    default_page_size: usize,
+    max_page_size: usize,
+    cursor: Option<String>,
+    limit: Option<usize>,
+    descending: Option<bool>,
+) -> anyhow::Result<Page<C>> {
+    let page: Page<C> =
+        Page::from_params::<PageError>(default_page_size, max_page_size, cursor, limit, descending)
+            .map_err(|e| anyhow!("{e}"))?;
+
+    assert!(
+        0 <= page.limit && page.limit <= max_page_size as i64,
+        "Page limit {} is outside 0..={max_page_size}",
+        page.limit,
+    );
+
+    if let Some(cursor) = &page.cursor {
+        let encoded = cursor.encode().expect("Failed to re-encode decoded cursor");
+        let decoded = C::decode(&encoded).expect("Failed to decode re-encoded cursor");
+        let reencoded = decoded.encode().expect("Failed to re-encode cursor");
+        assert_eq!(encoded, reencoded, "Cursor does not round-trip");
+    }
+
+    Ok(page)
+}
+
+fn object_filter(filter: &objects::SuiObjectDataFilter) -> anyhow::Result<()> {
+    use objects::SuiObjectDataFilter as F;
+    match filter {
+        F::StructType(type_) => type_string(type_.as_bytes())?,
+        F::MatchAll(filters) | F::MatchAny(filters) => {
+            let nodes = 1 + filters.iter().map(F::nodes).sum::<usize>();
+            assert_eq!(filter.nodes(), nodes, "Filter node count is inconsistent");
+            for filter in filters {
+                object_filter(filter)?;
+            }
+        }
+        F::Package(_) | F::MoveModule { .. } => {}
+    }
+
+    Ok(())
+}
+
+/// Build an input for the [`page`] entrypoint, from its pagination parameters.
+fn page_input(
+    kind: PageKind,
+    limit: Option<u64>,
+    descending: Option<bool>,
+    cursor: Option<&str>,
+) -> Vec<u8> {
+    let mut flags = 0;
+    if limit.is_some() {
+        flags |= HAS_LIMIT;
+    }
+
+    if let Some(descending) = descending {
+        flags |= HAS_ORDER;
+        if descending {
+            flags |= DESCENDING;
+        }
+    }
+
+    if cursor.is_some() {
+        flags |= HAS_CURSOR;
+    }
+
+    let mut input = vec![kind as u8, flags];
+    input.extend(limit.unwrap_or_default().to_le_bytes());
+    input.extend(990u64.to_le_bytes());
+    input.extend(1010u64.to_le_bytes());
+    input.extend(cursor.unwrap_or_default().as_bytes());
+    input
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    u64::from_le_bytes(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corpus_is_valid() {
+        for (target, input) in corpus() {
+            let result = match target {
+                "page" => page(&input),
+                "object_query" => object_query(&input),
+                "transaction_query" => transaction_query(&input),
+                "type_string" => type_string(&input),
+                _ => panic!("Unknown target {target}"),
+            };
+
+            assert!(result.is_ok(), "{target} rejected {input:?}: {result:?}");
+        }
+    }
+
+    #[test]
+    fn test_malformed_inputs() {
+        let cursor = "not base64!";
+        let too_large = page_input(PageKind::Transactions, Some(u64::MAX), None, None);
+        let bad_cursor = page_input(PageKind::Objects, None, None, Some(cursor));
+        let wrong_cursor = page_input(PageKind::Coins, None, None, Some(&Base64::encode(b"1000")));
+
+        assert!(page(&[]).is_err());
+        assert!(page(&too_large).is_err());
+        assert!(page(&bad_cursor).is_err());
+        assert!(page(&wrong_cursor).is_err());
+
+        assert!(object_query(br#"{"filter":{"StructType":"0x2::coin"}}"#).is_err());
+        assert!(object_query(br#"{"filter":{"MoveModule":{"package":"0x2"}}}"#).is_err());
+        assert!(transaction_query(br#"{"filter":{"Checkpoint":-1}}"#).is_err());
+
+        assert!(type_string(b"0x2::coin::Coin<").is_err());
+        assert!(type_string(&[0xff, 0xfe]).is_err());
+    }
+}
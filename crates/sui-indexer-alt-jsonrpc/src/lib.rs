@@ -2,43 +2,82 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Context as _;
+use api::chain::Chain;
 use api::checkpoints::Checkpoints;
-use api::coin::{Coins, CoinsConfig};
+use api::coin::{CoinMetadataCache, Coins};
 use api::dynamic_fields::DynamicFields;
-use api::move_utils::MoveUtils;
+use api::events::{Events, QueryEvents};
+use api::feed::Feed;
+use api::move_utils::{MoveUtils, NormalizedModulesCache};
 use api::name_service::NameService;
-use api::objects::{Objects, ObjectsConfig, QueryObjects};
+use api::objects::{Objects, QueryObjects};
+use api::protocol_config::ProtocolConfigs;
 use api::rpc_module::RpcModule;
-use api::transactions::{QueryTransactions, Transactions, TransactionsConfig};
+use api::transactions::{QueryTransactions, TransactionSubscriptions, Transactions};
+use api::webhooks::{delivery::WebhookDelivery, store::WebhookStore, Webhooks};
+use api::write::{Gas, Write};
+use auth::Authenticator;
+use compression::CompressionConfig;
 use config::RpcConfig;
+use config_watcher::ConfigWatcher;
 use data::system_package_task::{SystemPackageTask, SystemPackageTaskArgs};
-use jsonrpsee::server::{BatchRequestConfig, RpcServiceBuilder, ServerBuilder};
+use jsonrpsee::server::{
+    serve_with_graceful_shutdown, stop_channel, BatchRequestConfig, Methods, RpcServiceBuilder,
+    ServerBuilder,
+};
+use method_filter::MethodFilterLayer;
 use metrics::middleware::MetricsLayer;
 use metrics::RpcMetrics;
+use pinning::PinningLayer;
 use prometheus::Registry;
+use rate_limit::{PeerAddr, RateLimitConfig, RateLimiter};
+use response_size::ResponseSizeLayer;
 use serde_json::json;
-use sui_name_service::NameServiceConfig;
+use stream::StreamPublisher;
+use subscription::{SubscriptionManager, SubscriptionsConfig};
 use sui_open_rpc::Project;
 use sui_pg_db::DbArgs;
-use tokio::{join, signal, task::JoinHandle};
+use timeout::TimeoutLayer;
+use tls::Handshaker;
+use tokio::{join, net::TcpListener, signal, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
+use tower_http::add_extension::AddExtension;
+use tower_http::compression::{predicate::SizeAbove, CompressionLayer};
 use tower_layer::Identity;
-use tracing::info;
+use tracing::{debug, info, warn};
 
-use crate::api::governance::Governance;
+use crate::api::governance::{Governance, RgpCache, SystemStateCache};
 use crate::context::Context;
 
 mod api;
 pub mod args;
+mod auth;
+mod compression;
 pub mod config;
+mod config_watcher;
 mod context;
+mod cors;
 pub mod data;
 mod error;
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
+mod method_filter;
 mod metrics;
+mod move_registry;
 mod paginate;
+mod pinning;
+mod rate_limit;
+mod response_size;
+mod stream;
+mod subscription;
+mod timeout;
+mod tls;
+pub mod validate;
 
 #[derive(clap::Args, Debug, Clone)]
 pub struct RpcArgs {
@@ -50,6 +89,16 @@ pub struct RpcArgs {
     /// many requests, it will start responding with 429.
     #[clap(long, default_value_t = Self::default().max_in_flight_requests)]
     pub max_in_flight_requests: u32,
+
+    /// The maximum size of a request body, in bytes. Larger requests are rejected before they are
+    /// parsed.
+    #[clap(long, default_value_t = Self::default().max_request_body_bytes)]
+    pub max_request_body_bytes: u32,
+
+    /// The maximum size of a response, in bytes. Requests whose responses would be larger fail
+    /// with an error asking the client to request less data (e.g. by reducing the page size).
+    #[clap(long, default_value_t = Self::default().max_response_bytes)]
+    pub max_response_bytes: u32,
 }
 
 pub struct RpcService {
@@ -62,6 +111,36 @@ pub struct RpcService {
     /// Metrics for the RPC service.
     metrics: Arc<RpcMetrics>,
 
+    /// Limits on the rate of requests served.
+    rate_limiter: RateLimiter,
+
+    /// Checks the API keys that requests are made with.
+    authenticator: Authenticator,
+
+    /// If set, adds CORS headers to responses, and responds to pre-flight requests.
+    cors: Option<tower_http::cors::CorsLayer>,
+
+    /// Compresses responses, for clients that accept them compressed.
+    compression: CompressionLayer<SizeAbove>,
+
+    /// If set, the service serves HTTPS with this configuration, instead of plaintext HTTP.
+    tls: Option<Arc<rustls::ServerConfig>>,
+
+    /// Which methods requests are served for.
+    method_filter: MethodFilterLayer,
+
+    /// Deadlines for serving requests to each method.
+    timeouts: TimeoutLayer,
+
+    /// Explains how to avoid errors from responses that are too large.
+    response_size: ResponseSizeLayer,
+
+    /// Limits on subscriptions, applied to each WebSocket connection.
+    subscriptions_config: SubscriptionsConfig,
+
+    /// Forwards notifications to subscribers, for subscription methods.
+    subscriptions: SubscriptionManager,
+
     /// All the methods added to the server so far.
     modules: jsonrpsee::RpcModule<()>,
 
@@ -83,16 +162,20 @@ impl RpcService {
         let RpcArgs {
             rpc_listen_address,
             max_in_flight_requests,
+            max_request_body_bytes,
+            max_response_bytes,
         } = rpc_args;
 
         let metrics = RpcMetrics::new(registry);
 
+        // Requests are served over HTTP, and over WebSocket connections (on the same port), which
+        // clients need to use to open subscriptions.
         let server = ServerBuilder::new()
-            .http_only()
             // `jsonrpsee` calls this a limit on connections, but it is implemented as a limit on
-            // requests.
+            // HTTP requests, and WebSocket connections.
             .max_connections(max_in_flight_requests)
-            .max_response_body_size(u32::MAX)
+            .max_request_body_size(max_request_body_bytes)
+            .max_response_body_size(max_response_bytes)
             .set_batch_request_config(BatchRequestConfig::Disabled);
 
         let schema = Project::new(
@@ -106,10 +189,30 @@ impl RpcService {
             "https://raw.githubusercontent.com/MystenLabs/sui/main/LICENSE",
         );
 
+        let rate_limiter = RateLimiter::new(RateLimitConfig::default, metrics.clone());
+
+        let subscriptions_config = SubscriptionsConfig::default();
+        let send_timeout = subscriptions_config.send_timeout;
+        let subscriptions = SubscriptionManager::new(
+            subscriptions_config.max_subscriptions,
+            move || send_timeout,
+            metrics.clone(),
+        );
+
         Ok(Self {
             rpc_listen_address,
             server,
             metrics,
+            rate_limiter,
+            authenticator: Authenticator::default(),
+            cors: None,
+            compression: CompressionConfig::default().layer(),
+            tls: None,
+            method_filter: MethodFilterLayer::default(),
+            timeouts: TimeoutLayer::default(),
+            response_size: ResponseSizeLayer::new(max_response_bytes),
+            subscriptions_config,
+            subscriptions,
             modules: jsonrpsee::RpcModule::new(()),
             schema,
             cancel,
@@ -121,6 +224,64 @@ impl RpcService {
         self.metrics.clone()
     }
 
+    /// Reject requests that exceed the limits enforced by `rate_limiter`, with an HTTP 429
+    /// response. By default, requests are not rate limited.
+    pub(crate) fn set_rate_limiter(&mut self, rate_limiter: RateLimiter) {
+        self.rate_limiter = rate_limiter;
+    }
+
+    /// Reject requests that `authenticator` does not accept, with an HTTP 401 response. By
+    /// default, requests do not need to be authenticated.
+    pub(crate) fn set_authenticator(&mut self, authenticator: Authenticator) {
+        self.authenticator = authenticator;
+    }
+
+    /// Allow browsers to make cross-origin requests to the service, according to `cors`.
+    pub(crate) fn set_cors(&mut self, cors: tower_http::cors::CorsLayer) {
+        self.cors = Some(cors);
+    }
+
+    /// Compress responses with `compression`. By default, responses are compressed with gzip or
+    /// zstd, if the client accepts them, and they are large enough.
+    pub(crate) fn set_compression(&mut self, compression: CompressionLayer<SizeAbove>) {
+        self.compression = compression;
+    }
+
+    /// Serve HTTPS, terminating TLS with `tls`, instead of plaintext HTTP.
+    pub(crate) fn set_tls(&mut self, tls: rustls::ServerConfig) {
+        self.tls = Some(Arc::new(tls));
+    }
+
+    /// Reject requests to methods that `method_filter` reports as disabled, with an error saying
+    /// that they have been disabled by the operator. By default, all methods are enabled.
+    pub(crate) fn set_method_filter(&mut self, method_filter: MethodFilterLayer) {
+        self.method_filter = method_filter;
+    }
+
+    /// Enforce deadlines on requests, looking up the deadline for each request's method with
+    /// `timeouts`. By default, requests have no deadline.
+    pub(crate) fn set_timeouts(&mut self, timeouts: TimeoutLayer) {
+        self.timeouts = timeouts;
+    }
+
+    /// Limit subscriptions according to `config`, reading the send timeout for each new
+    /// subscription from `send_timeout`. The other limits are fixed once set.
+    pub(crate) fn set_subscriptions(
+        &mut self,
+        config: SubscriptionsConfig,
+        send_timeout: impl Fn() -> Duration + Send + Sync + 'static,
+    ) {
+        self.subscriptions =
+            SubscriptionManager::new(config.max_subscriptions, send_timeout, self.metrics());
+        self.subscriptions_config = config;
+    }
+
+    /// Forwards notifications to subscribers, for use by modules that offer subscriptions. Any
+    /// limits on subscriptions must be set before this is called.
+    pub fn subscriptions(&self) -> SubscriptionManager {
+        self.subscriptions.clone()
+    }
+
     /// Add an `RpcModule` to the service. The module's methods are combined with the existing
     /// methods registered on the service, and the operation will fail if there is any overlap.
     pub fn add_module(&mut self, module: impl RpcModule) -> anyhow::Result<()> {
@@ -137,6 +298,16 @@ impl RpcService {
             rpc_listen_address,
             server,
             metrics,
+            rate_limiter,
+            authenticator,
+            cors,
+            compression,
+            tls,
+            method_filter,
+            timeouts,
+            response_size,
+            subscriptions_config,
+            subscriptions: _,
             mut modules,
             schema,
             cancel,
@@ -150,17 +321,95 @@ impl RpcService {
             .register_method("rpc.discover", move |_, _, _| json!(schema.clone()))
             .context("Failed to add schema discovery method")?;
 
-        let middleware = RpcServiceBuilder::new().layer(MetricsLayer::new(
-            metrics,
-            modules.method_names().map(|n| n.to_owned()).collect(),
-        ));
-
-        let handle = server
-            .set_rpc_middleware(middleware)
-            .build(rpc_listen_address)
+        // Requests to disabled methods, requests that time out, and requests with responses that
+        // are too large are all recorded as failures, so those layers are applied inside the
+        // metrics layer.
+        let middleware = RpcServiceBuilder::new()
+            .layer(MetricsLayer::new(
+                metrics,
+                modules.method_names().map(|n| n.to_owned()).collect(),
+            ))
+            .layer(method_filter)
+            .layer(timeouts)
+            .layer(response_size)
+            .layer(PinningLayer);
+
+        // Rate limits and authentication are enforced on HTTP requests, before they are parsed, so
+        // that clients that have exceeded their limit, or are not allowed to use the service, cost
+        // it as little as possible. Authenticated requests carry the ID of their API key into the
+        // JSON-RPC middleware, to tag their metrics and logs.
+        //
+        // CORS is handled before either of these, so that pre-flight requests are answered without
+        // an API key, and so that browsers can read the errors from the other layers. Compression
+        // wraps everything else, because it changes the type of the response body.
+        let http_middleware = tower::ServiceBuilder::new()
+            .layer(compression)
+            .option_layer(cors)
+            .layer(rate_limiter)
+            .layer(authenticator);
+
+        let server = server
+            .max_subscriptions_per_connection(subscriptions_config.max_subscriptions_per_connection)
+            .set_message_buffer_capacity(subscriptions_config.buffer_capacity)
+            .set_http_middleware(http_middleware)
+            .set_rpc_middleware(middleware);
+
+        let listener = TcpListener::bind(rpc_listen_address)
             .await
-            .context("Failed to bind JSON-RPC service")?
-            .start(modules);
+            .context("Failed to bind JSON-RPC service")?;
+
+        // Connections are accepted here, rather than by `jsonrpsee`, so that each request can be
+        // tagged with the address of the peer that sent it (which rate limits identify clients
+        // by), and so that TLS can be terminated (which `jsonrpsee` does not do itself). Each
+        // connection is served by its own instance of the service.
+        let handshaker = tls.map(Handshaker::new);
+        let service_builder = server.to_service_builder();
+        let methods: Methods = modules.into();
+        let (stop_handle, handle) = stop_channel();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, peer) = tokio::select! {
+                    _ = stop_handle.clone().shutdown() => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            warn!("Failed to accept connection: {e}");
+                            continue;
+                        }
+                    },
+                };
+
+                let handshaker = handshaker.clone();
+                let service = AddExtension::new(
+                    service_builder
+                        .clone()
+                        .build(methods.clone(), stop_handle.clone()),
+                    PeerAddr(peer),
+                );
+                let stopped = stop_handle.clone().shutdown();
+
+                tokio::spawn(async move {
+                    let served = if let Some(handshaker) = handshaker {
+                        let stream = match handshaker.accept(stream).await {
+                            Ok(stream) => stream,
+                            Err(e) => {
+                                debug!("TLS handshake failed: {e:#}");
+                                return;
+                            }
+                        };
+
+                        serve_with_graceful_shutdown(stream, service, stopped).await
+                    } else {
+                        serve_with_graceful_shutdown(stream, service, stopped).await
+                    };
+
+                    if let Err(e) = served {
+                        debug!("Failed to serve connection: {e}");
+                    }
+                });
+            }
+        });
 
         // Set-up a helper task that will tear down the RPC service when the cancellation token is
         // triggered.
@@ -196,6 +445,8 @@ impl Default for RpcArgs {
         Self {
             rpc_listen_address: "0.0.0.0:6000".parse().unwrap(),
             max_in_flight_requests: 2000,
+            max_request_body_bytes: 10 * 1024 * 1024,
+            max_response_bytes: 100 * 1024 * 1024,
         }
     }
 }
@@ -206,66 +457,189 @@ impl Default for RpcArgs {
 ///
 /// The service may spin up auxiliary services (such as the system package task) to support itself,
 /// and will clean these up on shutdown as well.
+///
+/// If `config_path` is provided (the file that `rpc_config` was read from), the configuration is
+/// reloaded from it when the service receives SIGHUP, or when the file is modified. If
+/// `strict_config` is set, unrecognized fields in the configuration are treated as errors, rather
+/// than being ignored (with a warning).
+#[allow(clippy::too_many_arguments)]
 pub async fn start_rpc(
     db_args: DbArgs,
     rpc_args: RpcArgs,
     system_package_task_args: SystemPackageTaskArgs,
     rpc_config: RpcConfig,
+    config_path: Option<PathBuf>,
+    strict_config: bool,
     registry: &Registry,
     cancel: CancellationToken,
 ) -> anyhow::Result<JoinHandle<()>> {
-    let RpcConfig {
-        objects,
-        transactions,
-        name_service,
-        coins,
-        bigtable_config,
-        package_resolver,
-        extra: _,
-    } = rpc_config.finish();
-
-    let objects_config = objects.finish(ObjectsConfig::default());
-    let transactions_config = transactions.finish(TransactionsConfig::default());
-    let name_service_config = name_service.finish(NameServiceConfig::default());
-    let coins_config = coins.finish(CoinsConfig::default());
-    let package_resolver_limits = package_resolver.finish();
+    let tls_config = rpc_config.tls.clone();
+    let (config, bigtable_config) = rpc_config
+        .finish_service(strict_config)
+        .context("Invalid RPC configuration")?;
 
     let mut rpc = RpcService::new(rpc_args, registry, cancel.child_token())
         .context("Failed to create RPC service")?;
 
+    if let Some(cors) = config.cors.layer()? {
+        rpc.set_cors(cors);
+    }
+
+    rpc.set_compression(config.compression.layer());
+
+    let subscriptions_config = config.subscriptions.clone();
+
+    if let Some(tls_config) = &tls_config {
+        rpc.set_tls(tls::server_config(tls_config).context("Invalid TLS configuration")?);
+    }
+
+    let webhook_store = if config.webhooks.enabled {
+        Some(WebhookStore::new(db_args.clone()).await?)
+    } else {
+        None
+    };
+
     let context = Context::new(
         db_args,
-        bigtable_config,
-        package_resolver_limits,
+        bigtable_config.clone(),
+        config,
         rpc.metrics(),
         registry,
     )
     .await?;
 
+    // Rate limits, API keys, the methods that are enabled, deadlines, and subscription send
+    // timeouts are read from the context on each request, so that they are reloaded along with the
+    // rest of its configuration.
+    let method_filter_context = context.clone();
+    rpc.set_method_filter(MethodFilterLayer::new(move |method| {
+        method_filter_context.config().methods.is_enabled(method)
+    }));
+
+    let rate_limit_context = context.clone();
+    rpc.set_rate_limiter(RateLimiter::new(
+        move || rate_limit_context.config().rate_limit.clone(),
+        rpc.metrics(),
+    ));
+
+    let auth_context = context.clone();
+    rpc.set_authenticator(Authenticator::new(move |key| {
+        auth_context.config().auth.authenticate(key)
+    }));
+
+    let timeouts_context = context.clone();
+    rpc.set_timeouts(TimeoutLayer::new(move |method| {
+        timeouts_context.config().method_timeouts.get(method)
+    }));
+
+    let subscriptions_context = context.clone();
+    rpc.set_subscriptions(subscriptions_config, move || {
+        subscriptions_context.config().subscriptions.send_timeout
+    });
+
+    let feed = Feed::new(context.clone(), cancel.child_token());
+
+    let webhook_delivery = webhook_store.clone().map(|store| {
+        WebhookDelivery::new(
+            context.clone(),
+            store,
+            &feed,
+            rpc.metrics(),
+            cancel.child_token(),
+        )
+    });
+
+    let stream_config = context.config().stream.clone();
+    let stream_publisher = if stream_config.enabled {
+        Some(
+            StreamPublisher::new(&stream_config, &feed, rpc.metrics(), cancel.child_token())
+                .await?,
+        )
+    } else {
+        None
+    };
+
     let system_package_task = SystemPackageTask::new(
         context.clone(),
         system_package_task_args,
         cancel.child_token(),
     );
 
+    let config_watcher = config_path.map(|path| {
+        ConfigWatcher::new(
+            context.clone(),
+            path,
+            strict_config,
+            bigtable_config,
+            cancel.child_token(),
+        )
+    });
+
+    rpc.add_module(Chain(context.clone()))?;
     rpc.add_module(Checkpoints(context.clone()))?;
-    rpc.add_module(Coins(context.clone(), coins_config))?;
+    rpc.add_module(Coins(
+        context.clone(),
+        CoinMetadataCache::new(context.config().coins.metadata_cache_size),
+    ))?;
     rpc.add_module(DynamicFields(context.clone()))?;
-    rpc.add_module(Governance(context.clone()))?;
-    rpc.add_module(MoveUtils(context.clone()))?;
-    rpc.add_module(NameService(context.clone(), name_service_config))?;
-    rpc.add_module(Objects(context.clone(), objects_config.clone()))?;
-    rpc.add_module(QueryObjects(context.clone(), objects_config))?;
-    rpc.add_module(QueryTransactions(context.clone(), transactions_config))?;
+    rpc.add_module(Events(rpc.subscriptions(), feed.events()))?;
+    rpc.add_module(Governance(
+        context.clone(),
+        SystemStateCache::default(),
+        RgpCache::default(),
+    ))?;
+    rpc.add_module(MoveUtils(
+        context.clone(),
+        NormalizedModulesCache::new(context.config().move_utils.normalized_cache_size),
+    ))?;
+    rpc.add_module(NameService(context.clone()))?;
+    rpc.add_module(Objects(context.clone()))?;
+    rpc.add_module(ProtocolConfigs(context.clone()))?;
+    rpc.add_module(QueryEvents(context.clone()))?;
+    rpc.add_module(QueryObjects(context.clone()))?;
+    rpc.add_module(QueryTransactions(context.clone()))?;
     rpc.add_module(Transactions(context.clone()))?;
+    rpc.add_module(TransactionSubscriptions(
+        context.clone(),
+        rpc.subscriptions(),
+        feed.transactions(),
+    ))?;
+
+    if let Some(store) = webhook_store {
+        rpc.add_module(Webhooks(context.clone(), store))?;
+    }
+
+    if context.config().execution.fullnode_url.is_some() {
+        let client = reqwest::Client::new();
+        rpc.add_module(Write(context.clone(), client.clone()))?;
+        rpc.add_module(Gas(context.clone(), client))?;
+    }
 
     let h_rpc = rpc.run().await.context("Failed to start RPC service")?;
     let h_system_package_task = system_package_task.run();
+    let h_feed = feed.run();
+    let h_webhook_delivery = webhook_delivery.map(WebhookDelivery::run);
+    let h_stream_publisher = stream_publisher.map(StreamPublisher::run);
+    let h_config_watcher = config_watcher.map(ConfigWatcher::run);
+    let h_replica_monitor = context.pg_reader().monitor_replicas(cancel.child_token());
 
     Ok(tokio::spawn(async move {
         let _ = h_rpc.await;
         cancel.cancel();
         let _ = h_system_package_task.await;
+        let _ = h_feed.await;
+        if let Some(h_webhook_delivery) = h_webhook_delivery {
+            let _ = h_webhook_delivery.await;
+        }
+        if let Some(h_stream_publisher) = h_stream_publisher {
+            let _ = h_stream_publisher.await;
+        }
+        if let Some(h_config_watcher) = h_config_watcher {
+            let _ = h_config_watcher.await;
+        }
+        if let Some(h_replica_monitor) = h_replica_monitor {
+            let _ = h_replica_monitor.await;
+        }
     }))
 }
 
@@ -277,7 +651,15 @@ mod tests {
         time::Duration,
     };
 
-    use jsonrpsee::{core::RpcResult, proc_macros::rpc, types::error::METHOD_NOT_FOUND_CODE};
+    use futures::{stream, StreamExt};
+    use jsonrpsee::{
+        core::{client::SubscriptionClientT, RpcResult, SubscriptionResult},
+        proc_macros::rpc,
+        rpc_params,
+        server::PendingSubscriptionSink,
+        types::error::{METHOD_NOT_FOUND_CODE, OVERSIZED_RESPONSE_CODE},
+        ws_client::WsClientBuilder,
+    };
     use reqwest::Client;
     use serde_json::{json, Value};
     use sui_open_rpc::Module;
@@ -528,6 +910,189 @@ mod tests {
             .expect("Shutdown should succeed");
     }
 
+    #[tokio::test]
+    async fn test_response_too_large() {
+        let cancel = CancellationToken::new();
+        let rpc_listen_address = test_listen_address();
+
+        let mut rpc = RpcService::new(
+            RpcArgs {
+                rpc_listen_address,
+                max_response_bytes: 100,
+                ..Default::default()
+            },
+            &Registry::new(),
+            cancel.clone(),
+        )
+        .unwrap();
+
+        rpc.add_module(Foo).unwrap();
+        rpc.add_module(Big).unwrap();
+
+        let handle = rpc.run().await.unwrap();
+
+        let url = format!("http://{}/", rpc_listen_address);
+        let client = Client::new();
+
+        let call = |method: &'static str| {
+            client
+                .post(&url)
+                .json(&json!({
+                    "jsonrpc": "2.0",
+                    "method": method,
+                    "id": 1,
+                }))
+                .send()
+        };
+
+        let resp: Value = call("test_bar").await.unwrap().json().await.unwrap();
+        assert_eq!(resp["result"], 42);
+
+        let resp: Value = call("test_big").await.unwrap().json().await.unwrap();
+        assert_eq!(resp["error"]["code"], OVERSIZED_RESPONSE_CODE);
+        assert!(resp["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("reduce the page size"));
+
+        cancel.cancel();
+        tokio::time::timeout(Duration::from_millis(500), handle)
+            .await
+            .expect("Shutdown should not timeout")
+            .expect("Shutdown should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_method_timeouts() {
+        let cancel = CancellationToken::new();
+        let rpc_listen_address = test_listen_address();
+
+        let mut rpc = RpcService::new(
+            RpcArgs {
+                rpc_listen_address,
+                ..Default::default()
+            },
+            &Registry::new(),
+            cancel.clone(),
+        )
+        .unwrap();
+
+        rpc.add_module(Foo).unwrap();
+        rpc.add_module(Slow).unwrap();
+        rpc.set_timeouts(TimeoutLayer::new(|method| {
+            (method == "test_sleep" || method == "test_bar").then(|| Duration::from_millis(100))
+        }));
+
+        let metrics = rpc.metrics();
+        let handle = rpc.run().await.unwrap();
+
+        let url = format!("http://{}/", rpc_listen_address);
+        let client = Client::new();
+
+        let call = |method: &'static str| {
+            client
+                .post(&url)
+                .json(&json!({
+                    "jsonrpc": "2.0",
+                    "method": method,
+                    "id": 1,
+                }))
+                .send()
+        };
+
+        let resp: Value = call("test_bar").await.unwrap().json().await.unwrap();
+        assert_eq!(resp["result"], 42);
+
+        let resp: Value = call("test_sleep").await.unwrap().json().await.unwrap();
+        assert_eq!(resp["error"]["code"], crate::error::REQUEST_TIMEOUT_CODE);
+
+        assert_eq!(
+            metrics
+                .requests_failed
+                .with_label_values(&[
+                    "test_sleep",
+                    &format!("{}", crate::error::REQUEST_TIMEOUT_CODE)
+                ])
+                .get(),
+            1
+        );
+
+        cancel.cancel();
+        tokio::time::timeout(Duration::from_millis(500), handle)
+            .await
+            .expect("Shutdown should not timeout")
+            .expect("Shutdown should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions() {
+        let cancel = CancellationToken::new();
+        let rpc_listen_address = test_listen_address();
+
+        let mut rpc = RpcService::new(
+            RpcArgs {
+                rpc_listen_address,
+                ..Default::default()
+            },
+            &Registry::new(),
+            cancel.clone(),
+        )
+        .unwrap();
+
+        rpc.set_subscriptions(
+            SubscriptionsConfig {
+                max_subscriptions_per_connection: 1,
+                ..Default::default()
+            },
+            || Duration::from_secs(1),
+        );
+
+        rpc.add_module(Ticks(rpc.subscriptions())).unwrap();
+
+        let metrics = rpc.metrics();
+        let handle = rpc.run().await.unwrap();
+
+        let url = format!("ws://{}/", rpc_listen_address);
+        let client = WsClientBuilder::default().build(&url).await.unwrap();
+
+        let mut ticks = client
+            .subscribe::<u64, _>(
+                "test_subscribeTicks",
+                rpc_params![],
+                "test_unsubscribeTicks",
+            )
+            .await
+            .unwrap();
+
+        for i in 0..3 {
+            assert_eq!(ticks.next().await.unwrap().unwrap(), i);
+        }
+
+        // The connection already has as many subscriptions open as it is allowed.
+        client
+            .subscribe::<u64, _>(
+                "test_subscribeTicks",
+                rpc_params![],
+                "test_unsubscribeTicks",
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            metrics
+                .subscription_notifications
+                .with_label_values(&["test_subscribeTicks"])
+                .get(),
+            3
+        );
+
+        cancel.cancel();
+        tokio::time::timeout(Duration::from_millis(500), handle)
+            .await
+            .expect("Shutdown should not timeout")
+            .expect("Shutdown should succeed");
+    }
+
     // Test Helpers
 
     #[open_rpc(namespace = "test", tag = "Test API")]
@@ -554,9 +1119,33 @@ mod tests {
         fn baz(&self) -> RpcResult<u64>;
     }
 
+    #[open_rpc(namespace = "test", tag = "Test API")]
+    #[rpc(server, namespace = "test")]
+    trait SlowApi {
+        #[method(name = "sleep")]
+        async fn sleep(&self) -> RpcResult<u64>;
+    }
+
+    #[open_rpc(namespace = "test", tag = "Test API")]
+    #[rpc(server, namespace = "test")]
+    trait BigApi {
+        #[method(name = "big")]
+        fn big(&self) -> RpcResult<String>;
+    }
+
+    #[open_rpc(namespace = "test", tag = "Test API")]
+    #[rpc(server, namespace = "test")]
+    trait TicksApi {
+        #[subscription(name = "subscribeTicks", item = u64)]
+        fn subscribe_ticks(&self) -> SubscriptionResult;
+    }
+
     struct Foo;
     struct Bar;
     struct Baz;
+    struct Slow;
+    struct Big;
+    struct Ticks(SubscriptionManager);
 
     impl FooApiServer for Foo {
         fn bar(&self) -> RpcResult<u64> {
@@ -580,6 +1169,29 @@ mod tests {
         }
     }
 
+    #[async_trait::async_trait]
+    impl SlowApiServer for Slow {
+        async fn sleep(&self) -> RpcResult<u64> {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(46)
+        }
+    }
+
+    impl BigApiServer for Big {
+        fn big(&self) -> RpcResult<String> {
+            Ok("x".repeat(1000))
+        }
+    }
+
+    impl TicksApiServer for Ticks {
+        fn subscribe_ticks(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+            // Three ticks, and then the subscription stays open.
+            self.0
+                .subscribe(pending, stream::iter(0u64..3).chain(stream::pending()));
+            Ok(())
+        }
+    }
+
     impl RpcModule for Foo {
         fn schema(&self) -> Module {
             FooApiOpenRpc::module_doc()
@@ -610,6 +1222,36 @@ mod tests {
         }
     }
 
+    impl RpcModule for Slow {
+        fn schema(&self) -> Module {
+            SlowApiOpenRpc::module_doc()
+        }
+
+        fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+            self.into_rpc()
+        }
+    }
+
+    impl RpcModule for Big {
+        fn schema(&self) -> Module {
+            BigApiOpenRpc::module_doc()
+        }
+
+        fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+            self.into_rpc()
+        }
+    }
+
+    impl RpcModule for Ticks {
+        fn schema(&self) -> Module {
+            TicksApiOpenRpc::module_doc()
+        }
+
+        fn into_impl(self) -> jsonrpsee::RpcModule<Self> {
+            self.into_rpc()
+        }
+    }
+
     fn test_listen_address() -> SocketAddr {
         let port = get_available_port();
         SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)
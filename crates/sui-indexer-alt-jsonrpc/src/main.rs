@@ -1,16 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use clap::Parser;
 use prometheus::Registry;
 use sui_indexer_alt_jsonrpc::{
     args::{Args, Command},
     config::RpcConfig,
     start_rpc,
+    validate::validate_config,
 };
 use sui_indexer_alt_metrics::MetricsService;
-use tokio::fs;
 use tokio_util::sync::CancellationToken;
 
 #[tokio::main]
@@ -28,17 +28,24 @@ async fn main() -> anyhow::Result<()> {
             system_package_task_args,
             metrics_args,
             config,
+            strict_config,
+            dump_effective_config,
         } => {
-            let rpc_config = if let Some(path) = config {
-                let contents = fs::read_to_string(path)
-                    .await
-                    .context("Failed to read configuration TOML file")?;
-
-                toml::from_str(&contents).context("Failed to parse configuration TOML file")?
+            let rpc_config = if let Some(path) = &config {
+                RpcConfig::read(path).await?
             } else {
-                RpcConfig::default()
+                RpcConfig::from_env()?
             };
 
+            if dump_effective_config {
+                let effective = rpc_config.effective(strict_config)?;
+                let config_toml = toml::to_string_pretty(&effective)
+                    .context("Failed to serialize effective configuration to TOML.")?;
+
+                println!("{config_toml}");
+                return Ok(());
+            }
+
             let cancel = CancellationToken::new();
 
             let registry = Registry::new_custom(Some("jsonrpc_alt".into()), None)
@@ -51,6 +58,8 @@ async fn main() -> anyhow::Result<()> {
                 rpc_args,
                 system_package_task_args,
                 rpc_config,
+                config,
+                strict_config,
                 metrics.registry(),
                 cancel.child_token(),
             )
@@ -70,6 +79,20 @@ async fn main() -> anyhow::Result<()> {
 
             println!("{config_toml}");
         }
+
+        Command::ValidateConfig {
+            config,
+            strict_config,
+        } => {
+            let report = validate_config(args.db_args, config.as_deref(), strict_config).await;
+            let report_json = serde_json::to_string_pretty(&report)
+                .context("Failed to serialize validation report to JSON.")?;
+
+            println!("{report_json}");
+            if !report.passed() {
+                bail!("Configuration failed validation");
+            }
+        }
     }
 
     Ok(())
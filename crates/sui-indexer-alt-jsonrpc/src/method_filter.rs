@@ -0,0 +1,151 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeSet, sync::Arc};
+
+use futures::future::{self, BoxFuture, FutureExt};
+use jsonrpsee::{
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorObject, Request},
+    MethodResponse,
+};
+use tower_layer::Layer;
+
+use crate::error::METHOD_DISABLED_CODE;
+
+/// The method that serves the service's schema, which is not subject to the allowlist.
+const DISCOVER: &str = "rpc.discover";
+
+/// Which methods the operator has chosen to serve. Method names can be given in full
+/// (`suix_getOwnedObjects`) or without their namespace (`getOwnedObjects`).
+#[derive(Clone, Default, Debug)]
+pub struct MethodsConfig {
+    /// If set, only these methods are served (in addition to schema discovery).
+    pub enabled_methods: Option<BTreeSet<String>>,
+
+    /// These methods are not served, even if they are in `enabled_methods`.
+    pub disabled_methods: BTreeSet<String>,
+}
+
+/// Tower Layer that adds middleware to reject requests to methods that have been disabled.
+#[derive(Clone)]
+pub(crate) struct MethodFilterLayer {
+    is_enabled: Arc<dyn Fn(&str) -> bool + Send + Sync>,
+}
+
+/// The Tower Service responsible for rejecting requests to disabled methods before they reach the
+/// JSON-RPC request handler.
+pub(crate) struct MethodFilterService<S> {
+    layer: MethodFilterLayer,
+    inner: S,
+}
+
+impl MethodsConfig {
+    /// Whether requests to `method` should be served.
+    pub fn is_enabled(&self, method: &str) -> bool {
+        if contains(&self.disabled_methods, method) {
+            return false;
+        }
+
+        match &self.enabled_methods {
+            Some(enabled) => method == DISCOVER || contains(enabled, method),
+            None => true,
+        }
+    }
+}
+
+impl MethodFilterLayer {
+    /// Create a new layer that checks whether each request's method is enabled by calling
+    /// `is_enabled`. This is done on every request, so that methods can be enabled or disabled
+    /// while the service is running.
+    pub(crate) fn new(is_enabled: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            is_enabled: Arc::new(is_enabled),
+        }
+    }
+}
+
+impl Default for MethodFilterLayer {
+    /// A layer that lets requests to all methods through.
+    fn default() -> Self {
+        Self::new(|_| true)
+    }
+}
+
+impl<S> Layer<S> for MethodFilterLayer {
+    type Service = MethodFilterService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MethodFilterService {
+            layer: self.clone(),
+            inner,
+        }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for MethodFilterService<S>
+where
+    S: RpcServiceT<'a>,
+    S::Future: Send + 'a,
+{
+    type Future = BoxFuture<'a, MethodResponse>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        if (self.layer.is_enabled)(request.method_name()) {
+            return self.inner.call(request).boxed();
+        }
+
+        let error = ErrorObject::owned(
+            METHOD_DISABLED_CODE,
+            format!("Method disabled by operator: {}", request.method_name()),
+            None::<()>,
+        );
+
+        future::ready(MethodResponse::error(request.id, error)).boxed()
+    }
+}
+
+/// Whether `methods` contains `method`, either by its full name or by its name without its
+/// namespace.
+fn contains(methods: &BTreeSet<String>, method: &str) -> bool {
+    methods.contains(method)
+        || method
+            .split_once('_')
+            .is_some_and(|(_, name)| methods.contains(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(methods: &[&str]) -> BTreeSet<String> {
+        methods.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn test_disabled_methods() {
+        let config = MethodsConfig {
+            enabled_methods: None,
+            disabled_methods: set(&["suix_getOwnedObjects", "multiGetObjects"]),
+        };
+
+        assert!(!config.is_enabled("suix_getOwnedObjects"));
+        assert!(!config.is_enabled("sui_multiGetObjects"));
+        assert!(config.is_enabled("sui_getObject"));
+        assert!(config.is_enabled(DISCOVER));
+    }
+
+    #[test]
+    fn test_enabled_methods() {
+        let config = MethodsConfig {
+            enabled_methods: Some(set(&["suix_getBalance", "getAllBalances", "getCoins"])),
+            disabled_methods: set(&["suix_getCoins"]),
+        };
+
+        assert!(config.is_enabled("suix_getBalance"));
+        assert!(config.is_enabled("suix_getAllBalances"));
+        assert!(config.is_enabled(DISCOVER));
+        assert!(!config.is_enabled("suix_getCoins"));
+        assert!(!config.is_enabled("suix_getOwnedObjects"));
+    }
+}
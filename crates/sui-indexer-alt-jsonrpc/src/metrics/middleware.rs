@@ -16,6 +16,8 @@ use prometheus::{HistogramTimer, IntCounterVec};
 use tower_layer::Layer;
 use tracing::info;
 
+use crate::auth::ApiKeyId;
+
 use super::RpcMetrics;
 
 /// Tower Layer that adds middleware to record statistics about RPC requests (how long they took to
@@ -42,6 +44,7 @@ pin_project! {
     pub(crate) struct MetricsFuture<'a, F> {
         metrics: Option<RequestMetrics>,
         method: Cow<'a, str>,
+        key: Option<String>,
         #[pin]
         inner: F,
     }
@@ -88,6 +91,16 @@ where
             .with_label_values(&[method.as_ref()])
             .inc();
 
+        // Requests that were authenticated with an API key are also counted per key.
+        let key = request.extensions().get::<ApiKeyId>().map(|k| k.0.clone());
+        if let Some(key) = &key {
+            self.layer
+                .metrics
+                .requests_received_by_key
+                .with_label_values(&[key])
+                .inc();
+        }
+
         let timer = self
             .layer
             .metrics
@@ -102,6 +115,7 @@ where
                 failed: self.layer.metrics.requests_failed.clone(),
             }),
             method,
+            key,
             inner: self.inner.call(request),
         }
     }
@@ -125,6 +139,7 @@ where
         };
 
         let method = this.method.as_ref();
+        let key = this.key.as_deref();
         let elapsed_ms = metrics.timer.stop_and_record() / 1000.0;
 
         if let Some(code) = resp.as_error_code() {
@@ -132,10 +147,10 @@ where
                 .failed
                 .with_label_values(&[method, &format!("{code}")])
                 .inc();
-            info!(method, code, elapsed_ms, "Request failed");
+            info!(method, key, code, elapsed_ms, "Request failed");
         } else {
             metrics.succeeded.with_label_values(&[method]).inc();
-            info!(method, elapsed_ms, "Request succeeded");
+            info!(method, key, elapsed_ms, "Request succeeded");
         }
 
         Poll::Ready(resp)
@@ -5,8 +5,9 @@ use std::sync::Arc;
 
 use prometheus::{
     register_histogram_vec_with_registry, register_histogram_with_registry,
-    register_int_counter_vec_with_registry, register_int_counter_with_registry, Histogram,
-    HistogramVec, IntCounter, IntCounterVec, Registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGaugeVec, Registry,
 };
 
 pub(crate) mod middleware;
@@ -29,6 +30,19 @@ pub struct RpcMetrics {
     pub requests_received: IntCounterVec,
     pub requests_succeeded: IntCounterVec,
     pub requests_failed: IntCounterVec,
+    pub requests_rate_limited: IntCounter,
+    pub requests_received_by_key: IntCounterVec,
+
+    pub subscriptions_active: IntGaugeVec,
+    pub subscription_notifications: IntCounterVec,
+    pub subscriptions_lagged: IntCounterVec,
+
+    pub webhook_deliveries_succeeded: IntCounter,
+    pub webhook_deliveries_failed: IntCounter,
+    pub webhook_notifications_dropped: IntCounter,
+
+    pub stream_messages_published: IntCounter,
+    pub stream_messages_failed: IntCounter,
 }
 
 impl RpcMetrics {
@@ -91,6 +105,74 @@ impl RpcMetrics {
                 registry
             )
             .unwrap(),
+
+            requests_rate_limited: register_int_counter_with_registry!(
+                "rpc_requests_rate_limited",
+                "Number of HTTP requests rejected because of rate limits",
+                registry,
+            ).unwrap(),
+
+            requests_received_by_key: register_int_counter_vec_with_registry!(
+                "rpc_requests_received_by_key",
+                "Number of requests initiated by clients authenticated with each API key, by key ID",
+                &["key"],
+                registry
+            )
+            .unwrap(),
+
+            subscriptions_active: register_int_gauge_vec_with_registry!(
+                "rpc_subscriptions_active",
+                "Number of subscriptions currently open, by subscription method",
+                &["method"],
+                registry
+            )
+            .unwrap(),
+
+            subscription_notifications: register_int_counter_vec_with_registry!(
+                "rpc_subscription_notifications",
+                "Number of notifications sent to subscribers, by subscription method",
+                &["method"],
+                registry
+            )
+            .unwrap(),
+
+            subscriptions_lagged: register_int_counter_vec_with_registry!(
+                "rpc_subscriptions_lagged",
+                "Number of subscriptions closed because the subscriber was not keeping up, by subscription method",
+                &["method"],
+                registry
+            )
+            .unwrap(),
+
+            webhook_deliveries_succeeded: register_int_counter_with_registry!(
+                "rpc_webhook_deliveries_succeeded",
+                "Number of notifications delivered to webhooks",
+                registry,
+            ).unwrap(),
+
+            webhook_deliveries_failed: register_int_counter_with_registry!(
+                "rpc_webhook_deliveries_failed",
+                "Number of notifications that could not be delivered to webhooks, after retries",
+                registry,
+            ).unwrap(),
+
+            webhook_notifications_dropped: register_int_counter_with_registry!(
+                "rpc_webhook_notifications_dropped",
+                "Number of webhook notifications dropped before delivery was attempted, because delivery was not keeping up",
+                registry,
+            ).unwrap(),
+
+            stream_messages_published: register_int_counter_with_registry!(
+                "rpc_stream_messages_published",
+                "Number of events and transactions published to the message broker",
+                registry,
+            ).unwrap(),
+
+            stream_messages_failed: register_int_counter_with_registry!(
+                "rpc_stream_messages_failed",
+                "Number of events and transactions that could not be published to the message broker, or were dropped because publishing was not keeping up",
+                registry,
+            ).unwrap(),
         })
     }
 }
@@ -0,0 +1,388 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resolution of Move Registry (MVR) names in RPC inputs. Types can refer to packages by their
+//! registered name, e.g. `@org/app::module::Type`, or `@org/app/2::module::Type` for a specific
+//! version of the package, and these are replaced by the addresses that the names are registered
+//! to on-chain, before the type is used.
+
+use std::{collections::HashMap, str::FromStr, sync::LazyLock};
+
+use anyhow::Context as _;
+use diesel::{sql_types::Bool, ExpressionMethods, QueryDsl};
+use futures::future;
+use move_core_types::{ident_str, identifier::IdentStr, language_storage::StructTag};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use sui_indexer_alt_schema::schema::sum_packages;
+use sui_name_service::{validate_label, Domain};
+use sui_sql_macro::sql;
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    collection_types::VecMap,
+    dynamic_field::{derive_dynamic_field_id, Field},
+    id::ID,
+    TypeTag,
+};
+
+use crate::{
+    data::objects::load_live,
+    error::{invalid_params, RpcError},
+    Context,
+};
+
+const MOVE_REGISTRY_MODULE: &IdentStr = ident_str!("name");
+const MOVE_REGISTRY_TYPE: &IdentStr = ident_str!("Name");
+
+/// Address of the Move Registry package on mainnet.
+const MOVE_REGISTRY_PACKAGE: &str =
+    "0x62c1f5b1cb9e3bfc3dd1f73c95066487b662048a6358eabdbf67f6cdeca6db4b";
+
+/// ID of the table holding all Move Registry names on mainnet.
+const MOVE_REGISTRY_TABLE_ID: &str =
+    "0xe8417c530cde59eddf6dfb760e8a0e3e2c6f17c69ddaab5a73dd6a6e65fc463b";
+
+/// Matches every name in a type, in the format `@org/app` or `@org/app/1` (version 1).
+static VERSIONED_NAME_UNBOUND_REG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"([a-z0-9.\-@]*)\/([a-z0-9.-]*)(?:\/(\d+))?").unwrap());
+
+/// Matches a single name, in the format `@org/app` or `@org/app/1` (version 1).
+static VERSIONED_NAME_REG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([a-z0-9.\-@]*)\/([a-z0-9.-]*)(?:\/(\d+))?$").unwrap());
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MoveRegistryConfig {
+    /// Address of the Move Registry package.
+    pub package_address: SuiAddress,
+
+    /// ID of the table that registered names are stored in.
+    pub registry_id: ObjectID,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Invalid Move Registry name {0:?}")]
+    InvalidName(String),
+
+    #[error("Move Registry name {0:?} not found")]
+    NotFound(String),
+
+    #[error("Failed to parse type {0:?}: {1}")]
+    BadType(String, anyhow::Error),
+}
+
+/// A name, optionally qualified by the version of the package it refers to (defaulting to the
+/// latest version).
+#[derive(Debug, Clone)]
+pub(crate) struct VersionedName {
+    name: Name,
+    version: Option<u64>,
+}
+
+/// The on-chain representation of a Move Registry name, used as the key for its record.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Name {
+    org: Domain,
+    app: Vec<String>,
+}
+
+/// The on-chain record for a name. The layout of this type must match its on-chain definition.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct AppRecord {
+    app_cap_id: ID,
+    ns_nft_id: ID,
+    app_info: Option<AppInfo>,
+    networks: VecMap<String, AppInfo>,
+    metadata: VecMap<String, String>,
+    storage: ObjectID,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct AppInfo {
+    package_info_id: Option<ID>,
+    package_address: Option<SuiAddress>,
+    upgrade_cap_id: Option<ID>,
+}
+
+/// Parse `type_` as a type tag, where packages may be referred to by their Move Registry names,
+/// as well as their addresses. Names are resolved to the addresses they are registered to, and
+/// types are returned in their canonical form (referring to the packages that defined them).
+pub(crate) async fn resolve_type<E: From<Error> + std::error::Error>(
+    ctx: &Context,
+    config: &MoveRegistryConfig,
+    type_: &str,
+) -> Result<TypeTag, RpcError<E>> {
+    let names = parse_names(type_).map_err(|e| invalid_params(E::from(e)))?;
+    if names.is_empty() {
+        return sui_types::parse_sui_type_tag(type_)
+            .map_err(|e| invalid_params(E::from(Error::BadType(type_.to_owned(), e))));
+    }
+
+    let addresses = future::join_all(
+        names
+            .iter()
+            .map(|(_, versioned)| package_address(ctx, config, versioned)),
+    )
+    .await;
+
+    let mut resolved = HashMap::new();
+    for ((name, _), address) in names.into_iter().zip(addresses) {
+        let address = address.with_context(|| format!("Failed to resolve {name:?}"))?;
+        let Some(address) = address else {
+            return Err(invalid_params(E::from(Error::NotFound(name))));
+        };
+
+        resolved.insert(name, address);
+    }
+
+    let replaced = replace_names(type_, &resolved).map_err(|e| invalid_params(E::from(e)))?;
+    let tag = sui_types::parse_sui_type_tag(&replaced)
+        .map_err(|e| invalid_params(E::from(Error::BadType(type_.to_owned(), e))))?;
+
+    Ok(ctx
+        .package_resolver()
+        .canonical_type(tag)
+        .await
+        .with_context(|| format!("Failed to canonicalize type {type_:?}"))?)
+}
+
+/// Find all the Move Registry names in `type_`, and check that the type is otherwise valid.
+pub(crate) fn parse_names(type_: &str) -> Result<Vec<(String, VersionedName)>, Error> {
+    let mut names = vec![];
+    let mut error = None;
+
+    // Replace names with a placeholder address, to check that the rest of the type is valid
+    // before resolving any names.
+    let placeholder = VERSIONED_NAME_UNBOUND_REG.replace_all(type_, |c: &Captures| {
+        // SAFETY: Capture group 0 always corresponds to the whole match.
+        let name = c.get(0).unwrap().as_str();
+        match VersionedName::from_str(name) {
+            Ok(versioned) => names.push((name.to_owned(), versioned)),
+            Err(e) => {
+                error.get_or_insert(e);
+            }
+        }
+
+        "0x0"
+    });
+
+    if let Some(e) = error {
+        return Err(e);
+    }
+
+    sui_types::parse_sui_type_tag(&placeholder).map_err(|e| Error::BadType(type_.to_owned(), e))?;
+
+    Ok(names)
+}
+
+/// Replace the Move Registry names in `type_` with the addresses they resolved to.
+pub(crate) fn replace_names(
+    type_: &str,
+    resolved: &HashMap<String, ObjectID>,
+) -> Result<String, Error> {
+    let mut replaced = String::with_capacity(type_.len());
+    let mut last = 0;
+
+    for c in VERSIONED_NAME_UNBOUND_REG.captures_iter(type_) {
+        // SAFETY: Capture group 0 always corresponds to the whole match.
+        let m = c.get(0).unwrap();
+        let Some(address) = resolved.get(m.as_str()) else {
+            return Err(Error::NotFound(m.as_str().to_owned()));
+        };
+
+        replaced.push_str(&type_[last..m.start()]);
+        replaced.push_str(&address.to_string());
+        last = m.end();
+    }
+
+    replaced.push_str(&type_[last..]);
+    Ok(replaced)
+}
+
+/// Look up the address of the package that `versioned` refers to, if the name is registered, and
+/// the package at that version exists.
+async fn package_address(
+    ctx: &Context,
+    config: &MoveRegistryConfig,
+    versioned: &VersionedName,
+) -> anyhow::Result<Option<ObjectID>> {
+    use sum_packages::dsl as p;
+
+    let record_id = versioned
+        .name
+        .to_dynamic_field_id(config)
+        .context("Failed to derive record ID")?;
+
+    let Some(object) = load_live(ctx, record_id)
+        .await
+        .context("Failed to load record")?
+    else {
+        return Ok(None);
+    };
+
+    let move_object = object
+        .data
+        .try_as_move()
+        .context("Record is not a Move object")?;
+
+    let record: Field<Name, AppRecord> =
+        bcs::from_bytes(move_object.contents()).context("Failed to deserialize record")?;
+
+    let Some(address) = record.value.app_info.and_then(|i| i.package_address) else {
+        return Ok(None);
+    };
+
+    // The record points at one version of the package, find the requested version (or the
+    // latest) from the same package family.
+    let mut query = p::sum_packages
+        .select(p::package_id)
+        .filter(sql!(as Bool,
+            "original_id = (SELECT original_id FROM sum_packages WHERE package_id = {Bytea})",
+            address.to_vec(),
+        ))
+        .order_by(p::package_version.desc())
+        .limit(1)
+        .into_boxed();
+
+    if let Some(version) = versioned.version {
+        query = query.filter(p::package_version.eq(version as i64));
+    }
+
+    let package_ids: Vec<Vec<u8>> = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to the database")?
+        .results(query)
+        .await
+        .context("Failed to fetch package")?;
+
+    package_ids
+        .first()
+        .map(ObjectID::from_bytes)
+        .transpose()
+        .context("Failed to deserialize package ID")
+}
+
+impl Default for MoveRegistryConfig {
+    fn default() -> Self {
+        Self {
+            package_address: SuiAddress::from_str(MOVE_REGISTRY_PACKAGE).unwrap(),
+            registry_id: ObjectID::from_str(MOVE_REGISTRY_TABLE_ID).unwrap(),
+        }
+    }
+}
+
+impl Name {
+    /// ID of the dynamic field on the registry that holds this name's record.
+    fn to_dynamic_field_id(&self, config: &MoveRegistryConfig) -> anyhow::Result<ObjectID> {
+        let type_ = TypeTag::Struct(Box::new(StructTag {
+            address: config.package_address.into(),
+            module: MOVE_REGISTRY_MODULE.to_owned(),
+            name: MOVE_REGISTRY_TYPE.to_owned(),
+            type_params: vec![],
+        }));
+
+        Ok(derive_dynamic_field_id(
+            config.registry_id,
+            &type_,
+            &bcs::to_bytes(self)?,
+        )?)
+    }
+}
+
+impl FromStr for VersionedName {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidName(s.to_owned());
+        let caps = VERSIONED_NAME_REG.captures(s).ok_or_else(invalid)?;
+
+        // SAFETY: The regex matched, so its first two (non-optional) capture groups did as well.
+        let org = Domain::from_str(caps.get(1).unwrap().as_str()).map_err(|_| invalid())?;
+        let app = validate_label(caps.get(2).unwrap().as_str()).map_err(|_| invalid())?;
+
+        let version = caps
+            .get(3)
+            .map(|v| v.as_str().parse())
+            .transpose()
+            .map_err(|_| invalid())?;
+
+        Ok(Self {
+            name: Name {
+                org,
+                app: vec![app.to_owned()],
+            },
+            version,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_versioned_name() {
+        let n = VersionedName::from_str("@org/app").unwrap();
+        assert_eq!(n.version, None);
+        assert_eq!(n.name.app, vec!["app".to_owned()]);
+
+        let n = VersionedName::from_str("nested@org/app/34").unwrap();
+        assert_eq!(n.version, Some(34));
+
+        for bad in [
+            "@org/-app",
+            "@org/1.app",
+            "@org/app-",
+            "@org/app/",
+            "@org/app/v",
+            "@org",
+            "app",
+            "org/@app",
+            "",
+        ] {
+            assert!(VersionedName::from_str(bad).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_and_replace_names() {
+        let type_ = "@org/app::m::T<@org/other/2::n::U, 0x2::sui::SUI>";
+        let names: Vec<_> = parse_names(type_)
+            .unwrap()
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect();
+        assert_eq!(names, vec!["@org/app", "@org/other/2"]);
+
+        let resolved = HashMap::from_iter([
+            ("@org/app".to_owned(), ObjectID::from_single_byte(1)),
+            ("@org/other/2".to_owned(), ObjectID::from_single_byte(2)),
+        ]);
+
+        assert_eq!(
+            parse_sui_type(&replace_names(type_, &resolved).unwrap()),
+            parse_sui_type("0x1::m::T<0x2::n::U, 0x2::sui::SUI>"),
+        );
+
+        // Types without names have nothing to resolve.
+        assert!(parse_names("0x2::coin::Coin<0x2::sui::SUI>")
+            .unwrap()
+            .is_empty());
+
+        // Invalid names and types are caught before any names are resolved.
+        assert!(matches!(
+            parse_names("@org/app-::m::T"),
+            Err(Error::InvalidName(_))
+        ));
+        assert!(matches!(
+            parse_names("@org/app::m::T<"),
+            Err(Error::BadType(_, _))
+        ));
+    }
+
+    fn parse_sui_type(type_: &str) -> TypeTag {
+        sui_types::parse_sui_type_tag(type_).unwrap()
+    }
+}
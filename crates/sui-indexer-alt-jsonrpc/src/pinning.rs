@@ -0,0 +1,39 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::future::{BoxFuture, FutureExt};
+use jsonrpsee::{server::middleware::rpc::RpcServiceT, types::Request, MethodResponse};
+use tower_layer::Layer;
+
+use crate::data::pg_reader;
+
+/// Tower Layer that adds middleware to serve all the reads of each request from the same database,
+/// so that requests see a consistent view of the data, even when they are spread across read
+/// replicas (see [`pg_reader::pinned`]).
+#[derive(Clone, Default)]
+pub(crate) struct PinningLayer;
+
+/// The Tower Service responsible for pinning each request to a database.
+pub(crate) struct PinningService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for PinningLayer {
+    type Service = PinningService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PinningService { inner }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for PinningService<S>
+where
+    S: RpcServiceT<'a>,
+    S::Future: Send + 'a,
+{
+    type Future = BoxFuture<'a, MethodResponse>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        pg_reader::pinned(self.inner.call(request)).boxed()
+    }
+}
@@ -0,0 +1,389 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use futures::future::{self, BoxFuture, FutureExt};
+use ipnetwork::IpNetwork;
+use jsonrpsee::server::HttpBody;
+use lru::LruCache;
+use serde_json::json;
+use tower_layer::Layer;
+
+use crate::{error::RATE_LIMITED_CODE, metrics::RpcMetrics};
+
+/// Header that proxies in front of the service use to identify the client.
+const FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// Maximum number of clients whose limits are tracked. Beyond this, the clients that made a request
+/// least recently are forgotten, and start with a full bucket if they return.
+const MAX_CLIENTS: usize = 100_000;
+
+/// Limits on the rate of requests the service will serve. Limits are enforced using token buckets,
+/// so that clients can make short bursts of requests above their sustained rate.
+#[derive(Clone, Default, Debug)]
+pub struct RateLimitConfig {
+    /// Maximum number of requests per second served across all clients.
+    pub global_rps: Option<u32>,
+
+    /// Maximum number of requests per second served for each client, sustained.
+    pub client_rps: Option<u32>,
+
+    /// Maximum number of requests a client can make at once, after being idle. Defaults to
+    /// `client_rps`.
+    pub client_burst: Option<u32>,
+
+    /// Proxies (e.g. load balancers) whose `X-Forwarded-For` headers are trusted to identify the
+    /// clients they forward requests for.
+    pub trusted_proxies: Vec<IpNetwork>,
+}
+
+/// The address of the peer that a connection was accepted from. The service adds this to the
+/// extensions of every request received on the connection.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PeerAddr(pub SocketAddr);
+
+/// Tower Layer that adds middleware to reject HTTP requests that exceed the service's rate limits,
+/// before they are parsed as JSON-RPC requests.
+///
+/// Clients are identified by the address of the peer that sent the request, unless it is a trusted
+/// proxy, in which case the client is the last address in the `X-Forwarded-For` header that was not
+/// added by a trusted proxy (addresses before it could have been set by the client itself).
+/// Requests whose peer is not known all share a single client's limit.
+#[derive(Clone)]
+pub(crate) struct RateLimiter {
+    inner: Arc<Inner>,
+}
+
+/// The Tower Service responsible for rate limiting requests to the wrapped HTTP service.
+#[derive(Clone)]
+pub(crate) struct RateLimitService<S> {
+    limiter: RateLimiter,
+    inner: S,
+}
+
+struct Inner {
+    /// Reads the current limits, on every request, so that they can be changed while the service
+    /// is running.
+    config: Box<dyn Fn() -> RateLimitConfig + Send + Sync>,
+    metrics: Arc<RpcMetrics>,
+    global: Mutex<Bucket>,
+    clients: Mutex<LruCache<Option<IpAddr>, Bucket>>,
+}
+
+/// A token bucket, holding up to `burst` tokens that are replenished at a rate of `rps` per second.
+/// Each request takes a token from the bucket.
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(
+        config: impl Fn() -> RateLimitConfig + Send + Sync + 'static,
+        metrics: Arc<RpcMetrics>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                config: Box::new(config),
+                metrics,
+                global: Mutex::new(Bucket::new()),
+                clients: Mutex::new(LruCache::new(
+                    NonZeroUsize::new(MAX_CLIENTS).expect("MAX_CLIENTS is non-zero"),
+                )),
+            }),
+        }
+    }
+
+    /// Take a token for a request from `client` at time `now`. Fails with the time to wait before
+    /// retrying if the client, or the service as a whole, has exceeded its limit.
+    fn acquire(&self, client: Option<IpAddr>, now: Instant) -> Result<(), Duration> {
+        let config = (self.inner.config)();
+
+        if let Some(rps) = config.client_rps {
+            let burst = config.client_burst.unwrap_or(rps);
+            self.inner
+                .clients
+                .lock()
+                .unwrap()
+                .get_or_insert_mut(client, Bucket::new)
+                .acquire(rps, burst, now)?;
+        }
+
+        if let Some(rps) = config.global_rps {
+            self.inner.global.lock().unwrap().acquire(rps, rps, now)?;
+        }
+
+        Ok(())
+    }
+
+    /// The address of the client that sent `request` (see [`RateLimiter`]).
+    fn client<B>(&self, request: &http::Request<B>) -> Option<IpAddr> {
+        let PeerAddr(peer) = request.extensions().get::<PeerAddr>()?;
+        let config = (self.inner.config)();
+        let trusted = |ip: &IpAddr| config.trusted_proxies.iter().any(|net| net.contains(*ip));
+
+        let forwarded: Vec<_> = request
+            .headers()
+            .get_all(FORWARDED_FOR)
+            .iter()
+            .filter_map(|h| h.to_str().ok())
+            .flat_map(|h| h.split(','))
+            .collect();
+
+        // Each proxy appends the address it received the request from, so work backwards from
+        // the peer, for as long as the address is a trusted proxy.
+        let mut client = peer.ip();
+        for addr in forwarded.into_iter().rev() {
+            if !trusted(&client) {
+                break;
+            }
+
+            let Ok(addr) = addr.trim().parse() else {
+                break;
+            };
+
+            client = addr;
+        }
+
+        Some(client)
+    }
+}
+
+impl<S> Layer<S> for RateLimiter {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            limiter: self.clone(),
+            inner,
+        }
+    }
+}
+
+impl<S, B> tower::Service<http::Request<B>> for RateLimitService<S>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<HttpBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let client = self.limiter.client(&request);
+        match self.limiter.acquire(client, Instant::now()) {
+            Ok(()) => self.inner.call(request).boxed(),
+            Err(retry_after) => {
+                self.limiter.inner.metrics.requests_rate_limited.inc();
+                future::ready(Ok(too_many_requests(retry_after))).boxed()
+            }
+        }
+    }
+}
+
+impl Bucket {
+    /// A new bucket starts off full: Its tokens are capped at its burst capacity on first use.
+    fn new() -> Self {
+        Self {
+            tokens: f64::INFINITY,
+            refilled: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, rps: u32, burst: u32, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rps as f64).min(burst as f64);
+        self.refilled = now;
+    }
+
+    fn acquire(&mut self, rps: u32, burst: u32, now: Instant) -> Result<(), Duration> {
+        self.refill(rps, burst, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / rps as f64))
+        }
+    }
+}
+
+/// An HTTP 429 response, with a JSON-RPC error body, asking the client to retry after
+/// `retry_after` (rounded up to the nearest second).
+fn too_many_requests(retry_after: Duration) -> http::Response<HttpBody> {
+    let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    let body = json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": RATE_LIMITED_CODE,
+            "message": format!("Too many requests, retry after {secs}s"),
+        },
+        "id": null,
+    });
+
+    http::Response::builder()
+        .status(http::StatusCode::TOO_MANY_REQUESTS)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(http::header::RETRY_AFTER, secs.to_string())
+        .body(HttpBody::from(body.to_string()))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::Registry;
+
+    use super::*;
+
+    fn limiter(config: RateLimitConfig) -> RateLimiter {
+        RateLimiter::new(move || config.clone(), RpcMetrics::new(&Registry::new()))
+    }
+
+    fn request(peer: Option<&str>, forwarded: &[&str]) -> http::Request<()> {
+        let mut request = http::Request::builder();
+        for header in forwarded {
+            request = request.header(FORWARDED_FOR, *header);
+        }
+
+        let mut request = request.body(()).unwrap();
+        if let Some(peer) = peer {
+            let peer = SocketAddr::new(peer.parse().unwrap(), 1234);
+            request.extensions_mut().insert(PeerAddr(peer));
+        }
+
+        request
+    }
+
+    #[test]
+    fn test_client_limit() {
+        let limiter = limiter(RateLimitConfig {
+            global_rps: None,
+            client_rps: Some(2),
+            client_burst: Some(4),
+            ..Default::default()
+        });
+
+        let a = Some("10.0.0.1".parse().unwrap());
+        let b = Some("10.0.0.2".parse().unwrap());
+        let now = Instant::now();
+
+        // Clients can make a burst of requests, and are then limited to their sustained rate.
+        for _ in 0..4 {
+            limiter.acquire(a, now).unwrap();
+        }
+
+        let retry_after = limiter.acquire(a, now).unwrap_err();
+        assert_eq!(retry_after, Duration::from_millis(500));
+
+        // Other clients are unaffected.
+        limiter.acquire(b, now).unwrap();
+        limiter.acquire(None, now).unwrap();
+
+        // Tokens are replenished over time.
+        limiter.acquire(a, now + retry_after).unwrap();
+        limiter.acquire(a, now + retry_after).unwrap_err();
+    }
+
+    #[test]
+    fn test_global_limit() {
+        let limiter = limiter(RateLimitConfig {
+            global_rps: Some(3),
+            ..Default::default()
+        });
+
+        let now = Instant::now();
+        for i in 0..3 {
+            limiter
+                .acquire(Some(IpAddr::from([10, 0, 0, i])), now)
+                .unwrap();
+        }
+
+        limiter.acquire(None, now).unwrap_err();
+        limiter.acquire(None, now + Duration::from_secs(1)).unwrap();
+    }
+
+    #[test]
+    fn test_no_limit() {
+        let limiter = limiter(RateLimitConfig::default());
+        let now = Instant::now();
+        for _ in 0..1000 {
+            limiter.acquire(None, now).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_client_identity() {
+        let limiter = limiter(RateLimitConfig {
+            trusted_proxies: vec!["10.0.0.0/8".parse().unwrap()],
+            ..Default::default()
+        });
+
+        let client =
+            |peer: Option<&str>, forwarded: &[&str]| limiter.client(&request(peer, forwarded));
+        let ip = |addr: &str| Some(addr.parse::<IpAddr>().unwrap());
+
+        // Without a trusted proxy, the header is ignored, as the client could have set it.
+        assert_eq!(client(Some("1.1.1.1"), &["2.2.2.2"]), ip("1.1.1.1"));
+
+        // Behind trusted proxies, the client is the last untrusted address.
+        assert_eq!(client(Some("10.0.0.1"), &[]), ip("10.0.0.1"));
+        assert_eq!(client(Some("10.0.0.1"), &["2.2.2.2"]), ip("2.2.2.2"));
+        assert_eq!(
+            client(Some("10.0.0.1"), &["3.3.3.3, 2.2.2.2, 10.0.0.2"]),
+            ip("2.2.2.2"),
+        );
+
+        // Multiple headers are treated as a single list.
+        assert_eq!(
+            client(Some("10.0.0.1"), &["3.3.3.3", "2.2.2.2, 10.0.0.2"]),
+            ip("2.2.2.2"),
+        );
+
+        // Addresses that can't be parsed stop the search at the last trusted proxy.
+        assert_eq!(
+            client(Some("10.0.0.1"), &["2.2.2.2, garbage"]),
+            ip("10.0.0.1")
+        );
+
+        // Requests without a peer address can't be identified.
+        assert_eq!(client(None, &["2.2.2.2"]), None);
+    }
+
+    #[test]
+    fn test_bounded_clients() {
+        let limiter = limiter(RateLimitConfig {
+            client_rps: Some(1),
+            ..Default::default()
+        });
+
+        let now = Instant::now();
+        for i in 0..=MAX_CLIENTS as u32 {
+            limiter
+                .acquire(Some(IpAddr::from(i.to_be_bytes())), now)
+                .unwrap();
+        }
+
+        let clients = limiter.inner.clients.lock().unwrap();
+        assert_eq!(clients.len(), MAX_CLIENTS);
+
+        // The least recently seen client was forgotten.
+        assert!(!clients.contains(&Some(IpAddr::from([0, 0, 0, 0]))));
+    }
+
+    #[test]
+    fn test_too_many_requests() {
+        let response = too_many_requests(Duration::from_millis(1500));
+        assert_eq!(response.status(), http::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[http::header::RETRY_AFTER], "2");
+    }
+}
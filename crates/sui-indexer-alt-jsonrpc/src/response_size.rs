@@ -0,0 +1,79 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::future::{BoxFuture, FutureExt};
+use jsonrpsee::{
+    server::middleware::rpc::RpcServiceT,
+    types::{error::OVERSIZED_RESPONSE_CODE, ErrorObject, Request},
+    MethodResponse,
+};
+use tower_layer::Layer;
+
+/// Tower Layer that adds middleware to explain to clients what to do when their response was too
+/// large to send.
+///
+/// `jsonrpsee` stops serializing responses as soon as they exceed the maximum response size (so
+/// large responses do not need to be held in memory in full), and replaces them with a generic
+/// error. This middleware replaces that error with one that suggests how to make the response
+/// smaller.
+#[derive(Clone)]
+pub(crate) struct ResponseSizeLayer {
+    max_response_bytes: u32,
+}
+
+/// The Tower Service responsible for rewriting errors about oversized responses.
+pub(crate) struct ResponseSizeService<S> {
+    layer: ResponseSizeLayer,
+    inner: S,
+}
+
+impl ResponseSizeLayer {
+    pub(crate) fn new(max_response_bytes: u32) -> Self {
+        Self { max_response_bytes }
+    }
+}
+
+impl<S> Layer<S> for ResponseSizeLayer {
+    type Service = ResponseSizeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ResponseSizeService {
+            layer: self.clone(),
+            inner,
+        }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for ResponseSizeService<S>
+where
+    S: RpcServiceT<'a>,
+    S::Future: Send + 'a,
+{
+    type Future = BoxFuture<'a, MethodResponse>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let id = request.id.clone();
+        let max = self.layer.max_response_bytes;
+        let inner = self.inner.call(request);
+
+        async move {
+            let response = inner.await;
+            if response.as_error_code() != Some(OVERSIZED_RESPONSE_CODE) {
+                return response;
+            }
+
+            MethodResponse::error(
+                id,
+                ErrorObject::owned(
+                    OVERSIZED_RESPONSE_CODE,
+                    format!(
+                        "Response too large (over {max} bytes), reduce the page size or the \
+                         number of items requested"
+                    ),
+                    None::<()>,
+                ),
+            )
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+#[cfg(feature = "kafka")]
+use std::time::Duration;
+
+use anyhow::Context as _;
+#[cfg(feature = "kafka")]
+use rdkafka::{
+    producer::{FutureProducer, FutureRecord},
+    ClientConfig,
+};
+use serde::{Deserialize, Serialize};
+use sui_json_rpc_types::SuiEvent;
+use sui_types::{digests::TransactionDigest, sui_serde::BigInt};
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{api::feed::Feed, data::kv_loader::TransactionContents, metrics::RpcMetrics};
+
+/// How long Kafka tries to deliver a message for, before giving up on it.
+#[cfg(feature = "kafka")]
+const KAFKA_MESSAGE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where newly indexed events and transactions are published to, for consumers that want to
+/// follow everything the service indexes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamConfig {
+    /// Whether events and transactions are published. This is only read on start-up.
+    pub enabled: bool,
+
+    /// Which kind of message broker to publish to. The service must be built with the feature of
+    /// the same name for the backend to be available.
+    pub backend: StreamBackend,
+
+    /// How to reach the message broker: A comma-separated list of bootstrap servers for Kafka, or
+    /// a server URL for NATS.
+    pub servers: Option<String>,
+
+    /// The Kafka topic, or NATS subject, that events are published to, as JSON.
+    pub events_topic: String,
+
+    /// The Kafka topic, or NATS subject, that the digests of transactions are published to, as
+    /// JSON.
+    pub transactions_topic: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamBackend {
+    Kafka,
+    Nats,
+}
+
+/// Background task that publishes the events and transactions broadcast by the [Feed] to a
+/// message broker.
+///
+/// Publishing is best-effort: Messages that are rejected by the broker are dropped, as are
+/// messages that the task cannot keep up with.
+pub(crate) struct StreamPublisher {
+    producer: Producer,
+    events_topic: String,
+    transactions_topic: String,
+    /// Events broadcast by the feed.
+    events: broadcast::Receiver<Arc<SuiEvent>>,
+    /// Transactions broadcast by the feed.
+    transactions: broadcast::Receiver<Arc<TransactionContents>>,
+    metrics: Arc<RpcMetrics>,
+    /// Signal to cancel the task.
+    cancel: CancellationToken,
+}
+
+enum Producer {
+    #[cfg(feature = "kafka")]
+    Kafka(FutureProducer),
+    #[cfg(feature = "nats")]
+    Nats(async_nats::Client),
+}
+
+/// The message published for each transaction.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionMessage {
+    digest: TransactionDigest,
+    checkpoint: BigInt<u64>,
+    timestamp_ms: BigInt<u64>,
+}
+
+impl StreamPublisher {
+    /// Connect to the message broker described by `config`.
+    pub(crate) async fn new(
+        config: &StreamConfig,
+        feed: &Feed,
+        metrics: Arc<RpcMetrics>,
+        cancel: CancellationToken,
+    ) -> anyhow::Result<Self> {
+        let servers = config
+            .servers
+            .as_deref()
+            .context("No servers configured to publish events and transactions to")?;
+
+        Ok(Self {
+            producer: Producer::connect(config.backend, servers).await?,
+            events_topic: config.events_topic.clone(),
+            transactions_topic: config.transactions_topic.clone(),
+            events: feed.events().subscribe(),
+            transactions: feed.transactions().subscribe(),
+            metrics,
+            cancel,
+        })
+    }
+
+    /// Start a new task that publishes events and transactions as they are broadcast by the feed.
+    ///
+    /// This operation consumes the `self` and returns a handle to the spawned tokio task. The task
+    /// will continue to run until its cancellation token is triggered.
+    pub(crate) fn run(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let Self {
+                producer,
+                events_topic,
+                transactions_topic,
+                mut events,
+                mut transactions,
+                metrics,
+                cancel,
+            } = self;
+
+            loop {
+                let (topic, key, message) = tokio::select! {
+                    _ = cancel.cancelled() => {
+                        info!("Shutdown signal received, terminating stream publisher");
+                        break;
+                    }
+
+                    event = events.recv() => match event {
+                        Ok(event) => {
+                            let key = event.id.tx_digest.to_string();
+                            (&events_topic, key, serde_json::to_vec(&*event))
+                        }
+
+                        Err(RecvError::Lagged(skipped)) => {
+                            warn!(skipped, "Stream publisher fell behind the event feed");
+                            metrics.stream_messages_failed.inc_by(skipped);
+                            continue;
+                        }
+
+                        Err(RecvError::Closed) => break,
+                    },
+
+                    tx = transactions.recv() => match tx {
+                        Ok(tx) => {
+                            let Ok(digest) = tx.digest() else {
+                                warn!("Failed to decode transaction digest for stream");
+                                metrics.stream_messages_failed.inc();
+                                continue;
+                            };
+
+                            let message = TransactionMessage {
+                                digest,
+                                checkpoint: tx.cp_sequence_number().into(),
+                                timestamp_ms: tx.timestamp_ms().into(),
+                            };
+
+                            (&transactions_topic, digest.to_string(), serde_json::to_vec(&message))
+                        }
+
+                        Err(RecvError::Lagged(skipped)) => {
+                            warn!(skipped, "Stream publisher fell behind the transaction feed");
+                            metrics.stream_messages_failed.inc_by(skipped);
+                            continue;
+                        }
+
+                        Err(RecvError::Closed) => break,
+                    },
+                };
+
+                let message = match message {
+                    Ok(message) => message,
+                    Err(e) => {
+                        warn!(topic, "Failed to serialize message: {e}");
+                        metrics.stream_messages_failed.inc();
+                        continue;
+                    }
+                };
+
+                if let Err(e) = producer.publish(topic, &key, message, &metrics).await {
+                    warn!(topic, "Failed to publish message: {e:#}");
+                    metrics.stream_messages_failed.inc();
+                }
+            }
+        })
+    }
+}
+
+impl Producer {
+    /// Connect to the `backend` message broker at `servers`. Fails if the service was built without
+    /// support for that backend.
+    async fn connect(backend: StreamBackend, servers: &str) -> anyhow::Result<Self> {
+        match backend {
+            #[cfg(feature = "kafka")]
+            StreamBackend::Kafka => Ok(Producer::Kafka(
+                ClientConfig::new()
+                    .set("bootstrap.servers", servers)
+                    .set(
+                        "message.timeout.ms",
+                        KAFKA_MESSAGE_TIMEOUT.as_millis().to_string(),
+                    )
+                    .create()
+                    .context("Failed to create Kafka producer")?,
+            )),
+
+            #[cfg(feature = "nats")]
+            StreamBackend::Nats => Ok(Producer::Nats(
+                async_nats::connect(servers)
+                    .await
+                    .context("Failed to connect to NATS")?,
+            )),
+
+            #[allow(unreachable_patterns)]
+            backend => Err(anyhow::anyhow!(
+                "Cannot publish to {servers}: built without the `{}` feature",
+                backend.feature(),
+            )),
+        }
+    }
+
+    /// Publish `message` to `topic`. Messages with the same `key` are sent to the same Kafka
+    /// partition, so that they are consumed in order. Kafka deliveries are confirmed in the
+    /// background, so that the publisher does not wait for each message to be acknowledged before
+    /// sending the next one.
+    #[cfg_attr(not(feature = "kafka"), allow(unused_variables))]
+    async fn publish(
+        &self,
+        topic: &str,
+        key: &str,
+        message: Vec<u8>,
+        metrics: &Arc<RpcMetrics>,
+    ) -> anyhow::Result<()> {
+        match *self {
+            #[cfg(feature = "kafka")]
+            Producer::Kafka(ref producer) => {
+                let record = FutureRecord::to(topic).key(key).payload(&message);
+                let delivery = producer
+                    .send_result(record)
+                    .map_err(|(e, _)| e)
+                    .context("Failed to enqueue message")?;
+
+                let metrics = metrics.clone();
+                let topic = topic.to_owned();
+                tokio::spawn(async move {
+                    match delivery.await {
+                        Ok(Ok(_)) => metrics.stream_messages_published.inc(),
+                        Ok(Err((e, _))) => {
+                            warn!(topic, "Failed to deliver message: {e}");
+                            metrics.stream_messages_failed.inc();
+                        }
+                        Err(_) => metrics.stream_messages_failed.inc(),
+                    }
+                });
+
+                Ok(())
+            }
+
+            #[cfg(feature = "nats")]
+            Producer::Nats(ref client) => {
+                client
+                    .publish(topic.to_owned(), message.into())
+                    .await
+                    .context("Failed to publish message")?;
+
+                metrics.stream_messages_published.inc();
+                Ok(())
+            }
+        }
+    }
+}
+
+impl StreamBackend {
+    /// The cargo feature that includes support for this backend.
+    fn feature(&self) -> &'static str {
+        match self {
+            StreamBackend::Kafka => "kafka",
+            StreamBackend::Nats => "nats",
+        }
+    }
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: StreamBackend::Kafka,
+            servers: None,
+            events_topic: "sui.events".to_owned(),
+            transactions_topic: "sui.transactions".to_owned(),
+        }
+    }
+}
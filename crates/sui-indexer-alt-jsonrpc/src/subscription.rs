@@ -0,0 +1,199 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{pin::pin, sync::Arc, time::Duration};
+
+use futures::{Stream, StreamExt};
+use jsonrpsee::{
+    core::server::SendTimeoutError,
+    server::{PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink},
+    types::{error::TOO_MANY_SUBSCRIPTIONS_CODE, ErrorObject},
+};
+use serde::Serialize;
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+use crate::metrics::RpcMetrics;
+
+/// Limits on subscriptions, which clients can open over WebSocket connections.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubscriptionsConfig {
+    /// Maximum number of subscriptions open at once, across all connections.
+    pub max_subscriptions: usize,
+
+    /// Maximum number of subscriptions a single connection can have open at once.
+    pub max_subscriptions_per_connection: u32,
+
+    /// Maximum number of messages queued for a connection, before sending more to it waits for the
+    /// client to catch up.
+    pub buffer_capacity: u32,
+
+    /// How long to wait for a client to catch up before closing a subscription it is not keeping
+    /// up with.
+    pub send_timeout: Duration,
+}
+
+/// Forwards notifications to subscribers. Subscription methods hand off their subscriptions to the
+/// manager, along with the stream of items to notify the subscriber of, and the manager takes care
+/// of enforcing the limit on open subscriptions, and of subscribers that fall behind.
+#[derive(Clone)]
+pub struct SubscriptionManager {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// One permit per subscription that can be open at once.
+    permits: Arc<Semaphore>,
+
+    /// Reads how long to wait for slow subscribers, when each subscription starts, so that it can
+    /// be changed while the service is running.
+    send_timeout: Box<dyn Fn() -> Duration + Send + Sync>,
+
+    metrics: Arc<RpcMetrics>,
+}
+
+/// Why a subscription stopped.
+enum Closed {
+    /// The stream of items ran out.
+    Finished,
+
+    /// The client unsubscribed, or disconnected.
+    Unsubscribed,
+
+    /// The client did not accept notifications as fast as they were being produced.
+    Lagged,
+
+    /// An item could not be serialized.
+    Failed,
+}
+
+impl SubscriptionManager {
+    pub(crate) fn new(
+        max_subscriptions: usize,
+        send_timeout: impl Fn() -> Duration + Send + Sync + 'static,
+        metrics: Arc<RpcMetrics>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                permits: Arc::new(Semaphore::new(max_subscriptions)),
+                send_timeout: Box::new(send_timeout),
+                metrics,
+            }),
+        }
+    }
+
+    /// Accept the subscription in `pending`, and notify the subscriber of each item from `stream`
+    /// (in a separate task), until the stream ends, or the client unsubscribes.
+    ///
+    /// The stream is only polled when the subscriber is ready for the next notification, so slow
+    /// subscribers apply backpressure to it. Subscribers that are not ready within the send
+    /// timeout have their subscription closed. The subscription is rejected if the service already
+    /// has as many subscriptions open as it allows.
+    pub fn subscribe<S, T>(&self, pending: PendingSubscriptionSink, stream: S)
+    where
+        S: Stream<Item = T> + Send + 'static,
+        T: Serialize + Send + 'static,
+    {
+        let permit = self.inner.permits.clone().try_acquire_owned();
+        let send_timeout = (self.inner.send_timeout)();
+        let metrics = self.inner.metrics.clone();
+
+        tokio::spawn(async move {
+            let Ok(_permit) = permit else {
+                pending
+                    .reject(ErrorObject::owned(
+                        TOO_MANY_SUBSCRIPTIONS_CODE,
+                        "Too many subscriptions, try again later",
+                        None::<()>,
+                    ))
+                    .await;
+                return;
+            };
+
+            let Ok(sink) = pending.accept().await else {
+                return;
+            };
+
+            let method = sink.method_name().to_owned();
+            metrics
+                .subscriptions_active
+                .with_label_values(&[&method])
+                .inc();
+
+            match forward(&sink, stream, send_timeout, &metrics).await {
+                Closed::Finished => debug!(%method, "Subscription finished"),
+                Closed::Unsubscribed => debug!(%method, "Subscriber unsubscribed"),
+                Closed::Failed => {}
+                Closed::Lagged => {
+                    debug!(%method, "Closing subscription, subscriber is not keeping up");
+                    metrics
+                        .subscriptions_lagged
+                        .with_label_values(&[&method])
+                        .inc();
+                }
+            }
+
+            metrics
+                .subscriptions_active
+                .with_label_values(&[&method])
+                .dec();
+        });
+    }
+}
+
+impl Default for SubscriptionsConfig {
+    fn default() -> Self {
+        Self {
+            max_subscriptions: 10_000,
+            max_subscriptions_per_connection: 100,
+            buffer_capacity: 1024,
+            send_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Send each item from `stream` to `sink`, waiting at most `send_timeout` for space in the
+/// connection's buffer for each one.
+async fn forward<S, T>(
+    sink: &SubscriptionSink,
+    stream: S,
+    send_timeout: Duration,
+    metrics: &RpcMetrics,
+) -> Closed
+where
+    S: Stream<Item = T>,
+    T: Serialize,
+{
+    let mut stream = pin!(stream);
+
+    loop {
+        let item = tokio::select! {
+            _ = sink.closed() => return Closed::Unsubscribed,
+            item = stream.next() => item,
+        };
+
+        let Some(item) = item else {
+            return Closed::Finished;
+        };
+
+        let message = match SubscriptionMessage::from_json(&item) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!(
+                    method = sink.method_name(),
+                    "Failed to serialize notification: {e}"
+                );
+                return Closed::Failed;
+            }
+        };
+
+        match sink.send_timeout(message, send_timeout).await {
+            Ok(()) => metrics
+                .subscription_notifications
+                .with_label_values(&[sink.method_name()])
+                .inc(),
+            Err(SendTimeoutError::Timeout(_)) => return Closed::Lagged,
+            Err(SendTimeoutError::Closed(_)) => return Closed::Unsubscribed,
+        }
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use futures::future::{BoxFuture, FutureExt};
+use jsonrpsee::{
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorObject, Request},
+    MethodResponse,
+};
+use tokio::time::Instant;
+use tower_layer::Layer;
+use tracing::warn;
+
+use crate::error::REQUEST_TIMEOUT_CODE;
+
+tokio::task_local! {
+    /// The deadline of the request being served, if its method has one. Database connections
+    /// handed out while serving the request limit their queries to this deadline.
+    pub(crate) static REQUEST_DEADLINE: Instant;
+}
+
+/// Deadlines for serving requests to individual methods. Methods without a deadline can run for as
+/// long as they need to.
+#[derive(Clone, Default, Debug)]
+pub struct MethodTimeoutsConfig {
+    /// Deadlines keyed by method name. Names can be given in full (`suix_queryTransactionBlocks`)
+    /// or without their namespace (`queryTransactionBlocks`), in which case the deadline applies
+    /// to the method in any namespace. Full names take precedence.
+    pub timeouts: BTreeMap<String, Duration>,
+}
+
+/// Tower Layer that adds middleware to enforce per-method deadlines on RPC requests.
+#[derive(Clone)]
+pub(crate) struct TimeoutLayer {
+    timeouts: Arc<dyn Fn(&str) -> Option<Duration> + Send + Sync>,
+}
+
+/// The Tower Service responsible for wrapping the JSON-RPC request handler with a deadline.
+pub(crate) struct TimeoutService<S> {
+    layer: TimeoutLayer,
+    inner: S,
+}
+
+/// The deadline of the request being served by the current task, if it has one.
+pub(crate) fn deadline() -> Option<Instant> {
+    REQUEST_DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+impl MethodTimeoutsConfig {
+    /// The deadline for requests to `method`, if there is one.
+    pub fn get(&self, method: &str) -> Option<Duration> {
+        if let Some(timeout) = self.timeouts.get(method) {
+            return Some(*timeout);
+        }
+
+        let (_, name) = method.split_once('_')?;
+        self.timeouts.get(name).copied()
+    }
+}
+
+impl TimeoutLayer {
+    /// Create a new timeout layer that looks up the deadline for each request's method by calling
+    /// `timeouts`. This is done on every request, so that deadlines can change while the service
+    /// is running.
+    pub(crate) fn new(timeouts: impl Fn(&str) -> Option<Duration> + Send + Sync + 'static) -> Self {
+        Self {
+            timeouts: Arc::new(timeouts),
+        }
+    }
+}
+
+impl Default for TimeoutLayer {
+    /// A layer that does not enforce any deadlines.
+    fn default() -> Self {
+        Self::new(|_| None)
+    }
+}
+
+impl<S> Layer<S> for TimeoutLayer {
+    type Service = TimeoutService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimeoutService {
+            layer: self.clone(),
+            inner,
+        }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for TimeoutService<S>
+where
+    S: RpcServiceT<'a>,
+    S::Future: Send + 'a,
+{
+    type Future = BoxFuture<'a, MethodResponse>;
+
+    /// If the request's method has a deadline and the request is not served in time, its future
+    /// is dropped, and it fails with a `REQUEST_TIMEOUT_CODE` error.
+    ///
+    /// Dropping the future does not stop queries that it has already sent to the database, so the
+    /// deadline is also made available to the request's database connections (see
+    /// [`REQUEST_DEADLINE`]), which set their statement timeouts so that the database cancels
+    /// those queries at the deadline. Queries batched by data loaders run outside the request, and
+    /// are only subject to the database's configured statement timeout.
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let Some(timeout) = (self.layer.timeouts)(request.method_name()) else {
+            return self.inner.call(request).boxed();
+        };
+
+        let id = request.id.clone();
+        let method = request.method.clone();
+        let deadline = Instant::now() + timeout;
+        let inner = REQUEST_DEADLINE.scope(deadline, self.inner.call(request));
+
+        async move {
+            if let Ok(response) = tokio::time::timeout_at(deadline, inner).await {
+                return response;
+            }
+
+            warn!(method = method.as_ref(), ?timeout, "Request timed out");
+            MethodResponse::error(
+                id,
+                ErrorObject::owned(
+                    REQUEST_TIMEOUT_CODE,
+                    format!("Request timed out after {timeout:?}"),
+                    None::<()>,
+                ),
+            )
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_timeouts() {
+        let config = MethodTimeoutsConfig {
+            timeouts: BTreeMap::from([
+                ("multiGetObjects".to_owned(), Duration::from_secs(2)),
+                ("getObject".to_owned(), Duration::from_secs(3)),
+                ("sui_getObject".to_owned(), Duration::from_secs(4)),
+            ]),
+        };
+
+        let get = |m| config.get(m);
+        assert_eq!(get("sui_multiGetObjects"), Some(Duration::from_secs(2)));
+        assert_eq!(get("multiGetObjects"), Some(Duration::from_secs(2)));
+        assert_eq!(get("sui_getObject"), Some(Duration::from_secs(4)));
+        assert_eq!(get("suix_getObject"), Some(Duration::from_secs(3)));
+        assert_eq!(get("suix_queryTransactionBlocks"), None);
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{fs::File, io::BufReader, path::Path, sync::Arc, time::Duration};
+
+use anyhow::{bail, ensure, Context as _};
+use rustls::{
+    crypto::ring,
+    pki_types::{CertificateDer, PrivateKeyDer},
+    server::WebPkiClientVerifier,
+    RootCertStore, ServerConfig,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::Semaphore,
+};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+
+use crate::config::TlsConfig;
+
+/// Connections that have not completed their TLS handshake within this long are closed.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of TLS handshakes that are performed at once. Connections beyond this wait for a
+/// turn (which counts towards their handshake timeout).
+const MAX_CONCURRENT_HANDSHAKES: usize = 1024;
+
+/// Terminates TLS on accepted connections, limiting how long each handshake can take, and how many
+/// can be in progress at once, so that clients that open connections without completing their
+/// handshakes cannot tie up the service.
+#[derive(Clone)]
+pub(crate) struct Handshaker {
+    acceptor: TlsAcceptor,
+    permits: Arc<Semaphore>,
+    timeout: Duration,
+}
+
+/// Build the configuration for serving HTTPS, from the certificate chain and private key (and
+/// optionally the CA to verify client certificates against) in the PEM files that `config` points
+/// to.
+pub(crate) fn server_config(config: &TlsConfig) -> anyhow::Result<ServerConfig> {
+    let provider = Arc::new(ring::default_provider());
+    let certs = load_certs(&config.cert_path)?;
+    let key = load_private_key(&config.key_path)?;
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .context("Failed to configure TLS protocol versions")?;
+
+    let builder = if let Some(client_ca_path) = &config.client_ca_path {
+        let mut roots = RootCertStore::empty();
+        for cert in load_certs(client_ca_path)? {
+            roots
+                .add(cert)
+                .with_context(|| format!("Invalid client CA in {}", client_ca_path.display()))?;
+        }
+
+        let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+            .build()
+            .context("Failed to set-up client certificate verification")?;
+
+        builder.with_client_cert_verifier(verifier)
+    } else {
+        builder.with_no_client_auth()
+    };
+
+    let mut server_config = builder
+        .with_single_cert(certs, key)
+        .context("Certificate and private key do not match")?;
+
+    server_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(server_config)
+}
+
+impl Handshaker {
+    pub(crate) fn new(config: Arc<ServerConfig>) -> Self {
+        Self::with_limits(config, HANDSHAKE_TIMEOUT, MAX_CONCURRENT_HANDSHAKES)
+    }
+
+    fn with_limits(config: Arc<ServerConfig>, timeout: Duration, max_concurrent: usize) -> Self {
+        Self {
+            acceptor: TlsAcceptor::from(config),
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            timeout,
+        }
+    }
+
+    /// Perform the server side of the TLS handshake on `stream`.
+    pub(crate) async fn accept<IO>(&self, stream: IO) -> anyhow::Result<TlsStream<IO>>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        let handshake = async {
+            let _permit = self.permits.acquire().await?;
+            anyhow::Ok(self.acceptor.accept(stream).await?)
+        };
+
+        tokio::time::timeout(self.timeout, handshake)
+            .await
+            .context("TLS handshake timed out")?
+    }
+}
+
+/// Load all the certificates from the PEM file at `path`.
+fn load_certs(path: &Path) -> anyhow::Result<Vec<CertificateDer<'static>>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to parse certificates from {}", path.display()))?;
+
+    ensure!(
+        !certs.is_empty(),
+        "No certificates found in {}",
+        path.display()
+    );
+    Ok(certs)
+}
+
+/// Load the first private key from the PEM file at `path`.
+fn load_private_key(path: &Path) -> anyhow::Result<PrivateKeyDer<'static>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+
+    let Some(key) = rustls_pemfile::private_key(&mut BufReader::new(file))
+        .with_context(|| format!("Failed to parse private key from {}", path.display()))?
+    else {
+        bail!("No private key found in {}", path.display());
+    };
+
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// Configuration for a server with a self-signed certificate.
+    fn self_signed() -> ServerConfig {
+        let dir = tempfile::tempdir().unwrap();
+        let cert_path = dir.path().join("cert.pem");
+        let key_path = dir.path().join("key.pem");
+
+        let rcgen::CertifiedKey { cert, key_pair } =
+            rcgen::generate_simple_self_signed(vec!["localhost".to_owned()]).unwrap();
+        fs::write(&cert_path, cert.pem()).unwrap();
+        fs::write(&key_path, key_pair.serialize_pem()).unwrap();
+
+        server_config(&TlsConfig {
+            cert_path,
+            key_path,
+            client_ca_path: None,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_handshake_timeout() {
+        let handshaker =
+            Handshaker::with_limits(Arc::new(self_signed()), Duration::from_millis(200), 1);
+
+        // A client that connects, but never starts its handshake, holds up the only permit until
+        // it times out.
+        let (_client, server) = tokio::io::duplex(1024);
+        let stalled = tokio::spawn({
+            let handshaker = handshaker.clone();
+            async move { handshaker.accept(server).await.map(|_| ()) }
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(handshaker.permits.available_permits(), 0);
+
+        // Other connections wait for a turn, within their own timeout.
+        let (_client, server) = tokio::io::duplex(1024);
+        let err = handshaker.accept(server).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{err}");
+
+        let err = stalled.await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{err}");
+        assert_eq!(handshaker.permits.available_permits(), 1);
+    }
+
+    #[test]
+    fn test_server_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let cert_path = dir.path().join("cert.pem");
+        let key_path = dir.path().join("key.pem");
+
+        let rcgen::CertifiedKey { cert, key_pair } =
+            rcgen::generate_simple_self_signed(vec!["localhost".to_owned()]).unwrap();
+        fs::write(&cert_path, cert.pem()).unwrap();
+        fs::write(&key_path, key_pair.serialize_pem()).unwrap();
+
+        let config = TlsConfig {
+            cert_path: cert_path.clone(),
+            key_path: key_path.clone(),
+            client_ca_path: None,
+        };
+
+        server_config(&config).unwrap();
+
+        // Client certificates signed by the server's own certificate.
+        let mtls = TlsConfig {
+            client_ca_path: Some(cert_path.clone()),
+            ..config.clone()
+        };
+
+        server_config(&mtls).unwrap();
+
+        // The certificate file does not contain a private key.
+        let no_key = TlsConfig {
+            key_path: cert_path,
+            ..config
+        };
+
+        assert!(server_config(&no_key).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A dry-run of the RPC's start-up, which checks that its configuration is valid, and that the
+//! external resources it refers to (the database, Bigtable, and on-chain objects) are available,
+//! without starting the service.
+
+use std::{future::Future, path::Path, sync::Arc, time::Duration};
+
+use anyhow::{anyhow, bail, Context as _};
+use prometheus::Registry;
+use serde::Serialize;
+use sui_pg_db::DbArgs;
+use sui_types::base_types::ObjectID;
+
+use crate::{
+    config::RpcConfig,
+    context::Context,
+    data::{
+        bigtable_reader::BigtableReader,
+        kv_loader::KvLoader,
+        objects::load_live,
+        pg_reader::{DbConfig, PgReader},
+    },
+    metrics::RpcMetrics,
+};
+
+/// How long to wait for each check before giving up on it.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+const CONFIG: &str = "config";
+const DATABASE: &str = "database";
+const BIGTABLE: &str = "bigtable";
+const REGISTRY_ID: &str = "name-service.registry-id";
+const REVERSE_REGISTRY_ID: &str = "name-service.reverse-registry-id";
+
+/// The outcome of validating the configuration, with one entry per check, in the order they were
+/// performed.
+#[derive(Serialize, Debug, Default)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Check {
+    /// What was checked.
+    pub name: &'static str,
+    pub status: Status,
+    /// Details of the check's outcome, or the reason it failed or was skipped.
+    pub message: String,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    Passed,
+    Failed,
+    /// The check could not be performed, because a check it depends on failed, or because the
+    /// resource it checks is not configured.
+    Skipped,
+}
+
+impl Report {
+    /// Whether all the checks that were performed passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.status != Status::Failed)
+    }
+
+    /// Record the outcome of a check, returning whether it passed.
+    fn record(&mut self, name: &'static str, result: anyhow::Result<String>) -> bool {
+        let (status, message) = match result {
+            Ok(message) => (Status::Passed, message),
+            Err(e) => (Status::Failed, format!("{e:#}")),
+        };
+
+        self.checks.push(Check {
+            name,
+            status,
+            message,
+        });
+
+        status == Status::Passed
+    }
+
+    fn skip(&mut self, names: &[&'static str], reason: &str) {
+        for &name in names {
+            self.checks.push(Check {
+                name,
+                status: Status::Skipped,
+                message: reason.to_owned(),
+            });
+        }
+    }
+}
+
+/// Read the configuration from `config_path` (or use the default configuration, if there is no
+/// path), and check that the service could start with it: The configuration must be valid (with
+/// unrecognized fields treated as errors in `strict` mode), the database and Bigtable (if it is
+/// configured) must be reachable, and the name service's registries must exist on-chain.
+pub async fn validate_config(db_args: DbArgs, config_path: Option<&Path>, strict: bool) -> Report {
+    let mut report = Report::default();
+
+    let finished = match config_path {
+        Some(path) => RpcConfig::read(path).await,
+        None => RpcConfig::from_env(),
+    }
+    .and_then(|rpc_config| rpc_config.finish_service(strict));
+
+    let (config, bigtable_config) = match finished {
+        Ok(finished) => {
+            report.record(CONFIG, Ok("Configuration is valid".to_owned()));
+            finished
+        }
+
+        Err(e) => {
+            report.record(CONFIG, Err(e));
+            report.skip(
+                &[DATABASE, BIGTABLE, REGISTRY_ID, REVERSE_REGISTRY_ID],
+                "Configuration is invalid",
+            );
+            return report;
+        }
+    };
+
+    let database = report.record(
+        DATABASE,
+        timeout(check_database(db_args.clone(), &config.db)).await,
+    );
+
+    let bigtable = if let Some(bigtable_config) = &bigtable_config {
+        let instance_id = bigtable_config.instance_id.clone();
+        report.record(BIGTABLE, timeout(check_bigtable(instance_id)).await)
+    } else {
+        report.skip(&[BIGTABLE], "Bigtable is not configured");
+        true
+    };
+
+    if !database || !bigtable {
+        report.skip(
+            &[REGISTRY_ID, REVERSE_REGISTRY_ID],
+            "Cannot read objects without access to the database and Bigtable",
+        );
+        return report;
+    }
+
+    let name_service = config.name_service.clone();
+    let registry = Registry::new();
+    let metrics = RpcMetrics::new(&registry);
+    let context = match Context::new(db_args, bigtable_config, config, metrics, &registry).await {
+        Ok(context) => context,
+        Err(e) => {
+            let message = format!("Failed to set-up access to data: {e}");
+            for name in [REGISTRY_ID, REVERSE_REGISTRY_ID] {
+                report.record(name, Err(anyhow!(message.clone())));
+            }
+            return report;
+        }
+    };
+
+    for (name, id) in [
+        (REGISTRY_ID, name_service.registry_id),
+        (REVERSE_REGISTRY_ID, name_service.reverse_registry_id),
+    ] {
+        report.record(name, timeout(check_object(&context, id)).await);
+    }
+
+    report
+}
+
+/// Check that a connection can be established to the database.
+async fn check_database(db_args: DbArgs, config: &DbConfig) -> anyhow::Result<String> {
+    let registry = Registry::new();
+    let metrics = RpcMetrics::new(&registry);
+
+    let reader = PgReader::new(db_args, config, metrics, &registry)
+        .await
+        .context("Failed to create database connection pool")?;
+
+    reader
+        .connect()
+        .await
+        .context("Failed to connect to the database")?;
+
+    Ok("Connected to the database".to_owned())
+}
+
+/// Check that the Bigtable instance can be read from.
+async fn check_bigtable(instance_id: String) -> anyhow::Result<String> {
+    let reader = BigtableReader::new(instance_id.clone())
+        .await
+        .context("Failed to create Bigtable client")?;
+
+    let loader = KvLoader::new_with_bigtable(Arc::new(reader.as_data_loader()));
+    loader
+        .load_one_checkpoint(0)
+        .await
+        .context("Failed to read from Bigtable")?;
+
+    Ok(format!("Read from Bigtable instance {instance_id}"))
+}
+
+/// Check that the object with ID `id` exists and is live.
+async fn check_object(ctx: &Context, id: ObjectID) -> anyhow::Result<String> {
+    let Some(object) = load_live(ctx, id).await? else {
+        bail!("Object {id} does not exist, or is not live");
+    };
+
+    Ok(format!("Found object {id} at version {}", object.version()))
+}
+
+async fn timeout(check: impl Future<Output = anyhow::Result<String>>) -> anyhow::Result<String> {
+    tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .with_context(|| format!("Timed out after {CHECK_TIMEOUT:?}"))?
+}
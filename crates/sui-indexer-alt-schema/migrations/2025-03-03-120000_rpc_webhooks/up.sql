@@ -0,0 +1,23 @@
+-- Webhooks that clients of the RPC service have registered, to be notified of
+-- events or transactions as they are indexed.
+CREATE TABLE IF NOT EXISTS rpc_webhooks
+(
+    webhook_id                  BIGSERIAL PRIMARY KEY,
+    -- The URL that notifications are POSTed to.
+    url                         TEXT      NOT NULL,
+    -- JSON-encoded filter deciding which events or transactions are sent to
+    -- the webhook.
+    filter                      TEXT      NOT NULL,
+    -- Key that notifications are signed with, so that the receiver can check
+    -- that they came from the RPC service.
+    secret                      BYTEA     NOT NULL,
+    -- ID of the API key that registered the webhook, which its quota of
+    -- webhooks is counted against.
+    owner                       TEXT      NOT NULL,
+    -- Number of notifications in a row that could not be delivered to the
+    -- webhook. Webhooks are removed once this gets too high.
+    failures                    INT       NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS rpc_webhooks_owner
+ON rpc_webhooks (owner);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS sum_balances;
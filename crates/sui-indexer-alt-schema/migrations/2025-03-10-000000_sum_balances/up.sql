@@ -0,0 +1,24 @@
+-- A summary table of the balances of coins owned by addresses, aggregated by
+-- owner and coin type, kept up-to-date with the latest checkpoint.
+--
+-- This is used to fetch an address's balances without reading every coin it
+-- owns. Rows are removed when an address no longer owns any coins of a type.
+--
+-- Balances are accumulated from each checkpoint's changes, so the table is
+-- only complete once it has been populated from genesis. It is backfilled by
+-- the indexer re-ingesting from genesis for its pipeline when the pipeline has
+-- no watermark yet.
+CREATE TABLE IF NOT EXISTS sum_balances
+(
+    -- The address that owns the coins (through fast-path ownership).
+    owner_id                    BYTEA         NOT NULL,
+    -- The type of the coins, as a BCS-serialized `TypeTag`. This is only the
+    -- marker type, and not the full object type (e.g. `0x0...02::sui::SUI`).
+    coin_type                   BYTEA         NOT NULL,
+    -- The sum of the balances of the coins. Stored as a NUMERIC because the
+    -- sum of many `u64` balances may not fit in a BIGINT.
+    total_balance               NUMERIC       NOT NULL,
+    -- The number of coin objects that contribute to the balance.
+    coin_count                  BIGINT        NOT NULL,
+    PRIMARY KEY (owner_id, coin_type)
+);
@@ -146,6 +146,26 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    rpc_webhooks (webhook_id) {
+        webhook_id -> Int8,
+        url -> Text,
+        filter -> Text,
+        secret -> Bytea,
+        owner -> Text,
+        failures -> Int4,
+    }
+}
+
+diesel::table! {
+    sum_balances (owner_id, coin_type) {
+        owner_id -> Bytea,
+        coin_type -> Bytea,
+        total_balance -> Numeric,
+        coin_count -> Int8,
+    }
+}
+
 diesel::table! {
     sum_displays (object_type) {
         object_type -> Bytea,
@@ -240,6 +260,8 @@ diesel::allow_tables_to_appear_in_same_query!(
     kv_transactions,
     obj_info,
     obj_versions,
+    rpc_webhooks,
+    sum_balances,
     sum_displays,
     sum_packages,
     tx_affected_addresses,
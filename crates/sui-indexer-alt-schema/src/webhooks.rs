@@ -0,0 +1,24 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema::rpc_webhooks;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = rpc_webhooks)]
+pub struct StoredWebhook {
+    pub webhook_id: i64,
+    pub url: String,
+    pub filter: String,
+    pub secret: Vec<u8>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = rpc_webhooks)]
+pub struct NewWebhook {
+    pub url: String,
+    pub filter: String,
+    pub secret: Vec<u8>,
+    pub owner: String,
+}
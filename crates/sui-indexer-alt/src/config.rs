@@ -118,6 +118,7 @@ pub struct PipelineLayer {
     pub obj_info: Option<CommitterLayer>,
 
     // Sequential pipelines
+    pub sum_balances: Option<SequentialLayer>,
     pub sum_displays: Option<SequentialLayer>,
     pub sum_packages: Option<SequentialLayer>,
 
@@ -278,6 +279,7 @@ impl PipelineLayer {
         PipelineLayer {
             coin_balance_buckets: Some(Default::default()),
             obj_info: Some(Default::default()),
+            sum_balances: Some(Default::default()),
             sum_displays: Some(Default::default()),
             sum_packages: Some(Default::default()),
             cp_sequence_numbers: Some(Default::default()),
@@ -400,6 +402,7 @@ impl Merge for PipelineLayer {
         PipelineLayer {
             coin_balance_buckets: self.coin_balance_buckets.merge(other.coin_balance_buckets),
             obj_info: self.obj_info.merge(other.obj_info),
+            sum_balances: self.sum_balances.merge(other.sum_balances),
             sum_displays: self.sum_displays.merge(other.sum_displays),
             sum_packages: self.sum_packages.merge(other.sum_packages),
             cp_sequence_numbers: self.cp_sequence_numbers.merge(other.cp_sequence_numbers),
@@ -13,6 +13,7 @@ pub(crate) mod kv_protocol_configs;
 pub(crate) mod kv_transactions;
 pub(crate) mod obj_info;
 pub(crate) mod obj_versions;
+pub(crate) mod sum_balances;
 pub(crate) mod sum_displays;
 pub(crate) mod sum_packages;
 pub(crate) mod tx_affected_addresses;
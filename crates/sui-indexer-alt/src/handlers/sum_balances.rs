@@ -0,0 +1,250 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use diesel::sql_query;
+use diesel_async::RunQueryDsl;
+use sui_indexer_alt_framework::pipeline::{sequential::Handler, Processor};
+use sui_pg_db as db;
+use sui_types::{
+    full_checkpoint_content::CheckpointData,
+    object::{Object, Owner},
+};
+
+/// The maximum number of balance deltas to write in a single statement.
+const MAX_UPDATE_CHUNK_ROWS: usize = 1000;
+
+/// This handler tracks the total balance and number of coins of each type owned by each address.
+/// Each checkpoint contributes a delta for every (owner, coin type) pair whose coins it touched:
+/// The balances of coins it consumed are subtracted, and the balances of the coins it produced are
+/// added.
+///
+/// Because balances are accumulated from deltas, the pipeline must index every checkpoint from
+/// genesis. When it is added to an existing indexer, it has no watermark, so ingestion restarts
+/// from genesis to backfill it, and readers should not trust its balances until it has caught up.
+pub(crate) struct SumBalances;
+
+/// A change to the balance of coins of one type owned by one address.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct BalanceDelta {
+    pub owner_id: Vec<u8>,
+    pub coin_type: Vec<u8>,
+    pub balance: i128,
+    pub coin_count: i64,
+}
+
+impl Processor for SumBalances {
+    const NAME: &'static str = "sum_balances";
+
+    type Value = BalanceDelta;
+
+    fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
+        let mut deltas: BTreeMap<(Vec<u8>, Vec<u8>), (i128, i64)> = BTreeMap::new();
+
+        for input in checkpoint.checkpoint_input_objects().values() {
+            if let Some((owner_id, coin_type, balance)) = coin_balance(input)? {
+                let (total, count) = deltas.entry((owner_id, coin_type)).or_default();
+                *total -= balance as i128;
+                *count -= 1;
+            }
+        }
+
+        for output in checkpoint.latest_live_output_objects() {
+            if let Some((owner_id, coin_type, balance)) = coin_balance(output)? {
+                let (total, count) = deltas.entry((owner_id, coin_type)).or_default();
+                *total += balance as i128;
+                *count += 1;
+            }
+        }
+
+        // Coins that were touched without changing their balance or owner cancel out.
+        Ok(deltas
+            .into_iter()
+            .filter(|(_, (total, count))| *total != 0 || *count != 0)
+            .map(
+                |((owner_id, coin_type), (balance, coin_count))| BalanceDelta {
+                    owner_id,
+                    coin_type,
+                    balance,
+                    coin_count,
+                },
+            )
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler for SumBalances {
+    type Batch = BTreeMap<(Vec<u8>, Vec<u8>), BalanceDelta>;
+
+    fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>) {
+        for value in values {
+            let key = (value.owner_id.clone(), value.coin_type.clone());
+            let delta = batch.entry(key).or_insert_with(|| BalanceDelta {
+                owner_id: value.owner_id,
+                coin_type: value.coin_type,
+                ..Default::default()
+            });
+
+            delta.balance += value.balance;
+            delta.coin_count += value.coin_count;
+        }
+    }
+
+    async fn commit(batch: &Self::Batch, conn: &mut db::Connection<'_>) -> Result<usize> {
+        let deltas: Vec<_> = batch
+            .values()
+            .filter(|d| d.balance != 0 || d.coin_count != 0)
+            .collect();
+
+        let mut affected = 0;
+        for chunk in deltas.chunks(MAX_UPDATE_CHUNK_ROWS) {
+            // Balances are written as literals, because NUMERIC values can't be bound without
+            // pulling in support for arbitrary precision decimals.
+            let values = chunk
+                .iter()
+                .map(|d| {
+                    format!(
+                        "('\\x{}'::BYTEA, '\\x{}'::BYTEA, {}::NUMERIC, {}::BIGINT)",
+                        hex::encode(&d.owner_id),
+                        hex::encode(&d.coin_type),
+                        d.balance,
+                        d.coin_count,
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+
+            affected += sql_query(format!(
+                "
+                INSERT INTO sum_balances (owner_id, coin_type, total_balance, coin_count)
+                VALUES {values}
+                ON CONFLICT (owner_id, coin_type) DO UPDATE SET
+                    total_balance = sum_balances.total_balance + EXCLUDED.total_balance,
+                    coin_count = sum_balances.coin_count + EXCLUDED.coin_count
+                ",
+            ))
+            .execute(conn)
+            .await?;
+
+            // Remove balances for coin types that their owners no longer hold any coins of.
+            affected += sql_query(format!(
+                "
+                WITH deltas(owner_id, coin_type, total_balance, coin_count) AS (
+                    VALUES {values}
+                )
+                DELETE FROM sum_balances s
+                USING deltas d
+                WHERE s.owner_id = d.owner_id
+                  AND s.coin_type = d.coin_type
+                  AND s.coin_count = 0
+                ",
+            ))
+            .execute(conn)
+            .await?;
+        }
+
+        Ok(affected)
+    }
+}
+
+/// The owner, BCS-serialized coin type, and balance of `object`, if it is a coin owned by an
+/// address (through fast-path ownership).
+fn coin_balance(object: &Object) -> Result<Option<(Vec<u8>, Vec<u8>, u64)>> {
+    let Some(coin_type) = object.coin_type_maybe() else {
+        return Ok(None);
+    };
+
+    let Owner::AddressOwner(owner) = object.owner() else {
+        return Ok(None);
+    };
+
+    let coin = object
+        .as_coin_maybe()
+        .ok_or_else(|| anyhow!("Failed to deserialize Coin for {}", object.id()))?;
+
+    let coin_type = bcs::to_bytes(&coin_type)
+        .map_err(|e| anyhow!("Failed to serialize coin type for {}: {e}", object.id()))?;
+
+    Ok(Some((owner.to_vec(), coin_type, coin.balance.value())))
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::dsl::sql;
+    use diesel::sql_types::Text;
+    use diesel::{ExpressionMethods, QueryDsl};
+    use sui_indexer_alt_framework::Indexer;
+    use sui_indexer_alt_schema::{schema::sum_balances, MIGRATIONS};
+    use sui_types::gas_coin::GAS;
+    use sui_types::test_checkpoint_data_builder::TestCheckpointDataBuilder;
+
+    use super::*;
+
+    /// Index `checkpoint`, committing its deltas on their own.
+    async fn commit(conn: &mut db::Connection<'_>, checkpoint: CheckpointData) {
+        let values = SumBalances.process(&Arc::new(checkpoint)).unwrap();
+        let mut batch = BTreeMap::new();
+        SumBalances::batch(&mut batch, values);
+        SumBalances::commit(&batch, conn).await.unwrap();
+    }
+
+    /// All SUI balances, as (owner, total balance, coin count), ordered by owner.
+    async fn sui_balances(conn: &mut db::Connection<'_>) -> Vec<(Vec<u8>, String, i64)> {
+        let sui = bcs::to_bytes(&GAS::type_tag()).unwrap();
+        sum_balances::table
+            .select((
+                sum_balances::owner_id,
+                sql::<Text>("total_balance::TEXT"),
+                sum_balances::coin_count,
+            ))
+            .filter(sum_balances::coin_type.eq(sui))
+            .order_by(sum_balances::owner_id)
+            .load(conn)
+            .await
+            .unwrap()
+    }
+
+    /// The expected balance row for the owner at `owner_idx`.
+    fn balance(owner_idx: u8, total: &str, count: i64) -> (Vec<u8>, String, i64) {
+        let owner = TestCheckpointDataBuilder::derive_address(owner_idx);
+        (owner.to_vec(), total.to_owned(), count)
+    }
+
+    #[tokio::test]
+    async fn test_sum_balances() {
+        let (indexer, _db) = Indexer::new_for_testing(&MIGRATIONS).await;
+        let mut conn = indexer.db().connect().await.unwrap();
+        let mut builder = TestCheckpointDataBuilder::new(0);
+
+        // Gas coins are touched but not changed, so they don't contribute to balances.
+        builder = builder
+            .start_transaction(0)
+            .create_sui_object(0, 100)
+            .create_sui_object(1, 200)
+            .finish_transaction();
+        commit(&mut conn, builder.build_checkpoint()).await;
+        assert_eq!(sui_balances(&mut conn).await, [balance(0, "300", 2)]);
+
+        // Split a coin between owners, and delete another.
+        builder = builder
+            .start_transaction(0)
+            .transfer_coin_balance(0, 2, 1, 40)
+            .delete_object(1)
+            .finish_transaction();
+        commit(&mut conn, builder.build_checkpoint()).await;
+        let mut expect = vec![balance(0, "60", 1), balance(1, "40", 1)];
+        expect.sort();
+        assert_eq!(sui_balances(&mut conn).await, expect);
+
+        // An owner that gives away their last coin no longer has a balance.
+        builder = builder
+            .start_transaction(1)
+            .transfer_object(2, 0)
+            .finish_transaction();
+        commit(&mut conn, builder.build_checkpoint()).await;
+        assert_eq!(sui_balances(&mut conn).await, [balance(0, "100", 2)]);
+    }
+}
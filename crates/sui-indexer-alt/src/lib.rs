@@ -8,10 +8,11 @@ use handlers::{
     ev_emit_mod::EvEmitMod, ev_struct_inst::EvStructInst, kv_checkpoints::KvCheckpoints,
     kv_epoch_ends::KvEpochEnds, kv_epoch_starts::KvEpochStarts, kv_feature_flags::KvFeatureFlags,
     kv_objects::KvObjects, kv_protocol_configs::KvProtocolConfigs, kv_transactions::KvTransactions,
-    obj_info::ObjInfo, obj_versions::ObjVersions, sum_displays::SumDisplays,
-    sum_packages::SumPackages, tx_affected_addresses::TxAffectedAddresses,
-    tx_affected_objects::TxAffectedObjects, tx_balance_changes::TxBalanceChanges,
-    tx_calls::TxCalls, tx_digests::TxDigests, tx_kinds::TxKinds,
+    obj_info::ObjInfo, obj_versions::ObjVersions, sum_balances::SumBalances,
+    sum_displays::SumDisplays, sum_packages::SumPackages,
+    tx_affected_addresses::TxAffectedAddresses, tx_affected_objects::TxAffectedObjects,
+    tx_balance_changes::TxBalanceChanges, tx_calls::TxCalls, tx_digests::TxDigests,
+    tx_kinds::TxKinds,
 };
 use prometheus::Registry;
 use sui_indexer_alt_framework::handlers::cp_sequence_numbers::CpSequenceNumbers;
@@ -57,6 +58,7 @@ pub async fn setup_indexer(
     } = indexer_config.finish();
 
     let PipelineLayer {
+        sum_balances,
         sum_displays,
         sum_packages,
         coin_balance_buckets,
@@ -175,6 +177,7 @@ pub async fn setup_indexer(
     add_consistent!(ObjInfo::default(), obj_info);
 
     // Summary tables (without write-ahead log)
+    add_sequential!(SumBalances, sum_balances);
     add_sequential!(SumDisplays, sum_displays);
     add_sequential!(SumPackages, sum_packages);
 
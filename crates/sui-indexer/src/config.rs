@@ -54,11 +54,7 @@ impl NameServiceOptions {
             registry_id,
             reverse_registry_id,
         } = self.clone();
-        NameServiceConfig {
-            package_address,
-            registry_id,
-            reverse_registry_id,
-        }
+        NameServiceConfig::new(package_address, registry_id, reverse_registry_id)
     }
 }
 
@@ -68,6 +64,7 @@ impl Default for NameServiceOptions {
             package_address,
             registry_id,
             reverse_registry_id,
+            ..
         } = NameServiceConfig::default();
         Self {
             package_address,
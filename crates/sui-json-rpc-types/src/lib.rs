@@ -14,6 +14,7 @@ pub use sui_event::*;
 pub use sui_extended::*;
 pub use sui_governance::*;
 pub use sui_move::*;
+pub use sui_name_service::*;
 pub use sui_object::*;
 pub use sui_protocol::*;
 pub use sui_transaction::*;
@@ -32,6 +33,7 @@ mod sui_event;
 mod sui_extended;
 mod sui_governance;
 mod sui_move;
+mod sui_name_service;
 mod sui_object;
 mod sui_protocol;
 mod sui_transaction;
@@ -0,0 +1,82 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::sui_serde::BigInt;
+
+/// The result of resolving a SuiNS name, including whether its registration has expired.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiNameResolution {
+    /// The address the name resolves to. Always `null` for names that have expired.
+    pub address: Option<SuiAddress>,
+    /// When the name's registration expires (or expired). Subdomains without their own
+    /// registration expire with their parent. `null` if the subdomain's parent has been
+    /// re-registered or removed.
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub expiration_timestamp_ms: Option<u64>,
+    pub status: SuiNameStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SuiNameStatus {
+    /// The name's registration has not expired.
+    Active,
+    /// The name's registration has expired, but it keeps resolving until its grace period ends.
+    GracePeriod,
+    /// The name's registration has expired, and it no longer resolves.
+    Expired,
+}
+
+/// A SuiNS name's full record.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiNameRecord {
+    /// The name, e.g. `example.sui`.
+    pub name: String,
+    /// The ID of the registration NFT that controls the name (or its parent's, for subdomains
+    /// without their own registration).
+    pub nft_id: ObjectID,
+    /// The address the name points to, as recorded on-chain, even if the name has expired.
+    pub target_address: Option<SuiAddress>,
+    /// See [SuiNameResolution::expiration_timestamp_ms].
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub expiration_timestamp_ms: Option<u64>,
+    pub status: SuiNameStatus,
+    /// All data stored in the record, including the avatar, content hash, and any custom keys.
+    pub data: BTreeMap<String, String>,
+}
+
+/// What is needed to render a profile card for a SuiNS name. Fields are only populated for names
+/// that have not expired.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiNameProfile {
+    /// The name, e.g. `app.example.sui`.
+    pub name: String,
+    /// The name in its short format, e.g. `app@example`.
+    pub short_name: String,
+    pub address: Option<SuiAddress>,
+    /// The NFT to display as the name's avatar. Only set if the NFT is owned by `address`.
+    pub avatar: Option<ObjectID>,
+    /// The avatar NFT's type, e.g. `0x...::nft::Nft`.
+    pub avatar_type: Option<String>,
+    pub content_hash: Option<String>,
+    pub walrus_site_id: Option<String>,
+    /// See [SuiNameResolution::expiration_timestamp_ms].
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub expiration_timestamp_ms: Option<u64>,
+    pub status: SuiNameStatus,
+}
@@ -12,7 +12,7 @@ use std::{net::SocketAddr, sync::Arc};
 use sui_core::traffic_controller::{parse_ip, policies::TrafficTally, TrafficController};
 use sui_json_rpc_api::TRANSACTION_EXECUTION_CLIENT_ERROR_CODE;
 use sui_types::traffic_control::ClientIdSource;
-use sui_types::traffic_control::Weight;
+use sui_types::traffic_control::{MethodGroup, Weight};
 use tracing::error;
 
 const TOO_MANY_REQUESTS_MSG: &str = "Too many requests";
@@ -46,11 +46,14 @@ where
         async move {
             if let Some(traffic_controller) = traffic_controller {
                 let client = req.extensions().get::<IpAddr>().cloned();
-                if let Err(response) = handle_traffic_req(&traffic_controller, &client).await {
+                let method_group = method_group(req.method_name());
+                if let Err(response) =
+                    handle_traffic_req(&traffic_controller, &client, method_group).await
+                {
                     response
                 } else {
                     let response = service.call(req).await;
-                    handle_traffic_resp(&traffic_controller, client, &response);
+                    handle_traffic_resp(&traffic_controller, client, method_group, &response);
                     response
                 }
             } else {
@@ -61,11 +64,28 @@ where
     }
 }
 
+/// The traffic control group that a JSON-RPC method belongs to.
+fn method_group(method: &str) -> MethodGroup {
+    match method {
+        "sui_executeTransactionBlock"
+        | "sui_dryRunTransactionBlock"
+        | "sui_devInspectTransactionBlock" => MethodGroup::Execution,
+        m if m.starts_with("suix_subscribe") || m.starts_with("suix_unsubscribe") => {
+            MethodGroup::Subscription
+        }
+        _ => MethodGroup::Read,
+    }
+}
+
 async fn handle_traffic_req(
     traffic_controller: &TrafficController,
     client: &Option<IpAddr>,
+    method_group: MethodGroup,
 ) -> Result<(), MethodResponse> {
-    if !traffic_controller.check(client, &None).await {
+    if !traffic_controller
+        .check_method_group(client, &None, Some(method_group))
+        .await
+    {
         // Entity in blocklist
         let err_obj =
             ErrorObject::borrowed(ErrorCode::ServerIsBusy.code(), TOO_MANY_REQUESTS_MSG, None);
@@ -78,6 +98,7 @@ async fn handle_traffic_req(
 fn handle_traffic_resp(
     traffic_controller: &TrafficController,
     client: Option<IpAddr>,
+    method_group: MethodGroup,
     response: &MethodResponse,
 ) {
     let error = response.as_error_code().map(ErrorCode::from);
@@ -97,6 +118,7 @@ fn handle_traffic_resp(
         // suitable rpc provider (or run their own). Later we may want
         // to provide a weight distribution based on the method being called.
         spam_weight: Weight::one(),
+        method_group: Some(method_group),
         timestamp: SystemTime::now(),
     });
 }
@@ -0,0 +1,109 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, ensure};
+
+use sui_types::{
+    committee::Committee,
+    messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSummary, EndOfEpochData},
+};
+
+/// The committee for the epoch after `summary`, if `summary` is the last checkpoint of its epoch.
+/// The committee is only authentic if `summary` has been verified.
+pub fn next_committee(summary: &CheckpointSummary) -> Option<Committee> {
+    let EndOfEpochData {
+        next_epoch_committee,
+        ..
+    } = summary.end_of_epoch_data.as_ref()?;
+
+    Some(Committee::new(
+        summary.epoch.checked_add(1)?,
+        next_epoch_committee.iter().cloned().collect(),
+    ))
+}
+
+/// Verify a sequence of end-of-epoch checkpoint summaries, one per epoch, starting with the epoch
+/// of `committee`, and return the committee for the epoch after the last summary.
+///
+/// Each summary is verified against the committee certified by the summary before it, so this
+/// extends trust in `committee` (e.g. the genesis committee) to the committee of a later epoch,
+/// which can then be used to verify proofs from that epoch with [`crate::verify_proof`].
+pub fn verify_committee_chain(
+    committee: &Committee,
+    end_of_epoch_summaries: &[CertifiedCheckpointSummary],
+) -> anyhow::Result<Committee> {
+    let mut committee = committee.clone();
+
+    for summary in end_of_epoch_summaries {
+        ensure!(
+            summary.epoch == committee.epoch,
+            "Expected end of epoch checkpoint for epoch {}, got checkpoint {} from epoch {}",
+            committee.epoch,
+            summary.sequence_number,
+            summary.epoch,
+        );
+
+        summary.verify_authority_signatures(&committee)?;
+
+        committee = next_committee(summary).ok_or_else(|| {
+            anyhow!(
+                "Checkpoint {} is not the last checkpoint of epoch {}",
+                summary.sequence_number,
+                summary.epoch,
+            )
+        })?;
+    }
+
+    Ok(committee)
+}
+
+/// Verify a sequence of consecutive checkpoint summaries, starting in the epoch of `committee`,
+/// and return the committee to verify the checkpoint after the last summary with.
+///
+/// Every summary must be certified by the committee of its epoch (committees are taken from the
+/// end-of-epoch data of the summaries as the chain crosses epoch boundaries), follow on from the
+/// previous summary's sequence number, and commit to its digest.
+pub fn verify_checkpoint_chain(
+    committee: &Committee,
+    summaries: &[CertifiedCheckpointSummary],
+) -> anyhow::Result<Committee> {
+    let mut committee = committee.clone();
+    let mut prev: Option<&CertifiedCheckpointSummary> = None;
+
+    for summary in summaries {
+        if let Some(prev) = prev {
+            ensure!(
+                summary.sequence_number == prev.sequence_number + 1,
+                "Checkpoint {} does not follow checkpoint {}",
+                summary.sequence_number,
+                prev.sequence_number,
+            );
+
+            if summary.previous_digest != Some(*prev.digest()) {
+                bail!(
+                    "Checkpoint {} does not commit to the digest of checkpoint {}",
+                    summary.sequence_number,
+                    prev.sequence_number,
+                );
+            }
+        }
+
+        ensure!(
+            summary.epoch == committee.epoch,
+            "Checkpoint {} is from epoch {}, expected epoch {}",
+            summary.sequence_number,
+            summary.epoch,
+            committee.epoch,
+        );
+
+        summary.verify_authority_signatures(&committee)?;
+
+        if let Some(next) = next_committee(summary) {
+            committee = next;
+        }
+
+        prev = Some(summary);
+    }
+
+    Ok(committee)
+}
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod chain;
 pub mod construct;
 pub mod proof;
 
@@ -9,3 +10,6 @@ pub use proof::*;
 
 #[doc(inline)]
 pub use construct::*;
+
+#[doc(inline)]
+pub use chain::*;
@@ -9,11 +9,13 @@ use sui_types::{
     committee::Committee,
     effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
     event::{Event, EventID},
-    messages_checkpoint::{CertifiedCheckpointSummary, CheckpointContents, EndOfEpochData},
+    messages_checkpoint::{CertifiedCheckpointSummary, CheckpointContents},
     object::Object,
     transaction::Transaction,
 };
 
+use crate::chain::next_committee;
+
 /// Define aspect of Sui state that need to be certified in a proof
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub struct ProofTarget {
@@ -115,16 +117,8 @@ pub fn verify_proof(committee: &Committee, proof: &Proof) -> anyhow::Result<()>
 
     // If the proof target is the next committee check it
     if let Some(committee) = &proof.targets.committee {
-        match &summary.end_of_epoch_data {
-            Some(EndOfEpochData {
-                next_epoch_committee,
-                ..
-            }) => {
-                // Extract the end of epoch committee
-                let next_committee_data = next_epoch_committee.iter().cloned().collect();
-                let new_committee =
-                    Committee::new(summary.epoch().checked_add(1).unwrap(), next_committee_data);
-
+        match next_committee(summary) {
+            Some(new_committee) => {
                 if new_committee != *committee {
                     return Err(anyhow!(
                         "Given committee does not match the end of epoch committee"
@@ -3,6 +3,7 @@
 
 use anyhow::anyhow;
 
+use sui_light_client::chain::{next_committee, verify_checkpoint_chain, verify_committee_chain};
 use sui_light_client::construct::construct_proof;
 use sui_light_client::proof::{verify_proof, Proof, ProofTarget};
 
@@ -271,3 +272,54 @@ async fn test_event_target_fail_bad_event() {
 
     assert!(verify_proof(&committee, &event_proof).is_err());
 }
+
+#[tokio::test]
+async fn test_committee_chain() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+    let summary = full_checkpoint.checkpoint_summary;
+
+    let next = verify_committee_chain(&committee, &[summary.clone()]).unwrap();
+    assert_eq!(Some(next), next_committee(&summary));
+
+    // An empty chain certifies the committee it started with.
+    assert_eq!(verify_committee_chain(&committee, &[]).unwrap(), committee);
+}
+
+// Fail if the chain skips, or repeats, an epoch
+#[tokio::test]
+async fn test_committee_chain_fail_wrong_epoch() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+    let summary = full_checkpoint.checkpoint_summary;
+
+    assert!(verify_committee_chain(&committee, &[summary.clone(), summary]).is_err());
+}
+
+// Fail if the chain is verified against the wrong committee
+#[tokio::test]
+async fn test_committee_chain_fail_wrong_committee() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+    let summary = full_checkpoint.checkpoint_summary;
+
+    let (other, _) = Committee::new_simple_test_committee();
+    let wrong = Committee::new(committee.epoch, other.voting_rights.into_iter().collect());
+    assert!(verify_committee_chain(&wrong, &[summary]).is_err());
+}
+
+#[tokio::test]
+async fn test_checkpoint_chain() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+    let summary = full_checkpoint.checkpoint_summary;
+
+    // Crossing the epoch boundary moves on to the next committee.
+    let next = verify_checkpoint_chain(&committee, &[summary.clone()]).unwrap();
+    assert_eq!(Some(next), next_committee(&summary));
+}
+
+// Fail if the checkpoints in the chain are not consecutive
+#[tokio::test]
+async fn test_checkpoint_chain_fail_not_consecutive() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+    let summary = full_checkpoint.checkpoint_summary;
+
+    assert!(verify_checkpoint_chain(&committee, &[summary.clone(), summary]).is_err());
+}
@@ -23,6 +23,19 @@ const DEFAULT_TLD: &str = "sui";
 const ACCEPTED_SEPARATORS: [char; 2] = ['.', '*'];
 const SUI_NEW_FORMAT_SEPARATOR: char = '@';
 
+// Well-known keys in a `NameRecord`'s `data`.
+
+/// The ID of an NFT owned by the name's target address, to display as its avatar.
+pub const AVATAR_KEY: &str = "avatar";
+/// A content hash (e.g. an IPFS CID) for the name's website.
+pub const CONTENT_HASH_KEY: &str = "content_hash";
+/// The ID of a Walrus site served for the name.
+pub const WALRUS_SITE_ID_KEY: &str = "walrus_site_id";
+
+/// The maximum depth of a name that can be registered on-chain, including the TLD.
+/// E.g. `a.b.c.d.e.f.g.h.example.sui` has depth `10`.
+pub const DEFAULT_MAX_DEPTH: u8 = 10;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Registry {
     /// The `registry` table maps `Domain` to `NameRecord`.
@@ -88,6 +101,23 @@ pub struct NameServiceConfig {
     pub package_address: SuiAddress,
     pub registry_id: ObjectID,
     pub reverse_registry_id: ObjectID,
+    /// Names deeper than this can't be registered, so they are rejected without being looked up.
+    #[serde(default = "default_max_depth")]
+    pub max_depth: u8,
+    /// How long names keep resolving after their registration expires.
+    #[serde(default)]
+    pub grace_period_ms: u64,
+}
+
+/// Whether a name is still registered.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq)]
+pub enum NameStatus {
+    /// The name's registration has not expired.
+    Active,
+    /// The name's registration has expired, but it keeps resolving until its grace period ends.
+    GracePeriod,
+    /// The name's registration has expired, and it no longer resolves.
+    Expired,
 }
 
 /// Rust version of the Move sui::table::Table type.
@@ -116,6 +146,8 @@ pub enum NameServiceError {
     LabelsEmpty,
     #[error("Name Service: Domain must include only one separator")]
     InvalidSeparator,
+    #[error("Name Service: Domain depth: {0} exceeds maximum allowed depth: {1}")]
+    ExceedsMaxDepth(u8, u8),
 
     #[error("Name Service: Name has expired.")]
     NameExpired,
@@ -187,9 +219,23 @@ impl NameServiceConfig {
             package_address,
             registry_id,
             reverse_registry_id,
+            max_depth: DEFAULT_MAX_DEPTH,
+            grace_period_ms: 0,
         }
     }
 
+    /// Check that `domain` is shallow enough to have been registered.
+    pub fn validate_depth(&self, domain: &Domain) -> Result<(), NameServiceError> {
+        if domain.depth() > self.max_depth {
+            return Err(NameServiceError::ExceedsMaxDepth(
+                domain.depth(),
+                self.max_depth,
+            ));
+        }
+
+        Ok(())
+    }
+
     pub fn record_field_id(&self, domain: &Domain) -> ObjectID {
         let domain_type_tag = Domain::type_(self.package_address);
         let domain_bytes = bcs::to_bytes(domain).unwrap();
@@ -263,6 +309,59 @@ impl NameRecord {
     pub fn is_node_expired(&self, checkpoint_timestamp_ms: u64) -> bool {
         self.expiration_timestamp_ms < checkpoint_timestamp_ms
     }
+
+    /// When the domain this record belongs to expires, following the same rules as on-chain
+    /// lookups:
+    ///
+    /// - A `node` record (a domain, or a subdomain with its own registration) carries its own
+    ///   expiry.
+    /// - A `leaf` record expires with its parent's record: `parent` must be a `node` record for
+    ///   the same registration. Leaves can only be created under nodes, so this holds however
+    ///   deeply the leaf is nested.
+    ///
+    /// `parent` is only consulted for `leaf` records, and should be `None` if the parent's record
+    /// does not exist. Returns `None` if the leaf's parent is missing or is not its parent, in
+    /// which case the leaf has already expired.
+    pub fn effective_expiration_timestamp_ms(&self, parent: Option<&NameRecord>) -> Option<u64> {
+        if !self.is_leaf_record() {
+            return Some(self.expiration_timestamp_ms);
+        }
+
+        parent
+            .filter(|parent| !parent.is_leaf_record() && parent.is_valid_leaf_parent(self))
+            .map(|parent| parent.expiration_timestamp_ms)
+    }
+
+    /// Whether this record's domain is still registered at `checkpoint_timestamp_ms`, if names
+    /// keep resolving for `grace_period_ms` after they expire.
+    pub fn status(
+        &self,
+        parent: Option<&NameRecord>,
+        checkpoint_timestamp_ms: u64,
+        grace_period_ms: u64,
+    ) -> NameStatus {
+        match self.effective_expiration_timestamp_ms(parent) {
+            Some(expiry) if checkpoint_timestamp_ms <= expiry => NameStatus::Active,
+            Some(expiry) if checkpoint_timestamp_ms <= expiry.saturating_add(grace_period_ms) => {
+                NameStatus::GracePeriod
+            }
+            _ => NameStatus::Expired,
+        }
+    }
+
+    /// The address this record's domain resolves to at `checkpoint_timestamp_ms`, failing if it
+    /// has expired, and is past its grace period (see [`NameRecord::status`]).
+    pub fn resolve(
+        &self,
+        parent: Option<&NameRecord>,
+        checkpoint_timestamp_ms: u64,
+        grace_period_ms: u64,
+    ) -> Result<Option<SuiAddress>, NameServiceError> {
+        match self.status(parent, checkpoint_timestamp_ms, grace_period_ms) {
+            NameStatus::Active | NameStatus::GracePeriod => Ok(self.target_address),
+            NameStatus::Expired => Err(NameServiceError::NameExpired),
+        }
+    }
 }
 
 impl FromStr for Domain {
@@ -337,6 +436,10 @@ impl TryFrom<MoveObject> for NameRecord {
     }
 }
 
+fn default_max_depth() -> u8 {
+    DEFAULT_MAX_DEPTH
+}
+
 /// Parses a separator from the domain string input.
 /// E.g.  `example.sui` -> `.` | example*sui -> `@` | `example*sui` -> `*`
 fn separator(s: &str) -> Result<char, NameServiceError> {
@@ -453,6 +556,109 @@ mod tests {
         assert!(name.is_node_expired(system_time));
     }
 
+    #[test]
+    fn test_resolve() {
+        let system_time: u64 = 100;
+        let nft_id = sui_types::id::ID::new(ObjectID::random());
+        let target = SuiAddress::random_for_testing_only();
+
+        let node = NameRecord {
+            nft_id: nft_id.clone(),
+            data: VecMap { contents: vec![] },
+            target_address: Some(target),
+            expiration_timestamp_ms: system_time + 10,
+        };
+
+        let leaf = NameRecord {
+            expiration_timestamp_ms: LEAF_EXPIRATION_TIMESTAMP,
+            ..node.clone()
+        };
+
+        // Nodes are checked against their own expiry, regardless of their parent.
+        assert_eq!(node.resolve(None, system_time, 0), Ok(Some(target)));
+        assert_eq!(
+            node.resolve(None, system_time + 20, 0),
+            Err(NameServiceError::NameExpired)
+        );
+
+        // Leaves are checked against their parent.
+        assert_eq!(leaf.resolve(Some(&node), system_time, 0), Ok(Some(target)));
+        assert_eq!(
+            leaf.resolve(Some(&node), system_time + 20, 0),
+            Err(NameServiceError::NameExpired)
+        );
+        assert_eq!(
+            leaf.resolve(None, system_time, 0),
+            Err(NameServiceError::NameExpired)
+        );
+
+        // ...which must be a node for the same registration.
+        let reregistered = NameRecord {
+            nft_id: sui_types::id::ID::new(ObjectID::random()),
+            ..node.clone()
+        };
+        assert_eq!(
+            leaf.resolve(Some(&reregistered), system_time, 0),
+            Err(NameServiceError::NameExpired)
+        );
+        assert_eq!(
+            leaf.resolve(Some(&leaf), system_time, 0),
+            Err(NameServiceError::NameExpired)
+        );
+    }
+
+    #[test]
+    fn test_grace_period() {
+        let system_time: u64 = 100;
+        let node = NameRecord {
+            nft_id: sui_types::id::ID::new(ObjectID::random()),
+            data: VecMap { contents: vec![] },
+            target_address: Some(SuiAddress::random_for_testing_only()),
+            expiration_timestamp_ms: system_time,
+        };
+
+        let leaf = NameRecord {
+            expiration_timestamp_ms: LEAF_EXPIRATION_TIMESTAMP,
+            ..node.clone()
+        };
+
+        assert_eq!(node.status(None, system_time, 10), NameStatus::Active);
+        assert_eq!(node.status(None, system_time + 1, 0), NameStatus::Expired);
+        assert_eq!(
+            node.status(None, system_time + 10, 10),
+            NameStatus::GracePeriod
+        );
+        assert_eq!(node.status(None, system_time + 11, 10), NameStatus::Expired);
+
+        // Leaves inherit their parent's expiry, and grace period.
+        assert_eq!(
+            leaf.effective_expiration_timestamp_ms(Some(&node)),
+            Some(system_time)
+        );
+        assert_eq!(
+            leaf.status(Some(&node), system_time + 5, 10),
+            NameStatus::GracePeriod
+        );
+        assert!(leaf.resolve(Some(&node), system_time + 5, 10).is_ok());
+
+        // ...but a leaf without a valid parent can't be in its grace period.
+        assert_eq!(leaf.effective_expiration_timestamp_ms(None), None);
+        assert_eq!(leaf.status(None, system_time, 10), NameStatus::Expired);
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let config = NameServiceConfig::default();
+        let at_limit = "a.b.c.d.e.f.g.h.example.sui".parse::<Domain>().unwrap();
+        assert!(config.validate_depth(&at_limit).is_ok());
+
+        let too_deep = "z.a.b.c.d.e.f.g.h.example.sui".parse::<Domain>().unwrap();
+        assert_eq!(
+            config.validate_depth(&too_deep),
+            Err(NameServiceError::ExceedsMaxDepth(11, DEFAULT_MAX_DEPTH))
+        );
+    }
+
     #[test]
     fn test_name_service_outputs() {
         assert_eq!("@test".parse::<Domain>().unwrap().to_string(), "test.sui");
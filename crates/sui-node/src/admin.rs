@@ -57,6 +57,20 @@ use tracing::info;
 //
 //   $ curl -X POST 'http://127.0.0.1:1337/reset-tracing'
 //
+// Drain the validator ahead of maintenance in epoch 2. The validator stops signing transactions and
+// accepting certificates, and votes to close the epoch early:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/drain?epoch=2'
+//
+// Check on the progress of a drain. Once `ready_for_handover` is true, the validator has crossed
+// the epoch boundary, stopped draining, and its keys can be moved to another machine:
+//
+//   $ curl 'http://127.0.0.1:1337/drain-status'
+//
+// Cancel a drain, and resume signing transactions:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/cancel-drain'
+//
 // Get the node's randomness partial signatures for round 123.
 //
 //  $ curl 'http://127.0.0.1:1337/randomness-partial-sigs?round=123'
@@ -75,6 +89,9 @@ const TRACING_RESET_ROUTE: &str = "/reset-tracing";
 const SET_BUFFER_STAKE_ROUTE: &str = "/set-override-buffer-stake";
 const CLEAR_BUFFER_STAKE_ROUTE: &str = "/clear-override-buffer-stake";
 const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
+const DRAIN: &str = "/drain";
+const DRAIN_STATUS: &str = "/drain-status";
+const CANCEL_DRAIN: &str = "/cancel-drain";
 const CAPABILITIES: &str = "/capabilities";
 const NODE_CONFIG: &str = "/node-config";
 const RANDOMNESS_PARTIAL_SIGS_ROUTE: &str = "/randomness-partial-sigs";
@@ -108,6 +125,9 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
             post(clear_override_protocol_upgrade_buffer_stake),
         )
         .route(FORCE_CLOSE_EPOCH, post(force_close_epoch))
+        .route(DRAIN, post(drain))
+        .route(DRAIN_STATUS, get(drain_status))
+        .route(CANCEL_DRAIN, post(cancel_drain))
         .route(TRACING_ROUTE, post(enable_tracing))
         .route(TRACING_RESET_ROUTE, post(reset_tracing))
         .route(RANDOMNESS_PARTIAL_SIGS_ROUTE, get(randomness_partial_sigs))
@@ -333,6 +353,41 @@ async fn force_close_epoch(
     }
 }
 
+async fn drain(State(state): State<Arc<AppState>>, epoch: Query<Epoch>) -> (StatusCode, String) {
+    let Query(Epoch {
+        epoch: expected_epoch,
+    }) = epoch;
+    let epoch_store = state.node.state().load_epoch_store_one_call_per_task();
+    let actual_epoch = epoch_store.epoch();
+    if actual_epoch != expected_epoch {
+        let err = SuiError::WrongEpoch {
+            expected_epoch,
+            actual_epoch,
+        };
+        return (StatusCode::BAD_REQUEST, err.to_string());
+    }
+
+    match state.node.start_drain(&epoch_store).await {
+        Ok(()) => (
+            StatusCode::OK,
+            format!("drain started in epoch {actual_epoch}\n"),
+        ),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn drain_status(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    (
+        StatusCode::OK,
+        format!("{:#?}\n", state.node.drain_status()),
+    )
+}
+
+async fn cancel_drain(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    state.node.cancel_drain();
+    (StatusCode::OK, "drain cancelled\n".to_string())
+}
+
 #[derive(Deserialize)]
 struct Round {
     round: u64,
@@ -0,0 +1,314 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hmac::{hmac_sha3_256, HmacKey};
+use fastcrypto::traits::ToFromBytes;
+use mysten_metrics::spawn_monitored_task;
+use serde::Serialize;
+use sui_config::node::{EpochChangeEvent, EpochChangeWebhookConfig};
+use sui_types::base_types::AuthorityName;
+use sui_types::committee::EpochId;
+use tracing::{debug, warn};
+
+/// Header carrying the hex-encoded HMAC-SHA3-256 of the request body, if the webhook has a secret.
+pub const SIGNATURE_HEADER: &str = "X-Sui-Webhook-Signature";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_ATTEMPTS: u32 = 3;
+
+/// The JSON body POSTed to epoch change webhooks.
+#[derive(Serialize, Debug, Clone)]
+pub struct EpochChangePayload {
+    pub event: EpochChangeEvent,
+
+    /// The node that observed the change.
+    pub authority: AuthorityName,
+
+    /// For `epoch_ended`, the epoch that ended. Otherwise, the epoch that was entered.
+    pub epoch: EpochId,
+
+    /// The protocol version of `epoch`.
+    pub protocol_version: u64,
+
+    /// The protocol version of the previous epoch, for `reconfigured` and `protocol_upgraded`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_protocol_version: Option<u64>,
+
+    /// When the node observed the change, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+}
+
+/// Delivers epoch change notifications to the webhooks in `NodeConfig::epoch_change_webhooks`.
+///
+/// Deliveries happen in the background, and are retried a few times on failure, but are
+/// otherwise best-effort: a webhook that is down does not hold up reconfiguration.
+pub struct EpochWebhooks {
+    authority: AuthorityName,
+    client: reqwest::Client,
+    webhooks: Arc<Vec<EpochChangeWebhookConfig>>,
+}
+
+impl EpochWebhooks {
+    pub fn new(authority: AuthorityName, webhooks: Vec<EpochChangeWebhookConfig>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to build webhook client");
+
+        Self {
+            authority,
+            client,
+            webhooks: Arc::new(webhooks),
+        }
+    }
+
+    pub fn notify(
+        &self,
+        event: EpochChangeEvent,
+        epoch: EpochId,
+        protocol_version: u64,
+        previous_protocol_version: Option<u64>,
+    ) {
+        if !self.webhooks.iter().any(|w| w.wants(event)) {
+            return;
+        }
+
+        let payload = EpochChangePayload {
+            event,
+            authority: self.authority,
+            epoch,
+            protocol_version,
+            previous_protocol_version,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64),
+        };
+
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to serialize epoch change payload {payload:?}: {e}");
+                return;
+            }
+        };
+
+        for webhook in self.webhooks.iter().filter(|w| w.wants(event)) {
+            let client = self.client.clone();
+            let webhook = webhook.clone();
+            let body = body.clone();
+            spawn_monitored_task!(deliver(client, webhook, body));
+        }
+    }
+}
+
+async fn deliver(client: reqwest::Client, webhook: EpochChangeWebhookConfig, body: Vec<u8>) {
+    let signature = match &webhook.secret {
+        None => None,
+        Some(secret) => match HmacKey::from_bytes(secret.as_bytes()) {
+            Ok(key) => Some(Hex::encode(hmac_sha3_256(&key, &body).to_vec())),
+            Err(e) => {
+                warn!(
+                    url = webhook.url,
+                    "Invalid epoch change webhook secret: {e}"
+                );
+                return;
+            }
+        },
+    };
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request = client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+
+        if let Some(signature) = &signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(_) => {
+                debug!(url = webhook.url, "Delivered epoch change notification");
+                return;
+            }
+            Err(e) => {
+                warn!(
+                    url = webhook.url,
+                    attempt, "Failed to deliver epoch change notification: {e}"
+                );
+                if attempt < MAX_ATTEMPTS {
+                    tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use axum::{
+        body::Bytes,
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Router,
+    };
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    /// A webhook endpoint that fails the first `failures` requests it receives, and forwards the
+    /// signature header and body of every request it receives to the returned channel.
+    async fn mock_webhook(
+        failures: u32,
+    ) -> (String, mpsc::UnboundedReceiver<(Option<String>, Bytes)>) {
+        #[derive(Clone)]
+        struct Mock {
+            failures: Arc<AtomicU32>,
+            tx: mpsc::UnboundedSender<(Option<String>, Bytes)>,
+        }
+
+        async fn handle(State(mock): State<Mock>, headers: HeaderMap, body: Bytes) -> StatusCode {
+            let signature = headers
+                .get(SIGNATURE_HEADER)
+                .map(|v| v.to_str().unwrap().to_owned());
+            mock.tx.send((signature, body)).unwrap();
+
+            let failing = mock
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |f| f.checked_sub(1))
+                .is_ok();
+
+            if failing {
+                StatusCode::INTERNAL_SERVER_ERROR
+            } else {
+                StatusCode::OK
+            }
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mock = Mock {
+            failures: Arc::new(AtomicU32::new(failures)),
+            tx,
+        };
+
+        let router = Router::new().route("/hook", post(handle)).with_state(mock);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        (format!("http://{addr}/hook"), rx)
+    }
+
+    async fn recv(
+        rx: &mut mpsc::UnboundedReceiver<(Option<String>, Bytes)>,
+    ) -> (Option<String>, Bytes) {
+        tokio::time::timeout(Duration::from_secs(30), rx.recv())
+            .await
+            .expect("Timed out waiting for webhook delivery")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_delivers_signed_payload() {
+        let (url, mut rx) = mock_webhook(0).await;
+        let secret = "webhook-secret".to_owned();
+        let webhooks = EpochWebhooks::new(
+            AuthorityName::ZERO,
+            vec![EpochChangeWebhookConfig {
+                url,
+                secret: Some(secret.clone()),
+                events: vec![],
+            }],
+        );
+
+        webhooks.notify(EpochChangeEvent::Reconfigured, 5, 42, Some(41));
+        let (signature, body) = recv(&mut rx).await;
+
+        let key = HmacKey::from_bytes(secret.as_bytes()).unwrap();
+        let expect = Hex::encode(hmac_sha3_256(&key, &body).to_vec());
+        assert_eq!(signature, Some(expect));
+
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["event"], "reconfigured");
+        assert_eq!(payload["epoch"], 5);
+        assert_eq!(payload["protocol_version"], 42);
+        assert_eq!(payload["previous_protocol_version"], 41);
+    }
+
+    #[tokio::test]
+    async fn test_filters_events() {
+        let (url, mut rx) = mock_webhook(0).await;
+        let webhooks = EpochWebhooks::new(
+            AuthorityName::ZERO,
+            vec![EpochChangeWebhookConfig {
+                url,
+                secret: None,
+                events: vec![EpochChangeEvent::EpochEnded],
+            }],
+        );
+
+        // The webhook is not interested in reconfiguration, so only the end of the epoch is
+        // delivered to it.
+        webhooks.notify(EpochChangeEvent::Reconfigured, 6, 42, Some(42));
+        webhooks.notify(EpochChangeEvent::EpochEnded, 6, 42, None);
+
+        let (signature, body) = recv(&mut rx).await;
+        assert_eq!(signature, None);
+
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["event"], "epoch_ended");
+        assert!(payload.get("previous_protocol_version").is_none());
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retries_failed_delivery() {
+        let (url, mut rx) = mock_webhook(1).await;
+        let webhooks = EpochWebhooks::new(
+            AuthorityName::ZERO,
+            vec![EpochChangeWebhookConfig {
+                url,
+                secret: None,
+                events: vec![],
+            }],
+        );
+
+        webhooks.notify(EpochChangeEvent::EpochEnded, 7, 42, None);
+
+        // The first attempt fails, and the same payload is delivered again.
+        let (_, first) = recv(&mut rx).await;
+        let (_, second) = recv(&mut rx).await;
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let (url, mut rx) = mock_webhook(u32::MAX).await;
+        let webhooks = EpochWebhooks::new(
+            AuthorityName::ZERO,
+            vec![EpochChangeWebhookConfig {
+                url,
+                secret: None,
+                events: vec![],
+            }],
+        );
+
+        webhooks.notify(EpochChangeEvent::EpochEnded, 8, 42, None);
+        for _ in 0..MAX_ATTEMPTS {
+            recv(&mut rx).await;
+        }
+
+        // No further attempts are made after the last one.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,320 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::VecDeque;
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use fastcrypto::encoding::{Base64, Encoding};
+use futures::{Stream, StreamExt};
+use mysten_metrics::spawn_monitored_task;
+use sui_config::node::{EventFirehoseConfig, EventFirehoseFormat, EventFirehoseSink};
+use sui_core::subscription_handler::SubscriptionHandler;
+use sui_json_rpc_types::{EventFilter, SuiEvent};
+use sui_types::event::{Event, EventID};
+use sui_types::parse_sui_struct_tag;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// Delay before the first attempt to re-open the sink after a write fails. The delay doubles with
+/// each consecutive failure, up to `MAX_RETRY_DELAY`.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(100);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// How long a single write may take before the sink is considered unavailable.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The number of events that are buffered while the sink is unavailable. Once the buffer is full,
+/// the oldest events are dropped to make room for new ones.
+const MAX_BUFFERED_EVENTS: usize = 100_000;
+
+/// Start streaming events from `subscription_handler` to the sink described by `config`.
+///
+/// Fails if the configured event types cannot be parsed. Failures to open or write to the sink
+/// are retried with backoff, resuming from the first event that has not been written, while new
+/// events are buffered. Events are only dropped if the buffer fills up before the sink recovers.
+pub fn start_event_firehose(
+    config: EventFirehoseConfig,
+    subscription_handler: Arc<SubscriptionHandler>,
+) -> anyhow::Result<JoinHandle<()>> {
+    let filter = if config.event_types.is_empty() {
+        EventFilter::All([])
+    } else {
+        EventFilter::Any(
+            config
+                .event_types
+                .iter()
+                .map(|t| {
+                    parse_sui_struct_tag(t)
+                        .map(EventFilter::MoveEventType)
+                        .with_context(|| format!("Invalid event firehose event type: {t}"))
+                })
+                .collect::<anyhow::Result<_>>()?,
+        )
+    };
+
+    info!(sink = ?config.sink, format = ?config.format, "Starting event firehose");
+    let events = subscription_handler.subscribe_events(filter);
+    let firehose = run(config, events, MAX_BUFFERED_EVENTS);
+    Ok(spawn_monitored_task!(firehose))
+}
+
+/// Write `events` to the configured sink until the stream ends and every buffered event has been
+/// written. Events are read from the stream while the sink is being retried, so that the
+/// subscription does not fall behind, and up to `max_buffered` of them are held until they can be
+/// written.
+async fn run(
+    config: EventFirehoseConfig,
+    events: impl Stream<Item = SuiEvent>,
+    max_buffered: usize,
+) {
+    let mut events = pin!(events);
+    let mut events_done = false;
+
+    // Encoded events that have not been written yet, in order. The front of the queue is the
+    // cursor: it is only removed once it has been written to the sink.
+    let mut pending: VecDeque<(EventID, String)> = VecDeque::new();
+    let mut sink = None;
+    let mut retry_delay = INITIAL_RETRY_DELAY;
+    let mut retry_at = Instant::now();
+
+    while !(events_done && pending.is_empty()) {
+        tokio::select! {
+            event = events.next(), if !events_done => {
+                let Some(event) = event else {
+                    events_done = true;
+                    continue;
+                };
+
+                if !config.senders.is_empty() && !config.senders.contains(&event.sender) {
+                    continue;
+                }
+
+                match encode(&event, config.format) {
+                    Ok(line) => pending.push_back((event.id, line)),
+                    Err(e) => warn!(id = ?event.id, "Failed to encode event for firehose: {e}"),
+                }
+
+                if pending.len() > max_buffered {
+                    if let Some((id, _)) = pending.pop_front() {
+                        warn!(?id, "Event firehose buffer is full, dropping event");
+                    }
+                }
+            }
+
+            _ = tokio::time::sleep_until(retry_at), if !pending.is_empty() => {
+                match flush(&config.sink, &mut sink, &mut pending).await {
+                    Ok(()) => retry_delay = INITIAL_RETRY_DELAY,
+                    Err(e) => {
+                        sink = None;
+                        warn!(
+                            sink = ?config.sink,
+                            cursor = ?pending.front().map(|(id, _)| id),
+                            ?retry_delay,
+                            "Failed to write to event firehose sink: {e}",
+                        );
+
+                        retry_at = Instant::now() + retry_delay;
+                        retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Write all `pending` events to `sink`, opening it first if necessary. Events are removed from
+/// `pending` as they are written, so that if writing fails, the next attempt resumes from the
+/// first event that was not written.
+async fn flush(
+    config: &EventFirehoseSink,
+    sink: &mut Option<Box<dyn AsyncWrite + Send + Unpin>>,
+    pending: &mut VecDeque<(EventID, String)>,
+) -> std::io::Result<()> {
+    let s = match sink {
+        Some(s) => s,
+        None => sink.insert(open(config).await?),
+    };
+
+    while let Some((_, line)) = pending.front() {
+        tokio::time::timeout(WRITE_TIMEOUT, write_line(s.as_mut(), line))
+            .await
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "write timed out"))??;
+        pending.pop_front();
+    }
+
+    Ok(())
+}
+
+fn encode(event: &SuiEvent, format: EventFirehoseFormat) -> anyhow::Result<String> {
+    Ok(match format {
+        EventFirehoseFormat::Json => serde_json::to_string(event)?,
+        EventFirehoseFormat::Bcs => {
+            let raw = Event {
+                package_id: event.package_id,
+                transaction_module: event.transaction_module.clone(),
+                sender: event.sender,
+                type_: event.type_.clone(),
+                contents: event.bcs.bytes().to_vec(),
+            };
+
+            Base64::encode(bcs::to_bytes(&(&event.id, &raw))?)
+        }
+    })
+}
+
+async fn open(sink: &EventFirehoseSink) -> std::io::Result<Box<dyn AsyncWrite + Send + Unpin>> {
+    Ok(match sink {
+        EventFirehoseSink::File(path) => Box::new(
+            tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .await?,
+        ),
+        EventFirehoseSink::Tcp(addr) => Box::new(tokio::net::TcpStream::connect(addr).await?),
+    })
+}
+
+async fn write_line(sink: &mut (dyn AsyncWrite + Send + Unpin), line: &str) -> std::io::Result<()> {
+    sink.write_all(line.as_bytes()).await?;
+    sink.write_all(b"\n").await?;
+    sink.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::mpsc;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    fn config(sink: EventFirehoseSink) -> EventFirehoseConfig {
+        EventFirehoseConfig {
+            sink,
+            format: EventFirehoseFormat::Json,
+            event_types: vec![],
+            senders: vec![],
+        }
+    }
+
+    fn events(n: usize) -> Vec<SuiEvent> {
+        (0..n).map(|_| SuiEvent::random_for_testing()).collect()
+    }
+
+    fn ids(lines: &[String]) -> Vec<EventID> {
+        lines
+            .iter()
+            .map(|l| serde_json::from_str::<SuiEvent>(l).unwrap().id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_writes_events_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let events = events(3);
+
+        let mut config = config(EventFirehoseSink::File(path.clone()));
+        config.senders = vec![events[0].sender, events[2].sender];
+
+        let stream = futures::stream::iter(events.clone());
+        run(config, stream, MAX_BUFFERED_EVENTS).await;
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = contents.lines().map(String::from).collect();
+        assert_eq!(ids(&lines), vec![events[0].id, events[2].id]);
+    }
+
+    #[tokio::test]
+    async fn test_resumes_after_sink_recovers() {
+        // Reserve an address, but do not listen on it until some events have been sent, so that
+        // the first attempts to connect to the sink fail.
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let (tx, rx) = mpsc::unbounded();
+        let task = tokio::spawn(run(
+            config(EventFirehoseSink::Tcp(addr)),
+            rx,
+            MAX_BUFFERED_EVENTS,
+        ));
+
+        let events = events(5);
+        for event in &events[..3] {
+            tx.unbounded_send(event.clone()).unwrap();
+        }
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let listener = TcpListener::bind(addr).await.unwrap();
+        for event in &events[3..] {
+            tx.unbounded_send(event.clone()).unwrap();
+        }
+        drop(tx);
+
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut lines = BufReader::new(socket).lines();
+        let mut received = vec![];
+        while let Some(line) = lines.next_line().await.unwrap() {
+            received.push(line);
+            if received.len() == events.len() {
+                break;
+            }
+        }
+
+        // Every event is delivered, in order, despite the sink being unavailable at first.
+        let expect: Vec<_> = events.iter().map(|e| e.id).collect();
+        assert_eq!(ids(&received), expect);
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_drops_oldest_when_buffer_full() {
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let (tx, rx) = mpsc::unbounded();
+        let task = tokio::spawn(run(config(EventFirehoseSink::Tcp(addr)), rx, 2));
+
+        let events = events(4);
+        for event in &events {
+            tx.unbounded_send(event.clone()).unwrap();
+        }
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let listener = TcpListener::bind(addr).await.unwrap();
+        drop(tx);
+
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut lines = BufReader::new(socket).lines();
+        let mut received = vec![];
+        while let Some(line) = lines.next_line().await.unwrap() {
+            received.push(line);
+        }
+
+        assert_eq!(ids(&received), vec![events[2].id, events[3].id]);
+        task.await.unwrap();
+    }
+
+    #[test]
+    fn test_encode_bcs() {
+        let event = SuiEvent::random_for_testing();
+
+        let line = encode(&event, EventFirehoseFormat::Bcs).unwrap();
+        let (id, raw): (EventID, Event) = bcs::from_bytes(&Base64::decode(&line).unwrap()).unwrap();
+        assert_eq!(id, event.id);
+        assert_eq!(raw.sender, event.sender);
+        assert_eq!(raw.type_, event.type_);
+    }
+}
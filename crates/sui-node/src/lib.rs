@@ -10,6 +10,7 @@ use anemo_tower::trace::TraceLayer;
 use anyhow::anyhow;
 use anyhow::Result;
 use arc_swap::ArcSwap;
+use fastcrypto_zkp::bn254::zk_login::parse_jwks;
 use fastcrypto_zkp::bn254::zk_login::JwkId;
 use fastcrypto_zkp::bn254::zk_login::OIDCProvider;
 use futures::future::BoxFuture;
@@ -40,6 +41,7 @@ use sui_core::execution_cache::build_execution_cache;
 use sui_core::state_accumulator::StateAccumulatorMetrics;
 use sui_core::storage::RestReadStore;
 use sui_core::traffic_controller::metrics::TrafficControllerMetrics;
+use sui_core::validator_drain::ValidatorDrainStatus;
 use sui_json_rpc::bridge_api::BridgeReadApi;
 use sui_json_rpc_api::JsonRpcMetrics;
 use sui_network::randomness;
@@ -69,7 +71,9 @@ use mysten_network::server::ServerBuilder;
 use mysten_service::server_timing::server_timing_middleware;
 use sui_archival::reader::ArchiveReaderBalancer;
 use sui_archival::writer::ArchiveWriter;
-use sui_config::node::{DBCheckpointConfig, RunWithRange};
+use sui_config::node::{
+    CustomOidcProviderConfig, DBCheckpointConfig, EpochChangeEvent, RunWithRange,
+};
 use sui_config::node_config_metrics::NodeConfigMetrics;
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
 use sui_config::{ConsensusConfig, NodeConfig};
@@ -147,9 +151,12 @@ use sui_types::supported_protocol_versions::SupportedProtocolVersions;
 use typed_store::rocks::default_db_options;
 use typed_store::DBMetrics;
 
+use crate::epoch_webhooks::EpochWebhooks;
 use crate::metrics::{GrpcMetrics, SuiNodeMetrics};
 
 pub mod admin;
+mod epoch_webhooks;
+mod event_firehose;
 mod handle;
 pub mod metrics;
 
@@ -279,6 +286,9 @@ pub struct SuiNode {
     auth_agg: Arc<ArcSwap<AuthorityAggregator<NetworkAuthorityClient>>>,
 
     subscription_service_checkpoint_sender: Option<tokio::sync::mpsc::Sender<CheckpointData>>,
+
+    /// Notifies operator webhooks of epoch changes.
+    epoch_webhooks: EpochWebhooks,
 }
 
 impl fmt::Debug for SuiNode {
@@ -366,75 +376,176 @@ impl SuiNode {
             true
         }
 
-        // metrics is:
-        //  pub struct SuiNodeMetrics {
-        //      pub jwk_requests: IntCounterVec,
-        //      pub jwk_request_errors: IntCounterVec,
-        //      pub total_jwks: IntCounterVec,
-        //      pub unique_jwks: IntCounterVec,
-        //  }
-
         for p in supported_providers.into_iter() {
-            let provider_str = p.to_string();
-            let epoch_store = epoch_store.clone();
-            let consensus_adapter = consensus_adapter.clone();
-            let metrics = metrics.clone();
-            spawn_monitored_task!(epoch_store.clone().within_alive_epoch(
-                async move {
-                    // note: restart-safe de-duplication happens after consensus, this is
-                    // just best-effort to reduce unneeded submissions.
-                    let mut seen = HashSet::new();
-                    loop {
-                        info!("fetching JWK for provider {:?}", p);
-                        metrics.jwk_requests.with_label_values(&[&provider_str]).inc();
-                        match Self::fetch_jwks(authority, &p).await {
-                            Err(e) => {
-                                metrics.jwk_request_errors.with_label_values(&[&provider_str]).inc();
-                                warn!("Error when fetching JWK for provider {:?} {:?}", p, e);
-                                // Retry in 30 seconds
-                                tokio::time::sleep(Duration::from_secs(30)).await;
-                                continue;
-                            }
-                            Ok(mut keys) => {
-                                metrics.total_jwks
-                                    .with_label_values(&[&provider_str])
-                                    .inc_by(keys.len() as u64);
-
-                                keys.retain(|(id, jwk)| {
-                                    validate_jwk(&metrics, &p, id, jwk) &&
-                                    !epoch_store.jwk_active_in_current_epoch(id, jwk) &&
-                                    seen.insert((id.clone(), jwk.clone()))
-                                });
-
-                                metrics.unique_jwks
-                                    .with_label_values(&[&provider_str])
-                                    .inc_by(keys.len() as u64);
-
-                                // prevent oauth providers from sending too many keys,
-                                // inadvertently or otherwise
-                                if keys.len() > MAX_JWK_KEYS_PER_FETCH {
-                                    warn!("Provider {:?} sent too many JWKs, only the first {} will be used", p, MAX_JWK_KEYS_PER_FETCH);
-                                    keys.truncate(MAX_JWK_KEYS_PER_FETCH);
-                                }
-
-                                for (id, jwk) in keys.into_iter() {
-                                    info!("Submitting JWK to consensus: {:?}", id);
-
-                                    let txn = ConsensusTransaction::new_jwk_fetched(authority, id, jwk);
-                                    consensus_adapter.submit(txn, None, &epoch_store)
-                                        .tap_err(|e| warn!("Error when submitting JWKs to consensus {:?}", e))
-                                        .ok();
-                                }
-                            }
-                        }
-                        tokio::time::sleep(fetch_interval).await;
+            let validate = {
+                let metrics = metrics.clone();
+                let p = p.clone();
+                move |id: &JwkId, jwk: &JWK| validate_jwk(&metrics, &p, id, jwk)
+            };
+
+            Self::spawn_jwk_updater_task(
+                p.to_string(),
+                fetch_interval,
+                move || {
+                    let p = p.clone();
+                    async move { Self::fetch_jwks(authority, &p).await }
+                },
+                validate,
+                metrics.clone(),
+                authority,
+                epoch_store.clone(),
+                consensus_adapter.clone(),
+            );
+        }
+
+        for provider in config.zklogin_custom_oidc_providers.iter().cloned() {
+            let fetch_interval = provider
+                .cache_ttl_seconds
+                .map_or(fetch_interval, Duration::from_secs);
+            info!(
+                ?fetch_interval,
+                "Starting JWK updater task for custom provider: {:?}", provider.iss
+            );
+
+            let validate = {
+                let metrics = metrics.clone();
+                let provider_str = provider.iss.clone();
+                move |id: &JwkId, jwk: &JWK| {
+                    if check_total_jwk_size(id, jwk) {
+                        return true;
                     }
+
+                    warn!(
+                        "JWK {:?} (retrieved from {:?}) is too large",
+                        id, provider_str
+                    );
+                    metrics
+                        .invalid_jwks
+                        .with_label_values(&[&provider_str])
+                        .inc();
+                    false
                 }
-                .instrument(error_span!("jwk_updater_task", epoch)),
-            ));
+            };
+
+            Self::spawn_jwk_updater_task(
+                provider.iss.clone(),
+                fetch_interval,
+                move || {
+                    let provider = provider.clone();
+                    async move { Self::fetch_custom_jwks(&provider).await }
+                },
+                validate,
+                metrics.clone(),
+                authority,
+                epoch_store.clone(),
+                consensus_adapter.clone(),
+            );
         }
     }
 
+    /// Spawn a task for the rest of the epoch that fetches JWKs from `provider` every
+    /// `fetch_interval`, and submits the keys that pass `validate`, and are not already active, to
+    /// consensus.
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_jwk_updater_task<F, Fut>(
+        provider: String,
+        fetch_interval: Duration,
+        fetch: F,
+        validate: impl Fn(&JwkId, &JWK) -> bool + Send + 'static,
+        metrics: Arc<SuiNodeMetrics>,
+        authority: AuthorityName,
+        epoch_store: Arc<AuthorityPerEpochStore>,
+        consensus_adapter: Arc<ConsensusAdapter>,
+    ) where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = SuiResult<Vec<(JwkId, JWK)>>> + Send + 'static,
+    {
+        let epoch = epoch_store.epoch();
+        spawn_monitored_task!(epoch_store.clone().within_alive_epoch(
+            async move {
+                // note: restart-safe de-duplication happens after consensus, this is
+                // just best-effort to reduce unneeded submissions.
+                let mut seen = HashSet::new();
+                loop {
+                    info!("fetching JWK for provider {:?}", provider);
+                    metrics.jwk_requests.with_label_values(&[&provider]).inc();
+                    let mut keys = match fetch().await {
+                        Ok(keys) => keys,
+                        Err(e) => {
+                            metrics
+                                .jwk_request_errors
+                                .with_label_values(&[&provider])
+                                .inc();
+                            warn!(
+                                "Error when fetching JWK for provider {:?} {:?}",
+                                provider, e
+                            );
+                            // Retry in 30 seconds
+                            tokio::time::sleep(Duration::from_secs(30)).await;
+                            continue;
+                        }
+                    };
+
+                    metrics
+                        .total_jwks
+                        .with_label_values(&[&provider])
+                        .inc_by(keys.len() as u64);
+
+                    keys.retain(|(id, jwk)| {
+                        validate(id, jwk)
+                            && !epoch_store.jwk_active_in_current_epoch(id, jwk)
+                            && seen.insert((id.clone(), jwk.clone()))
+                    });
+
+                    metrics
+                        .unique_jwks
+                        .with_label_values(&[&provider])
+                        .inc_by(keys.len() as u64);
+
+                    // prevent oauth providers from sending too many keys,
+                    // inadvertently or otherwise
+                    if keys.len() > MAX_JWK_KEYS_PER_FETCH {
+                        warn!(
+                            "Provider {:?} sent too many JWKs, only the first {} will be used",
+                            provider, MAX_JWK_KEYS_PER_FETCH
+                        );
+                        keys.truncate(MAX_JWK_KEYS_PER_FETCH);
+                    }
+
+                    for (id, jwk) in keys.into_iter() {
+                        info!("Submitting JWK to consensus: {:?}", id);
+
+                        let txn = ConsensusTransaction::new_jwk_fetched(authority, id, jwk);
+                        consensus_adapter
+                            .submit(txn, None, &epoch_store)
+                            .tap_err(|e| warn!("Error when submitting JWKs to consensus {:?}", e))
+                            .ok();
+                    }
+
+                    tokio::time::sleep(fetch_interval).await;
+                }
+            }
+            .instrument(error_span!("jwk_updater_task", epoch)),
+        ));
+    }
+
+    /// Fetch the JSON Web Key Set of a custom OpenID provider.
+    async fn fetch_custom_jwks(
+        provider: &CustomOidcProviderConfig,
+    ) -> SuiResult<Vec<(JwkId, JWK)>> {
+        let bytes = reqwest::Client::new()
+            .get(&provider.jwks_uri)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|_| SuiError::JWKRetrievalError)?
+            .bytes()
+            .await
+            .map_err(|_| SuiError::JWKRetrievalError)?;
+
+        parse_custom_jwks(&bytes, &provider.iss)
+    }
+
     pub async fn start_async(
         config: NodeConfig,
         registry_service: RegistryService,
@@ -764,6 +875,16 @@ impl SuiNode {
         // Start the loop that receives new randomness and generates transactions for it.
         RandomnessRoundReceiver::spawn(state.clone(), randomness_rx);
 
+        if let Some(firehose_config) = config.event_firehose.clone() {
+            if index_store.is_none() {
+                warn!("Event firehose requires index processing, no events will be streamed");
+            }
+            event_firehose::start_event_firehose(
+                firehose_config,
+                state.subscription_handler.clone(),
+            )?;
+        }
+
         if config
             .expensive_safety_check_config
             .enable_secondary_index_checks()
@@ -868,6 +989,8 @@ impl SuiNode {
         // setup shutdown channel
         let (shutdown_channel, _) = broadcast::channel::<Option<RunWithRange>>(1);
 
+        let epoch_webhooks = EpochWebhooks::new(state.name, config.epoch_change_webhooks.clone());
+
         let node = Self {
             config,
             validator_components: Mutex::new(validator_components),
@@ -899,6 +1022,7 @@ impl SuiNode {
 
             auth_agg,
             subscription_service_checkpoint_sender,
+            epoch_webhooks,
         };
 
         info!("SuiNode started!");
@@ -943,6 +1067,30 @@ impl SuiNode {
         Ok(())
     }
 
+    /// Drain the validator ahead of maintenance: stop signing new transactions and accepting new
+    /// certificates, and vote to close the current epoch so that certificates already submitted to
+    /// consensus are sequenced and executed. The validator is ready to hand over its keys once the
+    /// network crosses the next epoch boundary (see [Self::drain_status]).
+    pub async fn start_drain(&self, epoch_store: &Arc<AuthorityPerEpochStore>) -> SuiResult {
+        self.close_epoch(epoch_store).await?;
+        self.state.drain_info.start(epoch_store.epoch());
+        Ok(())
+    }
+
+    /// Resume signing transactions. Certificates will only be accepted again from the next epoch
+    /// onwards, because closing an epoch cannot be undone.
+    pub fn cancel_drain(&self) {
+        self.state.drain_info.cancel();
+    }
+
+    pub fn drain_status(&self) -> ValidatorDrainStatus {
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        self.state.drain_info.status(
+            epoch_store.epoch(),
+            epoch_store.pending_consensus_certificates_count(),
+        )
+    }
+
     pub fn clear_override_protocol_upgrade_buffer_stake(&self, epoch: EpochId) -> SuiResult {
         self.state
             .clear_override_protocol_upgrade_buffer_stake(epoch)
@@ -1796,6 +1944,14 @@ impl SuiNode {
                 "Finished executing all checkpoints in epoch. About to reconfigure the system."
             );
 
+            let prev_protocol_version = cur_epoch_store.protocol_config().version.as_u64();
+            self.epoch_webhooks.notify(
+                EpochChangeEvent::EpochEnded,
+                cur_epoch_store.epoch(),
+                prev_protocol_version,
+                None,
+            );
+
             fail_point_async!("reconfig_delay");
 
             // We save the connection monitor status map regardless of validator / fullnode status
@@ -1963,6 +2119,22 @@ impl SuiNode {
                 .await?;
             }
 
+            let protocol_version = new_epoch_store.protocol_config().version.as_u64();
+            self.epoch_webhooks.notify(
+                EpochChangeEvent::Reconfigured,
+                next_epoch,
+                protocol_version,
+                Some(prev_protocol_version),
+            );
+            if protocol_version != prev_protocol_version {
+                self.epoch_webhooks.notify(
+                    EpochChangeEvent::ProtocolUpgraded,
+                    next_epoch,
+                    protocol_version,
+                    Some(prev_protocol_version),
+                );
+            }
+
             epoch_store = new_epoch_store;
             info!("Reconfiguration finished");
         }
@@ -2099,6 +2271,18 @@ impl SpawnOnce {
     }
 }
 
+/// Parse the JSON Web Key Set of a custom OpenID provider, recording its keys against that
+/// provider's issuer, `iss`. Key sets are parsed the same way as those of well-known providers, but
+/// `parse_jwks` records keys against the issuer of the (well-known) provider it is given, so the
+/// keys are re-keyed by the custom issuer.
+fn parse_custom_jwks(bytes: &[u8], iss: &str) -> SuiResult<Vec<(JwkId, JWK)>> {
+    let keys = parse_jwks(bytes, &OIDCProvider::Google).map_err(|_| SuiError::JWKRetrievalError)?;
+    Ok(keys
+        .into_iter()
+        .map(|(id, jwk)| (JwkId::new(iss.to_owned(), id.kid), jwk))
+        .collect())
+}
+
 /// Notify state-sync that a new list of trusted peers are now available.
 fn send_trusted_peer_change(
     config: &NodeConfig,
@@ -2314,3 +2498,69 @@ fn max_tx_per_checkpoint(protocol_config: &ProtocolConfig) -> usize {
 fn max_tx_per_checkpoint(_: &ProtocolConfig) -> usize {
     2
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use axum::{http::StatusCode, routing::get, Router};
+    use sui_types::zk_login_util::DEFAULT_JWK_BYTES;
+
+    use super::*;
+
+    const ISS: &str = "https://auth.example.com";
+
+    /// Serve `DEFAULT_JWK_BYTES` as a JSON Web Key Set at `/jwks`, returning the server's address.
+    async fn jwks_server() -> SocketAddr {
+        let router = Router::new()
+            .route("/jwks", get(|| async { DEFAULT_JWK_BYTES }))
+            .route("/error", get(|| async { StatusCode::SERVICE_UNAVAILABLE }));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        addr
+    }
+
+    fn provider(jwks_uri: String) -> CustomOidcProviderConfig {
+        CustomOidcProviderConfig {
+            iss: ISS.to_owned(),
+            jwks_uri,
+            cache_ttl_seconds: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_custom_jwks() {
+        let expect = parse_jwks(DEFAULT_JWK_BYTES, &OIDCProvider::Twitch).unwrap();
+        let keys = parse_custom_jwks(DEFAULT_JWK_BYTES, ISS).unwrap();
+
+        assert!(!keys.is_empty());
+        assert_eq!(keys.len(), expect.len());
+        for ((id, jwk), (expect_id, expect_jwk)) in keys.iter().zip(&expect) {
+            assert_eq!(id.iss, ISS);
+            assert_eq!(id.kid, expect_id.kid);
+            assert_eq!(jwk, expect_jwk);
+        }
+
+        assert!(matches!(
+            parse_custom_jwks(b"{\"not\": \"keys\"}", ISS),
+            Err(SuiError::JWKRetrievalError)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_custom_jwks() {
+        let addr = jwks_server().await;
+
+        let keys = SuiNode::fetch_custom_jwks(&provider(format!("http://{addr}/jwks")))
+            .await
+            .unwrap();
+        assert_eq!(keys, parse_custom_jwks(DEFAULT_JWK_BYTES, ISS).unwrap());
+
+        let err = SuiNode::fetch_custom_jwks(&provider(format!("http://{addr}/error")))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SuiError::JWKRetrievalError));
+    }
+}
@@ -55,7 +55,7 @@ pub struct Resolver<S> {
 
 /// Optional configuration that imposes limits on the work that the resolver can do for each
 /// request.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Limits {
     /// Maximum recursion depth through type parameters.
     pub max_type_argument_depth: usize,
@@ -769,6 +769,11 @@ impl Package {
         &self.modules
     }
 
+    /// The version of the package object this package was loaded from.
+    pub fn version(&self) -> SequenceNumber {
+        self.version
+    }
+
     fn data_def(&self, module_name: &str, datatype_name: &str) -> Result<DataDef> {
         let module = self.module(module_name)?;
         let Some(data_def) = module.data_def(datatype_name)? else {
@@ -8,7 +8,7 @@ use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
 use diesel_async::{
     pooled_connection::{
         bb8::{Pool, PooledConnection},
-        AsyncDieselConnectionManager,
+        AsyncDieselConnectionManager, PoolError,
     },
     AsyncPgConnection, RunQueryDsl,
 };
@@ -33,6 +33,20 @@ pub struct DbArgs {
     pub connection_timeout_ms: u64,
 }
 
+/// Tuning for the connection pool that is not exposed through [DbArgs]. Fields that are not set
+/// keep the pool's defaults.
+#[derive(Debug, Clone, Default)]
+pub struct PoolConfig {
+    /// Minimum number of idle connections to keep in the pool.
+    pub min_idle: Option<u32>,
+
+    /// Connections that have been idle for longer than this are closed.
+    pub idle_timeout: Option<Duration>,
+
+    /// Queries that take longer than this are cancelled by the database.
+    pub statement_timeout: Option<Duration>,
+}
+
 #[derive(Clone)]
 pub struct Db {
     read_only: bool,
@@ -54,16 +68,24 @@ impl Db {
     pub async fn for_write(config: DbArgs) -> anyhow::Result<Self> {
         Ok(Self {
             read_only: false,
-            pool: pool(config).await?,
+            pool: pool(config, PoolConfig::default()).await?,
         })
     }
 
     /// Construct a new DB connection pool that defaults to read-only transactions. Instances of
     /// [Db] can be cloned to share access to the same pool.
     pub async fn for_read(config: DbArgs) -> anyhow::Result<Self> {
+        Self::for_read_with_pool(config, PoolConfig::default()).await
+    }
+
+    /// Like [Self::for_read], but with additional tuning for the connection pool.
+    pub async fn for_read_with_pool(
+        config: DbArgs,
+        pool_config: PoolConfig,
+    ) -> anyhow::Result<Self> {
         Ok(Self {
             read_only: true,
-            pool: pool(config).await?,
+            pool: pool(config, pool_config).await?,
         })
     }
 
@@ -198,14 +220,39 @@ pub async fn reset_database<S: MigrationSource<Pg> + Send + Sync + 'static>(
     Ok(())
 }
 
-async fn pool(args: DbArgs) -> anyhow::Result<Pool<AsyncPgConnection>> {
+async fn pool(args: DbArgs, config: PoolConfig) -> anyhow::Result<Pool<AsyncPgConnection>> {
     let manager = AsyncDieselConnectionManager::new(args.database_url.as_str());
 
-    Ok(Pool::builder()
+    let mut builder = Pool::builder()
         .max_size(args.db_connection_pool_size)
         .connection_timeout(args.connection_timeout())
-        .build(manager)
-        .await?)
+        .min_idle(config.min_idle);
+
+    if let Some(idle_timeout) = config.idle_timeout {
+        builder = builder.idle_timeout(Some(idle_timeout));
+    }
+
+    if let Some(statement_timeout) = config.statement_timeout {
+        builder = builder.connection_customizer(Box::new(StatementTimeout(statement_timeout)));
+    }
+
+    Ok(builder.build(manager).await?)
+}
+
+/// Sets the statement timeout on every connection when it is added to the pool.
+#[derive(Debug)]
+struct StatementTimeout(Duration);
+
+#[async_trait::async_trait]
+impl bb8::CustomizeConnection<AsyncPgConnection, PoolError> for StatementTimeout {
+    async fn on_acquire(&self, conn: &mut AsyncPgConnection) -> Result<(), PoolError> {
+        diesel::sql_query(format!("SET statement_timeout = {}", self.0.as_millis()))
+            .execute(conn)
+            .await
+            .map_err(PoolError::QueryError)?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
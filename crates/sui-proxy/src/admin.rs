@@ -7,6 +7,7 @@ use crate::middleware::{
     expect_content_length, expect_mysten_proxy_header, expect_valid_public_key,
 };
 use crate::peers::{AllowedPeer, SuiNodeProvider};
+use crate::relabel::Relabeler;
 use crate::var;
 use anyhow::Error;
 use anyhow::Result;
@@ -96,6 +97,7 @@ pub fn app(
     labels: Labels,
     client: ReqwestClient,
     relay: HistogramRelay,
+    relabeler: Relabeler,
     allower: Option<SuiNodeProvider>,
 ) -> Router {
     // build our application with a route and our sender mpsc
@@ -121,6 +123,7 @@ pub fn app(
             20
         ))))
         .layer(Extension(relay))
+        .layer(Extension(relabeler))
         .layer(Extension(labels))
         .layer(Extension(client))
         .layer(
@@ -18,6 +18,9 @@ pub struct ProxyConfig {
     pub static_peers: Option<StaticPeerValidationConfig>,
     pub metrics_address: String,
     pub histogram_address: String,
+    /// rules to relabel, filter and aggregate metrics before they are forwarded
+    #[serde(default)]
+    pub relabel: RelabelConfig,
 }
 
 #[serde_as]
@@ -83,6 +86,86 @@ pub struct StaticPubKey {
     pub peer_id: String,
 }
 
+/// RelabelConfig controls the cardinality of the metrics we forward upstream.  Rules are applied in
+/// order to every metric received from a node, after the network and host labels are added.  Series
+/// that end up with identical labels after relabeling (for example, after dropping a per-peer label)
+/// are aggregated into one.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct RelabelConfig {
+    #[serde(default)]
+    pub rules: Vec<RelabelRule>,
+    /// how to combine gauges that end up with identical labels. counters and histograms are
+    /// always summed
+    #[serde(default)]
+    pub gauge_aggregation: GaugeAggregation,
+}
+
+/// RelabelRule is modeled after prometheus' relabel_config.  All regexes are anchored at both ends.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RelabelRule {
+    pub action: RelabelAction,
+    /// only apply this rule to metric families whose name matches this regex
+    pub metric: Option<String>,
+    /// labels whose values are joined with the separator and matched against the regex.  for
+    /// keep and drop, the metric name is matched if this is empty
+    #[serde(default)]
+    pub source_labels: Vec<String>,
+    #[serde(default = "separator_default")]
+    pub separator: String,
+    /// for labeldrop and labelkeep, this is matched against label names
+    #[serde(default = "regex_default")]
+    pub regex: String,
+    /// the label to write the replacement to, for the replace action
+    pub target_label: Option<String>,
+    /// the value to write to the target label, may refer to regex capture groups (eg $1).  if it
+    /// expands to an empty string, the target label is removed
+    #[serde(default = "replacement_default")]
+    pub replacement: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelabelAction {
+    /// set the target label from the source labels
+    Replace,
+    /// drop series that don't match
+    Keep,
+    /// drop series that match
+    Drop,
+    /// remove labels whose names match
+    LabelDrop,
+    /// remove labels whose names don't match
+    LabelKeep,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GaugeAggregation {
+    #[default]
+    Sum,
+    Max,
+    Min,
+}
+
+/// the default separator used to join source label values
+fn separator_default() -> String {
+    ";".to_string()
+}
+
+/// the default regex for relabel rules, matches anything
+fn regex_default() -> String {
+    "(.*)".to_string()
+}
+
+/// the default replacement for relabel rules, the whole match
+fn replacement_default() -> String {
+    "$1".to_string()
+}
+
 /// the default idle worker per host (reqwest to remote write url call)
 fn pool_max_idle_per_host_default() -> usize {
     8
@@ -5,6 +5,7 @@ use crate::consumer::{convert_to_remote_write, populate_labels, NodeMetric};
 use crate::histogram_relay::HistogramRelay;
 use crate::middleware::LenDelimProtobuf;
 use crate::peers::AllowedPeer;
+use crate::relabel::Relabeler;
 use axum::{
     extract::{ConnectInfo, Extension},
     http::StatusCode,
@@ -47,6 +48,7 @@ pub async fn publish_metrics(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Extension(AllowedPeer { name, public_key }): Extension<AllowedPeer>,
     Extension(relay): Extension<HistogramRelay>,
+    Extension(relabeler): Extension<Relabeler>,
     LenDelimProtobuf(data): LenDelimProtobuf,
 ) -> (StatusCode, &'static str) {
     HANDLER_HITS
@@ -56,6 +58,7 @@ pub async fn publish_metrics(
         .with_label_values(&["publish_metrics", &name])
         .start_timer();
     let data = populate_labels(name, labels.network, labels.inventory_hostname, data);
+    let data = relabeler.relabel(data);
     relay.submit(data.clone());
     let response = convert_to_remote_write(
         client.clone(),
@@ -9,6 +9,7 @@ pub mod metrics;
 pub mod middleware;
 pub mod peers;
 pub mod prom_to_mimir;
+pub mod relabel;
 pub mod remote_write;
 
 /// var extracts environment variables at runtime with a default fallback value
@@ -37,6 +38,7 @@ mod tests {
     use crate::admin::Labels;
     use crate::histogram_relay::HistogramRelay;
     use crate::prom_to_mimir::tests::*;
+    use crate::relabel::Relabeler;
 
     use crate::{admin::CertKeyPair, config::RemoteWriteConfig, peers::SuiNodeProvider};
     use axum::http::StatusCode;
@@ -134,6 +136,7 @@ mod tests {
             },
             client,
             HistogramRelay::new(),
+            Relabeler::default(),
             Some(allower.clone()),
         );
 
@@ -249,6 +252,7 @@ mod tests {
             },
             client,
             HistogramRelay::new(),
+            Relabeler::default(),
             Some(allower.clone()),
         );
 
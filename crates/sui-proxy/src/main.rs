@@ -11,6 +11,7 @@ use sui_proxy::{
     },
     config::load,
     histogram_relay, metrics,
+    relabel::Relabeler,
 };
 use sui_tls::TlsAcceptor;
 use telemetry_subscribers::TelemetryConfig;
@@ -74,6 +75,7 @@ async fn main() -> Result<()> {
     let metrics_listener = std::net::TcpListener::bind(config.metrics_address).unwrap();
     let acceptor = TlsAcceptor::new(tls_config);
     let client = make_reqwest_client(config.remote_write, APP_USER_AGENT);
+    let relabeler = Relabeler::new(&config.relabel)?;
     let histogram_relay = histogram_relay::start_prometheus_server(histogram_listener);
     let registry_service = metrics::start_prometheus_server(metrics_listener);
     let prometheus_registry = registry_service.default_registry();
@@ -92,6 +94,7 @@ async fn main() -> Result<()> {
         },
         client,
         histogram_relay,
+        relabeler,
         allower,
     );
 
@@ -0,0 +1,396 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::config::{GaugeAggregation, RelabelAction, RelabelConfig, RelabelRule};
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use prometheus::proto::{self, MetricFamily, MetricType};
+use prometheus::{register_counter_vec, CounterVec};
+use protobuf::RepeatedField;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+static RELABEL_OPS: Lazy<CounterVec> = Lazy::new(|| {
+    register_counter_vec!(
+        "relabel_operations",
+        "Number of series dropped or aggregated by relabeling, by operation.",
+        &["operation"]
+    )
+    .unwrap()
+});
+
+/// Relabeler applies the relabel rules from our config to metric families.  Regexes are compiled
+/// once, when the config is loaded.
+#[derive(Clone, Default)]
+pub struct Relabeler {
+    rules: Arc<Vec<Rule>>,
+    gauge_aggregation: GaugeAggregation,
+}
+
+struct Rule {
+    action: RelabelAction,
+    metric: Option<Regex>,
+    source_labels: Vec<String>,
+    separator: String,
+    regex: Regex,
+    target_label: Option<String>,
+    replacement: String,
+}
+
+/// anchor a regex at both ends, like prometheus does
+fn anchored(regex: &str) -> Result<Regex> {
+    Regex::new(&format!("^(?:{regex})$")).with_context(|| format!("invalid regex {regex:?}"))
+}
+
+impl Rule {
+    fn new(rule: &RelabelRule) -> Result<Self> {
+        if rule.action == RelabelAction::Replace && rule.target_label.is_none() {
+            bail!("relabel rule with action replace requires a target-label");
+        }
+        Ok(Self {
+            action: rule.action,
+            metric: rule.metric.as_deref().map(anchored).transpose()?,
+            source_labels: rule.source_labels.clone(),
+            separator: rule.separator.clone(),
+            regex: anchored(&rule.regex)?,
+            target_label: rule.target_label.clone(),
+            replacement: rule.replacement.clone(),
+        })
+    }
+
+    fn applies_to(&self, name: &str) -> bool {
+        self.metric.as_ref().map_or(true, |m| m.is_match(name))
+    }
+
+    /// the value the regex is matched against for this series
+    fn source_value(&self, name: &str, m: &proto::Metric) -> String {
+        let by_name = matches!(self.action, RelabelAction::Keep | RelabelAction::Drop);
+        if self.source_labels.is_empty() && by_name {
+            return name.to_owned();
+        }
+        self.source_labels
+            .iter()
+            .map(|source| {
+                m.get_label()
+                    .iter()
+                    .find(|l| l.get_name() == source)
+                    .map_or("", |l| l.get_value())
+            })
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+
+    /// apply this rule to a series, returning false if the series should be dropped
+    fn apply(&self, name: &str, m: &mut proto::Metric) -> bool {
+        match self.action {
+            RelabelAction::Keep => self.regex.is_match(&self.source_value(name, m)),
+            RelabelAction::Drop => !self.regex.is_match(&self.source_value(name, m)),
+            RelabelAction::LabelDrop => {
+                m.mut_label().retain(|l| !self.regex.is_match(l.get_name()));
+                true
+            }
+            RelabelAction::LabelKeep => {
+                m.mut_label().retain(|l| self.regex.is_match(l.get_name()));
+                true
+            }
+            RelabelAction::Replace => {
+                let value = self.source_value(name, m);
+                let Some(captures) = self.regex.captures(&value) else {
+                    return true;
+                };
+                let mut replacement = String::new();
+                captures.expand(&self.replacement, &mut replacement);
+
+                // target_label is checked when the rule is created
+                let target = self.target_label.as_deref().unwrap();
+                m.mut_label().retain(|l| l.get_name() != target);
+                if !replacement.is_empty() {
+                    let mut label = proto::LabelPair::default();
+                    label.set_name(target.into());
+                    label.set_value(replacement);
+                    m.mut_label().push(label);
+                }
+                true
+            }
+        }
+    }
+}
+
+impl Relabeler {
+    pub fn new(config: &RelabelConfig) -> Result<Self> {
+        let rules = config
+            .rules
+            .iter()
+            .map(Rule::new)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            rules: Arc::new(rules),
+            gauge_aggregation: config.gauge_aggregation,
+        })
+    }
+
+    /// relabel our metric families, dropping families that end up without any series, and
+    /// aggregating series that end up with identical labels
+    pub fn relabel(&self, data: Vec<MetricFamily>) -> Vec<MetricFamily> {
+        if self.rules.is_empty() {
+            return data;
+        }
+        data.into_iter()
+            .filter_map(|mut mf| {
+                let name = mf.get_name().to_owned();
+                let rules: Vec<_> = self.rules.iter().filter(|r| r.applies_to(&name)).collect();
+                if rules.is_empty() {
+                    return Some(mf);
+                }
+
+                let metrics = mf.take_metric().into_vec();
+                let received = metrics.len();
+                let kept: Vec<_> = metrics
+                    .into_iter()
+                    .filter_map(|mut m| rules.iter().all(|r| r.apply(&name, &mut m)).then_some(m))
+                    .collect();
+                RELABEL_OPS
+                    .with_label_values(&["dropped"])
+                    .inc_by((received - kept.len()) as f64);
+
+                if kept.is_empty() {
+                    return None;
+                }
+                let aggregated = self.aggregate(mf.get_field_type(), kept);
+                mf.set_metric(RepeatedField::from_vec(aggregated));
+                Some(mf)
+            })
+            .collect()
+    }
+
+    /// merge series with identical labels, keeping the order in which they were first seen
+    fn aggregate(&self, field_type: MetricType, metrics: Vec<proto::Metric>) -> Vec<proto::Metric> {
+        let mut index: HashMap<Vec<(String, String)>, usize> = HashMap::new();
+        let mut result: Vec<proto::Metric> = Vec::with_capacity(metrics.len());
+        for m in metrics {
+            let mut key: Vec<_> = m
+                .get_label()
+                .iter()
+                .map(|l| (l.get_name().to_owned(), l.get_value().to_owned()))
+                .collect();
+            key.sort();
+            match index.get(&key) {
+                Some(&i) => {
+                    RELABEL_OPS.with_label_values(&["aggregated"]).inc();
+                    merge(field_type, self.gauge_aggregation, &mut result[i], &m);
+                }
+                None => {
+                    index.insert(key, result.len());
+                    result.push(m);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// merge the values of `other` into `into`.  summaries lose their quantiles, which cannot be
+/// combined
+fn merge(
+    field_type: MetricType,
+    gauge_aggregation: GaugeAggregation,
+    into: &mut proto::Metric,
+    other: &proto::Metric,
+) {
+    match field_type {
+        MetricType::COUNTER => {
+            let value = into.get_counter().get_value() + other.get_counter().get_value();
+            into.mut_counter().set_value(value);
+        }
+        MetricType::GAUGE => {
+            let (a, b) = (into.get_gauge().get_value(), other.get_gauge().get_value());
+            let value = match gauge_aggregation {
+                GaugeAggregation::Sum => a + b,
+                GaugeAggregation::Max => a.max(b),
+                GaugeAggregation::Min => a.min(b),
+            };
+            into.mut_gauge().set_value(value);
+        }
+        MetricType::HISTOGRAM => {
+            let other = other.get_histogram();
+            let h = into.mut_histogram();
+            h.set_sample_count(h.get_sample_count() + other.get_sample_count());
+            h.set_sample_sum(h.get_sample_sum() + other.get_sample_sum());
+            for bucket in h.mut_bucket().iter_mut() {
+                if let Some(o) = other
+                    .get_bucket()
+                    .iter()
+                    .find(|o| o.get_upper_bound() == bucket.get_upper_bound())
+                {
+                    bucket.set_cumulative_count(
+                        bucket.get_cumulative_count() + o.get_cumulative_count(),
+                    );
+                }
+            }
+        }
+        MetricType::SUMMARY => {
+            let other = other.get_summary();
+            let s = into.mut_summary();
+            s.set_sample_count(s.get_sample_count() + other.get_sample_count());
+            s.set_sample_sum(s.get_sample_sum() + other.get_sample_sum());
+            s.clear_quantile();
+        }
+        MetricType::UNTYPED => {
+            let value = into.get_untyped().get_value() + other.get_untyped().get_value();
+            into.mut_untyped().set_value(value);
+        }
+    }
+    into.set_timestamp_ms(into.get_timestamp_ms().max(other.get_timestamp_ms()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prom_to_mimir::tests::{
+        create_counter, create_gauge, create_labels, create_metric_counter, create_metric_family,
+        create_metric_gauge,
+    };
+
+    fn rule(action: RelabelAction) -> RelabelRule {
+        RelabelRule {
+            action,
+            metric: None,
+            source_labels: vec![],
+            separator: ";".into(),
+            regex: "(.*)".into(),
+            target_label: None,
+            replacement: "$1".into(),
+        }
+    }
+
+    fn counters(name: &str, series: Vec<(Vec<(&str, &str)>, f64)>) -> MetricFamily {
+        create_metric_family(
+            name,
+            "help",
+            Some(MetricType::COUNTER),
+            RepeatedField::from_vec(
+                series
+                    .into_iter()
+                    .map(|(labels, value)| {
+                        create_metric_counter(
+                            RepeatedField::from_vec(create_labels(labels)),
+                            create_counter(value),
+                        )
+                    })
+                    .collect(),
+            ),
+        )
+    }
+
+    #[test]
+    fn test_drop_metric_family() {
+        let relabeler = Relabeler::new(&RelabelConfig {
+            rules: vec![RelabelRule {
+                regex: "narwhal_.*".into(),
+                ..rule(RelabelAction::Drop)
+            }],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let data = relabeler.relabel(vec![
+            counters("narwhal_sent", vec![(vec![], 1.0)]),
+            counters("sui_sent", vec![(vec![], 1.0)]),
+        ]);
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].get_name(), "sui_sent");
+    }
+
+    #[test]
+    fn test_labeldrop_aggregates_counters() {
+        let relabeler = Relabeler::new(&RelabelConfig {
+            rules: vec![RelabelRule {
+                metric: Some("consensus_.*".into()),
+                regex: "peer".into(),
+                ..rule(RelabelAction::LabelDrop)
+            }],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let data = relabeler.relabel(vec![
+            counters(
+                "consensus_sent",
+                vec![
+                    (vec![("host", "a"), ("peer", "1")], 1.0),
+                    (vec![("host", "a"), ("peer", "2")], 2.0),
+                    (vec![("host", "b"), ("peer", "1")], 4.0),
+                ],
+            ),
+            counters("other_sent", vec![(vec![("peer", "1")], 1.0)]),
+        ]);
+
+        let metrics = data[0].get_metric();
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0].get_label(), &create_labels(vec![("host", "a")]));
+        assert_eq!(metrics[0].get_counter().get_value(), 3.0);
+        assert_eq!(metrics[1].get_label(), &create_labels(vec![("host", "b")]));
+        assert_eq!(metrics[1].get_counter().get_value(), 4.0);
+
+        // metrics outside of the rule's scope are untouched
+        assert_eq!(
+            data[1].get_metric()[0].get_label(),
+            &create_labels(vec![("peer", "1")])
+        );
+    }
+
+    #[test]
+    fn test_replace_and_gauge_aggregation() {
+        let relabeler = Relabeler::new(&RelabelConfig {
+            rules: vec![
+                RelabelRule {
+                    source_labels: vec!["host".into()],
+                    regex: "(validator)-\\d+".into(),
+                    target_label: Some("role".into()),
+                    ..rule(RelabelAction::Replace)
+                },
+                RelabelRule {
+                    regex: "role".into(),
+                    ..rule(RelabelAction::LabelKeep)
+                },
+            ],
+            gauge_aggregation: GaugeAggregation::Max,
+        })
+        .unwrap();
+
+        let gauges = create_metric_family(
+            "height",
+            "help",
+            Some(MetricType::GAUGE),
+            RepeatedField::from_vec(
+                [("validator-0", 5.0), ("validator-1", 7.0)]
+                    .into_iter()
+                    .map(|(host, value)| {
+                        create_metric_gauge(
+                            RepeatedField::from_vec(create_labels(vec![("host", host)])),
+                            create_gauge(value),
+                        )
+                    })
+                    .collect(),
+            ),
+        );
+
+        let data = relabeler.relabel(vec![gauges]);
+        let metrics = data[0].get_metric();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(
+            metrics[0].get_label(),
+            &create_labels(vec![("role", "validator")])
+        );
+        assert_eq!(metrics[0].get_gauge().get_value(), 7.0);
+    }
+
+    #[test]
+    fn test_replace_requires_target_label() {
+        assert!(Relabeler::new(&RelabelConfig {
+            rules: vec![rule(RelabelAction::Replace)],
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
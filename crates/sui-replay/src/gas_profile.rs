@@ -0,0 +1,273 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversion of the gas profiles written by the Move VM's profiler (in speedscope's format) into
+//! folded stacks, the input format of flamegraph tools like `flamegraph.pl` and `inferno`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::Context;
+use move_binary_format::file_format::FunctionDefinitionIndex;
+use move_binary_format::CompiledModule;
+use move_bytecode_source_map::utils::source_map_from_file;
+use move_core_types::account_address::AccountAddress;
+use serde::Deserialize;
+use walkdir::WalkDir;
+
+const OPEN_FRAME: &str = "O";
+const CLOSE_FRAME: &str = "C";
+
+/// The subset of speedscope's file format that the Move VM's profiler writes.
+#[derive(Deserialize)]
+struct ProfileFile {
+    shared: Shared,
+    profiles: Vec<Profile>,
+}
+
+#[derive(Deserialize)]
+struct Shared {
+    frames: Vec<Frame>,
+}
+
+#[derive(Deserialize)]
+struct Frame {
+    /// The fully-qualified name of the frame.
+    file: String,
+}
+
+#[derive(Deserialize)]
+struct Profile {
+    #[serde(rename = "type")]
+    ty: String,
+    #[serde(default)]
+    events: Vec<Event>,
+    #[serde(default)]
+    samples: Vec<Vec<usize>>,
+    #[serde(default)]
+    weights: Vec<u64>,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    #[serde(rename = "type")]
+    ty: String,
+    frame: usize,
+    at: u64,
+}
+
+/// Where Move functions are defined in source, read from the build directories of packages.
+#[derive(Default)]
+pub struct SourceLocations {
+    /// Location by fully-qualified function name (`0x<address>::<module>::<function>`).
+    by_function: HashMap<String, String>,
+
+    /// Locations by `<module>::<function>`, for functions whose package was built without its
+    /// on-chain address.
+    by_name: HashMap<String, Vec<String>>,
+}
+
+impl SourceLocations {
+    /// Read the source locations of all functions in the packages built into `build_dirs`. Each
+    /// directory is a package's build output (e.g. `build/MyPackage`), and must contain its
+    /// bytecode, source maps and sources, as written by `sui move build`.
+    pub fn load(build_dirs: &[PathBuf]) -> anyhow::Result<Self> {
+        let mut locations = Self::default();
+
+        for build_dir in build_dirs {
+            let bytecode_dir = build_dir.join("bytecode_modules");
+            for entry in WalkDir::new(&bytecode_dir) {
+                let entry = entry.with_context(|| format!("Failed to read {build_dir:?}"))?;
+                let path = entry.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("mv") {
+                    continue;
+                }
+
+                let relative = path.strip_prefix(&bytecode_dir)?;
+                let source_map = build_dir
+                    .join("source_maps")
+                    .join(relative)
+                    .with_extension("mvsm");
+                let source = build_dir
+                    .join("sources")
+                    .join(relative)
+                    .with_extension("move");
+
+                if !source_map.exists() || !source.exists() {
+                    continue;
+                }
+
+                locations
+                    .add_module(path, &source_map, &source)
+                    .with_context(|| format!("Failed to read source locations for {path:?}"))?;
+            }
+        }
+
+        Ok(locations)
+    }
+
+    fn add_module(
+        &mut self,
+        bytecode: &Path,
+        source_map: &Path,
+        source: &Path,
+    ) -> anyhow::Result<()> {
+        let module = CompiledModule::deserialize_with_defaults(&fs::read(bytecode)?)?;
+        let source_map = source_map_from_file(source_map)?;
+        let text = fs::read_to_string(source)?;
+
+        let address = module.address();
+        let module_name = module.name();
+
+        for (idx, def) in module.function_defs().iter().enumerate() {
+            let handle = module.function_handle_at(def.function);
+            let function_name = module.identifier_at(handle.name);
+
+            let Ok(function_map) =
+                source_map.get_function_source_map(FunctionDefinitionIndex(idx as u16))
+            else {
+                continue;
+            };
+
+            let start = function_map.definition_location.start() as usize;
+            let line = text.get(..start).map_or(0, |t| t.matches('\n').count()) + 1;
+            let location = format!("{}:{line}", source.display());
+
+            let name = format!("{module_name}::{function_name}");
+            self.by_function
+                .insert(format!("0x{address}::{name}"), location.clone());
+            self.by_name.entry(name).or_default().push(location);
+        }
+
+        Ok(())
+    }
+
+    /// The source location of the function with the fully-qualified name `function`, if it is
+    /// known unambiguously.
+    fn get(&self, function: &str) -> Option<&str> {
+        if let Some(location) = self.by_function.get(function) {
+            return Some(location);
+        }
+
+        let (_, name) = function.split_once("::")?;
+        match self.by_name.get(name)?.as_slice() {
+            [location] => Some(location),
+            _ => None,
+        }
+    }
+}
+
+/// Convert the profile at `profile` into folded stacks, written next to it: the gas used by each
+/// stack of functions goes into `<profile>.gas.folded`, and the number of instructions it executed
+/// goes into `<profile>.instructions.folded` (if the profile counted instructions). Functions are
+/// annotated with their location in source, if it is found in `sources`.
+///
+/// Returns the paths of the files written.
+pub fn write_folded_profiles(
+    profile: &Path,
+    sources: &SourceLocations,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let file: ProfileFile = serde_json::from_slice(&fs::read(profile)?)
+        .with_context(|| format!("Failed to parse gas profile {profile:?}"))?;
+
+    let names: Vec<_> = file
+        .shared
+        .frames
+        .iter()
+        .map(|frame| frame_name(&frame.file, sources))
+        .collect();
+
+    let mut written = vec![];
+    for p in &file.profiles {
+        let (stacks, suffix) = match p.ty.as_str() {
+            "evented" => (gas_by_stack(&p.events), "gas.folded"),
+            "sampled" => (
+                p.samples
+                    .iter()
+                    .cloned()
+                    .zip(p.weights.iter().copied())
+                    .collect(),
+                "instructions.folded",
+            ),
+            _ => continue,
+        };
+
+        let output = profile.with_extension(suffix);
+        write_folded(&output, &names, &stacks)
+            .with_context(|| format!("Failed to write folded profile {output:?}"))?;
+        written.push(output);
+    }
+
+    Ok(written)
+}
+
+/// Attribute the gas used between consecutive events to the stack of frames open at the time.
+fn gas_by_stack(events: &[Event]) -> BTreeMap<Vec<usize>, u64> {
+    let mut stacks = BTreeMap::new();
+    let mut stack = vec![];
+    let mut last = 0;
+
+    for event in events {
+        if event.at > last && !stack.is_empty() {
+            *stacks.entry(stack.clone()).or_default() += event.at - last;
+        }
+        last = event.at;
+
+        match event.ty.as_str() {
+            OPEN_FRAME => stack.push(event.frame),
+            CLOSE_FRAME => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    stacks
+}
+
+fn write_folded(
+    output: &Path,
+    names: &[String],
+    stacks: &BTreeMap<Vec<usize>, u64>,
+) -> anyhow::Result<()> {
+    let mut out = std::io::BufWriter::new(fs::File::create(output)?);
+    for (stack, weight) in stacks {
+        if *weight == 0 {
+            continue;
+        }
+
+        let frames: Vec<_> = stack
+            .iter()
+            .map(|idx| names.get(*idx).map_or("?", String::as_str))
+            .collect();
+
+        writeln!(out, "{} {weight}", frames.join(";"))?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// The name to show for a frame in folded stacks: Move functions are shown with their address in
+/// short form, followed by their location in source, if known.
+fn frame_name(frame: &str, sources: &SourceLocations) -> String {
+    // Folded stacks separate frames with semi-colons.
+    let frame = frame.replace(';', ",");
+
+    let Some((address, rest)) = frame.split_once("::") else {
+        return frame;
+    };
+
+    let Ok(address) = AccountAddress::from_str(address) else {
+        return frame;
+    };
+
+    let name = format!("0x{}::{rest}", address.short_str_lossless());
+    match sources.get(&frame) {
+        Some(location) => format!("{name} [{location}]"),
+        None => name,
+    }
+}
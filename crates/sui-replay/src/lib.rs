@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::anyhow;
 use async_recursion::async_recursion;
 use clap::Parser;
 use config::ReplayableNetworkConfigSet;
@@ -23,7 +24,7 @@ use crate::replay::ProtocolVersionSummary;
 use move_vm_config::runtime::get_default_output_filepath;
 use std::env;
 use std::io::BufRead;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use sui_config::node::ExpensiveSafetyCheckConfig;
 use sui_protocol_config::Chain;
@@ -36,6 +37,7 @@ mod data_fetcher;
 mod displays;
 pub mod fuzz;
 pub mod fuzz_mutations;
+pub mod gas_profile;
 mod replay;
 #[cfg(test)]
 mod tests;
@@ -83,6 +85,16 @@ pub enum ReplayToolCommand {
         /// Optional output filepath for the profile generated by this run, if not specified defaults to `gas_profile_{tx_digest}_{unix_timestamp}.json in the working directory.
         #[arg(long, short, allow_hyphen_values = true)]
         profile_output: Option<PathBuf>,
+        /// Also write the profile as folded stacks, next to the profile: the gas used by each stack
+        /// of functions in `<profile>.gas.folded`, and the number of instructions it executed in
+        /// `<profile>.instructions.folded`. These can be rendered as flamegraphs with
+        /// `flamegraph.pl` or `inferno-flamegraph`.
+        #[arg(long)]
+        folded: bool,
+        /// Build directories of Move packages (e.g. `build/MyPackage`), used to annotate functions
+        /// in folded stacks with their location in source.
+        #[arg(long, num_args = 1.., requires = "folded")]
+        source_dirs: Vec<PathBuf>,
         /// Required config objects and versions of the config objects to use if replaying a
         /// transaction that utilizes the config object for regulated coin types and that has been
         /// denied.
@@ -355,9 +367,24 @@ pub async fn execute_replay_command(
             executor_version,
             protocol_version,
             profile_output,
+            folded,
+            source_dirs,
             config_objects,
         } => {
-            let output_path = profile_output.or(Some(get_default_output_filepath()));
+            let output_path = profile_output.unwrap_or_else(get_default_output_filepath);
+
+            // The profiler names its output after the transaction and the time it was written, so
+            // to find the profile to fold, it is written to a scratch directory first.
+            let scratch = tempfile::tempdir()?;
+            let profile_path = if folded {
+                scratch.path().join(
+                    output_path
+                        .file_name()
+                        .ok_or_else(|| anyhow!("Invalid profile output: {output_path:?}"))?,
+                )
+            } else {
+                output_path.clone()
+            };
 
             let tx_digest = TransactionDigest::from_str(&tx_digest)?;
             info!("Executing tx: {}", tx_digest);
@@ -368,11 +395,30 @@ pub async fn execute_replay_command(
                 use_authority,
                 executor_version,
                 protocol_version,
-                output_path,
+                Some(profile_path),
                 parse_configs_versions(config_objects),
             )
             .await?;
 
+            if folded {
+                let sources = gas_profile::SourceLocations::load(&source_dirs)?;
+                let output_dir = output_path.parent().unwrap_or(Path::new("."));
+                for entry in std::fs::read_dir(scratch.path())? {
+                    let scratch_profile = entry?.path();
+                    let profile = output_dir.join(
+                        scratch_profile
+                            .file_name()
+                            .expect("Profiles written by the profiler have file names"),
+                    );
+
+                    std::fs::copy(&scratch_profile, &profile)?;
+                    println!("Gas profile written to: {}", profile.display());
+                    for folded in gas_profile::write_folded_profiles(&profile, &sources)? {
+                        println!("Folded profile written to: {}", folded.display());
+                    }
+                }
+            }
+
             println!("Execution finished successfully.");
             Some((1u64, 1u64))
         }
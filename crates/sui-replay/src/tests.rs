@@ -172,3 +172,69 @@ async fn execute_replay(url: &str, tx: &TransactionDigest) -> Result<(), ReplayE
 
     Ok(())
 }
+
+/// Checks that gas profiles are folded into stacks of functions, weighted by the gas and the
+/// number of instructions each stack used.
+#[test]
+fn fold_gas_profile() {
+    use crate::gas_profile::{write_folded_profiles, SourceLocations};
+
+    let dir = tempfile::tempdir().unwrap();
+    let profile = dir.path().join("gas_profile.json");
+
+    let json = serde_json::json!({
+        "shared": {
+            "frames": [
+                { "name": "root", "file": "root" },
+                { "name": "split", "file": "0x0000000000000000000000000000000000000000000000000000000000000002::coin::split" },
+                { "name": "take", "file": "0x0000000000000000000000000000000000000000000000000000000000000002::balance::split" },
+            ]
+        },
+        "profiles": [
+            {
+                "type": "evented",
+                "events": [
+                    { "type": "O", "frame": 0, "at": 0 },
+                    { "type": "O", "frame": 1, "at": 10 },
+                    { "type": "O", "frame": 2, "at": 15 },
+                    { "type": "C", "frame": 2, "at": 45 },
+                    { "type": "C", "frame": 1, "at": 50 },
+                    { "type": "O", "frame": 1, "at": 60 },
+                    { "type": "C", "frame": 1, "at": 65 },
+                    { "type": "C", "frame": 0, "at": 70 },
+                ],
+            },
+            {
+                "type": "sampled",
+                "samples": [[0], [0, 1], [0, 1, 2]],
+                "weights": [3, 12, 7],
+            },
+        ],
+    });
+    std::fs::write(&profile, json.to_string()).unwrap();
+
+    let written = write_folded_profiles(&profile, &SourceLocations::default()).unwrap();
+    assert_eq!(
+        written,
+        vec![
+            dir.path().join("gas_profile.gas.folded"),
+            dir.path().join("gas_profile.instructions.folded"),
+        ]
+    );
+
+    let gas = std::fs::read_to_string(&written[0]).unwrap();
+    assert_eq!(
+        gas,
+        "root 25\n\
+         root;0x2::coin::split 15\n\
+         root;0x2::coin::split;0x2::balance::split 30\n"
+    );
+
+    let instructions = std::fs::read_to_string(&written[1]).unwrap();
+    assert_eq!(
+        instructions,
+        "root 3\n\
+         root;0x2::coin::split 12\n\
+         root;0x2::coin::split;0x2::balance::split 7\n"
+    );
+}
@@ -158,7 +158,13 @@ impl RpcService {
                     .add_service(SubscriptionServiceServer::new(subscription_service_handle));
             }
 
-            services.into_router()
+            // Checkpoints are also streamed as server-sent events, alongside the gRPC services, so
+            // that the stream is served with the same middleware as the rest of the API.
+            services.into_router().route(
+                "/checkpoints/stream",
+                axum::routing::get(service::checkpoint_stream::stream_checkpoints)
+                    .with_state(self.clone()),
+            )
         };
 
         let health_endpoint = axum::Router::new()
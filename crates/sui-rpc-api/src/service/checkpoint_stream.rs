@@ -0,0 +1,179 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::IntoResponse;
+use tap::Pipe;
+use tokio::sync::mpsc;
+use tracing::error;
+
+use crate::proto::node::v2::GetFullCheckpointResponse;
+use crate::proto::TryFromProtoError;
+use crate::types::CheckpointResponse;
+use crate::RpcService;
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct StreamCheckpointsOptions {
+    /// Request `CheckpointContents` be included in each streamed checkpoint.
+    ///
+    /// Defaults to `false` if not provided.
+    pub contents: Option<bool>,
+}
+
+/// Stream newly executed checkpoints to the client as server-sent events.
+///
+/// Each event carries the JSON encoding of a `CheckpointResponse` (sequence number, digest,
+/// summary and signature, plus contents if requested), and its event ID is the checkpoint's
+/// sequence number. Subscribers that cannot keep up with the chain are disconnected.
+pub async fn stream_checkpoints(
+    Query(options): Query<StreamCheckpointsOptions>,
+    State(state): State<RpcService>,
+) -> axum::response::Response {
+    let Some(handle) = state.subscription_service_handle.as_ref() else {
+        return (
+            StatusCode::NOT_FOUND,
+            "checkpoint subscriptions are not enabled",
+        )
+            .into_response();
+    };
+
+    let Some(receiver) = handle.register_subscription().await else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "too many existing subscriptions",
+        )
+            .into_response();
+    };
+
+    checkpoint_events(receiver, options.contents.unwrap_or(false))
+}
+
+/// A server-sent event response, with an event for each checkpoint received from the subscription
+/// service through `receiver`.
+fn checkpoint_events(
+    mut receiver: mpsc::Receiver<Arc<GetFullCheckpointResponse>>,
+    include_contents: bool,
+) -> axum::response::Response {
+    let events = async_stream::stream! {
+        while let Some(checkpoint) = receiver.recv().await {
+            let event = match checkpoint_event(&checkpoint, include_contents) {
+                Ok(event) => event,
+                Err(e) => {
+                    error!("unable to stream checkpoint: {e:?}");
+                    break;
+                }
+            };
+
+            yield Ok::<_, Infallible>(event);
+        }
+    };
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+fn checkpoint_event(
+    checkpoint: &GetFullCheckpointResponse,
+    include_contents: bool,
+) -> anyhow::Result<Event> {
+    let response = checkpoint_response(checkpoint, include_contents)?;
+    Ok(Event::default()
+        .id(response.sequence_number.to_string())
+        .event("checkpoint")
+        .json_data(response)?)
+}
+
+/// Convert a checkpoint from the subscription service into the JSON representation used by the
+/// stream, keeping only the fields that were requested.
+fn checkpoint_response(
+    checkpoint: &GetFullCheckpointResponse,
+    include_contents: bool,
+) -> Result<CheckpointResponse, TryFromProtoError> {
+    let sequence_number = checkpoint
+        .sequence_number
+        .ok_or_else(|| TryFromProtoError::missing("sequence_number"))?;
+
+    let digest = checkpoint
+        .digest
+        .as_ref()
+        .ok_or_else(|| TryFromProtoError::missing("digest"))?
+        .pipe(TryInto::try_into)?;
+
+    let summary = checkpoint
+        .summary
+        .as_ref()
+        .map(TryInto::try_into)
+        .transpose()?;
+
+    let signature = checkpoint
+        .signature
+        .as_ref()
+        .map(TryInto::try_into)
+        .transpose()?;
+
+    let contents = checkpoint
+        .contents
+        .as_ref()
+        .filter(|_| include_contents)
+        .map(TryInto::try_into)
+        .transpose()?;
+
+    Ok(CheckpointResponse {
+        sequence_number,
+        digest,
+        summary,
+        summary_bcs: None,
+        signature,
+        contents,
+        contents_bcs: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sui_types::test_checkpoint_data_builder::TestCheckpointDataBuilder;
+    use tokio_stream::StreamExt;
+
+    use crate::subscription::SubscriptionService;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscriber_receives_checkpoint() {
+        let registry = prometheus::Registry::new();
+        let (checkpoints, handle) = SubscriptionService::build(&registry);
+
+        let receiver = handle.register_subscription().await.unwrap();
+        let response = checkpoint_events(receiver, false);
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The checkpoint is committed after the client has subscribed.
+        let checkpoint = TestCheckpointDataBuilder::new(0)
+            .start_transaction(0)
+            .create_owned_object(0)
+            .finish_transaction()
+            .build_checkpoint();
+        let digest = checkpoint.checkpoint_summary.digest().to_string();
+        checkpoints.send(checkpoint).await.unwrap();
+
+        let mut body = response.into_body().into_data_stream();
+        let frame = tokio::time::timeout(Duration::from_secs(10), body.next())
+            .await
+            .expect("Timed out waiting for checkpoint")
+            .unwrap()
+            .unwrap();
+
+        let event = String::from_utf8(frame.to_vec()).unwrap();
+        assert!(event.contains("event: checkpoint"), "{event}");
+        assert!(event.contains("id: 0"), "{event}");
+        assert!(event.contains(&digest), "{event}");
+    }
+}
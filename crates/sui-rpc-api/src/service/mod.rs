@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod accounts;
+pub(crate) mod checkpoint_stream;
 pub(crate) mod checkpoints;
 mod coin_info;
 mod committee;
@@ -7,9 +7,11 @@ use request_loader::load_json_rpc_requests;
 use runner::run_queries;
 use std::time::Duration;
 use tracing::info;
+use workload::{gather_seeds, run_workloads, MethodReport, WorkloadConfig};
 
 pub mod request_loader;
 pub mod runner;
+pub mod workload;
 
 pub async fn run_benchmark(
     endpoint: &str,
@@ -57,3 +59,57 @@ pub async fn run_benchmark(
     }
     Ok(())
 }
+
+pub async fn run_workload_benchmark(
+    endpoint: &str,
+    workload_config: &WorkloadConfig,
+    concurrency: usize,
+    duration_secs: u64,
+) -> Result<()> {
+    info!(
+        "Sampling {} recent transactions for seeds",
+        workload_config.seed_transactions
+    );
+    let seeds = gather_seeds(endpoint, workload_config.seed_transactions).await?;
+    info!(
+        "Found {} addresses, {} objects, {} event types",
+        seeds.addresses.len(),
+        seeds.objects.len(),
+        seeds.event_types.len()
+    );
+
+    let report = run_workloads(
+        endpoint,
+        seeds,
+        workload_config,
+        concurrency,
+        Duration::from_secs(duration_secs),
+    )
+    .await?;
+
+    info!("Benchmark results:");
+    info!("=== Overall Statistics ===");
+    log_method_report(&report.total);
+    info!("=== Per-Method Statistics ===");
+    for (method, stats) in &report.per_method {
+        info!("Method: {}", method);
+        log_method_report(stats);
+    }
+
+    if let Some(path) = &workload_config.report {
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        info!("Report written to {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn log_method_report(stats: &MethodReport) {
+    info!("  Requests: {}", stats.requests);
+    info!("  Errors: {}", stats.errors);
+    info!("  Throughput: {:.1} req/s", stats.throughput_rps);
+    info!(
+        "  Latency: p50 {:.2}ms, p90 {:.2}ms, p99 {:.2}ms, max {:.2}ms",
+        stats.p50_latency_ms, stats.p90_latency_ms, stats.p99_latency_ms, stats.max_latency_ms
+    );
+}
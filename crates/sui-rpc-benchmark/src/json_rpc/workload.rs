@@ -0,0 +1,395 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// This module implements synthetic read workloads for the JSON RPC benchmark.
+/// Instead of replaying requests collected from production, it generates requests that
+/// resemble read traffic (paginating through owned objects, fetching objects in bulk,
+/// querying events), seeded with addresses, objects and event types that are sampled from
+/// the endpoint's recent transactions. It works against any endpoint that serves the
+/// JSON RPC API (a fullnode, or `sui-indexer-alt-jsonrpc`).
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::time::timeout;
+use tracing::{debug, info};
+
+/// How long to wait for a response before counting the request as an error.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of transactions to fetch per page, while gathering seeds.
+const SEED_PAGE_SIZE: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Workload {
+    /// Paginate through the objects owned by an address, with `suix_getOwnedObjects`.
+    OwnedObjects,
+    /// Fetch a batch of objects at once, with `sui_multiGetObjects`.
+    MultiGetObjects,
+    /// Query the latest events of a type, with `suix_queryEvents`.
+    Events,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct WorkloadConfig {
+    /// Workloads to run. Each request picks one of them at random.
+    #[clap(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "owned-objects,multi-get-objects,events"
+    )]
+    pub workloads: Vec<Workload>,
+    /// Number of results to request per page, for paginated queries.
+    #[clap(long, default_value = "50")]
+    pub page_size: usize,
+    /// Maximum number of pages to fetch when paginating through an address's owned objects.
+    #[clap(long, default_value = "10")]
+    pub max_pages: usize,
+    /// Number of objects to fetch in each `sui_multiGetObjects` request.
+    #[clap(long, default_value = "50")]
+    pub multi_get_size: usize,
+    /// Number of recent transactions to sample addresses, objects and event types from.
+    #[clap(long, default_value = "1000")]
+    pub seed_transactions: usize,
+    /// Optional path to write the report to, as JSON, to compare runs against each other.
+    #[clap(long)]
+    pub report: Option<PathBuf>,
+}
+
+/// Addresses, objects and event types that requests are generated from.
+#[derive(Debug, Default)]
+pub struct Seeds {
+    pub addresses: Vec<String>,
+    pub objects: Vec<String>,
+    pub event_types: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct MethodStats {
+    errors: usize,
+    latencies_ms: Vec<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MethodReport {
+    pub requests: usize,
+    pub errors: usize,
+    pub throughput_rps: f64,
+    pub p50_latency_ms: f64,
+    pub p90_latency_ms: f64,
+    pub p99_latency_ms: f64,
+    pub max_latency_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkloadReport {
+    pub endpoint: String,
+    pub concurrency: usize,
+    pub duration_secs: f64,
+    pub total: MethodReport,
+    pub per_method: BTreeMap<String, MethodReport>,
+}
+
+/// A JSON RPC client that records the latency and outcome of every request it sends.
+#[derive(Clone)]
+struct Client {
+    endpoint: String,
+    client: reqwest::Client,
+    stats: Arc<Mutex<BTreeMap<String, MethodStats>>>,
+}
+
+impl Client {
+    fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_owned(),
+            client: reqwest::Client::new(),
+            stats: Default::default(),
+        }
+    }
+
+    /// Send a request and return its result, or `None` if the request failed. Requests that
+    /// time out, fail at the HTTP level, or return a JSON RPC error all count as errors.
+    async fn call(&self, method: &str, params: Value) -> Option<Value> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let now = Instant::now();
+        let response = timeout(REQUEST_TIMEOUT, async {
+            let response = self.client.post(&self.endpoint).json(&body).send().await?;
+            response.error_for_status()?.json::<Value>().await
+        })
+        .await;
+        let latency_ms = now.elapsed().as_secs_f64() * 1000.0;
+
+        let result = match response {
+            Ok(Ok(mut response)) if response.get("error").is_none() => {
+                Some(response["result"].take())
+            }
+            Ok(Ok(response)) => {
+                debug!("{method} returned an error: {}", response["error"]);
+                None
+            }
+            Ok(Err(e)) => {
+                debug!("{method} failed: {e}");
+                None
+            }
+            Err(_) => {
+                debug!("{method} timed out");
+                None
+            }
+        };
+
+        let mut stats = self.stats.lock().unwrap();
+        let method_stats = stats.entry(method.to_owned()).or_default();
+        method_stats.latencies_ms.push(latency_ms);
+        if result.is_none() {
+            method_stats.errors += 1;
+        }
+
+        result
+    }
+}
+
+/// Sample addresses, objects and event types from the endpoint's most recent transactions.
+pub async fn gather_seeds(endpoint: &str, num_transactions: usize) -> Result<Seeds> {
+    let client = Client::new(endpoint);
+    let mut addresses = BTreeSet::new();
+    let mut objects = BTreeSet::new();
+    let mut event_types = BTreeSet::new();
+
+    let mut cursor = Value::Null;
+    let mut fetched = 0;
+    while fetched < num_transactions {
+        let limit = SEED_PAGE_SIZE.min(num_transactions - fetched);
+        let query = json!({
+            "options": {
+                "showInput": true,
+                "showObjectChanges": true,
+                "showEvents": true,
+            }
+        });
+
+        let Some(page) = client
+            .call(
+                "suix_queryTransactionBlocks",
+                json!([query, cursor, limit, true]),
+            )
+            .await
+        else {
+            bail!("Failed to fetch recent transactions from {endpoint}");
+        };
+
+        let transactions = page["data"]
+            .as_array()
+            .context("Malformed transactions page")?;
+        for tx in transactions {
+            if let Some(sender) = tx["transaction"]["data"]["sender"].as_str() {
+                addresses.insert(sender.to_owned());
+            }
+
+            for change in tx["objectChanges"].as_array().into_iter().flatten() {
+                if let Some(id) = change["objectId"].as_str() {
+                    objects.insert(id.to_owned());
+                }
+
+                if let Some(owner) = change["owner"]["AddressOwner"].as_str() {
+                    addresses.insert(owner.to_owned());
+                }
+            }
+
+            for event in tx["events"].as_array().into_iter().flatten() {
+                if let Some(ty) = event["type"].as_str() {
+                    event_types.insert(ty.to_owned());
+                }
+            }
+        }
+
+        fetched += transactions.len();
+        cursor = page["nextCursor"].clone();
+        if transactions.is_empty() || !page["hasNextPage"].as_bool().unwrap_or(false) {
+            break;
+        }
+    }
+
+    Ok(Seeds {
+        addresses: addresses.into_iter().collect(),
+        objects: objects.into_iter().collect(),
+        event_types: event_types.into_iter().collect(),
+    })
+}
+
+/// Run the configured workloads against `endpoint` from `concurrency` concurrent clients, for
+/// `duration`, and report the latency and throughput of each method.
+pub async fn run_workloads(
+    endpoint: &str,
+    seeds: Seeds,
+    config: &WorkloadConfig,
+    concurrency: usize,
+    duration: Duration,
+) -> Result<WorkloadReport> {
+    // Only run the workloads that have seeds to generate requests from.
+    let workloads: Vec<_> = config
+        .workloads
+        .iter()
+        .copied()
+        .filter(|workload| {
+            let seeded = match workload {
+                Workload::OwnedObjects => !seeds.addresses.is_empty(),
+                Workload::MultiGetObjects => !seeds.objects.is_empty(),
+                Workload::Events => !seeds.event_types.is_empty(),
+            };
+
+            if !seeded {
+                info!("Skipping {workload:?} workload: no seeds found");
+            }
+
+            seeded
+        })
+        .collect();
+
+    if workloads.is_empty() {
+        bail!("No workloads to run");
+    }
+
+    let client = Client::new(endpoint);
+    let seeds = Arc::new(seeds);
+    let workloads = Arc::new(workloads);
+    let start = Instant::now();
+    let deadline = start + duration;
+
+    let mut workers = Vec::with_capacity(concurrency);
+    for _ in 0..concurrency {
+        let client = client.clone();
+        let seeds = seeds.clone();
+        let workloads = workloads.clone();
+        let config = config.clone();
+
+        workers.push(tokio::spawn(async move {
+            let mut rng = StdRng::from_entropy();
+            while Instant::now() < deadline {
+                let workload = *workloads.choose(&mut rng).unwrap();
+                let run = run_workload(&client, &seeds, &config, workload, &mut rng);
+                // Stop in the middle of a workload (e.g. while paginating) if the benchmark is
+                // over.
+                if tokio::time::timeout_at(deadline.into(), run).await.is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+
+    for worker in workers {
+        worker.await.context("Benchmark worker panicked")?;
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let stats = std::mem::take(&mut *client.stats.lock().unwrap());
+
+    let mut all = MethodStats::default();
+    let mut per_method = BTreeMap::new();
+    for (method, stats) in stats {
+        all.errors += stats.errors;
+        all.latencies_ms.extend_from_slice(&stats.latencies_ms);
+        per_method.insert(method, method_report(stats, elapsed));
+    }
+
+    Ok(WorkloadReport {
+        endpoint: endpoint.to_owned(),
+        concurrency,
+        duration_secs: elapsed,
+        total: method_report(all, elapsed),
+        per_method,
+    })
+}
+
+async fn run_workload(
+    client: &Client,
+    seeds: &Seeds,
+    config: &WorkloadConfig,
+    workload: Workload,
+    rng: &mut StdRng,
+) {
+    match workload {
+        Workload::OwnedObjects => {
+            let address = seeds.addresses.choose(rng).unwrap();
+            let query = json!({ "options": { "showType": true, "showOwner": true } });
+
+            let mut cursor = Value::Null;
+            for _ in 0..config.max_pages {
+                let params = json!([address, query, cursor, config.page_size]);
+                let Some(page) = client.call("suix_getOwnedObjects", params).await else {
+                    break;
+                };
+
+                if !page["hasNextPage"].as_bool().unwrap_or(false) {
+                    break;
+                }
+
+                cursor = page["nextCursor"].clone();
+            }
+        }
+
+        Workload::MultiGetObjects => {
+            let ids: Vec<_> = seeds
+                .objects
+                .choose_multiple(rng, config.multi_get_size)
+                .collect();
+            let options = json!({ "showType": true, "showOwner": true, "showContent": true });
+            client
+                .call("sui_multiGetObjects", json!([ids, options]))
+                .await;
+        }
+
+        Workload::Events => {
+            let event_type = seeds.event_types.choose(rng).unwrap();
+            let filter = json!({ "MoveEventType": event_type });
+            client
+                .call(
+                    "suix_queryEvents",
+                    json!([filter, null, config.page_size, true]),
+                )
+                .await;
+        }
+    }
+}
+
+fn method_report(mut stats: MethodStats, elapsed_secs: f64) -> MethodReport {
+    stats.latencies_ms.sort_by(f64::total_cmp);
+    let requests = stats.latencies_ms.len();
+
+    MethodReport {
+        requests,
+        errors: stats.errors,
+        throughput_rps: if elapsed_secs > 0.0 {
+            requests as f64 / elapsed_secs
+        } else {
+            0.0
+        },
+        p50_latency_ms: percentile(&stats.latencies_ms, 50.0),
+        p90_latency_ms: percentile(&stats.latencies_ms, 90.0),
+        p99_latency_ms: percentile(&stats.latencies_ms, 99.0),
+        max_latency_ms: stats.latencies_ms.last().copied().unwrap_or(0.0),
+    }
+}
+
+/// The `p`-th percentile of `sorted`, using the nearest-rank method.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
@@ -16,6 +16,7 @@ use crate::config::BenchmarkConfig;
 use crate::direct::query_enricher::QueryEnricher;
 use crate::direct::query_executor::QueryExecutor;
 use crate::direct::query_template_generator::QueryTemplateGenerator;
+use crate::json_rpc::workload::WorkloadConfig;
 
 #[derive(Parser)]
 #[clap(
@@ -55,6 +56,18 @@ pub enum Command {
         #[clap(long, default_value = "requests.jsonl")]
         requests_file: String,
     },
+    /// Benchmark JSON RPC endpoints with synthetic read workloads, seeded from recent transactions
+    #[clap(name = "jsonrpc-workload")]
+    JsonRpcWorkload {
+        #[clap(long, default_value = "http://127.0.0.1:9000")]
+        endpoint: String,
+        #[clap(long, default_value = "50")]
+        concurrency: usize,
+        #[clap(long, default_value = "30")]
+        duration_secs: u64,
+        #[clap(flatten)]
+        workload_config: WorkloadConfig,
+    },
     /// Benchmark GraphQL queries
     #[clap(name = "graphql")]
     GraphQL {
@@ -115,6 +128,22 @@ pub async fn run_benchmarks() -> Result<(), anyhow::Error> {
             json_rpc::run_benchmark(&endpoint, &requests_file, concurrency, duration_secs).await?;
             Ok(())
         }
+        Command::JsonRpcWorkload {
+            endpoint,
+            concurrency,
+            duration_secs,
+            workload_config,
+        } => {
+            info!("Running JSON RPC workload benchmark against {endpoint} with concurrency={concurrency} duration_secs={duration_secs} workloads={:?}", workload_config.workloads);
+            json_rpc::run_workload_benchmark(
+                &endpoint,
+                &workload_config,
+                concurrency,
+                duration_secs,
+            )
+            .await?;
+            Ok(())
+        }
         Command::GraphQL { endpoint } => {
             info!("Running GraphQL benchmark against {}", endpoint);
             todo!()
@@ -626,6 +626,19 @@ impl ReadApi {
             .await?)
     }
 
+    /// Return the normalized module `module_name` in `package`, or an error upon failure.
+    pub async fn get_normalized_move_module(
+        &self,
+        package: ObjectID,
+        module_name: String,
+    ) -> SuiRpcResult<SuiMoveNormalizedModule> {
+        Ok(self
+            .api
+            .http
+            .get_normalized_move_module(package, module_name)
+            .await?)
+    }
+
     // TODO(devx): we can probably cache this given an epoch
     /// Return the reference gas price, or an error upon failure.
     pub async fn get_reference_gas_price(&self) -> SuiRpcResult<u64> {
@@ -90,10 +90,10 @@ use sui_json_rpc_api::{
 };
 pub use sui_json_rpc_types as rpc_types;
 use sui_json_rpc_types::{
-    ObjectsPage, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
-    SuiObjectResponseQuery,
+    ObjectsPage, SuiMoveNormalizedModule, SuiObjectDataFilter, SuiObjectDataOptions,
+    SuiObjectResponse, SuiObjectResponseQuery,
 };
-use sui_transaction_builder::{DataReader, TransactionBuilder};
+use sui_transaction_builder::{DataReader, ModuleResolver, TransactionBuilder};
 pub use sui_types as types;
 use sui_types::base_types::{ObjectID, ObjectInfo, SuiAddress};
 
@@ -621,3 +621,16 @@ impl DataReader for ReadApi {
         Ok(self.get_reference_gas_price().await?)
     }
 }
+
+#[async_trait]
+impl ModuleResolver for ReadApi {
+    async fn get_normalized_module(
+        &self,
+        package: ObjectID,
+        module: &str,
+    ) -> Result<SuiMoveNormalizedModule, anyhow::Error> {
+        Ok(self
+            .get_normalized_move_module(package, module.to_string())
+            .await?)
+    }
+}
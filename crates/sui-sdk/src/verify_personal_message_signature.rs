@@ -6,10 +6,11 @@ use std::sync::Arc;
 use crate::{error::Error, SuiClient};
 use fastcrypto::encoding::{Base64, Encoding};
 use fastcrypto::traits::ToFromBytes;
-use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
+use shared_crypto::intent::PersonalMessage;
 use sui_json_rpc_types::ZkLoginIntentScope;
 use sui_types::{
     base_types::SuiAddress,
+    personal_message::personal_message_intent,
     signature::{AuthenticatorTrait, GenericSignature, VerifyParams},
     signature_verification::VerifiedDigestCache,
 };
@@ -22,12 +23,7 @@ pub async fn verify_personal_message_signature(
     address: SuiAddress,
     client: Option<SuiClient>,
 ) -> Result<(), Error> {
-    let intent_msg = IntentMessage::new(
-        Intent::personal_message(),
-        PersonalMessage {
-            message: message.to_vec(),
-        },
-    );
+    let intent_msg = personal_message_intent(message);
     match signature {
         GenericSignature::ZkLoginAuthenticator(ref _sig) => {
             if let Some(client) = client {
@@ -7,7 +7,7 @@ use std::{num::NonZeroUsize, path::Path, sync::Arc};
 
 use rand::rngs::OsRng;
 use sui_config::genesis::{TokenAllocation, TokenDistributionScheduleBuilder};
-use sui_config::node::AuthorityOverloadConfig;
+use sui_config::node::{AuthorityOverloadConfig, CustomOidcProviderConfig};
 use sui_config::ExecutionCacheConfig;
 use sui_macros::nondeterministic;
 use sui_types::base_types::{AuthorityName, SuiAddress};
@@ -69,6 +69,7 @@ pub struct ConfigBuilder<R = OsRng> {
     reference_gas_price: Option<u64>,
     additional_objects: Vec<Object>,
     jwk_fetch_interval: Option<Duration>,
+    zklogin_custom_oidc_providers: Vec<CustomOidcProviderConfig>,
     num_unpruned_validators: Option<usize>,
     authority_overload_config: Option<AuthorityOverloadConfig>,
     execution_cache_config: Option<ExecutionCacheConfig>,
@@ -93,6 +94,7 @@ impl ConfigBuilder {
             reference_gas_price: None,
             additional_objects: vec![],
             jwk_fetch_interval: None,
+            zklogin_custom_oidc_providers: vec![],
             num_unpruned_validators: None,
             authority_overload_config: None,
             execution_cache_config: None,
@@ -160,6 +162,14 @@ impl<R> ConfigBuilder<R> {
         self
     }
 
+    pub fn with_zklogin_custom_oidc_providers(
+        mut self,
+        providers: Vec<CustomOidcProviderConfig>,
+    ) -> Self {
+        self.zklogin_custom_oidc_providers = providers;
+        self
+    }
+
     pub fn with_data_ingestion_dir(mut self, path: PathBuf) -> Self {
         self.data_ingestion_dir = Some(path);
         self
@@ -286,6 +296,7 @@ impl<R> ConfigBuilder<R> {
             additional_objects: self.additional_objects,
             num_unpruned_validators: self.num_unpruned_validators,
             jwk_fetch_interval: self.jwk_fetch_interval,
+            zklogin_custom_oidc_providers: self.zklogin_custom_oidc_providers,
             authority_overload_config: self.authority_overload_config,
             execution_cache_config: self.execution_cache_config,
             data_ingestion_dir: self.data_ingestion_dir,
@@ -453,6 +464,9 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
                     builder = builder.with_jwk_fetch_interval(jwk_fetch_interval);
                 }
 
+                builder = builder
+                    .with_zklogin_custom_oidc_providers(self.zklogin_custom_oidc_providers.clone());
+
                 if let Some(authority_overload_config) = &self.authority_overload_config {
                     builder =
                         builder.with_authority_overload_config(authority_overload_config.clone());
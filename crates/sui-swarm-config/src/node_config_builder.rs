@@ -15,7 +15,7 @@ use sui_config::node::{
     Genesis, KeyPairWithPath, StateArchiveConfig, StateSnapshotConfig,
     DEFAULT_GRPC_CONCURRENCY_LIMIT,
 };
-use sui_config::node::{default_zklogin_oauth_providers, RunWithRange};
+use sui_config::node::{default_zklogin_oauth_providers, CustomOidcProviderConfig, RunWithRange};
 use sui_config::p2p::{P2pConfig, SeedPeer, StateSyncConfig};
 use sui_config::verifier_signing_config::VerifierSigningConfig;
 use sui_config::{
@@ -38,6 +38,7 @@ pub struct ValidatorConfigBuilder {
     supported_protocol_versions: Option<SupportedProtocolVersions>,
     force_unpruned_checkpoints: bool,
     jwk_fetch_interval: Option<Duration>,
+    zklogin_custom_oidc_providers: Vec<CustomOidcProviderConfig>,
     authority_overload_config: Option<AuthorityOverloadConfig>,
     execution_cache_config: Option<ExecutionCacheConfig>,
     data_ingestion_dir: Option<PathBuf>,
@@ -81,6 +82,14 @@ impl ValidatorConfigBuilder {
         self
     }
 
+    pub fn with_zklogin_custom_oidc_providers(
+        mut self,
+        providers: Vec<CustomOidcProviderConfig>,
+    ) -> Self {
+        self.zklogin_custom_oidc_providers = providers;
+        self
+    }
+
     pub fn with_authority_overload_config(mut self, config: AuthorityOverloadConfig) -> Self {
         self.authority_overload_config = Some(config);
         self
@@ -225,6 +234,7 @@ impl ValidatorConfigBuilder {
                 .map(|i| i.as_secs())
                 .unwrap_or(3600),
             zklogin_oauth_providers: default_zklogin_oauth_providers(),
+            zklogin_custom_oidc_providers: self.zklogin_custom_oidc_providers,
             authority_overload_config: self.authority_overload_config.unwrap_or_default(),
             execution_cache: self.execution_cache_config.unwrap_or_default(),
             run_with_range: None,
@@ -238,6 +248,8 @@ impl ValidatorConfigBuilder {
             enable_db_write_stall: None,
             local_execution_time_channel_capacity: default_local_execution_time_channel_capacity(),
             local_execution_time_cache_size: default_local_execution_time_cache_size(),
+            epoch_change_webhooks: vec![],
+            event_firehose: None,
         }
     }
 
@@ -525,6 +537,7 @@ impl FullnodeConfigBuilder {
             // note: not used by fullnodes.
             jwk_fetch_interval_seconds: 3600,
             zklogin_oauth_providers: default_zklogin_oauth_providers(),
+            zklogin_custom_oidc_providers: vec![],
             authority_overload_config: Default::default(),
             run_with_range: self.run_with_range,
             jsonrpc_server_type: None,
@@ -539,6 +552,8 @@ impl FullnodeConfigBuilder {
             enable_db_write_stall: None,
             local_execution_time_channel_capacity: default_local_execution_time_channel_capacity(),
             local_execution_time_cache_size: default_local_execution_time_cache_size(),
+            epoch_change_webhooks: vec![],
+            event_firehose: None,
         }
     }
 }
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::Node;
-use anyhow::Result;
+use anyhow::{bail, Result};
+use consensus_config::ClockOffset;
 use futures::future::try_join_all;
 use rand::rngs::OsRng;
 use std::collections::HashMap;
@@ -15,7 +16,9 @@ use std::{
 };
 use sui_types::traffic_control::{PolicyConfig, RemoteFirewallConfig};
 
-use sui_config::node::{AuthorityOverloadConfig, DBCheckpointConfig, RunWithRange};
+use sui_config::node::{
+    AuthorityOverloadConfig, CustomOidcProviderConfig, DBCheckpointConfig, RunWithRange,
+};
 use sui_config::{ExecutionCacheConfig, NodeConfig};
 use sui_macros::nondeterministic;
 use sui_node::SuiNodeHandle;
@@ -28,6 +31,7 @@ use sui_swarm_config::network_config_builder::{
 };
 use sui_swarm_config::node_config_builder::FullnodeConfigBuilder;
 use sui_types::base_types::AuthorityName;
+use sui_types::committee::EpochId;
 use sui_types::object::Object;
 use sui_types::supported_protocol_versions::SupportedProtocolVersions;
 use tempfile::TempDir;
@@ -49,6 +53,7 @@ pub struct SwarmBuilder<R = OsRng> {
     fullnode_supported_protocol_versions_config: Option<ProtocolVersionsConfig>,
     db_checkpoint_config: DBCheckpointConfig,
     jwk_fetch_interval: Option<Duration>,
+    zklogin_custom_oidc_providers: Vec<CustomOidcProviderConfig>,
     num_unpruned_validators: Option<usize>,
     authority_overload_config: Option<AuthorityOverloadConfig>,
     execution_cache_config: Option<ExecutionCacheConfig>,
@@ -79,6 +84,7 @@ impl SwarmBuilder {
             fullnode_supported_protocol_versions_config: None,
             db_checkpoint_config: DBCheckpointConfig::default(),
             jwk_fetch_interval: None,
+            zklogin_custom_oidc_providers: vec![],
             num_unpruned_validators: None,
             authority_overload_config: None,
             execution_cache_config: None,
@@ -111,6 +117,7 @@ impl<R> SwarmBuilder<R> {
                 .fullnode_supported_protocol_versions_config,
             db_checkpoint_config: self.db_checkpoint_config,
             jwk_fetch_interval: self.jwk_fetch_interval,
+            zklogin_custom_oidc_providers: self.zklogin_custom_oidc_providers,
             num_unpruned_validators: self.num_unpruned_validators,
             authority_overload_config: self.authority_overload_config,
             execution_cache_config: self.execution_cache_config,
@@ -165,6 +172,14 @@ impl<R> SwarmBuilder<R> {
         self
     }
 
+    pub fn with_zklogin_custom_oidc_providers(
+        mut self,
+        providers: Vec<CustomOidcProviderConfig>,
+    ) -> Self {
+        self.zklogin_custom_oidc_providers = providers;
+        self
+    }
+
     pub fn with_network_config(mut self, network_config: NetworkConfig) -> Self {
         assert!(self.network_config.is_none() && self.genesis_config.is_none());
         self.network_config = Some(network_config);
@@ -344,6 +359,9 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
                 config_builder = config_builder.with_jwk_fetch_interval(jwk_fetch_interval);
             }
 
+            config_builder = config_builder
+                .with_zklogin_custom_oidc_providers(self.zklogin_custom_oidc_providers);
+
             if let Some(authority_overload_config) = self.authority_overload_config {
                 config_builder =
                     config_builder.with_authority_overload_config(authority_overload_config);
@@ -380,6 +398,7 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
                 .build()
         });
 
+        let clock_offset = ClockOffset::default();
         let mut nodes: HashMap<_, _> = network_config
             .validator_configs()
             .iter()
@@ -388,7 +407,8 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
                     "SwarmBuilder configuring validator with name {}",
                     config.protocol_public_key()
                 );
-                (config.protocol_public_key(), Node::new(config.to_owned()))
+                let config = share_clock_offset(config.to_owned(), &clock_offset);
+                (config.protocol_public_key(), Node::new(config))
             })
             .collect();
 
@@ -437,10 +457,23 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
             network_config,
             nodes,
             fullnode_config_builder,
+            clock_offset,
         }
     }
 }
 
+/// Share `clock_offset` with the consensus authority of the node that `config` is for, if it runs
+/// one.
+fn share_clock_offset(mut config: NodeConfig, clock_offset: &ClockOffset) -> NodeConfig {
+    if let Some(consensus_config) = &mut config.consensus_config {
+        consensus_config
+            .parameters
+            .get_or_insert_with(Default::default)
+            .clock_offset = clock_offset.clone();
+    }
+    config
+}
+
 /// A handle to an in-memory Sui Network.
 #[derive(Debug)]
 pub struct Swarm {
@@ -449,6 +482,8 @@ pub struct Swarm {
     nodes: HashMap<AuthorityName, Node>,
     // Save a copy of the fullnode config builder to build future fullnodes.
     fullnode_config_builder: FullnodeConfigBuilder,
+    // Offset added to the clocks of every validator in the swarm.
+    clock_offset: ClockOffset,
 }
 
 impl Drop for Swarm {
@@ -534,9 +569,65 @@ impl Swarm {
             .filter(|node| node.config().consensus_config.is_none())
     }
 
+    /// Move the clock that validators timestamp their blocks with forward by `duration`, so that
+    /// time-dependent logic (and epoch changes driven by the epoch duration) can be exercised
+    /// without waiting. This affects every validator in the swarm, including those added later.
+    pub fn advance_clock(&self, duration: Duration) {
+        self.clock_offset.advance(duration);
+    }
+
+    /// Ask a quorum of active validators to close the current epoch, and return the epoch being
+    /// closed. The network moves to the next epoch once the end of epoch checkpoint is certified,
+    /// which can be waited for with [`Swarm::wait_for_epoch`].
+    pub async fn close_epoch(&self) -> Result<EpochId> {
+        let handles: Vec<_> = self
+            .active_validators()
+            .filter_map(|node| node.get_node_handle())
+            .collect();
+
+        let Some(handle) = handles.first() else {
+            bail!("No active validators to close the epoch");
+        };
+
+        let committee = handle.state().clone_committee_for_testing();
+        let mut stake = 0;
+        for handle in &handles {
+            handle
+                .with_async(|node| node.close_epoch_for_testing())
+                .await?;
+
+            stake += committee.weight(&handle.state().name);
+            if stake >= committee.quorum_threshold() {
+                break;
+            }
+        }
+
+        Ok(committee.epoch)
+    }
+
+    /// Wait until every running node in the swarm has reached `epoch`, or `timeout` elapses.
+    pub async fn wait_for_epoch(&self, epoch: EpochId, timeout: Duration) -> Result<()> {
+        let handles: Vec<_> = self
+            .all_nodes()
+            .filter_map(|node| node.get_node_handle())
+            .collect();
+
+        tokio::time::timeout(timeout, async {
+            let mut interval = tokio::time::interval(Duration::from_millis(100));
+            while !handles
+                .iter()
+                .all(|handle| handle.state().current_epoch_for_testing() >= epoch)
+            {
+                interval.tick().await;
+            }
+        })
+        .await
+        .map_err(|_| anyhow::anyhow!("Timed out waiting for swarm to reach epoch {epoch}"))
+    }
+
     pub async fn spawn_new_node(&mut self, config: NodeConfig) -> SuiNodeHandle {
         let name = config.protocol_public_key();
-        let node = Node::new(config);
+        let node = Node::new(share_clock_offset(config, &self.clock_offset));
         node.start().await.unwrap();
         let handle = node.get_node_handle().unwrap();
         self.nodes.insert(name, node);
@@ -0,0 +1,608 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dump the transactions, effects and events in a range of checkpoints, read from a node's DB or
+//! from an archive, as JSON or Parquet, for ad-hoc analysis without running an indexer.
+//! Transactions, effects and events are rendered the same way as they are served over JSON-RPC.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use arrow::array::{ArrayRef, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use clap::{Parser, ValueEnum};
+use futures::TryStreamExt;
+use move_bytecode_utils::module_cache::{GetModule, ModuleCache};
+use move_core_types::language_storage::ModuleId;
+use move_core_types::resolver::ModuleResolver;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use prometheus::Registry;
+use serde::{Deserialize, Serialize};
+use sui_archival::reader::{ArchiveReader, ArchiveReaderMetrics};
+use sui_config::node::ArchiveReaderConfig;
+use sui_config::object_storage_config::ObjectStoreConfig;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::checkpoints::CheckpointStore;
+use sui_json_rpc_types::{
+    SuiTransactionBlock, SuiTransactionBlockEffects, SuiTransactionBlockEvents,
+};
+use sui_types::base_types::{ObjectID, TransactionDigest};
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::in_memory_storage::InMemoryStorage;
+use sui_types::messages_checkpoint::{
+    CheckpointContents, CheckpointSequenceNumber, CheckpointSummary, VerifiedCheckpoint,
+};
+use sui_types::storage::{get_module, BackingPackageStore, PackageObject};
+use sui_types::transaction::{SenderSignedData, TransactionDataAPI};
+use tracing::info;
+
+/// Number of rows buffered before they are written out as a Parquet row group.
+const PARQUET_BATCH_SIZE: usize = 10_000;
+
+#[derive(Parser, Clone)]
+pub enum CheckpointsCommand {
+    /// Dump the transactions, effects and events in a range of checkpoints, one row per
+    /// transaction. Checkpoints are read from a node's DB if `--db-path` is set (the node must not
+    /// be running), and otherwise streamed from the archive described by the object store options.
+    /// Archives do not contain events or packages, so when reading from an archive, events are
+    /// omitted, and pure transaction inputs whose type can't be inferred are left as raw bytes.
+    #[command(name = "dump")]
+    Dump(DumpOptions),
+}
+
+#[derive(Parser, Clone)]
+pub struct DumpOptions {
+    /// Path of the node's DB, containing its `store` and `checkpoints` directories.
+    #[arg(long)]
+    db_path: Option<PathBuf>,
+
+    #[command(flatten)]
+    object_store_config: ObjectStoreConfig,
+
+    /// Sequence number of the first checkpoint to dump.
+    #[arg(long, default_value_t = 0)]
+    start: CheckpointSequenceNumber,
+
+    /// Sequence number of the checkpoint after the last one to dump.
+    #[arg(long)]
+    end: CheckpointSequenceNumber,
+
+    #[arg(long, value_enum, default_value_t = DumpFormat::Json)]
+    format: DumpFormat,
+
+    /// File to write the dump to. JSON is written to stdout if this is not set, but it is required
+    /// for Parquet.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum DumpFormat {
+    /// One JSON object per line, per transaction.
+    Json,
+    /// A Parquet file with a row per transaction. Transactions, effects and events are stored as
+    /// JSON strings.
+    Parquet,
+}
+
+/// A transaction in a checkpoint, with its effects and events.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct TransactionRow {
+    checkpoint: CheckpointSequenceNumber,
+    epoch: u64,
+    timestamp_ms: u64,
+    digest: String,
+    sender: String,
+    transaction: SuiTransactionBlock,
+    effects: SuiTransactionBlockEffects,
+    events: Option<SuiTransactionBlockEvents>,
+}
+
+/// Reads checkpoints, and the packages needed to render their transactions and events, from the DB
+/// of a node that is not running.
+struct NodeDb {
+    checkpoints: Arc<CheckpointStore>,
+    perpetual: AuthorityPerpetualTables,
+}
+
+trait RowWriter {
+    fn write(&mut self, row: TransactionRow) -> Result<()>;
+
+    fn finish(&mut self) -> Result<()>;
+}
+
+struct JsonWriter(Box<dyn Write>);
+
+struct ParquetWriter {
+    schema: SchemaRef,
+    writer: ArrowWriter<File>,
+    rows: Vec<TransactionRow>,
+}
+
+pub async fn execute_checkpoints_command(cmd: CheckpointsCommand) -> Result<()> {
+    match cmd {
+        CheckpointsCommand::Dump(options) => dump_checkpoints(options).await,
+    }
+}
+
+async fn dump_checkpoints(options: DumpOptions) -> Result<()> {
+    let DumpOptions {
+        db_path,
+        object_store_config,
+        start,
+        end,
+        format,
+        output,
+    } = options;
+
+    ensure!(start < end, "--start must be less than --end");
+    let range = start..end;
+
+    let mut writer: Box<dyn RowWriter> = match (format, output) {
+        (DumpFormat::Json, None) => Box::new(JsonWriter(Box::new(BufWriter::new(io::stdout())))),
+        (DumpFormat::Json, Some(path)) => Box::new(JsonWriter(Box::new(BufWriter::new(
+            File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?,
+        )))),
+        (DumpFormat::Parquet, None) => bail!("--output is required for Parquet dumps"),
+        (DumpFormat::Parquet, Some(path)) => Box::new(ParquetWriter::new(&path)?),
+    };
+
+    if let Some(db_path) = db_path {
+        dump_node_db(&NodeDb::open(&db_path), range, writer.as_mut())?;
+    } else {
+        dump_archive(object_store_config, range, writer.as_mut()).await?;
+    }
+
+    writer.finish()
+}
+
+fn dump_node_db(
+    db: &NodeDb,
+    range: Range<CheckpointSequenceNumber>,
+    writer: &mut dyn RowWriter,
+) -> Result<()> {
+    let modules = ModuleCache::new(db);
+    for sequence_number in range {
+        let (checkpoint, contents) = db.checkpoint(sequence_number)?;
+        for digests in contents.iter() {
+            let (transaction, effects, events) = db.transaction(&digests.transaction)?;
+            writer.write(TransactionRow::new(
+                checkpoint.data(),
+                transaction,
+                effects,
+                events,
+                &modules,
+            )?)?;
+        }
+
+        log_progress(sequence_number);
+    }
+
+    Ok(())
+}
+
+/// Stream checkpoints in `range` from an archive, so that only the archive files being read are
+/// held in memory.
+async fn dump_archive(
+    remote_store_config: ObjectStoreConfig,
+    range: Range<CheckpointSequenceNumber>,
+    writer: &mut dyn RowWriter,
+) -> Result<()> {
+    let metrics = ArchiveReaderMetrics::new(&Registry::default());
+    let config = ArchiveReaderConfig {
+        remote_store_config,
+        download_concurrency: NonZeroUsize::new(5).unwrap(),
+        use_for_pruning_watermark: false,
+    };
+
+    let archive_reader = ArchiveReader::new(config, &metrics)?;
+    archive_reader.sync_manifest_once().await?;
+
+    // Archives don't contain packages, so there are no modules to render inputs with.
+    let modules = InMemoryStorage::new(vec![]);
+
+    let mut next = range.start;
+    let mut checkpoints = pin!(archive_reader.stream(range.clone()).await?);
+    while let Some((summary, contents)) = checkpoints.try_next().await? {
+        let sequence_number = summary.sequence_number;
+        for data in contents {
+            writer.write(TransactionRow::new(
+                summary.data(),
+                data.transaction.into_data(),
+                data.effects,
+                None,
+                &modules,
+            )?)?;
+        }
+
+        log_progress(sequence_number);
+        next = sequence_number + 1;
+    }
+
+    ensure!(
+        next == range.end,
+        "Archive only contains checkpoints up to {}",
+        next.saturating_sub(1),
+    );
+
+    Ok(())
+}
+
+fn log_progress(sequence_number: CheckpointSequenceNumber) {
+    if sequence_number % 1000 == 0 {
+        info!("Dumped checkpoint {sequence_number}");
+    }
+}
+
+impl TransactionRow {
+    /// Render a transaction in `checkpoint`, using `modules` to resolve the types of its pure
+    /// inputs and events.
+    fn new(
+        checkpoint: &CheckpointSummary,
+        transaction: SenderSignedData,
+        effects: TransactionEffects,
+        events: Option<TransactionEvents>,
+        modules: &impl GetModule,
+    ) -> Result<Self> {
+        let digest = *effects.transaction_digest();
+        let sender = transaction.transaction_data().sender();
+
+        let events = events
+            .map(|events| {
+                SuiTransactionBlockEvents::try_from_using_module_resolver(
+                    events,
+                    digest,
+                    Some(checkpoint.timestamp_ms),
+                    modules,
+                )
+            })
+            .transpose()
+            .with_context(|| format!("Failed to render events of transaction {digest}"))?;
+
+        Ok(Self {
+            checkpoint: checkpoint.sequence_number,
+            epoch: checkpoint.epoch,
+            timestamp_ms: checkpoint.timestamp_ms,
+            digest: digest.to_string(),
+            sender: sender.to_string(),
+            transaction: SuiTransactionBlock::try_from(transaction, modules)
+                .with_context(|| format!("Failed to render transaction {digest}"))?,
+            effects: SuiTransactionBlockEffects::try_from(effects)
+                .with_context(|| format!("Failed to render effects of transaction {digest}"))?,
+            events,
+        })
+    }
+}
+
+impl NodeDb {
+    fn open(db_path: &Path) -> Self {
+        Self {
+            checkpoints: CheckpointStore::new(&db_path.join("checkpoints")),
+            perpetual: AuthorityPerpetualTables::open(&db_path.join("store"), None),
+        }
+    }
+
+    fn checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<(VerifiedCheckpoint, CheckpointContents)> {
+        let checkpoint = self
+            .checkpoints
+            .get_checkpoint_by_sequence_number(sequence_number)?
+            .ok_or_else(|| anyhow!("Checkpoint {sequence_number} not found"))?;
+
+        let contents = self
+            .checkpoints
+            .get_checkpoint_contents(&checkpoint.content_digest)?
+            .ok_or_else(|| anyhow!("Contents of checkpoint {sequence_number} not found"))?;
+
+        Ok((checkpoint, contents))
+    }
+
+    fn transaction(
+        &self,
+        digest: &TransactionDigest,
+    ) -> Result<(
+        SenderSignedData,
+        TransactionEffects,
+        Option<TransactionEvents>,
+    )> {
+        let transaction = self
+            .perpetual
+            .get_transaction(digest)?
+            .ok_or_else(|| anyhow!("Transaction {digest} not found"))?;
+
+        let effects = self
+            .perpetual
+            .get_effects(digest)?
+            .ok_or_else(|| anyhow!("Effects of transaction {digest} not found"))?;
+
+        let events = match effects.events_digest() {
+            Some(events_digest) => self.perpetual.get_events(events_digest)?,
+            None => None,
+        };
+
+        Ok((transaction.into_inner().into_data(), effects, events))
+    }
+}
+
+impl BackingPackageStore for NodeDb {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<PackageObject>> {
+        Ok(self
+            .perpetual
+            .get_object_fallible(package_id)?
+            .filter(|object| object.is_package())
+            .map(PackageObject::new))
+    }
+}
+
+impl ModuleResolver for NodeDb {
+    type Error = SuiError;
+
+    fn get_module(&self, id: &ModuleId) -> SuiResult<Option<Vec<u8>>> {
+        get_module(self, id)
+    }
+}
+
+impl RowWriter for JsonWriter {
+    fn write(&mut self, row: TransactionRow) -> Result<()> {
+        serde_json::to_writer(&mut self.0, &row)?;
+        writeln!(self.0)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.0.flush()?;
+        Ok(())
+    }
+}
+
+impl ParquetWriter {
+    fn new(path: &Path) -> Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("checkpoint", DataType::UInt64, false),
+            Field::new("epoch", DataType::UInt64, false),
+            Field::new("timestamp_ms", DataType::UInt64, false),
+            Field::new("digest", DataType::Utf8, false),
+            Field::new("sender", DataType::Utf8, false),
+            Field::new("transaction", DataType::Utf8, false),
+            Field::new("effects", DataType::Utf8, false),
+            Field::new("events", DataType::Utf8, true),
+        ]));
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+
+        Ok(Self {
+            writer: ArrowWriter::try_new(file, schema.clone(), Some(properties))?,
+            schema,
+            rows: vec![],
+        })
+    }
+
+    /// Write buffered rows out as a row group.
+    fn flush(&mut self) -> Result<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+
+        let rows = std::mem::take(&mut self.rows);
+        let u64s = |f: fn(&TransactionRow) -> u64| -> ArrayRef {
+            Arc::new(UInt64Array::from_iter_values(rows.iter().map(f)))
+        };
+
+        let strings = |f: fn(&TransactionRow) -> &str| -> ArrayRef {
+            Arc::new(StringArray::from_iter_values(rows.iter().map(f)))
+        };
+
+        let mut transactions = Vec::with_capacity(rows.len());
+        let mut effects = Vec::with_capacity(rows.len());
+        let mut events = Vec::with_capacity(rows.len());
+        for row in &rows {
+            transactions.push(serde_json::to_string(&row.transaction)?);
+            effects.push(serde_json::to_string(&row.effects)?);
+            events.push(row.events.as_ref().map(serde_json::to_string).transpose()?);
+        }
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                u64s(|r| r.checkpoint),
+                u64s(|r| r.epoch),
+                u64s(|r| r.timestamp_ms),
+                strings(|r| r.digest.as_str()),
+                strings(|r| r.sender.as_str()),
+                Arc::new(StringArray::from(transactions)),
+                Arc::new(StringArray::from(effects)),
+                Arc::new(StringArray::from(events)),
+            ],
+        )?;
+
+        self.writer.write(&batch)?;
+        Ok(())
+    }
+}
+
+impl RowWriter for ParquetWriter {
+    fn write(&mut self, row: TransactionRow) -> Result<()> {
+        self.rows.push(row);
+        if self.rows.len() >= PARQUET_BATCH_SIZE {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.flush()?;
+        self.writer.finish()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use sui_json_rpc_types::{SuiCallArg, SuiTransactionBlockDataAPI, SuiTransactionBlockKind};
+    use sui_protocol_config::ProtocolConfig;
+    use sui_types::base_types::{random_object_ref, SuiAddress};
+    use sui_types::crypto::{get_key_pair, AccountKeyPair};
+    use sui_types::effects::TestEffectsBuilder;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::transaction::TransactionData;
+    use sui_types::utils::to_sender_signed_transaction;
+
+    use super::*;
+
+    /// A checkpoint containing a single SUI transfer, rendered as a row.
+    fn transfer_row() -> TransactionRow {
+        let (sender, key): (_, AccountKeyPair) = get_key_pair();
+        let data = TransactionData::new_transfer_sui(
+            SuiAddress::random_for_testing_only(),
+            sender,
+            Some(1_000),
+            random_object_ref(),
+            10_000_000,
+            1_000,
+        );
+
+        let transaction = to_sender_signed_transaction(data, &key).into_data();
+        let effects = TestEffectsBuilder::new(&transaction).build();
+        let contents =
+            CheckpointContents::new_with_digests_only_for_tests([effects.execution_digests()]);
+
+        let summary = CheckpointSummary::new(
+            &ProtocolConfig::get_for_max_version_UNSAFE(),
+            3,
+            42,
+            100,
+            &contents,
+            None,
+            GasCostSummary::default(),
+            None,
+            1_700_000_000_000,
+            vec![],
+        );
+
+        TransactionRow::new(
+            &summary,
+            transaction,
+            effects,
+            None,
+            &InMemoryStorage::new(vec![]),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pure_inputs_are_typed() {
+        let row = transfer_row();
+        assert_eq!(row.checkpoint, 42);
+        assert_eq!(row.epoch, 3);
+        assert_eq!(row.timestamp_ms, 1_700_000_000_000);
+        assert_eq!(row.sender, row.transaction.data.sender().to_string());
+        assert_eq!(row.digest, row.effects.transaction_digest().to_string());
+
+        let SuiTransactionBlockKind::ProgrammableTransaction(ptb) =
+            row.transaction.data.transaction()
+        else {
+            panic!("Expected a programmable transaction");
+        };
+
+        // The amount and recipient of a transfer have types that can be inferred from the commands
+        // that use them, so they are rendered as values, even without any packages to look at.
+        assert!(!ptb.inputs.is_empty());
+        for input in &ptb.inputs {
+            let SuiCallArg::Pure(value) = input else {
+                panic!("Expected a pure input, got {input:?}");
+            };
+            assert!(value.value_type().is_some(), "Untyped input: {value:?}");
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dump.json");
+
+        let mut writer = JsonWriter(Box::new(File::create(&path).unwrap()));
+        writer.write(transfer_row()).unwrap();
+        writer.write(transfer_row()).unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+
+        let dump = std::fs::read_to_string(&path).unwrap();
+        let rows: Vec<TransactionRow> = dump
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(rows.len(), 2);
+        for (line, row) in dump.lines().zip(&rows) {
+            assert_eq!(line, serde_json::to_string(row).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dump.parquet");
+
+        let expect = transfer_row();
+        let mut writer = ParquetWriter::new(&path).unwrap();
+        writer.write(expect.clone()).unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let batches: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+        assert_eq!(batches.len(), 1);
+
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 1);
+
+        let u64s = |name: &str| {
+            let column = batch.column_by_name(name).unwrap();
+            let column = column.as_any().downcast_ref::<UInt64Array>().unwrap();
+            column.value(0)
+        };
+
+        let strings = |name: &str| {
+            let column = batch.column_by_name(name).unwrap();
+            let column = column.as_any().downcast_ref::<StringArray>().unwrap();
+            (!column.is_null(0)).then(|| column.value(0).to_owned())
+        };
+
+        let actual = TransactionRow {
+            checkpoint: u64s("checkpoint"),
+            epoch: u64s("epoch"),
+            timestamp_ms: u64s("timestamp_ms"),
+            digest: strings("digest").unwrap(),
+            sender: strings("sender").unwrap(),
+            transaction: serde_json::from_str(&strings("transaction").unwrap()).unwrap(),
+            effects: serde_json::from_str(&strings("effects").unwrap()).unwrap(),
+            events: strings("events").map(|e| serde_json::from_str(&e).unwrap()),
+        };
+
+        assert_eq!(actual, expect);
+    }
+}
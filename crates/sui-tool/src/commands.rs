@@ -3,6 +3,7 @@
 
 use crate::{
     check_completed_snapshot,
+    checkpoint_dump::{execute_checkpoints_command, CheckpointsCommand},
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
     download_db_snapshot, download_formal_snapshot, dump_checkpoints_from_archive,
     get_latest_available_epoch, get_object, get_transaction_block, make_clients,
@@ -176,6 +177,13 @@ pub enum ToolCommand {
         max_content_length: usize,
     },
 
+    /// Tools to read checkpoints from a node's DB or an archive.
+    #[command(name = "checkpoints")]
+    Checkpoints {
+        #[command(subcommand)]
+        cmd: CheckpointsCommand,
+    },
+
     /// Download all packages to the local filesystem from a GraphQL service. Each package gets its
     /// own sub-directory, named for its ID on chain and version containing two metadata files
     /// (linkage.json and origins.json), a file containing the overall object and a file for every
@@ -1076,6 +1084,9 @@ impl ToolCommand {
                 dump_checkpoints_from_archive(object_store_config, start, end, max_content_length)
                     .await?;
             }
+            ToolCommand::Checkpoints { cmd } => {
+                execute_checkpoints_command(cmd).await?;
+            }
             ToolCommand::SignTransaction {
                 genesis,
                 sender_signed_data,
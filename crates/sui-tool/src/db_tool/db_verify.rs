@@ -0,0 +1,437 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::bail;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::checkpoints::CheckpointStore;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::{CheckpointContentsDigest, TransactionDigest, TransactionEffectsDigest};
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::message_envelope::Message;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::info;
+
+/// A single inconsistency found while cross-checking the node's column families.
+#[derive(Debug)]
+pub enum Inconsistency {
+    /// A checkpoint at or below the highest executed watermark is missing from the store.
+    MissingCheckpoint(CheckpointSequenceNumber),
+
+    /// A checkpoint's contents are missing.
+    MissingContents(CheckpointSequenceNumber, CheckpointContentsDigest),
+
+    /// A transaction included in an executed checkpoint is missing.
+    MissingTransaction(CheckpointSequenceNumber, TransactionDigest),
+
+    /// A transaction included in an executed checkpoint has no executed effects.
+    MissingEffects(CheckpointSequenceNumber, TransactionDigest),
+
+    /// The executed effects of a transaction differ from the effects certified by its checkpoint.
+    EffectsMismatch {
+        checkpoint: CheckpointSequenceNumber,
+        transaction: TransactionDigest,
+        expected: TransactionEffectsDigest,
+        actual: TransactionEffectsDigest,
+    },
+
+    /// An object version written by a transaction's effects is missing, and there is no newer
+    /// version of the object that could explain it having been pruned.
+    MissingObject {
+        checkpoint: CheckpointSequenceNumber,
+        transaction: TransactionDigest,
+        object: ObjectID,
+        version: SequenceNumber,
+    },
+}
+
+/// The outcome of verifying a range of executed checkpoints.
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    /// The first checkpoint that was checked (inclusive).
+    pub start: CheckpointSequenceNumber,
+
+    /// The last checkpoint that was checked (inclusive).
+    pub end: CheckpointSequenceNumber,
+
+    pub transactions_checked: u64,
+    pub objects_checked: u64,
+    pub inconsistencies: Vec<Inconsistency>,
+}
+
+impl Inconsistency {
+    pub fn checkpoint(&self) -> CheckpointSequenceNumber {
+        use Inconsistency as I;
+        match self {
+            I::MissingCheckpoint(cp)
+            | I::MissingContents(cp, _)
+            | I::MissingTransaction(cp, _)
+            | I::MissingEffects(cp, _) => *cp,
+            I::EffectsMismatch { checkpoint, .. } | I::MissingObject { checkpoint, .. } => {
+                *checkpoint
+            }
+        }
+    }
+
+    /// Whether this inconsistency can be fixed by re-executing the checkpoint it was found in.
+    /// Re-execution only rewrites the outputs of transactions, so checkpoints, their contents, and
+    /// transactions that are missing cannot be restored by this tool, and need to be synced again.
+    /// Effects that disagree with their certified checkpoint indicate a fork, and are not
+    /// recoverable either.
+    ///
+    /// The checkpoint executor skips transactions whose effects have already been stored, so only
+    /// missing effects are restored by re-execution. Objects that are missing despite their
+    /// transaction's effects being present would never be rewritten, so they are not recoverable.
+    pub fn is_recoverable(&self) -> bool {
+        use Inconsistency as I;
+        match self {
+            I::MissingEffects(_, _) => true,
+            I::MissingCheckpoint(_)
+            | I::MissingContents(_, _)
+            | I::MissingTransaction(_, _)
+            | I::EffectsMismatch { .. }
+            | I::MissingObject { .. } => false,
+        }
+    }
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Inconsistency as I;
+        match self {
+            I::MissingCheckpoint(cp) => write!(f, "checkpoint {cp}: checkpoint missing"),
+            I::MissingContents(cp, digest) => {
+                write!(f, "checkpoint {cp}: contents {digest} missing")
+            }
+            I::MissingTransaction(cp, tx) => write!(f, "checkpoint {cp}: transaction {tx} missing"),
+            I::MissingEffects(cp, tx) => {
+                write!(f, "checkpoint {cp}: effects for transaction {tx} missing")
+            }
+            I::EffectsMismatch {
+                checkpoint,
+                transaction,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "checkpoint {checkpoint}: transaction {transaction} has effects {actual}, but \
+                 checkpoint certifies {expected}",
+            ),
+            I::MissingObject {
+                checkpoint,
+                transaction,
+                object,
+                version,
+            } => write!(
+                f,
+                "checkpoint {checkpoint}: object {object} at version {} written by transaction \
+                 {transaction} missing",
+                version.value(),
+            ),
+        }
+    }
+}
+
+/// Cross-check the perpetual tables against the checkpoint store for every executed checkpoint in
+/// the given range (defaulting to everything that has not been pruned, up to the highest executed
+/// checkpoint):
+///
+/// - Every executed checkpoint has its summary and contents.
+/// - Every transaction in those checkpoints has been stored, along with its executed effects, and
+///   those effects match the digest certified by the checkpoint.
+/// - Every object version written by those effects is present, unless a newer version of the
+///   object exists (in which case it may have been pruned).
+pub fn verify_db(
+    path: &Path,
+    start: Option<CheckpointSequenceNumber>,
+    end: Option<CheckpointSequenceNumber>,
+) -> anyhow::Result<VerifyReport> {
+    let perpetual_db = AuthorityPerpetualTables::open(&path.join("store"), None);
+    let checkpoint_db = CheckpointStore::new(&path.join("checkpoints"));
+
+    let Some(highest_executed) = checkpoint_db.get_highest_executed_checkpoint_seq_number()? else {
+        bail!("No checkpoints have been executed");
+    };
+
+    // Data for pruned checkpoints is expected to be missing, so skip over it.
+    let lowest_unpruned = checkpoint_db
+        .get_highest_pruned_checkpoint_seq_number()?
+        .max(perpetual_db.get_highest_pruned_checkpoint()?)
+        .saturating_add(1)
+        .min(highest_executed);
+
+    let start = start.unwrap_or(lowest_unpruned).max(lowest_unpruned);
+    let end = end.unwrap_or(highest_executed).min(highest_executed);
+    if start > end {
+        bail!("Empty checkpoint range to verify: {start}..={end}");
+    }
+
+    let mut report = VerifyReport {
+        start,
+        end,
+        ..Default::default()
+    };
+
+    for seq in start..=end {
+        use Inconsistency as I;
+
+        let Some(checkpoint) = checkpoint_db.get_checkpoint_by_sequence_number(seq)? else {
+            report.inconsistencies.push(I::MissingCheckpoint(seq));
+            continue;
+        };
+
+        let Some(contents) = checkpoint_db.get_checkpoint_contents(&checkpoint.content_digest)?
+        else {
+            let digest = checkpoint.content_digest;
+            report.inconsistencies.push(I::MissingContents(seq, digest));
+            continue;
+        };
+
+        for digests in contents.iter() {
+            report.transactions_checked += 1;
+            let tx = digests.transaction;
+
+            if perpetual_db.get_transaction(&tx)?.is_none() {
+                report.inconsistencies.push(I::MissingTransaction(seq, tx));
+            }
+
+            let Some(effects) = perpetual_db.get_effects(&tx)? else {
+                report.inconsistencies.push(I::MissingEffects(seq, tx));
+                continue;
+            };
+
+            let actual = effects.digest();
+            if actual != digests.effects {
+                report.inconsistencies.push(I::EffectsMismatch {
+                    checkpoint: seq,
+                    transaction: tx,
+                    expected: digests.effects,
+                    actual,
+                });
+                continue;
+            }
+
+            for ((object, version, _), _, _) in effects.all_changed_objects() {
+                report.objects_checked += 1;
+                if perpetual_db
+                    .get_object_by_key_fallible(&object, version)?
+                    .is_some()
+                {
+                    continue;
+                }
+
+                if perpetual_db
+                    .get_newer_object_keys(&(object, version))?
+                    .is_empty()
+                {
+                    report.inconsistencies.push(I::MissingObject {
+                        checkpoint: seq,
+                        transaction: tx,
+                        object,
+                        version,
+                    });
+                }
+            }
+        }
+
+        if seq % 10000 == 0 {
+            info!("Verified checkpoints up to {seq}");
+        }
+    }
+
+    Ok(report)
+}
+
+/// Repair recoverable inconsistencies in `report` (missing effects) by rewinding checkpoint
+/// execution to just before the first affected checkpoint, so that the node re-executes it (and
+/// everything after it) on its next start. This is only possible if the rewind does not cross an epoch boundary.
+///
+/// Returns the checkpoint that execution was rewound to, or `None` if there was nothing to repair.
+pub fn repair_db(
+    path: &Path,
+    report: &VerifyReport,
+) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+    if let Some(fatal) = report.inconsistencies.iter().find(|i| !i.is_recoverable()) {
+        bail!("Cannot repair database, found unrecoverable inconsistency: {fatal}");
+    }
+
+    let Some(first) = report.inconsistencies.iter().map(|i| i.checkpoint()).min() else {
+        return Ok(None);
+    };
+
+    let Some(rewind_to) = first.checked_sub(1) else {
+        bail!("Cannot repair database, genesis checkpoint is inconsistent");
+    };
+
+    let checkpoint_db = CheckpointStore::new(&path.join("checkpoints"));
+    let Some(target) = checkpoint_db.get_checkpoint_by_sequence_number(rewind_to)? else {
+        bail!("Cannot repair database, checkpoint {rewind_to} to rewind to is missing");
+    };
+
+    let Some(highest_executed) = checkpoint_db.get_highest_executed_checkpoint()? else {
+        bail!("No checkpoints have been executed");
+    };
+
+    if target.epoch() != highest_executed.epoch() {
+        bail!(
+            "Cannot repair database, rewinding to checkpoint {rewind_to} would cross from epoch \
+             {} back into epoch {}",
+            highest_executed.epoch(),
+            target.epoch(),
+        );
+    }
+
+    checkpoint_db.set_highest_executed_checkpoint_subtle(&target)?;
+    Ok(Some(rewind_to))
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::messages_checkpoint::VerifiedCheckpoint;
+    use sui_types::test_checkpoint_data_builder::TestCheckpointDataBuilder;
+    use sui_types::transaction::VerifiedTransaction;
+
+    use super::*;
+
+    /// Data to leave out of the database, to simulate corruption.
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Omit {
+        Nothing,
+        Contents(CheckpointSequenceNumber),
+        Objects(CheckpointSequenceNumber),
+    }
+
+    /// Write `checkpoints` executed checkpoints to the database at `path`, each containing a
+    /// single transaction that creates an object.
+    fn populate(path: &Path, checkpoints: u64, omit: Omit) {
+        let perpetual_db = AuthorityPerpetualTables::open(&path.join("store"), None);
+        let checkpoint_db = CheckpointStore::new(&path.join("checkpoints"));
+
+        let mut builder = TestCheckpointDataBuilder::new(0);
+        for i in 0..checkpoints {
+            builder = builder
+                .start_transaction(0)
+                .create_owned_object(i)
+                .finish_transaction();
+
+            let data = builder.build_checkpoint();
+            let checkpoint = VerifiedCheckpoint::new_unchecked(data.checkpoint_summary);
+            checkpoint_db
+                .insert_verified_checkpoint(&checkpoint)
+                .unwrap();
+            if omit != Omit::Contents(i) {
+                checkpoint_db
+                    .insert_checkpoint_contents(data.checkpoint_contents)
+                    .unwrap();
+            }
+
+            for tx in data.transactions {
+                let transaction = VerifiedTransaction::new_unchecked(tx.transaction);
+                perpetual_db
+                    .insert_executed_transaction_test_only(transaction, tx.effects)
+                    .unwrap();
+
+                if omit != Omit::Objects(i) {
+                    for object in tx.output_objects {
+                        perpetual_db.insert_object_test_only(object).unwrap();
+                    }
+                }
+            }
+
+            checkpoint_db
+                .update_highest_executed_checkpoint(&checkpoint)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_verify_consistent() {
+        let dir = tempfile::tempdir().unwrap();
+        populate(dir.path(), 4, Omit::Nothing);
+
+        let report = verify_db(dir.path(), None, None).unwrap();
+        assert_eq!((report.start, report.end), (1, 3));
+        assert_eq!(report.transactions_checked, 3);
+        assert!(report.objects_checked > 0);
+        assert!(
+            report.inconsistencies.is_empty(),
+            "{:?}",
+            report.inconsistencies
+        );
+
+        assert_eq!(repair_db(dir.path(), &report).unwrap(), None);
+    }
+
+    #[test]
+    fn test_missing_objects_unrecoverable() {
+        let dir = tempfile::tempdir().unwrap();
+        populate(dir.path(), 4, Omit::Objects(2));
+
+        let report = verify_db(dir.path(), None, None).unwrap();
+        assert!(!report.inconsistencies.is_empty());
+        for inconsistency in &report.inconsistencies {
+            assert!(
+                matches!(
+                    inconsistency,
+                    Inconsistency::MissingObject { checkpoint: 2, .. }
+                ),
+                "{inconsistency}",
+            );
+            assert!(!inconsistency.is_recoverable());
+        }
+
+        // The transaction's effects are still present, so re-executing the checkpoint would skip
+        // it, and not bring back its objects. Repair refuses to run, and leaves the watermark
+        // where it was.
+        assert!(repair_db(dir.path(), &report).is_err());
+        let checkpoint_db = CheckpointStore::new(&dir.path().join("checkpoints"));
+        assert_eq!(
+            checkpoint_db
+                .get_highest_executed_checkpoint_seq_number()
+                .unwrap(),
+            Some(3),
+        );
+    }
+
+    #[test]
+    fn test_missing_contents_unrecoverable() {
+        let dir = tempfile::tempdir().unwrap();
+        populate(dir.path(), 4, Omit::Contents(2));
+
+        let report = verify_db(dir.path(), None, None).unwrap();
+        let [inconsistency] = &report.inconsistencies[..] else {
+            panic!(
+                "Expected one inconsistency, got {:?}",
+                report.inconsistencies
+            );
+        };
+
+        assert!(matches!(
+            inconsistency,
+            Inconsistency::MissingContents(2, _)
+        ));
+        assert!(!inconsistency.is_recoverable());
+
+        // Re-executing the checkpoint cannot bring back its contents, so repair refuses to run, and
+        // leaves the watermark where it was.
+        assert!(repair_db(dir.path(), &report).is_err());
+        let checkpoint_db = CheckpointStore::new(&dir.path().join("checkpoints"));
+        assert_eq!(
+            checkpoint_db
+                .get_highest_executed_checkpoint_seq_number()
+                .unwrap(),
+            Some(3),
+        );
+    }
+
+    #[test]
+    fn test_missing_checkpoint_unrecoverable() {
+        assert!(!Inconsistency::MissingCheckpoint(1).is_recoverable());
+        assert!(
+            !Inconsistency::MissingTransaction(1, TransactionDigest::random()).is_recoverable()
+        );
+        assert!(Inconsistency::MissingEffects(1, TransactionDigest::random()).is_recoverable());
+    }
+}
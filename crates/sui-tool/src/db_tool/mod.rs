@@ -4,6 +4,7 @@
 use self::db_dump::{dump_table, duplicate_objects_summary, list_tables, table_summary, StoreName};
 use self::index_search::{search_index, SearchRange};
 use crate::db_tool::db_dump::{compact, print_table_metadata, prune_checkpoints, prune_objects};
+use crate::db_tool::db_verify::{repair_db, verify_db};
 use anyhow::{anyhow, bail};
 use clap::Parser;
 use std::path::{Path, PathBuf};
@@ -16,6 +17,7 @@ use sui_types::effects::TransactionEffectsAPI;
 use sui_types::messages_checkpoint::{CheckpointDigest, CheckpointSequenceNumber};
 use typed_store::rocks::MetricConf;
 pub mod db_dump;
+pub mod db_verify;
 mod index_search;
 
 #[derive(Parser)]
@@ -40,6 +42,7 @@ pub enum DbToolCommand {
     PruneObjects,
     PruneCheckpoints,
     SetCheckpointWatermark(SetCheckpointWatermarkOptions),
+    Verify(VerifyOptions),
 }
 
 #[derive(Parser)]
@@ -176,6 +179,24 @@ pub struct SetCheckpointWatermarkOptions {
     highest_synced: Option<CheckpointSequenceNumber>,
 }
 
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct VerifyOptions {
+    /// First checkpoint to verify. Defaults to the lowest checkpoint that has not been pruned.
+    #[arg(long)]
+    start: Option<CheckpointSequenceNumber>,
+
+    /// Last checkpoint to verify. Defaults to the highest executed checkpoint.
+    #[arg(long)]
+    end: Option<CheckpointSequenceNumber>,
+
+    /// Attempt to repair recoverable inconsistencies (missing effects), by rewinding checkpoint
+    /// execution to before the first affected checkpoint, so that the node re-executes it on
+    /// restart.
+    #[arg(long)]
+    repair: bool,
+}
+
 pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> anyhow::Result<()> {
     match cmd {
         DbToolCommand::ListTables => print_db_all_tables(db_path),
@@ -232,6 +253,7 @@ pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> an
             Ok(())
         }
         DbToolCommand::SetCheckpointWatermark(d) => set_checkpoint_watermark(&db_path, d),
+        DbToolCommand::Verify(d) => verify(&db_path, d),
     }
 }
 
@@ -499,3 +521,40 @@ pub fn set_checkpoint_watermark(
     }
     Ok(())
 }
+
+/// Cross-check the database for consistency between checkpoints, transactions, effects and
+/// objects, optionally repairing what can be recovered by re-execution.
+/// Run with (for example):
+/// cargo run --package sui-tool -- db-tool --db-path /opt/sui/db/authorities_db/live verify --repair
+pub fn verify(path: &Path, options: VerifyOptions) -> anyhow::Result<()> {
+    let report = verify_db(path, options.start, options.end)?;
+    println!(
+        "Verified checkpoints {}..={}: {} transactions, {} objects, {} inconsistencies",
+        report.start,
+        report.end,
+        report.transactions_checked,
+        report.objects_checked,
+        report.inconsistencies.len(),
+    );
+
+    for inconsistency in &report.inconsistencies {
+        println!("  {inconsistency}");
+    }
+
+    if report.inconsistencies.is_empty() {
+        return Ok(());
+    }
+
+    if !options.repair {
+        bail!("Database is inconsistent, re-run with --repair to attempt recovery");
+    }
+
+    if let Some(checkpoint) = repair_db(path, &report)? {
+        println!(
+            "Rewound checkpoint execution to {checkpoint}, restart the node to re-execute \
+             subsequent checkpoints"
+        );
+    }
+
+    Ok(())
+}
@@ -68,6 +68,7 @@ use sui_types::messages_grpc::{
 use sui_types::storage::{ReadStore, SharedInMemoryStore};
 use tracing::info;
 
+pub mod checkpoint_dump;
 pub mod commands;
 pub mod db_tool;
 
@@ -11,7 +11,6 @@ use async_trait::async_trait;
 use futures::future::join_all;
 use move_binary_format::binary_config::BinaryConfig;
 use move_binary_format::file_format::SignatureToken;
-use move_binary_format::CompiledModule;
 use move_core_types::ident_str;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{StructTag, TypeTag};
@@ -34,6 +33,10 @@ use sui_types::transaction::{
 };
 use sui_types::{coin, fp_ensure, SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID};
 
+pub use move_call::ModuleResolver;
+
+mod move_call;
+
 #[async_trait]
 pub trait DataReader {
     async fn get_owned_objects(
@@ -462,8 +465,7 @@ impl TransactionBuilder {
         id: ObjectID,
         objects: &mut BTreeMap<ObjectID, Object>,
         is_mutable_ref: bool,
-        view: &CompiledModule,
-        arg_type: &SignatureToken,
+        is_receiving: bool,
     ) -> Result<ObjectArg, anyhow::Error> {
         let response = self
             .0
@@ -474,7 +476,7 @@ impl TransactionBuilder {
         let obj_ref = obj.compute_object_reference();
         let owner = obj.owner.clone();
         objects.insert(id, obj);
-        if is_receiving_argument(view, arg_type) {
+        if is_receiving {
             return Ok(ObjectArg::Receiving(obj_ref));
         }
         Ok(match owner {
@@ -546,8 +548,7 @@ impl TransactionBuilder {
                         // Is mutable if passed by mutable reference or by value
                         matches!(expected_type, SignatureToken::MutableReference(_))
                             || !expected_type.is_reference(),
-                        &module,
-                        &expected_type,
+                        is_receiving_argument(&module, &expected_type),
                     )
                     .await?,
                 )),
@@ -560,8 +561,7 @@ impl TransactionBuilder {
                                 id,
                                 &mut objects,
                                 /* is_mutable_ref */ false,
-                                &module,
-                                &expected_type,
+                                is_receiving_argument(&module, &expected_type),
                             )
                             .await?,
                         )
@@ -0,0 +1,418 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, ensure};
+use async_trait::async_trait;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::{IdentStr, Identifier};
+use move_core_types::language_storage::TypeTag;
+use sui_json::{MoveTypeLayout, SuiJsonValue};
+use sui_json_rpc_types::{SuiMoveNormalizedModule, SuiMoveNormalizedType};
+use sui_types::base_types::{
+    move_ascii_str_layout, move_utf8_str_layout, ObjectID, RESOLVED_ASCII_STR, RESOLVED_STD_OPTION,
+    RESOLVED_UTF8_STR, TX_CONTEXT_MODULE_NAME, TX_CONTEXT_STRUCT_NAME,
+};
+use sui_types::id::{ID, RESOLVED_SUI_ID};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::{Argument, CallArg, Command};
+use sui_types::transfer::RESOLVED_RECEIVING_STRUCT;
+use sui_types::SUI_FRAMEWORK_ADDRESS;
+
+use crate::TransactionBuilder;
+
+/// Source of the normalized signatures of Move modules, used to coerce the arguments of Move
+/// calls to the types their functions expect.
+#[async_trait]
+pub trait ModuleResolver {
+    async fn get_normalized_module(
+        &self,
+        package: ObjectID,
+        module: &str,
+    ) -> Result<SuiMoveNormalizedModule, anyhow::Error>;
+}
+
+/// How an argument to a Move call is passed, based on its parameter's type.
+enum ParamKind {
+    /// A pure value, with the layout to serialize its JSON representation with.
+    Pure(MoveTypeLayout),
+
+    /// An object, passed by (mutable) reference or by value, or received. Shared objects are
+    /// accessed mutably unless they are passed by immutable reference.
+    Object { mutable: bool, receiving: bool },
+
+    /// A vector of objects, passed by value.
+    ObjVec,
+}
+
+impl TransactionBuilder {
+    /// Add a call to `package::module::function` to `builder`, returning its result.
+    ///
+    /// Unlike [`TransactionBuilder::single_move_call`], the function's signature is fetched
+    /// through `resolver`, rather than by deserializing the package's modules. Each of
+    /// `call_args` is coerced to the type of its parameter: pure values are serialized from their
+    /// JSON representation, and object IDs are turned into inputs for the latest versions of the
+    /// objects, shared or owned as appropriate. A trailing `TxContext` parameter is supplied by
+    /// the runtime, and should not be passed.
+    pub async fn resolved_move_call(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        resolver: &(dyn ModuleResolver + Sync),
+        package: ObjectID,
+        module: &str,
+        function: &str,
+        type_args: Vec<TypeTag>,
+        call_args: Vec<SuiJsonValue>,
+    ) -> Result<Argument, anyhow::Error> {
+        let normalized = resolver.get_normalized_module(package, module).await?;
+        let Some(signature) = normalized.exposed_functions.get(function) else {
+            bail!("Could not resolve function {function} in module {package}::{module}");
+        };
+
+        ensure!(
+            type_args.len() == signature.type_parameters.len(),
+            "Expected {} type arguments for {package}::{module}::{function}, got {}",
+            signature.type_parameters.len(),
+            type_args.len(),
+        );
+
+        let mut parameters = signature.parameters.as_slice();
+        if let Some((last, rest)) = parameters.split_last() {
+            if is_tx_context(last) {
+                parameters = rest;
+            }
+        }
+
+        ensure!(
+            call_args.len() == parameters.len(),
+            "Expected {} arguments for {package}::{module}::{function}, got {}",
+            parameters.len(),
+            call_args.len(),
+        );
+
+        let mut arguments = vec![];
+        let mut objects = BTreeMap::new();
+        for (i, (arg, param)) in call_args.into_iter().zip(parameters).enumerate() {
+            let kind = param_kind(param, &type_args).ok_or_else(|| {
+                anyhow!("Cannot pass argument {i} of {package}::{module}::{function}: {param:?}")
+            })?;
+
+            arguments.push(match kind {
+                ParamKind::Pure(layout) => {
+                    let bytes = arg
+                        .to_bcs_bytes(&layout)
+                        .map_err(|e| anyhow!("Invalid argument {i}: {e}"))?;
+                    builder.input(CallArg::Pure(bytes))?
+                }
+
+                ParamKind::Object { mutable, receiving } => {
+                    let id = object_id(&arg.to_json_value())?;
+                    let obj = self
+                        .get_object_arg(id, &mut objects, mutable, receiving)
+                        .await?;
+                    builder.input(CallArg::Object(obj))?
+                }
+
+                ParamKind::ObjVec => {
+                    let serde_json::Value::Array(ids) = arg.to_json_value() else {
+                        bail!("Invalid argument {i}: expected an array of object IDs");
+                    };
+
+                    let mut objs = vec![];
+                    for id in &ids {
+                        let id = object_id(id)?;
+                        objs.push(
+                            self.get_object_arg(
+                                id,
+                                &mut objects,
+                                /* is_mutable_ref */ false,
+                                /* is_receiving */ false,
+                            )
+                            .await?,
+                        );
+                    }
+                    builder.make_obj_vec(objs)?
+                }
+            });
+        }
+
+        Ok(builder.command(Command::move_call(
+            package,
+            Identifier::from_str(module)?,
+            Identifier::from_str(function)?,
+            type_args,
+            arguments,
+        )))
+    }
+}
+
+fn object_id(value: &serde_json::Value) -> Result<ObjectID, anyhow::Error> {
+    let serde_json::Value::String(id) = value else {
+        bail!("Expected an object ID, got {value}");
+    };
+
+    Ok(ObjectID::from_hex_literal(id)?)
+}
+
+/// How to pass an argument for `param`, following the classification the fullnode uses when
+/// resolving JSON arguments, except that pure values can also be passed by reference.
+fn param_kind(param: &SuiMoveNormalizedType, type_args: &[TypeTag]) -> Option<ParamKind> {
+    use SuiMoveNormalizedType as T;
+    let (inner, by_imm_ref, by_ref) = match param {
+        T::Reference(inner) => (&**inner, true, true),
+        T::MutableReference(inner) => (&**inner, false, true),
+        _ => (param, false, false),
+    };
+
+    if let Some(layout) = pure_layout(inner, type_args) {
+        return Some(ParamKind::Pure(layout));
+    }
+
+    Some(match inner {
+        T::Struct { .. } | T::TypeParameter(_) => ParamKind::Object {
+            mutable: !by_imm_ref,
+            receiving: is_receiving(inner),
+        },
+
+        T::Vector(elem) if !by_ref && matches!(**elem, T::Struct { .. }) => ParamKind::ObjVec,
+
+        _ => return None,
+    })
+}
+
+/// The layout of `param` if it is a pure type, that can be passed as a BCS-encoded value.
+fn pure_layout(param: &SuiMoveNormalizedType, type_args: &[TypeTag]) -> Option<MoveTypeLayout> {
+    use MoveTypeLayout as L;
+    use SuiMoveNormalizedType as T;
+    Some(match param {
+        T::Bool => L::Bool,
+        T::U8 => L::U8,
+        T::U16 => L::U16,
+        T::U32 => L::U32,
+        T::U64 => L::U64,
+        T::U128 => L::U128,
+        T::U256 => L::U256,
+        T::Address => L::Address,
+        T::Vector(inner) => L::Vector(Box::new(pure_layout(inner, type_args)?)),
+        T::TypeParameter(ix) => type_tag_layout(type_args.get(*ix as usize)?)?,
+
+        T::Struct { type_arguments, .. } => {
+            if is_struct(param, RESOLVED_ASCII_STR) {
+                L::Struct(Box::new(move_ascii_str_layout()))
+            } else if is_struct(param, RESOLVED_UTF8_STR) {
+                L::Struct(Box::new(move_utf8_str_layout()))
+            } else if is_struct(param, RESOLVED_SUI_ID) {
+                L::Struct(Box::new(ID::layout()))
+            } else if is_struct(param, RESOLVED_STD_OPTION) && type_arguments.len() == 1 {
+                // Options are represented as vectors of zero or one elements.
+                L::Vector(Box::new(pure_layout(&type_arguments[0], type_args)?))
+            } else {
+                return None;
+            }
+        }
+
+        T::Signer | T::Reference(_) | T::MutableReference(_) => return None,
+    })
+}
+
+/// The layout of a type argument, if it is a pure type.
+fn type_tag_layout(tag: &TypeTag) -> Option<MoveTypeLayout> {
+    use MoveTypeLayout as L;
+    Some(match tag {
+        TypeTag::Bool => L::Bool,
+        TypeTag::U8 => L::U8,
+        TypeTag::U16 => L::U16,
+        TypeTag::U32 => L::U32,
+        TypeTag::U64 => L::U64,
+        TypeTag::U128 => L::U128,
+        TypeTag::U256 => L::U256,
+        TypeTag::Address => L::Address,
+        TypeTag::Vector(inner) => L::Vector(Box::new(type_tag_layout(inner)?)),
+        TypeTag::Struct(s) => {
+            let resolved = (&s.address, s.module.as_ident_str(), s.name.as_ident_str());
+            if resolved == RESOLVED_ASCII_STR {
+                L::Struct(Box::new(move_ascii_str_layout()))
+            } else if resolved == RESOLVED_UTF8_STR {
+                L::Struct(Box::new(move_utf8_str_layout()))
+            } else if resolved == RESOLVED_SUI_ID {
+                L::Struct(Box::new(ID::layout()))
+            } else {
+                return None;
+            }
+        }
+        TypeTag::Signer => return None,
+    })
+}
+
+fn is_tx_context(param: &SuiMoveNormalizedType) -> bool {
+    use SuiMoveNormalizedType as T;
+    let (T::Reference(inner) | T::MutableReference(inner)) = param else {
+        return false;
+    };
+
+    is_struct(
+        inner,
+        (
+            &SUI_FRAMEWORK_ADDRESS,
+            TX_CONTEXT_MODULE_NAME,
+            TX_CONTEXT_STRUCT_NAME,
+        ),
+    )
+}
+
+fn is_receiving(ty: &SuiMoveNormalizedType) -> bool {
+    matches!(
+        ty,
+        SuiMoveNormalizedType::Struct { type_arguments, .. } if type_arguments.len() == 1,
+    ) && is_struct(ty, RESOLVED_RECEIVING_STRUCT)
+}
+
+fn is_struct(
+    ty: &SuiMoveNormalizedType,
+    (address, module, name): (&AccountAddress, &IdentStr, &IdentStr),
+) -> bool {
+    let SuiMoveNormalizedType::Struct {
+        address: a,
+        module: m,
+        name: n,
+        ..
+    } = ty
+    else {
+        return false;
+    };
+
+    AccountAddress::from_hex_literal(a).is_ok_and(|a| a == *address)
+        && m == module.as_str()
+        && n == name.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use SuiMoveNormalizedType as T;
+
+    fn struct_(address: &str, module: &str, name: &str, type_arguments: Vec<T>) -> T {
+        T::Struct {
+            address: address.to_owned(),
+            module: module.to_owned(),
+            name: name.to_owned(),
+            type_arguments,
+        }
+    }
+
+    fn coin() -> T {
+        struct_(
+            "0x2",
+            "coin",
+            "Coin",
+            vec![struct_("0x2", "sui", "SUI", vec![])],
+        )
+    }
+
+    fn kind(param: T, type_args: &[TypeTag]) -> Option<ParamKind> {
+        param_kind(&param, type_args)
+    }
+
+    fn is_object(kind: Option<ParamKind>, mutable: bool, receiving: bool) -> bool {
+        matches!(
+            kind,
+            Some(ParamKind::Object { mutable: m, receiving: r }) if m == mutable && r == receiving,
+        )
+    }
+
+    #[test]
+    fn test_pure_params() {
+        use MoveTypeLayout as L;
+        assert!(matches!(kind(T::U64, &[]), Some(ParamKind::Pure(L::U64))));
+        assert!(matches!(
+            kind(T::Vector(Box::new(T::Address)), &[]),
+            Some(ParamKind::Pure(L::Vector(_)))
+        ));
+        assert!(matches!(
+            kind(struct_("0x1", "string", "String", vec![]), &[]),
+            Some(ParamKind::Pure(L::Struct(_)))
+        ));
+        assert!(matches!(
+            kind(T::TypeParameter(0), &[TypeTag::U8]),
+            Some(ParamKind::Pure(L::U8))
+        ));
+
+        // Pure values passed by reference are still pure.
+        assert!(matches!(
+            kind(T::Reference(Box::new(T::U64)), &[]),
+            Some(ParamKind::Pure(L::U64))
+        ));
+        assert!(matches!(
+            kind(
+                T::MutableReference(Box::new(T::Vector(Box::new(T::U8)))),
+                &[]
+            ),
+            Some(ParamKind::Pure(L::Vector(_)))
+        ));
+    }
+
+    #[test]
+    fn test_object_params() {
+        let coin_tag = TypeTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap();
+
+        // Only objects passed by immutable reference are accessed immutably.
+        assert!(is_object(
+            kind(T::Reference(Box::new(coin())), &[]),
+            false,
+            false
+        ));
+        assert!(is_object(
+            kind(T::MutableReference(Box::new(coin())), &[]),
+            true,
+            false
+        ));
+        assert!(is_object(kind(coin(), &[]), true, false));
+
+        assert!(is_object(
+            kind(T::TypeParameter(0), &[coin_tag.clone()]),
+            true,
+            false
+        ));
+        assert!(is_object(
+            kind(T::Reference(Box::new(T::TypeParameter(0))), &[coin_tag]),
+            false,
+            false
+        ));
+    }
+
+    #[test]
+    fn test_receiving_params() {
+        let receiving = || struct_("0x2", "transfer", "Receiving", vec![coin()]);
+        assert!(is_object(kind(receiving(), &[]), true, true));
+        assert!(is_object(
+            kind(T::Reference(Box::new(receiving())), &[]),
+            false,
+            true
+        ));
+
+        // A `Receiving` without its type argument is not the framework's type.
+        assert!(is_object(
+            kind(struct_("0x2", "transfer", "Receiving", vec![]), &[]),
+            true,
+            false
+        ));
+    }
+
+    #[test]
+    fn test_vector_params() {
+        assert!(matches!(
+            kind(T::Vector(Box::new(coin())), &[]),
+            Some(ParamKind::ObjVec)
+        ));
+
+        // Vectors of objects can only be passed by value, and vectors of type parameters that are
+        // not pure are not supported.
+        assert!(kind(T::Reference(Box::new(T::Vector(Box::new(coin())))), &[]).is_none());
+        assert!(kind(T::Vector(Box::new(T::TypeParameter(0))), &[]).is_none());
+        assert!(kind(T::Signer, &[]).is_none());
+        assert!(kind(T::Reference(Box::new(T::Signer)), &[]).is_none());
+    }
+}
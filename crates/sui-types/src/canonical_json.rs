@@ -0,0 +1,177 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A canonical JSON encoding for transaction effects, events and object changes, suitable for
+//! hashing and diffing outside of Sui.
+//!
+//! The canonical encoding of a value starts from its `serde` JSON representation (the same
+//! representation produced by `serde_json::to_value`), and then applies the following rules,
+//! which make the output valid input to, and a fixed point of,
+//! [RFC 8785](https://www.rfc-editor.org/rfc/rfc8785) (JSON Canonicalization Scheme):
+//!
+//! - Object members are sorted by key, comparing keys as sequences of UTF-16 code units, so the
+//!   output does not depend on the order in which fields are declared or serialized.
+//! - There is no insignificant whitespace.
+//! - Strings are escaped minimally: `"`, `\` and control characters are escaped (using the short
+//!   forms `\b`, `\t`, `\n`, `\f`, `\r` where available, and `\u00XX` otherwise), and all other
+//!   characters are written as UTF-8.
+//! - Integers between `-(2^53 - 1)` and `2^53 - 1` (inclusive) are written in full, without
+//!   exponents, leading zeros, or a fractional part. Integers outside that range can't be
+//!   represented exactly as IEEE 754 doubles (which RFC 8785 requires of all numbers), so they
+//!   are written as strings containing their decimal representation instead.
+//! - None of the supported types contain floating point numbers, and encoding a value that does
+//!   fails with [`CanonicalJsonError::FloatingPoint`].
+//!
+//! The shape of the encoded value is determined by the `serde` representation of the type:
+//!
+//! - `TransactionEffects` is an object with a single key naming its version (`"V1"` or `"V2"`),
+//!   whose value is an object containing the fields of that version of the effects.
+//! - `TransactionEvents` is an object with a single `"data"` key, holding an array of events.
+//! - `Event` is an object with keys `"contents"` (an array of bytes, the BCS encoding of the
+//!   event), `"package_id"`, `"sender"`, `"transaction_module"`, and `"type_"`.
+//! - `ObjectChange` is an object with keys `"id"`, `"id_operation"`, `"input_digest"`,
+//!   `"input_version"`, `"output_digest"` and `"output_version"`, where missing versions and
+//!   digests are `null`.
+//!
+//! Digests and addresses are encoded as they are in their `serde` representations (Base58 and
+//! `0x`-prefixed hex strings respectively), and byte arrays are encoded as arrays of integers.
+//!
+//! A [JSON Schema](https://json-schema.org/draft/2020-12) describing these shapes is available as
+//! [`SCHEMA`].
+
+use std::io::Write;
+
+use fastcrypto::hash::HashFunction;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::crypto::DefaultHash;
+use crate::effects::{ObjectChange, TransactionEffects, TransactionEvents};
+use crate::event::Event;
+
+#[cfg(test)]
+#[path = "unit_tests/canonical_json_tests.rs"]
+mod canonical_json_tests;
+
+/// JSON Schema (draft 2020-12) for the canonical JSON encodings of the types that implement
+/// [`CanonicalJson`].
+pub const SCHEMA: &str = include_str!("canonical_json.schema.json");
+
+/// The largest integer that can be represented exactly as an IEEE 754 double (`2^53 - 1`).
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CanonicalJsonError {
+    #[error("Failed to serialize value to JSON: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Canonical JSON does not support floating point numbers: {0}")]
+    FloatingPoint(serde_json::Number),
+}
+
+/// Types with a canonical JSON encoding, as described in the [module documentation](self).
+pub trait CanonicalJson: Serialize {
+    /// The canonical JSON encoding of this value, as UTF-8 bytes.
+    fn to_canonical_json_bytes(&self) -> Result<Vec<u8>, CanonicalJsonError> {
+        let value = serde_json::to_value(self)?;
+        let mut buf = vec![];
+        write_canonical(&mut buf, &value)?;
+        Ok(buf)
+    }
+
+    /// The canonical JSON encoding of this value.
+    fn to_canonical_json(&self) -> Result<String, CanonicalJsonError> {
+        let bytes = self.to_canonical_json_bytes()?;
+        Ok(String::from_utf8(bytes).expect("Canonical JSON is always valid UTF-8"))
+    }
+
+    /// The Blake2b256 digest of this value's canonical JSON encoding.
+    fn canonical_json_digest(&self) -> Result<[u8; 32], CanonicalJsonError> {
+        let bytes = self.to_canonical_json_bytes()?;
+        Ok(DefaultHash::digest(bytes).digest)
+    }
+}
+
+impl CanonicalJson for TransactionEffects {}
+impl CanonicalJson for TransactionEvents {}
+impl CanonicalJson for Event {}
+impl CanonicalJson for ObjectChange {}
+impl<T: CanonicalJson> CanonicalJson for [T] {}
+impl<T: CanonicalJson> CanonicalJson for Vec<T> {}
+
+fn write_canonical(buf: &mut Vec<u8>, value: &Value) -> Result<(), CanonicalJsonError> {
+    match value {
+        Value::Null => buf.extend_from_slice(b"null"),
+        Value::Bool(b) => buf.extend_from_slice(if *b { b"true" } else { b"false" }),
+
+        Value::Number(n) => {
+            if n.is_f64() {
+                return Err(CanonicalJsonError::FloatingPoint(n.clone()));
+            }
+
+            let safe = if let Some(u) = n.as_u64() {
+                u <= MAX_SAFE_INTEGER
+            } else {
+                n.as_i64()
+                    .is_some_and(|i| i.unsigned_abs() <= MAX_SAFE_INTEGER)
+            };
+
+            if safe {
+                write!(buf, "{n}").expect("Writing to a Vec cannot fail");
+            } else {
+                write!(buf, "\"{n}\"").expect("Writing to a Vec cannot fail");
+            }
+        }
+
+        Value::String(s) => write_string(buf, s),
+
+        Value::Array(elements) => {
+            buf.push(b'[');
+            for (i, element) in elements.iter().enumerate() {
+                if i > 0 {
+                    buf.push(b',');
+                }
+                write_canonical(buf, element)?;
+            }
+            buf.push(b']');
+        }
+
+        Value::Object(members) => {
+            let mut members: Vec<_> = members.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            buf.push(b'{');
+            for (i, (key, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    buf.push(b',');
+                }
+                write_string(buf, key);
+                buf.push(b':');
+                write_canonical(buf, value)?;
+            }
+            buf.push(b'}');
+        }
+    }
+
+    Ok(())
+}
+
+fn write_string(buf: &mut Vec<u8>, s: &str) {
+    buf.push(b'"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.extend_from_slice(b"\\\""),
+            '\\' => buf.extend_from_slice(b"\\\\"),
+            '\u{08}' => buf.extend_from_slice(b"\\b"),
+            '\t' => buf.extend_from_slice(b"\\t"),
+            '\n' => buf.extend_from_slice(b"\\n"),
+            '\u{0C}' => buf.extend_from_slice(b"\\f"),
+            '\r' => buf.extend_from_slice(b"\\r"),
+            c if c < ' ' => {
+                write!(buf, "\\u{:04x}", c as u32).expect("Writing to a Vec cannot fail")
+            }
+            c => buf.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    buf.push(b'"');
+}
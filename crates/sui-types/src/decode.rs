@@ -0,0 +1,594 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decode `TransactionData` into a structured, human-readable description, for review screens
+//! that need to show a user what they are about to sign.
+//!
+//! Arguments are resolved to the inputs they refer to, and pure inputs are interpreted using the
+//! position they are used in, where that is unambiguous (amounts to split off coins are `u64`s,
+//! recipients of transfers are addresses). Calls to well-known framework functions are labelled
+//! with a short description of what they do.
+
+use std::fmt;
+
+use fastcrypto::encoding::{Encoding, Hex};
+use move_core_types::language_storage::TypeTag;
+use serde::Serialize;
+
+use crate::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    digests::ObjectDigest,
+    gas_coin::GAS,
+    transaction::{
+        Argument, CallArg, Command, ObjectArg, ProgrammableTransaction, TransactionData,
+        TransactionDataAPI, TransactionExpiration, TransactionKind,
+    },
+    SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID,
+};
+
+#[cfg(test)]
+#[path = "unit_tests/decode_tests.rs"]
+mod decode_tests;
+
+/// Well-known framework functions, and a description of what calling them does.
+const KNOWN_FUNCTIONS: &[(ObjectID, &str, &str, &str)] = &[
+    (SUI_FRAMEWORK_PACKAGE_ID, "coin", "split", "Split coin"),
+    (SUI_FRAMEWORK_PACKAGE_ID, "coin", "join", "Merge coins"),
+    (SUI_FRAMEWORK_PACKAGE_ID, "pay", "split", "Split coin"),
+    (SUI_FRAMEWORK_PACKAGE_ID, "pay", "join", "Merge coins"),
+    (SUI_FRAMEWORK_PACKAGE_ID, "pay", "join_vec", "Merge coins"),
+    (
+        SUI_FRAMEWORK_PACKAGE_ID,
+        "transfer",
+        "public_transfer",
+        "Transfer object",
+    ),
+    (
+        SUI_FRAMEWORK_PACKAGE_ID,
+        "transfer",
+        "public_share_object",
+        "Share object",
+    ),
+    (
+        SUI_FRAMEWORK_PACKAGE_ID,
+        "transfer",
+        "public_freeze_object",
+        "Freeze object",
+    ),
+    (
+        SUI_FRAMEWORK_PACKAGE_ID,
+        "package",
+        "make_immutable",
+        "Make package immutable",
+    ),
+    (
+        SUI_FRAMEWORK_PACKAGE_ID,
+        "package",
+        "authorize_upgrade",
+        "Authorize package upgrade",
+    ),
+    (
+        SUI_FRAMEWORK_PACKAGE_ID,
+        "package",
+        "commit_upgrade",
+        "Commit package upgrade",
+    ),
+    (
+        SUI_SYSTEM_PACKAGE_ID,
+        "sui_system",
+        "request_add_stake",
+        "Stake SUI",
+    ),
+    (
+        SUI_SYSTEM_PACKAGE_ID,
+        "sui_system",
+        "request_add_stake_mul_coin",
+        "Stake SUI",
+    ),
+    (
+        SUI_SYSTEM_PACKAGE_ID,
+        "sui_system",
+        "request_withdraw_stake",
+        "Withdraw stake",
+    ),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    #[error("Invalid transaction data: {0}")]
+    Bcs(#[from] bcs::Error),
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DecodedTransaction {
+    pub sender: SuiAddress,
+    pub gas: DecodedGas,
+
+    /// The epoch after which the transaction can no longer be executed, if any.
+    pub expiration: Option<u64>,
+
+    pub kind: DecodedKind,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DecodedGas {
+    pub owner: SuiAddress,
+    pub budget: u64,
+    pub price: u64,
+    pub payment: Vec<DecodedObject>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DecodedKind {
+    Programmable {
+        commands: Vec<DecodedCommand>,
+    },
+
+    /// Transactions that are only ever created by the system, identified by their kind.
+    System {
+        name: &'static str,
+    },
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum DecodedCommand {
+    MoveCall {
+        /// The function being called, as `package::module::function`.
+        target: String,
+
+        /// What the function does, if it is a well-known framework function.
+        description: Option<&'static str>,
+
+        type_arguments: Vec<String>,
+        arguments: Vec<DecodedValue>,
+    },
+
+    TransferObjects {
+        objects: Vec<DecodedValue>,
+        recipient: DecodedValue,
+    },
+
+    SplitCoins {
+        coin: DecodedValue,
+        amounts: Vec<DecodedValue>,
+    },
+
+    MergeCoins {
+        coin: DecodedValue,
+        sources: Vec<DecodedValue>,
+    },
+
+    MakeMoveVec {
+        type_: Option<String>,
+        elements: Vec<DecodedValue>,
+    },
+
+    Publish {
+        modules: usize,
+        dependencies: Vec<ObjectID>,
+    },
+
+    Upgrade {
+        package: ObjectID,
+        modules: usize,
+        dependencies: Vec<ObjectID>,
+        ticket: DecodedValue,
+    },
+}
+
+/// A command argument, resolved to the input it refers to, if any.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DecodedValue {
+    GasCoin,
+    Object(DecodedObject),
+    Address {
+        address: SuiAddress,
+    },
+    Amount {
+        amount: u64,
+
+        /// The type of coin the amount is denominated in (e.g. `0x2::sui::SUI`), if it could be
+        /// determined from the transaction.
+        coin_type: Option<String>,
+    },
+
+    /// A pure input that could not be interpreted from context, as raw BCS bytes.
+    Pure {
+        bytes: Vec<u8>,
+    },
+
+    /// The result of an earlier command.
+    Result {
+        command: u16,
+    },
+
+    /// One of the results of an earlier command.
+    NestedResult {
+        command: u16,
+        index: u16,
+    },
+
+    /// An argument referring to an input that does not exist.
+    InvalidInput {
+        input: u16,
+    },
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "ownership", rename_all = "snake_case")]
+pub enum DecodedObject {
+    Owned {
+        id: ObjectID,
+        version: SequenceNumber,
+        digest: ObjectDigest,
+    },
+
+    Shared {
+        id: ObjectID,
+        initial_shared_version: SequenceNumber,
+        mutable: bool,
+    },
+
+    Receiving {
+        id: ObjectID,
+        version: SequenceNumber,
+        digest: ObjectDigest,
+    },
+}
+
+/// How a pure input should be interpreted, based on where it is used.
+#[derive(Clone)]
+enum PureHint {
+    Address,
+    /// An amount of a coin, with the coin's type, if known.
+    Amount(Option<TypeTag>),
+    Unknown,
+}
+
+/// Decode BCS-encoded `TransactionData`.
+pub fn decode_transaction_bytes(bytes: &[u8]) -> Result<DecodedTransaction, DecodeError> {
+    let data: TransactionData = bcs::from_bytes(bytes)?;
+    Ok(decode_transaction(&data))
+}
+
+pub fn decode_transaction(data: &TransactionData) -> DecodedTransaction {
+    let gas = data.gas_data();
+
+    DecodedTransaction {
+        sender: data.sender(),
+        gas: DecodedGas {
+            owner: gas.owner,
+            budget: gas.budget,
+            price: gas.price,
+            payment: gas
+                .payment
+                .iter()
+                .map(|&(id, version, digest)| DecodedObject::Owned {
+                    id,
+                    version,
+                    digest,
+                })
+                .collect(),
+        },
+        expiration: match data.expiration() {
+            TransactionExpiration::None => None,
+            TransactionExpiration::Epoch(epoch) => Some(*epoch),
+        },
+        kind: match data.kind() {
+            TransactionKind::ProgrammableTransaction(pt) => DecodedKind::Programmable {
+                commands: decode_commands(pt),
+            },
+            kind => DecodedKind::System { name: kind.name() },
+        },
+    }
+}
+
+fn decode_commands(pt: &ProgrammableTransaction) -> Vec<DecodedCommand> {
+    use PureHint as H;
+    let value = |arg: &Argument, hint: PureHint| decode_argument(&pt.inputs, arg, hint);
+    let values = |args: &[Argument], hint: PureHint| -> Vec<DecodedValue> {
+        args.iter().map(|a| value(a, hint.clone())).collect()
+    };
+
+    pt.commands
+        .iter()
+        .enumerate()
+        .map(|(i, command)| match command {
+            Command::MoveCall(call) => DecodedCommand::MoveCall {
+                target: format!("{}::{}::{}", call.package, call.module, call.function),
+                description: KNOWN_FUNCTIONS
+                    .iter()
+                    .find(|(p, m, f, _)| {
+                        *p == call.package && *m == call.module && *f == call.function
+                    })
+                    .map(|(_, _, _, description)| *description),
+                type_arguments: call
+                    .type_arguments
+                    .iter()
+                    .map(|t| t.to_canonical_string(/* with_prefix */ true))
+                    .collect(),
+                arguments: values(&call.arguments, H::Unknown),
+            },
+
+            Command::TransferObjects(objects, recipient) => DecodedCommand::TransferObjects {
+                objects: values(objects, H::Unknown),
+                recipient: value(recipient, H::Address),
+            },
+
+            Command::SplitCoins(coin, amounts) => DecodedCommand::SplitCoins {
+                coin: value(coin, H::Unknown),
+                amounts: values(amounts, H::Amount(coin_type(&pt.commands[..i], coin))),
+            },
+
+            Command::MergeCoins(coin, sources) => DecodedCommand::MergeCoins {
+                coin: value(coin, H::Unknown),
+                sources: values(sources, H::Unknown),
+            },
+
+            Command::MakeMoveVec(type_, elements) => DecodedCommand::MakeMoveVec {
+                type_: type_
+                    .as_ref()
+                    .map(|t| t.to_canonical_string(/* with_prefix */ true)),
+                elements: values(elements, H::Unknown),
+            },
+
+            Command::Publish(modules, dependencies) => DecodedCommand::Publish {
+                modules: modules.len(),
+                dependencies: dependencies.clone(),
+            },
+
+            Command::Upgrade(modules, dependencies, package, ticket) => DecodedCommand::Upgrade {
+                package: *package,
+                modules: modules.len(),
+                dependencies: dependencies.clone(),
+                ticket: value(ticket, H::Unknown),
+            },
+        })
+        .collect()
+}
+
+/// The type of coin that `arg` refers to, if it can be determined from the commands that precede
+/// it: The gas coin is always a SUI coin, and coins split off another coin, or by a call to a
+/// framework function that splits or creates coins, share its type.
+fn coin_type(preceding: &[Command], arg: &Argument) -> Option<TypeTag> {
+    let command = match *arg {
+        Argument::GasCoin => return Some(GAS::type_tag()),
+        Argument::Input(_) => return None,
+        Argument::Result(command) | Argument::NestedResult(command, _) => command as usize,
+    };
+
+    match preceding.get(command)? {
+        Command::SplitCoins(coin, _) => coin_type(&preceding[..command], coin),
+        Command::MoveCall(call)
+            if call.package == SUI_FRAMEWORK_PACKAGE_ID
+                && matches!(
+                    (call.module.as_str(), call.function.as_str()),
+                    ("coin", "split" | "take" | "from_balance" | "mint")
+                )
+                && call.type_arguments.len() == 1 =>
+        {
+            Some(call.type_arguments[0].clone())
+        }
+        _ => None,
+    }
+}
+
+fn decode_argument(inputs: &[CallArg], arg: &Argument, hint: PureHint) -> DecodedValue {
+    match *arg {
+        Argument::GasCoin => DecodedValue::GasCoin,
+        Argument::Result(command) => DecodedValue::Result { command },
+        Argument::NestedResult(command, index) => DecodedValue::NestedResult { command, index },
+        Argument::Input(input) => match inputs.get(input as usize) {
+            None => DecodedValue::InvalidInput { input },
+            Some(CallArg::Object(obj)) => DecodedValue::Object(decode_object(obj)),
+            Some(CallArg::Pure(bytes)) => decode_pure(bytes, hint),
+        },
+    }
+}
+
+fn decode_object(obj: &ObjectArg) -> DecodedObject {
+    match *obj {
+        ObjectArg::ImmOrOwnedObject((id, version, digest)) => DecodedObject::Owned {
+            id,
+            version,
+            digest,
+        },
+        ObjectArg::SharedObject {
+            id,
+            initial_shared_version,
+            mutable,
+        } => DecodedObject::Shared {
+            id,
+            initial_shared_version,
+            mutable,
+        },
+        ObjectArg::Receiving((id, version, digest)) => DecodedObject::Receiving {
+            id,
+            version,
+            digest,
+        },
+    }
+}
+
+fn decode_pure(bytes: &[u8], hint: PureHint) -> DecodedValue {
+    match hint {
+        PureHint::Address => {
+            if let Ok(address) = bcs::from_bytes(bytes) {
+                return DecodedValue::Address { address };
+            }
+        }
+        PureHint::Amount(coin_type) => {
+            if let Ok(amount) = bcs::from_bytes(bytes) {
+                return DecodedValue::Amount {
+                    amount,
+                    coin_type: coin_type
+                        .map(|t| t.to_canonical_string(/* with_prefix */ true)),
+                };
+            }
+        }
+        PureHint::Unknown => {}
+    }
+
+    DecodedValue::Pure {
+        bytes: bytes.to_vec(),
+    }
+}
+
+impl fmt::Display for DecodedTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Sender: {}", self.sender)?;
+        if let Some(epoch) = self.expiration {
+            writeln!(f, "Expires after epoch: {epoch}")?;
+        }
+
+        writeln!(
+            f,
+            "Gas: budget {} MIST at price {} MIST, paid by {}",
+            self.gas.budget, self.gas.price, self.gas.owner
+        )?;
+
+        match &self.kind {
+            DecodedKind::System { name } => writeln!(f, "System transaction: {name}"),
+            DecodedKind::Programmable { commands } => {
+                for (i, command) in commands.iter().enumerate() {
+                    writeln!(f, "{i}: {command}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for DecodedCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use DecodedCommand as C;
+        match self {
+            C::MoveCall {
+                target,
+                description,
+                type_arguments,
+                arguments,
+            } => {
+                if let Some(description) = description {
+                    write!(f, "{description} (")?;
+                }
+                write!(f, "Call {target}")?;
+                if !type_arguments.is_empty() {
+                    write!(f, "<{}>", type_arguments.join(", "))?;
+                }
+                write!(f, "(")?;
+                write_values(f, arguments)?;
+                write!(f, ")")?;
+                if description.is_some() {
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+
+            C::TransferObjects { objects, recipient } => {
+                write!(f, "Transfer ")?;
+                write_values(f, objects)?;
+                write!(f, " to {recipient}")
+            }
+
+            C::SplitCoins { coin, amounts } => {
+                write!(f, "Split ")?;
+                write_values(f, amounts)?;
+                write!(f, " from {coin}")
+            }
+
+            C::MergeCoins { coin, sources } => {
+                write!(f, "Merge ")?;
+                write_values(f, sources)?;
+                write!(f, " into {coin}")
+            }
+
+            C::MakeMoveVec { type_, elements } => {
+                write!(f, "Make vector")?;
+                if let Some(type_) = type_ {
+                    write!(f, "<{type_}>")?;
+                }
+                write!(f, " [")?;
+                write_values(f, elements)?;
+                write!(f, "]")
+            }
+
+            C::Publish {
+                modules,
+                dependencies,
+            } => write!(
+                f,
+                "Publish package ({modules} modules, {} dependencies)",
+                dependencies.len()
+            ),
+
+            C::Upgrade {
+                package,
+                modules,
+                dependencies,
+                ticket,
+            } => write!(
+                f,
+                "Upgrade package {package} ({modules} modules, {} dependencies) with {ticket}",
+                dependencies.len()
+            ),
+        }
+    }
+}
+
+impl fmt::Display for DecodedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use DecodedValue as V;
+        match self {
+            V::GasCoin => write!(f, "gas coin"),
+            V::Object(obj) => write!(f, "{obj}"),
+            V::Address { address } => write!(f, "{address}"),
+            V::Amount {
+                amount,
+                coin_type: None,
+            } => write!(f, "{amount}"),
+            V::Amount {
+                amount,
+                coin_type: Some(coin_type),
+            } => {
+                if *coin_type == GAS::type_tag().to_canonical_string(/* with_prefix */ true) {
+                    write!(f, "{amount} MIST")
+                } else {
+                    write!(f, "{amount} of {coin_type}")
+                }
+            }
+            V::Pure { bytes } => write!(f, "0x{}", Hex::encode(bytes)),
+            V::Result { command } => write!(f, "result of command {command}"),
+            V::NestedResult { command, index } => {
+                write!(f, "result {index} of command {command}")
+            }
+            V::InvalidInput { input } => write!(f, "invalid input {input}"),
+        }
+    }
+}
+
+impl fmt::Display for DecodedObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodedObject::Owned { id, .. } => write!(f, "object {id}"),
+            DecodedObject::Shared { id, mutable, .. } => {
+                let access = if *mutable { "mutable" } else { "immutable" };
+                write!(f, "shared object {id} ({access})")
+            }
+            DecodedObject::Receiving { id, .. } => write!(f, "received object {id}"),
+        }
+    }
+}
+
+fn write_values(f: &mut fmt::Formatter<'_>, values: &[DecodedValue]) -> fmt::Result {
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{value}")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,256 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversions between versions of `TransactionEffects`, for stores that hold effects in one
+//! version and need to serve them in another.
+//!
+//! Neither version can represent everything the other does, so each conversion reports the
+//! information it could not carry over (see [`EffectsConversionLoss`]). Converted effects have a
+//! different digest from the effects they were converted from, so they cannot be checked against
+//! a certificate or a checkpoint, and should only be used to present effects to clients.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{
+    EffectsObjectChange, IDOperation, InputSharedObject, ObjectIn, ObjectOut, TransactionEffects,
+    TransactionEffectsAPI, UnchangedSharedKind,
+};
+use crate::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use crate::digests::{ObjectDigest, TransactionDigest};
+use crate::execution::SharedInput;
+use crate::object::Owner;
+
+/// Information that was lost while converting effects between versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EffectsConversionLoss {
+    /// The digest and owner the object had before the transaction. V1 effects only record the
+    /// version of modified objects, so when converting to V1 they are dropped, and when
+    /// converting to V2, placeholders are used unless they were supplied: `ObjectDigest::MIN`, and
+    /// the object's new owner (or an address owner if it was frozen or no longer exists).
+    InputState(ObjectID),
+
+    /// An object that was created and then wrapped by the transaction, which V1 effects do not
+    /// record.
+    CreatedAndWrapped(ObjectID),
+
+    /// A per-epoch config object that was read by the transaction, which V1 effects do not
+    /// record.
+    PerEpochConfig(ObjectID),
+
+    /// The digest of the effects' auxiliary data, which V1 effects do not have.
+    AuxDataDigest,
+}
+
+/// The result of converting effects to another version.
+#[derive(Debug, Clone)]
+pub struct ConvertedEffects {
+    pub effects: TransactionEffects,
+
+    /// Information from the original effects that is missing, or replaced with placeholders, in
+    /// `effects`. Empty if the conversion was lossless.
+    pub losses: Vec<EffectsConversionLoss>,
+}
+
+impl TransactionEffects {
+    /// Convert these effects to V1. Effects that are already V1 are returned as-is.
+    pub fn to_v1(&self) -> ConvertedEffects {
+        let TransactionEffects::V2(v2) = self else {
+            return ConvertedEffects::lossless(self.clone());
+        };
+
+        let mut losses: Vec<_> = self
+            .modified_at_versions()
+            .into_iter()
+            .map(|(id, _)| EffectsConversionLoss::InputState(id))
+            .collect();
+
+        for change in self.object_changes() {
+            if change.input_version.is_none()
+                && change.output_version.is_none()
+                && change.id_operation == IDOperation::Created
+            {
+                losses.push(EffectsConversionLoss::CreatedAndWrapped(change.id));
+            }
+        }
+
+        for (id, kind) in self.unchanged_shared_objects() {
+            if matches!(kind, UnchangedSharedKind::PerEpochConfig) {
+                losses.push(EffectsConversionLoss::PerEpochConfig(id));
+            }
+        }
+
+        if v2.aux_data_digest().is_some() {
+            losses.push(EffectsConversionLoss::AuxDataDigest);
+        }
+
+        let effects = TransactionEffects::new_from_execution_v1(
+            self.status().clone(),
+            self.executed_epoch(),
+            self.gas_cost_summary().clone(),
+            self.modified_at_versions(),
+            self.input_shared_objects()
+                .iter()
+                .map(InputSharedObject::object_ref)
+                .collect(),
+            *self.transaction_digest(),
+            self.created(),
+            self.mutated(),
+            self.unwrapped(),
+            self.deleted(),
+            self.unwrapped_then_deleted(),
+            self.wrapped(),
+            self.gas_object(),
+            self.events_digest().copied(),
+            self.dependencies().to_vec(),
+        );
+
+        ConvertedEffects { effects, losses }
+    }
+
+    /// Convert these effects to V2. Effects that are already V2 are returned as-is.
+    ///
+    /// V1 effects do not record the digests and owners of objects before the transaction, so
+    /// they are looked up with `input_state`, given an object's ID and input version. A
+    /// placeholder is used for any object it returns `None` for, and reported as a loss.
+    pub fn to_v2(
+        &self,
+        input_state: impl Fn(ObjectID, SequenceNumber) -> Option<(ObjectDigest, Owner)>,
+    ) -> ConvertedEffects {
+        if matches!(self, TransactionEffects::V2(_)) {
+            return ConvertedEffects::lossless(self.clone());
+        }
+
+        let lamport_version = self.lamport_version();
+        let mut losses = vec![];
+        let mut changed_objects = BTreeMap::new();
+
+        let mut inputs: BTreeMap<_, _> = self.modified_at_versions().into_iter().collect();
+        let mut add_change = |id: ObjectID,
+                              placeholder_owner: Owner,
+                              output_state: ObjectOut,
+                              id_operation: IDOperation| {
+            let input = match inputs.remove(&id) {
+                None => ObjectIn::NotExist,
+                Some(version) => ObjectIn::Exist(match input_state(id, version) {
+                    Some((digest, owner)) => ((version, digest), owner),
+                    None => {
+                        losses.push(EffectsConversionLoss::InputState(id));
+                        ((version, ObjectDigest::MIN), placeholder_owner)
+                    }
+                }),
+            };
+
+            changed_objects.insert(
+                id,
+                EffectsObjectChange {
+                    input_state: input,
+                    output_state,
+                    id_operation,
+                },
+            );
+        };
+
+        // V1 effects do not distinguish packages from other immutable objects, but packages are
+        // the only objects that are written at a version other than the lamport version. The
+        // placeholder input owner for a written object is its new owner, unless it was frozen, or
+        // it is a package.
+        let placeholder_owner = Owner::AddressOwner(SuiAddress::ZERO);
+        let write = |(_, version, digest), owner: Owner| {
+            if version != lamport_version {
+                (Owner::Immutable, ObjectOut::PackageWrite((version, digest)))
+            } else if owner.is_immutable() {
+                let placeholder = placeholder_owner.clone();
+                (placeholder, ObjectOut::ObjectWrite((digest, owner)))
+            } else {
+                (owner.clone(), ObjectOut::ObjectWrite((digest, owner)))
+            }
+        };
+
+        for (oref, owner) in self.created() {
+            let (placeholder, output) = write(oref, owner);
+            add_change(oref.0, placeholder, output, IDOperation::Created);
+        }
+
+        for (oref, owner) in self.mutated().into_iter().chain(self.unwrapped()) {
+            let (placeholder, output) = write(oref, owner);
+            add_change(oref.0, placeholder, output, IDOperation::None);
+        }
+
+        for (id, _, _) in self.deleted() {
+            let owner = placeholder_owner.clone();
+            add_change(id, owner, ObjectOut::NotExist, IDOperation::Deleted);
+        }
+
+        for (id, _, _) in self.unwrapped_then_deleted() {
+            let owner = placeholder_owner.clone();
+            add_change(id, owner, ObjectOut::NotExist, IDOperation::Deleted);
+        }
+
+        for (id, _, _) in self.wrapped() {
+            let owner = placeholder_owner.clone();
+            add_change(id, owner, ObjectOut::NotExist, IDOperation::None);
+        }
+
+        let shared_objects = self
+            .input_shared_objects()
+            .into_iter()
+            .map(|shared| match shared {
+                InputSharedObject::Mutate(oref) | InputSharedObject::ReadOnly(oref)
+                    if oref.2 == ObjectDigest::OBJECT_DIGEST_DELETED =>
+                {
+                    let mutable = matches!(shared, InputSharedObject::Mutate(_));
+                    SharedInput::Deleted((oref.0, oref.1, mutable, TransactionDigest::ZERO))
+                }
+                InputSharedObject::Mutate(oref) | InputSharedObject::ReadOnly(oref)
+                    if oref.2 == ObjectDigest::OBJECT_DIGEST_CANCELLED =>
+                {
+                    SharedInput::Cancelled((oref.0, oref.1))
+                }
+                InputSharedObject::Mutate(oref) | InputSharedObject::ReadOnly(oref) => {
+                    SharedInput::Existing(oref)
+                }
+                InputSharedObject::ReadDeleted(id, version) => {
+                    SharedInput::Deleted((id, version, false, TransactionDigest::ZERO))
+                }
+                InputSharedObject::MutateDeleted(id, version) => {
+                    SharedInput::Deleted((id, version, true, TransactionDigest::ZERO))
+                }
+                InputSharedObject::Cancelled(id, version) => SharedInput::Cancelled((id, version)),
+            })
+            .collect();
+
+        // System transactions in V1 effects have a placeholder gas object, which is not one of
+        // the objects the transaction changed.
+        let ((gas_id, _, _), _) = self.gas_object();
+        let gas_object = changed_objects.contains_key(&gas_id).then_some(gas_id);
+
+        let effects = TransactionEffects::new_from_execution_v2(
+            self.status().clone(),
+            self.executed_epoch(),
+            self.gas_cost_summary().clone(),
+            shared_objects,
+            BTreeSet::new(),
+            *self.transaction_digest(),
+            lamport_version,
+            changed_objects,
+            gas_object,
+            self.events_digest().copied(),
+            self.dependencies().to_vec(),
+        );
+
+        ConvertedEffects { effects, losses }
+    }
+}
+
+impl ConvertedEffects {
+    fn lossless(effects: TransactionEffects) -> Self {
+        Self {
+            effects,
+            losses: vec![],
+        }
+    }
+
+    pub fn is_lossless(&self) -> bool {
+        self.losses.is_empty()
+    }
+}
@@ -576,6 +576,10 @@ impl TransactionEffectsV2 {
     pub fn changed_objects(&self) -> &[(ObjectID, EffectsObjectChange)] {
         &self.changed_objects
     }
+
+    pub(super) fn aux_data_digest(&self) -> Option<&EffectsAuxDataDigest> {
+        self.aux_data_digest.as_ref()
+    }
 }
 
 impl Default for TransactionEffectsV2 {
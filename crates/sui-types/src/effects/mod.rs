@@ -19,6 +19,7 @@ use crate::gas::GasCostSummary;
 use crate::message_envelope::{Envelope, Message, TrustedEnvelope, VerifiedEnvelope};
 use crate::object::Owner;
 use crate::storage::WriteKind;
+pub use compat::{ConvertedEffects, EffectsConversionLoss};
 use effects_v1::TransactionEffectsV1;
 pub use effects_v2::UnchangedSharedKind;
 use enum_dispatch::enum_dispatch;
@@ -28,11 +29,16 @@ use shared_crypto::intent::{Intent, IntentScope};
 use std::collections::{BTreeMap, BTreeSet};
 pub use test_effects_builder::TestEffectsBuilder;
 
+mod compat;
 mod effects_v1;
 mod effects_v2;
 mod object_change;
 mod test_effects_builder;
 
+#[cfg(test)]
+#[path = "../unit_tests/effects_compat_tests.rs"]
+mod effects_compat_tests;
+
 // Since `std::mem::size_of` may not be stable across platforms, we use rough constants
 // We need these for estimating effects sizes
 // Approximate size of `ObjectRef` type in bytes
@@ -374,7 +380,7 @@ pub trait TransactionEffectsAPI {
     fn unsafe_add_object_tombstone_for_testing(&mut self, obj_ref: ObjectRef);
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ObjectChange {
     pub id: ObjectID,
     pub input_version: Option<SequenceNumber>,
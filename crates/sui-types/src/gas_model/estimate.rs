@@ -0,0 +1,116 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Estimate the gas cost of a transaction from a description of what it does, without executing
+//! it.
+//!
+//! The estimate goes through the same gas meter that execution uses, so it applies the protocol
+//! version's cost tables, computation bucketing (or rounding), and storage rebate rate. It is only
+//! as accurate as its inputs: object sizes should be measured the way execution measures them
+//! (`Object::object_size_for_gas_pricing`), and the cost of running Move code has to be supplied
+//! by the caller (e.g. from a previous dry run of a similar transaction).
+
+use move_core_types::gas_algebra::InternalGas;
+
+use crate::{
+    base_types::ObjectID,
+    error::{ExecutionErrorKind, SuiResult},
+    gas::{GasCostSummary, SuiGasStatus, SuiGasStatusAPI},
+    gas_model::units_types::Gas,
+};
+use sui_protocol_config::ProtocolConfig;
+
+#[cfg(test)]
+#[path = "../unit_tests/gas_estimate_tests.rs"]
+mod gas_estimate_tests;
+
+/// The characteristics of a transaction that contribute to its gas cost.
+#[derive(Debug, Default, Clone)]
+pub struct TransactionCostInputs {
+    /// Sizes of the objects the transaction reads, including its gas coins.
+    pub input_object_sizes: Vec<usize>,
+
+    /// Total size of the modules the transaction publishes or upgrades, in bytes.
+    pub published_package_bytes: usize,
+
+    /// Gas units spent executing Move code and native commands, before the gas price is applied.
+    pub execution_units: u64,
+
+    /// Objects that are created or mutated by the transaction, including the gas coin.
+    pub written_objects: Vec<WrittenObject>,
+
+    /// Storage rebates of the objects that the transaction deletes or wraps.
+    pub deleted_object_rebates: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrittenObject {
+    /// Size of the object after the transaction.
+    pub new_size: usize,
+
+    /// Storage rebate of the object before the transaction, or 0 if it is being created.
+    pub previous_storage_rebate: u64,
+}
+
+impl WrittenObject {
+    pub fn created(new_size: usize) -> Self {
+        Self {
+            new_size,
+            previous_storage_rebate: 0,
+        }
+    }
+
+    pub fn mutated(new_size: usize, previous_storage_rebate: u64) -> Self {
+        Self {
+            new_size,
+            previous_storage_rebate,
+        }
+    }
+}
+
+/// Estimate the computation and storage costs of a transaction described by `inputs`, under the
+/// gas model of `config`, paying `gas_price` (which must be at least `reference_gas_price`).
+///
+/// The estimate is computed against the maximum budget for the protocol version, so it reports
+/// what the transaction would cost rather than whether a particular budget is enough. It fails if
+/// the gas price is invalid, or if the transaction would exceed the maximum budget.
+pub fn estimate_gas_cost(
+    config: &ProtocolConfig,
+    reference_gas_price: u64,
+    gas_price: u64,
+    inputs: &TransactionCostInputs,
+) -> SuiResult<GasCostSummary> {
+    let mut status =
+        SuiGasStatus::new(config.max_tx_gas(), gas_price, reference_gas_price, config)?;
+
+    for size in &inputs.input_object_sizes {
+        status.charge_storage_read(*size)?;
+    }
+
+    if inputs.published_package_bytes > 0 {
+        status.charge_publish_package(inputs.published_package_bytes)?;
+    }
+
+    let execution: InternalGas = Gas::new(inputs.execution_units).to_unit();
+    if status.move_gas_status_mut().deduct_gas(execution).is_err() {
+        return Err(ExecutionErrorKind::InsufficientGas.into());
+    }
+
+    // The gas meter tracks storage per object, but only the totals contribute to the summary, so
+    // the objects do not need to be told apart.
+    for object in &inputs.written_objects {
+        status.track_storage_mutation(
+            ObjectID::ZERO,
+            object.new_size,
+            object.previous_storage_rebate,
+        );
+    }
+
+    for rebate in &inputs.deleted_object_rebates {
+        status.track_storage_mutation(ObjectID::ZERO, 0, *rebate);
+    }
+
+    status.bucketize_computation()?;
+    status.charge_storage_and_rebate()?;
+    Ok(status.summary())
+}
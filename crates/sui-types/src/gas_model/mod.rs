@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod estimate;
 pub mod gas_predicates;
 pub mod gas_v2;
 pub mod tables;
@@ -31,12 +31,14 @@ pub mod authenticator_state;
 pub mod balance;
 pub mod base_types;
 pub mod bridge;
+pub mod canonical_json;
 pub mod clock;
 pub mod coin;
 pub mod collection_types;
 pub mod committee;
 pub mod config;
 pub mod crypto;
+pub mod decode;
 pub mod deny_list_v1;
 pub mod deny_list_v2;
 pub mod digests;
@@ -71,6 +73,7 @@ pub mod multisig_legacy;
 pub mod nitro_attestation;
 pub mod object;
 pub mod passkey_authenticator;
+pub mod personal_message;
 pub mod programmable_transaction_builder;
 pub mod quorum_driver_types;
 pub mod randomness_state;
@@ -82,6 +85,8 @@ pub mod sui_serde;
 pub mod sui_system_state;
 pub mod supported_protocol_versions;
 pub mod test_checkpoint_data_builder;
+#[cfg(any(feature = "test-utils", test))]
+pub mod test_utils;
 pub mod traffic_control;
 pub mod transaction;
 pub mod transaction_executor;
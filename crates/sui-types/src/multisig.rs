@@ -24,6 +24,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use shared_crypto::intent::IntentMessage;
 use std::{
+    collections::BTreeMap,
     hash::{Hash, Hasher},
     str::FromStr,
     sync::Arc,
@@ -464,3 +465,210 @@ impl MultiSigPublicKey {
         Ok(self.to_owned())
     }
 }
+
+/// Errors from assembling a [struct MultiSigPublicKey] with [struct MultiSigPublicKeyBuilder],
+/// or a [struct MultiSig] with [struct MultiSigAggregator].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MultiSigCompositionError {
+    #[error("Public key {0:?} is already a participant")]
+    DuplicateParticipant(PublicKey),
+
+    #[error("Public key {0:?} is not a participant")]
+    UnknownParticipant(PublicKey),
+
+    #[error("Participant {0:?} has already signed")]
+    DuplicateSignature(PublicKey),
+
+    #[error("Participant {0:?} has zero weight")]
+    ZeroWeight(PublicKey),
+
+    #[error("Too many participants: at most {MAX_SIGNER_IN_MULTISIG} are supported")]
+    TooManyParticipants,
+
+    #[error("No participants")]
+    NoParticipants,
+
+    #[error("Threshold must be greater than zero")]
+    ZeroThreshold,
+
+    #[error("Threshold {threshold} is unreachable, participants' total weight is {total}")]
+    UnreachableThreshold {
+        threshold: ThresholdUnit,
+        total: ThresholdUnit,
+    },
+
+    #[error("Signatures have weight {weight}, below threshold {threshold}")]
+    BelowThreshold {
+        weight: ThresholdUnit,
+        threshold: ThresholdUnit,
+    },
+
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+}
+
+/// Assembles a [struct MultiSigPublicKey] one participant at a time, reporting which
+/// participant or setting is at fault if the key would be invalid.
+#[derive(Debug, Clone, Default)]
+pub struct MultiSigPublicKeyBuilder {
+    pk_map: Vec<(PublicKey, WeightUnit)>,
+    threshold: ThresholdUnit,
+}
+
+impl MultiSigPublicKeyBuilder {
+    pub fn new(threshold: ThresholdUnit) -> Self {
+        Self {
+            pk_map: vec![],
+            threshold,
+        }
+    }
+
+    /// Add a participant. Participants are ordered by when they were added, which affects the
+    /// derived address.
+    pub fn add_participant(
+        &mut self,
+        pk: PublicKey,
+        weight: WeightUnit,
+    ) -> Result<&mut Self, MultiSigCompositionError> {
+        if weight == 0 {
+            return Err(MultiSigCompositionError::ZeroWeight(pk));
+        }
+
+        if self.pk_map.iter().any(|(other, _)| *other == pk) {
+            return Err(MultiSigCompositionError::DuplicateParticipant(pk));
+        }
+
+        if self.pk_map.len() >= MAX_SIGNER_IN_MULTISIG {
+            return Err(MultiSigCompositionError::TooManyParticipants);
+        }
+
+        self.pk_map.push((pk, weight));
+        Ok(self)
+    }
+
+    pub fn set_threshold(&mut self, threshold: ThresholdUnit) -> &mut Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// The sum of all participants' weights.
+    pub fn total_weight(&self) -> ThresholdUnit {
+        self.pk_map.iter().map(|(_, w)| *w as ThresholdUnit).sum()
+    }
+
+    /// The address that the MultiSig public key being built would have, if it is valid.
+    pub fn address(&self) -> Result<SuiAddress, MultiSigCompositionError> {
+        Ok(SuiAddress::from(&self.build()?))
+    }
+
+    pub fn build(&self) -> Result<MultiSigPublicKey, MultiSigCompositionError> {
+        if self.pk_map.is_empty() {
+            return Err(MultiSigCompositionError::NoParticipants);
+        }
+
+        if self.threshold == 0 {
+            return Err(MultiSigCompositionError::ZeroThreshold);
+        }
+
+        let total = self.total_weight();
+        if total < self.threshold {
+            return Err(MultiSigCompositionError::UnreachableThreshold {
+                threshold: self.threshold,
+                total,
+            });
+        }
+
+        Ok(MultiSigPublicKey::insecure_new(
+            self.pk_map.clone(),
+            self.threshold,
+        ))
+    }
+}
+
+/// Collects signatures from the participants of a [struct MultiSigPublicKey] in any order, and
+/// combines them into a [struct MultiSig] once their weight reaches the threshold.
+///
+/// Signatures are attributed to participants by their public key, but are not verified
+/// against the message being signed: the combined MultiSig is verified as a whole.
+#[derive(Debug, Clone)]
+pub struct MultiSigAggregator {
+    multisig_pk: MultiSigPublicKey,
+    /// Signatures collected so far, by the index of their signer in `multisig_pk`.
+    sigs: BTreeMap<u8, GenericSignature>,
+}
+
+impl MultiSigAggregator {
+    pub fn new(multisig_pk: MultiSigPublicKey) -> Result<Self, MultiSigCompositionError> {
+        multisig_pk.validate().map_err(|_| {
+            MultiSigCompositionError::InvalidSignature("Invalid multisig public key".to_string())
+        })?;
+
+        Ok(Self {
+            multisig_pk,
+            sigs: BTreeMap::new(),
+        })
+    }
+
+    pub fn multisig_pk(&self) -> &MultiSigPublicKey {
+        &self.multisig_pk
+    }
+
+    /// Add a participant's signature. Returns whether the signatures collected so far are
+    /// enough to reach the threshold.
+    pub fn add_signature(
+        &mut self,
+        sig: GenericSignature,
+    ) -> Result<bool, MultiSigCompositionError> {
+        let pk = sig
+            .to_public_key()
+            .map_err(|e| MultiSigCompositionError::InvalidSignature(e.to_string()))?;
+
+        let Some(index) = self.multisig_pk.get_index(&pk) else {
+            return Err(MultiSigCompositionError::UnknownParticipant(pk));
+        };
+
+        if self.sigs.contains_key(&index) {
+            return Err(MultiSigCompositionError::DuplicateSignature(pk));
+        }
+
+        self.sigs.insert(index, sig);
+        Ok(self.is_complete())
+    }
+
+    /// The total weight of the participants who have signed so far.
+    pub fn weight(&self) -> ThresholdUnit {
+        self.sigs
+            .keys()
+            .map(|i| self.multisig_pk.pk_map[*i as usize].1 as ThresholdUnit)
+            .sum()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.weight() >= self.multisig_pk.threshold
+    }
+
+    /// The participants who have not signed yet.
+    pub fn missing_signers(&self) -> Vec<&(PublicKey, WeightUnit)> {
+        self.multisig_pk
+            .pk_map
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.sigs.contains_key(&(*i as u8)))
+            .map(|(_, participant)| participant)
+            .collect()
+    }
+
+    /// Combine the collected signatures into a MultiSig, as long as they reach the threshold.
+    pub fn finish(self) -> Result<MultiSig, MultiSigCompositionError> {
+        let weight = self.weight();
+        if weight < self.multisig_pk.threshold {
+            return Err(MultiSigCompositionError::BelowThreshold {
+                weight,
+                threshold: self.multisig_pk.threshold,
+            });
+        }
+
+        MultiSig::combine(self.sigs.into_values().collect(), self.multisig_pk)
+            .map_err(|e| MultiSigCompositionError::InvalidSignature(e.to_string()))
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+
+use move_core_types::annotated_value::{MoveStruct, MoveStructLayout, MoveValue};
+use move_core_types::identifier::Identifier;
+use serde::Serialize;
+
+use crate::base_types::{MoveObjectType, ObjectID, SequenceNumber};
+use crate::error::SuiError;
+
+use super::MoveObject;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ObjectDiffError {
+    #[error("Cannot diff different objects: {0} and {1}")]
+    DifferentObjects(ObjectID, ObjectID),
+
+    #[error("Object {id} changed type from {before} to {after}")]
+    TypeChanged {
+        id: ObjectID,
+        before: MoveObjectType,
+        after: MoveObjectType,
+    },
+
+    #[error(transparent)]
+    Deserialization(#[from] SuiError),
+}
+
+/// The changes to an object's Move contents between two of its versions.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ObjectDiff {
+    pub id: ObjectID,
+    pub before_version: SequenceNumber,
+    pub after_version: SequenceNumber,
+
+    /// Changes to the object's fields, in the order the fields are laid out, with nested fields
+    /// appearing after the fields that contain them. Empty if the contents did not change.
+    pub changes: Vec<FieldChange>,
+}
+
+/// A change to a single value within an object, identified by its path from the root of the
+/// object, e.g. `balance.value`, or `items[3]`.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FieldChange {
+    /// An element that was appended to a vector.
+    Added { path: String, value: MoveValue },
+
+    /// An element that was removed from the end of a vector.
+    Removed { path: String, value: MoveValue },
+
+    /// A value that was replaced with a different value of the same type.
+    Modified {
+        path: String,
+        before: MoveValue,
+        after: MoveValue,
+    },
+}
+
+/// Diff two versions of the same Move object, field-by-field. `layout` is the layout of the
+/// object's type, as returned by a package resolver (the type of an object does not change
+/// between versions, so the same layout applies to both).
+pub fn diff_objects(
+    before: &MoveObject,
+    after: &MoveObject,
+    layout: &MoveStructLayout,
+) -> Result<ObjectDiff, ObjectDiffError> {
+    let id = before.id();
+    if id != after.id() {
+        return Err(ObjectDiffError::DifferentObjects(id, after.id()));
+    }
+
+    if before.type_() != after.type_() {
+        return Err(ObjectDiffError::TypeChanged {
+            id,
+            before: before.type_().clone(),
+            after: after.type_().clone(),
+        });
+    }
+
+    let before_struct = before.to_move_struct(layout)?;
+    let after_struct = after.to_move_struct(layout)?;
+
+    Ok(ObjectDiff {
+        id,
+        before_version: before.version(),
+        after_version: after.version(),
+        changes: diff_move_structs(&before_struct, &after_struct),
+    })
+}
+
+/// Diff two values of the same struct type, field-by-field. Paths in the resulting changes are
+/// relative to the struct.
+pub fn diff_move_structs(before: &MoveStruct, after: &MoveStruct) -> Vec<FieldChange> {
+    let mut changes = vec![];
+    diff_fields(
+        &mut String::new(),
+        &before.fields,
+        &after.fields,
+        &mut changes,
+    );
+    changes
+}
+
+fn diff_values(
+    path: &mut String,
+    before: &MoveValue,
+    after: &MoveValue,
+    out: &mut Vec<FieldChange>,
+) {
+    use MoveValue as V;
+    match (before, after) {
+        (V::Struct(b), V::Struct(a)) if b.type_ == a.type_ => {
+            diff_fields(path, &b.fields, &a.fields, out)
+        }
+
+        // Fields are only comparable within the same variant. If the variant changed, the whole
+        // value is reported as modified.
+        (V::Variant(b), V::Variant(a)) if b.type_ == a.type_ && b.tag == a.tag => {
+            diff_fields(path, &b.fields, &a.fields, out)
+        }
+
+        (V::Vector(b), V::Vector(a)) => {
+            for (i, (b, a)) in b.iter().zip(a).enumerate() {
+                with_segment(path, Segment::Index(i), |path| diff_values(path, b, a, out));
+            }
+
+            for (i, value) in a.iter().enumerate().skip(b.len()) {
+                with_segment(path, Segment::Index(i), |path| {
+                    out.push(FieldChange::Added {
+                        path: path.clone(),
+                        value: value.clone(),
+                    })
+                });
+            }
+
+            for (i, value) in b.iter().enumerate().skip(a.len()) {
+                with_segment(path, Segment::Index(i), |path| {
+                    out.push(FieldChange::Removed {
+                        path: path.clone(),
+                        value: value.clone(),
+                    })
+                });
+            }
+        }
+
+        (b, a) if b == a => {}
+
+        (b, a) => out.push(FieldChange::Modified {
+            path: path.clone(),
+            before: b.clone(),
+            after: a.clone(),
+        }),
+    }
+}
+
+fn diff_fields(
+    path: &mut String,
+    before: &[(Identifier, MoveValue)],
+    after: &[(Identifier, MoveValue)],
+    out: &mut Vec<FieldChange>,
+) {
+    // Values of the same type (and variant) have the same fields, in the same order.
+    for ((name, b), (_, a)) in before.iter().zip(after) {
+        with_segment(path, Segment::Field(name.as_str()), |path| {
+            diff_values(path, b, a, out)
+        });
+    }
+}
+
+enum Segment<'a> {
+    Field(&'a str),
+    Index(usize),
+}
+
+/// Extend `path` with `segment` for the duration of `f`.
+fn with_segment(path: &mut String, segment: Segment<'_>, f: impl FnOnce(&mut String)) {
+    let len = path.len();
+    match segment {
+        Segment::Field(name) if len == 0 => path.push_str(name),
+        Segment::Field(name) => {
+            path.push('.');
+            path.push_str(name);
+        }
+        Segment::Index(i) => {
+            use std::fmt::Write;
+            write!(path, "[{i}]").unwrap();
+        }
+    }
+
+    f(path);
+    path.truncate(len);
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldChange::Added { path, value } => write!(f, "+ {path}: {value}"),
+            FieldChange::Removed { path, value } => write!(f, "- {path}: {value}"),
+            FieldChange::Modified {
+                path,
+                before,
+                after,
+            } => write!(f, "~ {path}: {before} -> {after}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use move_core_types::account_address::AccountAddress;
+
+    use super::*;
+    use crate::object::bounded_visitor::tests::{value_, variant_};
+
+    fn struct_(value: MoveValue) -> MoveStruct {
+        let MoveValue::Struct(s) = value else {
+            panic!("Expected a struct, got {value:?}");
+        };
+        s
+    }
+
+    fn lines(changes: &[FieldChange]) -> Vec<String> {
+        changes.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_no_changes() {
+        use MoveValue as V;
+        let value = struct_(value_(
+            "0x0::foo::Bar",
+            vec![("a", V::U64(42)), ("b", V::Vector(vec![V::U8(1)]))],
+        ));
+
+        assert!(diff_move_structs(&value, &value).is_empty());
+    }
+
+    #[test]
+    fn test_nested_changes() {
+        use MoveValue as V;
+        let before = struct_(value_(
+            "0x0::foo::Bar",
+            vec![
+                ("a", V::U64(42)),
+                ("b", value_("0x0::foo::Baz", vec![("c", V::Bool(true))])),
+                ("d", V::Address(AccountAddress::ONE)),
+            ],
+        ));
+
+        let after = struct_(value_(
+            "0x0::foo::Bar",
+            vec![
+                ("a", V::U64(43)),
+                ("b", value_("0x0::foo::Baz", vec![("c", V::Bool(false))])),
+                ("d", V::Address(AccountAddress::ONE)),
+            ],
+        ));
+
+        assert_eq!(
+            lines(&diff_move_structs(&before, &after)),
+            vec!["~ a: 42u64 -> 43u64", "~ b.c: true -> false"],
+        );
+    }
+
+    #[test]
+    fn test_vector_changes() {
+        use MoveValue as V;
+        let before = struct_(value_(
+            "0x0::foo::Bar",
+            vec![
+                ("grow", V::Vector(vec![V::U64(1)])),
+                ("shrink", V::Vector(vec![V::U64(1), V::U64(2), V::U64(3)])),
+            ],
+        ));
+
+        let after = struct_(value_(
+            "0x0::foo::Bar",
+            vec![
+                ("grow", V::Vector(vec![V::U64(2), V::U64(3)])),
+                ("shrink", V::Vector(vec![V::U64(1)])),
+            ],
+        ));
+
+        assert_eq!(
+            lines(&diff_move_structs(&before, &after)),
+            vec![
+                "~ grow[0]: 1u64 -> 2u64",
+                "+ grow[1]: 3u64",
+                "- shrink[1]: 2u64",
+                "- shrink[2]: 3u64",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_variant_changes() {
+        use MoveValue as V;
+        let some = |v| variant_("0x0::foo::Opt", "Some", 1, vec![("v", V::U64(v))]);
+        let none = variant_("0x0::foo::Opt", "None", 0, vec![]);
+
+        let before = struct_(value_(
+            "0x0::foo::Bar",
+            vec![("a", some(1)), ("b", some(2))],
+        ));
+        let after = struct_(value_(
+            "0x0::foo::Bar",
+            vec![("a", some(3)), ("b", none.clone())],
+        ));
+
+        let changes = diff_move_structs(&before, &after);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].to_string(), "~ a.v: 1u64 -> 3u64");
+        assert_eq!(
+            changes[1],
+            FieldChange::Modified {
+                path: "b".to_string(),
+                before: some(2),
+                after: none,
+            }
+        );
+    }
+}
@@ -177,10 +177,73 @@ impl PasskeyAuthenticator {
         raw.try_into()
     }
 
+    /// Construct a [struct PasskeyAuthenticator] from the response to a WebAuthn
+    /// `navigator.credentials.get` request, whose challenge was produced by
+    /// [fn passkey_challenge]. `der_signature` is the DER encoded signature from the response,
+    /// which is normalized to low-s, and `pk` is the passkey's public key, e.g. from
+    /// [fn passkey_public_key_from_der].
+    pub fn from_webauthn_assertion(
+        authenticator_data: Vec<u8>,
+        client_data_json: String,
+        der_signature: &[u8],
+        pk: &PublicKey,
+    ) -> SuiResult<Self> {
+        let PublicKey::Passkey(pk) = pk else {
+            return Err(SuiError::InvalidSignature {
+                error: "Invalid passkey pk".to_string(),
+            });
+        };
+
+        let sig = p256::ecdsa::Signature::from_der(der_signature).map_err(|_| {
+            SuiError::InvalidSignature {
+                error: "Invalid DER signature".to_string(),
+            }
+        })?;
+        let sig = sig.normalize_s().unwrap_or(sig);
+
+        let mut bytes = Vec::with_capacity(Secp256r1SuiSignature::LENGTH);
+        bytes.push(SignatureScheme::Secp256r1.flag());
+        bytes.extend_from_slice(&sig.to_bytes());
+        bytes.extend_from_slice(&pk.0);
+
+        let user_signature =
+            Secp256r1SuiSignature::from_bytes(&bytes).map_err(|_| SuiError::InvalidSignature {
+                error: "Invalid r1 sig".to_string(),
+            })?;
+
+        RawPasskeyAuthenticator {
+            authenticator_data,
+            client_data_json,
+            user_signature: Signature::Secp256r1SuiSignature(user_signature),
+        }
+        .try_into()
+    }
+
     /// Returns the public key of the passkey authenticator.
     pub fn get_pk(&self) -> SuiResult<PublicKey> {
         Ok(PublicKey::Passkey((&self.pk).into()))
     }
+
+    /// The origin that the client reported making the request from, in `clientDataJSON`.
+    pub fn origin(&self) -> SuiResult<String> {
+        let client_data: CollectedClientData = serde_json::from_str(&self.client_data_json)
+            .map_err(|_| SuiError::InvalidSignature {
+                error: "Invalid client data json".to_string(),
+            })?;
+        Ok(client_data.origin)
+    }
+
+    /// Check that this authenticator's challenge is bound to `intent_msg`, without verifying
+    /// its signature.
+    pub fn check_challenge<T: Serialize>(&self, intent_msg: &IntentMessage<T>) -> SuiResult {
+        if self.challenge != to_signing_message(intent_msg) {
+            return Err(SuiError::InvalidSignature {
+                error: "Invalid challenge".to_string(),
+            });
+        };
+
+        Ok(())
+    }
 }
 
 /// Necessary trait for [struct SenderSignedData].
@@ -221,11 +284,7 @@ impl AuthenticatorTrait for PasskeyAuthenticator {
         T: Serialize,
     {
         // Check the intent and signing is consisted from what's parsed from client_data_json.challenge
-        if self.challenge != to_signing_message(intent_msg) {
-            return Err(SuiError::InvalidSignature {
-                error: "Invalid challenge".to_string(),
-            });
-        };
+        self.check_challenge(intent_msg)?;
 
         // Construct msg = authenticator_data || sha256(client_data_json).
         let mut message = self.authenticator_data.clone();
@@ -284,3 +343,45 @@ pub fn to_signing_message<T: Serialize>(
     bcs::serialize_into(&mut hasher, intent_msg).expect("Message serialization should not fail");
     hasher.finalize().digest
 }
+
+/// The challenge to request a passkey to sign, for it to authorize `intent_msg`: the
+/// unpadded Base64URL encoding of [fn to_signing_message].
+pub fn passkey_challenge<T: Serialize>(intent_msg: &IntentMessage<T>) -> String {
+    Base64UrlUnpadded::encode_string(&to_signing_message(intent_msg))
+}
+
+/// The `clientDataJSON` a WebAuthn client would produce when asked by `origin` to sign
+/// `intent_msg` with a passkey. Useful for signers that implement the authenticator side of
+/// WebAuthn themselves.
+pub fn passkey_client_data_json<T: Serialize>(
+    intent_msg: &IntentMessage<T>,
+    origin: &str,
+) -> String {
+    serde_json::json!({
+        "type": "webauthn.get",
+        "challenge": passkey_challenge(intent_msg),
+        "origin": origin,
+        "crossOrigin": false,
+    })
+    .to_string()
+}
+
+/// Convert a passkey's public key, in the DER encoded SubjectPublicKeyInfo form returned when
+/// the passkey is created, into its compressed form.
+pub fn passkey_public_key_from_der(der: &[u8]) -> SuiResult<PublicKey> {
+    use p256::pkcs8::DecodePublicKey;
+
+    let pk = p256::ecdsa::VerifyingKey::from_public_key_der(der).map_err(|_| {
+        SuiError::InvalidSignature {
+            error: "Invalid DER public key".to_string(),
+        }
+    })?;
+
+    PublicKey::try_from_bytes(
+        SignatureScheme::PasskeyAuthenticator,
+        pk.to_encoded_point(true).as_bytes(),
+    )
+    .map_err(|_| SuiError::InvalidSignature {
+        error: "Invalid passkey pk".to_string(),
+    })
+}
@@ -0,0 +1,82 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing and verifying personal messages (arbitrary bytes signed by a user, e.g. to prove
+//! ownership of an address when signing in to an application).
+//!
+//! Personal messages are signed as an `IntentMessage<PersonalMessage>` with the personal message
+//! intent, so that a signature over a personal message can never be confused with a signature
+//! over a transaction.
+
+use std::sync::Arc;
+
+use fastcrypto::traits::Signer;
+use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
+
+use crate::{
+    base_types::SuiAddress,
+    committee::EpochId,
+    crypto::{CompressedSignature, Signature},
+    error::SuiResult,
+    signature::{GenericSignature, VerifyParams},
+    signature_verification::VerifiedDigestCache,
+};
+
+#[cfg(test)]
+#[path = "unit_tests/personal_message_tests.rs"]
+mod personal_message_tests;
+
+/// The intent message that is signed to sign `message` as a personal message.
+pub fn personal_message_intent(message: impl Into<Vec<u8>>) -> IntentMessage<PersonalMessage> {
+    IntentMessage::new(
+        Intent::personal_message(),
+        PersonalMessage {
+            message: message.into(),
+        },
+    )
+}
+
+/// Sign `message` as a personal message.
+pub fn sign_personal_message(
+    message: impl Into<Vec<u8>>,
+    signer: &dyn Signer<Signature>,
+) -> Signature {
+    Signature::new_secure(&personal_message_intent(message), signer)
+}
+
+/// Whether verifying `signature` requires JWKs in `VerifyParams`, because it is a zkLogin
+/// signature or a MultiSig containing one.
+pub fn requires_jwks(signature: &GenericSignature) -> bool {
+    match signature {
+        GenericSignature::ZkLoginAuthenticator(_) => true,
+        GenericSignature::MultiSig(multisig) => multisig
+            .get_sigs()
+            .iter()
+            .any(|s| matches!(s, CompressedSignature::ZkLogin(_))),
+        GenericSignature::MultiSigLegacy(_)
+        | GenericSignature::Signature(_)
+        | GenericSignature::PasskeyAuthenticator(_) => false,
+    }
+}
+
+/// Verify that `signature` is a valid signature of `message` as a personal message by `author`,
+/// for any kind of signature a transaction can be signed with.
+///
+/// `current_epoch` is used to check that zkLogin signatures have not expired, and
+/// `verify_params` must contain the JWKs that zkLogin signatures were issued against (see
+/// [fn requires_jwks]).
+pub fn verify_personal_message_signature(
+    message: &[u8],
+    signature: &GenericSignature,
+    author: SuiAddress,
+    current_epoch: EpochId,
+    verify_params: &VerifyParams,
+) -> SuiResult {
+    signature.verify_authenticator(
+        &personal_message_intent(message),
+        author,
+        current_epoch,
+        verify_params,
+        Arc::new(VerifiedDigestCache::new_empty()),
+    )
+}
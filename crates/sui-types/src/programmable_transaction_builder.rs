@@ -8,14 +8,20 @@ use anyhow::Context;
 use indexmap::IndexMap;
 use move_core_types::{ident_str, identifier::Identifier, language_storage::TypeTag};
 use serde::Serialize;
+use sui_protocol_config::ProtocolConfig;
 
 use crate::{
     base_types::{ObjectID, ObjectRef, SuiAddress},
+    error::UserInputError,
     move_package::PACKAGE_MODULE_NAME,
     transaction::{Argument, CallArg, Command, ObjectArg, ProgrammableTransaction},
     SUI_FRAMEWORK_PACKAGE_ID,
 };
 
+#[cfg(test)]
+#[path = "unit_tests/programmable_transaction_builder_tests.rs"]
+mod programmable_transaction_builder_tests;
+
 #[derive(PartialEq, Eq, Hash)]
 enum BuilderArg {
     Object(ObjectID),
@@ -319,3 +325,305 @@ impl ProgrammableTransactionBuilder {
         Ok(())
     }
 }
+
+/// Errors detected by `ValidatingProgrammableTransactionBuilder` while a transaction is being
+/// built. Commands and arguments are identified by their position in the transaction.
+#[derive(Debug, thiserror::Error)]
+pub enum PtbBuilderError {
+    #[error(
+        "Command {command}, argument {argument}: input {input} does not exist ({inputs} inputs)"
+    )]
+    InputOutOfBounds {
+        command: usize,
+        argument: usize,
+        input: u16,
+        inputs: usize,
+    },
+
+    #[error(
+        "Command {command}, argument {argument}: result of command {result} is not available, \
+         only the results of earlier commands can be used"
+    )]
+    ResultOutOfBounds {
+        command: usize,
+        argument: usize,
+        result: u16,
+    },
+
+    #[error("Command {command}, argument {argument}: command {result} does not return a value")]
+    NoResults {
+        command: usize,
+        argument: usize,
+        result: u16,
+    },
+
+    #[error(
+        "Command {command}, argument {argument}: command {result} returns {results} values, \
+         which must be accessed individually"
+    )]
+    MultipleResults {
+        command: usize,
+        argument: usize,
+        result: u16,
+        results: usize,
+    },
+
+    #[error(
+        "Command {command}, argument {argument}: command {result} returns {results} values, \
+         value {nested} does not exist"
+    )]
+    NestedResultOutOfBounds {
+        command: usize,
+        argument: usize,
+        result: u16,
+        nested: u16,
+        results: usize,
+    },
+
+    #[error("Command {command}: {error}")]
+    InvalidCommand {
+        command: usize,
+        error: UserInputError,
+    },
+
+    #[error("Invalid input: {0}")]
+    InvalidInput(UserInputError),
+
+    #[error("Invalid input: {0}")]
+    ConflictingInput(String),
+
+    #[error("Serializing pure argument: {0}")]
+    Serialization(bcs::Error),
+
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(UserInputError),
+}
+
+/// A `ProgrammableTransactionBuilder` that checks each input and command against the limits in
+/// `ProtocolConfig` as it is added, and checks that every argument refers to an input or
+/// result that exists, so that malformed transactions are rejected before they are signed,
+/// rather than when they are executed.
+///
+/// Inputs are de-duplicated in the same way as `ProgrammableTransactionBuilder`. Move calls are
+/// assumed to return any number of values, because their signatures are not known to the
+/// builder.
+pub struct ValidatingProgrammableTransactionBuilder<'a> {
+    inner: ProgrammableTransactionBuilder,
+    config: &'a ProtocolConfig,
+    /// The number of values returned by each command added so far, if known.
+    results: Vec<Option<usize>>,
+}
+
+impl<'a> ValidatingProgrammableTransactionBuilder<'a> {
+    pub fn new(config: &'a ProtocolConfig) -> Self {
+        Self {
+            inner: ProgrammableTransactionBuilder::new(),
+            config,
+            results: vec![],
+        }
+    }
+
+    /// Finish building, checking the limits that apply to the transaction as a whole (e.g. the
+    /// number of input objects).
+    pub fn finish(self) -> Result<ProgrammableTransaction, PtbBuilderError> {
+        let pt = self.inner.finish();
+        pt.validity_check(self.config)
+            .map_err(PtbBuilderError::InvalidTransaction)?;
+        Ok(pt)
+    }
+
+    pub fn pure<T: Serialize>(&mut self, value: T) -> Result<Argument, PtbBuilderError> {
+        let bytes = bcs::to_bytes(&value).map_err(PtbBuilderError::Serialization)?;
+        self.input(CallArg::Pure(bytes))
+    }
+
+    pub fn obj(&mut self, obj_arg: ObjectArg) -> Result<Argument, PtbBuilderError> {
+        self.input(CallArg::Object(obj_arg))
+    }
+
+    pub fn input(&mut self, call_arg: CallArg) -> Result<Argument, PtbBuilderError> {
+        call_arg
+            .validity_check(self.config)
+            .map_err(PtbBuilderError::InvalidInput)?;
+        self.inner
+            .input(call_arg)
+            .map_err(|e| PtbBuilderError::ConflictingInput(e.to_string()))
+    }
+
+    /// Add `command` to the transaction, returning an argument referring to its result.
+    pub fn command(&mut self, command: Command) -> Result<Argument, PtbBuilderError> {
+        let index = self.results.len();
+        let invalid = |error| PtbBuilderError::InvalidCommand {
+            command: index,
+            error,
+        };
+
+        let max_commands = self.config.max_programmable_tx_commands() as usize;
+        if index + 1 >= max_commands {
+            return Err(invalid(UserInputError::SizeLimitExceeded {
+                limit: "maximum commands in a programmable transaction".to_string(),
+                value: max_commands.to_string(),
+            }));
+        }
+
+        command.validity_check(self.config).map_err(invalid)?;
+        for (position, arg) in command_arguments(&command).into_iter().enumerate() {
+            self.check_argument(index, position, arg)?;
+        }
+
+        self.results.push(result_count(&command));
+        Ok(self.inner.command(command))
+    }
+
+    pub fn programmable_move_call(
+        &mut self,
+        package: ObjectID,
+        module: Identifier,
+        function: Identifier,
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<Argument>,
+    ) -> Result<Argument, PtbBuilderError> {
+        self.command(Command::move_call(
+            package,
+            module,
+            function,
+            type_arguments,
+            arguments,
+        ))
+    }
+
+    pub fn transfer_objects(
+        &mut self,
+        objects: Vec<Argument>,
+        recipient: SuiAddress,
+    ) -> Result<(), PtbBuilderError> {
+        let recipient = self.pure(recipient)?;
+        self.command(Command::TransferObjects(objects, recipient))?;
+        Ok(())
+    }
+
+    /// Split `amounts` off `coin`, returning an argument for each new coin.
+    pub fn split_coins(
+        &mut self,
+        coin: Argument,
+        amounts: Vec<u64>,
+    ) -> Result<Vec<Argument>, PtbBuilderError> {
+        let amounts = amounts
+            .into_iter()
+            .map(|a| self.pure(a))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let count = amounts.len();
+        let Argument::Result(split) = self.command(Command::SplitCoins(coin, amounts))? else {
+            unreachable!("command should always give an Argument::Result");
+        };
+
+        Ok((0..count as u16)
+            .map(|i| Argument::NestedResult(split, i))
+            .collect())
+    }
+
+    pub fn merge_coins(
+        &mut self,
+        coin: Argument,
+        sources: Vec<Argument>,
+    ) -> Result<(), PtbBuilderError> {
+        self.command(Command::MergeCoins(coin, sources))?;
+        Ok(())
+    }
+
+    fn check_argument(
+        &self,
+        command: usize,
+        argument: usize,
+        arg: Argument,
+    ) -> Result<(), PtbBuilderError> {
+        let results_of = |result: u16| {
+            self.results
+                .get(result as usize)
+                .copied()
+                .ok_or(PtbBuilderError::ResultOutOfBounds {
+                    command,
+                    argument,
+                    result,
+                })
+        };
+
+        match arg {
+            Argument::GasCoin => Ok(()),
+
+            Argument::Input(input) => {
+                let inputs = self.inner.inputs.len();
+                if (input as usize) < inputs {
+                    Ok(())
+                } else {
+                    Err(PtbBuilderError::InputOutOfBounds {
+                        command,
+                        argument,
+                        input,
+                        inputs,
+                    })
+                }
+            }
+
+            Argument::Result(result) => match results_of(result)? {
+                None | Some(1) => Ok(()),
+                Some(0) => Err(PtbBuilderError::NoResults {
+                    command,
+                    argument,
+                    result,
+                }),
+                Some(results) => Err(PtbBuilderError::MultipleResults {
+                    command,
+                    argument,
+                    result,
+                    results,
+                }),
+            },
+
+            Argument::NestedResult(result, nested) => match results_of(result)? {
+                None => Ok(()),
+                Some(0) => Err(PtbBuilderError::NoResults {
+                    command,
+                    argument,
+                    result,
+                }),
+                Some(results) if (nested as usize) < results => Ok(()),
+                Some(results) => Err(PtbBuilderError::NestedResultOutOfBounds {
+                    command,
+                    argument,
+                    result,
+                    nested,
+                    results,
+                }),
+            },
+        }
+    }
+}
+
+/// All the arguments of `command`, in order.
+fn command_arguments(command: &Command) -> Vec<Argument> {
+    match command {
+        Command::MoveCall(call) => call.arguments.clone(),
+        Command::TransferObjects(objects, recipient) => {
+            objects.iter().chain([recipient]).copied().collect()
+        }
+        Command::SplitCoins(coin, amounts) => [coin].into_iter().chain(amounts).copied().collect(),
+        Command::MergeCoins(coin, sources) => [coin].into_iter().chain(sources).copied().collect(),
+        Command::MakeMoveVec(_, elements) => elements.clone(),
+        Command::Publish(_, _) => vec![],
+        Command::Upgrade(_, _, _, ticket) => vec![*ticket],
+    }
+}
+
+/// The number of values returned by `command`, if it can be known without type information.
+fn result_count(command: &Command) -> Option<usize> {
+    match command {
+        Command::MoveCall(_) => None,
+        Command::TransferObjects(_, _) | Command::MergeCoins(_, _) => Some(0),
+        Command::SplitCoins(_, amounts) => Some(amounts.len()),
+        Command::MakeMoveVec(_, _) | Command::Publish(_, _) | Command::Upgrade(_, _, _, _) => {
+            Some(1)
+        }
+    }
+}
@@ -1,20 +1,30 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use fastcrypto::hash::HashFunction;
+use fastcrypto::traits::VerifyingKey;
 use nonempty::NonEmpty;
-use shared_crypto::intent::Intent;
+use serde::Serialize;
+use shared_crypto::intent::{Intent, IntentMessage};
 
+use crate::base_types::SuiAddress;
 use crate::committee::EpochId;
+use crate::crypto::{DefaultHash, Signature, SuiSignatureInner};
 use crate::digests::ZKLoginInputsDigest;
 use crate::error::{SuiError, SuiResult};
-use crate::signature::VerifyParams;
+use crate::signature::{GenericSignature, VerifyParams};
 use crate::transaction::{SenderSignedData, TransactionDataAPI};
 use lru::LruCache;
 use parking_lot::RwLock;
 use prometheus::IntCounter;
+use std::collections::BTreeMap;
 use std::hash::Hash;
 use std::sync::Arc;
 
+/// Default number of plain user signatures of the same scheme to verify together in
+/// [fn batch_verify_user_signatures].
+pub const DEFAULT_USER_SIGNATURE_BATCH_SIZE: usize = 64;
+
 // Cache up to 20000 verified certs. We will need to tune this number in the future - a decent
 // guess to start with is that it should be 10-20 times larger than peak transactions per second,
 // on the assumption that we should see most certs twice within about 10-20 seconds at most: Once via RPC, once via consensus.
@@ -112,12 +122,96 @@ pub fn verify_sender_signed_data_message_signatures(
     zklogin_inputs_cache: Arc<VerifiedDigestCache<ZKLoginInputsDigest>>,
 ) -> SuiResult {
     let intent_message = txn.intent_message();
+
+    // Every signature must be valid.
+    for (signer, signature) in signer_signatures(txn, verify_params)? {
+        signature.verify_authenticator(
+            intent_message,
+            signer,
+            current_epoch,
+            verify_params,
+            zklogin_inputs_cache.clone(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Like [fn verify_sender_signed_data_message_signatures], but for many transactions at once.
+/// Plain (single key) user signatures across all the transactions are verified together in
+/// batches of `batch_size`, using [fn batch_verify_user_signatures], and other kinds of
+/// signatures are verified individually. Returns a result for each transaction, in order.
+pub fn batch_verify_sender_signed_data_message_signatures(
+    txns: &[&SenderSignedData],
+    current_epoch: EpochId,
+    verify_params: &VerifyParams,
+    zklogin_inputs_cache: Arc<VerifiedDigestCache<ZKLoginInputsDigest>>,
+    batch_size: usize,
+) -> Vec<SuiResult> {
+    let mut results: Vec<SuiResult> = vec![Ok(()); txns.len()];
+
+    // Index of the transaction that each plain signature in the batch came from.
+    let mut batch_owners = vec![];
+    let mut batch = vec![];
+
+    for (i, txn) in txns.iter().enumerate() {
+        let intent_message = txn.intent_message();
+        let signatures = match signer_signatures(txn, verify_params) {
+            Ok(signatures) => signatures,
+            Err(e) => {
+                results[i] = Err(e);
+                continue;
+            }
+        };
+
+        let mut digest = None;
+        for (signer, signature) in signatures {
+            if let GenericSignature::Signature(signature) = signature {
+                let digest = *digest.get_or_insert_with(|| signing_digest(intent_message));
+                batch_owners.push(i);
+                batch.push(UserSignatureToVerify {
+                    digest,
+                    author: signer,
+                    signature,
+                });
+                continue;
+            }
+
+            if let Err(e) = signature.verify_authenticator(
+                intent_message,
+                signer,
+                current_epoch,
+                verify_params,
+                zklogin_inputs_cache.clone(),
+            ) {
+                results[i] = Err(e);
+                break;
+            }
+        }
+    }
+
+    let batch_results = batch_verify_user_signatures(&batch, batch_size);
+    for (i, result) in batch_owners.into_iter().zip(batch_results) {
+        if results[i].is_ok() {
+            results[i] = result;
+        }
+    }
+
+    results
+}
+
+/// Checks that every signer of `txn` has provided exactly one signature, and returns the
+/// signatures by signer. System transactions do not require signatures.
+fn signer_signatures<'a>(
+    txn: &'a SenderSignedData,
+    verify_params: &VerifyParams,
+) -> SuiResult<BTreeMap<SuiAddress, &'a GenericSignature>> {
+    let intent_message = txn.intent_message();
     assert_eq!(intent_message.intent, Intent::sui_transaction());
 
     // 1. System transactions do not require signatures. User-submitted transactions are verified not to
     // be system transactions before this point
     if intent_message.value.is_system_tx() {
-        return Ok(());
+        return Ok(BTreeMap::new());
     }
 
     // 2. One signature per signer is required.
@@ -141,15 +235,105 @@ pub fn verify_sender_signed_data_message_signatures(
         }
     }
 
-    // 4. Every signature must be valid.
-    for (signer, signature) in present_sigs {
-        signature.verify_authenticator(
-            intent_message,
-            signer,
-            current_epoch,
-            verify_params,
-            zklogin_inputs_cache.clone(),
-        )?;
+    Ok(present_sigs)
+}
+
+/// A plain user signature to be verified by [fn batch_verify_user_signatures].
+pub struct UserSignatureToVerify<'a> {
+    /// The digest of the intent message that was signed, see [fn signing_digest].
+    pub digest: [u8; DefaultHash::OUTPUT_SIZE],
+    pub author: SuiAddress,
+    pub signature: &'a Signature,
+}
+
+/// The digest that plain user signatures commit to: the hash of the BCS encoded intent message.
+pub fn signing_digest<T: Serialize>(
+    intent_msg: &IntentMessage<T>,
+) -> [u8; DefaultHash::OUTPUT_SIZE] {
+    let mut hasher = DefaultHash::default();
+    bcs::serialize_into(&mut hasher, intent_msg).expect("Message serialization should not fail");
+    hasher.finalize().digest
+}
+
+/// Verify many plain user signatures, each over its own message, with the same checks as
+/// `Signature::verify_secure`. Signatures are grouped by scheme and verified `batch_size` at a
+/// time using the scheme's batch verification (Ed25519 verifies batches faster than individual
+/// signatures). If a batch fails, its signatures are re-verified individually to find the
+/// invalid ones. Returns a result for each signature, in order.
+pub fn batch_verify_user_signatures(
+    signatures: &[UserSignatureToVerify<'_>],
+    batch_size: usize,
+) -> Vec<SuiResult> {
+    let mut results: Vec<SuiResult> = vec![Ok(()); signatures.len()];
+    let mut ed25519 = vec![];
+    let mut secp256k1 = vec![];
+    let mut secp256r1 = vec![];
+
+    for (i, s) in signatures.iter().enumerate() {
+        match s.signature {
+            Signature::Ed25519SuiSignature(sig) => ed25519.push((i, s, sig)),
+            Signature::Secp256k1SuiSignature(sig) => secp256k1.push((i, s, sig)),
+            Signature::Secp256r1SuiSignature(sig) => secp256r1.push((i, s, sig)),
+        }
+    }
+
+    let batch_size = batch_size.max(1);
+    batch_verify_scheme(&ed25519, batch_size, &mut results);
+    batch_verify_scheme(&secp256k1, batch_size, &mut results);
+    batch_verify_scheme(&secp256r1, batch_size, &mut results);
+    results
+}
+
+fn batch_verify_scheme<S: SuiSignatureInner>(
+    signatures: &[(usize, &UserSignatureToVerify<'_>, &S)],
+    batch_size: usize,
+    results: &mut [SuiResult],
+) {
+    for chunk in signatures.chunks(batch_size) {
+        let mut indices = vec![];
+        let mut digests = vec![];
+        let mut pks = vec![];
+        let mut sigs = vec![];
+
+        for (i, s, sig) in chunk {
+            let (sig, pk) = match sig.get_verification_inputs() {
+                Ok(inputs) => inputs,
+                Err(e) => {
+                    results[*i] = Err(e);
+                    continue;
+                }
+            };
+
+            let address = SuiAddress::from(&pk);
+            if s.author != address {
+                results[*i] = Err(SuiError::IncorrectSigner {
+                    error: format!(
+                        "Incorrect signer, expected {:?}, got {:?}",
+                        s.author, address
+                    ),
+                });
+                continue;
+            }
+
+            indices.push(*i);
+            digests.push(&s.digest[..]);
+            pks.push(pk);
+            sigs.push(sig);
+        }
+
+        if indices.is_empty()
+            || S::PubKey::verify_batch_empty_fail_different_msg(&digests, &pks, &sigs).is_ok()
+        {
+            continue;
+        }
+
+        // Find the signatures that caused the batch to fail.
+        for (j, i) in indices.into_iter().enumerate() {
+            if let Err(e) = pks[j].verify(digests[j], &sigs[j]) {
+                results[i] = Err(SuiError::InvalidSignature {
+                    error: format!("Fail to verify user sig {}", e),
+                });
+            }
+        }
     }
-    Ok(())
 }
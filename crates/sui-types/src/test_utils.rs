@@ -0,0 +1,520 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Builders for fixtures of core types, for tests in this crate and in downstream crates (such as
+//! custom indexer pipelines) that need values without going through execution.
+//!
+//! Every builder fills in the fields that were not set explicitly from an RNG that is passed to
+//! its `build` method, so fixtures built from an RNG created by [`seeded_rng`] are the same on
+//! every run. The values built are well-formed, but not necessarily consistent with each other
+//! (e.g. object digests are not the digests of the objects they refer to).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use fastcrypto::traits::AllowedRng;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress};
+use crate::committee::EpochId;
+use crate::digests::{
+    CheckpointContentsDigest, CheckpointDigest, ObjectDigest, TransactionDigest,
+    TransactionEventsDigest,
+};
+use crate::effects::{EffectsObjectChange, IDOperation, ObjectIn, ObjectOut, TransactionEffects};
+use crate::execution::SharedInput;
+use crate::execution_status::ExecutionStatus;
+use crate::gas::GasCostSummary;
+use crate::message_envelope::Message;
+use crate::messages_checkpoint::{
+    CheckpointSequenceNumber, CheckpointSummary, CheckpointTimestamp, EndOfEpochData,
+};
+use crate::object::{MoveObject, Object, Owner, GAS_VALUE_FOR_TESTING, OBJECT_START_VERSION};
+use crate::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use crate::transaction::{TransactionData, TransactionDataAPI};
+
+/// Default gas budget and price for transactions built by [`TransactionDataBuilder`].
+const GAS_BUDGET: u64 = 50_000_000;
+const GAS_PRICE: u64 = 1000;
+
+/// An RNG that produces the same values for the same `seed`, to pass to the builders in this
+/// module.
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// Builds an [`Object`]: a gas coin, a coin of some other type, or an arbitrary Move object.
+#[derive(Clone, Debug)]
+pub struct ObjectBuilder {
+    id: Option<ObjectID>,
+    version: SequenceNumber,
+    owner: Option<Owner>,
+    previous_transaction: Option<TransactionDigest>,
+    contents: ObjectContents,
+}
+
+#[derive(Clone, Debug)]
+enum ObjectContents {
+    Gas(u64),
+    Coin(TypeTag, u64),
+    Move { type_: StructTag, fields: Vec<u8> },
+}
+
+/// Builds [`TransactionData`] for a programmable transaction.
+#[derive(Default)]
+pub struct TransactionDataBuilder {
+    sender: Option<SuiAddress>,
+    gas_payment: Vec<ObjectRef>,
+    gas_budget: Option<u64>,
+    gas_price: Option<u64>,
+    ptb: ProgrammableTransactionBuilder,
+}
+
+/// Builds V2 [`TransactionEffects`] from a description of the objects the transaction changed.
+#[derive(Clone, Debug, Default)]
+pub struct EffectsBuilder {
+    transaction_digest: Option<TransactionDigest>,
+    executed_epoch: EpochId,
+    status: Option<ExecutionStatus>,
+    gas_used: GasCostSummary,
+    gas: Option<(ObjectID, SequenceNumber, Owner)>,
+    shared_inputs: Vec<ObjectRef>,
+    created: Vec<(ObjectID, Owner)>,
+    mutated: Vec<(ObjectID, SequenceNumber, Owner)>,
+    deleted: Vec<(ObjectID, SequenceNumber)>,
+    wrapped: Vec<(ObjectID, SequenceNumber)>,
+    events_digest: Option<TransactionEventsDigest>,
+    dependencies: Vec<TransactionDigest>,
+}
+
+/// Builds a [`CheckpointSummary`].
+#[derive(Clone, Debug, Default)]
+pub struct CheckpointSummaryBuilder {
+    epoch: EpochId,
+    sequence_number: CheckpointSequenceNumber,
+    network_total_transactions: u64,
+    content_digest: Option<CheckpointContentsDigest>,
+    previous_digest: Option<CheckpointDigest>,
+    epoch_rolling_gas_cost_summary: GasCostSummary,
+    timestamp_ms: CheckpointTimestamp,
+    end_of_epoch_data: Option<EndOfEpochData>,
+}
+
+impl ObjectBuilder {
+    /// A gas coin holding `value` MIST.
+    pub fn gas(value: u64) -> Self {
+        Self::new(ObjectContents::Gas(value))
+    }
+
+    /// A `Coin<coin_type>` holding `value`.
+    pub fn coin(coin_type: TypeTag, value: u64) -> Self {
+        Self::new(ObjectContents::Coin(coin_type, value))
+    }
+
+    /// A Move object of type `type_`, whose fields after its `id: UID` are the BCS-encoded
+    /// `fields`.
+    pub fn move_object(type_: StructTag, fields: Vec<u8>) -> Self {
+        Self::new(ObjectContents::Move { type_, fields })
+    }
+
+    fn new(contents: ObjectContents) -> Self {
+        Self {
+            id: None,
+            version: OBJECT_START_VERSION,
+            owner: None,
+            previous_transaction: None,
+            contents,
+        }
+    }
+
+    pub fn id(mut self, id: ObjectID) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn version(mut self, version: SequenceNumber) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Defaults to an address owner with a random address.
+    pub fn owner(mut self, owner: Owner) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    pub fn previous_transaction(mut self, digest: TransactionDigest) -> Self {
+        self.previous_transaction = Some(digest);
+        self
+    }
+
+    pub fn build<R: AllowedRng>(self, rng: &mut R) -> Object {
+        let id = self.id.unwrap_or_else(|| ObjectID::random_from_rng(rng));
+        let owner = self
+            .owner
+            .unwrap_or_else(|| Owner::AddressOwner(SuiAddress::generate(&mut *rng)));
+        let previous_transaction = self
+            .previous_transaction
+            .unwrap_or_else(|| TransactionDigest::new(rng.gen()));
+
+        let object = match self.contents {
+            ObjectContents::Gas(value) => MoveObject::new_gas_coin(self.version, id, value),
+            ObjectContents::Coin(coin_type, value) => {
+                MoveObject::new_coin(coin_type, self.version, id, value)
+            }
+            ObjectContents::Move { type_, fields } => {
+                let contents = [id.to_vec(), fields].concat();
+                // SAFETY: The type and contents are provided by the test, which is responsible for
+                // making sure they match.
+                unsafe {
+                    MoveObject::new_from_execution_with_limit(
+                        type_.into(),
+                        /* has_public_transfer */ true,
+                        self.version,
+                        contents,
+                        u64::MAX,
+                    )
+                }
+                .expect("object contents should fit within the size limit")
+            }
+        };
+
+        Object::new_move(object, owner, previous_transaction)
+    }
+}
+
+impl TransactionDataBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defaults to a random address.
+    pub fn sender(mut self, sender: SuiAddress) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    /// Add a coin to pay for gas with. Defaults to a single random gas coin.
+    pub fn gas_payment(mut self, gas: ObjectRef) -> Self {
+        self.gas_payment.push(gas);
+        self
+    }
+
+    pub fn gas_budget(mut self, gas_budget: u64) -> Self {
+        self.gas_budget = Some(gas_budget);
+        self
+    }
+
+    pub fn gas_price(mut self, gas_price: u64) -> Self {
+        self.gas_price = Some(gas_price);
+        self
+    }
+
+    /// Transfer `amount` MIST split off the gas coin (or the whole gas coin if `amount` is `None`)
+    /// to `recipient`.
+    pub fn transfer_sui(mut self, recipient: SuiAddress, amount: Option<u64>) -> Self {
+        self.ptb.transfer_sui(recipient, amount);
+        self
+    }
+
+    /// Add arbitrary commands to the transaction.
+    pub fn commands(mut self, f: impl FnOnce(&mut ProgrammableTransactionBuilder)) -> Self {
+        f(&mut self.ptb);
+        self
+    }
+
+    pub fn build<R: AllowedRng>(self, rng: &mut R) -> TransactionData {
+        let sender = self
+            .sender
+            .unwrap_or_else(|| SuiAddress::generate(&mut *rng));
+
+        let gas_payment = if self.gas_payment.is_empty() {
+            vec![random_object_ref(rng, OBJECT_START_VERSION)]
+        } else {
+            self.gas_payment
+        };
+
+        TransactionData::new_programmable(
+            sender,
+            gas_payment,
+            self.ptb.finish(),
+            self.gas_budget.unwrap_or(GAS_BUDGET),
+            self.gas_price.unwrap_or(GAS_PRICE),
+        )
+    }
+}
+
+impl EffectsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Effects for `transaction`: its digest, and its first gas coin, which is mutated and stays
+    /// with the gas owner. Its shared inputs are not included, because their versions are only
+    /// known after sequencing (see [`EffectsBuilder::shared_input`]).
+    pub fn for_transaction(transaction: &TransactionData) -> Self {
+        let mut builder = Self::new().transaction_digest(transaction.digest());
+        if let Some((id, version, _)) = transaction.gas().first() {
+            let owner = Owner::AddressOwner(transaction.gas_owner());
+            builder = builder.gas_object(*id, *version, owner);
+        }
+
+        builder
+    }
+
+    /// Defaults to a random digest.
+    pub fn transaction_digest(mut self, digest: TransactionDigest) -> Self {
+        self.transaction_digest = Some(digest);
+        self
+    }
+
+    pub fn executed_epoch(mut self, epoch: EpochId) -> Self {
+        self.executed_epoch = epoch;
+        self
+    }
+
+    /// Defaults to success.
+    pub fn status(mut self, status: ExecutionStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn gas_used(mut self, gas_used: GasCostSummary) -> Self {
+        self.gas_used = gas_used;
+        self
+    }
+
+    /// The gas coin, at its version before the transaction, and its owner. Effects without a gas
+    /// object are the effects of a system transaction.
+    pub fn gas_object(mut self, id: ObjectID, version: SequenceNumber, owner: Owner) -> Self {
+        self.gas = Some((id, version, owner));
+        self
+    }
+
+    /// A shared object that the transaction read, but did not modify.
+    pub fn shared_input(mut self, object: ObjectRef) -> Self {
+        self.shared_inputs.push(object);
+        self
+    }
+
+    pub fn created(mut self, id: ObjectID, owner: Owner) -> Self {
+        self.created.push((id, owner));
+        self
+    }
+
+    /// An object that was modified, at its version before the transaction, and its new owner.
+    pub fn mutated(mut self, id: ObjectID, version: SequenceNumber, owner: Owner) -> Self {
+        self.mutated.push((id, version, owner));
+        self
+    }
+
+    /// An object that was deleted, at its version before the transaction.
+    pub fn deleted(mut self, id: ObjectID, version: SequenceNumber) -> Self {
+        self.deleted.push((id, version));
+        self
+    }
+
+    /// An object that was wrapped, at its version before the transaction.
+    pub fn wrapped(mut self, id: ObjectID, version: SequenceNumber) -> Self {
+        self.wrapped.push((id, version));
+        self
+    }
+
+    pub fn events_digest(mut self, digest: TransactionEventsDigest) -> Self {
+        self.events_digest = Some(digest);
+        self
+    }
+
+    pub fn dependency(mut self, digest: TransactionDigest) -> Self {
+        self.dependencies.push(digest);
+        self
+    }
+
+    /// Build the effects. Input and output digests of objects are random, and the lamport
+    /// version is one more than the highest input version. Objects that are deleted or wrapped
+    /// are treated as having been owned by a random address.
+    pub fn build<R: AllowedRng>(self, rng: &mut R) -> TransactionEffects {
+        let transaction_digest = self
+            .transaction_digest
+            .unwrap_or_else(|| TransactionDigest::new(rng.gen()));
+
+        let lamport_version = SequenceNumber::lamport_increment(
+            self.gas
+                .iter()
+                .chain(&self.mutated)
+                .map(|(_, version, _)| *version)
+                .chain(self.deleted.iter().map(|(_, version)| *version))
+                .chain(self.wrapped.iter().map(|(_, version)| *version))
+                .chain(self.shared_inputs.iter().map(|(_, version, _)| *version)),
+        );
+
+        let mut changes = BTreeMap::new();
+        for (id, owner) in self.created {
+            let digest = ObjectDigest::new(rng.gen());
+            changes.insert(
+                id,
+                EffectsObjectChange {
+                    input_state: ObjectIn::NotExist,
+                    output_state: ObjectOut::ObjectWrite((digest, owner)),
+                    id_operation: IDOperation::Created,
+                },
+            );
+        }
+
+        for (id, version, owner) in self.gas.iter().cloned().chain(self.mutated) {
+            let (input, output) = (ObjectDigest::new(rng.gen()), ObjectDigest::new(rng.gen()));
+            changes.insert(
+                id,
+                EffectsObjectChange {
+                    input_state: ObjectIn::Exist(((version, input), owner.clone())),
+                    output_state: ObjectOut::ObjectWrite((output, owner)),
+                    id_operation: IDOperation::None,
+                },
+            );
+        }
+
+        let removed = self
+            .deleted
+            .into_iter()
+            .map(|(id, version)| (id, version, IDOperation::Deleted))
+            .chain(
+                self.wrapped
+                    .into_iter()
+                    .map(|(id, version)| (id, version, IDOperation::None)),
+            );
+
+        for (id, version, id_operation) in removed {
+            let digest = ObjectDigest::new(rng.gen());
+            let owner = Owner::AddressOwner(SuiAddress::generate(&mut *rng));
+            changes.insert(
+                id,
+                EffectsObjectChange {
+                    input_state: ObjectIn::Exist(((version, digest), owner)),
+                    output_state: ObjectOut::NotExist,
+                    id_operation,
+                },
+            );
+        }
+
+        TransactionEffects::new_from_execution_v2(
+            self.status.unwrap_or(ExecutionStatus::Success),
+            self.executed_epoch,
+            self.gas_used,
+            self.shared_inputs
+                .into_iter()
+                .map(SharedInput::Existing)
+                .collect(),
+            BTreeSet::new(),
+            transaction_digest,
+            lamport_version,
+            changes,
+            self.gas.map(|(id, _, _)| id),
+            self.events_digest,
+            self.dependencies,
+        )
+    }
+}
+
+impl CheckpointSummaryBuilder {
+    pub fn new(sequence_number: CheckpointSequenceNumber) -> Self {
+        Self {
+            sequence_number,
+            ..Default::default()
+        }
+    }
+
+    /// The checkpoint after `previous`, in the same epoch, unless `previous` was the last
+    /// checkpoint of its epoch. It includes no transactions and has the same timestamp until
+    /// they are set.
+    pub fn after(previous: &CheckpointSummary) -> Self {
+        let epoch_ended = previous.end_of_epoch_data.is_some();
+        Self {
+            epoch: previous.epoch + epoch_ended as u64,
+            sequence_number: previous.sequence_number + 1,
+            network_total_transactions: previous.network_total_transactions,
+            previous_digest: Some(previous.digest()),
+            epoch_rolling_gas_cost_summary: if epoch_ended {
+                GasCostSummary::default()
+            } else {
+                previous.epoch_rolling_gas_cost_summary.clone()
+            },
+            timestamp_ms: previous.timestamp_ms,
+            ..Default::default()
+        }
+    }
+
+    pub fn epoch(mut self, epoch: EpochId) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    pub fn network_total_transactions(mut self, total: u64) -> Self {
+        self.network_total_transactions = total;
+        self
+    }
+
+    /// Defaults to a random digest.
+    pub fn content_digest(mut self, digest: CheckpointContentsDigest) -> Self {
+        self.content_digest = Some(digest);
+        self
+    }
+
+    pub fn previous_digest(mut self, digest: CheckpointDigest) -> Self {
+        self.previous_digest = Some(digest);
+        self
+    }
+
+    pub fn epoch_rolling_gas_cost_summary(mut self, summary: GasCostSummary) -> Self {
+        self.epoch_rolling_gas_cost_summary = summary;
+        self
+    }
+
+    pub fn timestamp_ms(mut self, timestamp_ms: CheckpointTimestamp) -> Self {
+        self.timestamp_ms = timestamp_ms;
+        self
+    }
+
+    /// Make this the last checkpoint of its epoch.
+    pub fn end_of_epoch(mut self, data: EndOfEpochData) -> Self {
+        self.end_of_epoch_data = Some(data);
+        self
+    }
+
+    pub fn build<R: AllowedRng>(self, rng: &mut R) -> CheckpointSummary {
+        CheckpointSummary {
+            epoch: self.epoch,
+            sequence_number: self.sequence_number,
+            network_total_transactions: self.network_total_transactions,
+            content_digest: self
+                .content_digest
+                .unwrap_or_else(|| CheckpointContentsDigest::new(rng.gen())),
+            previous_digest: self.previous_digest,
+            epoch_rolling_gas_cost_summary: self.epoch_rolling_gas_cost_summary,
+            timestamp_ms: self.timestamp_ms,
+            checkpoint_commitments: vec![],
+            end_of_epoch_data: self.end_of_epoch_data,
+            version_specific_data: vec![],
+        }
+    }
+}
+
+/// A reference to a random object at `version`.
+pub fn random_object_ref<R: AllowedRng>(rng: &mut R, version: SequenceNumber) -> ObjectRef {
+    (
+        ObjectID::random_from_rng(rng),
+        version,
+        ObjectDigest::new(rng.gen()),
+    )
+}
+
+/// A gas coin holding [`GAS_VALUE_FOR_TESTING`] MIST, owned by `owner`.
+pub fn gas_object<R: AllowedRng>(rng: &mut R, owner: SuiAddress) -> Object {
+    ObjectBuilder::gas(GAS_VALUE_FOR_TESTING)
+        .owner(Owner::AddressOwner(owner))
+        .build(rng)
+}
+
+#[cfg(test)]
+#[path = "unit_tests/test_utils_tests.rs"]
+mod test_utils_tests;
@@ -3,6 +3,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 // These values set to loosely attempt to limit
@@ -226,6 +227,31 @@ pub enum PolicyType {
     TestPanicOnInvocation,
 }
 
+/// A group of RPC methods that share a traffic control budget. Requests are tallied and
+/// blocked per group only if the group is given its own policies in
+/// `PolicyConfig::method_group_policies`; otherwise they fall under the top-level policies.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum MethodGroup {
+    /// Requests that only read state.
+    Read,
+    /// Requests that sign, submit or execute transactions.
+    Execution,
+    /// Requests that open or manage streaming subscriptions.
+    Subscription,
+}
+
+/// Spam and error policies scoped to a single `MethodGroup`. A client that exceeds these is
+/// only blocked from methods in that group.
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct MethodGroupPolicyConfig {
+    #[serde(default)]
+    pub spam_policy_type: PolicyType,
+    #[serde(default)]
+    pub error_policy_type: PolicyType,
+}
+
 #[serde_as]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
@@ -256,6 +282,12 @@ pub struct PolicyConfig {
     /// and any blocklist related configuration will be ignored.
     #[serde(default)]
     pub allow_list: Option<Vec<String>>,
+    /// Policies for method groups that should be tallied and blocked independently of other
+    /// requests, for example so that clients abusing reads are not also blocked from
+    /// submitting transactions. Blocklist TTLs, sampling and dry-run mode are shared with the
+    /// top-level policies.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub method_group_policies: BTreeMap<MethodGroup, MethodGroupPolicyConfig>,
 }
 
 impl Default for PolicyConfig {
@@ -270,6 +302,7 @@ impl Default for PolicyConfig {
             spam_sample_rate: default_spam_sample_rate(),
             dry_run: default_dry_run(),
             allow_list: None,
+            method_group_policies: BTreeMap::new(),
         }
     }
 }
@@ -898,7 +898,7 @@ impl Command {
         }
     }
 
-    fn validity_check(&self, config: &ProtocolConfig) -> UserInputResult {
+    pub(crate) fn validity_check(&self, config: &ProtocolConfig) -> UserInputResult {
         match self {
             Command::MoveCall(call) => call.validity_check(config)?,
             Command::TransferObjects(args, _)
@@ -1025,7 +1025,7 @@ impl ProgrammableTransaction {
             .collect()
     }
 
-    fn validity_check(&self, config: &ProtocolConfig) -> UserInputResult {
+    pub(crate) fn validity_check(&self, config: &ProtocolConfig) -> UserInputResult {
         let ProgrammableTransaction { inputs, commands } = self;
         fp_ensure!(
             commands.len() < config.max_programmable_tx_commands() as usize,
@@ -0,0 +1,166 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Serialize;
+
+use super::{write_canonical, CanonicalJson, CanonicalJsonError, SCHEMA};
+use crate::base_types::{ObjectID, SequenceNumber};
+use crate::crypto::DefaultHash;
+use crate::digests::ObjectDigest;
+use crate::effects::{IDOperation, ObjectChange, TransactionEffects};
+use fastcrypto::hash::HashFunction;
+
+fn canonical(value: impl Serialize) -> Result<String, CanonicalJsonError> {
+    let mut buf = vec![];
+    write_canonical(&mut buf, &serde_json::to_value(value)?)?;
+    Ok(String::from_utf8(buf).unwrap())
+}
+
+#[test]
+fn test_keys_are_sorted() {
+    #[derive(Serialize)]
+    struct S {
+        z: u64,
+        a: Vec<u64>,
+        m: Inner,
+    }
+
+    #[derive(Serialize)]
+    struct Inner {
+        y: bool,
+        b: Option<u64>,
+    }
+
+    let s = S {
+        z: u64::MAX,
+        a: vec![3, 1, 2],
+        m: Inner { y: true, b: None },
+    };
+
+    assert_eq!(
+        canonical(s).unwrap(),
+        r#"{"a":[3,1,2],"m":{"b":null,"y":true},"z":"18446744073709551615"}"#,
+    );
+}
+
+#[test]
+fn test_large_integers_are_strings() {
+    assert_eq!(canonical(9007199254740991u64).unwrap(), "9007199254740991");
+    assert_eq!(
+        canonical(9007199254740992u64).unwrap(),
+        r#""9007199254740992""#
+    );
+    assert_eq!(
+        canonical(-9007199254740991i64).unwrap(),
+        "-9007199254740991"
+    );
+    assert_eq!(canonical(i64::MIN).unwrap(), r#""-9223372036854775808""#);
+}
+
+#[test]
+fn test_string_escapes() {
+    assert_eq!(
+        canonical("a\"b\\c\n\u{1}é").unwrap(),
+        r#""a\"b\\c\n\u0001é""#,
+    );
+}
+
+#[test]
+fn test_floats_rejected() {
+    assert!(matches!(
+        canonical(1.5f64),
+        Err(CanonicalJsonError::FloatingPoint(_))
+    ));
+}
+
+#[test]
+fn test_object_change() {
+    let change = ObjectChange {
+        id: ObjectID::ZERO,
+        input_version: None,
+        input_digest: None,
+        output_version: Some(SequenceNumber::from_u64(1)),
+        output_digest: Some(ObjectDigest::MIN),
+        id_operation: IDOperation::Created,
+    };
+
+    assert_eq!(
+        change.to_canonical_json().unwrap(),
+        format!(
+            r#"{{"id":"{}","id_operation":"Created","input_digest":null,"input_version":null,"output_digest":"{}","output_version":1}}"#,
+            ObjectID::ZERO,
+            ObjectDigest::MIN,
+        ),
+    );
+}
+
+#[test]
+fn test_effects_golden() {
+    let effects = TransactionEffects::default();
+    let json = effects.to_canonical_json().unwrap();
+
+    let expected = concat!(
+        r#"{"V2":{"aux_data_digest":null,"changed_objects":[],"dependencies":[],"#,
+        r#""events_digest":null,"executed_epoch":0,"gas_object_index":null,"gas_used":{"#,
+        r#""computationCost":"0","nonRefundableStorageFee":"0","storageCost":"0","#,
+        r#""storageRebate":"0"},"lamport_version":0,"status":"Success","#,
+        r#""transaction_digest":"11111111111111111111111111111111","#,
+        r#""unchanged_shared_objects":[]}}"#,
+    );
+
+    assert_eq!(json, expected);
+    assert_eq!(
+        effects.canonical_json_digest().unwrap(),
+        DefaultHash::digest(expected.as_bytes()).digest,
+    );
+
+    // Re-parsing and re-encoding the canonical form is the identity.
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(canonical(value).unwrap(), json);
+}
+
+#[test]
+fn test_schema_matches_encoding() {
+    let schema: serde_json::Value = serde_json::from_str(SCHEMA).unwrap();
+    let defs = &schema["$defs"];
+
+    let required = |def: &str| -> Vec<String> {
+        let mut fields: Vec<_> = defs[def]["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f.as_str().unwrap().to_owned())
+            .collect();
+        fields.sort();
+        fields
+    };
+
+    let keys = |value: serde_json::Value| -> Vec<String> {
+        let mut keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    };
+
+    let effects = serde_json::to_value(TransactionEffects::default()).unwrap();
+    assert_eq!(
+        required("TransactionEffectsV2"),
+        keys(effects["V2"].clone())
+    );
+    assert_eq!(
+        required("GasCostSummary"),
+        keys(effects["V2"]["gas_used"].clone())
+    );
+
+    let change = ObjectChange {
+        id: ObjectID::ZERO,
+        input_version: None,
+        input_digest: None,
+        output_version: None,
+        output_digest: None,
+        id_operation: IDOperation::None,
+    };
+    assert_eq!(
+        required("ObjectChange"),
+        keys(serde_json::to_value(change).unwrap())
+    );
+}
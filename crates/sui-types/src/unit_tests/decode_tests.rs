@@ -0,0 +1,193 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::identifier::Identifier;
+
+use super::{decode_transaction_bytes, DecodedCommand, DecodedKind, DecodedObject, DecodedValue};
+use crate::base_types::{dbg_addr, random_object_ref, ObjectID};
+use crate::gas_coin::GAS;
+use crate::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use crate::transaction::{Command, ObjectArg, TransactionData};
+use crate::{parse_sui_type_tag, SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID};
+
+#[test]
+fn test_decode_transfer_sui() {
+    let sender = dbg_addr(1);
+    let recipient = dbg_addr(2);
+    let gas = random_object_ref();
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.transfer_sui(recipient, Some(100));
+    let data = TransactionData::new_programmable(sender, vec![gas], builder.finish(), 5000, 1000);
+
+    let decoded = decode_transaction_bytes(&bcs::to_bytes(&data).unwrap()).unwrap();
+    assert_eq!(decoded.sender, sender);
+    assert_eq!(decoded.gas.budget, 5000);
+    assert_eq!(decoded.gas.price, 1000);
+    assert_eq!(
+        decoded.gas.payment,
+        vec![DecodedObject::Owned {
+            id: gas.0,
+            version: gas.1,
+            digest: gas.2,
+        }]
+    );
+
+    let DecodedKind::Programmable { commands } = &decoded.kind else {
+        panic!("Expected a programmable transaction");
+    };
+
+    assert_eq!(
+        commands,
+        &vec![
+            DecodedCommand::SplitCoins {
+                coin: DecodedValue::GasCoin,
+                amounts: vec![DecodedValue::Amount {
+                    amount: 100,
+                    coin_type: Some(GAS::type_tag().to_canonical_string(true)),
+                }],
+            },
+            DecodedCommand::TransferObjects {
+                objects: vec![DecodedValue::Result { command: 0 }],
+                recipient: DecodedValue::Address { address: recipient },
+            },
+        ]
+    );
+
+    let description = decoded.to_string();
+    assert!(description.contains("0: Split 100 MIST from gas coin"));
+    assert!(description.contains(&format!("1: Transfer result of command 0 to {recipient}")));
+}
+
+#[test]
+fn test_decode_amount_coin_types() {
+    let usdc = parse_sui_type_tag("0xabc::usdc::USDC").unwrap();
+    let coin = random_object_ref();
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    let balance = builder.obj(ObjectArg::ImmOrOwnedObject(coin)).unwrap();
+    let amount = builder.pure(10u64).unwrap();
+    let taken = builder.programmable_move_call(
+        SUI_FRAMEWORK_PACKAGE_ID,
+        Identifier::new("coin").unwrap(),
+        Identifier::new("take").unwrap(),
+        vec![usdc.clone()],
+        vec![balance, amount],
+    );
+
+    // The type of a coin taken from a balance is known from the call's type argument, and the
+    // type of a coin passed in as an input is not known.
+    let first = builder.pure(100u64).unwrap();
+    builder.command(Command::SplitCoins(taken, vec![first]));
+    let second = builder.pure(200u64).unwrap();
+    builder.command(Command::SplitCoins(balance, vec![second]));
+
+    let data = TransactionData::new_programmable(
+        dbg_addr(1),
+        vec![random_object_ref()],
+        builder.finish(),
+        5000,
+        1000,
+    );
+
+    let decoded = decode_transaction_bytes(&bcs::to_bytes(&data).unwrap()).unwrap();
+    let DecodedKind::Programmable { commands } = &decoded.kind else {
+        panic!("Expected a programmable transaction");
+    };
+
+    let usdc = usdc.to_canonical_string(/* with_prefix */ true);
+    assert_eq!(
+        commands[1],
+        DecodedCommand::SplitCoins {
+            coin: DecodedValue::Result { command: 0 },
+            amounts: vec![DecodedValue::Amount {
+                amount: 100,
+                coin_type: Some(usdc.clone()),
+            }],
+        }
+    );
+
+    let DecodedCommand::SplitCoins { amounts, .. } = &commands[2] else {
+        panic!("Expected a split");
+    };
+    assert_eq!(
+        amounts,
+        &vec![DecodedValue::Amount {
+            amount: 200,
+            coin_type: None,
+        }]
+    );
+
+    let description = decoded.to_string();
+    assert!(description.contains(&format!("1: Split 100 of {usdc} from result of command 0")));
+    assert!(description.contains(&format!("2: Split 200 from object {}", coin.0)));
+    assert!(!description.contains("100 MIST"));
+}
+
+#[test]
+fn test_decode_known_move_call() {
+    let validator = dbg_addr(3);
+    let system_state = ObjectID::random();
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    let state = builder
+        .obj(ObjectArg::SharedObject {
+            id: system_state,
+            initial_shared_version: 1.into(),
+            mutable: true,
+        })
+        .unwrap();
+    let validator_arg = builder.pure(validator).unwrap();
+    builder.programmable_move_call(
+        SUI_SYSTEM_PACKAGE_ID,
+        Identifier::new("sui_system").unwrap(),
+        Identifier::new("request_add_stake").unwrap(),
+        vec![],
+        vec![state, validator_arg],
+    );
+
+    let data = TransactionData::new_programmable(
+        dbg_addr(1),
+        vec![random_object_ref()],
+        builder.finish(),
+        5000,
+        1000,
+    );
+
+    let decoded = decode_transaction_bytes(&bcs::to_bytes(&data).unwrap()).unwrap();
+    let DecodedKind::Programmable { commands } = &decoded.kind else {
+        panic!("Expected a programmable transaction");
+    };
+
+    let DecodedCommand::MoveCall {
+        description,
+        arguments,
+        ..
+    } = &commands[0]
+    else {
+        panic!("Expected a move call");
+    };
+
+    assert_eq!(*description, Some("Stake SUI"));
+    assert_eq!(
+        arguments[0],
+        DecodedValue::Object(DecodedObject::Shared {
+            id: system_state,
+            initial_shared_version: 1.into(),
+            mutable: true,
+        })
+    );
+
+    // Pure arguments to Move calls are not interpreted.
+    assert_eq!(
+        arguments[1],
+        DecodedValue::Pure {
+            bytes: bcs::to_bytes(&validator).unwrap()
+        }
+    );
+}
+
+#[test]
+fn test_decode_invalid_bytes() {
+    assert!(decode_transaction_bytes(&[0, 1, 2]).is_err());
+}
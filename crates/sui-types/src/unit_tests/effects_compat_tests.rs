@@ -0,0 +1,201 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{
+    EffectsConversionLoss, EffectsObjectChange, IDOperation, ObjectIn, ObjectOut,
+    TransactionEffects, TransactionEffectsAPI,
+};
+use crate::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use crate::digests::{ObjectDigest, TransactionDigest};
+use crate::execution::SharedInput;
+use crate::execution_status::ExecutionStatus;
+use crate::gas::GasCostSummary;
+use crate::object::Owner;
+
+struct Fixture {
+    effects: TransactionEffects,
+    gas: ObjectID,
+    created: ObjectID,
+    deleted: ObjectID,
+}
+
+fn v2_effects(
+    extra_changes: Vec<(ObjectID, EffectsObjectChange)>,
+    per_epoch_config: BTreeSet<ObjectID>,
+) -> Fixture {
+    let sender = Owner::AddressOwner(SuiAddress::random_for_testing_only());
+    let (gas, created, deleted, shared) = (
+        ObjectID::random(),
+        ObjectID::random(),
+        ObjectID::random(),
+        ObjectID::random(),
+    );
+
+    let mut changes: BTreeMap<_, _> = extra_changes.into_iter().collect();
+    changes.insert(
+        gas,
+        EffectsObjectChange {
+            input_state: ObjectIn::Exist((
+                (SequenceNumber::from_u64(3), ObjectDigest::random()),
+                sender.clone(),
+            )),
+            output_state: ObjectOut::ObjectWrite((ObjectDigest::random(), sender.clone())),
+            id_operation: IDOperation::None,
+        },
+    );
+
+    changes.insert(
+        created,
+        EffectsObjectChange {
+            input_state: ObjectIn::NotExist,
+            output_state: ObjectOut::ObjectWrite((ObjectDigest::random(), sender.clone())),
+            id_operation: IDOperation::Created,
+        },
+    );
+
+    changes.insert(
+        deleted,
+        EffectsObjectChange {
+            input_state: ObjectIn::Exist((
+                (SequenceNumber::from_u64(5), ObjectDigest::random()),
+                sender.clone(),
+            )),
+            output_state: ObjectOut::NotExist,
+            id_operation: IDOperation::Deleted,
+        },
+    );
+
+    let effects = TransactionEffects::new_from_execution_v2(
+        ExecutionStatus::Success,
+        1,
+        GasCostSummary::new(1000, 2000, 500, 10),
+        vec![SharedInput::Existing((
+            shared,
+            SequenceNumber::from_u64(4),
+            ObjectDigest::random(),
+        ))],
+        per_epoch_config,
+        TransactionDigest::random(),
+        SequenceNumber::from_u64(6),
+        changes,
+        Some(gas),
+        None,
+        vec![TransactionDigest::random()],
+    );
+
+    Fixture {
+        effects,
+        gas,
+        created,
+        deleted,
+    }
+}
+
+#[test]
+fn test_round_trip_with_input_state() {
+    let Fixture {
+        effects,
+        gas,
+        deleted,
+        ..
+    } = v2_effects(vec![], BTreeSet::new());
+
+    let v1 = effects.to_v1();
+    assert!(matches!(v1.effects, TransactionEffects::V1(_)));
+    assert_eq!(v1.losses.len(), 2);
+    assert!(v1.losses.contains(&EffectsConversionLoss::InputState(gas)));
+    assert!(v1
+        .losses
+        .contains(&EffectsConversionLoss::InputState(deleted)));
+
+    let inputs: BTreeMap<_, _> = effects
+        .old_object_metadata()
+        .into_iter()
+        .map(|((id, version, digest), owner)| ((id, version), (digest, owner)))
+        .collect();
+
+    let v2 = v1
+        .effects
+        .to_v2(|id, version| inputs.get(&(id, version)).cloned());
+
+    assert!(v2.is_lossless(), "{:?}", v2.losses);
+    assert_eq!(v2.effects, effects);
+}
+
+#[test]
+fn test_round_trip_without_input_state() {
+    let Fixture {
+        effects,
+        gas,
+        created,
+        deleted,
+    } = v2_effects(vec![], BTreeSet::new());
+
+    let v2 = effects.to_v1().effects.to_v2(|_, _| None);
+    assert_eq!(v2.losses.len(), 2);
+    assert!(v2.losses.contains(&EffectsConversionLoss::InputState(gas)));
+    assert!(v2
+        .losses
+        .contains(&EffectsConversionLoss::InputState(deleted)));
+
+    // The output state is preserved, only the input state is replaced by placeholders.
+    assert_ne!(v2.effects, effects);
+    assert_eq!(v2.effects.gas_object(), effects.gas_object());
+    assert_eq!(v2.effects.created(), effects.created());
+    assert_eq!(v2.effects.deleted(), effects.deleted());
+    assert_eq!(
+        v2.effects.modified_at_versions(),
+        effects.modified_at_versions()
+    );
+    assert_eq!(
+        v2.effects.input_shared_objects(),
+        effects.input_shared_objects()
+    );
+
+    assert!(v2
+        .effects
+        .old_object_metadata()
+        .iter()
+        .all(|((id, _, digest), _)| *id != created && *digest == ObjectDigest::MIN));
+}
+
+#[test]
+fn test_v2_only_changes() {
+    let wrapped = ObjectID::random();
+    let config = ObjectID::random();
+    let Fixture { effects, .. } = v2_effects(
+        vec![(
+            wrapped,
+            EffectsObjectChange {
+                input_state: ObjectIn::NotExist,
+                output_state: ObjectOut::NotExist,
+                id_operation: IDOperation::Created,
+            },
+        )],
+        BTreeSet::from([config]),
+    );
+
+    let v1 = effects.to_v1();
+    assert!(v1
+        .losses
+        .contains(&EffectsConversionLoss::CreatedAndWrapped(wrapped)));
+    assert!(v1
+        .losses
+        .contains(&EffectsConversionLoss::PerEpochConfig(config)));
+}
+
+#[test]
+fn test_same_version_is_lossless() {
+    let Fixture { effects, .. } = v2_effects(vec![], BTreeSet::new());
+
+    let v2 = effects.to_v2(|_, _| None);
+    assert!(v2.is_lossless());
+    assert_eq!(v2.effects, effects);
+
+    let v1 = effects.to_v1().effects;
+    let converted = v1.to_v1();
+    assert!(converted.is_lossless());
+    assert_eq!(converted.effects, v1);
+}
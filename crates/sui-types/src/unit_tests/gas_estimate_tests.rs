@@ -0,0 +1,111 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_protocol_config::ProtocolConfig;
+
+use super::{estimate_gas_cost, TransactionCostInputs, WrittenObject};
+
+const RGP: u64 = 1000;
+
+#[test]
+fn test_storage_cost_scales_with_size() {
+    let config = ProtocolConfig::get_for_max_version_UNSAFE();
+    let per_byte = config.obj_data_cost_refundable() * config.storage_gas_price();
+
+    let small = estimate_gas_cost(
+        &config,
+        RGP,
+        RGP,
+        &TransactionCostInputs {
+            written_objects: vec![WrittenObject::created(100)],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let large = estimate_gas_cost(
+        &config,
+        RGP,
+        RGP,
+        &TransactionCostInputs {
+            written_objects: vec![WrittenObject::created(100), WrittenObject::created(200)],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(small.storage_cost, 100 * per_byte);
+    assert_eq!(large.storage_cost, 300 * per_byte);
+    assert_eq!(small.storage_rebate, 0);
+    assert_eq!(small.computation_cost, large.computation_cost);
+}
+
+#[test]
+fn test_rebates() {
+    let config = ProtocolConfig::get_for_max_version_UNSAFE();
+    let summary = estimate_gas_cost(
+        &config,
+        RGP,
+        RGP,
+        &TransactionCostInputs {
+            written_objects: vec![WrittenObject::mutated(100, 1_000_000)],
+            deleted_object_rebates: vec![1_000_000],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Part of each rebate is kept by the system as a non-refundable fee.
+    assert_eq!(
+        summary.storage_rebate + summary.non_refundable_storage_fee,
+        2_000_000
+    );
+    assert!(summary.non_refundable_storage_fee > 0);
+}
+
+#[test]
+fn test_computation_cost() {
+    let config = ProtocolConfig::get_for_max_version_UNSAFE();
+    let estimate = |gas_price, execution_units| {
+        estimate_gas_cost(
+            &config,
+            RGP,
+            gas_price,
+            &TransactionCostInputs {
+                input_object_sizes: vec![100, 200],
+                execution_units,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    };
+
+    let cheap = estimate(RGP, 0);
+    let expensive = estimate(RGP, 1_000_000);
+    let tipped = estimate(2 * RGP, 0);
+
+    assert!(cheap.computation_cost > 0);
+    assert!(expensive.computation_cost >= 1_000_000 * RGP);
+    assert_eq!(tipped.computation_cost, 2 * cheap.computation_cost);
+    assert_eq!(cheap.computation_cost % RGP, 0);
+}
+
+#[test]
+fn test_invalid_estimates() {
+    let config = ProtocolConfig::get_for_max_version_UNSAFE();
+
+    // Gas price below the reference gas price.
+    estimate_gas_cost(&config, RGP, RGP - 1, &TransactionCostInputs::default()).unwrap_err();
+
+    // More computation than any transaction can afford.
+    estimate_gas_cost(
+        &config,
+        RGP,
+        RGP,
+        &TransactionCostInputs {
+            execution_units: config.max_tx_gas(),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+}
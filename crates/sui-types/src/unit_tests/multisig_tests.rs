@@ -1,7 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{MultiSigPublicKey, ThresholdUnit, WeightUnit};
+use super::{
+    MultiSigAggregator, MultiSigCompositionError, MultiSigPublicKey, MultiSigPublicKeyBuilder,
+    ThresholdUnit, WeightUnit,
+};
 use crate::{
     base_types::SuiAddress,
     crypto::{
@@ -498,3 +501,91 @@ fn test_derive_multisig_address() {
             .unwrap()
     );
 }
+
+#[test]
+fn test_multisig_pk_builder() {
+    let pk1 = SuiKeyPair::Ed25519(get_key_pair().1).public();
+    let pk2 = SuiKeyPair::Secp256k1(get_key_pair().1).public();
+
+    let mut builder = MultiSigPublicKeyBuilder::new(3);
+    assert_eq!(
+        builder.build().unwrap_err(),
+        MultiSigCompositionError::NoParticipants
+    );
+
+    builder.add_participant(pk1.clone(), 1).unwrap();
+    assert_eq!(
+        builder.add_participant(pk1.clone(), 2).unwrap_err(),
+        MultiSigCompositionError::DuplicateParticipant(pk1.clone()),
+    );
+    assert_eq!(
+        builder.add_participant(pk2.clone(), 0).unwrap_err(),
+        MultiSigCompositionError::ZeroWeight(pk2.clone()),
+    );
+
+    builder.add_participant(pk2.clone(), 1).unwrap();
+    assert_eq!(
+        builder.address().unwrap_err(),
+        MultiSigCompositionError::UnreachableThreshold {
+            threshold: 3,
+            total: 2
+        },
+    );
+
+    builder.set_threshold(2);
+    let multisig_pk = MultiSigPublicKey::new(vec![pk1, pk2], vec![1, 1], 2).unwrap();
+    assert_eq!(builder.build().unwrap(), multisig_pk);
+    assert_eq!(builder.address().unwrap(), SuiAddress::from(&multisig_pk));
+}
+
+#[test]
+fn test_multisig_aggregator() {
+    let kp1: SuiKeyPair = SuiKeyPair::Ed25519(get_key_pair().1);
+    let kp2: SuiKeyPair = SuiKeyPair::Secp256k1(get_key_pair().1);
+    let kp3: SuiKeyPair = SuiKeyPair::Secp256r1(get_key_pair().1);
+    let outsider: SuiKeyPair = SuiKeyPair::Ed25519(get_key_pair().1);
+
+    let multisig_pk = MultiSigPublicKey::new(
+        vec![kp1.public(), kp2.public(), kp3.public()],
+        vec![1, 1, 1],
+        2,
+    )
+    .unwrap();
+
+    let msg = IntentMessage::new(
+        Intent::sui_transaction(),
+        PersonalMessage {
+            message: "Hello".as_bytes().to_vec(),
+        },
+    );
+    let sig = |kp: &SuiKeyPair| -> GenericSignature { Signature::new_secure(&msg, kp).into() };
+
+    let mut aggregator = MultiSigAggregator::new(multisig_pk.clone()).unwrap();
+    assert_eq!(
+        aggregator.add_signature(sig(&outsider)).unwrap_err(),
+        MultiSigCompositionError::UnknownParticipant(outsider.public()),
+    );
+
+    // Signatures can be added out of order.
+    assert!(!aggregator.add_signature(sig(&kp3)).unwrap());
+    assert_eq!(
+        aggregator.add_signature(sig(&kp3)).unwrap_err(),
+        MultiSigCompositionError::DuplicateSignature(kp3.public()),
+    );
+    assert_eq!(aggregator.missing_signers().len(), 2);
+    assert!(matches!(
+        aggregator.clone().finish().unwrap_err(),
+        MultiSigCompositionError::BelowThreshold {
+            weight: 1,
+            threshold: 2
+        }
+    ));
+
+    assert!(aggregator.add_signature(sig(&kp1)).unwrap());
+    let multisig = aggregator.finish().unwrap();
+    assert_eq!(multisig.get_indices().unwrap(), vec![0, 2]);
+    assert_eq!(
+        multisig,
+        MultiSig::combine(vec![sig(&kp1), sig(&kp3)], multisig_pk).unwrap()
+    );
+}
@@ -3,7 +3,7 @@
 
 use std::sync::Arc;
 
-use super::to_signing_message;
+use super::{passkey_client_data_json, passkey_public_key_from_der, to_signing_message};
 use crate::crypto::DefaultHash;
 use crate::passkey_authenticator::{PasskeyAuthenticator, RawPasskeyAuthenticator};
 use crate::{
@@ -399,3 +399,66 @@ async fn test_passkey_fails_wrong_client_data_type() {
 //     );
 //     assert!(res.is_ok());
 // }
+
+#[test]
+fn test_passkey_from_webauthn_assertion() {
+    use fastcrypto::hash::Sha256;
+    use p256::ecdsa::signature::Signer;
+    use p256::pkcs8::EncodePublicKey;
+
+    let signing_key = p256::ecdsa::SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+    let pk_der = signing_key
+        .verifying_key()
+        .to_public_key_der()
+        .unwrap()
+        .into_vec();
+    let pk = passkey_public_key_from_der(&pk_der).unwrap();
+    let sender = SuiAddress::from(&pk);
+
+    let object = Object::immutable_with_id_for_testing(ObjectID::ZERO);
+    let tx_data = TransactionData::new_transfer_sui(
+        dbg_addr(2),
+        sender,
+        None,
+        object.compute_object_reference(),
+        1000 * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        1000,
+    );
+    let intent_msg = IntentMessage::new(Intent::sui_transaction(), tx_data);
+
+    // Act as the authenticator: sign authenticator_data || sha256(client_data_json).
+    let authenticator_data = vec![0; 37];
+    let client_data_json = passkey_client_data_json(&intent_msg, "https://www.sui.io");
+    let mut message = authenticator_data.clone();
+    message.extend_from_slice(&Sha256::digest(client_data_json.as_bytes()).digest);
+    let signature: p256::ecdsa::Signature = signing_key.sign(&message);
+
+    let passkey = PasskeyAuthenticator::from_webauthn_assertion(
+        authenticator_data,
+        client_data_json,
+        signature.to_der().as_bytes(),
+        &pk,
+    )
+    .unwrap();
+
+    assert_eq!(passkey.get_pk().unwrap(), pk);
+    assert_eq!(passkey.origin().unwrap(), "https://www.sui.io");
+    passkey.check_challenge(&intent_msg).unwrap();
+
+    let sig = GenericSignature::PasskeyAuthenticator(passkey);
+    sig.verify_authenticator(
+        &intent_msg,
+        sender,
+        0,
+        &Default::default(),
+        Arc::new(VerifiedDigestCache::new_empty()),
+    )
+    .unwrap();
+
+    // The challenge is bound to the transaction it was created for.
+    let other_msg = IntentMessage::new(Intent::personal_message(), intent_msg.value);
+    let GenericSignature::PasskeyAuthenticator(passkey) = sig else {
+        unreachable!()
+    };
+    assert!(passkey.check_challenge(&other_msg).is_err());
+}
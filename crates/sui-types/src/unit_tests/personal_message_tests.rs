@@ -0,0 +1,75 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use shared_crypto::intent::{Intent, IntentScope};
+
+use super::{
+    personal_message_intent, requires_jwks, sign_personal_message,
+    verify_personal_message_signature,
+};
+use crate::{
+    base_types::SuiAddress,
+    crypto::{get_key_pair, Signature, SuiKeyPair},
+    multisig::{MultiSig, MultiSigPublicKey},
+    signature::{GenericSignature, VerifyParams},
+};
+
+#[test]
+fn test_personal_message_intent() {
+    let intent_msg = personal_message_intent(b"hello".to_vec());
+    assert_eq!(intent_msg.intent, Intent::personal_message());
+    assert_eq!(intent_msg.intent.scope, IntentScope::PersonalMessage);
+    assert_eq!(intent_msg.value.message, b"hello");
+}
+
+#[test]
+fn test_sign_and_verify_personal_message() {
+    let kp = SuiKeyPair::Ed25519(get_key_pair().1);
+    let address = SuiAddress::from(&kp.public());
+    let params = VerifyParams::default();
+
+    let sig: GenericSignature = sign_personal_message(b"hello".to_vec(), &kp).into();
+    assert!(!requires_jwks(&sig));
+
+    verify_personal_message_signature(b"hello", &sig, address, 0, &params).unwrap();
+    verify_personal_message_signature(b"goodbye", &sig, address, 0, &params).unwrap_err();
+
+    let other = SuiAddress::from(&SuiKeyPair::Ed25519(get_key_pair().1).public());
+    verify_personal_message_signature(b"hello", &sig, other, 0, &params).unwrap_err();
+
+    // A signature over the same bytes as a transaction is not a valid personal message signature.
+    let tx_sig: GenericSignature = Signature::new_secure(
+        &shared_crypto::intent::IntentMessage::new(
+            Intent::sui_transaction(),
+            personal_message_intent(b"hello".to_vec()).value,
+        ),
+        &kp,
+    )
+    .into();
+    verify_personal_message_signature(b"hello", &tx_sig, address, 0, &params).unwrap_err();
+}
+
+#[test]
+fn test_verify_multisig_personal_message() {
+    let kp1 = SuiKeyPair::Ed25519(get_key_pair().1);
+    let kp2 = SuiKeyPair::Secp256k1(get_key_pair().1);
+    let multisig_pk =
+        MultiSigPublicKey::new(vec![kp1.public(), kp2.public()], vec![1, 1], 2).unwrap();
+    let address = SuiAddress::from(&multisig_pk);
+
+    let multisig = MultiSig::combine(
+        vec![
+            sign_personal_message(b"hello".to_vec(), &kp1).into(),
+            sign_personal_message(b"hello".to_vec(), &kp2).into(),
+        ],
+        multisig_pk,
+    )
+    .unwrap();
+
+    let sig = GenericSignature::MultiSig(multisig);
+    assert!(!requires_jwks(&sig));
+
+    let params = VerifyParams::default();
+    verify_personal_message_signature(b"hello", &sig, address, 0, &params).unwrap();
+    verify_personal_message_signature(b"goodbye", &sig, address, 0, &params).unwrap_err();
+}
@@ -0,0 +1,154 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::identifier::Identifier;
+use sui_protocol_config::ProtocolConfig;
+
+use super::{PtbBuilderError, ValidatingProgrammableTransactionBuilder};
+use crate::base_types::{ObjectID, SuiAddress};
+use crate::transaction::{Argument, Command};
+
+fn move_call(arguments: Vec<Argument>) -> Command {
+    Command::move_call(
+        ObjectID::ZERO,
+        Identifier::new("m").unwrap(),
+        Identifier::new("f").unwrap(),
+        vec![],
+        arguments,
+    )
+}
+
+#[test]
+fn test_valid_transaction() {
+    let config = ProtocolConfig::get_for_max_version_UNSAFE();
+    let mut builder = ValidatingProgrammableTransactionBuilder::new(&config);
+
+    let coins = builder.split_coins(Argument::GasCoin, vec![1, 2]).unwrap();
+    let result = builder.command(move_call(coins.clone())).unwrap();
+    builder
+        .command(move_call(vec![result, Argument::NestedResult(1, 5)]))
+        .unwrap();
+    builder.transfer_objects(coins, SuiAddress::ZERO).unwrap();
+
+    let pt = builder.finish().unwrap();
+    assert_eq!(pt.commands.len(), 4);
+}
+
+#[test]
+fn test_inputs_are_deduplicated() {
+    let config = ProtocolConfig::get_for_max_version_UNSAFE();
+    let mut builder = ValidatingProgrammableTransactionBuilder::new(&config);
+
+    let a = builder.pure(42u64).unwrap();
+    let b = builder.pure(42u64).unwrap();
+    assert_eq!(a, b);
+
+    builder.command(move_call(vec![a, b])).unwrap();
+    assert_eq!(builder.finish().unwrap().inputs.len(), 1);
+}
+
+#[test]
+fn test_input_out_of_bounds() {
+    let config = ProtocolConfig::get_for_max_version_UNSAFE();
+    let mut builder = ValidatingProgrammableTransactionBuilder::new(&config);
+
+    builder.pure(1u64).unwrap();
+    let err = builder
+        .command(move_call(vec![Argument::Input(0), Argument::Input(1)]))
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        PtbBuilderError::InputOutOfBounds {
+            command: 0,
+            argument: 1,
+            input: 1,
+            inputs: 1,
+        }
+    ));
+}
+
+#[test]
+fn test_result_from_later_command() {
+    let config = ProtocolConfig::get_for_max_version_UNSAFE();
+    let mut builder = ValidatingProgrammableTransactionBuilder::new(&config);
+
+    let err = builder
+        .command(move_call(vec![Argument::Result(0)]))
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        PtbBuilderError::ResultOutOfBounds {
+            command: 0,
+            argument: 0,
+            result: 0,
+        }
+    ));
+}
+
+#[test]
+fn test_result_arity() {
+    let config = ProtocolConfig::get_for_max_version_UNSAFE();
+    let mut builder = ValidatingProgrammableTransactionBuilder::new(&config);
+
+    builder.merge_coins(Argument::GasCoin, vec![]).unwrap_err();
+    let coins = builder.split_coins(Argument::GasCoin, vec![1, 2]).unwrap();
+    builder.merge_coins(coins[0], vec![coins[1]]).unwrap();
+
+    // Splitting two coins gives two results, which must be accessed individually.
+    let err = builder
+        .command(move_call(vec![Argument::Result(0)]))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PtbBuilderError::MultipleResults { results: 2, .. }
+    ));
+
+    let err = builder
+        .command(move_call(vec![Argument::NestedResult(0, 2)]))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PtbBuilderError::NestedResultOutOfBounds {
+            nested: 2,
+            results: 2,
+            ..
+        }
+    ));
+
+    // Merging coins does not return anything.
+    let err = builder
+        .command(move_call(vec![Argument::Result(1)]))
+        .unwrap_err();
+    assert!(matches!(err, PtbBuilderError::NoResults { result: 1, .. }));
+}
+
+#[test]
+fn test_invalid_command() {
+    let config = ProtocolConfig::get_for_max_version_UNSAFE();
+    let mut builder = ValidatingProgrammableTransactionBuilder::new(&config);
+
+    let err = builder
+        .command(Command::MakeMoveVec(None, vec![]))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PtbBuilderError::InvalidCommand { command: 0, .. }
+    ));
+}
+
+#[test]
+fn test_too_many_commands() {
+    let mut config = ProtocolConfig::get_for_max_version_UNSAFE();
+    config.set_max_programmable_tx_commands_for_testing(3);
+    let mut builder = ValidatingProgrammableTransactionBuilder::new(&config);
+
+    builder.command(move_call(vec![])).unwrap();
+    builder.command(move_call(vec![])).unwrap();
+    let err = builder.command(move_call(vec![])).unwrap_err();
+    assert!(matches!(
+        err,
+        PtbBuilderError::InvalidCommand { command: 2, .. }
+    ));
+}
@@ -0,0 +1,92 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::effects::TransactionEffectsAPI;
+use crate::gas_coin::GasCoin;
+
+#[test]
+fn test_same_seed_same_fixtures() {
+    let build = |seed| {
+        let mut rng = seeded_rng(seed);
+        let gas = ObjectBuilder::gas(1000).build(&mut rng);
+        let tx = TransactionDataBuilder::new()
+            .gas_payment(gas.compute_object_reference())
+            .transfer_sui(SuiAddress::generate(&mut rng), Some(10))
+            .build(&mut rng);
+        let fx = EffectsBuilder::for_transaction(&tx)
+            .created(ObjectID::random_from_rng(&mut rng), gas.owner.clone())
+            .build(&mut rng);
+        let cp = CheckpointSummaryBuilder::new(0).build(&mut rng);
+        (gas, tx, fx, cp)
+    };
+
+    assert_eq!(build(42), build(42));
+    assert_ne!(build(42), build(43));
+}
+
+#[test]
+fn test_object_builder() {
+    let mut rng = seeded_rng(0);
+    let id = ObjectID::random_from_rng(&mut rng);
+    let owner = Owner::AddressOwner(SuiAddress::generate(&mut rng));
+
+    let gas = ObjectBuilder::gas(1000)
+        .id(id)
+        .version(SequenceNumber::from_u64(7))
+        .owner(owner.clone())
+        .build(&mut rng);
+
+    assert_eq!(gas.id(), id);
+    assert_eq!(gas.version(), SequenceNumber::from_u64(7));
+    assert_eq!(gas.owner, owner);
+    assert_eq!(GasCoin::try_from(&gas).unwrap().value(), 1000);
+
+    let type_: StructTag = "0x2::coin::TreasuryCap<0x2::sui::SUI>".parse().unwrap();
+    let fields = bcs::to_bytes(&0u64).unwrap();
+    let obj = ObjectBuilder::move_object(type_.clone(), fields.clone()).build(&mut rng);
+
+    let contents = obj.data.try_as_move().unwrap();
+    assert_eq!(contents.type_().clone(), type_.into());
+    assert_eq!(contents.contents(), [obj.id().to_vec(), fields].concat());
+}
+
+#[test]
+fn test_effects_for_transaction() {
+    let mut rng = seeded_rng(0);
+    let tx = TransactionDataBuilder::new().build(&mut rng);
+    let gas = tx.gas()[0];
+
+    let created = ObjectID::random_from_rng(&mut rng);
+    let deleted = ObjectID::random_from_rng(&mut rng);
+    let fx = EffectsBuilder::for_transaction(&tx)
+        .created(created, Owner::AddressOwner(tx.sender()))
+        .deleted(deleted, SequenceNumber::from_u64(10))
+        .build(&mut rng);
+
+    assert_eq!(fx.transaction_digest(), &tx.digest());
+    assert_eq!(fx.gas_object().0 .0, gas.0);
+    assert_eq!(fx.lamport_version(), SequenceNumber::from_u64(11));
+    assert_eq!(fx.created().len(), 1);
+    assert_eq!(fx.created()[0].0 .0, created);
+    assert_eq!(fx.deleted().len(), 1);
+    assert_eq!(fx.deleted()[0].0, deleted);
+}
+
+#[test]
+fn test_checkpoint_summary_chain() {
+    let mut rng = seeded_rng(0);
+    let first = CheckpointSummaryBuilder::new(0)
+        .network_total_transactions(1)
+        .build(&mut rng);
+
+    let second = CheckpointSummaryBuilder::after(&first)
+        .network_total_transactions(3)
+        .timestamp_ms(1000)
+        .build(&mut rng);
+
+    assert_eq!(second.sequence_number, 1);
+    assert_eq!(second.epoch, first.epoch);
+    assert_eq!(second.previous_digest, Some(first.digest()));
+    assert_ne!(second.content_digest, first.content_digest);
+}
@@ -176,7 +176,7 @@ mod zk_login {
         USER_ADDRESS.with(|a| *a)
     }
 
-    fn get_zklogin_user_key() -> SuiKeyPair {
+    pub fn get_zklogin_user_key() -> SuiKeyPair {
         SuiKeyPair::Ed25519(Ed25519KeyPair::generate(&mut StdRng::from_seed([0; 32])))
     }
 
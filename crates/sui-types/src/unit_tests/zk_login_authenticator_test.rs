@@ -4,14 +4,23 @@
 use std::str::FromStr;
 use std::sync::Arc;
 
-use crate::crypto::{PublicKey, SignatureScheme, ZkLoginPublicIdentifier};
+use crate::crypto::{
+    get_key_pair, PublicKey, SignatureScheme, SuiKeyPair, ZkLoginPublicIdentifier,
+};
 
 use crate::signature::VerifyParams;
 use crate::signature_verification::VerifiedDigestCache;
-use crate::utils::{get_zklogin_user_address, make_zklogin_tx, sign_zklogin_personal_msg};
+use crate::utils::{
+    get_zklogin_user_address, get_zklogin_user_key, make_zklogin_tx, sign_zklogin_personal_msg,
+};
 use crate::utils::{load_test_vectors, SHORT_ADDRESS_SEED};
+use crate::zk_login_authenticator::{
+    verify_zklogin_address, verify_zklogin_max_epoch, verify_zklogin_proof,
+};
 use crate::{
-    base_types::SuiAddress, signature::GenericSignature, zk_login_util::DEFAULT_JWK_BYTES,
+    base_types::SuiAddress,
+    signature::GenericSignature,
+    zk_login_util::{get_zklogin_inputs, DEFAULT_JWK_BYTES},
 };
 use fastcrypto::encoding::Base64;
 use fastcrypto::traits::ToFromBytes;
@@ -111,3 +120,38 @@ fn zklogin_sign_personal_message() {
     // Verify passes.
     assert!(res.is_ok());
 }
+
+#[test]
+fn zklogin_verify_offline() {
+    let inputs = get_zklogin_inputs();
+    let ephemeral_key = get_zklogin_user_key().public();
+    let jwks: ImHashMap<JwkId, JWK> = parse_jwks(DEFAULT_JWK_BYTES, &OIDCProvider::Twitch)
+        .unwrap()
+        .into_iter()
+        .collect();
+
+    verify_zklogin_address(&inputs, get_zklogin_user_address(), false).unwrap();
+    verify_zklogin_address(&inputs, SuiAddress::ZERO, true).unwrap_err();
+
+    // The test proof is valid until epoch 10.
+    verify_zklogin_proof(&inputs, 10, &ephemeral_key, &jwks, &ZkLoginEnv::Test).unwrap();
+
+    // ...but only for the ephemeral key and max epoch it was generated for.
+    verify_zklogin_proof(&inputs, 11, &ephemeral_key, &jwks, &ZkLoginEnv::Test).unwrap_err();
+    let other_key = SuiKeyPair::Ed25519(get_key_pair().1).public();
+    verify_zklogin_proof(&inputs, 10, &other_key, &jwks, &ZkLoginEnv::Test).unwrap_err();
+
+    // ...and only if the JWK it was issued against is known.
+    let no_jwks = ImHashMap::new();
+    verify_zklogin_proof(&inputs, 10, &ephemeral_key, &no_jwks, &ZkLoginEnv::Test).unwrap_err();
+}
+
+#[test]
+fn zklogin_verify_max_epoch() {
+    verify_zklogin_max_epoch(10, 5, None).unwrap();
+    verify_zklogin_max_epoch(10, 10, None).unwrap();
+    verify_zklogin_max_epoch(10, 11, None).unwrap_err();
+
+    verify_zklogin_max_epoch(10, 5, Some(5)).unwrap();
+    verify_zklogin_max_epoch(10, 5, Some(4)).unwrap_err();
+}
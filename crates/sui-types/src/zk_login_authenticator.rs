@@ -124,32 +124,7 @@ impl AuthenticatorTrait for ZkLoginAuthenticator {
         epoch: EpochId,
         max_epoch_upper_bound_delta: Option<u64>,
     ) -> SuiResult {
-        // the checks here ensure that `current_epoch + max_epoch_upper_bound_delta >= self.max_epoch >= current_epoch`.
-        // 1. if the config for upper bound is set, ensure that the max epoch in signature is not larger than epoch + upper_bound.
-        if let Some(delta) = max_epoch_upper_bound_delta {
-            let max_epoch_upper_bound = epoch + delta;
-            if self.get_max_epoch() > max_epoch_upper_bound {
-                return Err(SuiError::InvalidSignature {
-                    error: format!(
-                        "ZKLogin max epoch too large {}, current epoch {}, max accepted: {}",
-                        self.get_max_epoch(),
-                        epoch,
-                        max_epoch_upper_bound
-                    ),
-                });
-            }
-        }
-        // 2. ensure that max epoch in signature is greater than the current epoch.
-        if epoch > self.get_max_epoch() {
-            return Err(SuiError::InvalidSignature {
-                error: format!(
-                    "ZKLogin expired at epoch {}, current epoch {}",
-                    self.get_max_epoch(),
-                    epoch
-                ),
-            });
-        }
-        Ok(())
+        verify_zklogin_max_epoch(self.get_max_epoch(), epoch, max_epoch_upper_bound_delta)
     }
 
     /// Verify an intent message of a transaction with an zk login authenticator.
@@ -163,15 +138,11 @@ impl AuthenticatorTrait for ZkLoginAuthenticator {
     where
         T: Serialize,
     {
-        // Always evaluate the unpadded address derivation.
-        if author != SuiAddress::try_from_unpadded(&self.inputs)? {
-            // If the verify_legacy_zklogin_address flag is set, also evaluate the padded address derivation.
-            if !aux_verify_data.verify_legacy_zklogin_address
-                || author != SuiAddress::try_from_padded(&self.inputs)?
-            {
-                return Err(SuiError::InvalidAddress);
-            }
-        }
+        verify_zklogin_address(
+            &self.inputs,
+            author,
+            aux_verify_data.verify_legacy_zklogin_address,
+        )?;
 
         // Only when supported_providers list is not empty, we check if the provider is supported. Otherwise,
         // we just use the JWK map to check if its supported.
@@ -242,6 +213,87 @@ fn verify_zklogin_inputs_wrapper(
     })
 }
 
+// The functions below verify the parts of a zkLogin signature independently, without access to a
+// node: callers supply the JWKs that were active when the proof was generated and the current
+// epoch. A zkLogin signature is valid if its address, max epoch and proof all verify, and its
+// ephemeral signature verifies against the ephemeral public key.
+
+/// Verify that `address` is derived from the issuer and address seed in `inputs`. If
+/// `allow_legacy` is set, addresses derived from the padded address seed are also accepted.
+pub fn verify_zklogin_address(
+    inputs: &ZkLoginInputs,
+    address: SuiAddress,
+    allow_legacy: bool,
+) -> SuiResult {
+    // Always evaluate the unpadded address derivation.
+    if address == SuiAddress::try_from_unpadded(inputs)? {
+        return Ok(());
+    }
+
+    // If legacy addresses are allowed, also evaluate the padded address derivation.
+    if allow_legacy && address == SuiAddress::try_from_padded(inputs)? {
+        return Ok(());
+    }
+
+    Err(SuiError::InvalidAddress)
+}
+
+/// Verify that a proof with `max_epoch` is still valid at `current_epoch`, and, if
+/// `max_epoch_upper_bound_delta` is set, that `max_epoch` is not too far in the future.
+pub fn verify_zklogin_max_epoch(
+    max_epoch: EpochId,
+    current_epoch: EpochId,
+    max_epoch_upper_bound_delta: Option<u64>,
+) -> SuiResult {
+    // the checks here ensure that `current_epoch + max_epoch_upper_bound_delta >= max_epoch >= current_epoch`.
+    // 1. if the config for upper bound is set, ensure that the max epoch in signature is not larger than epoch + upper_bound.
+    if let Some(delta) = max_epoch_upper_bound_delta {
+        let max_epoch_upper_bound = current_epoch + delta;
+        if max_epoch > max_epoch_upper_bound {
+            return Err(SuiError::InvalidSignature {
+                error: format!(
+                    "ZKLogin max epoch too large {}, current epoch {}, max accepted: {}",
+                    max_epoch, current_epoch, max_epoch_upper_bound
+                ),
+            });
+        }
+    }
+    // 2. ensure that max epoch in signature is greater than the current epoch.
+    if current_epoch > max_epoch {
+        return Err(SuiError::InvalidSignature {
+            error: format!(
+                "ZKLogin expired at epoch {}, current epoch {}",
+                max_epoch, current_epoch
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Verify the Groth16 proof in `inputs`, binding `ephemeral_key` (the key that signs on behalf of
+/// the zkLogin address) to a JWT issued by a provider whose key is in `jwks`, valid until
+/// `max_epoch`. `env` selects the verifying key: production proofs are only valid under
+/// `ZkLoginEnv::Prod`.
+pub fn verify_zklogin_proof(
+    inputs: &ZkLoginInputs,
+    max_epoch: EpochId,
+    ephemeral_key: &PublicKey,
+    jwks: &im::HashMap<JwkId, JWK>,
+    env: &ZkLoginEnv,
+) -> SuiResult {
+    let mut extended_pk_bytes = vec![ephemeral_key.flag()];
+    extended_pk_bytes.extend(ephemeral_key.as_ref());
+    verify_zklogin_inputs_wrapper(
+        ZkLoginCachingParams {
+            inputs: inputs.clone(),
+            max_epoch,
+            extended_pk_bytes,
+        },
+        jwks,
+        env,
+    )
+}
+
 impl ToFromBytes for ZkLoginAuthenticator {
     fn from_bytes(bytes: &[u8]) -> Result<Self, FastCryptoError> {
         // The first byte matches the flag of MultiSig.
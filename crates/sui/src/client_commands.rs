@@ -708,6 +708,8 @@ impl SuiClientCommands {
                     executor_version: None,
                     protocol_version: None,
                     profile_output,
+                    folded: false,
+                    source_dirs: vec![],
                     config_objects: None,
                 };
                 let rpc = context.config.get_active_env()?.rpc.clone();
@@ -13,6 +13,8 @@ pub mod genesis_ceremony;
 pub mod genesis_inspector;
 pub mod key_identity;
 pub mod keytool;
+pub mod localnet_admin;
+pub mod localnet_stack;
 pub mod shell;
 pub mod sui_commands;
 pub mod upgrade_compatibility;
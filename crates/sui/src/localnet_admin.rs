@@ -0,0 +1,78 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Admin server for a local network started by `sui start`, to control time in the network so
+//! that time-dependent Move logic (vesting, auctions, etc.) can be tested deterministically.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::post,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sui_swarm::memory::Swarm;
+use sui_types::committee::EpochId;
+use tokio::net::TcpListener;
+use tracing::info;
+
+pub const ADVANCE_CLOCK_PATH: &str = "/advance-clock";
+pub const ADVANCE_EPOCH_PATH: &str = "/advance-epoch";
+
+/// How long to wait for every node to reach the next epoch, after closing the current one.
+const EPOCH_CHANGE_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize, Debug)]
+struct AdvanceClockParams {
+    duration_ms: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct AdvanceEpochResponse {
+    epoch: EpochId,
+}
+
+/// Serve the admin routes for `swarm` on `address`, until the process exits.
+pub async fn start_localnet_admin(swarm: Arc<Swarm>, address: SocketAddr) -> anyhow::Result<()> {
+    let router = Router::new()
+        .route(ADVANCE_CLOCK_PATH, post(advance_clock))
+        .route(ADVANCE_EPOCH_PATH, post(advance_epoch))
+        .with_state(swarm);
+
+    let listener = TcpListener::bind(address).await?;
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    Ok(())
+}
+
+/// `POST /advance-clock?duration_ms=<ms>`: Move the network's clock forward by `duration_ms`. If
+/// this moves the clock past the end of the current epoch, the network changes epoch.
+async fn advance_clock(
+    State(swarm): State<Arc<Swarm>>,
+    Query(params): Query<AdvanceClockParams>,
+) -> StatusCode {
+    info!("Advancing clock by {}ms", params.duration_ms);
+    swarm.advance_clock(Duration::from_millis(params.duration_ms));
+    StatusCode::OK
+}
+
+/// `POST /advance-epoch`: Close the current epoch, and respond with the new epoch once every node
+/// has reached it.
+async fn advance_epoch(
+    State(swarm): State<Arc<Swarm>>,
+) -> Result<Json<AdvanceEpochResponse>, (StatusCode, String)> {
+    let internal = |e: anyhow::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+
+    let epoch = swarm.close_epoch().await.map_err(internal)?;
+    info!("Closing epoch {epoch}");
+
+    swarm
+        .wait_for_epoch(epoch + 1, EPOCH_CHANGE_TIMEOUT)
+        .await
+        .map_err(internal)?;
+
+    Ok(Json(AdvanceEpochResponse { epoch: epoch + 1 }))
+}
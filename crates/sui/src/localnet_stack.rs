@@ -0,0 +1,381 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generates the configuration for a full-stack local development environment: a local network
+//! with a faucet, a Postgres database, the indexer (`sui-indexer-alt`) and the JSON-RPC service
+//! that reads from its database (`sui-indexer-alt-jsonrpc`).
+//!
+//! The local network writes its checkpoints to a data ingestion directory that the indexer reads
+//! from. The network is re-created from scratch every time the stack starts, so the indexer's
+//! database and the ingestion directory are wiped on start-up as well, to stay consistent with
+//! it.
+//!
+//! Example usage:
+//! sui localnet generate --output-dir ./localnet
+//! cd localnet && docker compose up
+
+use anyhow::{bail, Context};
+use clap::*;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_IMAGE_TAG: &str = "devnet";
+const POSTGRES_IMAGE: &str = "postgres:15";
+const POSTGRES_PASSWORD: &str = "postgrespw";
+const DATABASE_NAME: &str = "sui_indexer_alt";
+
+/// Ports that services listen on for metrics, inside the stack. They are not published, but need
+/// to be distinct when all services run on the same host.
+const INDEXER_METRICS_PORT: u16 = 9185;
+const JSONRPC_METRICS_PORT: u16 = 9186;
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum LocalnetCommand {
+    /// Generate the configuration for a local network, a faucet, a Postgres database, the indexer
+    /// and the JSON-RPC service, wired together with consistent ports.
+    Generate(GenerateArgs),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum StackFormat {
+    /// A `docker-compose.yaml` file, using the images published by Mysten Labs.
+    Compose,
+    /// A set of systemd units, running binaries installed on the host, against a Postgres server
+    /// that is managed separately.
+    Systemd,
+}
+
+#[derive(Args, Clone, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub struct GenerateArgs {
+    /// Directory to write the configuration to. It is created if it does not exist.
+    #[clap(long, default_value = "localnet")]
+    pub output_dir: PathBuf,
+
+    /// Format of the configuration to generate.
+    #[clap(long, value_enum, default_value = "compose")]
+    pub format: StackFormat,
+
+    /// Tag of the `mysten/sui-tools`, `mysten/sui-indexer-alt` and
+    /// `mysten/sui-indexer-alt-jsonrpc` images to use (compose only).
+    #[clap(long, default_value = DEFAULT_IMAGE_TAG)]
+    pub image_tag: String,
+
+    /// Directory that the `sui`, `sui-indexer-alt` and `sui-indexer-alt-jsonrpc` binaries are
+    /// installed in (systemd only).
+    #[clap(long, default_value = "/usr/local/bin")]
+    pub bin_dir: PathBuf,
+
+    /// Directory that the local network's state and checkpoints are written to (systemd only).
+    #[clap(long, default_value = "/var/lib/sui-localnet")]
+    pub data_dir: PathBuf,
+
+    /// Port for the fullnode's JSON-RPC server.
+    #[clap(long, default_value = "9000")]
+    pub fullnode_rpc_port: u16,
+
+    /// Port for the faucet.
+    #[clap(long, default_value = "9123")]
+    pub faucet_port: u16,
+
+    /// Port for the JSON-RPC service backed by the indexer's database.
+    #[clap(long, default_value = "6000")]
+    pub jsonrpc_port: u16,
+
+    /// Port for the Postgres database.
+    #[clap(long, default_value = "5432")]
+    pub postgres_port: u16,
+
+    /// Overwrite existing files in the output directory.
+    #[clap(long)]
+    pub force: bool,
+}
+
+impl LocalnetCommand {
+    pub fn execute(self) -> anyhow::Result<()> {
+        match self {
+            LocalnetCommand::Generate(args) => {
+                let files = generate(&args)?;
+                println!(
+                    "Generated a local network stack in {}:",
+                    args.output_dir.display()
+                );
+                for file in &files {
+                    println!("  {}", file.display());
+                }
+
+                match args.format {
+                    StackFormat::Compose => println!(
+                        "\nStart it with `docker compose up` from that directory. Services:"
+                    ),
+                    StackFormat::Systemd => println!(
+                        "\nInstall the units into /etc/systemd/system and start them with \
+                         `systemctl start sui-localnet.target`. Services:"
+                    ),
+                }
+                println!(
+                    "  Fullnode RPC:  http://127.0.0.1:{}",
+                    args.fullnode_rpc_port
+                );
+                println!("  Faucet:        http://127.0.0.1:{}", args.faucet_port);
+                println!("  JSON-RPC:      http://127.0.0.1:{}", args.jsonrpc_port);
+                println!(
+                    "  Postgres:      {}",
+                    database_url("127.0.0.1", args.postgres_port)
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Write the configuration for the stack described by `args` to its output directory, and return
+/// the paths of the files written.
+pub fn generate(args: &GenerateArgs) -> anyhow::Result<Vec<PathBuf>> {
+    let files = match args.format {
+        StackFormat::Compose => vec![("docker-compose.yaml", docker_compose(args))],
+        StackFormat::Systemd => systemd_units(args),
+    };
+
+    fs::create_dir_all(&args.output_dir).with_context(|| {
+        format!(
+            "Failed to create output directory {}",
+            args.output_dir.display()
+        )
+    })?;
+
+    if !args.force {
+        for (name, _) in &files {
+            let path = args.output_dir.join(name);
+            if path.exists() {
+                bail!(
+                    "{} already exists, pass --force to overwrite it",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    let mut written = vec![];
+    for (name, contents) in files {
+        let path = args.output_dir.join(name);
+        fs::write(&path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+fn database_url(host: &str, port: u16) -> String {
+    format!("postgres://postgres:{POSTGRES_PASSWORD}@{host}:{port}/{DATABASE_NAME}")
+}
+
+/// The command that starts the local network, writing its checkpoints to `ingestion_dir`.
+fn localnet_command(sui: &str, ingestion_dir: &str, args: &GenerateArgs) -> String {
+    format!(
+        "{sui} start --force-regenesis --with-faucet=0.0.0.0:{} --fullnode-rpc-port {} \
+         --data-ingestion-dir {ingestion_dir}",
+        args.faucet_port, args.fullnode_rpc_port,
+    )
+}
+
+/// The command that resets the indexer's database, generates its default configuration into
+/// `config`, and starts indexing the checkpoints in `ingestion_dir`.
+fn indexer_command(indexer: &str, db_url: &str, ingestion_dir: &str, config: &str) -> String {
+    format!(
+        "{indexer} --database-url {db_url} reset-database && \
+         {indexer} generate-config > {config} && \
+         exec {indexer} --database-url {db_url} indexer \
+         --local-ingestion-path {ingestion_dir} \
+         --metrics-address 0.0.0.0:{INDEXER_METRICS_PORT} \
+         --config {config}"
+    )
+}
+
+fn jsonrpc_command(jsonrpc: &str, db_url: &str, port: u16) -> String {
+    format!(
+        "{jsonrpc} --database-url {db_url} rpc \
+         --rpc-listen-address 0.0.0.0:{port} \
+         --metrics-address 0.0.0.0:{JSONRPC_METRICS_PORT}"
+    )
+}
+
+fn docker_compose(args: &GenerateArgs) -> String {
+    let tag = &args.image_tag;
+    let ingestion_dir = "/data/ingestion";
+    let db_url = database_url("postgres", 5432);
+
+    // The ingestion directory is shared between the network and the indexer. It is emptied
+    // before the network starts, as the network is re-created from genesis.
+    let localnet = format!(
+        "rm -rf {ingestion_dir}/* && exec {}",
+        localnet_command("sui", ingestion_dir, args)
+    );
+    let indexer = indexer_command(
+        "sui-indexer-alt",
+        &db_url,
+        ingestion_dir,
+        "/tmp/indexer.toml",
+    );
+    let jsonrpc = jsonrpc_command("sui-indexer-alt-jsonrpc", &db_url, args.jsonrpc_port);
+
+    format!(
+        r#"---
+# Generated by `sui localnet generate`.
+version: "3.9"
+
+services:
+  localnet:
+    image: mysten/sui-tools:{tag}
+    restart: unless-stopped
+    environment:
+      - RUST_LOG=info
+    command: ["sh", "-c", "{localnet}"]
+    ports:
+      - "{rpc}:{rpc}"
+      - "{faucet}:{faucet}"
+    volumes:
+      - ingestion:{ingestion_dir}
+
+  postgres:
+    image: {POSTGRES_IMAGE}
+    restart: unless-stopped
+    environment:
+      - POSTGRES_PASSWORD={POSTGRES_PASSWORD}
+      - POSTGRES_DB={DATABASE_NAME}
+    ports:
+      - "{postgres}:5432"
+    healthcheck:
+      test: ["CMD-SHELL", "pg_isready -U postgres"]
+      interval: 2s
+      timeout: 5s
+      retries: 15
+
+  indexer:
+    image: mysten/sui-indexer-alt:{tag}
+    restart: unless-stopped
+    environment:
+      - RUST_LOG=info
+    command: ["sh", "-c", "{indexer}"]
+    volumes:
+      - ingestion:{ingestion_dir}
+    depends_on:
+      postgres:
+        condition: service_healthy
+      localnet:
+        condition: service_started
+
+  jsonrpc:
+    image: mysten/sui-indexer-alt-jsonrpc:{tag}
+    restart: unless-stopped
+    environment:
+      - RUST_LOG=info
+    command: ["sh", "-c", "{jsonrpc}"]
+    ports:
+      - "{jsonrpc_port}:{jsonrpc_port}"
+    depends_on:
+      postgres:
+        condition: service_healthy
+      indexer:
+        condition: service_started
+
+volumes:
+  ingestion:
+"#,
+        rpc = args.fullnode_rpc_port,
+        faucet = args.faucet_port,
+        postgres = args.postgres_port,
+        jsonrpc_port = args.jsonrpc_port,
+    )
+}
+
+fn systemd_units(args: &GenerateArgs) -> Vec<(&'static str, String)> {
+    let bin = |name: &str| args.bin_dir.join(name).display().to_string();
+    let data_dir = args.data_dir.display().to_string();
+    let ingestion_dir = args.data_dir.join("ingestion").display().to_string();
+    let indexer_config = args.data_dir.join("indexer.toml").display().to_string();
+    let db_url = database_url("127.0.0.1", args.postgres_port);
+
+    let localnet = format!(
+        "rm -rf {ingestion_dir} && exec {}",
+        localnet_command(&bin("sui"), &ingestion_dir, args)
+    );
+    let indexer = indexer_command(
+        &bin("sui-indexer-alt"),
+        &db_url,
+        &ingestion_dir,
+        &indexer_config,
+    );
+    let jsonrpc = jsonrpc_command(&bin("sui-indexer-alt-jsonrpc"), &db_url, args.jsonrpc_port);
+
+    let target = format!(
+        r#"# Generated by `sui localnet generate`.
+[Unit]
+Description=Sui local network stack
+Requires=sui-localnet.service sui-localnet-indexer.service sui-localnet-jsonrpc.service
+After=sui-localnet.service sui-localnet-indexer.service sui-localnet-jsonrpc.service
+
+[Install]
+WantedBy=multi-user.target
+"#
+    );
+
+    let service = |description: &str, after: &str, command: &str| {
+        format!(
+            r#"# Generated by `sui localnet generate`.
+[Unit]
+Description={description}
+PartOf=sui-localnet.target
+After={after}
+Wants={after}
+
+[Service]
+Environment=RUST_LOG=info
+ExecStartPre=/bin/mkdir -p {data_dir}
+ExecStart=/bin/sh -c '{command}'
+Restart=on-failure
+RestartSec=5
+
+[Install]
+WantedBy=sui-localnet.target
+"#
+        )
+    };
+
+    // Postgres is expected to be managed by the host's own unit, with a database created for the
+    // indexer (`createdb sui_indexer_alt`) and the password of the `postgres` user set to match
+    // the database URL.
+    vec![
+        ("sui-localnet.target", target),
+        (
+            "sui-localnet.service",
+            service(
+                "Sui local network and faucet",
+                "network-online.target",
+                &localnet,
+            ),
+        ),
+        (
+            "sui-localnet-indexer.service",
+            service(
+                "Sui local network indexer",
+                "postgresql.service sui-localnet.service",
+                &indexer,
+            ),
+        ),
+        (
+            "sui-localnet-jsonrpc.service",
+            service(
+                "Sui local network JSON-RPC service",
+                "postgresql.service sui-localnet-indexer.service",
+                &jsonrpc,
+            ),
+        ),
+    ]
+}
+
+#[cfg(test)]
+#[path = "unit_tests/localnet_stack_tests.rs"]
+mod localnet_stack_tests;
@@ -6,6 +6,8 @@ use crate::console::start_console;
 use crate::fire_drill::{run_fire_drill, FireDrill};
 use crate::genesis_ceremony::{run, Ceremony};
 use crate::keytool::KeyToolCommand;
+use crate::localnet_admin::start_localnet_admin;
+use crate::localnet_stack::LocalnetCommand;
 use crate::validator_commands::SuiValidatorCommand;
 use anyhow::{anyhow, bail, ensure, Context};
 use clap::*;
@@ -70,6 +72,8 @@ const DEFAULT_GRAPHQL_PORT: u16 = 9125;
 
 const DEFAULT_INDEXER_PORT: u16 = 9124;
 
+const DEFAULT_ADMIN_PORT: u16 = 9126;
+
 #[derive(Args)]
 pub struct IndexerArgs {
     /// Start an indexer with default host and port: 0.0.0.0:9124. This flag accepts also a port,
@@ -190,6 +194,20 @@ pub enum SuiCommand {
         )]
         with_faucet: Option<String>,
 
+        /// Start an admin server to control time in the local network, with default host and
+        /// port: 127.0.0.1:9126. This flag accepts also a port, a host, or both (e.g.,
+        /// 127.0.0.1:9126). It serves the following routes:
+        /// - `POST /advance-clock?duration_ms=<ms>` moves the network's clock forward.
+        /// - `POST /advance-epoch` forces the network to change epoch.
+        #[clap(
+            long,
+            default_missing_value = "127.0.0.1:9126",
+            num_args = 0..=1,
+            require_equals = true,
+            value_name = "ADMIN_HOST_PORT",
+        )]
+        with_admin: Option<String>,
+
         #[clap(flatten)]
         indexer_feature_args: IndexerArgs,
 
@@ -338,6 +356,14 @@ pub enum SuiCommand {
         bridge_committee_config_path: PathBuf,
     },
 
+    /// Generate the configuration for a full-stack local development environment: a local
+    /// network with a faucet, Postgres, the indexer and its JSON-RPC service.
+    #[clap(name = "localnet")]
+    Localnet {
+        #[clap(subcommand)]
+        cmd: LocalnetCommand,
+    },
+
     /// Tool for Fire Drill
     FireDrill {
         #[clap(subcommand)]
@@ -380,6 +406,7 @@ impl SuiCommand {
                 config_dir,
                 force_regenesis,
                 with_faucet,
+                with_admin,
 
                 indexer_feature_args,
                 fullnode_rpc_port,
@@ -391,6 +418,7 @@ impl SuiCommand {
                 start(
                     config_dir.clone(),
                     with_faucet,
+                    with_admin,
                     indexer_feature_args,
                     force_regenesis,
                     epoch_duration_ms,
@@ -615,6 +643,7 @@ impl SuiCommand {
                 futures::future::join_all(tasks).await;
                 Ok(())
             }
+            SuiCommand::Localnet { cmd } => cmd.execute(),
             SuiCommand::FireDrill { fire_drill } => run_fire_drill(fire_drill).await,
             SuiCommand::Analyzer => {
                 analyzer::run();
@@ -628,6 +657,7 @@ impl SuiCommand {
 async fn start(
     config: Option<PathBuf>,
     with_faucet: Option<String>,
+    with_admin: Option<String>,
     indexer_feature_args: IndexerArgs,
     force_regenesis: bool,
     epoch_duration_ms: Option<u64>,
@@ -931,6 +961,14 @@ async fn start(
         start_faucet(app_state, CONCURRENCY_LIMIT, &prometheus_registry).await?;
     }
 
+    let swarm = Arc::new(swarm);
+    if let Some(input) = with_admin {
+        let admin_address = parse_host_port(input, DEFAULT_ADMIN_PORT)
+            .map_err(|_| anyhow!("Invalid admin host and port"))?;
+        info!("Starting the admin service at {admin_address}");
+        start_localnet_admin(swarm.clone(), admin_address).await?;
+    }
+
     let mut interval = tokio::time::interval(std::time::Duration::from_secs(3));
     let mut unhealthy_cnt = 0;
     loop {
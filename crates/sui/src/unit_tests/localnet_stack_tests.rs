@@ -0,0 +1,74 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{generate, GenerateArgs, StackFormat};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn args(output_dir: PathBuf, format: StackFormat) -> GenerateArgs {
+    GenerateArgs {
+        output_dir,
+        format,
+        image_tag: "devnet".to_string(),
+        bin_dir: PathBuf::from("/usr/local/bin"),
+        data_dir: PathBuf::from("/var/lib/sui-localnet"),
+        fullnode_rpc_port: 9000,
+        faucet_port: 9123,
+        jsonrpc_port: 6000,
+        postgres_port: 5433,
+        force: false,
+    }
+}
+
+#[test]
+fn test_generate_compose() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new()?;
+    let args = args(temp_dir.path().to_path_buf(), StackFormat::Compose);
+
+    let files = generate(&args)?;
+    assert_eq!(files, vec![temp_dir.path().join("docker-compose.yaml")]);
+
+    let compose: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(&files[0])?)?;
+    let services = compose["services"].as_mapping().unwrap();
+    let names: Vec<_> = services.keys().filter_map(|k| k.as_str()).collect();
+    assert_eq!(names, vec!["localnet", "postgres", "indexer", "jsonrpc"]);
+
+    // Services reach Postgres on its port inside the stack, which is published on the host under
+    // the requested port.
+    let indexer = compose["services"]["indexer"]["command"][2]
+        .as_str()
+        .unwrap();
+    assert!(indexer.contains("@postgres:5432/sui_indexer_alt"));
+    assert!(indexer.contains("--local-ingestion-path /data/ingestion"));
+    assert_eq!(
+        compose["services"]["postgres"]["ports"][0].as_str(),
+        Some("5433:5432")
+    );
+
+    // Files are not overwritten unless asked to.
+    assert!(generate(&args).is_err());
+    generate(&GenerateArgs {
+        force: true,
+        ..args
+    })?;
+    Ok(())
+}
+
+#[test]
+fn test_generate_systemd() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new()?;
+    let args = args(temp_dir.path().to_path_buf(), StackFormat::Systemd);
+
+    let files = generate(&args)?;
+    assert_eq!(files.len(), 4);
+
+    let indexer = fs::read_to_string(temp_dir.path().join("sui-localnet-indexer.service"))?;
+    assert!(indexer.contains("@127.0.0.1:5433/sui_indexer_alt"));
+    assert!(indexer.contains("--local-ingestion-path /var/lib/sui-localnet/ingestion"));
+
+    let localnet = fs::read_to_string(temp_dir.path().join("sui-localnet.service"))?;
+    assert!(localnet.contains("--data-ingestion-dir /var/lib/sui-localnet/ingestion"));
+    assert!(localnet.contains("--with-faucet=0.0.0.0:9123"));
+    Ok(())
+}
@@ -50,6 +50,8 @@ async fn test_profiler() {
         executor_version: None,
         protocol_version: None,
         profile_output: Some(profile_output),
+        folded: false,
+        source_dirs: vec![],
         config_objects: None,
     };
 
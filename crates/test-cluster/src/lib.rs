@@ -12,7 +12,9 @@ use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use sui_config::genesis::Genesis;
-use sui_config::node::{AuthorityOverloadConfig, DBCheckpointConfig, RunWithRange};
+use sui_config::node::{
+    AuthorityOverloadConfig, CustomOidcProviderConfig, DBCheckpointConfig, RunWithRange,
+};
 use sui_config::{Config, ExecutionCacheConfig, SUI_CLIENT_CONFIG, SUI_NETWORK_CONFIG};
 use sui_config::{NodeConfig, PersistedConfig, SUI_KEYSTORE_FILENAME};
 use sui_core::authority_aggregator::AuthorityAggregator;
@@ -340,36 +342,26 @@ impl TestCluster {
         let start = Instant::now();
 
         // Close epoch on 2f+1 validators.
-        let cur_committee = self
-            .fullnode_handle
-            .sui_node
-            .with(|node| node.state().clone_committee_for_testing());
-        let mut cur_stake = 0;
-        for node in self.swarm.active_validators() {
-            node.get_node_handle()
-                .unwrap()
-                .with_async(|node| async {
-                    node.close_epoch_for_testing().await.unwrap_or_else(|_| {
-                        fatal!(
-                            "Failed to close epoch for validator {:?}",
-                            node.state().name
-                        );
-                    });
-                    cur_stake += cur_committee.weight(&node.state().name);
-                })
-                .await;
-            if cur_stake >= cur_committee.quorum_threshold() {
-                break;
-            }
-        }
+        let epoch = self
+            .swarm
+            .close_epoch()
+            .await
+            .unwrap_or_else(|e| fatal!("Failed to close epoch: {e:?}"));
         info!("close_epoch complete after {:?}", start.elapsed());
 
-        self.wait_for_epoch(Some(cur_committee.epoch + 1)).await;
-        self.wait_for_epoch_all_nodes(cur_committee.epoch + 1).await;
+        self.wait_for_epoch(Some(epoch + 1)).await;
+        self.wait_for_epoch_all_nodes(epoch + 1).await;
 
         info!("reconfiguration complete after {:?}", start.elapsed());
     }
 
+    /// Move the clock that validators timestamp consensus commits with forward by `duration`, so
+    /// that time-dependent Move logic sees time pass without waiting for it. If this moves the
+    /// clock past the end of the epoch, the network changes epoch as it would have normally.
+    pub fn advance_clock(&self, duration: Duration) {
+        self.swarm.advance_clock(duration);
+    }
+
     /// To detect whether the network has reached such state, we use the fullnode as the
     /// source of truth, since a fullnode only does epoch transition when the network has
     /// done so.
@@ -834,6 +826,7 @@ pub struct TestClusterBuilder {
     db_checkpoint_config_fullnodes: DBCheckpointConfig,
     num_unpruned_validators: Option<usize>,
     jwk_fetch_interval: Option<Duration>,
+    zklogin_custom_oidc_providers: Vec<CustomOidcProviderConfig>,
     config_dir: Option<PathBuf>,
     default_jwks: bool,
     authority_overload_config: Option<AuthorityOverloadConfig>,
@@ -866,6 +859,7 @@ impl TestClusterBuilder {
             db_checkpoint_config_fullnodes: DBCheckpointConfig::default(),
             num_unpruned_validators: None,
             jwk_fetch_interval: None,
+            zklogin_custom_oidc_providers: vec![],
             config_dir: None,
             default_jwks: false,
             authority_overload_config: None,
@@ -983,6 +977,14 @@ impl TestClusterBuilder {
         self
     }
 
+    pub fn with_zklogin_custom_oidc_providers(
+        mut self,
+        providers: Vec<CustomOidcProviderConfig>,
+    ) -> Self {
+        self.zklogin_custom_oidc_providers = providers;
+        self
+    }
+
     pub fn with_fullnode_supported_protocol_versions_config(
         mut self,
         c: SupportedProtocolVersions,
@@ -1236,6 +1238,9 @@ impl TestClusterBuilder {
             builder = builder.with_jwk_fetch_interval(jwk_fetch_interval);
         }
 
+        builder =
+            builder.with_zklogin_custom_oidc_providers(self.zklogin_custom_oidc_providers.clone());
+
         if let Some(config_dir) = self.config_dir.take() {
             builder = builder.dir(config_dir);
         }
@@ -39,7 +39,14 @@ pub struct Profile {
     unit: String,
     start_value: u64,
     end_value: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     events: Vec<Event>,
+    // Only set for sampled profiles: each sample is a stack of frames (outermost first), weighted
+    // by the corresponding entry in `weights`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    samples: Vec<Vec<u64>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    weights: Vec<u64>,
 }
 
 #[allow(dead_code)]
@@ -60,6 +67,13 @@ pub struct GasProfiler {
     pub config: Option<VMProfilerConfig>,
     #[serde(skip)]
     finished: bool,
+
+    /// Frames of the functions currently being executed, outermost first.
+    #[serde(skip)]
+    stack: Vec<u64>,
+    /// Number of instructions executed directly by each stack of functions.
+    #[serde(skip)]
+    instructions: BTreeMap<Vec<u64>, u64>,
 }
 
 #[cfg(feature = "tracing")]
@@ -88,10 +102,14 @@ impl GasProfiler {
                 start_value: 0,
                 end_value: 0,
                 events: vec![],
+                samples: vec![],
+                weights: vec![],
             }],
             start_gas,
             config: config.clone(),
             finished: false,
+            stack: vec![],
+            instructions: BTreeMap::new(),
         };
         profile_open_frame_impl!(
             Some(&mut prof),
@@ -153,13 +171,8 @@ impl GasProfiler {
         }
 
         let frame_idx = self.add_frame(metadata.clone(), frame_name, metadata);
-        let start = self.start_gas();
-
-        self.profiles[0].events.push(Event {
-            ty: Self::OPEN_FRAME_IDENT.to_string(),
-            frame: frame_idx,
-            at: start - gas_start,
-        });
+        self.stack.push(frame_idx);
+        self.push_event(Self::OPEN_FRAME_IDENT, frame_idx, gas_start);
     }
 
     #[cfg(feature = "tracing")]
@@ -168,14 +181,81 @@ impl GasProfiler {
             return;
         }
         let frame_idx = self.add_frame(metadata.clone(), frame_name, metadata);
-        let start = self.start_gas();
+        self.stack.pop();
+        self.push_event(Self::CLOSE_FRAME_IDENT, frame_idx, gas_end);
+    }
+
+    /// Count an instruction executed by the function on top of the stack. Instructions are counted
+    /// whether or not they are tracked as frames of their own.
+    #[cfg(feature = "tracing")]
+    pub fn count_instruction(&mut self) {
+        if self.config.is_none() || self.start_gas == 0 {
+            return;
+        }
+
+        if let Some(count) = self.instructions.get_mut(self.stack.as_slice()) {
+            *count += 1;
+        } else {
+            self.instructions.insert(self.stack.clone(), 1);
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    pub fn open_instr_frame(&mut self, instr_name: String, gas_start: u64) {
+        if self.config.is_none() || self.start_gas == 0 {
+            return;
+        }
+
+        let frame_idx = self.add_frame(instr_name.clone(), instr_name.clone(), instr_name);
+        self.push_event(Self::OPEN_FRAME_IDENT, frame_idx, gas_start);
+    }
+
+    #[cfg(feature = "tracing")]
+    pub fn close_instr_frame(&mut self, instr_name: String, gas_end: u64) {
+        if self.config.is_none() || self.start_gas == 0 {
+            return;
+        }
+
+        let frame_idx = self.add_frame(instr_name.clone(), instr_name.clone(), instr_name);
+        self.push_event(Self::CLOSE_FRAME_IDENT, frame_idx, gas_end);
+    }
 
+    #[cfg(feature = "tracing")]
+    fn push_event(&mut self, ty: &str, frame_idx: u64, gas_rem: u64) {
+        let at = self.start_gas() - gas_rem;
         self.profiles[0].events.push(Event {
-            ty: Self::CLOSE_FRAME_IDENT.to_string(),
+            ty: ty.to_string(),
             frame: frame_idx,
-            at: start - gas_end,
+            at,
+        });
+
+        if ty == Self::CLOSE_FRAME_IDENT {
+            self.profiles[0].end_value = at;
+        }
+    }
+
+    /// Add the instruction counts gathered so far as a second, sampled, profile, weighted by the
+    /// number of instructions executed in each stack of functions.
+    #[cfg(feature = "tracing")]
+    fn add_instruction_profile(&mut self) {
+        if self.instructions.is_empty() {
+            return;
+        }
+
+        let instructions = std::mem::take(&mut self.instructions);
+        let end_value = instructions.values().sum();
+        let (samples, weights) = instructions.into_iter().unzip();
+
+        self.profiles.push(Profile {
+            ty: "sampled".to_string(),
+            name: format!("{} (instructions)", self.name),
+            unit: "none".to_string(),
+            start_value: 0,
+            end_value,
+            events: vec![],
+            samples,
+            weights,
         });
-        self.profiles[0].end_value = start - gas_end;
     }
 
     #[cfg(feature = "tracing")]
@@ -227,7 +307,9 @@ impl GasProfiler {
         let end_gas = self.start_gas() - self.profiles[0].end_value;
         let mut q = Some(self);
         profile_close_frame_impl!(&mut q, Self::TOP_LEVEL_FRAME_NAME.to_string(), end_gas);
-        profile_dump_file!(q.unwrap());
+        let prof = q.unwrap();
+        prof.add_instruction_profile();
+        profile_dump_file!(prof);
     }
 }
 
@@ -313,9 +395,10 @@ macro_rules! profile_open_instr {
         {
             let gas_rem = $gas_meter.remaining_gas().into();
             if let Some(profiler) = $gas_meter.get_profiler_mut() {
+                profiler.count_instruction();
                 if let Some(config) = &profiler.config {
                     if config.track_bytecode_instructions {
-                        profiler.open_frame($frame_name.clone(), $frame_name, gas_rem)
+                        profiler.open_instr_frame($frame_name, gas_rem)
                     }
                 }
             }
@@ -332,7 +415,7 @@ macro_rules! profile_close_instr {
             if let Some(profiler) = $gas_meter.get_profiler_mut() {
                 if let Some(config) = &profiler.config {
                     if config.track_bytecode_instructions {
-                        profiler.close_frame($frame_name.clone(), $frame_name, gas_rem)
+                        profiler.close_instr_frame($frame_name, gas_rem)
                     }
                 }
             }